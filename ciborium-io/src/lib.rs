@@ -10,9 +10,28 @@
 //!
 //! If the `std` feature is enabled, we provide blanket implementations for
 //! all `std::io` types. If the `alloc` feature is enabled, we provide
-//! implementations for `Vec<u8>`. In all cases, you get implementations
+//! implementations for `Vec<u8>`, and for `VecDeque<u8>`, which can be used
+//! as a pipe: reads take bytes from the front, writes add them to the back. In all cases, you get implementations
 //! for byte slices. You can, of course, implement the traits for your own
 //! types.
+//!
+//! The `BufRead` trait extends `Read` for readers with an internal buffer,
+//! just like `std::io::BufRead`, and the `Seek` trait extends `Write` for
+//! writers which can go back to overwrite earlier bytes, with a blanket
+//! implementation for `std::io::Seek` types like `Cursor` and `File`.
+//!
+//! There are also a few small writers: [`Counter`] counts the bytes written
+//! through it, [`Sink`] counts bytes while discarding them and [`Tee`]
+//! writes the same bytes to two writers. With the `std` feature,
+//! [`BlockingAdapter`] retries writes to a nonblocking writer. For readers,
+//! [`Take`] limits the number of bytes which can be read and [`Peekable`]
+//! allows looking ahead. Finally, [`IterRead`] reads from an iterator of
+//! chunks and [`FnWrite`] writes to a function, which suits message-based
+//! transports, while [`Slices`] reads from a list of slices in place.
+//!
+//! If the `async` feature is enabled, we also provide the `AsyncRead` and
+//! `AsyncWrite` traits, which are poll-based versions of the same traits
+//! for use with any executor.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
@@ -32,6 +51,35 @@ pub trait Read {
 
     /// Reads exactly `data.len()` bytes or fails
     fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Whether the error means that the input ended before the read was done
+    ///
+    /// This lets a consumer tell a truncated input, which may just need
+    /// more bytes, from other failures. By default, no error is the end of
+    /// the input, so readers which can end should override this.
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        let _ = error;
+        false
+    }
+}
+
+/// A trait indicating a reader with an internal buffer
+///
+/// Note that this is similar to `std::io::BufRead`, but simplified for use
+/// in a `no_std` context. It lets a consumer look at the buffered bytes
+/// directly, rather than copying them out with [`Read::read_exact()`].
+pub trait BufRead: Read {
+    /// Returns the buffered bytes, filling the buffer first if it is empty
+    ///
+    /// An empty slice is returned only at the end of the input.
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error>;
+
+    /// Marks the first `amt` buffered bytes as read
+    ///
+    /// `amt` must not be more than the length of the last slice returned by
+    /// [`BufRead::fill_buf()`].
+    fn consume(&mut self, amt: usize);
 }
 
 /// A trait indicating a type that can write bytes
@@ -49,6 +97,87 @@ pub trait Write {
     fn flush(&mut self) -> Result<(), Self::Error>;
 }
 
+/// A trait indicating a writer which can go back to an earlier position
+///
+/// This allows bytes which were already written to be overwritten, such
+/// as a length which was not known when it was first written. Positions
+/// are counted in bytes from the start of the output. Like `std::io::Seek`,
+/// writing after going back overwrites the bytes there rather than
+/// inserting new ones.
+pub trait Seek: Write {
+    /// Returns the current position
+    fn position(&mut self) -> Result<u64, Self::Error>;
+
+    /// Moves to the given position
+    fn seek_to(&mut self, position: u64) -> Result<(), Self::Error>;
+}
+
+/// A trait indicating a type that can read bytes asynchronously
+///
+/// This is the poll-based counterpart of [`Read`]. Unlike `std` style
+/// readers, it has no end-of-file result: once no more bytes will arrive,
+/// `poll_read()` must fail, just as [`Read::read_exact()`] does.
+#[cfg(feature = "async")]
+pub trait AsyncRead {
+    /// The error type
+    type Error;
+
+    /// Reads at least one byte into `data`, which is not empty
+    ///
+    /// Returns the number of bytes read. If the bytes are not available yet,
+    /// returns `Poll::Pending` and arranges for the task to be woken.
+    fn poll_read(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        data: &mut [u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>>;
+
+    /// Whether the error means that no more bytes will arrive
+    ///
+    /// See [`Read::is_eof()`].
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        let _ = error;
+        false
+    }
+}
+
+/// A trait indicating a type that can write bytes asynchronously
+///
+/// This is the poll-based counterpart of [`Write`].
+#[cfg(feature = "async")]
+pub trait AsyncWrite {
+    /// The error type
+    type Error;
+
+    /// Writes at least one byte from `data`, which is not empty
+    ///
+    /// Returns the number of bytes written. If no bytes can be written yet,
+    /// returns `Poll::Pending` and arranges for the task to be woken.
+    fn poll_write(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        data: &[u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>>;
+
+    /// Flushes all output
+    fn poll_flush(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>>;
+
+    /// Returns the error for a write which accepted no bytes
+    ///
+    /// Callers which write a whole buffer report this when `poll_write()`
+    /// returns `Ok(0)`, as `std::io::ErrorKind::WriteZero` is reported
+    /// by [`Write::write_all()`]. The default is `None`, for error types
+    /// which cannot express it.
+    #[inline]
+    fn write_zero() -> Option<Self::Error> {
+        None
+    }
+}
+
 #[cfg(feature = "std")]
 impl<T: std::io::Read> Read for T {
     type Error = std::io::Error;
@@ -57,6 +186,24 @@ impl<T: std::io::Read> Read for T {
     fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
         self.read_exact(data)
     }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        error.kind() == std::io::ErrorKind::UnexpectedEof
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: std::io::BufRead> BufRead for T {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        self.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.consume(amt)
+    }
 }
 
 #[cfg(feature = "std")]
@@ -74,6 +221,20 @@ impl<T: std::io::Write> Write for T {
     }
 }
 
+#[cfg(feature = "std")]
+impl<T: std::io::Write + std::io::Seek> Seek for T {
+    #[inline]
+    fn position(&mut self) -> Result<u64, Self::Error> {
+        self.stream_position()
+    }
+
+    #[inline]
+    fn seek_to(&mut self, position: u64) -> Result<(), Self::Error> {
+        self.seek(std::io::SeekFrom::Start(position))?;
+        Ok(())
+    }
+}
+
 #[cfg(not(feature = "std"))]
 impl<R: Read + ?Sized> Read for &mut R {
     type Error = R::Error;
@@ -82,6 +243,24 @@ impl<R: Read + ?Sized> Read for &mut R {
     fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
         (**self).read_exact(data)
     }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        R::is_eof(error)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl<R: BufRead + ?Sized> BufRead for &mut R {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        (**self).fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -99,11 +278,34 @@ impl<W: Write + ?Sized> Write for &mut W {
     }
 }
 
+#[cfg(not(feature = "std"))]
+impl<W: Seek + ?Sized> Seek for &mut W {
+    #[inline]
+    fn position(&mut self) -> Result<u64, Self::Error> {
+        (**self).position()
+    }
+
+    #[inline]
+    fn seek_to(&mut self, position: u64) -> Result<(), Self::Error> {
+        (**self).seek_to(position)
+    }
+}
+
 /// An error indicating there are no more bytes to read
 #[cfg(not(feature = "std"))]
 #[derive(Debug)]
 pub struct EndOfFile(());
 
+#[cfg(not(feature = "std"))]
+impl core::fmt::Display for EndOfFile {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("unexpected end of input")
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for EndOfFile {}
+
 #[cfg(not(feature = "std"))]
 impl Read for &[u8] {
     type Error = EndOfFile;
@@ -119,6 +321,24 @@ impl Read for &[u8] {
         *self = suffix;
         Ok(())
     }
+
+    #[inline]
+    fn is_eof(_: &Self::Error) -> bool {
+        true
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl BufRead for &[u8] {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        Ok(*self)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt..];
+    }
 }
 
 /// An error indicating that the output cannot accept more bytes
@@ -126,6 +346,16 @@ impl Read for &[u8] {
 #[derive(Debug)]
 pub struct OutOfSpace(());
 
+#[cfg(not(feature = "std"))]
+impl core::fmt::Display for OutOfSpace {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("out of space")
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::error::Error for OutOfSpace {}
+
 #[cfg(not(feature = "std"))]
 impl Write for &mut [u8] {
     type Error = OutOfSpace;
@@ -164,102 +394,1569 @@ impl Write for alloc::vec::Vec<u8> {
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+impl Read for alloc::collections::VecDeque<u8> {
+    type Error = EndOfFile;
 
-    #[test]
-    fn read_eof() {
-        let mut reader = &[1u8; 0][..];
-        let mut buffer = [0u8; 1];
+    #[inline]
+    fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
+        if data.len() > self.len() {
+            return Err(EndOfFile(()));
+        }
 
-        reader.read_exact(&mut buffer[..]).unwrap_err();
+        // The bytes may wrap around the end of the ring buffer.
+        let (front, back) = self.as_slices();
+        let split = front.len().min(data.len());
+        let (head, tail) = data.split_at_mut(split);
+        head.copy_from_slice(&front[..split]);
+        tail.copy_from_slice(&back[..tail.len()]);
+
+        self.drain(..data.len());
+        Ok(())
     }
 
-    #[test]
-    fn read_one() {
-        let mut reader = &[1u8; 1][..];
-        let mut buffer = [0u8; 1];
+    #[inline]
+    fn is_eof(_: &Self::Error) -> bool {
+        true
+    }
+}
 
-        reader.read_exact(&mut buffer[..]).unwrap();
-        assert_eq!(buffer[0], 1);
+#[cfg(all(not(feature = "std"), feature = "alloc"))]
+impl Write for alloc::collections::VecDeque<u8> {
+    type Error = core::convert::Infallible;
 
-        reader.read_exact(&mut buffer[..]).unwrap_err();
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.extend(data);
+        Ok(())
     }
 
-    #[test]
-    fn read_two() {
-        let mut reader = &[1u8; 2][..];
-        let mut buffer = [0u8; 1];
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
 
-        reader.read_exact(&mut buffer[..]).unwrap();
-        assert_eq!(buffer[0], 1);
+/// A writer which counts the bytes written to the inner writer
+///
+/// Only bytes which were written successfully are counted. Errors from the
+/// inner writer are passed through, and flushing flushes the inner writer.
+///
+/// ```rust
+/// use ciborium_io::{Counter, Write};
+///
+/// let mut buffer = [0u8; 4];
+/// let mut counter = Counter::new(&mut buffer[..]);
+/// counter.write_all(b"abc").unwrap();
+/// counter.flush().unwrap();
+/// assert_eq!(counter.count(), 3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Counter<W> {
+    writer: W,
+    count: u64,
+}
 
-        reader.read_exact(&mut buffer[..]).unwrap();
-        assert_eq!(buffer[0], 1);
+impl<W> Counter<W> {
+    /// Wraps a writer, starting from a count of zero
+    #[inline]
+    pub const fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
 
-        reader.read_exact(&mut buffer[..]).unwrap_err();
+    /// The number of bytes written so far
+    #[inline]
+    pub const fn count(&self) -> u64 {
+        self.count
     }
 
-    #[test]
-    #[cfg(feature = "std")]
-    fn read_std() {
-        let mut reader = std::io::repeat(1);
-        let mut buffer = [0u8; 2];
+    /// A reference to the inner writer
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
 
-        reader.read_exact(&mut buffer[..]).unwrap();
-        assert_eq!(buffer[0], 1);
-        assert_eq!(buffer[1], 1);
+    /// A mutable reference to the inner writer
+    ///
+    /// Bytes written directly to the inner writer are not counted.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
     }
 
-    #[test]
-    fn write_oos() {
-        let mut writer = &mut [0u8; 0][..];
+    /// Returns the inner writer
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
 
-        writer.write_all(&[1u8; 1][..]).unwrap_err();
+impl<W: Write> Write for Counter<W> {
+    type Error = W::Error;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_all(data)?;
+        self.count += data.len() as u64;
+        Ok(())
     }
 
-    #[test]
-    fn write_one() {
-        let mut buffer = [0u8; 1];
-        let mut writer = &mut buffer[..];
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+}
 
-        writer.write_all(&[1u8; 1][..]).unwrap();
-        writer.write_all(&[1u8; 1][..]).unwrap_err();
-        assert_eq!(buffer[0], 1);
+/// A writer which discards all bytes, but counts them
+///
+/// This is useful to find the size of an encoding without storing it.
+///
+/// ```rust
+/// use ciborium_io::{Sink, Write};
+///
+/// let mut sink = Sink::new();
+/// sink.write_all(b"abc").unwrap();
+/// sink.write_all(b"de").unwrap();
+/// assert_eq!(sink.count(), 5);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Sink {
+    count: u64,
+}
+
+impl Sink {
+    /// Creates a sink with a count of zero
+    #[inline]
+    pub const fn new() -> Self {
+        Self { count: 0 }
     }
 
-    #[test]
-    fn write_two() {
-        let mut buffer = [0u8; 2];
-        let mut writer = &mut buffer[..];
+    /// The number of bytes written so far
+    #[inline]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+}
 
-        writer.write_all(&[1u8; 1][..]).unwrap();
-        writer.write_all(&[1u8; 1][..]).unwrap();
-        writer.write_all(&[1u8; 1][..]).unwrap_err();
-        assert_eq!(buffer[0], 1);
-        assert_eq!(buffer[1], 1);
+impl Write for Sink {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.count += data.len() as u64;
+        Ok(())
     }
 
-    #[test]
-    #[cfg(feature = "alloc")]
-    fn write_vec() {
-        let mut buffer = alloc::vec::Vec::new();
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
 
-        buffer.write_all(&[1u8; 1][..]).unwrap();
-        buffer.write_all(&[1u8; 1][..]).unwrap();
+/// A writer which writes all bytes to two writers
+///
+/// Each write goes to the first writer and then to the second one. If
+/// either fails, the error is returned at once, saying which writer failed;
+/// the second writer is not written to if the first one fails. Flushing
+/// flushes both writers in the same order, with the same policy.
+///
+/// Tees nest, so more than two writers can be combined, and they combine
+/// with the other writers in this crate.
+///
+/// ```rust
+/// use ciborium_io::{Counter, Tee, Write};
+///
+/// let mut socket = [0u8; 3];
+/// let mut log = [0u8; 3];
+///
+/// let mut tee = Tee::new(Counter::new(&mut socket[..]), &mut log[..]);
+/// tee.write_all(b"abc").unwrap();
+/// tee.flush().unwrap();
+///
+/// let (counter, _) = tee.into_inner();
+/// assert_eq!(counter.count(), 3);
+/// assert_eq!((&socket, &log), (b"abc", b"abc"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Tee<A, B> {
+    first: A,
+    second: B,
+}
 
-        assert_eq!(buffer.len(), 2);
-        assert_eq!(buffer[0], 1);
-        assert_eq!(buffer[1], 1);
+/// An error from one of the writers of a [`Tee`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TeeError<A, B> {
+    /// The first writer failed
+    First(A),
+
+    /// The second writer failed, after the first one succeeded
+    Second(B),
+}
+
+impl<A: core::fmt::Display, B: core::fmt::Display> core::fmt::Display for TeeError<A, B> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::First(e) => write!(f, "first writer failed: {}", e),
+            Self::Second(e) => write!(f, "second writer failed: {}", e),
+        }
     }
+}
 
-    #[test]
-    #[cfg(feature = "std")]
-    fn write_std() {
-        let mut writer = std::io::sink();
+impl<A, B> core::error::Error for TeeError<A, B>
+where
+    A: core::error::Error + 'static,
+    B: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::First(e) => Some(e),
+            Self::Second(e) => Some(e),
+        }
+    }
+}
 
+impl<A, B> Tee<A, B> {
+    /// Combines two writers
+    #[inline]
+    pub const fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// References to the two writers
+    #[inline]
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Mutable references to the two writers
+    #[inline]
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Returns the two writers
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    type Error = TeeError<A::Error, B::Error>;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.first.write_all(data).map_err(TeeError::First)?;
+        self.second.write_all(data).map_err(TeeError::Second)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.first.flush().map_err(TeeError::First)?;
+        self.second.flush().map_err(TeeError::Second)
+    }
+}
+
+/// A reader which fails once more than a limited number of bytes are read
+///
+/// Unlike `std::io::Take`, which reports the end of the input at the limit,
+/// a read past the limit fails with [`TakeError::LimitExceeded`]. This way,
+/// an input which is too large can be told apart from one which is
+/// truncated. A read which fails this way consumes nothing, neither from
+/// the inner reader nor from the limit.
+///
+/// ```rust
+/// use ciborium_io::{Read, Take, TakeError};
+///
+/// let mut take = Take::new(&b"abcdef"[..], 4);
+/// let mut buffer = [0u8; 3];
+///
+/// take.read_exact(&mut buffer[..]).unwrap();
+/// assert_eq!(&buffer, b"abc");
+/// assert_eq!(take.remaining(), 1);
+///
+/// assert!(matches!(
+///     take.read_exact(&mut buffer[..2]),
+///     Err(TakeError::LimitExceeded)
+/// ));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Take<R> {
+    reader: R,
+    remaining: u64,
+}
+
+/// An error from a [`Take`] reader
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TakeError<E> {
+    /// The inner reader failed
+    Io(E),
+
+    /// The read would have exceeded the limit
+    LimitExceeded,
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for TakeError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::LimitExceeded => f.write_str("input limit exceeded"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for TakeError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::LimitExceeded => None,
+        }
+    }
+}
+
+impl<R> Take<R> {
+    /// Wraps a reader, allowing at most `limit` bytes to be read
+    #[inline]
+    pub const fn new(reader: R, limit: u64) -> Self {
+        Self {
+            reader,
+            remaining: limit,
+        }
+    }
+
+    /// The number of bytes which can still be read
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Sets the number of bytes which can still be read
+    ///
+    /// This allows a reader to be reused for the next message of a stream.
+    #[inline]
+    pub fn set_limit(&mut self, limit: u64) {
+        self.remaining = limit;
+    }
+
+    /// A reference to the inner reader
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// A mutable reference to the inner reader
+    ///
+    /// Bytes read directly from the inner reader do not count towards the
+    /// limit.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Returns the inner reader
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Read for Take<R> {
+    type Error = TakeError<R::Error>;
+
+    #[inline]
+    fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
+        let len = data.len() as u64;
+        if len > self.remaining {
+            return Err(TakeError::LimitExceeded);
+        }
+
+        self.reader.read_exact(data).map_err(TakeError::Io)?;
+        self.remaining -= len;
+        Ok(())
+    }
+
+    /// Reaching the limit is not the end of the input, which goes on.
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        match error {
+            TakeError::Io(e) => R::is_eof(e),
+            TakeError::LimitExceeded => false,
+        }
+    }
+}
+
+/// A reader which can look ahead by up to `N` bytes
+///
+/// Peeked bytes are kept in a buffer, and reading drains this buffer before
+/// reading from the inner reader again. This makes it possible to sniff the
+/// format of an input before deciding how to read it.
+///
+/// ```rust
+/// use ciborium_io::{Peekable, Read};
+///
+/// // The self-described CBOR tag, followed by the data item `1`
+/// let mut reader = Peekable::<_, 3>::new(&[0xd9, 0xd9, 0xf7, 0x01][..]);
+///
+/// let mut magic = [0u8; 3];
+/// assert_eq!(reader.peek(&mut magic[..]).unwrap(), 3);
+/// assert_eq!(magic, [0xd9, 0xd9, 0xf7]);
+///
+/// let mut buffer = [0u8; 4];
+/// reader.read_exact(&mut buffer[..]).unwrap();
+/// assert_eq!(buffer, [0xd9, 0xd9, 0xf7, 0x01]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Peekable<R, const N: usize> {
+    reader: R,
+    buffer: [u8; N],
+    start: usize,
+    end: usize,
+}
+
+impl<R, const N: usize> Peekable<R, N> {
+    /// Wraps a reader with an empty lookahead buffer
+    #[inline]
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: [0; N],
+            start: 0,
+            end: 0,
+        }
+    }
+
+    /// The bytes which have been peeked but not read yet
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer[self.start..self.end]
+    }
+
+    /// A reference to the inner reader
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns the inner reader
+    ///
+    /// Any bytes which have been peeked but not read yet are lost; see
+    /// [`Peekable::buffer()`].
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read, const N: usize> Peekable<R, N> {
+    /// Copies the next bytes into `data` without consuming them
+    ///
+    /// At most `N` bytes are peeked. Returns how many bytes were copied,
+    /// which is the smaller of `data.len()` and `N`. Bytes are read from
+    /// the inner reader one at a time, so if it fails, for example at the
+    /// end of the input, the bytes read before the failure are still
+    /// returned by later reads.
+    pub fn peek(&mut self, data: &mut [u8]) -> Result<usize, R::Error> {
+        let want = data.len().min(N);
+
+        if self.start + want > N {
+            self.buffer.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        while self.end - self.start < want {
+            let end = self.end;
+            self.reader.read_exact(&mut self.buffer[end..end + 1])?;
+            self.end += 1;
+        }
+
+        data[..want].copy_from_slice(&self.buffer[self.start..self.start + want]);
+        Ok(want)
+    }
+}
+
+impl<R: Read, const N: usize> Read for Peekable<R, N> {
+    type Error = R::Error;
+
+    #[inline]
+    fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
+        let len = data.len().min(self.end - self.start);
+        data[..len].copy_from_slice(&self.buffer[self.start..self.start + len]);
+        self.start += len;
+
+        if self.start == self.end {
+            self.start = 0;
+            self.end = 0;
+        }
+
+        match &mut data[len..] {
+            [] => Ok(()),
+            rest => self.reader.read_exact(rest),
+        }
+    }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        R::is_eof(error)
+    }
+}
+
+/// A reader over an iterator of chunks of bytes
+///
+/// This adapts a message-oriented source, such as a channel of frames, to
+/// [`Read`]. The chunks can be any byte container, such as `Vec<u8>` or
+/// `&[u8]`, and need not line up with data items: the rest of a chunk is
+/// kept for the next read, and a read may span many chunks. Empty chunks
+/// are skipped.
+///
+/// A read fails with [`IterReadError::EndOfFile`] if the iterator ends
+/// first, or with [`IterReadError::Io`] if it yields an error. Either way,
+/// the bytes read so far by that call are lost.
+///
+/// ```rust
+/// use ciborium_io::{IterRead, Read};
+///
+/// let chunks = [Ok::<_, ()>(&b"ab"[..]), Ok(&b""[..]), Ok(&b"cde"[..])];
+/// let mut reader = IterRead::new(chunks.iter().copied());
+/// let mut buffer = [0u8; 3];
+///
+/// reader.read_exact(&mut buffer[..]).unwrap();
+/// assert_eq!(&buffer, b"abc");
+/// reader.read_exact(&mut buffer[..2]).unwrap();
+/// assert_eq!(&buffer[..2], b"de");
+/// reader.read_exact(&mut buffer[..1]).unwrap_err();
+/// ```
+#[derive(Clone, Debug)]
+pub struct IterRead<I: Iterator> {
+    chunks: I,
+    chunk: Option<I::Item>,
+    offset: usize,
+}
+
+/// An error from an [`IterRead`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IterReadError<E> {
+    /// The iterator yielded an error
+    Io(E),
+
+    /// The iterator ended before the read was done
+    EndOfFile,
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for IterReadError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::EndOfFile => f.write_str("unexpected end of input"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for IterReadError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::EndOfFile => None,
+        }
+    }
+}
+
+impl<I: Iterator> IterRead<I> {
+    /// Wraps an iterator of chunks
+    #[inline]
+    pub fn new(chunks: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            chunks: chunks.into_iter(),
+            chunk: None,
+            offset: 0,
+        }
+    }
+
+    /// Returns the iterator
+    ///
+    /// The unread part of the current chunk, if any, is dropped.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.chunks
+    }
+}
+
+impl<I, C, E> Read for IterRead<I>
+where
+    I: Iterator<Item = Result<C, E>>,
+    C: AsRef<[u8]>,
+{
+    type Error = IterReadError<E>;
+
+    fn read_exact(&mut self, mut data: &mut [u8]) -> Result<(), Self::Error> {
+        while !data.is_empty() {
+            let chunk = match &self.chunk {
+                Some(Ok(chunk)) if self.offset < chunk.as_ref().len() => chunk.as_ref(),
+                _ => {
+                    self.chunk = None;
+                    self.offset = 0;
+
+                    match self.chunks.next() {
+                        Some(Ok(chunk)) => self.chunk = Some(Ok(chunk)),
+                        Some(Err(e)) => return Err(IterReadError::Io(e)),
+                        None => return Err(IterReadError::EndOfFile),
+                    }
+
+                    continue;
+                }
+            };
+
+            let rest = &chunk[self.offset..];
+            let len = rest.len().min(data.len());
+            data[..len].copy_from_slice(&rest[..len]);
+            data = &mut data[len..];
+            self.offset += len;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        matches!(error, IterReadError::EndOfFile)
+    }
+}
+
+/// A buffered reader over a list of byte slices
+///
+/// The slices are read one after the other, as if they were joined, but
+/// they are never copied into one buffer. This suits input which is not
+/// contiguous in memory, such as the two halves of a ring buffer. Empty
+/// slices are skipped.
+///
+/// The buffer of the reader is the rest of the current slice. So, a decoder
+/// can use the bytes of a string in place when they are all in one slice,
+/// and must copy them only when they span two. A read fails with
+/// [`IterReadError::EndOfFile`] if the slices end first.
+///
+/// ```rust
+/// use ciborium_io::{BufRead, Read, Slices};
+///
+/// let (head, tail) = (&b"abc"[..], &b"de"[..]);
+/// let mut reader = Slices::new([head, tail]);
+/// let mut buffer = [0u8; 2];
+///
+/// assert_eq!(reader.fill_buf().unwrap(), b"abc");
+/// reader.consume(2);
+///
+/// // This read spans both slices.
+/// reader.read_exact(&mut buffer[..]).unwrap();
+/// assert_eq!(&buffer, b"cd");
+/// assert_eq!(reader.fill_buf().unwrap(), b"e");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Slices<'a, I> {
+    slices: I,
+    slice: &'a [u8],
+}
+
+impl<'a, I: Iterator<Item = &'a [u8]>> Slices<'a, I> {
+    /// Wraps a list of slices
+    #[inline]
+    pub fn new(slices: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            slices: slices.into_iter(),
+            slice: &[],
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a [u8]>> Read for Slices<'a, I> {
+    type Error = IterReadError<core::convert::Infallible>;
+
+    fn read_exact(&mut self, mut data: &mut [u8]) -> Result<(), Self::Error> {
+        while !data.is_empty() {
+            let rest = self.fill_buf()?;
+            if rest.is_empty() {
+                return Err(IterReadError::EndOfFile);
+            }
+
+            let len = rest.len().min(data.len());
+            data[..len].copy_from_slice(&rest[..len]);
+            data = &mut data[len..];
+            self.consume(len);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        matches!(error, IterReadError::EndOfFile)
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a [u8]>> BufRead for Slices<'a, I> {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8], Self::Error> {
+        while self.slice.is_empty() {
+            match self.slices.next() {
+                Some(slice) => self.slice = slice,
+                None => break,
+            }
+        }
+
+        Ok(self.slice)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.slice = &self.slice[amt..];
+    }
+}
+
+/// A writer which passes all bytes to a function
+///
+/// Each call to [`Write::write_all()`] calls the function once with the
+/// same bytes, and its errors are passed through. This adapts a
+/// message-oriented sink to [`Write`]. Flushing does nothing.
+///
+/// ```rust
+/// use ciborium_io::{FnWrite, Write};
+///
+/// let mut count = 0;
+/// let mut writer = FnWrite::new(|data: &[u8]| {
+///     count += data.len();
+///     Ok::<_, ()>(())
+/// });
+///
+/// writer.write_all(b"abc").unwrap();
+/// writer.write_all(b"de").unwrap();
+/// assert_eq!(count, 5);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FnWrite<F>(F);
+
+impl<F> FnWrite<F> {
+    /// Wraps a function
+    #[inline]
+    pub const fn new(function: F) -> Self {
+        Self(function)
+    }
+
+    /// Returns the function
+    #[inline]
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+}
+
+impl<F, E> Write for FnWrite<F>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    type Error = E;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        (self.0)(data)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The default wait of a [`BlockingAdapter`], which yields the thread
+#[cfg(feature = "std")]
+pub type Yield = fn(u32) -> std::io::Result<()>;
+
+/// A writer which retries writes to a nonblocking writer until they finish
+///
+/// When the inner writer fails with `std::io::ErrorKind::WouldBlock`, the
+/// adapter calls its wait function and tries again; writes which are
+/// interrupted are retried at once. Any other error is returned. The wait
+/// function gets the number of retries since the last progress, starting
+/// from zero, so it can back off or time out. If it returns an error, the
+/// write stops with that error. By default, the thread yields.
+///
+/// This is needed because encoding is not resumable: once a write fails,
+/// part of a data item may have been written and the rest is lost. With
+/// the adapter, a nonblocking writer can be used with an encoder or
+/// serializer as if it were blocking. Alternatively, a value can be encoded
+/// into a `Vec<u8>` first and written out with the event loop.
+///
+/// ```rust
+/// use ciborium_io::{BlockingAdapter, Write};
+///
+/// let mut waits = 0;
+/// let mut writer = BlockingAdapter::new(Vec::new()).with_wait(|_| {
+///     waits += 1;
+///     Ok(())
+/// });
+///
+/// writer.write_all(b"abc").unwrap();
+/// writer.flush().unwrap();
+/// assert_eq!(writer.into_inner(), b"abc");
+/// assert_eq!(waits, 0);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct BlockingAdapter<W, F = Yield> {
+    writer: W,
+    wait: F,
+}
+
+#[cfg(feature = "std")]
+impl<W> BlockingAdapter<W> {
+    /// Wraps a writer, yielding the thread while it would block
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            wait: |_| {
+                std::thread::yield_now();
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W, F> BlockingAdapter<W, F> {
+    /// Replaces the function which is called while the writer would block
+    #[inline]
+    pub fn with_wait<G>(self, wait: G) -> BlockingAdapter<W, G>
+    where
+        G: FnMut(u32) -> std::io::Result<()>,
+    {
+        BlockingAdapter {
+            writer: self.writer,
+            wait,
+        }
+    }
+
+    /// A reference to the inner writer
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// A mutable reference to the inner writer
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the inner writer
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W, F> BlockingAdapter<W, F>
+where
+    F: FnMut(u32) -> std::io::Result<()>,
+{
+    /// Calls `op` until it finishes, waiting while it would block
+    fn retry<T>(&mut self, mut op: impl FnMut(&mut W) -> std::io::Result<T>) -> std::io::Result<T> {
+        use std::io::ErrorKind;
+
+        let mut retries = 0;
+        loop {
+            match op(&mut self.writer) {
+                Ok(x) => return Ok(x),
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    (self.wait)(retries)?;
+                    retries = retries.saturating_add(1);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W, F> Write for BlockingAdapter<W, F>
+where
+    W: std::io::Write,
+    F: FnMut(u32) -> std::io::Result<()>,
+{
+    type Error = std::io::Error;
+
+    fn write_all(&mut self, mut data: &[u8]) -> Result<(), Self::Error> {
+        while !data.is_empty() {
+            match self.retry(|w| w.write(data))? {
+                0 => return Err(std::io::ErrorKind::WriteZero.into()),
+                n => data = &data[n..],
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.retry(|w| w.flush())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncRead + Unpin + ?Sized> AsyncRead for &mut R {
+    type Error = R::Error;
+
+    #[inline]
+    fn poll_read(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        data: &mut [u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>> {
+        core::pin::Pin::new(&mut **self).poll_read(cx, data)
+    }
+
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        R::is_eof(error)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<W: AsyncWrite + Unpin + ?Sized> AsyncWrite for &mut W {
+    type Error = W::Error;
+
+    #[inline]
+    fn poll_write(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+        data: &[u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>> {
+        core::pin::Pin::new(&mut **self).poll_write(cx, data)
+    }
+
+    #[inline]
+    fn poll_flush(
+        mut self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        core::pin::Pin::new(&mut **self).poll_flush(cx)
+    }
+
+    #[inline]
+    fn write_zero() -> Option<Self::Error> {
+        W::write_zero()
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncRead for &[u8] {
+    #[cfg(feature = "std")]
+    type Error = std::io::Error;
+
+    #[cfg(not(feature = "std"))]
+    type Error = EndOfFile;
+
+    #[inline]
+    fn poll_read(
+        mut self: core::pin::Pin<&mut Self>,
+        _cx: &mut core::task::Context<'_>,
+        data: &mut [u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>> {
+        if self.is_empty() {
+            #[cfg(feature = "std")]
+            let error = std::io::ErrorKind::UnexpectedEof.into();
+
+            #[cfg(not(feature = "std"))]
+            let error = EndOfFile(());
+
+            return core::task::Poll::Ready(Err(error));
+        }
+
+        let len = data.len().min(self.len());
+        let (prefix, suffix) = self.split_at(len);
+        data[..len].copy_from_slice(prefix);
+        *self = suffix;
+        core::task::Poll::Ready(Ok(len))
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn is_eof(error: &Self::Error) -> bool {
+        error.kind() == std::io::ErrorKind::UnexpectedEof
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[inline]
+    fn is_eof(_: &Self::Error) -> bool {
+        true
+    }
+}
+
+#[cfg(all(feature = "async", feature = "alloc"))]
+impl AsyncWrite for alloc::vec::Vec<u8> {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn poll_write(
+        mut self: core::pin::Pin<&mut Self>,
+        _cx: &mut core::task::Context<'_>,
+        data: &[u8],
+    ) -> core::task::Poll<Result<usize, Self::Error>> {
+        self.extend_from_slice(data);
+        core::task::Poll::Ready(Ok(data.len()))
+    }
+
+    #[inline]
+    fn poll_flush(
+        self: core::pin::Pin<&mut Self>,
+        _cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        core::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_eof() {
+        let mut reader = &[1u8; 0][..];
+        let mut buffer = [0u8; 1];
+
+        let error = reader.read_exact(&mut buffer[..]).unwrap_err();
+        assert!(<&[u8] as Read>::is_eof(&error));
+    }
+
+    #[test]
+    fn read_one() {
+        let mut reader = &[1u8; 1][..];
+        let mut buffer = [0u8; 1];
+
+        reader.read_exact(&mut buffer[..]).unwrap();
+        assert_eq!(buffer[0], 1);
+
+        reader.read_exact(&mut buffer[..]).unwrap_err();
+    }
+
+    #[test]
+    fn read_two() {
+        let mut reader = &[1u8; 2][..];
+        let mut buffer = [0u8; 1];
+
+        reader.read_exact(&mut buffer[..]).unwrap();
+        assert_eq!(buffer[0], 1);
+
+        reader.read_exact(&mut buffer[..]).unwrap();
+        assert_eq!(buffer[0], 1);
+
+        reader.read_exact(&mut buffer[..]).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn read_std() {
+        let mut reader = std::io::repeat(1);
+        let mut buffer = [0u8; 2];
+
+        reader.read_exact(&mut buffer[..]).unwrap();
+        assert_eq!(buffer[0], 1);
+        assert_eq!(buffer[1], 1);
+    }
+
+    #[test]
+    fn buf_read() {
+        let mut reader = &[1u8, 2, 3][..];
+        let mut buffer = [0u8; 1];
+
+        assert_eq!(BufRead::fill_buf(&mut reader).unwrap(), &[1, 2, 3]);
+        BufRead::consume(&mut reader, 2);
+        assert_eq!(BufRead::fill_buf(&mut reader).unwrap(), &[3]);
+
+        Read::read_exact(&mut reader, &mut buffer[..]).unwrap();
+        assert_eq!(buffer[0], 3);
+        assert!(BufRead::fill_buf(&mut reader).unwrap().is_empty());
+    }
+
+    #[test]
+    fn write_oos() {
+        let mut writer = &mut [0u8; 0][..];
+
+        writer.write_all(&[1u8; 1][..]).unwrap_err();
+    }
+
+    #[test]
+    fn write_one() {
+        let mut buffer = [0u8; 1];
+        let mut writer = &mut buffer[..];
+
+        writer.write_all(&[1u8; 1][..]).unwrap();
+        writer.write_all(&[1u8; 1][..]).unwrap_err();
+        assert_eq!(buffer[0], 1);
+    }
+
+    #[test]
+    fn write_two() {
+        let mut buffer = [0u8; 2];
+        let mut writer = &mut buffer[..];
+
+        writer.write_all(&[1u8; 1][..]).unwrap();
+        writer.write_all(&[1u8; 1][..]).unwrap();
+        writer.write_all(&[1u8; 1][..]).unwrap_err();
+        assert_eq!(buffer[0], 1);
+        assert_eq!(buffer[1], 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn write_vec() {
+        let mut buffer = alloc::vec::Vec::new();
+
+        buffer.write_all(&[1u8; 1][..]).unwrap();
+        buffer.write_all(&[1u8; 1][..]).unwrap();
+
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer[0], 1);
+        assert_eq!(buffer[1], 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn vec_deque() {
+        let mut deque = alloc::collections::VecDeque::with_capacity(8);
+        let mut buffer = [0u8; 8];
+
+        // Rotate the deque, so that its contents wrap around.
+        Write::write_all(&mut &mut deque, &[0u8; 6][..]).unwrap();
+        Read::read_exact(&mut &mut deque, &mut buffer[..5]).unwrap();
+        Write::write_all(&mut &mut deque, &[1u8, 2, 3, 4, 5, 6][..]).unwrap();
+        assert!(!deque.as_slices().1.is_empty());
+
+        // Read across the two halves.
+        Read::read_exact(&mut &mut deque, &mut buffer[..6]).unwrap();
+        assert_eq!(buffer[..6], [0, 1, 2, 3, 4, 5]);
+
+        Write::write_all(&mut &mut deque, &[7u8][..]).unwrap();
+        Read::read_exact(&mut &mut deque, &mut buffer[..2]).unwrap();
+        assert_eq!(buffer[..2], [6, 7]);
+        assert!(deque.is_empty());
+
+        Read::read_exact(&mut &mut deque, &mut buffer[..1]).unwrap_err();
+        Read::read_exact(&mut &mut deque, &mut buffer[..0]).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(not(feature = "std"), feature = "alloc"))]
+    fn vec_deque_eof() {
+        let mut deque: alloc::collections::VecDeque<u8> = [1u8, 2, 3].iter().copied().collect();
+        deque.rotate_left(2);
+        let mut buffer = [0u8; 4];
+
+        // A short read consumes nothing.
+        Read::read_exact(&mut deque, &mut buffer[..]).unwrap_err();
+        assert_eq!(deque, [3, 1, 2]);
+
+        Read::read_exact(&mut deque, &mut buffer[..3]).unwrap();
+        assert_eq!(buffer[..3], [3, 1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn write_std() {
+        let mut writer = std::io::sink();
+
+        writer.write_all(&[1u8; 1][..]).unwrap();
+        writer.write_all(&[1u8; 1][..]).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn read_async() {
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut reader = &[1u8, 2, 3][..];
+        let mut buffer = [0u8; 2];
+
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buffer[..]);
+        assert!(matches!(poll, Poll::Ready(Ok(2))));
+        assert_eq!(buffer, [1, 2]);
+
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buffer[..]);
+        assert!(matches!(poll, Poll::Ready(Ok(1))));
+        assert_eq!(buffer[0], 3);
+
+        let poll = Pin::new(&mut reader).poll_read(&mut cx, &mut buffer[..]);
+        assert!(matches!(poll, Poll::Ready(Err(..))));
+    }
+
+    #[test]
+    #[cfg(all(feature = "async", feature = "alloc"))]
+    fn write_async() {
+        use core::pin::Pin;
+        use core::task::{Context, Poll, Waker};
+
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut buffer = alloc::vec::Vec::new();
+
+        let poll = Pin::new(&mut &mut buffer).poll_write(&mut cx, &[1u8, 2][..]);
+        assert!(matches!(poll, Poll::Ready(Ok(2))));
+        assert!(matches!(
+            Pin::new(&mut buffer).poll_flush(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert_eq!(buffer, [1, 2]);
+    }
+
+    #[test]
+    fn counter() {
+        let mut buffer = [0u8; 3];
+        let mut counter = Counter::new(&mut buffer[..]);
+
+        counter.write_all(&[1u8; 2][..]).unwrap();
+        assert_eq!(counter.count(), 2);
+
+        counter.write_all(&[2u8; 1][..]).unwrap();
+        assert_eq!(counter.count(), 3);
+        assert!(counter.get_ref().is_empty());
+
+        // A failed write is not counted.
+        counter.write_all(&[3u8; 1][..]).unwrap_err();
+        assert_eq!(counter.count(), 3);
+        assert_eq!(buffer, [1, 1, 2]);
+    }
+
+    #[test]
+    fn counter_flush() {
+        struct Flushes(usize);
+
+        #[cfg(feature = "std")]
+        impl std::io::Write for Flushes {
+            fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+                Ok(data.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+
+        #[cfg(not(feature = "std"))]
+        impl Write for Flushes {
+            type Error = ();
+
+            fn write_all(&mut self, _data: &[u8]) -> Result<(), ()> {
+                Ok(())
+            }
+
+            fn flush(&mut self) -> Result<(), ()> {
+                self.0 += 1;
+                Ok(())
+            }
+        }
+
+        let mut counter = Counter::new(Flushes(0));
+        counter.write_all(&[1u8; 4][..]).unwrap();
+        counter.flush().unwrap();
+        counter.flush().unwrap();
+        assert_eq!(counter.count(), 4);
+        assert_eq!(counter.into_inner().0, 2);
+    }
+
+    #[test]
+    fn sink() {
+        let mut sink = Sink::new();
+        sink.write_all(&[1u8; 3][..]).unwrap();
+        sink.write_all(&[][..]).unwrap();
+        sink.flush().unwrap();
+        sink.write_all(&[1u8; 2][..]).unwrap();
+        assert_eq!(sink.count(), 5);
+    }
+
+    #[test]
+    fn tee() {
+        let mut first = [0u8; 3];
+        let mut second = [0u8; 2];
+        let mut tee = Tee::new(Counter::new(&mut first[..]), Counter::new(&mut second[..]));
+
+        tee.write_all(&[1u8; 2][..]).unwrap();
+        tee.flush().unwrap();
+
+        // The first writer has space left, but the second one does not.
+        match tee.write_all(&[2u8; 1][..]).unwrap_err() {
+            TeeError::Second(..) => (),
+            TeeError::First(..) => panic!("the first writer should succeed"),
+        }
+
+        // The first writer is full, so the second one is not written to.
+        match tee.write_all(&[3u8; 1][..]).unwrap_err() {
+            TeeError::First(..) => (),
+            TeeError::Second(..) => panic!("the first writer should fail"),
+        }
+
+        let (a, b) = tee.into_inner();
+        assert_eq!((a.count(), b.count()), (3, 2));
+        assert_eq!(first, [1, 1, 2]);
+        assert_eq!(second, [1, 1]);
+    }
+
+    #[test]
+    fn tee_sinks() {
+        let mut tee = Tee::new(Sink::new(), Tee::new(Sink::new(), Sink::new()));
+        tee.write_all(&[1u8; 4][..]).unwrap();
+        tee.flush().unwrap();
+
+        let (a, rest) = tee.into_inner();
+        let (b, c) = rest.into_inner();
+        assert_eq!((a.count(), b.count(), c.count()), (4, 4, 4));
+    }
+
+    #[test]
+    fn take() {
+        let mut take = Take::new(&[1u8, 2, 3, 4][..], 3);
+        let mut buffer = [0u8; 2];
+
+        take.read_exact(&mut buffer[..]).unwrap();
+        assert_eq!(take.remaining(), 1);
+
+        // The limit is checked before reading anything, and is not the
+        // end of the input.
+        match take.read_exact(&mut buffer[..]) {
+            Err(e @ TakeError::LimitExceeded) => assert!(!Take::<&[u8]>::is_eof(&e)),
+            _ => panic!("the limit should be exceeded"),
+        }
+
+        take.read_exact(&mut buffer[..1]).unwrap();
+        assert_eq!((take.remaining(), buffer[0]), (0, 3));
+        take.read_exact(&mut buffer[..0]).unwrap();
+
+        // The inner reader still fails on its own.
+        take.set_limit(5);
+        match take.read_exact(&mut buffer[..]) {
+            Err(e @ TakeError::Io(..)) => assert!(Take::<&[u8]>::is_eof(&e)),
+            _ => panic!("the inner reader should fail"),
+        }
+    }
+
+    #[test]
+    fn peekable() {
+        let mut reader = Peekable::<_, 4>::new(&[1u8, 2, 3, 4, 5, 6][..]);
+        let mut buffer = [0u8; 8];
+
+        assert_eq!(reader.peek(&mut buffer[..2]).unwrap(), 2);
+        assert_eq!(buffer[..2], [1, 2]);
+
+        // Peeking is limited to the size of the lookahead buffer.
+        assert_eq!(reader.peek(&mut buffer[..]).unwrap(), 4);
+        assert_eq!(buffer[..4], [1, 2, 3, 4]);
+
+        reader.read_exact(&mut buffer[..1]).unwrap();
+        assert_eq!((buffer[0], reader.buffer()), (1, &[2, 3, 4][..]));
+
+        // The buffered bytes are shifted to make room.
+        assert_eq!(reader.peek(&mut buffer[..4]).unwrap(), 4);
+        assert_eq!(buffer[..4], [2, 3, 4, 5]);
+
+        reader.read_exact(&mut buffer[..5]).unwrap();
+        assert_eq!(buffer[..5], [2, 3, 4, 5, 6]);
+        assert!(reader.buffer().is_empty());
+
+        reader.peek(&mut buffer[..1]).unwrap_err();
+        reader.read_exact(&mut buffer[..1]).unwrap_err();
+        assert_eq!(reader.peek(&mut buffer[..0]).unwrap(), 0);
+    }
+
+    #[test]
+    fn peekable_eof() {
+        let mut reader = Peekable::<_, 4>::new(&[1u8, 2][..]);
+        let mut buffer = [0u8; 4];
+
+        // The bytes before the end are kept.
+        reader.peek(&mut buffer[..]).unwrap_err();
+        assert_eq!(reader.buffer(), &[1, 2]);
+
+        reader.read_exact(&mut buffer[..2]).unwrap();
+        assert_eq!(buffer[..2], [1, 2]);
+    }
+
+    #[test]
+    fn iter_read() {
+        let input = [1u8, 2, 3, 4, 5, 6, 7];
+
+        // Split the input in three at every pair of offsets, and read it
+        // back split in two at every offset.
+        for a in 0..=input.len() {
+            for b in a..=input.len() {
+                for c in 0..=input.len() {
+                    let chunks = [&input[..a], &input[a..b], &input[b..]];
+                    let mut reader = IterRead::new(chunks.iter().map(Ok::<_, ()>));
+                    let mut buffer = [0u8; 7];
+
+                    reader.read_exact(&mut buffer[..c]).unwrap();
+                    reader.read_exact(&mut buffer[c..]).unwrap();
+                    assert_eq!(buffer, input);
+
+                    match reader.read_exact(&mut buffer[..1]) {
+                        Err(IterReadError::EndOfFile) => (),
+                        _ => panic!("the input should end"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iter_read_error() {
+        let chunks = [Ok(&[1u8, 2][..]), Err(7), Ok(&[3u8][..])];
+        let mut reader = IterRead::new(chunks.iter().copied());
+        let mut buffer = [0u8; 3];
+
+        match reader.read_exact(&mut buffer[..]) {
+            Err(e @ IterReadError::Io(7)) => assert!(!IterRead::<
+                core::iter::Empty<Result<&[u8], i32>>,
+            >::is_eof(&e)),
+            _ => panic!("the error should be passed through"),
+        }
+
+        // Reading continues after the error.
+        reader.read_exact(&mut buffer[..1]).unwrap();
+        assert_eq!(buffer[0], 3);
+        reader.read_exact(&mut buffer[..0]).unwrap();
+    }
+
+    #[test]
+    fn slices() {
+        let input = [1u8, 2, 3, 4, 5, 6, 7];
+
+        // Split the input in three at every pair of offsets, and read it
+        // back split in two at every offset.
+        for a in 0..=input.len() {
+            for b in a..=input.len() {
+                for c in 0..=input.len() {
+                    let mut reader = Slices::new([&input[..a], &input[a..b], &input[b..]]);
+                    let mut buffer = [0u8; 7];
+
+                    reader.read_exact(&mut buffer[..c]).unwrap();
+                    reader.read_exact(&mut buffer[c..]).unwrap();
+                    assert_eq!(buffer, input);
+
+                    assert!(reader.fill_buf().unwrap().is_empty());
+                    match reader.read_exact(&mut buffer[..1]) {
+                        Err(IterReadError::EndOfFile) => (),
+                        _ => panic!("the input should end"),
+                    }
+                }
+            }
+        }
+
+        // The buffer never spans two slices.
+        let mut reader = Slices::new([&input[..2], &[][..], &input[2..]]);
+        assert_eq!(reader.fill_buf().unwrap(), [1, 2]);
+        reader.consume(1);
+        assert_eq!(reader.fill_buf().unwrap(), [2]);
+        reader.consume(1);
+        assert_eq!(reader.fill_buf().unwrap(), [3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn fn_write() {
+        let mut buffer = [0u8; 4];
+        let mut len = 0;
+        let mut calls = 0;
+
+        let mut writer = FnWrite::new(|data: &[u8]| {
+            calls += 1;
+            let end = len + data.len();
+            if end > buffer.len() {
+                return Err(end);
+            }
+
+            buffer[len..end].copy_from_slice(data);
+            len = end;
+            Ok(())
+        });
+
+        writer.write_all(&[1u8, 2][..]).unwrap();
+        writer.write_all(&[3u8][..]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.write_all(&[4u8, 5][..]), Err(5));
+        assert_eq!((calls, len), (3, 3));
+        assert_eq!(buffer[..3], [1, 2, 3]);
+    }
+
+    /// A nonblocking writer which would block on every other write
+    #[cfg(feature = "std")]
+    struct Nonblocking {
+        data: alloc::vec::Vec<u8>,
+        ready: bool,
+    }
+
+    #[cfg(feature = "std")]
+    impl std::io::Write for Nonblocking {
+        fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+            self.ready = !self.ready;
+            if !self.ready {
+                return Err(std::io::ErrorKind::WouldBlock.into());
+            }
+
+            let n = data.len().min(2);
+            self.data.extend_from_slice(&data[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn blocking_adapter() {
+        let inner = Nonblocking {
+            data: alloc::vec::Vec::new(),
+            ready: true,
+        };
+
+        let mut waits = alloc::vec::Vec::new();
+        let mut writer = BlockingAdapter::new(inner).with_wait(|n| {
+            waits.push(n);
+            Ok(())
+        });
+
+        writer.write_all(&[1u8, 2, 3, 4, 5][..]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.into_inner().data, [1, 2, 3, 4, 5]);
+
+        // Each of the three partial writes blocked once first.
+        assert_eq!(waits, [0, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn blocking_adapter_timeout() {
+        use std::io::ErrorKind;
+
+        let mut writer = BlockingAdapter::new(std::io::sink()).with_wait(|_| unreachable!());
+        writer.write_all(&[1u8; 4][..]).unwrap();
+
+        let inner = Nonblocking {
+            data: alloc::vec::Vec::new(),
+            ready: false,
+        };
+
+        // The wait function can give up.
+        let mut writer = BlockingAdapter::new(inner)
+            .with_wait(|_| Err(std::io::Error::new(ErrorKind::TimedOut, "timed out")));
+
+        let err = writer.write_all(&[1u8; 4][..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(writer.get_ref().data, [1, 1]);
+
+        // A full writer is an error rather than a loop.
+        let mut buffer = [0u8; 1];
+        let mut writer = BlockingAdapter::new(&mut buffer[..]);
         writer.write_all(&[1u8; 1][..]).unwrap();
-        writer.write_all(&[1u8; 1][..]).unwrap();
+        let err = writer.write_all(&[1u8; 1][..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
     }
 }
@@ -1,6 +1,6 @@
 use super::*;
 
-use ciborium_io::Read;
+use ciborium_io::{BufRead, Read};
 
 use core::convert::TryInto;
 
@@ -16,6 +16,150 @@ pub enum Error<T> {
     ///
     /// Contains the offset into the stream where the syntax error occurred.
     Syntax(usize),
+
+    /// A break which does not end an indefinite-length container or string
+    ///
+    /// This is a stray break, or one in place of an item of a definite-length
+    /// container, of the value of a map entry or of the item wrapped by a tag.
+    UnexpectedBreak {
+        /// The offset of the break
+        offset: usize,
+    },
+
+    /// An indefinite-length string is followed by an item which is not a chunk
+    ///
+    /// Only definite-length strings of the same type are chunks, so this is
+    /// any other item, including a string of the other type or of
+    /// indefinite length. The encoder most likely forgot the break which
+    /// ends the string. The
+    /// break of an indefinite-length array or map cannot be found missing
+    /// until the input ends, which is reported as the end of the input.
+    MissingBreak {
+        /// The offset of the header of the string
+        container_offset: usize,
+    },
+
+    /// A definite-length array or map is cut short by a break
+    ///
+    /// The break belongs to an indefinite-length container around it, which
+    /// ends before the items the array or map declares. Arrays count items
+    /// and maps count entries. This is raised by [`Events`](crate::Events),
+    /// which tracks the containers around each item.
+    ContainerUnderflow {
+        /// The offset of the header of the array or map
+        offset: usize,
+
+        /// The number of items or entries in the header
+        declared: usize,
+
+        /// The number of whole items or entries before the break
+        actual: usize,
+    },
+
+    /// A text string, or one of its chunks, is not valid UTF-8
+    ///
+    /// A chunk which ends within a character is invalid, even if the next
+    /// chunk completes the character.
+    InvalidUtf8 {
+        /// The index of the chunk, which is zero for a definite length
+        chunk: usize,
+
+        /// The offset of the header of the chunk
+        offset: usize,
+    },
+
+    /// The input goes beyond one of the limits of the decoder
+    ///
+    /// Nesting too deeply is rejected to prevent a stack overflow, and an
+    /// item budget bounds the work spent on hostile input. Every limit is
+    /// reported the same way, so that they can be handled alike.
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: LimitKind,
+
+        /// The value of the limit
+        limit: u64,
+
+        /// The value which the input reached, beyond the limit
+        observed: u64,
+
+        /// The offset of the header which went beyond the limit
+        offset: usize,
+    },
+}
+
+/// A limit on the input, as reported by [`Error::LimitExceeded`]
+///
+/// More limits may be added later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LimitKind {
+    /// How many levels of containers may be open
+    ///
+    /// See [`Decoder::with_max_depth()`], [`Decoder::skip_with_max_depth()`]
+    /// and [`Limits::max_depth()`].
+    Depth,
+
+    /// How many headers may be pulled
+    ///
+    /// See [`Decoder::with_item_budget()`].
+    Items,
+
+    /// How many bytes a length-prefixed frame may have
+    ///
+    /// This is not checked by the decoder, but by readers of framed input,
+    /// such as ciborium's `framed` module.
+    FrameLength,
+}
+
+impl core::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Depth => f.write_str("nesting depth"),
+            Self::Items => f.write_str("item count"),
+            Self::FrameLength => f.write_str("frame length"),
+        }
+    }
+}
+
+impl<T> Error<T> {
+    /// The error for going beyond the limit of this kind
+    #[inline]
+    pub(crate) fn limit(kind: LimitKind, limit: usize, offset: usize) -> Self {
+        let limit = limit as u64;
+
+        Self::LimitExceeded {
+            kind,
+            limit,
+            observed: limit.saturating_add(1),
+            offset,
+        }
+    }
+}
+
+/// How many more levels of containers may be opened, out of a maximum
+///
+/// This is for callers which recurse into containers themselves.
+#[derive(Copy, Clone)]
+pub(crate) struct Depth {
+    left: usize,
+    max: usize,
+}
+
+impl Depth {
+    #[inline]
+    pub(crate) fn new(max: usize) -> Self {
+        Self { left: max, max }
+    }
+
+    /// Opens a level for the container whose header is at `offset`
+    #[inline]
+    pub(crate) fn open<T>(self, offset: usize) -> Result<Self, Error<T>> {
+        match self.left.checked_sub(1) {
+            Some(left) => Ok(Self { left, ..self }),
+            None => Err(Error::limit(LimitKind::Depth, self.max, offset)),
+        }
+    }
 }
 
 impl<T> From<T> for Error<T> {
@@ -25,15 +169,73 @@ impl<T> From<T> for Error<T> {
     }
 }
 
+impl<T: core::fmt::Display> core::fmt::Display for Error<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::Syntax(offset) => write!(f, "syntax error at offset {}", offset),
+            Self::UnexpectedBreak { offset } => write!(f, "unexpected break at offset {}", offset),
+            Self::MissingBreak { container_offset } => write!(
+                f,
+                "missing break for the string at offset {}",
+                container_offset
+            ),
+            Self::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "the container at offset {} declares {} items but has {}",
+                offset, declared, actual
+            ),
+            Self::InvalidUtf8 { chunk, offset } => write!(
+                f,
+                "invalid UTF-8 in chunk {} of the text at offset {}",
+                chunk, offset
+            ),
+            Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            } => write!(
+                f,
+                "{} limit of {} exceeded at offset {}, with {}",
+                kind, limit, offset, observed
+            ),
+        }
+    }
+}
+
+impl<T: core::error::Error + 'static> core::error::Error for Error<T> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// A decoder for deserializing CBOR items
 ///
 /// This decoder manages the low-level decoding of CBOR items into `Header`
 /// objects. It also contains utility functions for parsing segmented bytes
 /// and text inputs.
+///
+/// With the `alloc` feature, the decoder can also track how deeply the
+/// items it pulls are nested, and limit it. See [`Decoder::with_max_depth()`]. The number of
+/// headers it pulls can be limited too; see [`Decoder::with_item_budget()`].
 pub struct Decoder<R: Read> {
     reader: R,
     offset: usize,
     buffer: Option<Title>,
+    budget: Option<usize>,
+    max_items: usize,
+    #[cfg(feature = "alloc")]
+    nesting: Option<crate::nest::Nesting>,
+    #[cfg(feature = "alloc")]
+    record: Option<alloc::vec::Vec<u8>>,
 }
 
 impl<R: Read> From<R> for Decoder<R> {
@@ -43,10 +245,105 @@ impl<R: Read> From<R> for Decoder<R> {
             reader: value,
             offset: 0,
             buffer: None,
+            budget: None,
+            max_items: 0,
+            #[cfg(feature = "alloc")]
+            nesting: None,
+            #[cfg(feature = "alloc")]
+            record: None,
         }
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<R: Read> Decoder<R> {
+    /// Creates a decoder which allows at most `max_depth` nested levels
+    ///
+    /// Every array, map and tag opens a level, which is closed once all of
+    /// its items have been pulled: after the last item of a definite-length
+    /// container, after the break of an indefinite-length one, or after
+    /// the item wrapped by a tag. An empty container opens no level, and
+    /// neither do strings, including the chunks of indefinite-length ones.
+    ///
+    /// Pulling a header which would open a level beyond the limit fails
+    /// with [`Error::LimitExceeded`] for [`LimitKind::Depth`], at the offset
+    /// of the header. A decoder created with `from()` has no limit, and it
+    /// does not track the depth, so that pulling costs nothing more.
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Decoder, Error, Header, LimitKind};
+    ///
+    /// let bytes = [0x81, 0x81, 0x81, 0x00];
+    /// let mut decoder = Decoder::with_max_depth(&bytes[..], 2);
+    ///
+    /// assert_eq!(decoder.pull().unwrap(), Header::Array(Some(1)));
+    /// assert_eq!(decoder.pull().unwrap(), Header::Array(Some(1)));
+    /// assert_eq!(decoder.depth(), Some(2));
+    /// assert!(matches!(
+    ///     decoder.pull(),
+    ///     Err(Error::LimitExceeded {
+    ///         kind: LimitKind::Depth,
+    ///         limit: 2,
+    ///         observed: 3,
+    ///         offset: 2,
+    ///     })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn with_max_depth(reader: R, max_depth: usize) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            buffer: None,
+            budget: None,
+            max_items: 0,
+            nesting: Some(crate::nest::Nesting::new(max_depth)),
+            record: None,
+        }
+    }
+
+    /// Gets the number of levels which are currently open
+    ///
+    /// This includes the effect of the last header pulled: it is one after
+    /// pulling the header of a non-empty array, and it drops back to zero
+    /// as soon as the last item of the array is pulled. A header pushed
+    /// back with [`Decoder::push()`] is not counted until it is pulled
+    /// again. It is `None` for a decoder which does not track the depth,
+    /// because it was not created by [`Decoder::with_max_depth()`].
+    #[inline]
+    pub fn depth(&self) -> Option<usize> {
+        self.nesting.as_ref().map(|x| x.depth())
+    }
+
+    /// Reads one complete data item, appending its encoding to `buffer`
+    ///
+    /// This is [`Decoder::skip()`], except that the bytes of the item are
+    /// kept, exactly as they were read, so that the item can be written
+    /// out again unchanged, or decoded later. A header which was peeked at
+    /// is kept as it was encoded; one which was pushed back with
+    /// [`Decoder::push()`] is kept in its shortest form. If there is an
+    /// error, the bytes read before it are still appended.
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Decoder, Header};
+    ///
+    /// // [1, 24(h'02')], 3, where the 1 is not in its shortest form
+    /// let bytes = [0x82, 0x18, 0x01, 0xd8, 0x18, 0x41, 0x02, 0x03];
+    /// let mut decoder = Decoder::from(&bytes[..]);
+    ///
+    /// let mut item = Vec::new();
+    /// decoder.capture(&mut item).unwrap();
+    /// assert_eq!(item, bytes[..7]);
+    /// assert_eq!(decoder.pull().unwrap(), Header::Positive(3));
+    /// ```
+    pub fn capture(&mut self, buffer: &mut alloc::vec::Vec<u8>) -> Result<(), Error<R::Error>> {
+        self.record = Some(core::mem::take(buffer));
+        let result = self.skip();
+        *buffer = self.record.take().unwrap_or_default();
+        result
+    }
+}
+
 impl<R: Read> Read for Decoder<R> {
     type Error = R::Error;
 
@@ -55,15 +352,74 @@ impl<R: Read> Read for Decoder<R> {
         assert!(self.buffer.is_none());
         self.reader.read_exact(data)?;
         self.offset += data.len();
+
+        #[cfg(feature = "alloc")]
+        if let Some(record) = self.record.as_mut() {
+            record.extend_from_slice(data);
+        }
+
         Ok(())
     }
 }
 
 impl<R: Read> Decoder<R> {
+    /// Creates a decoder which pulls at most `max_items` headers
+    ///
+    /// Every header counts, including breaks and the chunks of strings, so
+    /// a loop of pulls over hostile input ends even if the input never
+    /// does, such as an endless indefinite-length array. Once the budget is
+    /// spent, pulling fails with [`Error::LimitExceeded`] for
+    /// [`LimitKind::Items`] without reading anything. A header pushed back with [`Decoder::push()`] is
+    /// refunded, and peeking is free.
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Decoder, Error, Header};
+    ///
+    /// // [_ 0, 0, 0, ...
+    /// let bytes = [0x9f, 0x00, 0x00, 0x00];
+    /// let mut decoder = Decoder::with_item_budget(&bytes[..], 3);
+    ///
+    /// assert_eq!(decoder.pull().unwrap(), Header::Array(None));
+    /// assert_eq!(decoder.pull().unwrap(), Header::Positive(0));
+    /// assert_eq!(decoder.pull().unwrap(), Header::Positive(0));
+    /// assert!(matches!(
+    ///     decoder.pull(),
+    ///     Err(Error::LimitExceeded { limit: 3, offset: 3, .. })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn with_item_budget(reader: R, max_items: usize) -> Self {
+        let mut decoder = Self::from(reader);
+        decoder.set_item_budget(max_items);
+        decoder
+    }
+
+    /// Sets how many more headers the decoder may pull
+    ///
+    /// This replaces any budget left. See [`Decoder::with_item_budget()`].
+    #[inline]
+    pub fn set_item_budget(&mut self, max_items: usize) {
+        self.budget = Some(max_items);
+        self.max_items = max_items;
+    }
+
+    /// Gets how many more headers the decoder may pull, if it has a budget
+    #[inline]
+    pub fn item_budget(&self) -> Option<usize> {
+        self.budget
+    }
+
     #[inline]
     fn pull_title(&mut self) -> Result<Title, Error<R::Error>> {
         if let Some(title) = self.buffer.take() {
             self.offset += title.1.as_ref().len() + 1;
+
+            #[cfg(feature = "alloc")]
+            if let Some(record) = self.record.as_mut() {
+                record.push(title.prefix());
+                record.extend_from_slice(title.1.as_ref());
+            }
+
             return Ok(title);
         }
 
@@ -103,15 +459,59 @@ impl<R: Read> Decoder<R> {
         self.offset -= item.1.as_ref().len() + 1;
     }
 
-    /// Pulls the next header from the input
+    /// Pulls the next header, without counting it in the nesting or budget
+    ///
+    /// This is for callers which track the nesting themselves, so that
+    /// nothing is allocated.
     #[inline]
-    pub fn pull(&mut self) -> Result<Header, Error<R::Error>> {
+    pub(crate) fn pull_untracked(&mut self) -> Result<Header, Error<R::Error>> {
         let offset = self.offset;
         self.pull_title()?
             .try_into()
             .map_err(|_| Error::Syntax(offset))
     }
 
+    /// Pulls the next header from the input
+    #[inline]
+    pub fn pull(&mut self) -> Result<Header, Error<R::Error>> {
+        let offset = self.offset;
+
+        if self.budget == Some(0) {
+            return Err(Error::limit(LimitKind::Items, self.max_items, offset));
+        }
+
+        let header = self.pull_untracked()?;
+
+        if let Some(budget) = self.budget.as_mut() {
+            *budget -= 1;
+        }
+
+        #[cfg(feature = "alloc")]
+        if let Some(nesting) = self.nesting.as_mut() {
+            nesting.pull(header, offset)?;
+        }
+
+        Ok(header)
+    }
+
+    /// Pulls the next header, which must be in its preferred encoding
+    ///
+    /// This is like [`Decoder::pull()`], but it is a syntax error if the
+    /// header is longer than [`Header::length()`]: an integer, length, tag
+    /// or simple value with more bytes than needed, or a float which could
+    /// be shorter without losing precision. It is used to check the input
+    /// for deterministic encoding. Indefinite lengths are still accepted.
+    #[inline]
+    pub fn pull_canonical(&mut self) -> Result<Header, Error<R::Error>> {
+        let offset = self.offset;
+        let header = self.pull()?;
+
+        match self.offset - offset == header.length() {
+            true => Ok(header),
+            false => Err(Error::Syntax(offset)),
+        }
+    }
+
     /// Push a single header into the input buffer
     ///
     /// # Panics
@@ -121,7 +521,35 @@ impl<R: Read> Decoder<R> {
     /// pulling a header to ensure there is nothing in the input buffer.
     #[inline]
     pub fn push(&mut self, item: Header) {
-        self.push_title(Title::from(item))
+        self.push_title(Title::from(item));
+
+        if let Some(budget) = self.budget.as_mut() {
+            *budget = budget.saturating_add(1);
+        }
+
+        #[cfg(feature = "alloc")]
+        if let Some(nesting) = self.nesting.as_mut() {
+            nesting.push();
+        }
+    }
+
+    /// Returns the next header without consuming it
+    ///
+    /// The header is kept in the input buffer, so the next pull returns it
+    /// again, and peeking more than once returns the same header. The
+    /// offset is left before the header, just as after [`Decoder::push()`].
+    /// Only the header is read, never the body of a string. If the header
+    /// is invalid, the syntax error is returned by each peek or pull.
+    ///
+    /// Unlike pulling and then pushing a header, this keeps the header
+    /// exactly as it was encoded, so the offset stays accurate for headers
+    /// which are not in their shortest form.
+    #[inline]
+    pub fn peek(&mut self) -> Result<Header, Error<R::Error>> {
+        let offset = self.offset;
+        let title = self.pull_title()?;
+        self.push_title(title);
+        title.try_into().map_err(|_| Error::Syntax(offset))
     }
 
     /// Gets the current byte offset into the stream
@@ -129,11 +557,163 @@ impl<R: Read> Decoder<R> {
     /// The offset starts at zero when the decoder is created. Therefore, if
     /// bytes were already read from the reader before the decoder was created,
     /// you must account for this.
+    ///
+    /// The offset counts every byte read from the reader: headers, and the
+    /// bodies of strings read through the decoder or its segments. A header
+    /// pushed back with [`Decoder::push()`] is not counted until it is pulled
+    /// again. After an error, the offset still counts every byte read before
+    /// the failing read, while the failing read itself is not counted. So,
+    /// after a syntax error in a header, the offset is just past that header.
     #[inline]
-    pub fn offset(&mut self) -> usize {
+    pub fn offset(&self) -> usize {
         self.offset
     }
 
+    /// Skips one complete data item
+    ///
+    /// This is [`Decoder::skip_with_max_depth()`] with a limit of 256
+    /// nested containers, which is the same as the limit of the serde
+    /// deserializer in `ciborium`.
+    #[inline]
+    pub fn skip(&mut self) -> Result<(), Error<R::Error>> {
+        self.skip_with_max_depth(256)
+    }
+
+    /// Skips one complete data item, with at most `max_depth` nested containers
+    ///
+    /// The whole item is consumed without interpreting it: containers with
+    /// all of their items, tags with the item they wrap and strings with
+    /// their bodies, including all chunks of indefinite-length strings. The
+    /// next pull returns the header which follows the item. Text is not
+    /// checked to be valid UTF-8. The bodies of strings are read and
+    /// discarded in small pieces, so nothing is allocated.
+    ///
+    /// A break where an item is expected fails with [`Error::UnexpectedBreak`],
+    /// and an item which is not a chunk within an indefinite-length string
+    /// fails with [`Error::MissingBreak`]. Containers nested more than
+    /// `max_depth` deep fail with
+    /// [`Error::LimitExceeded`] for [`LimitKind::Depth`].
+    pub fn skip_with_max_depth(&mut self, max_depth: usize) -> Result<(), Error<R::Error>> {
+        let offset = self.offset;
+        let header = self.pull_item()?;
+        self.skip_body(header, offset, Depth::new(max_depth))
+    }
+
+    /// Pulls the header of an item, which must not be a break
+    #[inline]
+    fn pull_item(&mut self) -> Result<Header, Error<R::Error>> {
+        let offset = self.offset;
+        match self.pull()? {
+            Header::Break => Err(Error::UnexpectedBreak { offset }),
+            header => Ok(header),
+        }
+    }
+
+    /// Skips the rest of the item whose header was just pulled at `start`
+    fn skip_body(
+        &mut self,
+        mut header: Header,
+        mut start: usize,
+        depth: Depth,
+    ) -> Result<(), Error<R::Error>> {
+        // Tags can be chained, but each one wraps exactly one item.
+        while let Header::Tag(..) = header {
+            start = self.offset;
+            header = self.pull_item()?;
+        }
+
+        let offset = self.offset;
+        let len = match header {
+            Header::Bytes(len) | Header::Text(len) => return self.skip_string(header, len),
+            Header::Array(len) => len,
+            Header::Map(Some(len)) => Some(len.checked_mul(2).ok_or(Error::Syntax(offset))?),
+            Header::Map(None) => return self.skip_map(start, depth),
+            _ => return Ok(()),
+        };
+
+        let depth = depth.open(start)?;
+
+        match len {
+            Some(len) => {
+                for _ in 0..len {
+                    self.skip_item(depth)?;
+                }
+            }
+
+            None => loop {
+                let offset = self.offset;
+                match self.pull()? {
+                    Header::Break => break,
+                    header => self.skip_body(header, offset, depth)?,
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Skips the entries of an indefinite-length map, opened at `start`
+    ///
+    /// A break may only come where a key is expected: a break after a key,
+    /// with no value, is an unexpected break.
+    fn skip_map(&mut self, start: usize, depth: Depth) -> Result<(), Error<R::Error>> {
+        let depth = depth.open(start)?;
+
+        loop {
+            let offset = self.offset;
+            match self.pull()? {
+                Header::Break => return Ok(()),
+                header => self.skip_body(header, offset, depth)?,
+            }
+
+            self.skip_item(depth)?;
+        }
+    }
+
+    /// Skips one complete item, which must not be a break
+    #[inline]
+    fn skip_item(&mut self, depth: Depth) -> Result<(), Error<R::Error>> {
+        let offset = self.offset;
+        let header = self.pull_item()?;
+        self.skip_body(header, offset, depth)
+    }
+
+    /// Skips the body of a string, made of chunks if its length is `None`
+    fn skip_string(&mut self, header: Header, len: Option<usize>) -> Result<(), Error<R::Error>> {
+        if let Some(len) = len {
+            return self.discard(len);
+        }
+
+        // The header of an indefinite-length string is a single byte.
+        let start = self.offset - 1;
+
+        loop {
+            match (header, self.pull()?) {
+                (_, Header::Break) => return Ok(()),
+                (Header::Bytes(..), Header::Bytes(Some(len))) => self.discard(len)?,
+                (Header::Text(..), Header::Text(Some(len))) => self.discard(len)?,
+                _ => {
+                    return Err(Error::MissingBreak {
+                        container_offset: start,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Reads and discards `len` bytes
+    fn discard(&mut self, mut len: usize) -> Result<(), Error<R::Error>> {
+        let mut buffer = [0u8; 64];
+
+        while len > 0 {
+            let n = len.min(buffer.len());
+            self.read_exact(&mut buffer[..n])?;
+            len -= n;
+        }
+
+        Ok(())
+    }
+
     /// Process an incoming bytes item
     ///
     /// In CBOR, bytes can be segmented. The logic for this can be a bit tricky,
@@ -146,7 +726,7 @@ impl<R: Read> Decoder<R> {
     /// at the cost of memory usage. You should consider this trade off when
     /// deciding the size of your buffer.
     #[inline]
-    pub fn bytes(&mut self, len: Option<usize>) -> Segments<R, crate::seg::Bytes> {
+    pub fn bytes(&mut self, len: Option<usize>) -> Segments<'_, R, crate::seg::Bytes> {
         self.push(Header::Bytes(len));
         Segments::new(self, |header| match header {
             Header::Bytes(len) => Ok(len),
@@ -166,7 +746,7 @@ impl<R: Read> Decoder<R> {
     /// at the cost of memory usage. You should consider this trade off when
     /// deciding the size of your buffer.
     #[inline]
-    pub fn text(&mut self, len: Option<usize>) -> Segments<R, crate::seg::Text> {
+    pub fn text(&mut self, len: Option<usize>) -> Segments<'_, R, crate::seg::Text> {
         self.push(Header::Text(len));
         Segments::new(self, |header| match header {
             Header::Text(len) => Ok(len),
@@ -174,3 +754,62 @@ impl<R: Read> Decoder<R> {
         })
     }
 }
+
+impl<R: BufRead> Decoder<R> {
+    /// Returns the next `len` bytes, if the reader has all of them buffered
+    ///
+    /// This allows the bytes of a string to be used in place, without
+    /// copying them. Nothing is consumed; call [`Decoder::consume()`]
+    /// afterwards. `None` is returned if fewer than `len` bytes are
+    /// buffered, in which case the bytes should be read as usual.
+    ///
+    /// For input which is not contiguous, like [`ciborium_io::Slices`], the
+    /// buffer is the rest of the current segment. So, a string is borrowed
+    /// only if it is entirely in one segment, and copied otherwise:
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Decoder, Header};
+    /// use ciborium_io::{Read, Slices};
+    ///
+    /// // ["ab", "cd"], split within the second string
+    /// let input = [0x82, 0x62, 0x61, 0x62, 0x62, 0x63, 0x64];
+    /// let mut decoder = Decoder::from(Slices::new([&input[..6], &input[6..]]));
+    /// let mut buffer = [0u8; 2];
+    ///
+    /// assert_eq!(decoder.pull().unwrap(), Header::Array(Some(2)));
+    /// assert_eq!(decoder.pull().unwrap(), Header::Text(Some(2)));
+    /// assert_eq!(decoder.contiguous(2).unwrap(), Some(&b"ab"[..]));
+    /// decoder.consume(2);
+    ///
+    /// assert_eq!(decoder.pull().unwrap(), Header::Text(Some(2)));
+    /// assert_eq!(decoder.contiguous(2).unwrap(), None);
+    /// decoder.read_exact(&mut buffer[..]).unwrap();
+    /// assert_eq!(&buffer, b"cd");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This function panics if there is a header in the input buffer.
+    #[inline]
+    pub fn contiguous(&mut self, len: usize) -> Result<Option<&[u8]>, Error<R::Error>> {
+        assert!(self.buffer.is_none());
+        Ok(self.reader.fill_buf()?.get(..len))
+    }
+
+    /// Returns the bytes which the reader has buffered
+    ///
+    /// This is empty only at the end of the input.
+    #[inline]
+    pub(crate) fn fill_buf(&mut self) -> Result<&[u8], Error<R::Error>> {
+        assert!(self.buffer.is_none());
+        Ok(self.reader.fill_buf()?)
+    }
+
+    /// Consumes `len` bytes returned by [`Decoder::contiguous()`]
+    #[inline]
+    pub fn consume(&mut self, len: usize) {
+        assert!(self.buffer.is_none());
+        self.reader.consume(len);
+        self.offset += len;
+    }
+}
@@ -1,6 +1,47 @@
 use super::*;
 
-use ciborium_io::Write;
+use ciborium_io::{Seek, Write};
+
+/// An error that occurred while pushing a header
+#[derive(Debug)]
+pub enum PushError<T> {
+    /// An error occurred while writing bytes
+    ///
+    /// Contains the underlying error returned while writing.
+    Io(T),
+
+    /// The header is a reserved simple value
+    ///
+    /// Simple values 24 to 31 have no valid encoding: the one byte form
+    /// uses those minor values for other purposes, and the two byte form
+    /// requires values of at least 32.
+    ReservedSimple(u8),
+}
+
+impl<T> From<T> for PushError<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl<T: core::fmt::Display> core::fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::ReservedSimple(x) => write!(f, "simple value {} is reserved", x),
+        }
+    }
+}
+
+impl<T: core::error::Error + 'static> core::error::Error for PushError<T> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::ReservedSimple(..) => None,
+        }
+    }
+}
 
 /// An encoder for serializing CBOR items
 ///
@@ -29,32 +70,71 @@ impl<W: Write> Write for Encoder<W> {
 
 impl<W: Write> Encoder<W> {
     /// Push a `Header` to the wire
+    ///
+    /// The header is written in its preferred encoding: integers, lengths,
+    /// tags and simple values use the fewest bytes possible, and floats use
+    /// the shortest width which keeps all bits of the value, including the
+    /// payload of a NaN. See [`Header::length()`]. Simple values 0 to 23
+    /// take one byte and simple values 32 to 255 take two.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is a reserved simple value (24 to 31). Use
+    /// [`Encoder::try_push()`] to get an error instead.
     #[inline]
     pub fn push(&mut self, header: Header) -> Result<(), W::Error> {
+        match self.try_push(header) {
+            Ok(()) => Ok(()),
+            Err(PushError::Io(e)) => Err(e),
+            Err(PushError::ReservedSimple(x)) => {
+                panic!("simple value {} is reserved and cannot be encoded", x)
+            }
+        }
+    }
+
+    /// Push a `Header` to the wire, rejecting reserved simple values
+    ///
+    /// This is like [`Encoder::push()`], but a simple value from 24 to 31
+    /// is a [`PushError::ReservedSimple`] error, and nothing is written.
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Encoder, Header, PushError};
+    ///
+    /// let mut buffer = [0u8; 2];
+    /// let mut encoder = Encoder::from(&mut buffer[..]);
+    ///
+    /// assert!(matches!(
+    ///     encoder.try_push(Header::Simple(24)),
+    ///     Err(PushError::ReservedSimple(24))
+    /// ));
+    ///
+    /// encoder.try_push(Header::Simple(42)).unwrap();
+    /// assert_eq!(buffer, [0xf8, 42]);
+    /// ```
+    #[inline]
+    pub fn try_push(&mut self, header: Header) -> Result<(), PushError<W::Error>> {
+        if let Header::Simple(x @ 24..=31) = header {
+            return Err(PushError::ReservedSimple(x));
+        }
+
         let title = Title::from(header);
 
-        let major = match title.0 {
-            Major::Positive => 0,
-            Major::Negative => 1,
-            Major::Bytes => 2,
-            Major::Text => 3,
-            Major::Array => 4,
-            Major::Map => 5,
-            Major::Tag => 6,
-            Major::Other => 7,
-        };
-
-        let minor = match title.1 {
-            Minor::This(x) => x,
-            Minor::Next1(..) => 24,
-            Minor::Next2(..) => 25,
-            Minor::Next4(..) => 26,
-            Minor::Next8(..) => 27,
-            Minor::More => 31,
-        };
-
-        self.0.write_all(&[major << 5 | minor])?;
-        self.0.write_all(title.1.as_ref())
+        self.0.write_all(&[title.prefix()])?;
+        Ok(self.0.write_all(title.1.as_ref())?)
+    }
+
+    /// Push a float in its preferred encoding, with a canonical NaN
+    ///
+    /// Like [`Encoder::push()`], the float is written as a half, single or
+    /// double float, whichever is the shortest without losing precision.
+    /// In addition, every NaN is written as the quiet NaN `0xf97e00`, as
+    /// deterministic encoding requires, so its sign and payload are lost.
+    #[inline]
+    pub fn push_float_preferred(&mut self, value: f64) -> Result<(), W::Error> {
+        match value.is_nan() {
+            true => self.push(Header::Float(f64::NAN)),
+            false => self.push(Header::Float(value)),
+        }
     }
 
     /// Serialize a byte slice as CBOR
@@ -68,7 +148,7 @@ impl<W: Write> Encoder<W> {
         value: &[u8],
         segment: impl Into<Option<usize>>,
     ) -> Result<(), W::Error> {
-        let max = segment.into().unwrap_or_else(|| value.len());
+        let max = segment.into().unwrap_or(value.len());
         let max = core::cmp::max(max, 1);
 
         if max >= value.len() {
@@ -97,7 +177,7 @@ impl<W: Write> Encoder<W> {
     /// should not be relied upon.
     #[inline]
     pub fn text(&mut self, value: &str, segment: impl Into<Option<usize>>) -> Result<(), W::Error> {
-        let max = segment.into().unwrap_or_else(|| value.len());
+        let max = segment.into().unwrap_or(value.len());
         let max = core::cmp::max(max, 4);
 
         if max >= value.len() {
@@ -124,4 +204,292 @@ impl<W: Write> Encoder<W> {
 
         Ok(())
     }
+
+    /// Starts a bytes item of indefinite length, written in chunks
+    ///
+    /// The data written to the returned writer is collected in the buffer,
+    /// and each time the buffer is full, it is written as one chunk. So,
+    /// every chunk but the last is as long as the buffer. Call
+    /// [`BytesWriter::end()`] to write the last chunk and the break; if the
+    /// writer is dropped instead, this happens on drop and errors are lost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is empty.
+    ///
+    /// ```rust
+    /// use ciborium_ll::Encoder;
+    /// use ciborium_io::Write as _;
+    ///
+    /// let mut output = [0u8; 9];
+    /// let mut encoder = Encoder::from(&mut output[..]);
+    ///
+    /// let mut buffer = [0u8; 2];
+    /// let mut writer = encoder.bytes_writer(&mut buffer).unwrap();
+    /// writer.write_all(&[1, 2, 3]).unwrap();
+    /// writer.end().unwrap();
+    ///
+    /// assert_eq!(output, [0x5f, 0x42, 1, 2, 0x41, 3, 0xff, 0, 0]);
+    /// ```
+    pub fn bytes_writer<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+    ) -> Result<BytesWriter<'a, W>, W::Error> {
+        assert!(!buffer.is_empty(), "the buffer must not be empty");
+        Ok(BytesWriter(Chunks::new(self, buffer, Header::Bytes)?))
+    }
+
+    /// Starts a text item of indefinite length, written in chunks
+    ///
+    /// This is like [`Encoder::bytes_writer()`], for text written with
+    /// [`TextWriter::write_str()`]. A chunk is never split within a
+    /// character, so it may be up to 3 bytes shorter than the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is shorter than 4 bytes, the longest UTF-8
+    /// character.
+    pub fn text_writer<'a>(
+        &'a mut self,
+        buffer: &'a mut [u8],
+    ) -> Result<TextWriter<'a, W>, W::Error> {
+        assert!(buffer.len() >= 4, "the buffer must be at least 4 bytes");
+        Ok(TextWriter(Chunks::new(self, buffer, Header::Text)?))
+    }
+}
+
+/// The position of a container header whose length is not known yet
+///
+/// See [`Encoder::array_placeholder()`]. It can only be finalized once,
+/// so it is neither `Copy` nor `Clone`.
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "the length must be filled in with `Encoder::finalize_placeholder()`"]
+pub struct Placeholder {
+    position: u64,
+    prefix: u8,
+}
+
+impl Placeholder {
+    /// The position of the header in the output
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl<W: Seek> Encoder<W> {
+    /// Writes the header of an array whose length is filled in later
+    ///
+    /// When the number of items is not known in advance, this avoids both
+    /// an indefinite-length array and collecting the items first. The
+    /// header is always written in its longest form, with 8 bytes for the
+    /// length, so that filling in the length never moves the items after
+    /// it. Write the items, then call [`Encoder::finalize_placeholder()`].
+    ///
+    /// As the header is not in its shortest form, the output is not in
+    /// deterministic encoding. The writer can be any `std::io::Seek`
+    /// writer, such as a `Cursor<Vec<u8>>` or a `File`.
+    #[inline]
+    pub fn array_placeholder(&mut self) -> Result<Placeholder, W::Error> {
+        self.placeholder(0x9b)
+    }
+
+    /// Writes the header of a map whose length is filled in later
+    ///
+    /// This is like [`Encoder::array_placeholder()`], but the length is
+    /// the number of key-value pairs.
+    #[inline]
+    pub fn map_placeholder(&mut self) -> Result<Placeholder, W::Error> {
+        self.placeholder(0xbb)
+    }
+
+    fn placeholder(&mut self, prefix: u8) -> Result<Placeholder, W::Error> {
+        let position = self.0.position()?;
+        self.0.write_all(&[prefix])?;
+        self.0.write_all(&[0; 8])?;
+        Ok(Placeholder { position, prefix })
+    }
+
+    /// Fills in the length of a header written by a placeholder method
+    ///
+    /// The writer goes back to the header, overwrites it with the length
+    /// and returns to where it was, so more items can follow.
+    pub fn finalize_placeholder(
+        &mut self,
+        placeholder: Placeholder,
+        len: usize,
+    ) -> Result<(), W::Error> {
+        let end = self.0.position()?;
+        self.0.seek_to(placeholder.position)?;
+        self.0.write_all(&[placeholder.prefix])?;
+        self.0.write_all(&(len as u64).to_be_bytes())?;
+        self.0.seek_to(end)
+    }
+}
+
+/// The chunks of a string of indefinite length
+struct Chunks<'a, W: Write> {
+    encoder: &'a mut Encoder<W>,
+    buffer: &'a mut [u8],
+    len: usize,
+    header: fn(Option<usize>) -> Header,
+    ended: bool,
+}
+
+impl<'a, W: Write> Chunks<'a, W> {
+    fn new(
+        encoder: &'a mut Encoder<W>,
+        buffer: &'a mut [u8],
+        header: fn(Option<usize>) -> Header,
+    ) -> Result<Self, W::Error> {
+        encoder.push(header(None))?;
+
+        Ok(Self {
+            encoder,
+            buffer,
+            len: 0,
+            header,
+            ended: false,
+        })
+    }
+
+    /// Writes one chunk straight from the data
+    fn chunk(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        self.encoder.push((self.header)(Some(data.len())))?;
+        self.encoder.write_all(data)
+    }
+
+    /// Appends data which fits in the buffer, writing the buffer once full
+    fn append(&mut self, data: &[u8]) -> Result<(), W::Error> {
+        self.buffer[self.len..][..data.len()].copy_from_slice(data);
+        self.len += data.len();
+
+        match self.len == self.buffer.len() {
+            true => self.emit(),
+            false => Ok(()),
+        }
+    }
+
+    /// Writes the buffered data, if any, as a chunk
+    fn emit(&mut self) -> Result<(), W::Error> {
+        if self.len > 0 {
+            self.encoder.push((self.header)(Some(self.len)))?;
+            self.encoder.write_all(&self.buffer[..self.len])?;
+            self.len = 0;
+        }
+
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), W::Error> {
+        self.ended = true;
+        self.emit()?;
+        self.encoder.push(Header::Break)
+    }
+
+    fn flush(&mut self) -> Result<(), W::Error> {
+        self.emit()?;
+        self.encoder.flush()
+    }
+}
+
+impl<W: Write> Drop for Chunks<'_, W> {
+    fn drop(&mut self) {
+        if !self.ended {
+            let _ = self.end();
+        }
+    }
+}
+
+/// A writer for the chunks of a bytes item
+///
+/// See [`Encoder::bytes_writer()`].
+pub struct BytesWriter<'a, W: Write>(Chunks<'a, W>);
+
+impl<W: Write> BytesWriter<'_, W> {
+    /// Writes the last chunk and the break which ends the item
+    #[inline]
+    pub fn end(mut self) -> Result<(), W::Error> {
+        self.0.end()
+    }
+}
+
+impl<W: Write> Write for BytesWriter<'_, W> {
+    type Error = W::Error;
+
+    fn write_all(&mut self, mut data: &[u8]) -> Result<(), Self::Error> {
+        while !data.is_empty() {
+            let space = self.0.buffer.len() - self.0.len;
+
+            // Data which fills whole chunks is not copied.
+            let n = match self.0.len {
+                0 if data.len() >= space => {
+                    self.0.chunk(&data[..space])?;
+                    space
+                }
+
+                _ => {
+                    let n = data.len().min(space);
+                    self.0.append(&data[..n])?;
+                    n
+                }
+            };
+
+            data = &data[n..];
+        }
+
+        Ok(())
+    }
+
+    /// Writes the buffered data as a chunk, and flushes the writer
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+/// A writer for the chunks of a text item
+///
+/// See [`Encoder::text_writer()`].
+pub struct TextWriter<'a, W: Write>(Chunks<'a, W>);
+
+impl<W: Write> TextWriter<'_, W> {
+    /// Writes text, which may end up split between chunks
+    pub fn write_str(&mut self, mut text: &str) -> Result<(), W::Error> {
+        while !text.is_empty() {
+            let space = self.0.buffer.len() - self.0.len;
+
+            // Split at the last character which still fits.
+            let mut n = space.min(text.len());
+            while !text.is_char_boundary(n) {
+                n -= 1;
+            }
+
+            match self.0.len {
+                0 if n < text.len() => self.0.chunk(&text.as_bytes()[..n])?,
+                _ if n < text.len() => {
+                    self.0.append(&text.as_bytes()[..n])?;
+                    self.0.emit()?;
+                }
+
+                _ => self.0.append(text.as_bytes())?,
+            }
+
+            text = &text[n..];
+        }
+
+        Ok(())
+    }
+
+    /// Writes the buffered text as a chunk, and flushes the writer
+    #[inline]
+    pub fn flush(&mut self) -> Result<(), W::Error> {
+        self.0.flush()
+    }
+
+    /// Writes the last chunk and the break which ends the item
+    #[inline]
+    pub fn end(mut self) -> Result<(), W::Error> {
+        self.0.end()
+    }
 }
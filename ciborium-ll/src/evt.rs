@@ -0,0 +1,413 @@
+use super::*;
+
+use crate::seg::Parser;
+
+use alloc::vec::Vec;
+use ciborium_io::Read;
+
+/// An event in a stream of CBOR items
+///
+/// See [`Events`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event<'a> {
+    /// A positive integer
+    Positive(u64),
+
+    /// A negative integer, with all bits inverted (see [`Header::Negative`])
+    Negative(u64),
+
+    /// A floating point value
+    Float(f64),
+
+    /// A simple value, including booleans, null and undefined
+    Simple(u8),
+
+    /// A tag, which applies to the item whose events follow
+    Tag(u64),
+
+    /// The start of a bytes item, with its length if it is definite
+    StartBytes(Option<usize>),
+
+    /// A part of the bytes item which was started last
+    Bytes(&'a [u8]),
+
+    /// The end of a bytes item
+    EndBytes,
+
+    /// The start of a text item, with its length if it is definite
+    StartText(Option<usize>),
+
+    /// A part of the text item which was started last
+    ///
+    /// The part never ends within a character.
+    Text(&'a str),
+
+    /// The end of a text item
+    EndText,
+
+    /// The start of an array, with its length if it is definite
+    StartArray(Option<usize>),
+
+    /// The end of an array
+    EndArray,
+
+    /// The start of a map, with its length in pairs if it is definite
+    StartMap(Option<usize>),
+
+    /// The events of a map key follow
+    Key,
+
+    /// The events of a map value follow
+    Value,
+
+    /// The end of a map
+    EndMap,
+}
+
+/// An open array or map
+struct Frame {
+    map: bool,
+    left: Option<usize>,
+    index: usize,
+    offset: usize,
+}
+
+impl Frame {
+    /// The error for a break which ends a definite-length container early
+    fn underflow<T>(&self, left: usize) -> Error<T> {
+        let (declared, actual) = (self.index + left, self.index);
+
+        match self.map {
+            false => Error::ContainerUnderflow {
+                offset: self.offset,
+                declared,
+                actual,
+            },
+
+            true => Error::ContainerUnderflow {
+                offset: self.offset,
+                declared: declared / 2,
+                actual: actual / 2,
+            },
+        }
+    }
+}
+
+/// The string which is being read
+struct Str {
+    text: bool,
+    indefinite: bool,
+    unread: usize,
+    offset: usize,
+    start: usize,
+    chunks: usize,
+    parser: crate::seg::Text,
+}
+
+impl Str {
+    /// The error for text which is not valid UTF-8 in the current chunk
+    fn invalid<T>(&self) -> Error<T> {
+        Error::InvalidUtf8 {
+            chunk: self.chunks - 1,
+            offset: self.offset,
+        }
+    }
+}
+
+/// A pull parser which produces the events of one data item
+///
+/// This sits between [`Decoder::pull()`] and a full deserializer. The
+/// events follow the structure of the item: every container has a start
+/// and an end event, even when its length is definite, and every key and
+/// value in a map is preceded by a [`Event::Key`] or [`Event::Value`]
+/// event. Strings are produced in parts which fit in the buffer, and the
+/// chunks of indefinite-length strings are joined.
+///
+/// The input must be well-formed: a break which does not end an
+/// indefinite-length container or string, a map which ends after a key or
+/// a tag without an item fails with [`Error::UnexpectedBreak`], a
+/// definite-length container which the break of an indefinite-length one
+/// around it cuts short fails with [`Error::ContainerUnderflow`], an item
+/// in place of a chunk, like a string of the other type, fails with
+/// [`Error::MissingBreak`], and text which is not valid UTF-8, chunk by
+/// chunk, fails with [`Error::InvalidUtf8`]. Items nested more deeply
+/// than the limit of the decoder fail with [`Error::LimitExceeded`].
+///
+/// ```rust
+/// use ciborium_ll::{Decoder, Event, Events};
+///
+/// // {"a": [1]}
+/// let input = [0xa1, 0x61, 0x61, 0x81, 0x01];
+/// let mut buffer = [0u8; 16];
+/// let mut events = Events::new(Decoder::from(&input[..]), &mut buffer);
+///
+/// assert_eq!(events.pull().unwrap(), Some(Event::StartMap(Some(1))));
+/// assert_eq!(events.pull().unwrap(), Some(Event::Key));
+/// assert_eq!(events.pull().unwrap(), Some(Event::StartText(Some(1))));
+/// assert_eq!(events.pull().unwrap(), Some(Event::Text("a")));
+/// assert_eq!(events.pull().unwrap(), Some(Event::EndText));
+/// assert_eq!(events.pull().unwrap(), Some(Event::Value));
+/// assert_eq!(events.pull().unwrap(), Some(Event::StartArray(Some(1))));
+/// assert_eq!(events.pull().unwrap(), Some(Event::Positive(1)));
+/// assert_eq!(events.pull().unwrap(), Some(Event::EndArray));
+/// assert_eq!(events.pull().unwrap(), Some(Event::EndMap));
+/// assert_eq!(events.pull().unwrap(), None);
+/// ```
+pub struct Events<'a, R: Read> {
+    decoder: Decoder<R>,
+    buffer: &'a mut [u8],
+    frames: Vec<Frame>,
+    string: Option<Str>,
+    inside: bool,
+    done: bool,
+}
+
+impl<'a, R: Read> Events<'a, R> {
+    /// Creates the events of the next data item from the decoder
+    ///
+    /// The buffer holds the parts of strings, so it bounds their size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is shorter than 4 bytes, the longest UTF-8
+    /// character.
+    #[inline]
+    pub fn new(decoder: Decoder<R>, buffer: &'a mut [u8]) -> Self {
+        assert!(buffer.len() >= 4, "the buffer must be at least 4 bytes");
+
+        Self {
+            decoder,
+            buffer,
+            frames: Vec::new(),
+            string: None,
+            inside: false,
+            done: false,
+        }
+    }
+
+    /// Gets the current byte offset into the stream
+    ///
+    /// See [`Decoder::offset()`].
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.decoder.offset()
+    }
+
+    /// Returns the decoder, which is just after the last event
+    ///
+    /// Once all events have been pulled, the next data item can be read.
+    #[inline]
+    pub fn into_inner(self) -> Decoder<R> {
+        self.decoder
+    }
+
+    /// Counts an item which has just ended
+    fn complete(&mut self) {
+        self.inside = false;
+
+        match self.frames.last_mut() {
+            None => self.done = true,
+            Some(frame) => {
+                frame.index += 1;
+                if let Some(left) = frame.left.as_mut() {
+                    *left -= 1;
+                }
+            }
+        }
+    }
+
+    /// Gets the next event
+    ///
+    /// Returns `Ok(None)` once the data item has ended.
+    pub fn pull(&mut self) -> Result<Option<Event<'_>>, Error<R::Error>> {
+        if self.string.is_some() {
+            return self.part().map(Some);
+        }
+
+        if self.done {
+            return Ok(None);
+        }
+
+        if let Some(Frame { left: Some(0), .. }) = self.frames.last() {
+            return Ok(Some(self.end()));
+        }
+
+        let offset = self.decoder.offset();
+        let header = self.decoder.pull()?;
+
+        if header == Header::Break {
+            // Only an indefinite-length container can end here, and a map
+            // only after a whole pair. A break for an indefinite-length
+            // container further out ends a definite-length one too early.
+            let outer = self.frames.iter().any(|x| x.left.is_none());
+
+            return match self.frames.last() {
+                Some(Frame {
+                    map,
+                    left: None,
+                    index,
+                    ..
+                }) if !self.inside && (!map || index % 2 == 0) => Ok(Some(self.end())),
+
+                Some(
+                    frame @ Frame {
+                        left: Some(left), ..
+                    },
+                ) if !self.inside && outer => Err(frame.underflow(*left)),
+
+                _ => Err(Error::UnexpectedBreak { offset }),
+            };
+        }
+
+        // Announce the key or value first, and return to the item later.
+        if let Some(Frame {
+            map: true, index, ..
+        }) = self.frames.last()
+        {
+            if !self.inside {
+                let event = match index % 2 {
+                    0 => Event::Key,
+                    _ => Event::Value,
+                };
+
+                self.decoder.push(header);
+                self.inside = true;
+                return Ok(Some(event));
+            }
+        }
+
+        let event = match header {
+            Header::Positive(x) => Event::Positive(x),
+            Header::Negative(x) => Event::Negative(x),
+            Header::Float(x) => Event::Float(x),
+            Header::Simple(x) => Event::Simple(x),
+            Header::Break => unreachable!(),
+
+            Header::Tag(x) => {
+                self.inside = true;
+                return Ok(Some(Event::Tag(x)));
+            }
+
+            Header::Bytes(len) | Header::Text(len) => {
+                let text = matches!(header, Header::Text(..));
+
+                self.string = Some(Str {
+                    text,
+                    indefinite: len.is_none(),
+                    unread: len.unwrap_or(0),
+                    offset,
+                    start: offset,
+                    chunks: len.is_some() as usize,
+                    parser: Default::default(),
+                });
+
+                return Ok(Some(match text {
+                    true => Event::StartText(len),
+                    false => Event::StartBytes(len),
+                }));
+            }
+
+            Header::Array(len) => {
+                self.open(false, len, offset);
+                return Ok(Some(Event::StartArray(len)));
+            }
+
+            Header::Map(len) => {
+                let items = match len {
+                    Some(len) => Some(len.checked_mul(2).ok_or(Error::Syntax(offset))?),
+                    None => None,
+                };
+
+                self.open(true, items, offset);
+                return Ok(Some(Event::StartMap(len)));
+            }
+        };
+
+        self.complete();
+        Ok(Some(event))
+    }
+
+    fn open(&mut self, map: bool, left: Option<usize>, offset: usize) {
+        self.inside = false;
+        self.frames.push(Frame {
+            map,
+            left,
+            index: 0,
+            offset,
+        });
+    }
+
+    /// Ends the current container
+    fn end(&mut self) -> Event<'static> {
+        let map = self.frames.pop().map(|x| x.map);
+        self.complete();
+
+        match map {
+            Some(true) => Event::EndMap,
+            _ => Event::EndArray,
+        }
+    }
+
+    /// Gets the next part of the current string, or its end
+    fn part(&mut self) -> Result<Event<'_>, Error<R::Error>> {
+        loop {
+            let string = self.string.as_mut().unwrap();
+
+            if string.unread == 0 {
+                if string.indefinite {
+                    let offset = self.decoder.offset();
+                    match (string.text, self.decoder.pull()?) {
+                        (_, Header::Break) => (),
+
+                        (false, Header::Bytes(Some(len))) | (true, Header::Text(Some(len))) => {
+                            string.unread = len;
+                            string.offset = offset;
+                            string.chunks += 1;
+                            continue;
+                        }
+
+                        _ => {
+                            return Err(Error::MissingBreak {
+                                container_offset: string.start,
+                            })
+                        }
+                    }
+                }
+
+                let text = string.text;
+                self.string = None;
+                self.complete();
+
+                return Ok(match text {
+                    true => Event::EndText,
+                    false => Event::EndBytes,
+                });
+            }
+
+            let saved = string.parser.saved();
+            let size = self.buffer.len().min(saved + string.unread);
+            self.decoder.read_exact(&mut self.buffer[saved..size])?;
+            string.unread -= size - saved;
+
+            if !string.text {
+                return Ok(Event::Bytes(&self.buffer[..size]));
+            }
+
+            let len = match string.parser.parse(&mut self.buffer[..size]) {
+                Ok(text) => text.len(),
+                Err(..) => return Err(string.invalid()),
+            };
+
+            // A character must not span two chunks.
+            if string.unread == 0 && string.parser.saved() > 0 {
+                return Err(string.invalid());
+            }
+
+            // Wait for the rest of a character which was cut off.
+            if len > 0 {
+                let text = core::str::from_utf8(&self.buffer[..len]).unwrap();
+                return Ok(Event::Text(text));
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Conversions between half precision floats and `f64`
+//!
+//! CBOR encodes a float as a half, single or double float, and the
+//! preferred encoding is the shortest which keeps the exact value. These
+//! functions convert the bits of a half float, as found on the wire after
+//! the initial byte `0xf9`, exactly and without a half float type.
+//!
+//! ```rust
+//! use ciborium_ll::half;
+//!
+//! assert_eq!(half::to_f64(0x3e00), 1.5);
+//! assert_eq!(half::from_f64(1.5), Some(0x3e00));
+//! assert_eq!(half::from_f64(1.1), None);
+//! ```
+
+/// The bits of the payload of an `f64` which a half float cannot hold
+const LOST: u64 = (1 << 42) - 1;
+
+/// Converts the bits of a half float to an `f64`
+///
+/// Every half float is exactly representable as an `f64`, including the
+/// subnormals and the sign of zero. For a NaN, the sign is kept, and the
+/// 10 bits of the payload become the top bits of the payload of the `f64`,
+/// so a quiet NaN stays quiet and a signaling NaN stays signaling.
+#[inline]
+pub fn to_f64(bits: u16) -> f64 {
+    let sign = u64::from(bits >> 15) << 63;
+    let exp = u64::from(bits >> 10 & 0x1f);
+    let man = u64::from(bits & 0x3ff);
+
+    match exp {
+        // Zero and the subnormals, which are multiples of 2^-24
+        0 => {
+            let value = man as f64 * f64::from_bits(0x3e70_0000_0000_0000);
+            f64::from_bits(sign | value.to_bits())
+        }
+
+        // The infinities and NaNs
+        0x1f => f64::from_bits(sign | 0x7ff0_0000_0000_0000 | man << 42),
+
+        _ => f64::from_bits(sign | (exp + 1023 - 15) << 52 | man << 42),
+    }
+}
+
+/// Converts an `f64` to the bits of a half float, if it fits exactly
+///
+/// Returns `None` if the value would be rounded, overflow to infinity or
+/// underflow to zero. A NaN fits if its payload has no bits beyond the top
+/// 10, which become the payload of the half float. So, whenever a value
+/// fits, `to_f64()` restores its original bits.
+#[inline]
+pub fn from_f64(value: f64) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = (bits >> 48) as u16 & 0x8000;
+    let exp = (bits >> 52 & 0x7ff) as i32 - 1023;
+    let man = bits & ((1 << 52) - 1);
+
+    match exp {
+        // The infinities and NaNs
+        1024 if man & LOST == 0 => Some(sign | 0x7c00 | (man >> 42) as u16),
+
+        // Zero, but not the subnormals, which are much too small
+        -1023 if man == 0 => Some(sign),
+
+        // The normals
+        -14..=15 if man & LOST == 0 => Some(sign | ((exp + 15) as u16) << 10 | (man >> 42) as u16),
+
+        // The subnormals, including the implicit leading bit
+        -24..=-15 => {
+            let man = man | 1 << 52;
+            let shift = 52 - 24 - exp;
+
+            match man & ((1 << shift) - 1) {
+                0 => Some(sign | (man >> shift) as u16),
+                _ => None,
+            }
+        }
+
+        _ => None,
+    }
+}
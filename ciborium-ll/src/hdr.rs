@@ -2,8 +2,6 @@ use super::*;
 
 use core::convert::TryFrom;
 
-use half::f16;
-
 /// A semantic representation of a CBOR item header
 ///
 /// This structure represents the valid values of a CBOR item header and is
@@ -20,14 +18,19 @@ pub enum Header {
     /// A negative integer
     ///
     /// Note well that this value has all bits inverted from a normal signed
-    /// integer. For example, to convert the `u64` to a `i128` you would do
-    /// this: `neg as i128 ^ !0`.
+    /// integer: it holds `-1 - n` for the integer `n`. Use
+    /// [`Header::as_i128()`] and [`Header::from_i128()`] to convert it.
     Negative(u64),
 
     /// A floating point value
     Float(f64),
 
     /// A "simple" value
+    ///
+    /// Values 0 to 23 are encoded in one byte and values 32 to 255 in two
+    /// (see the `simple` module for the assigned ones). Values 24 to 31 are
+    /// reserved: a decoder never produces them, and an encoder refuses to
+    /// write them.
     Simple(u8),
 
     /// A tag
@@ -74,6 +77,139 @@ pub enum Header {
     Map(Option<usize>),
 }
 
+/// An integer which does not fit in a `Positive` or `Negative` header
+///
+/// A header holds integers from `-2^64` to `2^64 - 1`. Larger integers
+/// must be encoded as bignums (tags 2 and 3). Contains the integer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TooLarge(pub i128);
+
+impl core::fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "integer {} is too large for a header", self.0)
+    }
+}
+
+impl core::error::Error for TooLarge {}
+
+impl Header {
+    /// The integer of a `Positive` or `Negative` header
+    ///
+    /// Returns `None` for any other header.
+    ///
+    /// ```rust
+    /// use ciborium_ll::Header;
+    ///
+    /// assert_eq!(Header::Positive(7).as_i128(), Some(7));
+    /// assert_eq!(Header::Negative(0).as_i128(), Some(-1));
+    /// assert_eq!(Header::Negative(u64::MAX).as_i128(), Some(-1 << 64));
+    /// assert_eq!(Header::Tag(7).as_i128(), None);
+    /// ```
+    #[inline]
+    pub fn as_i128(&self) -> Option<i128> {
+        match *self {
+            Header::Positive(x) => Some(x.into()),
+            Header::Negative(x) => Some(i128::from(x) ^ !0),
+            _ => None,
+        }
+    }
+
+    /// The `Positive` or `Negative` header of an integer
+    ///
+    /// Fails if the integer needs a bignum instead.
+    ///
+    /// ```rust
+    /// use ciborium_ll::{Header, TooLarge};
+    ///
+    /// assert_eq!(Header::from_i128(7), Ok(Header::Positive(7)));
+    /// assert_eq!(Header::from_i128(-1), Ok(Header::Negative(0)));
+    /// assert_eq!(Header::from_i128(-1 << 64), Ok(Header::Negative(u64::MAX)));
+    /// assert_eq!(Header::from_i128(1 << 64), Err(TooLarge(1 << 64)));
+    /// ```
+    #[inline]
+    pub fn from_i128(value: i128) -> Result<Self, TooLarge> {
+        let header = match value.is_negative() {
+            false => u64::try_from(value).map(Header::Positive),
+            true => u64::try_from(value ^ !0).map(Header::Negative),
+        };
+
+        header.or(Err(TooLarge(value)))
+    }
+
+    /// The number of bytes of the header in its preferred encoding
+    ///
+    /// This is the shortest encoding of the header, which is what
+    /// [`Encoder::push()`] writes: 1, 2, 3, 5 or 9 bytes. For strings,
+    /// this does not include the body, and for arrays and maps, it does not
+    /// include the items. Floats are as short as they can be without
+    /// losing precision.
+    ///
+    /// ```rust
+    /// use ciborium_ll::Header;
+    ///
+    /// assert_eq!(Header::Positive(23).length(), 1);
+    /// assert_eq!(Header::Positive(24).length(), 2);
+    /// assert_eq!(Header::Bytes(Some(300)).length(), 3);
+    /// assert_eq!(Header::Float(1.5).length(), 3);
+    /// assert_eq!(Header::Float(1.1).length(), 9);
+    /// ```
+    #[inline]
+    pub fn length(&self) -> usize {
+        Title::from(*self).1.as_ref().len() + 1
+    }
+}
+
+/// Formats the header in a compact form of diagnostic notation
+///
+/// Integers are written as numbers, and floats with a suffix for the width
+/// of their preferred encoding. Strings and containers show their length,
+/// or `_` when it is indefinite; their contents are not part of the header.
+///
+/// ```rust
+/// use ciborium_ll::Header;
+///
+/// assert_eq!(Header::Negative(499).to_string(), "-500");
+/// assert_eq!(Header::Float(1.5).to_string(), "1.5_f16");
+/// assert_eq!(Header::Map(Some(3)).to_string(), "map(3)");
+/// assert_eq!(Header::Array(None).to_string(), "array(_)");
+/// assert_eq!(Header::Tag(37).to_string(), "tag(37)");
+/// ```
+impl core::fmt::Display for Header {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let len = |f: &mut core::fmt::Formatter<'_>, name, len: Option<usize>| match len {
+            Some(x) => write!(f, "{}({})", name, x),
+            None => write!(f, "{}(_)", name),
+        };
+
+        match *self {
+            Self::Positive(x) => write!(f, "{}", x),
+            Self::Negative(x) => write!(f, "{}", x as i128 ^ !0),
+            Self::Simple(x) => write!(f, "simple({})", x),
+            Self::Tag(x) => write!(f, "tag({})", x),
+            Self::Break => write!(f, "break"),
+            Self::Bytes(x) => len(f, "bytes", x),
+            Self::Text(x) => len(f, "text", x),
+            Self::Array(x) => len(f, "array", x),
+            Self::Map(x) => len(f, "map", x),
+
+            Self::Float(x) => {
+                match x {
+                    x if x.is_nan() => write!(f, "NaN")?,
+                    x if x.is_infinite() && x > 0.0 => write!(f, "Infinity")?,
+                    x if x.is_infinite() => write!(f, "-Infinity")?,
+                    x => write!(f, "{:?}", x)?,
+                }
+
+                match Title::from(*self).1 {
+                    Minor::Next2(..) => write!(f, "_f16"),
+                    Minor::Next4(..) => write!(f, "_f32"),
+                    _ => write!(f, "_f64"),
+                }
+            }
+        }
+    }
+}
+
 impl TryFrom<Title> for Header {
     type Error = InvalidError;
 
@@ -109,8 +245,11 @@ impl TryFrom<Title> for Header {
 
             Title(Major::Other, Minor::More) => Self::Break,
             Title(Major::Other, Minor::This(x)) => Self::Simple(x),
-            Title(Major::Other, Minor::Next1(x)) => Self::Simple(x[0]),
-            Title(Major::Other, Minor::Next2(x)) => Self::Float(f16::from_be_bytes(x).into()),
+            Title(Major::Other, Minor::Next1([x])) if x >= 32 => Self::Simple(x),
+            Title(Major::Other, Minor::Next1(..)) => return Err(InvalidError(())),
+            Title(Major::Other, Minor::Next2(x)) => {
+                Self::Float(half::to_f64(u16::from_be_bytes(x)))
+            }
             Title(Major::Other, Minor::Next4(x)) => Self::Float(f32::from_be_bytes(x).into()),
             Title(Major::Other, Minor::Next8(x)) => Self::Float(f64::from_be_bytes(x)),
         })
@@ -121,9 +260,9 @@ impl From<Header> for Title {
     fn from(header: Header) -> Self {
         let int = |i: u64| match i {
             x if x <= 23 => Minor::This(i as u8),
-            x if x <= core::u8::MAX as u64 => Minor::Next1([i as u8]),
-            x if x <= core::u16::MAX as u64 => Minor::Next2((i as u16).to_be_bytes()),
-            x if x <= core::u32::MAX as u64 => Minor::Next4((i as u32).to_be_bytes()),
+            x if x <= u8::MAX as u64 => Minor::Next1([i as u8]),
+            x if x <= u16::MAX as u64 => Minor::Next2((i as u16).to_be_bytes()),
+            x if x <= u32::MAX as u64 => Minor::Next4((i as u32).to_be_bytes()),
             x => Minor::Next8(x.to_be_bytes()),
         };
 
@@ -146,12 +285,11 @@ impl From<Header> for Title {
             },
 
             Header::Float(n64) => {
-                let n16 = f16::from_f64(n64);
                 let n32 = n64 as f32;
 
                 Title(
                     Major::Other,
-                    if f64::from(n16).to_bits() == n64.to_bits() {
+                    if let Some(n16) = half::from_f64(n64) {
                         Minor::Next2(n16.to_be_bytes())
                     } else if f64::from(n32).to_bits() == n64.to_bits() {
                         Minor::Next4(n32.to_be_bytes())
@@ -133,15 +133,31 @@ extern crate alloc;
 
 mod dec;
 mod enc;
+#[cfg(feature = "alloc")]
+mod evt;
+pub mod half;
 mod hdr;
+#[cfg(feature = "alloc")]
+mod nest;
 mod seg;
+mod val;
+#[cfg(feature = "alloc")]
+mod vis;
 
 pub use dec::*;
 pub use enc::*;
+#[cfg(feature = "alloc")]
+pub use evt::{Event, Events};
 pub use hdr::*;
 pub use seg::{Segment, Segments};
+pub use val::{compare_canonical, validate, validate_buffered, Limits};
+#[cfg(feature = "alloc")]
+pub use vis::{decode_with, Visitor};
 
 /// Simple value constants
+///
+/// Any other simple value from 0 to 19 or from 32 to 255 may also be used;
+/// values 24 to 31 are reserved and have no encoding.
 pub mod simple {
     #![allow(missing_docs)]
 
@@ -152,11 +168,232 @@ pub mod simple {
 }
 
 /// Tag constants
+///
+/// These are the commonly used tags from the IANA CBOR tags registry.
 pub mod tag {
-    #![allow(missing_docs)]
+    /// Standard date/time string (0)
+    pub const DATETIME_STRING: u64 = 0;
 
+    /// Epoch-based date/time (1)
+    pub const EPOCH_TIME: u64 = 1;
+
+    /// Unsigned bignum (2)
     pub const BIGPOS: u64 = 2;
+
+    /// Negative bignum (3)
     pub const BIGNEG: u64 = 3;
+
+    /// Decimal fraction (4)
+    pub const DECIMAL_FRACTION: u64 = 4;
+
+    /// Bigfloat (5)
+    pub const BIGFLOAT: u64 = 5;
+
+    /// COSE single recipient encrypted data object (16)
+    pub const COSE_ENCRYPT0: u64 = 16;
+
+    /// COSE MAC without recipients object (17)
+    pub const COSE_MAC0: u64 = 17;
+
+    /// COSE single signer data object (18)
+    pub const COSE_SIGN1: u64 = 18;
+
+    /// Expected conversion to base64url encoding (21)
+    pub const EXPECTED_B64URL: u64 = 21;
+
+    /// Expected conversion to base64 encoding (22)
+    pub const EXPECTED_B64: u64 = 22;
+
+    /// Expected conversion to base16 encoding (23)
+    pub const EXPECTED_B16: u64 = 23;
+
+    /// Encoded CBOR data item (24)
+    pub const EMBEDDED_CBOR: u64 = 24;
+
+    /// Rational number (30)
+    pub const RATIONAL: u64 = 30;
+
+    /// URI (32)
+    pub const URI: u64 = 32;
+
+    /// base64url-encoded text (33)
+    pub const B64URL: u64 = 33;
+
+    /// base64-encoded text (34)
+    pub const B64: u64 = 34;
+
+    /// MIME message (36)
+    pub const MIME: u64 = 36;
+
+    /// Binary UUID (37)
+    pub const UUID: u64 = 37;
+
+    /// Language-tagged string (38)
+    pub const LANG_STRING: u64 = 38;
+
+    /// CBOR Web Token (61)
+    pub const CWT: u64 = 61;
+
+    /// Uint8 typed array (64)
+    pub const UINT8_ARRAY: u64 = 64;
+
+    /// Uint16 big endian typed array (65)
+    pub const UINT16BE_ARRAY: u64 = 65;
+
+    /// Uint32 big endian typed array (66)
+    pub const UINT32BE_ARRAY: u64 = 66;
+
+    /// Uint64 big endian typed array (67)
+    pub const UINT64BE_ARRAY: u64 = 67;
+
+    /// Uint8 clamped typed array (68)
+    pub const UINT8_CLAMPED_ARRAY: u64 = 68;
+
+    /// Uint16 little endian typed array (69)
+    pub const UINT16LE_ARRAY: u64 = 69;
+
+    /// Uint32 little endian typed array (70)
+    pub const UINT32LE_ARRAY: u64 = 70;
+
+    /// Uint64 little endian typed array (71)
+    pub const UINT64LE_ARRAY: u64 = 71;
+
+    /// Sint8 typed array (72)
+    pub const SINT8_ARRAY: u64 = 72;
+
+    /// Sint16 big endian typed array (73)
+    pub const SINT16BE_ARRAY: u64 = 73;
+
+    /// Sint32 big endian typed array (74)
+    pub const SINT32BE_ARRAY: u64 = 74;
+
+    /// Sint64 big endian typed array (75)
+    pub const SINT64BE_ARRAY: u64 = 75;
+
+    /// Sint16 little endian typed array (77)
+    pub const SINT16LE_ARRAY: u64 = 77;
+
+    /// Sint32 little endian typed array (78)
+    pub const SINT32LE_ARRAY: u64 = 78;
+
+    /// Sint64 little endian typed array (79)
+    pub const SINT64LE_ARRAY: u64 = 79;
+
+    /// Binary16 big endian typed array (80)
+    pub const FLOAT16BE_ARRAY: u64 = 80;
+
+    /// Binary32 big endian typed array (81)
+    pub const FLOAT32BE_ARRAY: u64 = 81;
+
+    /// Binary64 big endian typed array (82)
+    pub const FLOAT64BE_ARRAY: u64 = 82;
+
+    /// Binary128 big endian typed array (83)
+    pub const FLOAT128BE_ARRAY: u64 = 83;
+
+    /// Binary16 little endian typed array (84)
+    pub const FLOAT16LE_ARRAY: u64 = 84;
+
+    /// Binary32 little endian typed array (85)
+    pub const FLOAT32LE_ARRAY: u64 = 85;
+
+    /// Binary64 little endian typed array (86)
+    pub const FLOAT64LE_ARRAY: u64 = 86;
+
+    /// Binary128 little endian typed array (87)
+    pub const FLOAT128LE_ARRAY: u64 = 87;
+
+    /// COSE encrypted data object (96)
+    pub const COSE_ENCRYPT: u64 = 96;
+
+    /// COSE MACed data object (97)
+    pub const COSE_MAC: u64 = 97;
+
+    /// COSE signed data object (98)
+    pub const COSE_SIGN: u64 = 98;
+
+    /// Days since 1970-01-01 (100)
+    pub const EPOCH_DATE: u64 = 100;
+
+    /// Network address (260)
+    pub const NETWORK_ADDRESS: u64 = 260;
+
+    /// Network address prefix (261)
+    pub const NETWORK_PREFIX: u64 = 261;
+
+    /// RFC 3339 full-date string (1004)
+    pub const DATE_STRING: u64 = 1004;
+
+    /// Self-described CBOR (55799)
+    pub const SELF_DESCRIBED: u64 = 55799;
+
+    /// Returns a short description of a tag, if it is a known tag
+    ///
+    /// Every tag with a constant in this module is known.
+    ///
+    /// ```rust
+    /// use ciborium_ll::tag;
+    ///
+    /// assert_eq!(tag::name(tag::EPOCH_TIME), Some("epoch time"));
+    /// assert_eq!(tag::name(6), None);
+    /// ```
+    pub fn name(tag: u64) -> Option<&'static str> {
+        Some(match tag {
+            DATETIME_STRING => "date/time string",
+            EPOCH_TIME => "epoch time",
+            BIGPOS => "unsigned bignum",
+            BIGNEG => "negative bignum",
+            DECIMAL_FRACTION => "decimal fraction",
+            BIGFLOAT => "bigfloat",
+            COSE_ENCRYPT0 => "COSE_Encrypt0",
+            COSE_MAC0 => "COSE_Mac0",
+            COSE_SIGN1 => "COSE_Sign1",
+            EXPECTED_B64URL => "expected base64url",
+            EXPECTED_B64 => "expected base64",
+            EXPECTED_B16 => "expected base16",
+            EMBEDDED_CBOR => "embedded CBOR",
+            RATIONAL => "rational number",
+            URI => "URI",
+            B64URL => "base64url",
+            B64 => "base64",
+            MIME => "MIME message",
+            UUID => "UUID",
+            LANG_STRING => "language-tagged string",
+            CWT => "CBOR Web Token",
+            UINT8_ARRAY => "uint8 typed array",
+            UINT16BE_ARRAY => "uint16 big endian typed array",
+            UINT32BE_ARRAY => "uint32 big endian typed array",
+            UINT64BE_ARRAY => "uint64 big endian typed array",
+            UINT8_CLAMPED_ARRAY => "uint8 clamped typed array",
+            UINT16LE_ARRAY => "uint16 little endian typed array",
+            UINT32LE_ARRAY => "uint32 little endian typed array",
+            UINT64LE_ARRAY => "uint64 little endian typed array",
+            SINT8_ARRAY => "sint8 typed array",
+            SINT16BE_ARRAY => "sint16 big endian typed array",
+            SINT32BE_ARRAY => "sint32 big endian typed array",
+            SINT64BE_ARRAY => "sint64 big endian typed array",
+            SINT16LE_ARRAY => "sint16 little endian typed array",
+            SINT32LE_ARRAY => "sint32 little endian typed array",
+            SINT64LE_ARRAY => "sint64 little endian typed array",
+            FLOAT16BE_ARRAY => "binary16 big endian typed array",
+            FLOAT32BE_ARRAY => "binary32 big endian typed array",
+            FLOAT64BE_ARRAY => "binary64 big endian typed array",
+            FLOAT128BE_ARRAY => "binary128 big endian typed array",
+            FLOAT16LE_ARRAY => "binary16 little endian typed array",
+            FLOAT32LE_ARRAY => "binary32 little endian typed array",
+            FLOAT64LE_ARRAY => "binary64 little endian typed array",
+            FLOAT128LE_ARRAY => "binary128 little endian typed array",
+            COSE_ENCRYPT => "COSE_Encrypt",
+            COSE_MAC => "COSE_Mac",
+            COSE_SIGN => "COSE_Sign",
+            EPOCH_DATE => "epoch date",
+            NETWORK_ADDRESS => "network address",
+            NETWORK_PREFIX => "network prefix",
+            DATE_STRING => "date string",
+            SELF_DESCRIBED => "self-described CBOR",
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -215,6 +452,34 @@ impl AsMut<[u8]> for Minor {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 struct Title(pub Major, pub Minor);
 
+impl Title {
+    /// The first byte of the encoding, which the bytes of the minor follow
+    #[inline]
+    fn prefix(&self) -> u8 {
+        let major = match self.0 {
+            Major::Positive => 0,
+            Major::Negative => 1,
+            Major::Bytes => 2,
+            Major::Text => 3,
+            Major::Array => 4,
+            Major::Map => 5,
+            Major::Tag => 6,
+            Major::Other => 7,
+        };
+
+        let minor = match self.1 {
+            Minor::This(x) => x,
+            Minor::Next1(..) => 24,
+            Minor::Next2(..) => 25,
+            Minor::Next4(..) => 26,
+            Minor::Next8(..) => 27,
+            Minor::More => 31,
+        };
+
+        major << 5 | minor
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,8 +492,6 @@ mod tests {
 
     #[test]
     fn leaf() {
-        use core::f64::{INFINITY, NAN};
-
         let data = &[
             (Header::Positive(0), "00", true),
             (Header::Positive(1), "01", true),
@@ -263,21 +526,21 @@ mod tests {
             (Header::Float(0.00006103515625), "f90400", true),
             (Header::Float(-4.0), "f9c400", true),
             (Header::Float(-4.1), "fbc010666666666666", true),
-            (Header::Float(INFINITY), "f97c00", true),
-            (Header::Float(NAN), "f97e00", true),
-            (Header::Float(-INFINITY), "f9fc00", true),
-            (Header::Float(INFINITY), "fa7f800000", false),
-            (Header::Float(NAN), "fa7fc00000", false),
-            (Header::Float(-INFINITY), "faff800000", false),
-            (Header::Float(INFINITY), "fb7ff0000000000000", false),
-            (Header::Float(NAN), "fb7ff8000000000000", false),
-            (Header::Float(-INFINITY), "fbfff0000000000000", false),
+            (Header::Float(f64::INFINITY), "f97c00", true),
+            (Header::Float(f64::NAN), "f97e00", true),
+            (Header::Float(-f64::INFINITY), "f9fc00", true),
+            (Header::Float(f64::INFINITY), "fa7f800000", false),
+            (Header::Float(f64::NAN), "fa7fc00000", false),
+            (Header::Float(-f64::INFINITY), "faff800000", false),
+            (Header::Float(f64::INFINITY), "fb7ff0000000000000", false),
+            (Header::Float(f64::NAN), "fb7ff8000000000000", false),
+            (Header::Float(-f64::INFINITY), "fbfff0000000000000", false),
             (Header::Simple(simple::FALSE), "f4", true),
             (Header::Simple(simple::TRUE), "f5", true),
             (Header::Simple(simple::NULL), "f6", true),
             (Header::Simple(simple::UNDEFINED), "f7", true),
             (Header::Simple(16), "f0", true),
-            (Header::Simple(24), "f818", true),
+            (Header::Simple(32), "f820", true),
             (Header::Simple(255), "f8ff", true),
             (Header::Tag(0), "c0", true),
             (Header::Tag(1), "c1", true),
@@ -466,8 +729,8 @@ mod tests {
 
             // Test decoding
             let mut decoder = Decoder::from(&bytes[..]);
-            for header in headers.iter().cloned() {
-                assert_eq!(header, decoder.pull().unwrap());
+            for header in headers.iter() {
+                assert_eq!(*header, decoder.pull().unwrap());
             }
 
             // Test encoding
@@ -483,4 +746,1933 @@ mod tests {
             assert_eq!(&bytes[..], &buffer[..1024 - len]);
         }
     }
+
+    #[test]
+    fn offset() {
+        // [h'0102', "a", 1000]
+        let bytes = hex::decode("8342010261611903e8").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert_eq!(decoder.offset(), 0);
+
+        assert_eq!(decoder.pull().unwrap(), Header::Array(Some(3)));
+        assert_eq!(decoder.offset(), 1);
+
+        assert_eq!(decoder.pull().unwrap(), Header::Bytes(Some(2)));
+        assert_eq!(decoder.offset(), 2);
+
+        let mut buffer = [0u8; 2];
+        ciborium_io::Read::read_exact(&mut decoder, &mut buffer[..]).unwrap();
+        assert_eq!(decoder.offset(), 4);
+
+        assert_eq!(decoder.pull().unwrap(), Header::Text(Some(1)));
+        let mut segments = decoder.text(Some(1));
+        while let Some(mut segment) = segments.pull().unwrap() {
+            while segment.pull(&mut buffer[..]).unwrap().is_some() {}
+        }
+        assert_eq!(decoder.offset(), 6);
+
+        // Pushing a header back rewinds the offset until it is pulled.
+        let header = decoder.pull().unwrap();
+        assert_eq!((header, decoder.offset()), (Header::Positive(1000), 9));
+        decoder.push(header);
+        assert_eq!(decoder.offset(), 6);
+        assert_eq!(decoder.pull().unwrap(), Header::Positive(1000));
+        assert_eq!(decoder.offset(), 9);
+
+        // A failed read is not counted.
+        decoder.pull().unwrap_err();
+        assert_eq!(decoder.offset(), 9);
+    }
+
+    #[test]
+    fn offset_errors() {
+        // A reserved minor value is a syntax error at the prefix.
+        let bytes = hex::decode("011c").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        decoder.pull().unwrap();
+        assert!(matches!(decoder.pull(), Err(Error::Syntax(1))));
+        assert_eq!(decoder.offset(), 2);
+
+        // A truncated header counts the bytes read before the failure.
+        let bytes = hex::decode("1903").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert!(matches!(decoder.pull(), Err(Error::Io(..))));
+        assert_eq!(decoder.offset(), 1);
+
+        // An invalid header counts all of its bytes.
+        let bytes = hex::decode("011f").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        decoder.pull().unwrap();
+        assert!(matches!(decoder.pull(), Err(Error::Syntax(1))));
+        assert_eq!(decoder.offset(), 2);
+    }
+
+    #[test]
+    fn integers() {
+        let max = i128::from(u64::MAX);
+
+        let data = [
+            (0, Ok(Header::Positive(0))),
+            (1, Ok(Header::Positive(1))),
+            (-1, Ok(Header::Negative(0))),
+            (-2, Ok(Header::Negative(1))),
+            (i64::MAX.into(), Ok(Header::Positive(i64::MAX as u64))),
+            (i64::MIN.into(), Ok(neg!(i64::MIN))),
+            (max, Ok(Header::Positive(u64::MAX))),
+            (-max, Ok(Header::Negative(u64::MAX - 1))),
+            (-max - 1, Ok(Header::Negative(u64::MAX))),
+            (max + 1, Err(TooLarge(max + 1))),
+            (-max - 2, Err(TooLarge(-max - 2))),
+            (i128::MAX, Err(TooLarge(i128::MAX))),
+            (i128::MIN, Err(TooLarge(i128::MIN))),
+        ];
+
+        for (value, header) in data {
+            assert_eq!(Header::from_i128(value), header, "{}", value);
+
+            if let Ok(header) = header {
+                assert_eq!(header.as_i128(), Some(value));
+            }
+        }
+
+        // Every header around the edges converts back and forth.
+        for x in (0..=u64::MAX)
+            .take(256)
+            .chain((0..=u64::MAX).rev().take(256))
+        {
+            for header in [Header::Positive(x), Header::Negative(x)] {
+                let value = header.as_i128().unwrap();
+                assert_eq!(Header::from_i128(value), Ok(header));
+                assert_eq!(value.is_negative(), matches!(header, Header::Negative(..)));
+            }
+        }
+
+        for header in [Header::Tag(1), Header::Simple(1), Header::Array(Some(1))] {
+            assert_eq!(header.as_i128(), None);
+        }
+    }
+
+    #[test]
+    fn skip() {
+        let data: &[&str] = &[
+            // Scalars, strings and tags
+            "01",
+            "3903e7",
+            "f97e00",
+            "f820",
+            "4401020304",
+            "6161",
+            "5f4201024103ff",
+            "7f616161626163ff",
+            "c1c21a514b67b0",
+            // Definite containers
+            "80",
+            "83010203",
+            "a201020304",
+            "a2616182010261628103",
+            "8301820203820405",
+            // Indefinite containers, nested in each other and in definite ones
+            "9fff",
+            "9f018202039f0405ffff",
+            "83019f0203ff820405",
+            "bf61610161629f0203ffff",
+            "a161619fbf01c606ffff",
+            "825f41014102ff7f6161ff",
+        ];
+
+        // Surrounds the item with a prefix and a suffix
+        fn wrap<'a>(buffer: &'a mut [u8], prefix: u8, item: &[u8], suffix: &[u8]) -> &'a [u8] {
+            let len = item.len() + suffix.len() + 1;
+            buffer[0] = prefix;
+            buffer[1..=item.len()].copy_from_slice(item);
+            buffer[item.len() + 1..len].copy_from_slice(suffix);
+            &buffer[..len]
+        }
+
+        for item in data {
+            let item = hex::decode(item).unwrap();
+            let mut buffer = [0u8; 64];
+
+            // The item, followed by a sibling and a break.
+            let bytes = wrap(&mut buffer, 0x9f, &item, &[0x17, 0xff]);
+            let mut decoder = Decoder::from(bytes);
+
+            assert_eq!(decoder.pull().unwrap(), Header::Array(None));
+            decoder.skip().unwrap();
+            assert_eq!(decoder.offset(), bytes.len() - 2, "{:x?}", item);
+            assert_eq!(decoder.pull().unwrap(), Header::Positive(23));
+            assert_eq!(decoder.pull().unwrap(), Header::Break);
+
+            // Inside a definite array, too.
+            let bytes = wrap(&mut buffer, 0x82, &item, &[0x17]);
+            let mut decoder = Decoder::from(bytes);
+
+            assert_eq!(decoder.pull().unwrap(), Header::Array(Some(2)));
+            decoder.skip().unwrap();
+            assert_eq!(decoder.pull().unwrap(), Header::Positive(23));
+            decoder.pull().unwrap_err();
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn capture() {
+        use alloc::{vec, vec::Vec};
+
+        let data: &[&str] = &[
+            "01",
+            "1801",
+            "3a000003e7",
+            "fb3ff8000000000000",
+            "5f4201024103ff",
+            "7f616161626163ff",
+            "c1c21a514b67b0",
+            "9f018202039f0405ffff",
+            "b9000161619fbf01c606ffff",
+        ];
+
+        for item in data {
+            let item = hex::decode(item).unwrap();
+            let mut bytes = item.clone();
+            bytes.push(0x17);
+
+            let mut captured = vec![0xaa];
+            let mut decoder = Decoder::from(&bytes[..]);
+            decoder.capture(&mut captured).unwrap();
+            assert_eq!(captured[1..], item[..], "{:x?}", item);
+            assert_eq!(decoder.pull().unwrap(), Header::Positive(23));
+
+            // A header which was peeked at is kept as it was encoded.
+            let mut captured = Vec::new();
+            let mut decoder = Decoder::from(&bytes[..]);
+            decoder.peek().unwrap();
+            decoder.capture(&mut captured).unwrap();
+            assert_eq!(captured, item, "{:x?}", item);
+        }
+
+        // The bytes before an error are kept.
+        let bytes = hex::decode("830102ff").unwrap();
+        let mut captured = Vec::new();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert!(decoder.capture(&mut captured).is_err());
+        assert_eq!(captured, bytes);
+    }
+
+    #[test]
+    fn skip_errors() {
+        // A break instead of an item
+        let data: &[(&str, usize)] = &[
+            ("ff", 0),
+            ("8201ff", 2),
+            ("a101ff", 2),
+            ("c1ff", 1),
+            // A key with no value in an indefinite-length map
+            ("bf01ff", 2),
+            ("bf010203ff", 4),
+            ("9fbf01ffff", 3),
+        ];
+
+        for (item, offset) in data {
+            let bytes = hex::decode(item).unwrap();
+            let mut decoder = Decoder::from(&bytes[..]);
+
+            match decoder.skip() {
+                Err(Error::UnexpectedBreak { offset: x }) => assert_eq!(x, *offset, "{}", item),
+                _ => panic!("expected an unexpected break for {}", item),
+            }
+        }
+
+        let data: &[(&str, usize)] = &[
+            // An item instead of a chunk, so the break is missing
+            ("5f410101", 0),
+            ("827f616101", 1),
+            // Strings of the wrong type or of indefinite length are not chunks
+            ("5f6161ff", 0),
+            ("7f4161ff", 0),
+            ("5f5f4101ffff", 0),
+        ];
+
+        for (item, offset) in data {
+            let bytes = hex::decode(item).unwrap();
+            let mut decoder = Decoder::from(&bytes[..]);
+
+            match decoder.skip() {
+                Err(Error::MissingBreak { container_offset }) => {
+                    assert_eq!(container_offset, *offset, "{}", item)
+                }
+                _ => panic!("expected a missing break for {}", item),
+            }
+        }
+
+        // Truncated input
+        for item in &["82", "9f01", "43", "5f4101", "c1"] {
+            let bytes = hex::decode(item).unwrap();
+            let mut decoder = Decoder::from(&bytes[..]);
+            assert!(matches!(decoder.skip(), Err(Error::Io(..))), "{}", item);
+        }
+    }
+
+    #[test]
+    fn skip_depth() {
+        // Three nested containers, of each kind
+        for item in &["81a1019f01ff", "9fbf019fffffff"] {
+            let bytes = hex::decode(item).unwrap();
+
+            let mut decoder = Decoder::from(&bytes[..]);
+            decoder.skip_with_max_depth(3).unwrap();
+
+            let mut decoder = Decoder::from(&bytes[..]);
+            assert!(matches!(
+                decoder.skip_with_max_depth(2),
+                Err(Error::LimitExceeded {
+                    kind: LimitKind::Depth,
+                    limit: 2,
+                    observed: 3,
+                    offset: 3,
+                })
+            ));
+        }
+
+        // Scalars and tags do not count.
+        let bytes = hex::decode("c1c101").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        decoder.skip_with_max_depth(0).unwrap();
+
+        let bytes = [0x81; 300];
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert!(matches!(
+            decoder.skip(),
+            Err(Error::LimitExceeded {
+                kind: LimitKind::Depth,
+                limit: 256,
+                observed: 257,
+                offset: 256,
+            })
+        ));
+    }
+
+    #[test]
+    fn validate() {
+        /// A buffered reader which has at most 3 bytes buffered at a time
+        struct Trickle<'a>(&'a [u8]);
+
+        impl ciborium_io::Read for Trickle<'_> {
+            type Error = ();
+
+            fn read_exact(&mut self, data: &mut [u8]) -> Result<(), ()> {
+                self.0.read_exact(data).or(Err(()))
+            }
+        }
+
+        impl ciborium_io::BufRead for Trickle<'_> {
+            fn fill_buf(&mut self) -> Result<&[u8], ()> {
+                Ok(&self.0[..self.0.len().min(3)])
+            }
+
+            fn consume(&mut self, amt: usize) {
+                self.0 = &self.0[amt..];
+            }
+        }
+
+        let good: &[&str] = &[
+            "01",
+            "f820",
+            "c1c21a514b67b0",
+            "4401020304",
+            "5f4201024103ff",
+            // "\u00fc\u6c34\u{10151}", whole and in chunks
+            "69c3bce6b0b4f0908591",
+            "7f62c3bc63e6b0b464f0908591ff",
+            "a2616182010261628103",
+            "8301820203820405",
+            "bf61610161629f0203ffff",
+            "825f41014102ff7f6161ff",
+        ];
+
+        for item in good {
+            let bytes = hex::decode(item).unwrap();
+            super::validate(&bytes[..], Limits::new()).unwrap();
+            validate_buffered(&bytes[..], Limits::new()).unwrap();
+            validate_buffered(Trickle(&bytes), Limits::new()).unwrap();
+        }
+
+        let breaks: &[(&str, usize)] = &[
+            // A break instead of an item
+            ("ff", 0),
+            ("8201ff", 2),
+            ("c1ff", 1),
+            // A map which ends after a key
+            ("bf01ff", 2),
+        ];
+
+        for (item, offset) in breaks {
+            let bytes = hex::decode(item).unwrap();
+
+            match super::validate(&bytes[..], Limits::new()) {
+                Err(Error::UnexpectedBreak { offset: x }) => assert_eq!(x, *offset, "{}", item),
+                _ => panic!("expected an unexpected break for {}", item),
+            }
+
+            match validate_buffered(Trickle(&bytes), Limits::new()) {
+                Err(Error::UnexpectedBreak { offset: x }) => assert_eq!(x, *offset, "{}", item),
+                _ => panic!("expected an unexpected break for {}", item),
+            }
+        }
+
+        // An item instead of a chunk, or a string of the wrong type
+        for (item, offset) in &[("827f616101", 1), ("5f6161ff", 0)] {
+            let bytes = hex::decode(item).unwrap();
+
+            match super::validate(&bytes[..], Limits::new()) {
+                Err(Error::MissingBreak { container_offset }) => {
+                    assert_eq!(container_offset, *offset, "{}", item)
+                }
+                _ => panic!("expected a missing break for {}", item),
+            }
+
+            match validate_buffered(Trickle(&bytes), Limits::new()) {
+                Err(Error::MissingBreak { container_offset }) => {
+                    assert_eq!(container_offset, *offset, "{}", item)
+                }
+                _ => panic!("expected a missing break for {}", item),
+            }
+        }
+
+        let bad: &[(&str, usize)] = &[
+            // An invalid header
+            ("1c", 0),
+        ];
+
+        for (item, offset) in bad {
+            let bytes = hex::decode(item).unwrap();
+
+            match super::validate(&bytes[..], Limits::new()) {
+                Err(Error::Syntax(x)) => assert_eq!(x, *offset, "{}", item),
+                _ => panic!("expected a syntax error for {}", item),
+            }
+
+            match validate_buffered(Trickle(&bytes), Limits::new()) {
+                Err(Error::Syntax(x)) => assert_eq!(x, *offset, "{}", item),
+                _ => panic!("expected a syntax error for {}", item),
+            }
+        }
+
+        let invalid: &[(&str, usize, usize)] = &[
+            // Invalid UTF-8, in the string or the chunk
+            ("8261616180", 0, 3),
+            ("63e6b061", 0, 0),
+            ("7f6161626180ff", 1, 3),
+            // A character which spans two chunks
+            ("7f61c361bcff", 0, 1),
+        ];
+
+        for (item, chunk, offset) in invalid {
+            let bytes = hex::decode(item).unwrap();
+            let expected = (*chunk, *offset);
+
+            match super::validate(&bytes[..], Limits::new()) {
+                Err(Error::InvalidUtf8 { chunk, offset }) => {
+                    assert_eq!((chunk, offset), expected, "{}", item)
+                }
+                _ => panic!("expected invalid UTF-8 for {}", item),
+            }
+
+            match validate_buffered(Trickle(&bytes), Limits::new()) {
+                Err(Error::InvalidUtf8 { chunk, offset }) => {
+                    assert_eq!((chunk, offset), expected, "{}", item)
+                }
+                _ => panic!("expected invalid UTF-8 for {}", item),
+            }
+        }
+
+        // Truncated input
+        for item in &["82", "9f01", "43", "62c3", "5f4101", "c1"] {
+            let bytes = hex::decode(item).unwrap();
+            let result = super::validate(&bytes[..], Limits::new());
+            assert!(matches!(result, Err(Error::Io(..))), "{}", item);
+            let result = validate_buffered(Trickle(&bytes), Limits::new());
+            assert!(matches!(result, Err(Error::Io(..))), "{}", item);
+        }
+
+        // Nothing after the item is read.
+        let bytes = hex::decode("8101ff").unwrap();
+        let mut reader = &bytes[..];
+        validate_buffered(&mut reader, Limits::new()).unwrap();
+        assert_eq!(reader, &[0xff]);
+
+        let bytes = [0x81; 300];
+        let result = super::validate(&bytes[..], Limits::new());
+        assert!(matches!(
+            result,
+            Err(Error::LimitExceeded {
+                kind: LimitKind::Depth,
+                limit: 256,
+                offset: 256,
+                ..
+            })
+        ));
+        let result = super::validate(&bytes[..4], Limits::new().max_depth(3));
+        assert!(matches!(
+            result,
+            Err(Error::LimitExceeded {
+                limit: 3,
+                observed: 4,
+                offset: 3,
+                ..
+            })
+        ));
+        let result = super::validate(&bytes[..3], Limits::new().max_depth(3));
+        assert!(matches!(result, Err(Error::Io(..))));
+    }
+
+    #[test]
+    fn contiguous() {
+        // ["hello", h'0102', "wor"]
+        let bytes = hex::decode("836568656c6c6f42010263776f72").unwrap();
+        let strings: [(usize, &[u8]); 3] = [(2, b"hello"), (8, &[1, 2]), (11, b"wor")];
+
+        // Split the input at every offset, so that each string is either
+        // in one segment or straddles the two.
+        for split in 0..=bytes.len() {
+            let reader = ciborium_io::Slices::new([&bytes[..split], &bytes[split..]]);
+            let mut decoder = Decoder::from(reader);
+            let mut buffer = [0u8; 5];
+
+            assert_eq!(decoder.pull().unwrap(), Header::Array(Some(3)));
+
+            for (start, body) in strings {
+                let len = match decoder.pull().unwrap() {
+                    Header::Bytes(Some(len)) | Header::Text(Some(len)) => len,
+                    x => panic!("unexpected header: {:?}", x),
+                };
+
+                let straddles = start < split && split < start + len;
+
+                match decoder.contiguous(len).unwrap() {
+                    Some(borrowed) => {
+                        assert!(!straddles, "borrowed across {}", split);
+                        assert_eq!(borrowed, body);
+                        decoder.consume(len);
+                    }
+
+                    None => {
+                        assert!(straddles, "copied at {}", split);
+                        ciborium_io::Read::read_exact(&mut decoder, &mut buffer[..len]).unwrap();
+                        assert_eq!(&buffer[..len], body);
+                    }
+                }
+
+                assert_eq!(decoder.offset(), start + len);
+            }
+
+            assert!(decoder.pull().is_err());
+        }
+    }
+
+    #[test]
+    fn compare_canonical() {
+        // The keys in the example of RFC 8949 section 4.2.1, in order
+        let sorted: [&[u8]; 8] = [
+            &[0x0a],
+            &[0x18, 0x64],
+            &[0x20],
+            &[0x61, b'z'],
+            &[0x62, b'a', b'a'],
+            &[0x81, 0x18, 0x64],
+            &[0x81, 0x20],
+            &[0xf4],
+        ];
+
+        let mut shuffled = sorted;
+        shuffled.reverse();
+        shuffled.swap(1, 5);
+        shuffled.sort_by(|a, b| super::compare_canonical(a, b).unwrap());
+        assert_eq!(shuffled, sorted);
+
+        for key in sorted {
+            let result = super::compare_canonical(key, key);
+            assert_eq!(result.unwrap(), core::cmp::Ordering::Equal);
+        }
+
+        // Each side must be exactly one well-formed item.
+        let bad: &[(&[u8], &[u8])] = &[
+            (&[], &[0x00]),
+            (&[0x00], &[0x62, 0x61]),
+            (&[0x82, 0x00], &[0x00]),
+            (&[0x9f, 0x00], &[0x00]),
+        ];
+
+        for (a, b) in bad {
+            let result = super::compare_canonical(a, b);
+            assert!(matches!(result, Err(Error::Io(..))), "{:x?}", a);
+            let result = super::compare_canonical(b, a);
+            assert!(matches!(result, Err(Error::Io(..))), "{:x?}", a);
+        }
+
+        let bad: &[(&[u8], usize)] = &[
+            (&[0x00, 0x00], 1),
+            (&[0x62, 0x61, 0x62, 0xff], 3),
+            (&[0x1c], 0),
+        ];
+
+        for (a, offset) in bad {
+            match super::compare_canonical(a, &[0x00]) {
+                Err(Error::Syntax(x)) => assert_eq!(x, *offset, "{:x?}", a),
+                _ => panic!("expected a syntax error for {:x?}", a),
+            }
+        }
+
+        match super::compare_canonical(&[0xff], &[0x00]) {
+            Err(Error::UnexpectedBreak { offset }) => assert_eq!(offset, 0),
+            _ => panic!("expected an unexpected break"),
+        }
+    }
+
+    #[test]
+    fn peek() {
+        // [h'01', 0]
+        let bytes = hex::decode("824101180000").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+
+        assert_eq!(decoder.peek().unwrap(), Header::Array(Some(2)));
+        assert_eq!(decoder.peek().unwrap(), Header::Array(Some(2)));
+        assert_eq!(decoder.offset(), 0);
+        assert_eq!(decoder.pull().unwrap(), Header::Array(Some(2)));
+        assert_eq!(decoder.offset(), 1);
+
+        // Peeking at a string does not read its body.
+        assert_eq!(decoder.peek().unwrap(), Header::Bytes(Some(1)));
+        assert_eq!(decoder.offset(), 1);
+        decoder.skip().unwrap();
+        assert_eq!(decoder.offset(), 3);
+
+        // The header is kept as it was encoded, in two bytes.
+        assert_eq!(decoder.peek().unwrap(), Header::Positive(0));
+        assert_eq!(decoder.offset(), 3);
+        assert_eq!(decoder.pull().unwrap(), Header::Positive(0));
+        assert_eq!(decoder.offset(), 5);
+
+        assert_eq!(decoder.peek().unwrap(), Header::Positive(0));
+        assert_eq!(decoder.pull().unwrap(), Header::Positive(0));
+        assert_eq!(decoder.offset(), 6);
+        decoder.peek().unwrap_err();
+        assert_eq!(decoder.offset(), 6);
+
+        // An invalid header fails each time, at the same offset.
+        let bytes = hex::decode("1f").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert!(matches!(decoder.peek(), Err(Error::Syntax(0))));
+        assert!(matches!(decoder.peek(), Err(Error::Syntax(0))));
+        assert!(matches!(decoder.pull(), Err(Error::Syntax(0))));
+    }
+
+    #[test]
+    fn length() {
+        let data: &[(Header, usize)] = &[
+            (Header::Positive(0), 1),
+            (Header::Positive(23), 1),
+            (Header::Positive(24), 2),
+            (Header::Positive(255), 2),
+            (Header::Positive(256), 3),
+            (Header::Positive(65535), 3),
+            (Header::Positive(65536), 5),
+            (Header::Positive(u32::MAX as u64), 5),
+            (Header::Positive(u32::MAX as u64 + 1), 9),
+            (Header::Positive(u64::MAX), 9),
+            (Header::Negative(23), 1),
+            (Header::Negative(24), 2),
+            (Header::Negative(u32::MAX as u64 + 1), 9),
+            (Header::Tag(23), 1),
+            (Header::Tag(256), 3),
+            (Header::Bytes(Some(23)), 1),
+            (Header::Bytes(Some(255)), 2),
+            (Header::Text(Some(65536)), 5),
+            (Header::Array(Some(256)), 3),
+            (Header::Map(Some(24)), 2),
+            (Header::Bytes(None), 1),
+            (Header::Map(None), 1),
+            (Header::Break, 1),
+            (Header::Simple(23), 1),
+            (Header::Simple(32), 2),
+            (Header::Float(0.0), 3),
+            (Header::Float(65504.0), 3),
+            (Header::Float(100000.0), 5),
+            (Header::Float(f64::NAN), 3),
+            (Header::Float(1.1), 9),
+        ];
+
+        for (header, length) in data.iter().cloned() {
+            assert_eq!(header.length(), length, "{:?}", header);
+
+            // The length matches what the encoder writes.
+            let mut buffer = [0u8; 9];
+            let mut writer = &mut buffer[..];
+            Encoder::from(&mut writer).push(header).unwrap();
+            assert_eq!(9 - writer.len(), length, "{:?}", header);
+        }
+    }
+
+    #[test]
+    fn pull_canonical() {
+        let data: &[(&str, bool)] = &[
+            ("17", true),
+            ("1817", false),
+            ("1818", true),
+            ("1900ff", false),
+            ("190100", true),
+            ("1a0000ffff", false),
+            ("1a00010000", true),
+            ("1b00000000ffffffff", false),
+            ("1b0000000100000000", true),
+            ("3817", false),
+            ("d81f", true),
+            ("d90017", false),
+            ("5817", false),
+            ("7f", true),
+            ("9800", false),
+            ("b818", true),
+            ("f820", true),
+            ("f93e00", true),
+            ("fa3fc00000", false),
+            ("fb3ff8000000000000", false),
+            ("fa47c35000", true),
+            ("fb3ff199999999999a", true),
+        ];
+
+        for (bytes, canonical) in data {
+            let bytes = hex::decode(bytes).unwrap();
+
+            let mut decoder = Decoder::from(&bytes[..]);
+            let header = decoder.pull().unwrap();
+
+            let mut decoder = Decoder::from(&bytes[..]);
+            match decoder.pull_canonical() {
+                Ok(x) if *canonical => assert_eq!(x, header),
+                Err(Error::Syntax(0)) if !canonical => (),
+                x => panic!("unexpected result for {:x?}: {:?}", bytes, x),
+            }
+        }
+    }
+
+    /// Encodes a float with `Encoder::push_float_preferred()`
+    fn preferred(value: f64, buffer: &mut [u8; 9]) -> &[u8] {
+        let mut writer = &mut buffer[..];
+        Encoder::from(&mut writer)
+            .push_float_preferred(value)
+            .unwrap();
+        let len = 9 - writer.len();
+        &buffer[..len]
+    }
+
+    #[test]
+    fn push_float_preferred() {
+        let mut buffer = [0u8; 9];
+
+        // Every half float is written as a half float, except that all NaNs
+        // are written as the same one.
+        for bits in 0..=u16::MAX {
+            let value = half::to_f64(bits);
+            let bytes = preferred(value, &mut buffer);
+
+            match value.is_nan() {
+                true => assert_eq!(bytes, [0xf9, 0x7e, 0x00]),
+                false => assert_eq!(bytes, [&[0xf9][..], &bits.to_be_bytes()].concat()),
+            }
+        }
+
+        let data: &[(f64, &str)] = &[
+            (65504.0, "f97bff"),
+            (65505.0, "fa477fe100"),
+            (5.960464477539063e-8, "f90001"),
+            (f64::from_bits(0x3e60_0000_0000_0000), "fa33000000"),
+            (f32::from_bits(1).into(), "fa00000001"),
+            (f64::from_bits(0x3690_0000_0000_0000), "fb3690000000000000"),
+            (f32::MAX.into(), "fa7f7fffff"),
+            (f64::from_bits(0x47f0_0000_0000_0000), "fb47f0000000000000"),
+            (f64::MIN_POSITIVE, "fb0010000000000000"),
+            (f64::INFINITY, "f97c00"),
+            (f64::NEG_INFINITY, "f9fc00"),
+            (-0.0, "f98000"),
+            (-f64::NAN, "f97e00"),
+            (f64::from_bits(0x7ff0_0000_0000_0001), "f97e00"),
+            (f32::from_bits(0xff80_0001).into(), "f97e00"),
+        ];
+
+        for (value, bytes) in data.iter().cloned() {
+            let bytes = hex::decode(bytes).unwrap();
+            assert_eq!(preferred(value, &mut buffer), &bytes[..], "{}", value);
+
+            // Without a NaN, the value round-trips.
+            let mut decoder = Decoder::from(&bytes[..]);
+            match decoder.pull().unwrap() {
+                Header::Float(x) if value.is_nan() => assert!(x.is_nan()),
+                Header::Float(x) => assert_eq!(x.to_bits(), value.to_bits()),
+                header => panic!("unexpected header {:?}", header),
+            }
+        }
+    }
+
+    #[test]
+    fn push_minimal() {
+        let data: &[(u64, &str)] = &[
+            (0, "00"),
+            (23, "17"),
+            (24, "1818"),
+            (255, "18ff"),
+            (256, "190100"),
+            (65535, "19ffff"),
+            (65536, "1a00010000"),
+            (u32::MAX as u64, "1affffffff"),
+            (u32::MAX as u64 + 1, "1b0000000100000000"),
+            (u64::MAX, "1bffffffffffffffff"),
+        ];
+
+        for (value, bytes) in data.iter().cloned() {
+            let mut bytes = hex::decode(bytes).unwrap();
+
+            for (major, header) in [(0u8, Header::Positive(value)), (1, Header::Negative(value))] {
+                bytes[0] = bytes[0] & 0x1f | major << 5;
+
+                let mut buffer = [0u8; 9];
+                let mut writer = &mut buffer[..];
+                Encoder::from(&mut writer).push(header).unwrap();
+                let len = 9 - writer.len();
+                assert_eq!(&buffer[..len], &bytes[..], "{:?}", header);
+            }
+        }
+    }
+
+    #[test]
+    fn simple() {
+        for x in 0..=255u8 {
+            let mut buffer = [0u8; 2];
+            let mut writer = &mut buffer[..];
+            let result = Encoder::from(&mut writer).try_push(Header::Simple(x));
+            let len = 2 - writer.len();
+
+            match x {
+                0..=23 => {
+                    result.unwrap();
+                    assert_eq!(&buffer[..len], &[0xe0 | x], "{}", x);
+                }
+
+                24..=31 => {
+                    assert!(matches!(result, Err(PushError::ReservedSimple(y)) if y == x));
+                    assert_eq!(len, 0, "{}", x);
+                }
+
+                _ => {
+                    result.unwrap();
+                    assert_eq!(&buffer[..len], &[0xf8, x], "{}", x);
+                }
+            }
+
+            // The two byte form decodes only when it is required.
+            let bytes = [0xf8, x];
+            let mut decoder = Decoder::from(&bytes[..]);
+            match decoder.pull() {
+                Ok(Header::Simple(y)) if x >= 32 => assert_eq!(y, x),
+                Err(Error::Syntax(0)) if x < 32 => (),
+                r => panic!("unexpected result for simple({}): {:?}", x, r),
+            }
+        }
+
+        // The one byte form covers 0 to 23; 24 to 27 are floats and the
+        // two byte form, 28 to 30 are reserved and 31 is the break.
+        for minor in 0..=23u8 {
+            let bytes = [0xe0 | minor];
+            let mut decoder = Decoder::from(&bytes[..]);
+            assert_eq!(decoder.pull().unwrap(), Header::Simple(minor));
+        }
+
+        for minor in 28..=30u8 {
+            let bytes = [0xe0 | minor];
+            let mut decoder = Decoder::from(&bytes[..]);
+            assert!(matches!(decoder.pull(), Err(Error::Syntax(0))));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "simple value 24 is reserved")]
+    fn push_reserved_simple() {
+        let mut buffer = [0u8; 2];
+        let _ = Encoder::from(&mut buffer[..]).push(Header::Simple(24));
+    }
+
+    #[test]
+    fn display() {
+        use core::fmt::Write as _;
+
+        struct Buffer([u8; 32], usize);
+
+        impl core::fmt::Write for Buffer {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                let end = self.1 + s.len();
+                self.0[self.1..end].copy_from_slice(s.as_bytes());
+                self.1 = end;
+                Ok(())
+            }
+        }
+
+        let data: &[(Header, &str)] = &[
+            (Header::Positive(0), "0"),
+            (Header::Positive(u64::MAX), "18446744073709551615"),
+            (Header::Negative(0), "-1"),
+            (Header::Negative(499), "-500"),
+            (Header::Negative(u64::MAX), "-18446744073709551616"),
+            (Header::Float(0.0), "0.0_f16"),
+            (Header::Float(-0.0), "-0.0_f16"),
+            (Header::Float(1.5), "1.5_f16"),
+            (Header::Float(100000.0), "100000.0_f32"),
+            (Header::Float(1.1), "1.1_f64"),
+            (Header::Float(f64::NAN), "NaN_f16"),
+            (Header::Float(f64::INFINITY), "Infinity_f16"),
+            (Header::Float(f64::NEG_INFINITY), "-Infinity_f16"),
+            (
+                Header::Float(f64::from_bits(0x7ff0_0000_0000_0001)),
+                "NaN_f64",
+            ),
+            (Header::Simple(simple::FALSE), "simple(20)"),
+            (Header::Simple(simple::UNDEFINED), "simple(23)"),
+            (Header::Simple(255), "simple(255)"),
+            (Header::Tag(37), "tag(37)"),
+            (Header::Break, "break"),
+            (Header::Bytes(Some(12)), "bytes(12)"),
+            (Header::Bytes(None), "bytes(_)"),
+            (Header::Text(Some(0)), "text(0)"),
+            (Header::Text(None), "text(_)"),
+            (Header::Array(Some(2)), "array(2)"),
+            (Header::Array(None), "array(_)"),
+            (Header::Map(Some(3)), "map(3)"),
+            (Header::Map(None), "map(_)"),
+        ];
+
+        for (header, text) in data {
+            let mut buffer = Buffer([0; 32], 0);
+            write!(buffer, "{}", header).unwrap();
+            assert_eq!(&buffer.0[..buffer.1], text.as_bytes(), "{:?}", header);
+        }
+    }
+
+    #[test]
+    fn item_budget() {
+        /// An endless indefinite-length array of zeros
+        struct Endless(bool);
+
+        impl ciborium_io::Read for Endless {
+            type Error = ();
+
+            fn read_exact(&mut self, data: &mut [u8]) -> Result<(), ()> {
+                data.fill(0);
+                if !core::mem::replace(&mut self.0, true) {
+                    data[0] = 0x9f;
+                }
+
+                Ok(())
+            }
+        }
+
+        let mut decoder = Decoder::with_item_budget(Endless(false), 100);
+        assert_eq!(decoder.pull().unwrap(), Header::Array(None));
+        assert_eq!(decoder.item_budget(), Some(99));
+
+        // Peeking is free, and pushing back refunds the header.
+        assert_eq!(decoder.peek().unwrap(), Header::Positive(0));
+        assert_eq!(decoder.item_budget(), Some(99));
+        let header = decoder.pull().unwrap();
+        decoder.push(header);
+        assert_eq!(decoder.item_budget(), Some(99));
+
+        for _ in 0..99 {
+            assert_eq!(decoder.pull().unwrap(), Header::Positive(0));
+        }
+
+        let offset = decoder.offset();
+
+        match decoder.pull() {
+            Err(Error::LimitExceeded {
+                kind: LimitKind::Items,
+                limit: 100,
+                observed: 101,
+                offset: x,
+            }) => assert_eq!(x, offset),
+            _ => panic!("expected the item limit to be exceeded"),
+        }
+
+        assert!(matches!(
+            decoder.skip(),
+            Err(Error::LimitExceeded {
+                kind: LimitKind::Items,
+                ..
+            })
+        ));
+        assert_eq!(decoder.offset(), offset);
+
+        // The chunks of a string and its break count too.
+        let bytes = hex::decode("5f41014102ff").unwrap();
+
+        for budget in 0..4 {
+            let mut decoder = Decoder::with_item_budget(&bytes[..], budget);
+            assert!(matches!(
+                decoder.skip(),
+                Err(Error::LimitExceeded {
+                    kind: LimitKind::Items,
+                    ..
+                })
+            ));
+        }
+
+        let mut decoder = Decoder::with_item_budget(&bytes[..], 4);
+        decoder.skip().unwrap();
+        assert_eq!(decoder.item_budget(), Some(0));
+
+        // There is no budget by default.
+        assert_eq!(Decoder::from(&bytes[..]).item_budget(), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn depth() {
+        let data: &[(&str, &[usize])] = &[
+            ("01", &[0]),
+            ("80", &[0]),
+            ("a0", &[0]),
+            ("8101", &[1, 0]),
+            ("820102", &[1, 1, 0]),
+            ("a10102", &[1, 1, 0]),
+            ("c101", &[1, 0]),
+            ("c1c101", &[1, 2, 0]),
+            ("9f01ff", &[1, 1, 0]),
+            ("9fff", &[1, 0]),
+            ("8180", &[1, 0]),
+            ("82818101818101", &[1, 2, 3, 1, 2, 3, 0]),
+            ("829f01ff02", &[1, 2, 2, 1, 0]),
+            ("bf01c19f02ffff", &[1, 1, 2, 3, 3, 1, 0]),
+            ("824161817f6161ff", &[1, 1, 2, 2, 2, 0]),
+        ];
+
+        for (bytes, depths) in data {
+            let bytes = hex::decode(bytes).unwrap();
+            let mut decoder = Decoder::with_max_depth(&bytes[..], usize::MAX);
+
+            for depth in depths.iter() {
+                match decoder.pull().unwrap() {
+                    Header::Bytes(Some(len)) | Header::Text(Some(len)) => {
+                        let mut buffer = [0u8; 8];
+                        ciborium_io::Read::read_exact(&mut decoder, &mut buffer[..len]).unwrap();
+                    }
+
+                    _ => (),
+                }
+
+                assert_eq!(decoder.depth(), Some(*depth), "{:x?}", bytes);
+            }
+        }
+
+        // A header pushed back is not counted until it is pulled again.
+        let bytes = hex::decode("81818101").unwrap();
+        let mut decoder = Decoder::with_max_depth(&bytes[..], usize::MAX);
+        assert_eq!(decoder.pull().unwrap(), Header::Array(Some(1)));
+        let header = decoder.pull().unwrap();
+        assert_eq!(decoder.depth(), Some(2));
+        decoder.push(header);
+        assert_eq!(decoder.depth(), Some(1));
+        assert_eq!(decoder.peek().unwrap(), header);
+        assert_eq!(decoder.pull().unwrap(), header);
+        assert_eq!(decoder.depth(), Some(2));
+        decoder.skip().unwrap();
+        assert_eq!(decoder.depth(), Some(0));
+
+        // Without a limit, the depth is not tracked at all.
+        let mut decoder = Decoder::from(&bytes[..]);
+        decoder.skip().unwrap();
+        assert_eq!(decoder.depth(), None);
+
+        // The limit applies to arrays, maps and tags, but not strings.
+        for (bytes, max) in [
+            ("818181818100", 5),
+            ("a101a101a10100", 3),
+            ("c1c1c101", 3),
+            ("8180", 1),
+            ("815f41014102ff", 1),
+        ] {
+            let bytes = hex::decode(bytes).unwrap();
+
+            let mut decoder = Decoder::with_max_depth(&bytes[..], max);
+            decoder.skip().unwrap();
+
+            let mut decoder = Decoder::with_max_depth(&bytes[..], max - 1);
+            assert!(matches!(
+                decoder.skip(),
+                Err(Error::LimitExceeded {
+                    kind: LimitKind::Depth,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn events() {
+        use Event::*;
+
+        let data: &[(&str, &[Event<'_>])] = &[
+            ("00", &[Positive(0)]),
+            ("3903e7", &[Negative(999)]),
+            ("f93e00", &[Float(1.5)]),
+            ("f7", &[Simple(simple::UNDEFINED)]),
+            ("40", &[StartBytes(Some(0)), EndBytes]),
+            (
+                "4401020304",
+                &[StartBytes(Some(4)), Bytes(&[1, 2, 3, 4]), EndBytes],
+            ),
+            ("6449455446", &[StartText(Some(4)), Text("IETF"), EndText]),
+            ("c11a514b67b0", &[Tag(1), Positive(1363896240)]),
+            ("80", &[StartArray(Some(0)), EndArray]),
+            ("a0", &[StartMap(Some(0)), EndMap]),
+            // [1, [2, 3], [4, 5]]
+            (
+                "8301820203820405",
+                &[
+                    StartArray(Some(3)),
+                    Positive(1),
+                    StartArray(Some(2)),
+                    Positive(2),
+                    Positive(3),
+                    EndArray,
+                    StartArray(Some(2)),
+                    Positive(4),
+                    Positive(5),
+                    EndArray,
+                    EndArray,
+                ],
+            ),
+            // {"a": 1, "b": [2, 3]}
+            (
+                "a26161016162820203",
+                &[
+                    StartMap(Some(2)),
+                    Key,
+                    StartText(Some(1)),
+                    Text("a"),
+                    EndText,
+                    Value,
+                    Positive(1),
+                    Key,
+                    StartText(Some(1)),
+                    Text("b"),
+                    EndText,
+                    Value,
+                    StartArray(Some(2)),
+                    Positive(2),
+                    Positive(3),
+                    EndArray,
+                    EndMap,
+                ],
+            ),
+            // ["a", {"b": "c"}]
+            (
+                "826161a161626163",
+                &[
+                    StartArray(Some(2)),
+                    StartText(Some(1)),
+                    Text("a"),
+                    EndText,
+                    StartMap(Some(1)),
+                    Key,
+                    StartText(Some(1)),
+                    Text("b"),
+                    EndText,
+                    Value,
+                    StartText(Some(1)),
+                    Text("c"),
+                    EndText,
+                    EndMap,
+                    EndArray,
+                ],
+            ),
+            // (_ h'0102', h'030405')
+            (
+                "5f42010243030405ff",
+                &[
+                    StartBytes(None),
+                    Bytes(&[1, 2]),
+                    Bytes(&[3, 4, 5]),
+                    EndBytes,
+                ],
+            ),
+            // (_ "strea", "ming")
+            (
+                "7f657374726561646d696e67ff",
+                &[StartText(None), Text("strea"), Text("ming"), EndText],
+            ),
+            // [_ 1, [2, 3], [_ 4, 5]]
+            (
+                "9f018202039f0405ffff",
+                &[
+                    StartArray(None),
+                    Positive(1),
+                    StartArray(Some(2)),
+                    Positive(2),
+                    Positive(3),
+                    EndArray,
+                    StartArray(None),
+                    Positive(4),
+                    Positive(5),
+                    EndArray,
+                    EndArray,
+                ],
+            ),
+            // {_ "a": 1, "b": [_ ]}
+            (
+                "bf61610161629fffff",
+                &[
+                    StartMap(None),
+                    Key,
+                    StartText(Some(1)),
+                    Text("a"),
+                    EndText,
+                    Value,
+                    Positive(1),
+                    Key,
+                    StartText(Some(1)),
+                    Text("b"),
+                    EndText,
+                    Value,
+                    StartArray(None),
+                    EndArray,
+                    EndMap,
+                ],
+            ),
+            // {1: 32("x")}, with the tag after the value marker
+            (
+                "a101d8206178",
+                &[
+                    StartMap(Some(1)),
+                    Key,
+                    Positive(1),
+                    Value,
+                    Tag(32),
+                    StartText(Some(1)),
+                    Text("x"),
+                    EndText,
+                    EndMap,
+                ],
+            ),
+            // A string longer than the buffer, with a character across parts
+            (
+                "6a6162636465666768c3a9",
+                &[
+                    StartText(Some(10)),
+                    Text("abcdefgh"),
+                    Text("\u{e9}"),
+                    EndText,
+                ],
+            ),
+        ];
+
+        for (bytes, expected) in data {
+            let bytes = hex::decode(bytes).unwrap();
+            let mut buffer = [0u8; 9];
+            let mut events = Events::new(Decoder::from(&bytes[..]), &mut buffer);
+
+            for event in expected.iter() {
+                assert_eq!(events.pull().unwrap(), Some(*event), "{:x?}", bytes);
+            }
+
+            assert_eq!(events.pull().unwrap(), None, "{:x?}", bytes);
+            assert_eq!(events.offset(), bytes.len());
+        }
+
+        // Only the first of several items is read.
+        let bytes = hex::decode("0102").unwrap();
+        let mut buffer = [0u8; 4];
+        let mut events = Events::new(Decoder::from(&bytes[..]), &mut buffer);
+        assert_eq!(events.pull().unwrap(), Some(Positive(1)));
+        assert_eq!(events.pull().unwrap(), None);
+        assert_eq!(events.into_inner().pull().unwrap(), Header::Positive(2));
+
+        fn underflow(offset: usize, declared: usize, actual: usize) -> Error<()> {
+            Error::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            }
+        }
+
+        let errors: &[(&str, Error<()>)] = &[
+            // Breaks which do not end an indefinite-length item
+            ("ff", Error::UnexpectedBreak { offset: 0 }),
+            ("8201ff", Error::UnexpectedBreak { offset: 2 }),
+            ("bf01ff", Error::UnexpectedBreak { offset: 2 }),
+            ("9fc1ff", Error::UnexpectedBreak { offset: 2 }),
+            // An item instead of a chunk, including strings of the wrong
+            // type or of indefinite length
+            (
+                "827f616101",
+                Error::MissingBreak {
+                    container_offset: 1,
+                },
+            ),
+            (
+                "5f6161ff",
+                Error::MissingBreak {
+                    container_offset: 0,
+                },
+            ),
+            (
+                "7f7f6161ffff",
+                Error::MissingBreak {
+                    container_offset: 0,
+                },
+            ),
+            // Definite-length containers cut short by an outer break
+            ("9f8201ff", underflow(1, 2, 1)),
+            ("9fa30102ff", underflow(1, 3, 1)),
+            ("9fa101ff", underflow(1, 1, 0)),
+            ("bf6161a20102ff", underflow(3, 2, 1)),
+            ("bf0182830102ff", underflow(3, 3, 2)),
+            ("9f9f8300ffff", underflow(2, 3, 1)),
+            // A tag without an item does not cut a container short
+            ("9f81c1ff", Error::UnexpectedBreak { offset: 3 }),
+        ];
+
+        for (bytes, expected) in errors {
+            let bytes = hex::decode(bytes).unwrap();
+            let mut buffer = [0u8; 4];
+            let mut events = Events::new(Decoder::from(&bytes[..]), &mut buffer);
+
+            let error = loop {
+                match events.pull() {
+                    Ok(Some(..)) => continue,
+                    Ok(None) => panic!("expected an error for {:x?}", bytes),
+                    Err(e) => break e,
+                }
+            };
+
+            match (error, expected) {
+                (Error::Syntax(x), Error::Syntax(y)) => assert_eq!(x, *y, "{:x?}", bytes),
+                (Error::UnexpectedBreak { offset: x }, Error::UnexpectedBreak { offset: y }) => {
+                    assert_eq!(x, *y, "{:x?}", bytes)
+                }
+                (
+                    Error::MissingBreak {
+                        container_offset: x,
+                    },
+                    Error::MissingBreak {
+                        container_offset: y,
+                    },
+                ) => assert_eq!(x, *y, "{:x?}", bytes),
+                (
+                    Error::ContainerUnderflow {
+                        offset,
+                        declared,
+                        actual,
+                    },
+                    Error::ContainerUnderflow {
+                        offset: o,
+                        declared: d,
+                        actual: a,
+                    },
+                ) => assert_eq!((offset, declared, actual), (*o, *d, *a), "{:x?}", bytes),
+                (e, ..) => panic!("unexpected error for {:x?}: {:?}", bytes, e),
+            }
+        }
+
+        // Invalid UTF-8, and a character split between chunks
+        for (bytes, chunk, offset) in [("61ff", 0, 0), ("7f61c361a9ff", 0, 1)] {
+            let bytes = hex::decode(bytes).unwrap();
+            let mut buffer = [0u8; 4];
+            let mut events = Events::new(Decoder::from(&bytes[..]), &mut buffer);
+
+            let error = loop {
+                match events.pull() {
+                    Ok(Some(..)) => continue,
+                    Ok(None) => panic!("expected an error for {:x?}", bytes),
+                    Err(e) => break e,
+                }
+            };
+
+            match error {
+                Error::InvalidUtf8 {
+                    chunk: c,
+                    offset: o,
+                } => assert_eq!((c, o), (chunk, offset)),
+                e => panic!("unexpected error for {:x?}: {:?}", bytes, e),
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn visitor() {
+        use core::ops::ControlFlow;
+
+        /// A document whose last value is a very long bytes item
+        struct Synthetic<'a> {
+            prefix: &'a [u8],
+            len: usize,
+            position: &'a mut usize,
+        }
+
+        impl ciborium_io::Read for Synthetic<'_> {
+            type Error = ();
+
+            fn read_exact(&mut self, data: &mut [u8]) -> Result<(), ()> {
+                if *self.position + data.len() > self.len {
+                    return Err(());
+                }
+
+                for byte in data.iter_mut() {
+                    *byte = self.prefix.get(*self.position).copied().unwrap_or(0);
+                    *self.position += 1;
+                }
+
+                Ok(())
+            }
+        }
+
+        /// Finds the integer value of a top-level key
+        struct Find {
+            key: &'static str,
+            depth: usize,
+            matched: Option<usize>,
+            in_key: bool,
+            wanted: bool,
+            found: Option<i128>,
+        }
+
+        impl Visitor for Find {
+            fn on_int(&mut self, value: i128) -> ControlFlow<()> {
+                match self.wanted && self.depth == 1 {
+                    true => {
+                        self.found = Some(value);
+                        ControlFlow::Break(())
+                    }
+
+                    false => ControlFlow::Continue(()),
+                }
+            }
+
+            fn on_text_chunk(&mut self, chunk: &str) -> ControlFlow<()> {
+                if self.in_key && self.depth == 1 {
+                    self.matched = self.matched.and_then(|n| {
+                        let rest = self.key.get(n..)?;
+                        rest.starts_with(chunk).then(|| n + chunk.len())
+                    });
+                }
+
+                ControlFlow::Continue(())
+            }
+
+            fn on_array_start(&mut self, _: Option<usize>) -> ControlFlow<()> {
+                self.depth += 1;
+                ControlFlow::Continue(())
+            }
+
+            fn on_array_end(&mut self) -> ControlFlow<()> {
+                self.depth -= 1;
+                ControlFlow::Continue(())
+            }
+
+            fn on_map_start(&mut self, _: Option<usize>) -> ControlFlow<()> {
+                self.depth += 1;
+                ControlFlow::Continue(())
+            }
+
+            fn on_map_end(&mut self) -> ControlFlow<()> {
+                self.depth -= 1;
+                ControlFlow::Continue(())
+            }
+
+            fn on_key(&mut self) -> ControlFlow<()> {
+                if self.depth == 1 {
+                    self.in_key = true;
+                    self.wanted = false;
+                    self.matched = Some(0);
+                }
+
+                ControlFlow::Continue(())
+            }
+
+            fn on_value(&mut self) -> ControlFlow<()> {
+                if self.depth == 1 {
+                    self.in_key = false;
+                    self.wanted = self.matched == Some(self.key.len());
+                }
+
+                ControlFlow::Continue(())
+            }
+        }
+
+        // {"count": 1, "nested": {"target": 0}, "tar": 7, "target": 42,
+        //  "blob": h'00...00'}, where the blob is 100 MiB long.
+        let prefix = hex::decode(concat!(
+            "a5",
+            "65636f756e7401",
+            "666e6573746564a166746172676574",
+            "00",
+            "6374617207",
+            "66746172676574182a",
+            "64626c6f62",
+            "5a06400000",
+        ))
+        .unwrap();
+
+        let blob = 100 * 1024 * 1024;
+        let len = prefix.len() + blob;
+
+        let mut find = Find {
+            key: "target",
+            depth: 0,
+            matched: None,
+            in_key: false,
+            wanted: false,
+            found: None,
+        };
+
+        let mut position = 0;
+        let reader = Synthetic {
+            prefix: &prefix,
+            len,
+            position: &mut position,
+        };
+
+        let flow = decode_with(reader, &mut find).unwrap();
+        assert!(flow.is_break());
+        assert_eq!(find.found, Some(42));
+
+        // Nothing after the value was read.
+        assert_eq!(position, prefix.len() - 10);
+
+        // Without the key, the whole document is read.
+        find.key = "missing";
+        find.depth = 0;
+        find.found = None;
+
+        let mut position = 0;
+        let reader = Synthetic {
+            prefix: &prefix,
+            len,
+            position: &mut position,
+        };
+
+        let flow = decode_with(reader, &mut find).unwrap();
+        assert!(flow.is_continue());
+        assert_eq!(find.found, None);
+        assert_eq!(position, len);
+    }
+
+    #[test]
+    fn half_to_f64() {
+        for bits in 0..=u16::MAX {
+            let negative = bits & 0x8000 != 0;
+            let exp = i32::from(bits >> 10 & 0x1f);
+            let man = bits & 0x3ff;
+
+            let value = half::to_f64(bits);
+            assert_eq!(value.is_sign_negative(), negative, "{:04x}", bits);
+
+            match (exp, man) {
+                (0x1f, 0) => assert!(value.is_infinite(), "{:04x}", bits),
+
+                // The payload is kept in the top bits, quiet or not.
+                (0x1f, _) => {
+                    assert!(value.is_nan(), "{:04x}", bits);
+                    let payload = value.to_bits() & ((1 << 52) - 1);
+                    assert_eq!(payload, u64::from(man) << 42, "{:04x}", bits);
+                }
+
+                // Compare with the definition, using exact powers of two.
+                _ => {
+                    let (exp, significand) = match exp {
+                        0 => (-24, f64::from(man)),
+                        _ => (exp - 25, f64::from(man | 0x400)),
+                    };
+
+                    let scale = f64::from_bits(((1023 + exp) as u64) << 52);
+                    assert_eq!(value.abs(), significand * scale, "{:04x}", bits);
+                }
+            }
+        }
+
+        assert_eq!(half::to_f64(0x0001), 5.960464477539063e-8);
+        assert_eq!(half::to_f64(0x03ff), 0.00006097555160522461);
+        assert_eq!(half::to_f64(0x0400), 0.00006103515625);
+        assert_eq!(half::to_f64(0x7bff), 65504.0);
+        assert_eq!(half::to_f64(0xc400), -4.0);
+        assert_eq!(half::to_f64(0x8000).to_bits(), (-0.0f64).to_bits());
+        assert_eq!(half::to_f64(0x7e00).to_bits(), f64::NAN.to_bits());
+        assert_eq!(half::to_f64(0x7c01).to_bits(), 0x7ff0_0400_0000_0000);
+    }
+
+    #[test]
+    fn half_from_f64() {
+        // Every half float round-trips, bit for bit.
+        for bits in 0..=u16::MAX {
+            let value = half::to_f64(bits);
+            assert_eq!(half::from_f64(value), Some(bits), "{:04x}", bits);
+        }
+
+        let data: &[(f64, Option<u16>)] = &[
+            (0.0, Some(0x0000)),
+            (-0.0, Some(0x8000)),
+            (1.0, Some(0x3c00)),
+            (65504.0, Some(0x7bff)),
+            (f64::INFINITY, Some(0x7c00)),
+            (f64::NEG_INFINITY, Some(0xfc00)),
+            (f64::NAN, Some(0x7e00)),
+            (-f64::NAN, Some(0xfe00)),
+            (f64::from_bits(0x7ff0_0400_0000_0000), Some(0x7c01)),
+            (5.960464477539063e-8, Some(0x0001)),
+            // Rounded, too large, too small or with a longer payload
+            (1.1, None),
+            (65505.0, None),
+            (65520.0, None),
+            (f64::MAX, None),
+            (f64::from_bits(0x3e60_0000_0000_0000), None),
+            (5.960464477539063e-8 * 1.5, None),
+            (f64::from_bits(1), None),
+            (f64::MIN_POSITIVE, None),
+            (f64::from_bits(0x7ff0_0000_0000_0001), None),
+            (f64::from_bits(0x7ff8_0000_0000_0001), None),
+        ];
+
+        for (value, bits) in data.iter().cloned() {
+            assert_eq!(half::from_f64(value), bits, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn placeholder() {
+        /// A writer which can overwrite the bytes of a buffer
+        struct Patch<'a> {
+            buffer: &'a mut [u8],
+            position: usize,
+        }
+
+        impl ciborium_io::Write for Patch<'_> {
+            type Error = ();
+
+            fn write_all(&mut self, data: &[u8]) -> Result<(), ()> {
+                let end = self.position + data.len();
+                self.buffer
+                    .get_mut(self.position..end)
+                    .ok_or(())?
+                    .copy_from_slice(data);
+                self.position = end;
+                Ok(())
+            }
+
+            fn flush(&mut self) -> Result<(), ()> {
+                Ok(())
+            }
+        }
+
+        impl ciborium_io::Seek for Patch<'_> {
+            fn position(&mut self) -> Result<u64, ()> {
+                Ok(self.position as u64)
+            }
+
+            fn seek_to(&mut self, position: u64) -> Result<(), ()> {
+                self.position = position as usize;
+                Ok(())
+            }
+        }
+
+        /// Writes [1, {2: [], 3: 4}, "a"], filling in the lengths later
+        fn patched<W: ciborium_io::Seek>(encoder: &mut Encoder<W>) -> Result<(), W::Error> {
+            let array = encoder.array_placeholder()?;
+            encoder.push(Header::Positive(1))?;
+
+            let map = encoder.map_placeholder()?;
+            encoder.push(Header::Positive(2))?;
+            let empty = encoder.array_placeholder()?;
+            encoder.finalize_placeholder(empty, 0)?;
+            encoder.push(Header::Positive(3))?;
+            encoder.push(Header::Positive(4))?;
+            encoder.finalize_placeholder(map, 2)?;
+
+            encoder.text("a", None)?;
+            encoder.finalize_placeholder(array, 3)
+        }
+
+        // The same items, as their headers
+        let headers = [
+            Header::Array(Some(3)),
+            Header::Positive(1),
+            Header::Map(Some(2)),
+            Header::Positive(2),
+            Header::Array(Some(0)),
+            Header::Positive(3),
+            Header::Positive(4),
+            Header::Text(Some(1)),
+        ];
+
+        let mut buffer = [0u8; 64];
+        let mut encoder = Encoder::from(Patch {
+            buffer: &mut buffer,
+            position: 0,
+        });
+
+        patched(&mut encoder).unwrap();
+        encoder.push(Header::Positive(5)).unwrap();
+
+        let mut decoder = Decoder::from(&buffer[..]);
+        for header in headers {
+            assert_eq!(decoder.pull().unwrap(), header);
+        }
+
+        let mut text = [0u8; 1];
+        ciborium_io::Read::read_exact(&mut decoder, &mut text).unwrap();
+        assert_eq!(&text, b"a");
+        assert_eq!(decoder.pull().unwrap(), Header::Positive(5));
+
+        // Each placeholder takes 9 bytes, with the length as 8 bytes.
+        assert_eq!(decoder.offset(), 3 * 9 + 7);
+        assert_eq!(buffer[..9], [0x9b, 0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(buffer[10..19], [0xbb, 0, 0, 0, 0, 0, 0, 0, 2]);
+
+        // The placeholder must fit in the output.
+        let mut buffer = [0u8; 4];
+        let mut encoder = Encoder::from(Patch {
+            buffer: &mut buffer,
+            position: 0,
+        });
+        encoder.array_placeholder().unwrap_err();
+
+        #[cfg(feature = "std")]
+        {
+            let mut cursor = std::io::Cursor::new(std::vec::Vec::new());
+            patched(&mut Encoder::from(&mut cursor)).unwrap();
+
+            let bytes = cursor.into_inner();
+            assert_eq!(bytes.len(), 3 * 9 + 6);
+
+            let mut decoder = Decoder::from(&bytes[..]);
+            for header in headers {
+                assert_eq!(decoder.pull().unwrap(), header);
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_writers() {
+        use ciborium_io::Write as _;
+
+        let mut output = [0u8; 64];
+        let mut writer = &mut output[..];
+        let mut encoder = Encoder::from(&mut writer);
+
+        let mut buffer = [0u8; 4];
+        let mut bytes = encoder.bytes_writer(&mut buffer).unwrap();
+        bytes.write_all(&[1, 2, 3]).unwrap();
+        bytes.write_all(&[4, 5, 6, 7, 8, 9, 10, 11, 12]).unwrap();
+        bytes.write_all(&[]).unwrap();
+        bytes.write_all(&[13]).unwrap();
+        bytes.end().unwrap();
+
+        // Dropping the writer ends the item, too.
+        let mut bytes = encoder.bytes_writer(&mut buffer).unwrap();
+        bytes.write_all(&[14, 15]).unwrap();
+        drop(bytes);
+
+        let len = 64 - writer.len();
+        let expected = hex::decode(concat!(
+            "5f",
+            "4401020304",
+            "4405060708",
+            "44090a0b0c",
+            "410d",
+            "ff",
+            "5f420e0fff",
+        ))
+        .unwrap();
+        assert_eq!(&output[..len], &expected[..]);
+
+        // Characters are never split between chunks.
+        let mut output = [0u8; 64];
+        let mut writer = &mut output[..];
+        let mut encoder = Encoder::from(&mut writer);
+
+        let mut buffer = [0u8; 5];
+        let mut text = encoder.text_writer(&mut buffer).unwrap();
+        text.write_str("a\u{e9}").unwrap();
+        text.write_str("\u{20ac}x").unwrap();
+        text.write_str("").unwrap();
+        text.write_str("yz\u{1f600}\u{1f600}").unwrap();
+        text.flush().unwrap();
+        text.write_str("!").unwrap();
+        text.end().unwrap();
+
+        let len = 64 - writer.len();
+        let expected = hex::decode(concat!(
+            "7f",
+            "6361c3a9",
+            "65e282ac7879",
+            "657af09f9880",
+            "64f09f9880",
+            "6121",
+            "ff",
+        ))
+        .unwrap();
+        assert_eq!(&output[..len], &expected[..]);
+
+        // The chunks are read back as one string.
+        let mut decoder = Decoder::from(&output[..len]);
+        assert_eq!(decoder.pull().unwrap(), Header::Text(None));
+
+        let mut segments = decoder.text(None);
+        let mut joined = [0u8; 32];
+        let mut total = 0;
+        while let Some(mut segment) = segments.pull().unwrap() {
+            let mut scratch = [0u8; 8];
+            while let Some(chunk) = segment.pull(&mut scratch).unwrap() {
+                joined[total..][..chunk.len()].copy_from_slice(chunk.as_bytes());
+                total += chunk.len();
+            }
+        }
+
+        let text = "a\u{e9}\u{20ac}xyz\u{1f600}\u{1f600}!";
+        assert_eq!(&joined[..total], text.as_bytes());
+    }
+
+    #[test]
+    fn segment_read() {
+        // (_ "aé", "€xyz"), where the second chunk is longer than the buffer
+        let bytes = hex::decode("7f6361c3a966e282ac78797aff").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert_eq!(decoder.pull().unwrap(), Header::Text(None));
+
+        let mut output = [0u8; 16];
+        let mut len = 0;
+        let mut reads = 0;
+
+        let mut buffer = [0u8; 4];
+        let mut segments = decoder.text(None);
+        while let Some(mut segment) = segments.pull().unwrap() {
+            loop {
+                let n = segment.read(&mut buffer).unwrap();
+                if n == 0 {
+                    break;
+                }
+
+                assert!(core::str::from_utf8(&buffer[..n]).is_ok());
+                output[len..len + n].copy_from_slice(&buffer[..n]);
+                len += n;
+                reads += 1;
+            }
+        }
+
+        assert_eq!(core::str::from_utf8(&output[..len]).unwrap(), "aé€xyz");
+        assert_eq!((reads, decoder.offset()), (3, bytes.len()));
+
+        // A byte string of any length, one byte at a time
+        let mut bytes = [7u8; 1003];
+        bytes[..3].copy_from_slice(&[0x59, 0x03, 0xe8]);
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert_eq!(decoder.pull().unwrap(), Header::Bytes(Some(1000)));
+
+        let mut count = 0;
+        let mut segments = decoder.bytes(Some(1000));
+        while let Some(mut segment) = segments.pull().unwrap() {
+            while segment.read(&mut buffer[..1]).unwrap() == 1 {
+                assert_eq!(buffer[0], 7);
+                count += 1;
+            }
+        }
+
+        assert_eq!(count, 1000);
+
+        // A truncated character is an error.
+        let bytes = hex::decode("61c3").unwrap();
+        let mut decoder = Decoder::from(&bytes[..]);
+        assert_eq!(decoder.pull().unwrap(), Header::Text(Some(1)));
+        let mut segments = decoder.text(Some(1));
+        let mut segment = segments.pull().unwrap().unwrap();
+        assert!(matches!(
+            segment.read(&mut buffer),
+            Err(Error::InvalidUtf8 {
+                chunk: 0,
+                offset: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn text_chunks() {
+        // Characters of every width, each split at every byte into two
+        // chunks after "ab": (_ "ab" + head, tail)
+        for c in ["\u{e9}", "\u{20ac}", "\u{1d11e}"] {
+            for split in 0..=c.len() {
+                let (head, tail) = c.as_bytes().split_at(split);
+                let mut bytes = [0u8; 10];
+                let len = c.len() + 6;
+                bytes[0] = 0x7f;
+                bytes[1] = 0x62 + head.len() as u8;
+                bytes[2..4].copy_from_slice(b"ab");
+                bytes[4..][..head.len()].copy_from_slice(head);
+                bytes[4 + head.len()] = 0x60 + tail.len() as u8;
+                bytes[5 + head.len()..][..tail.len()].copy_from_slice(tail);
+                bytes[len - 1] = 0xff;
+                let bytes = &bytes[..len];
+
+                // Only a split at either end of the character is allowed.
+                let whole = split == 0 || split == c.len();
+
+                // The buffer also splits the first chunk within the character.
+                let mut decoder = Decoder::from(bytes);
+                assert_eq!(decoder.pull().unwrap(), Header::Text(None));
+
+                let mut output = [0u8; 8];
+                let mut out = 0;
+                let mut buffer = [0u8; 4];
+                let mut segments = decoder.text(None);
+
+                type E = Error<<&'static [u8] as ciborium_io::Read>::Error>;
+                let result = (|| -> Result<(), E> {
+                    while let Some(mut segment) = segments.pull()? {
+                        loop {
+                            let n = match segment.read(&mut buffer)? {
+                                0 => break,
+                                n => n,
+                            };
+
+                            assert!(core::str::from_utf8(&buffer[..n]).is_ok());
+                            output[out..][..n].copy_from_slice(&buffer[..n]);
+                            out += n;
+                        }
+                    }
+
+                    Ok(())
+                })();
+
+                match result {
+                    Ok(()) => {
+                        assert!(whole, "{:x?}", bytes);
+                        assert_eq!(&output[..2], b"ab");
+                        assert_eq!(&output[2..out], c.as_bytes());
+                    }
+
+                    Err(Error::InvalidUtf8 { chunk, offset }) => {
+                        assert!(!whole, "{:x?}", bytes);
+                        assert_eq!((chunk, offset), (0, 1));
+                    }
+
+                    Err(e) => panic!("unexpected error for {:x?}: {:?}", bytes, e),
+                }
+
+                // The same is true of validation, which never buffers.
+                match super::validate(bytes, Limits::new()) {
+                    Ok(()) => assert!(whole, "{:x?}", bytes),
+                    Err(Error::InvalidUtf8 { chunk, offset }) => {
+                        assert!(!whole, "{:x?}", bytes);
+                        assert_eq!((chunk, offset), (0, 1));
+                    }
+                    Err(e) => panic!("unexpected error for {:x?}: {:?}", bytes, e),
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use super::*;
+
+use alloc::vec::Vec;
+
+/// What a header does to the nesting of the items around it
+enum Step {
+    /// Opens a level with this many items, or `None` until a break
+    Open(Option<usize>),
+
+    /// Completes an item in the current level
+    Complete,
+
+    /// Closes the current, indefinite-length level with a break
+    Close,
+
+    /// Starts an indefinite-length string, whose chunks end with a break
+    String,
+
+    /// Has no effect, like a chunk of a string
+    None,
+}
+
+/// Tracks the containers and tags which are open in the input
+///
+/// The effect of the last header pulled is kept pending until the next
+/// pull, so that the header can still be pushed back without having been
+/// counted.
+pub(crate) struct Nesting {
+    max: usize,
+    levels: Vec<Option<usize>>,
+    string: bool,
+    pending: Option<Header>,
+}
+
+/// The number of levels closed when an item completes in the last level
+fn closed(levels: &[Option<usize>]) -> usize {
+    levels.iter().rev().take_while(|x| **x == Some(1)).count()
+}
+
+impl Nesting {
+    #[inline]
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            max,
+            levels: Vec::new(),
+            string: false,
+            pending: None,
+        }
+    }
+
+    fn step(&self, header: Header) -> Step {
+        match header {
+            Header::Break if self.string => Step::Complete,
+            _ if self.string => Step::None,
+
+            Header::Break => match self.levels.last() {
+                Some(None) => Step::Close,
+                _ => Step::None,
+            },
+
+            Header::Array(Some(0)) | Header::Map(Some(0)) => Step::Complete,
+            Header::Array(len) => Step::Open(len),
+            Header::Map(len) => Step::Open(len.map(|x| x.saturating_mul(2))),
+            Header::Tag(..) => Step::Open(Some(1)),
+            Header::Bytes(None) | Header::Text(None) => Step::String,
+            _ => Step::Complete,
+        }
+    }
+
+    /// Applies the effect of the pending header, if any
+    fn settle(&mut self) {
+        let header = match self.pending.take() {
+            Some(header) => header,
+            None => return,
+        };
+
+        match self.step(header) {
+            Step::Open(len) => self.levels.push(len),
+            Step::String => self.string = true,
+            Step::None => (),
+
+            Step::Close => {
+                self.levels.pop();
+                self.complete();
+            }
+
+            Step::Complete => {
+                self.string = false;
+                self.complete();
+            }
+        }
+    }
+
+    fn complete(&mut self) {
+        while let Some(level) = self.levels.last_mut() {
+            match level {
+                Some(1) => {
+                    self.levels.pop();
+                }
+
+                Some(n) => {
+                    *n -= 1;
+                    return;
+                }
+
+                None => return,
+            }
+        }
+    }
+
+    /// Counts a header which was just pulled at `offset`
+    ///
+    /// Fails if the header would open more levels than the limit.
+    pub(crate) fn pull<T>(&mut self, header: Header, offset: usize) -> Result<(), Error<T>> {
+        self.settle();
+
+        if let Step::Open(..) = self.step(header) {
+            if self.levels.len() >= self.max {
+                return Err(Error::limit(LimitKind::Depth, self.max, offset));
+            }
+        }
+
+        self.pending = Some(header);
+        Ok(())
+    }
+
+    /// Forgets the last header pulled, which was pushed back
+    #[inline]
+    pub(crate) fn push(&mut self) {
+        self.pending = None;
+    }
+
+    /// The number of open levels, including the effect of the last header
+    pub(crate) fn depth(&self) -> usize {
+        let len = self.levels.len();
+
+        let header = match self.pending {
+            Some(header) => header,
+            None => return len,
+        };
+
+        match self.step(header) {
+            Step::Open(..) => len + 1,
+            Step::Complete => len - closed(&self.levels),
+            Step::Close => len - 1 - closed(&self.levels[..len - 1]),
+            Step::String | Step::None => len,
+        }
+    }
+}
@@ -56,8 +56,9 @@ impl Parser for Bytes {
 /// A text parser
 ///
 /// This parser converts the input bytes to a `str`. This parser preserves
-/// trailing invalid UTF-8 sequences in the case that chunking fell in the
-/// middle of a valid UTF-8 character.
+/// the start of a character which is cut off at the end of the input, in
+/// the case that buffering fell in the middle of a valid UTF-8 character,
+/// so that text is validated incrementally without being concatenated.
 #[derive(Default)]
 pub struct Text {
     stored: usize,
@@ -78,14 +79,17 @@ impl Parser for Text {
         bytes[..self.stored].clone_from_slice(&self.buffer[..self.stored]);
 
         Ok(match core::str::from_utf8(bytes) {
-            Ok(s) => s,
+            Ok(s) => {
+                self.stored = 0;
+                s
+            }
             Err(e) => {
                 let valid_len = e.valid_up_to();
                 let invalid_len = bytes.len() - valid_len;
 
-                // If the size of the invalid UTF-8 is large enough to hold
-                // all valid UTF-8 characters, we have a syntax error.
-                if invalid_len > self.buffer.len() {
+                // Only a character which is cut off at the end may be kept
+                // for the next read cycle; anything else is invalid.
+                if e.error_len().is_some() {
                     return Err(e);
                 }
 
@@ -112,6 +116,7 @@ pub struct Segment<'r, R: Read, P: Parser> {
     reader: &'r mut Decoder<R>,
     unread: usize,
     offset: usize,
+    index: usize,
     parser: P,
 }
 
@@ -122,9 +127,25 @@ impl<'r, R: Read, P: Parser> Segment<'r, R, P> {
         self.unread + self.parser.saved()
     }
 
+    /// Gets the index of the segment among the chunks of its item
+    ///
+    /// This is zero for an item of definite length.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Gets the offset of the header of the segment
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
     /// Gets the next parsed chunk within the segment
     ///
-    /// Returns `Ok(None)` when all chunks have been read.
+    /// Returns `Ok(None)` when all chunks have been read. Text is validated
+    /// as it is read, and fails with [`Error::InvalidUtf8`] as soon as it is
+    /// invalid, including when the segment ends within a character.
     #[inline]
     pub fn pull<'a>(
         &mut self,
@@ -132,10 +153,15 @@ impl<'r, R: Read, P: Parser> Segment<'r, R, P> {
     ) -> Result<Option<&'a P::Item>, Error<R::Error>> {
         use core::cmp::min;
 
+        let invalid = Error::InvalidUtf8 {
+            chunk: self.index,
+            offset: self.offset,
+        };
+
         let prev = self.parser.saved();
         match self.unread {
             0 if prev == 0 => return Ok(None),
-            0 => return Err(Error::Syntax(self.offset)),
+            0 => return Err(invalid),
             _ => (),
         }
 
@@ -148,10 +174,70 @@ impl<'r, R: Read, P: Parser> Segment<'r, R, P> {
         self.reader.read_exact(next)?;
         self.unread -= next.len();
 
-        self.parser
-            .parse(full)
-            .or(Err(Error::Syntax(self.offset)))
-            .map(Some)
+        let item = self.parser.parse(full).or(Err(invalid))?;
+
+        // A character must not continue in the next segment.
+        if self.unread == 0 && self.parser.saved() > 0 {
+            return Err(Error::InvalidUtf8 {
+                chunk: self.index,
+                offset: self.offset,
+            });
+        }
+
+        Ok(Some(item))
+    }
+}
+
+impl<'r, R: Read, P: Parser> Segment<'r, R, P>
+where
+    P::Item: AsRef<[u8]>,
+{
+    /// Reads the next part of the segment into the buffer
+    ///
+    /// Returns the number of bytes written to the start of the buffer, or
+    /// zero once the segment is exhausted. This can be called repeatedly,
+    /// so a segment of any length can be streamed through a small buffer,
+    /// for example to hash it or to write it somewhere else. For text,
+    /// the bytes are valid UTF-8 and never end within a character, so the
+    /// buffer must be at least 4 bytes long; for bytes, it must not be
+    /// empty.
+    ///
+    /// ```rust
+    /// use ciborium_ll::Decoder;
+    ///
+    /// // h'0102030405'
+    /// let input = [0x45, 1, 2, 3, 4, 5];
+    /// let mut decoder = Decoder::from(&input[..]);
+    /// let header = decoder.pull().unwrap();
+    /// assert_eq!(header, ciborium_ll::Header::Bytes(Some(5)));
+    ///
+    /// let mut sum = 0;
+    /// let mut buffer = [0u8; 2];
+    /// let mut segments = decoder.bytes(Some(5));
+    /// while let Some(mut segment) = segments.pull().unwrap() {
+    ///     loop {
+    ///         match segment.read(&mut buffer).unwrap() {
+    ///             0 => break,
+    ///             n => sum += buffer[..n].iter().map(|x| *x as u32).sum::<u32>(),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(sum, 15);
+    /// ```
+    #[inline]
+    pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error<R::Error>> {
+        loop {
+            // The parser may keep the start of a character for the next
+            // read, in which case nothing is returned yet.
+            let progress = buffer.len() > self.parser.saved();
+
+            return match self.pull(buffer)? {
+                Some(x) if x.as_ref().is_empty() && progress && self.left() > 0 => continue,
+                Some(x) => Ok(x.as_ref().len()),
+                None => Ok(0),
+            };
+        }
     }
 }
 
@@ -163,6 +249,8 @@ pub struct Segments<'r, R: Read, P: Parser> {
     reader: &'r mut Decoder<R>,
     finish: bool,
     nested: usize,
+    start: usize,
+    index: usize,
     parser: PhantomData<P>,
     unwrap: fn(Header) -> Result<Option<usize>, ()>,
 }
@@ -177,6 +265,8 @@ impl<'r, R: Read, P: Parser> Segments<'r, R, P> {
             reader: decoder,
             finish: false,
             nested: 0,
+            start: 0,
+            index: 0,
             parser: PhantomData,
             unwrap,
         }
@@ -186,21 +276,33 @@ impl<'r, R: Read, P: Parser> Segments<'r, R, P> {
     ///
     /// Returns `Ok(None)` at the conclusion of the stream.
     #[inline]
-    pub fn pull(&mut self) -> Result<Option<Segment<R, P>>, Error<R::Error>> {
+    pub fn pull(&mut self) -> Result<Option<Segment<'_, R, P>>, Error<R::Error>> {
         while !self.finish {
             let offset = self.reader.offset();
             match self.reader.pull()? {
                 Header::Break if self.nested == 1 => return Ok(None),
                 Header::Break if self.nested > 1 => self.nested -= 1,
                 header => match (self.unwrap)(header) {
-                    Err(..) => return Err(Error::Syntax(offset)),
-                    Ok(None) => self.nested += 1,
+                    Err(..) => {
+                        return Err(Error::MissingBreak {
+                            container_offset: self.start,
+                        })
+                    }
+                    Ok(None) => {
+                        if self.nested == 0 {
+                            self.start = offset;
+                        }
+
+                        self.nested += 1;
+                    }
                     Ok(Some(len)) => {
                         self.finish = self.nested == 0;
+                        self.index += 1;
                         return Ok(Some(Segment {
                             reader: self.reader,
                             unread: len,
                             offset,
+                            index: self.index - 1,
                             parser: P::default(),
                         }));
                     }
@@ -0,0 +1,380 @@
+use super::*;
+
+use crate::dec::Depth;
+
+use ciborium_io::{BufRead, Read};
+
+use core::cmp::Ordering;
+
+/// Limits on the input accepted by [`validate()`]
+///
+/// ```rust
+/// use ciborium_ll::{validate, Error, Limits};
+///
+/// // [[[]]]
+/// let input = [0x81, 0x81, 0x80];
+///
+/// assert!(validate(&input[..], Limits::new()).is_ok());
+/// assert!(matches!(
+///     validate(&input[..], Limits::new().max_depth(1)),
+///     Err(Error::LimitExceeded { limit: 1, offset: 1, .. })
+/// ));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    max_depth: usize,
+}
+
+impl Default for Limits {
+    #[inline]
+    fn default() -> Self {
+        Self { max_depth: 256 }
+    }
+}
+
+impl Limits {
+    /// Creates the default limits
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many containers may be nested
+    ///
+    /// This counts arrays and maps, like [`Decoder::skip_with_max_depth()`],
+    /// and is 256 by default. Items nested more deeply are rejected with
+    /// [`Error::LimitExceeded`] for [`LimitKind::Depth`].
+    #[inline]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+/// Checks UTF-8 which arrives in pieces
+///
+/// A character may be cut off at the end of one piece and continued in
+/// the next, so its first bytes are kept until it is complete.
+#[derive(Default)]
+struct Utf8 {
+    partial: [u8; 4],
+    len: usize,
+}
+
+impl Utf8 {
+    fn feed(&mut self, mut data: &[u8]) -> Result<(), ()> {
+        if self.len > 0 {
+            let width = match self.partial[0] {
+                0xf0..=0xff => 4,
+                0xe0..=0xef => 3,
+                _ => 2,
+            };
+
+            let n = data.len().min(width - self.len);
+            self.partial[self.len..][..n].copy_from_slice(&data[..n]);
+            self.len += n;
+            data = &data[n..];
+
+            if self.len < width {
+                return Ok(());
+            }
+
+            core::str::from_utf8(&self.partial[..width]).or(Err(()))?;
+            self.len = 0;
+        }
+
+        if let Err(e) = core::str::from_utf8(data) {
+            // Only a character which is cut off at the end may be invalid.
+            if e.error_len().is_some() {
+                return Err(());
+            }
+
+            let rest = &data[e.valid_up_to()..];
+            self.partial[..rest.len()].copy_from_slice(rest);
+            self.len = rest.len();
+        }
+
+        Ok(())
+    }
+
+    /// Fails if the last character was cut off
+    fn finish(&self) -> Result<(), ()> {
+        match self.len {
+            0 => Ok(()),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How the bodies of strings are read
+trait Bodies<R: Read> {
+    /// Reads `len` bytes, passing them to `check` in pieces
+    ///
+    /// If a piece fails the check, this fails with an error for text which
+    /// is not valid UTF-8, in the chunk with this index and offset.
+    fn read(
+        decoder: &mut Decoder<R>,
+        len: usize,
+        chunk: (usize, usize),
+        check: impl FnMut(&[u8]) -> Result<(), ()>,
+    ) -> Result<(), Error<R::Error>>;
+}
+
+/// Reads the bodies of strings through a small buffer on the stack
+struct Copied;
+
+impl<R: Read> Bodies<R> for Copied {
+    fn read(
+        decoder: &mut Decoder<R>,
+        mut len: usize,
+        (chunk, offset): (usize, usize),
+        mut check: impl FnMut(&[u8]) -> Result<(), ()>,
+    ) -> Result<(), Error<R::Error>> {
+        let mut buffer = [0u8; 256];
+
+        while len > 0 {
+            let n = len.min(buffer.len());
+            decoder.read_exact(&mut buffer[..n])?;
+            len -= n;
+            check(&buffer[..n]).or(Err(Error::InvalidUtf8 { chunk, offset }))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the bodies of strings in place, from the buffer of the reader
+struct InPlace;
+
+impl<R: BufRead> Bodies<R> for InPlace {
+    fn read(
+        decoder: &mut Decoder<R>,
+        mut len: usize,
+        (chunk, offset): (usize, usize),
+        mut check: impl FnMut(&[u8]) -> Result<(), ()>,
+    ) -> Result<(), Error<R::Error>> {
+        while len > 0 {
+            let data = decoder.fill_buf()?;
+
+            // At the end of the input, let the reader report the error.
+            if data.is_empty() {
+                return Copied::read(decoder, len, (chunk, offset), check);
+            }
+
+            let n = len.min(data.len());
+            let checked = check(&data[..n]);
+            decoder.consume(n);
+            len -= n;
+            checked.or(Err(Error::InvalidUtf8 { chunk, offset }))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Pulls the header of an item, which must not be a break
+#[inline]
+fn pull_item<R: Read>(decoder: &mut Decoder<R>) -> Result<Header, Error<R::Error>> {
+    let offset = decoder.offset();
+    match decoder.pull_untracked()? {
+        Header::Break => Err(Error::UnexpectedBreak { offset }),
+        header => Ok(header),
+    }
+}
+
+/// Checks the rest of the item whose header was just pulled at `offset`
+fn item<R: Read, B: Bodies<R>>(
+    decoder: &mut Decoder<R>,
+    mut offset: usize,
+    mut header: Header,
+    depth: Depth,
+) -> Result<(), Error<R::Error>> {
+    // Tags can be chained, but each one wraps exactly one item.
+    while let Header::Tag(..) = header {
+        offset = decoder.offset();
+        header = pull_item(decoder)?;
+    }
+
+    let len = match header {
+        Header::Bytes(len) => return string::<R, B>(decoder, offset, header, len),
+        Header::Text(len) => return string::<R, B>(decoder, offset, header, len),
+        Header::Array(len) => len,
+        Header::Map(len) => match len {
+            Some(len) => Some(len.checked_mul(2).ok_or(Error::Syntax(offset))?),
+            None => None,
+        },
+        _ => return Ok(()),
+    };
+
+    let depth = depth.open(offset)?;
+
+    match len {
+        Some(len) => {
+            for _ in 0..len {
+                let offset = decoder.offset();
+                let header = pull_item(decoder)?;
+                item::<R, B>(decoder, offset, header, depth)?;
+            }
+        }
+
+        None => {
+            let map = matches!(header, Header::Map(..));
+            let mut key = true;
+
+            loop {
+                let offset = decoder.offset();
+                match decoder.pull_untracked()? {
+                    // A map must not end after a key.
+                    Header::Break if map && !key => return Err(Error::UnexpectedBreak { offset }),
+                    Header::Break => break,
+                    header => item::<R, B>(decoder, offset, header, depth)?,
+                }
+
+                key = !key;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the body of a string, made of chunks if its length is `None`
+fn string<R: Read, B: Bodies<R>>(
+    decoder: &mut Decoder<R>,
+    start: usize,
+    header: Header,
+    len: Option<usize>,
+) -> Result<(), Error<R::Error>> {
+    let text = matches!(header, Header::Text(..));
+    let chunk = |decoder: &mut Decoder<R>, len, (index, offset)| match text {
+        false => B::read(decoder, len, (index, offset), |_| Ok(())),
+        true => {
+            // Each chunk must be valid UTF-8 on its own.
+            let mut utf8 = Utf8::default();
+            B::read(decoder, len, (index, offset), |data| utf8.feed(data))?;
+            utf8.finish().or(Err(Error::InvalidUtf8 {
+                chunk: index,
+                offset,
+            }))
+        }
+    };
+
+    if let Some(len) = len {
+        return chunk(decoder, len, (0, start));
+    }
+
+    for index in 0.. {
+        let offset = decoder.offset();
+        match (header, decoder.pull_untracked()?) {
+            (_, Header::Break) => return Ok(()),
+            (Header::Bytes(..), Header::Bytes(Some(len))) => chunk(decoder, len, (index, offset))?,
+            (Header::Text(..), Header::Text(Some(len))) => chunk(decoder, len, (index, offset))?,
+            _ => {
+                return Err(Error::MissingBreak {
+                    container_offset: start,
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the reader holds one well-formed data item
+///
+/// The whole item is read and checked without building anything: every
+/// header must be valid, breaks must end an indefinite-length container or
+/// string (and a map only after a value), tags must wrap an item, chunks
+/// must have the type of their string, every body must be complete and
+/// every text string, or each of its chunks, must be valid UTF-8. Syntax
+/// errors report the offset of the offending header, a misplaced break fails
+/// with [`Error::UnexpectedBreak`], an item in place of a chunk fails with
+/// [`Error::MissingBreak`], and text which is not valid UTF-8 fails with
+/// [`Error::InvalidUtf8`] for its string or chunk.
+///
+/// Nothing is allocated; the bodies of strings are read through a small
+/// buffer on the stack and checked piece by piece. Use [`validate_buffered()`]
+/// to avoid even that copy. Nothing after the item is read, so a stream of
+/// items can be checked by calling this repeatedly on the same reader.
+///
+/// ```rust
+/// use ciborium_ll::{validate, Error, Limits};
+///
+/// // ["a", {_ 1: 2}]
+/// let good = [0x82, 0x61, 0x61, 0xbf, 0x01, 0x02, 0xff];
+/// assert!(validate(&good[..], Limits::new()).is_ok());
+///
+/// // ["\xff"]
+/// let bad = [0x81, 0x61, 0xff];
+/// assert!(matches!(
+///     validate(&bad[..], Limits::new()),
+///     Err(Error::InvalidUtf8 { chunk: 0, offset: 1 })
+/// ));
+/// ```
+pub fn validate<R: Read>(reader: R, limits: Limits) -> Result<(), Error<R::Error>> {
+    let mut decoder = Decoder::from(reader);
+    let header = pull_item(&mut decoder)?;
+    item::<R, Copied>(&mut decoder, 0, header, Depth::new(limits.max_depth))
+}
+
+/// Checks that the buffered reader holds one well-formed data item
+///
+/// This is like [`validate()`], but the bodies of strings are checked in
+/// place, in the buffer of the reader, so they are never copied. This is
+/// the fastest way to check a slice, which is a buffered reader itself.
+///
+/// ```rust
+/// use ciborium_ll::{validate_buffered, Limits};
+///
+/// // h'0102'
+/// let input = [0x42, 0x01, 0x02];
+/// assert!(validate_buffered(&input[..], Limits::new()).is_ok());
+/// assert!(validate_buffered(&input[..2], Limits::new()).is_err());
+/// ```
+pub fn validate_buffered<R: BufRead>(reader: R, limits: Limits) -> Result<(), Error<R::Error>> {
+    let mut decoder = Decoder::from(reader);
+    let header = pull_item(&mut decoder)?;
+    item::<R, InPlace>(&mut decoder, 0, header, Depth::new(limits.max_depth))
+}
+
+/// Checks that the slice holds exactly one well-formed data item
+fn exactly_one(bytes: &[u8]) -> Result<(), Error<<&'static [u8] as Read>::Error>> {
+    let mut reader = bytes;
+    validate_buffered(&mut reader, Limits::new())?;
+
+    match reader.len() {
+        0 => Ok(()),
+        n => Err(Error::Syntax(bytes.len() - n)),
+    }
+}
+
+/// Compares two encoded data items in canonical order
+///
+/// This is the order of map keys in deterministic encoding, as described
+/// in RFC 8949 section 4.2.1: the items are compared bytewise, so a shorter
+/// item which is a prefix of a longer one comes first. Unlike comparing
+/// the slices directly, each slice must hold exactly one well-formed data
+/// item, checked as by [`validate()`]: a truncated item fails with an I/O
+/// error, and bytes after the item fail with a syntax error at their
+/// offset. A failure in `a` is reported before any failure in `b`.
+///
+/// ```rust
+/// use ciborium_ll::{compare_canonical, Error};
+/// use core::cmp::Ordering;
+///
+/// // 10 sorts before -1, and "z" before "aa"
+/// assert_eq!(compare_canonical(&[0x0a], &[0x20]).unwrap(), Ordering::Less);
+/// assert_eq!(compare_canonical(b"\x61z", b"\x62aa").unwrap(), Ordering::Less);
+///
+/// // Truncated and trailing bytes are rejected.
+/// assert!(matches!(compare_canonical(&[0x18], &[0x00]), Err(Error::Io(..))));
+/// assert!(matches!(compare_canonical(&[0x00], &[0x00, 0x00]), Err(Error::Syntax(1))));
+/// ```
+pub fn compare_canonical(
+    a: &[u8],
+    b: &[u8],
+) -> Result<Ordering, Error<<&'static [u8] as Read>::Error>> {
+    exactly_one(a)?;
+    exactly_one(b)?;
+    Ok(a.cmp(b))
+}
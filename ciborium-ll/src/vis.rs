@@ -0,0 +1,170 @@
+use super::*;
+
+use ciborium_io::Read;
+use core::ops::ControlFlow;
+
+/// Callbacks for the events of a data item
+///
+/// Each method is called for one [`Event`] and returns whether to go on.
+/// Returning [`ControlFlow::Break`] stops decoding at once, so the rest of
+/// the input is never read. All methods do nothing by default, so only the
+/// interesting ones need to be implemented.
+///
+/// See [`Events::visit()`] and [`decode_with()`].
+#[allow(unused_variables)]
+pub trait Visitor {
+    /// Called for an integer
+    fn on_int(&mut self, value: i128) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for a floating point value
+    fn on_float(&mut self, value: f64) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for a simple value, including booleans, null and undefined
+    fn on_simple(&mut self, value: u8) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for a tag, before the item it applies to
+    fn on_tag(&mut self, tag: u64) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the start of a bytes item
+    fn on_bytes_start(&mut self, len: Option<usize>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for each part of a bytes item
+    fn on_bytes_chunk(&mut self, chunk: &[u8]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the end of a bytes item
+    fn on_bytes_end(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the start of a text item
+    fn on_text_start(&mut self, len: Option<usize>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called for each part of a text item, which never ends within a character
+    fn on_text_chunk(&mut self, chunk: &str) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the end of a text item
+    fn on_text_end(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the start of an array
+    fn on_array_start(&mut self, len: Option<usize>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the end of an array
+    fn on_array_end(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the start of a map, with its length in pairs
+    fn on_map_start(&mut self, len: Option<usize>) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called before the events of each key in a map
+    fn on_key(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called before the events of each value in a map
+    fn on_value(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// Called at the end of a map
+    fn on_map_end(&mut self) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl<R: Read> Events<'_, R> {
+    /// Passes the remaining events of the data item to the visitor
+    ///
+    /// Returns [`ControlFlow::Break`] if the visitor stopped early, in which
+    /// case the events after the one which stopped it can still be pulled.
+    pub fn visit<V: Visitor + ?Sized>(
+        &mut self,
+        visitor: &mut V,
+    ) -> Result<ControlFlow<()>, Error<R::Error>> {
+        while let Some(event) = self.pull()? {
+            let flow = match event {
+                Event::Positive(x) => visitor.on_int(x.into()),
+                Event::Negative(x) => visitor.on_int(x as i128 ^ !0),
+                Event::Float(x) => visitor.on_float(x),
+                Event::Simple(x) => visitor.on_simple(x),
+                Event::Tag(x) => visitor.on_tag(x),
+                Event::StartBytes(len) => visitor.on_bytes_start(len),
+                Event::Bytes(x) => visitor.on_bytes_chunk(x),
+                Event::EndBytes => visitor.on_bytes_end(),
+                Event::StartText(len) => visitor.on_text_start(len),
+                Event::Text(x) => visitor.on_text_chunk(x),
+                Event::EndText => visitor.on_text_end(),
+                Event::StartArray(len) => visitor.on_array_start(len),
+                Event::EndArray => visitor.on_array_end(),
+                Event::StartMap(len) => visitor.on_map_start(len),
+                Event::Key => visitor.on_key(),
+                Event::Value => visitor.on_value(),
+                Event::EndMap => visitor.on_map_end(),
+            };
+
+            if flow.is_break() {
+                return Ok(flow);
+            }
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// Decodes one data item from the reader, passing its events to the visitor
+///
+/// This reads strings in parts of up to 1024 bytes, so memory use does not
+/// depend on the size of the input, apart from the open containers. If the
+/// visitor stops early, nothing more is read. See [`Events`] for the
+/// checks made on the input.
+///
+/// ```rust
+/// use ciborium_ll::{decode_with, Visitor};
+/// use core::ops::ControlFlow;
+///
+/// /// Finds the first integer
+/// struct First(Option<i128>);
+///
+/// impl Visitor for First {
+///     fn on_int(&mut self, value: i128) -> ControlFlow<()> {
+///         self.0 = Some(value);
+///         ControlFlow::Break(())
+///     }
+/// }
+///
+/// // ["a", -7, <garbage>]
+/// let input = [0x83, 0x61, 0x61, 0x26, 0xff];
+/// let mut first = First(None);
+///
+/// assert!(decode_with(&input[..], &mut first).unwrap().is_break());
+/// assert_eq!(first.0, Some(-7));
+/// ```
+pub fn decode_with<R: Read, V: Visitor + ?Sized>(
+    reader: R,
+    visitor: &mut V,
+) -> Result<ControlFlow<()>, Error<R::Error>> {
+    let mut buffer = [0u8; 1024];
+    Events::new(Decoder::from(reader), &mut buffer).visit(visitor)
+}
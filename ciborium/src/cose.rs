@@ -0,0 +1,546 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structural types for COSE messages (RFC 9052)
+//!
+//! These types only describe the layout of COSE messages; they perform no
+//! cryptography. Each message encodes as an array of the right arity,
+//! wrapped in its tag. When reading, the tag is optional, as the
+//! specification allows, but any other tag is rejected. Headers are kept as
+//! encoded byte strings (protected) or maps (unprotected).
+//!
+//! The helpers such as [`CoseSign1::sig_structure()`] build the encoded
+//! structure which is passed to the signing, MAC or encryption algorithm.
+//!
+//! ```rust
+//! use ciborium::{cbor, cose::CoseSign1, de::from_reader, ser::into_writer, value::Value};
+//!
+//! let mut message = CoseSign1 {
+//!     protected: vec![0xa1, 0x01, 0x26], // { 1: -7 }
+//!     unprotected: cbor!({ 4 => Value::Bytes(b"11".to_vec()) }).unwrap(),
+//!     payload: Some(b"hello".to_vec()),
+//!     signature: Vec::new(),
+//! };
+//!
+//! let to_be_signed = message.sig_structure(&[], None).unwrap();
+//! message.signature = vec![0; 64]; // sign(to_be_signed)
+//!
+//! let mut bytes = Vec::new();
+//! into_writer(&message, &mut bytes).unwrap();
+//! assert_eq!(bytes[0], 0xd2); // tag 18
+//!
+//! let decoded: CoseSign1 = from_reader(&bytes[..]).unwrap();
+//! assert_eq!(decoded, message);
+//! ```
+
+use crate::tag::{Bytes, Captured};
+use crate::value::Value;
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Encodes a signature, MAC or encryption structure
+fn structure<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    crate::ser::into_writer(value, &mut bytes).expect("structures contain only bytes and text");
+    bytes
+}
+
+/// The items of a COSE array, taken in order
+struct Fields {
+    name: &'static str,
+    items: vec::IntoIter<Value>,
+}
+
+impl Fields {
+    fn new(name: &'static str, value: Value, min: usize, max: usize) -> Result<Self, String> {
+        match value {
+            Value::Array(x) if (min..=max).contains(&x.len()) => Ok(Self {
+                name,
+                items: x.into_iter(),
+            }),
+
+            _ if min == max => Err(format!("{} must be an array of {} items", name, min)),
+            _ => Err(format!(
+                "{} must be an array of {} to {} items",
+                name, min, max
+            )),
+        }
+    }
+
+    fn bytes(&mut self, field: &str) -> Result<Vec<u8>, String> {
+        match self.items.next() {
+            Some(Value::Bytes(x)) => Ok(x),
+            _ => Err(format!("{} {} must be a byte string", self.name, field)),
+        }
+    }
+
+    fn nullable(&mut self, field: &str) -> Result<Option<Vec<u8>>, String> {
+        match self.items.next() {
+            Some(Value::Bytes(x)) => Ok(Some(x)),
+            Some(Value::Null) => Ok(None),
+            _ => Err(format!(
+                "{} {} must be a byte string or null",
+                self.name, field
+            )),
+        }
+    }
+
+    fn map(&mut self, field: &str) -> Result<Value, String> {
+        match self.items.next() {
+            Some(x @ Value::Map(..)) => Ok(x),
+            _ => Err(format!("{} {} must be a map", self.name, field)),
+        }
+    }
+
+    fn structures<T: Structure>(&mut self, field: &str) -> Result<Vec<T>, String> {
+        match self.items.next() {
+            Some(Value::Array(x)) => x.into_iter().map(T::from_value).collect(),
+            None => Ok(Vec::new()),
+            _ => Err(format!("{} {} must be an array", self.name, field)),
+        }
+    }
+}
+
+/// A COSE structure which is decoded from an array
+trait Structure: Sized {
+    const NAME: &'static str;
+    const TAG: Option<u64>;
+    const MIN: usize;
+    const MAX: usize;
+
+    fn parse(fields: Fields) -> Result<Self, String>;
+
+    fn from_value(value: Value) -> Result<Self, String> {
+        let value = match (value, Self::TAG) {
+            (Value::Tag(t, v), Some(tag)) if t == tag => *v,
+            (Value::Tag(t, ..), Some(tag)) => {
+                return Err(format!(
+                    "expected tag {} ({}), found tag {}",
+                    tag,
+                    Self::NAME,
+                    t
+                ))
+            }
+            (Value::Tag(t, ..), None) => {
+                return Err(format!(
+                    "{} must not be tagged, found tag {}",
+                    Self::NAME,
+                    t
+                ))
+            }
+            (value, ..) => value,
+        };
+
+        Self::parse(Fields::new(Self::NAME, value, Self::MIN, Self::MAX)?)
+    }
+}
+
+fn deserialize<'de, T: Structure, D: de::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let value = match Captured::<Value>::deserialize(deserializer)? {
+        Captured(Some(tag), value) => Value::Tag(tag, value.into()),
+        Captured(None, value) => value,
+    };
+
+    T::from_value(value).map_err(de::Error::custom)
+}
+
+fn serialize<T: Serialize, S: ser::Serializer>(
+    tag: u64,
+    items: T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    Captured(Some(tag), items).serialize(serializer)
+}
+
+macro_rules! structure {
+    ($name:ident, $text:literal, $tag:expr, $min:literal..=$max:literal, $fields:ident => $parse:expr) => {
+        impl Structure for $name {
+            const NAME: &'static str = $text;
+            const TAG: Option<u64> = $tag;
+            const MIN: usize = $min;
+            const MAX: usize = $max;
+
+            fn parse(mut $fields: Fields) -> Result<Self, String> {
+                $parse
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            #[inline]
+            fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// A signed message with a single signer (COSE_Sign1, tag 18)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseSign1 {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The payload, or `None` if the payload is detached
+    pub payload: Option<Vec<u8>>,
+
+    /// The signature
+    pub signature: Vec<u8>,
+}
+
+structure!(CoseSign1, "COSE_Sign1", Some(18), 4..=4, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    payload: f.nullable("payload")?,
+    signature: f.bytes("signature")?,
+}));
+
+impl Serialize for CoseSign1 {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.payload.as_deref().map(Bytes),
+            Bytes(&self.signature),
+        );
+
+        serialize(18, items, serializer)
+    }
+}
+
+impl CoseSign1 {
+    /// Encodes the `Sig_structure` which is signed to produce the signature
+    ///
+    /// A detached payload must be supplied as `detached`. Returns `None`
+    /// if there is no payload.
+    pub fn sig_structure(&self, external_aad: &[u8], detached: Option<&[u8]>) -> Option<Vec<u8>> {
+        let payload = self.payload.as_deref().or(detached)?;
+        Some(structure(&(
+            "Signature1",
+            Bytes(&self.protected),
+            Bytes(external_aad),
+            Bytes(payload),
+        )))
+    }
+}
+
+/// A signature within a [`CoseSign`] message (COSE_Signature)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseSignature {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The signature
+    pub signature: Vec<u8>,
+}
+
+structure!(CoseSignature, "COSE_Signature", None, 3..=3, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    signature: f.bytes("signature")?,
+}));
+
+impl Serialize for CoseSignature {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            Bytes(&self.signature),
+        );
+
+        items.serialize(serializer)
+    }
+}
+
+/// A signed message with any number of signers (COSE_Sign, tag 98)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseSign {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The payload, or `None` if the payload is detached
+    pub payload: Option<Vec<u8>>,
+
+    /// The signatures
+    pub signatures: Vec<CoseSignature>,
+}
+
+structure!(CoseSign, "COSE_Sign", Some(98), 4..=4, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    payload: f.nullable("payload")?,
+    signatures: f.structures("signatures")?,
+}));
+
+impl Serialize for CoseSign {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.payload.as_deref().map(Bytes),
+            &self.signatures,
+        );
+
+        serialize(98, items, serializer)
+    }
+}
+
+impl CoseSign {
+    /// Encodes the `Sig_structure` which is signed to produce `signature`
+    ///
+    /// A detached payload must be supplied as `detached`. Returns `None`
+    /// if there is no payload.
+    pub fn sig_structure(
+        &self,
+        signature: &CoseSignature,
+        external_aad: &[u8],
+        detached: Option<&[u8]>,
+    ) -> Option<Vec<u8>> {
+        let payload = self.payload.as_deref().or(detached)?;
+        Some(structure(&(
+            "Signature",
+            Bytes(&self.protected),
+            Bytes(&signature.protected),
+            Bytes(external_aad),
+            Bytes(payload),
+        )))
+    }
+}
+
+/// A MACed message with implicit keys (COSE_Mac0, tag 17)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseMac0 {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The payload, or `None` if the payload is detached
+    pub payload: Option<Vec<u8>>,
+
+    /// The MAC
+    pub tag: Vec<u8>,
+}
+
+structure!(CoseMac0, "COSE_Mac0", Some(17), 4..=4, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    payload: f.nullable("payload")?,
+    tag: f.bytes("tag")?,
+}));
+
+impl Serialize for CoseMac0 {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.payload.as_deref().map(Bytes),
+            Bytes(&self.tag),
+        );
+
+        serialize(17, items, serializer)
+    }
+}
+
+impl CoseMac0 {
+    /// Encodes the `MAC_structure` which is MACed to produce the tag
+    ///
+    /// A detached payload must be supplied as `detached`. Returns `None`
+    /// if there is no payload.
+    pub fn mac_structure(&self, external_aad: &[u8], detached: Option<&[u8]>) -> Option<Vec<u8>> {
+        let payload = self.payload.as_deref().or(detached)?;
+        Some(structure(&(
+            "MAC0",
+            Bytes(&self.protected),
+            Bytes(external_aad),
+            Bytes(payload),
+        )))
+    }
+}
+
+/// A recipient within a [`CoseEncrypt`] or [`CoseMac`] message (COSE_recipient)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseRecipient {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The encrypted key, or `None` if there is none
+    pub ciphertext: Option<Vec<u8>>,
+
+    /// The nested recipients, which are omitted when empty
+    pub recipients: Vec<CoseRecipient>,
+}
+
+structure!(CoseRecipient, "COSE_recipient", None, 3..=4, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    ciphertext: f.nullable("ciphertext")?,
+    recipients: f.structures("recipients")?,
+}));
+
+impl Serialize for CoseRecipient {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let protected = Bytes(&self.protected);
+        let ciphertext = self.ciphertext.as_deref().map(Bytes);
+
+        match self.recipients.is_empty() {
+            true => (protected, &self.unprotected, ciphertext).serialize(serializer),
+            false => {
+                (protected, &self.unprotected, ciphertext, &self.recipients).serialize(serializer)
+            }
+        }
+    }
+}
+
+/// An encrypted message with implicit keys (COSE_Encrypt0, tag 16)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseEncrypt0 {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The ciphertext, or `None` if the ciphertext is detached
+    pub ciphertext: Option<Vec<u8>>,
+}
+
+structure!(CoseEncrypt0, "COSE_Encrypt0", Some(16), 3..=3, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    ciphertext: f.nullable("ciphertext")?,
+}));
+
+impl Serialize for CoseEncrypt0 {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.ciphertext.as_deref().map(Bytes),
+        );
+
+        serialize(16, items, serializer)
+    }
+}
+
+impl CoseEncrypt0 {
+    /// Encodes the `Enc_structure` which is the additional authenticated data
+    pub fn enc_structure(&self, external_aad: &[u8]) -> Vec<u8> {
+        structure(&("Encrypt0", Bytes(&self.protected), Bytes(external_aad)))
+    }
+}
+
+/// An encrypted message with any number of recipients (COSE_Encrypt, tag 96)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseEncrypt {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The ciphertext, or `None` if the ciphertext is detached
+    pub ciphertext: Option<Vec<u8>>,
+
+    /// The recipients
+    pub recipients: Vec<CoseRecipient>,
+}
+
+structure!(CoseEncrypt, "COSE_Encrypt", Some(96), 4..=4, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    ciphertext: f.nullable("ciphertext")?,
+    recipients: f.structures("recipients")?,
+}));
+
+impl Serialize for CoseEncrypt {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.ciphertext.as_deref().map(Bytes),
+            &self.recipients,
+        );
+
+        serialize(96, items, serializer)
+    }
+}
+
+impl CoseEncrypt {
+    /// Encodes the `Enc_structure` which is the additional authenticated data
+    pub fn enc_structure(&self, external_aad: &[u8]) -> Vec<u8> {
+        structure(&("Encrypt", Bytes(&self.protected), Bytes(external_aad)))
+    }
+}
+
+/// A MACed message with any number of recipients (COSE_Mac, tag 97)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseMac {
+    /// The encoded protected header map
+    pub protected: Vec<u8>,
+
+    /// The unprotected header map
+    pub unprotected: Value,
+
+    /// The payload, or `None` if the payload is detached
+    pub payload: Option<Vec<u8>>,
+
+    /// The MAC
+    pub tag: Vec<u8>,
+
+    /// The recipients
+    pub recipients: Vec<CoseRecipient>,
+}
+
+structure!(CoseMac, "COSE_Mac", Some(97), 5..=5, f => Ok(Self {
+    protected: f.bytes("protected header")?,
+    unprotected: f.map("unprotected header")?,
+    payload: f.nullable("payload")?,
+    tag: f.bytes("tag")?,
+    recipients: f.structures("recipients")?,
+}));
+
+impl Serialize for CoseMac {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let items = (
+            Bytes(&self.protected),
+            &self.unprotected,
+            self.payload.as_deref().map(Bytes),
+            Bytes(&self.tag),
+            &self.recipients,
+        );
+
+        serialize(97, items, serializer)
+    }
+}
+
+impl CoseMac {
+    /// Encodes the `MAC_structure` which is MACed to produce the tag
+    ///
+    /// A detached payload must be supplied as `detached`. Returns `None`
+    /// if there is no payload.
+    pub fn mac_structure(&self, external_aad: &[u8], detached: Option<&[u8]>) -> Option<Vec<u8>> {
+        let payload = self.payload.as_deref().or(detached)?;
+        Some(structure(&(
+            "MAC",
+            Bytes(&self.protected),
+            Bytes(external_aad),
+            Bytes(payload),
+        )))
+    }
+}
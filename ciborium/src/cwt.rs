@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Claims for CBOR Web Tokens (RFC 8392)
+//!
+//! [`Claims`] encodes the claims map of a token, using the registered
+//! integer keys for the well-known claims. The claims are usually the
+//! payload of a COSE message, which is in turn wrapped by [`Token`] to add
+//! the optional tag 61. No signing or verification is performed here.
+//!
+//! ```rust
+//! use ciborium::{cbor, cwt::Claims, tag::Timestamp, value::Value};
+//!
+//! let claims = Claims {
+//!     iss: Some("coap://as.example.com".into()),
+//!     exp: Timestamp::new(1444064944, 0),
+//!     other: vec![(Value::from(-70000), Value::from(true))],
+//!     ..Default::default()
+//! };
+//!
+//! let value = Value::serialized(&claims).unwrap();
+//! assert_eq!(value, cbor!({
+//!     1 => "coap://as.example.com",
+//!     4 => 1444064944,
+//!     -70000 => true,
+//! }).unwrap());
+//!
+//! assert_eq!(value.deserialized::<Claims>().unwrap(), claims);
+//! ```
+
+use crate::tag::{Captured, Required, Timestamp};
+use crate::value::Value;
+
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+
+use serde::{de, ser, ser::SerializeMap as _, Deserialize, Serialize};
+
+/// The claims of a CBOR Web Token
+///
+/// Each well-known claim is written under its registered integer key, and
+/// only when it is present. The numeric dates (`exp`, `nbf` and `iat`) are
+/// written untagged, as an integer when the seconds are whole and as a
+/// float otherwise. Any other claims, including private ones, are kept in
+/// `other` in their original order.
+///
+/// When reading, the claims must be a map. A well-known claim with the
+/// wrong type or appearing more than once is an error.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Claims {
+    /// The issuer (key 1)
+    pub iss: Option<String>,
+
+    /// The subject (key 2)
+    pub sub: Option<String>,
+
+    /// The audience (key 3)
+    pub aud: Option<String>,
+
+    /// The expiration time (key 4)
+    pub exp: Option<Timestamp>,
+
+    /// The time before which the token must not be accepted (key 5)
+    pub nbf: Option<Timestamp>,
+
+    /// The time at which the token was issued (key 6)
+    pub iat: Option<Timestamp>,
+
+    /// The token identifier (key 7)
+    pub cti: Option<Vec<u8>>,
+
+    /// All other claims, keyed by their original keys
+    pub other: Vec<(Value, Value)>,
+}
+
+/// Encodes a numeric date as an integer or a float
+fn numeric_date(time: Timestamp) -> Value {
+    match time.nanos() {
+        0 => time.secs().into(),
+        _ => time.as_secs_f64().into(),
+    }
+}
+
+/// Decodes a numeric date from an integer or a float
+fn timestamp(key: i128, value: Value) -> Result<Timestamp, String> {
+    let time = match value {
+        Value::Integer(x) => i64::try_from(x).ok().and_then(|x| Timestamp::new(x, 0)),
+        Value::Float(x) if f64::from(x).is_finite() => Timestamp::from_secs_f64(x.into()).ok(),
+        _ => return Err(alloc::format!("CWT claim {} must be a number", key)),
+    };
+
+    time.ok_or_else(|| alloc::format!("CWT claim {} out of range", key))
+}
+
+fn text(key: i128, value: Value) -> Result<String, String> {
+    match value {
+        Value::Text(x) => Ok(x),
+        _ => Err(alloc::format!("CWT claim {} must be text", key)),
+    }
+}
+
+impl Serialize for Claims {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let known = [
+            self.iss.is_some(),
+            self.sub.is_some(),
+            self.aud.is_some(),
+            self.exp.is_some(),
+            self.nbf.is_some(),
+            self.iat.is_some(),
+            self.cti.is_some(),
+        ];
+
+        let len = known.iter().filter(|x| **x).count() + self.other.len();
+        let mut map = serializer.serialize_map(Some(len))?;
+
+        if let Some(x) = &self.iss {
+            map.serialize_entry(&1, x)?;
+        }
+
+        if let Some(x) = &self.sub {
+            map.serialize_entry(&2, x)?;
+        }
+
+        if let Some(x) = &self.aud {
+            map.serialize_entry(&3, x)?;
+        }
+
+        if let Some(x) = self.exp {
+            map.serialize_entry(&4, &numeric_date(x))?;
+        }
+
+        if let Some(x) = self.nbf {
+            map.serialize_entry(&5, &numeric_date(x))?;
+        }
+
+        if let Some(x) = self.iat {
+            map.serialize_entry(&6, &numeric_date(x))?;
+        }
+
+        if let Some(x) = &self.cti {
+            map.serialize_entry(&7, &Value::Bytes(x.clone()))?;
+        }
+
+        for (k, v) in &self.other {
+            map.serialize_entry(k, v)?;
+        }
+
+        map.end()
+    }
+}
+
+impl Claims {
+    fn from_value(value: Value) -> Result<Self, String> {
+        let entries = match value {
+            Value::Map(x) => x,
+            _ => return Err("CWT claims must be a map".into()),
+        };
+
+        let mut claims = Self::default();
+
+        for (k, v) in entries {
+            let key = match &k {
+                Value::Integer(x) => i128::from(*x),
+                _ => {
+                    claims.other.push((k, v));
+                    continue;
+                }
+            };
+
+            let duplicate = match key {
+                1 => claims.iss.replace(text(key, v)?).is_some(),
+                2 => claims.sub.replace(text(key, v)?).is_some(),
+                3 => claims.aud.replace(text(key, v)?).is_some(),
+                4 => claims.exp.replace(timestamp(key, v)?).is_some(),
+                5 => claims.nbf.replace(timestamp(key, v)?).is_some(),
+                6 => claims.iat.replace(timestamp(key, v)?).is_some(),
+                7 => match v {
+                    Value::Bytes(x) => claims.cti.replace(x).is_some(),
+                    _ => return Err("CWT claim 7 must be a byte string".into()),
+                },
+
+                _ => {
+                    claims.other.push((k, v));
+                    continue;
+                }
+            };
+
+            if duplicate {
+                return Err(alloc::format!("duplicate CWT claim {}", key));
+            }
+        }
+
+        Ok(claims)
+    }
+}
+
+impl<'de> Deserialize<'de> for Claims {
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Self::from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// A CBOR Web Token, wrapped by tag 61
+///
+/// The token is usually a COSE message whose payload is the encoded
+/// [`Claims`]. The tag is always written, but it is optional when reading,
+/// since a token's type is often known from its context. Any other tag
+/// is rejected.
+///
+/// ```rust
+/// use ciborium::{cbor, cwt::Token, value::Value};
+///
+/// let value = Value::serialized(&Token(7)).unwrap();
+/// assert_eq!(value, cbor!(tag!(61, 7)).unwrap());
+/// assert_eq!(value.deserialized::<Token<u8>>().unwrap(), Token(7));
+/// assert_eq!(cbor!(7).unwrap().deserialized::<Token<u8>>().unwrap(), Token(7));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Token<T>(pub T);
+
+impl<T: Serialize> Serialize for Token<T> {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 61>(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Token<T> {
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<T>::deserialize(deserializer)? {
+            Captured(Some(61), value) | Captured(None, value) => Ok(Self(value)),
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 61 (CBOR Web Token), found tag {}",
+                tag
+            ))),
+        }
+    }
+}
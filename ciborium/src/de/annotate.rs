@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Error;
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Debug, Write};
+
+use ciborium_ll::{Decoder, Header};
+
+/// The number of bytes shown around the offset of an error
+const WINDOW: usize = 16;
+
+/// The number of headers shown before the offset of an error
+const HEADERS: usize = 8;
+
+/// Tracks the containers, tags and strings which are open, to indent headers
+#[derive(Default)]
+struct Levels(Vec<Option<usize>>);
+
+impl Levels {
+    /// Counts an item which has just ended
+    fn complete(&mut self) {
+        while let Some(level) = self.0.last_mut() {
+            match level {
+                Some(1) => {
+                    self.0.pop();
+                }
+
+                Some(n) => {
+                    *n -= 1;
+                    return;
+                }
+
+                None => return,
+            }
+        }
+    }
+
+    /// Gets the indentation of the header and counts it
+    fn step(&mut self, header: Header) -> usize {
+        let depth = self.0.len();
+
+        match header {
+            Header::Break => {
+                if let Some(None) = self.0.last() {
+                    self.0.pop();
+                    self.complete();
+                }
+
+                return depth.saturating_sub(1);
+            }
+
+            Header::Array(Some(0)) | Header::Map(Some(0)) => self.complete(),
+            Header::Array(len) => self.0.push(len),
+            Header::Map(len) => self.0.push(len.map(|x| x.saturating_mul(2))),
+            Header::Tag(..) => self.0.push(Some(1)),
+            Header::Bytes(None) | Header::Text(None) => self.0.push(None),
+            _ => self.complete(),
+        }
+
+        depth
+    }
+}
+
+/// Decodes the header at the start of `input`, with its length in bytes
+fn header(input: &[u8]) -> Option<(Header, usize)> {
+    let mut decoder = Decoder::from(input);
+    let header = decoder.pull().ok()?;
+    Some((header, decoder.offset()))
+}
+
+/// Writes the bytes around `offset`, with a caret under the byte at `offset`
+fn window(out: &mut String, input: &[u8], offset: usize) {
+    let start = offset
+        .saturating_sub(WINDOW / 2)
+        .min(input.len().saturating_sub(WINDOW));
+    let end = input.len().min(start + WINDOW);
+
+    write!(out, "{:>8} ", start).unwrap();
+    for byte in &input[start..end] {
+        write!(out, " {:02x}", byte).unwrap();
+    }
+
+    // The caret may be just after the input, if it ended too early.
+    out.push('\n');
+    out.extend(core::iter::repeat(' ').take(9 + 3 * (offset - start) + 1));
+    out.push_str("^^\n");
+}
+
+/// Writes the headers up to `offset`, and the header there, if any
+fn headers(out: &mut String, input: &[u8], offset: usize) {
+    let mut lines = Vec::new();
+    let mut levels = Levels::default();
+    let mut pos = 0;
+
+    while pos < offset {
+        let (header, len) = match header(&input[pos..]) {
+            Some(x) => x,
+            None => break,
+        };
+
+        let indent = levels.step(header);
+        lines.push((pos, indent, header));
+
+        // Skip the body of a string, which holds no headers.
+        pos += len;
+        if let Header::Bytes(Some(len)) | Header::Text(Some(len)) = header {
+            pos = pos.saturating_add(len);
+        }
+    }
+
+    if lines.len() > HEADERS {
+        out.push_str("       ...\n");
+    }
+
+    for (pos, indent, header) in &lines[lines.len().saturating_sub(HEADERS)..] {
+        writeln!(
+            out,
+            "{:>8}  {:indent$}{}",
+            pos,
+            "",
+            header,
+            indent = indent * 2
+        )
+        .unwrap();
+    }
+
+    // The offset may be within the body of the last string.
+    if pos != offset {
+        return;
+    }
+
+    let depth = levels.0.len();
+    match header(&input[offset..]) {
+        Some((header, ..)) => {
+            let indent = levels.step(header);
+            writeln!(
+                out,
+                "{:>8}  {:indent$}{}  <--",
+                offset,
+                "",
+                header,
+                indent = indent * 2
+            )
+        }
+
+        None => writeln!(
+            out,
+            "{:>8}  {:indent$}??  <--",
+            offset,
+            "",
+            indent = depth * 2
+        ),
+    }
+    .unwrap();
+}
+
+impl<T: Debug> Error<T> {
+    /// Describes the error in the context of the input which caused it
+    ///
+    /// This is meant for logs and debugging. Besides the error itself, if it
+    /// has an offset, this shows a window of up to 16 bytes around the offset,
+    /// with a caret under the byte at the offset, and the last few headers
+    /// before it, indented by nesting, in the diagnostic notation of
+    /// [`Header`]. The header at the offset, if it can be decoded, is
+    /// marked with an arrow. The input must be the one which was decoded,
+    /// from its start.
+    ///
+    /// ```rust
+    /// use ciborium::de::from_reader;
+    /// use ciborium::value::Value;
+    ///
+    /// // [1, "a", <invalid>]
+    /// let input = [0x83, 0x01, 0x61, 0x61, 0x1c];
+    /// let error = from_reader::<Value, _>(&input[..]).unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     error.annotate(&input),
+    ///     "Syntax(4)\n\
+    ///      \x20      0  83 01 61 61 1c\n\
+    ///      \x20                     ^^\n\
+    ///      \x20      0  array(3)\n\
+    ///      \x20      1    1\n\
+    ///      \x20      2    text(1)\n\
+    ///      \x20      4    ??  <--\n"
+    /// );
+    /// ```
+    pub fn annotate(&self, input: &[u8]) -> String {
+        let offset = match *self {
+            Self::Syntax(x) => Some(x),
+            Self::UnexpectedBreak { offset } => Some(offset),
+            Self::InvalidUtf8 { offset, .. } => Some(offset),
+            Self::MissingBreak { container_offset } => Some(container_offset),
+            Self::ContainerUnderflow { offset, .. } => Some(offset),
+            Self::ContainerOverflow { offset, .. } => Some(offset),
+            Self::UnexpectedEof { offset } => Some(offset),
+            Self::Semantic(x, ..) => x,
+            Self::InvalidType { offset, .. } => Some(offset),
+            Self::LimitExceeded { offset, .. } => Some(offset),
+            _ => None,
+        };
+
+        let mut out = String::new();
+        let offset = match offset {
+            Some(x) if x <= input.len() => x,
+            _ => {
+                writeln!(out, "{}", self).unwrap();
+                return out;
+            }
+        };
+
+        writeln!(out, "{}", self).unwrap();
+        window(&mut out, input, offset);
+        headers(&mut out, input, offset);
+        out
+    }
+}
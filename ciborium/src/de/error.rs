@@ -3,14 +3,29 @@
 use alloc::string::{String, ToString};
 use core::fmt::{Debug, Display, Formatter, Result};
 
+use crate::IoMessage;
+
+use ciborium_io::Read;
+use ciborium_ll::{simple, Header, LimitKind};
 use serde::de::{Error as DeError, StdError};
 
 /// An error occurred during deserialization
+///
+/// This implements `core::error::Error` when the error of the reader does,
+/// and that error is its `source()`. With the `std` feature, it can also be
+/// turned into a `std::io::Error`, keeping the kind of an I/O error.
+///
+/// The error is `Send`, `Sync` and `'static` whenever the error of the
+/// reader is; [`Error::into_owned()`] makes it so for any reader.
 #[derive(Debug)]
 pub enum Error<T> {
     /// An error occurred while reading bytes
     ///
-    /// Contains the underlying error reaturned while reading.
+    /// Contains the underlying error reaturned while reading. Only the
+    /// reader raises this: the input is checked by the decoder and the
+    /// deserializer, above the reader, so an input which is too deep or
+    /// malformed is never reported as an I/O error. A reader which ran out
+    /// of input is reported as [`Error::UnexpectedEof`] instead.
     Io(T),
 
     /// An error occurred while parsing bytes
@@ -18,6 +33,79 @@ pub enum Error<T> {
     /// Contains the offset into the stream where the syntax error occurred.
     Syntax(usize),
 
+    /// A break which does not end an indefinite-length container or string
+    ///
+    /// This is a stray break, or one in place of an item of a definite-length
+    /// container, of the value of a map entry or of the item wrapped by a tag.
+    UnexpectedBreak {
+        /// The offset of the break
+        offset: usize,
+    },
+
+    /// A text string, or one of its chunks, is not valid UTF-8
+    ///
+    /// Each chunk of an indefinite-length string must be valid on its own,
+    /// so a chunk which ends within a character is invalid, even if the next
+    /// chunk completes the character.
+    InvalidUtf8 {
+        /// The index of the chunk, which is zero for a definite length
+        chunk: usize,
+
+        /// The offset of the header of the chunk
+        offset: usize,
+    },
+
+    /// An indefinite-length string is followed by an item which is not a chunk
+    ///
+    /// The encoder most likely forgot the break which ends the string.
+    MissingBreak {
+        /// The offset of the header of the string
+        container_offset: usize,
+    },
+
+    /// A definite-length array or map is cut short by a break
+    ///
+    /// The break belongs to an indefinite-length container around it, which
+    /// ends before the items the array or map declares. Arrays count items
+    /// and maps count entries.
+    ContainerUnderflow {
+        /// The offset of the header of the array or map
+        offset: usize,
+
+        /// The number of items or entries in the header
+        declared: usize,
+
+        /// The number of whole items or entries before the break
+        actual: usize,
+    },
+
+    /// A definite-length array or map has more items than were deserialized
+    ///
+    /// The type took only some of the items or entries the header declares,
+    /// like a tuple which is shorter than the array, so the rest would be
+    /// misread as the items which follow.
+    ContainerOverflow {
+        /// The offset of the header of the array or map
+        offset: usize,
+
+        /// The number of items or entries in the header
+        declared: usize,
+
+        /// The number of items or entries which were deserialized
+        actual: usize,
+    },
+
+    /// The input ended in the middle of a data item
+    ///
+    /// Contains the offset of the read which could not be completed. This
+    /// is raised instead of [`Error::Io`] when the reader reports the end of
+    /// its input (see [`ciborium_io::Read::is_eof()`]), so an incremental
+    /// parser can tell that it needs more bytes, even without `std`.
+    UnexpectedEof {
+        /// The offset of the read which could not be completed
+        offset: usize,
+    },
+
     /// An error occurred while processing a parsed value
     ///
     /// Contains a description of the error that occurred and (optionally)
@@ -25,18 +113,404 @@ pub enum Error<T> {
     /// processed when the error occurred.
     Semantic(Option<usize>, String),
 
+    /// A data item does not have the type which was expected
+    ///
+    /// This is a semantic error which the deserializer raises itself, when
+    /// the type asked for by serde does not match the item in the input.
+    /// Mismatches found by a visitor are still [`Error::Semantic`].
+    InvalidType {
+        /// The offset of the item
+        offset: usize,
+
+        /// What was expected, like `"bytes"` or `"map"`
+        expected: &'static str,
+
+        /// What was found instead
+        found: FoundKind,
+    },
+
     /// The input caused serde to recurse too much
     ///
-    /// This error prevents a stack overflow.
+    /// This is no longer returned: nesting too deeply fails with
+    /// [`Error::LimitExceeded`] for [`LimitKind::Depth`] instead.
+    #[deprecated(note = "nesting too deeply is `Error::LimitExceeded` for `LimitKind::Depth`")]
     RecursionLimitExceeded,
+
+    /// The input goes beyond one of the limits of the deserializer
+    ///
+    /// [`Options::max_depth()`](crate::de::Options::max_depth) prevents a
+    /// stack overflow, and [`Options::max_items()`](crate::de::Options::max_items)
+    /// bounds the work spent on hostile input. Every limit is reported the
+    /// same way, with the numbers, so that they can be logged and shown alike.
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: LimitKind,
+
+        /// The value of the limit
+        limit: u64,
+
+        /// The value which the input reached, beyond the limit
+        observed: u64,
+
+        /// The offset of the header which went beyond the limit
+        offset: usize,
+    },
+}
+
+/// The kind of a deserialization error
+///
+/// This allows errors to be told apart without looking at their contents,
+/// for example to map them to the error codes of a protocol. Each variant
+/// of [`Error`] has one kind; more kinds may be added later.
+///
+/// There is no kind for a scratch buffer which is too small, since strings
+/// which do not fit in it are streamed instead. Nor is there one for
+/// duplicate map keys: the deserializer does not compare keys, and a type
+/// which rejects them, like a derived struct with a repeated field, does
+/// so through serde, which is [`ErrorKind::Semantic`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The reader failed, see [`Error::Io`]
+    Io,
+
+    /// The input ended too early, see [`Error::UnexpectedEof`]
+    UnexpectedEof,
+
+    /// The input is not well-formed CBOR, see [`Error::Syntax`],
+    /// [`Error::UnexpectedBreak`], [`Error::MissingBreak`] and
+    /// [`Error::ContainerUnderflow`]
+    Syntax,
+
+    /// The input does not match the type, see [`Error::Semantic`],
+    /// [`Error::InvalidType`] and [`Error::ContainerOverflow`]
+    Semantic,
+
+    /// The input is nested too deeply, see [`Error::LimitExceeded`] for
+    /// [`LimitKind::Depth`]
+    RecursionLimit,
+
+    /// The input goes beyond another limit, see [`Error::LimitExceeded`]
+    LimitExceeded,
+}
+
+/// The kind of data item found by the deserializer
+///
+/// This is the major type of the header, with integers split by sign and
+/// the well-known simple values told apart. See [`Error::InvalidType`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FoundKind {
+    /// A positive integer
+    Positive,
+
+    /// A negative integer
+    Negative,
+
+    /// A bytes item
+    Bytes,
+
+    /// A text item
+    Text,
+
+    /// An array
+    Array,
+
+    /// A map
+    Map,
+
+    /// A tag
+    Tag,
+
+    /// `true` or `false`
+    Bool,
+
+    /// `null`
+    Null,
+
+    /// `undefined`
+    Undefined,
+
+    /// Another simple value
+    Simple,
+
+    /// A floating point value
+    Float,
+
+    /// A break
+    Break,
+}
+
+impl From<Header> for FoundKind {
+    #[inline]
+    fn from(value: Header) -> Self {
+        match value {
+            Header::Positive(..) => Self::Positive,
+            Header::Negative(..) => Self::Negative,
+            Header::Bytes(..) => Self::Bytes,
+            Header::Text(..) => Self::Text,
+            Header::Array(..) => Self::Array,
+            Header::Map(..) => Self::Map,
+            Header::Tag(..) => Self::Tag,
+            Header::Simple(simple::FALSE | simple::TRUE) => Self::Bool,
+            Header::Simple(simple::NULL) => Self::Null,
+            Header::Simple(simple::UNDEFINED) => Self::Undefined,
+            Header::Simple(..) => Self::Simple,
+            Header::Float(..) => Self::Float,
+            Header::Break => Self::Break,
+        }
+    }
+}
+
+impl Display for FoundKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            Self::Positive => "positive integer",
+            Self::Negative => "negative integer",
+            Self::Bytes => "bytes",
+            Self::Text => "text",
+            Self::Array => "array",
+            Self::Map => "map",
+            Self::Tag => "tag",
+            Self::Bool => "bool",
+            Self::Null => "null",
+            Self::Undefined => "undefined",
+            Self::Simple => "simple value",
+            Self::Float => "float",
+            Self::Break => "break",
+        })
+    }
 }
 
 impl<T> Error<T> {
+    /// Gets the kind of the error
+    ///
+    /// ```rust
+    /// use ciborium::de::{from_reader, ErrorKind};
+    /// use ciborium::value::Value;
+    ///
+    /// let error = from_reader::<Value, _>(&[0x1c][..]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::Syntax);
+    ///
+    /// let error = from_reader::<bool, _>(&[0x01][..]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::Semantic);
+    /// ```
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Io(..) => ErrorKind::Io,
+            Self::UnexpectedEof { .. } => ErrorKind::UnexpectedEof,
+            Self::Syntax(..) | Self::UnexpectedBreak { .. } | Self::MissingBreak { .. } => {
+                ErrorKind::Syntax
+            }
+            Self::InvalidUtf8 { .. } | Self::ContainerUnderflow { .. } => ErrorKind::Syntax,
+            Self::Semantic(..) | Self::InvalidType { .. } | Self::ContainerOverflow { .. } => {
+                ErrorKind::Semantic
+            }
+            #[allow(deprecated)]
+            Self::RecursionLimitExceeded
+            | Self::LimitExceeded {
+                kind: LimitKind::Depth,
+                ..
+            } => ErrorKind::RecursionLimit,
+            Self::LimitExceeded { .. } => ErrorKind::LimitExceeded,
+        }
+    }
+
+    /// The error for nesting more than `limit` levels, at the header at `offset`
+    #[inline]
+    pub(crate) fn depth(limit: usize, offset: usize) -> Self {
+        let limit = limit as u64;
+
+        Self::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit,
+            observed: limit.saturating_add(1),
+            offset,
+        }
+    }
+
     /// A helper method for composing a semantic error
     #[inline]
     pub fn semantic(offset: impl Into<Option<usize>>, msg: impl Into<String>) -> Self {
         Self::Semantic(offset.into(), msg.into())
     }
+
+    /// Gets the error of the reader, if reading failed
+    #[inline]
+    pub fn as_io(&self) -> Option<&T> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Turns a read which ran out of input at `offset` into `UnexpectedEof`
+    #[inline]
+    pub(crate) fn eof<R: Read<Error = T>>(self, offset: usize) -> Self {
+        match self {
+            Self::Io(e) if R::is_eof(&e) => Self::UnexpectedEof { offset },
+            e => e,
+        }
+    }
+
+    /// Moves the offset of the error on by `by`, for input which started
+    /// that far into a larger input
+    pub(crate) fn shifted(self, by: usize) -> Self {
+        match self {
+            Self::Syntax(x) => Self::Syntax(x + by),
+            Self::UnexpectedBreak { offset } => Self::UnexpectedBreak {
+                offset: offset + by,
+            },
+            Self::InvalidUtf8 { chunk, offset } => Self::InvalidUtf8 {
+                chunk,
+                offset: offset + by,
+            },
+            Self::MissingBreak { container_offset } => Self::MissingBreak {
+                container_offset: container_offset + by,
+            },
+            Self::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            } => Self::ContainerUnderflow {
+                offset: offset + by,
+                declared,
+                actual,
+            },
+            Self::ContainerOverflow {
+                offset,
+                declared,
+                actual,
+            } => Self::ContainerOverflow {
+                offset: offset + by,
+                declared,
+                actual,
+            },
+            Self::UnexpectedEof { offset } => Self::UnexpectedEof {
+                offset: offset + by,
+            },
+            Self::Semantic(x, msg) => Self::Semantic(x.map(|x| x + by), msg),
+            Self::InvalidType {
+                offset,
+                expected,
+                found,
+            } => Self::InvalidType {
+                offset: offset + by,
+                expected,
+                found,
+            },
+            Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            } => Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset: offset + by,
+            },
+            e => e,
+        }
+    }
+
+    /// Takes the error of the reader, if reading failed
+    ///
+    /// Otherwise, the error is returned unchanged.
+    #[inline]
+    pub fn into_io(self) -> core::result::Result<T, Self> {
+        match self {
+            Self::Io(e) => Ok(e),
+            e => Err(e),
+        }
+    }
+
+    /// Replaces the error of the reader by its message
+    ///
+    /// The result is `Send`, `Sync` and `'static`, and implements
+    /// `core::error::Error`, whatever the reader, so it can be boxed into
+    /// an application error and sent to another thread.
+    ///
+    /// ```rust
+    /// use ciborium::de::from_reader;
+    /// use ciborium::value::Value;
+    ///
+    /// let error = from_reader::<Value, _>(&[0x1c][..]).unwrap_err();
+    /// let boxed: Box<dyn std::error::Error + Send + Sync> = error.into_owned().into();
+    /// assert_eq!(boxed.to_string(), "Syntax(0)");
+    /// ```
+    pub fn into_owned(self) -> Error<IoMessage>
+    where
+        T: Display,
+    {
+        self.map_io(|e| e.to_string().into())
+    }
+
+    /// Changes the error of the reader with `func`, keeping everything else
+    ///
+    /// This unifies the errors of different readers, such as to return them
+    /// through one application error.
+    ///
+    /// ```rust
+    /// use ciborium::de::{from_reader, Error};
+    /// use ciborium::value::Value;
+    ///
+    /// let error = from_reader::<Value, _>(&[0x1c][..]).unwrap_err();
+    /// let error: Error<String> = error.map_io(|e| e.to_string());
+    /// assert!(matches!(error, Error::Syntax(0)));
+    /// ```
+    pub fn map_io<U>(self, func: impl FnOnce(T) -> U) -> Error<U> {
+        match self {
+            Self::Io(e) => Error::Io(func(e)),
+            Self::Syntax(x) => Error::Syntax(x),
+            Self::UnexpectedBreak { offset } => Error::UnexpectedBreak { offset },
+            Self::InvalidUtf8 { chunk, offset } => Error::InvalidUtf8 { chunk, offset },
+            Self::MissingBreak { container_offset } => Error::MissingBreak { container_offset },
+            Self::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            } => Error::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            },
+            Self::ContainerOverflow {
+                offset,
+                declared,
+                actual,
+            } => Error::ContainerOverflow {
+                offset,
+                declared,
+                actual,
+            },
+            Self::UnexpectedEof { offset } => Error::UnexpectedEof { offset },
+            Self::Semantic(x, msg) => Error::Semantic(x, msg),
+            Self::InvalidType {
+                offset,
+                expected,
+                found,
+            } => Error::InvalidType {
+                offset,
+                expected,
+                found,
+            },
+            #[allow(deprecated)]
+            Self::RecursionLimitExceeded => Error::RecursionLimitExceeded,
+            Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            } => Error::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            },
+        }
+    }
 }
 
 impl<T> From<T> for Error<T> {
@@ -52,6 +526,33 @@ impl<T> From<ciborium_ll::Error<T>> for Error<T> {
         match value {
             ciborium_ll::Error::Io(x) => Self::Io(x),
             ciborium_ll::Error::Syntax(x) => Self::Syntax(x),
+            ciborium_ll::Error::UnexpectedBreak { offset } => Self::UnexpectedBreak { offset },
+            ciborium_ll::Error::MissingBreak { container_offset } => {
+                Self::MissingBreak { container_offset }
+            }
+            ciborium_ll::Error::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            } => Self::ContainerUnderflow {
+                offset,
+                declared,
+                actual,
+            },
+            ciborium_ll::Error::InvalidUtf8 { chunk, offset } => {
+                Self::InvalidUtf8 { chunk, offset }
+            }
+            ciborium_ll::Error::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            } => Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            },
         }
     }
 }
@@ -59,15 +560,114 @@ impl<T> From<ciborium_ll::Error<T>> for Error<T> {
 impl<T: Debug> Display for Error<T> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "{:?}", self)
+        match self {
+            Self::InvalidType {
+                offset,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invalid type at offset {}: expected {}, found {}",
+                offset, expected, found
+            ),
+
+            Self::InvalidUtf8 { chunk, offset } => write!(
+                f,
+                "invalid UTF-8 in chunk {} of the text at offset {}",
+                chunk, offset
+            ),
+
+            Self::LimitExceeded {
+                kind,
+                limit,
+                observed,
+                offset,
+            } => write!(
+                f,
+                "{} limit of {} exceeded at offset {}, with {}",
+                kind, limit, offset, observed
+            ),
+
+            e => write!(f, "{:?}", e),
+        }
     }
 }
 
-impl<T: Debug> StdError for Error<T> {}
+impl<T: StdError + 'static> StdError for Error<T> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
-impl<T: Debug> DeError for Error<T> {
+impl<T: StdError + 'static> DeError for Error<T> {
     #[inline]
     fn custom<U: Display>(msg: U) -> Self {
         Self::Semantic(None, msg.to_string())
     }
 }
+
+/// The error returned by the deserializer to serde
+///
+/// serde requires its errors to implement `std::error::Error`, which `Error`
+/// only does when the reader's error does, so that it can be the source. The
+/// reader's error need only implement `Debug` to be read from, so the wrapper
+/// is removed before an [`Error`] is returned to the caller.
+#[derive(Debug)]
+pub(crate) struct Internal<T>(pub Error<T>);
+
+impl<T> From<T> for Internal<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self(Error::Io(value))
+    }
+}
+
+impl<T> From<Error<T>> for Internal<T> {
+    #[inline]
+    fn from(value: Error<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<ciborium_ll::Error<T>> for Internal<T> {
+    #[inline]
+    fn from(value: ciborium_ll::Error<T>) -> Self {
+        Self(value.into())
+    }
+}
+
+impl<T: Debug> Display for Internal<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<T: Debug> StdError for Internal<T> {}
+
+impl<T: Debug> DeError for Internal<T> {
+    #[inline]
+    fn custom<U: Display>(msg: U) -> Self {
+        Self(Error::Semantic(None, msg.to_string()))
+    }
+}
+
+/// Turns the error into an I/O error, so it can be returned from I/O code
+///
+/// The error of the reader is returned as is, so its kind is kept. The end
+/// of the input has the kind `UnexpectedEof`, and any other error has the
+/// kind `InvalidData`; both wrap this error.
+#[cfg(feature = "std")]
+impl From<Error<std::io::Error>> for std::io::Error {
+    #[inline]
+    fn from(value: Error<std::io::Error>) -> Self {
+        match value {
+            Error::Io(e) => e,
+            e @ Error::UnexpectedEof { .. } => Self::new(std::io::ErrorKind::UnexpectedEof, e),
+            e => Self::new(std::io::ErrorKind::InvalidData, e),
+        }
+    }
+}
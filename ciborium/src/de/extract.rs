@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Access, Declared, Deserializer, Error, Expected, Internal, Options};
+
+use core::fmt::{Formatter, Result as FmtResult};
+use core::marker::PhantomData;
+
+use ciborium_io::Read;
+use ciborium_ll::Header;
+use serde::de::{self, IgnoredAny, IntoDeserializer};
+use serde::forward_to_deserialize_any;
+
+/// Reads a map with only the entries whose keys are among some fields
+///
+/// The other entries are skipped over without being parsed, whatever the
+/// type asks for.
+pub(super) struct Fields<'a, 'f, 'de, 'b, R: Read>(
+    pub(super) &'a mut Deserializer<'de, 'b, R>,
+    pub(super) &'f [&'f str],
+);
+
+impl<'de, 'a, 'f, 'b, R: Read> de::Deserializer<'de> for Fields<'a, 'f, 'de, 'b, R>
+where
+    R::Error: core::fmt::Debug,
+{
+    type Error = Internal<R::Error>;
+
+    #[inline]
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let Fields(de, fields) = self;
+
+        loop {
+            let offset = de.decoder.offset();
+
+            return match de.pull()? {
+                Header::Tag(..) => continue,
+
+                Header::Map(len) => {
+                    let mut access = Access(de, len, Declared { offset, len }, 0);
+                    let value =
+                        access.open(|access| visitor.visit_map(Selected(access, fields)))?;
+                    access.end().map(|()| value)
+                }
+
+                header => Err(header.expected(offset, "map")),
+            };
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128
+        u8 u16 u32 u64 u128
+        bool f32 f64
+        char str string
+        bytes byte_buf
+        seq map
+        struct tuple tuple_struct
+        identifier ignored_any
+        option unit unit_struct newtype_struct enum
+    }
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// The entries of a map which are among the fields
+struct Selected<'x, 'a, 'f, 'de, 'b, R: Read>(&'x mut Access<'a, 'de, 'b, R>, &'f [&'f str]);
+
+impl<'de, 'x, 'a, 'f, 'b, R: Read> de::MapAccess<'de> for Selected<'x, 'a, 'f, 'de, 'b, R>
+where
+    R::Error: core::fmt::Debug,
+{
+    type Error = Internal<R::Error>;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        loop {
+            if self.0 .1 == Some(0) {
+                return Ok(None);
+            }
+
+            // Only text keys can name a field. Any other key, or the break
+            // which ends the map, is left to the access to take.
+            let field = match self.0 .0.decoder.peek()? {
+                Header::Text(..) => de::MapAccess::next_key_seed(self.0, Field(self.1))?,
+                _ => de::MapAccess::next_key_seed(self.0, PhantomData::<IgnoredAny>)?.map(|_| None),
+            };
+
+            match field {
+                None => return Ok(None),
+                Some(Some(index)) => {
+                    return seed
+                        .deserialize(self.1[index].into_deserializer())
+                        .map(Some)
+                }
+                Some(None) => {
+                    de::MapAccess::next_value_seed(self.0, PhantomData::<IgnoredAny>)?;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        de::MapAccess::next_value_seed(self.0, seed)
+    }
+}
+
+/// Finds which of the fields a text key is, if any
+struct Field<'f>(&'f [&'f str]);
+
+impl<'de> de::DeserializeSeed<'de> for Field<'_> {
+    type Value = Option<usize>;
+
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for Field<'_> {
+    type Value = Option<usize>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+        write!(formatter, "a text key")
+    }
+
+    #[inline]
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.0.iter().position(|field| *field == v))
+    }
+}
+
+/// The value of the first entry, if any
+struct First<T>(Option<T>);
+
+impl<'de, T: de::Deserialize<'de>> de::Deserialize<'de> for First<T> {
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor<T>(PhantomData<T>);
+
+        impl<'de, T: de::Deserialize<'de>> de::Visitor<'de> for Visitor<T> {
+            type Value = First<T>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+                write!(formatter, "a map")
+            }
+
+            #[inline]
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut first = None;
+
+                // Any later entries with the same key are skipped.
+                while map.next_key::<IgnoredAny>()?.is_some() {
+                    match first {
+                        None => first = Some(map.next_value()?),
+                        Some(..) => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+
+                Ok(First(first))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor(PhantomData))
+    }
+}
+
+/// Fetches the values of a map from a slice by key, without parsing the rest
+///
+/// Each lookup reads through the map once: the keys are compared as they
+/// are read, and the values of other entries are skipped over without
+/// being parsed. So a lookup takes time in proportion to the size of the
+/// map, and memory only for the value it returns, however large the map
+/// is. See [`extract()`](crate::de::extract) to fetch several fields in a
+/// single pass.
+///
+/// ```rust
+/// use ciborium::de::MapAccessor;
+/// use ciborium::value::RawCborRef;
+///
+/// // {"id": 7, "payload": [1, 2, 3], "name": "probe"}
+/// let bytes = b"\xa3\x62id\x07\x67payload\x83\x01\x02\x03\x64name\x65probe";
+/// let map = MapAccessor::new(bytes);
+///
+/// assert_eq!(map.get::<u8>("id").unwrap(), Some(7));
+/// assert_eq!(map.get::<&str>("name").unwrap(), Some("probe"));
+/// assert_eq!(map.get::<u8>("size").unwrap(), None);
+///
+/// // A value can be kept unparsed, too.
+/// let payload = map.get::<RawCborRef>("payload").unwrap().unwrap();
+/// assert_eq!(payload.as_bytes(), [0x83, 0x01, 0x02, 0x03]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct MapAccessor<'de> {
+    slice: &'de [u8],
+    options: Options,
+}
+
+impl<'de> MapAccessor<'de> {
+    /// Creates an accessor for the map encoded at the start of `slice`
+    ///
+    /// Nothing is read until a value is fetched.
+    #[inline]
+    pub fn new(slice: &'de [u8]) -> Self {
+        Self::with_options(slice, Options::new())
+    }
+
+    /// Creates an accessor which reads the map with these options
+    #[inline]
+    pub fn with_options(slice: &'de [u8], options: Options) -> Self {
+        Self { slice, options }
+    }
+
+    /// Fetches the value of the first entry with the text key `key`
+    ///
+    /// This is `None` if there is no such entry. The whole map is still
+    /// read, so an error anywhere in it fails the lookup.
+    #[inline]
+    pub fn get<T: de::Deserialize<'de>>(
+        &self,
+        key: &str,
+    ) -> Result<Option<T>, Error<<&'de [u8] as Read>::Error>> {
+        let first: First<T> = self.options.extract(self.slice, &[key])?;
+        Ok(first.0)
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reads the bytes of exactly one data item from an asynchronous reader
+
+use super::Error;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::pin::Pin;
+
+use ciborium_io::AsyncRead;
+
+/// The largest number of string bytes which are read at once
+const CHUNK: usize = 4096;
+
+/// Appends exactly `len` bytes from the reader to the buffer
+async fn fill<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    len: usize,
+) -> Result<(), Error<R::Error>> {
+    let end = buffer.len().saturating_add(len);
+
+    while buffer.len() < end {
+        let start = buffer.len();
+        buffer.resize(end.min(start + CHUNK), 0);
+
+        let data = &mut buffer[start..];
+        let len = data.len();
+        let read =
+            core::future::poll_fn(|cx| Pin::new(&mut *reader).poll_read(cx, &mut *data)).await;
+
+        match read {
+            // A reader which yields nothing has reached the end of its input.
+            Ok(0) => {
+                buffer.truncate(start);
+                return Err(Error::UnexpectedEof { offset: start });
+            }
+
+            Ok(n) => buffer.truncate(start + n.min(len)),
+            Err(e) => {
+                buffer.truncate(start);
+
+                return Err(match R::is_eof(&e) {
+                    true => Error::UnexpectedEof { offset: start },
+                    false => Error::Io(e),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Appends the encoding of one data item to the buffer
+///
+/// Only the headers are parsed, so that the length of the item is known;
+/// the contents are checked later by the deserializer. Definite-length
+/// strings are read in chunks, so a bogus length fails at the end of the
+/// input rather than allocating everything up front.
+pub(super) async fn read_item<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    depth: usize,
+) -> Result<(), Error<R::Error>> {
+    // The number of items left in each open container, or `None` for
+    // containers of indefinite length, which end with a break.
+    let mut stack: Vec<Option<u64>> = alloc::vec![Some(1)];
+
+    while let Some(top) = stack.last_mut() {
+        if *top == Some(0) {
+            stack.pop();
+            continue;
+        }
+
+        let offset = buffer.len();
+        fill(reader, buffer, 1).await?;
+        let initial = buffer[offset];
+
+        if initial == 0xff {
+            match top {
+                None => {
+                    stack.pop();
+                    continue;
+                }
+
+                Some(..) => return Err(Error::UnexpectedBreak { offset }),
+            }
+        }
+
+        if let Some(n) = top {
+            *n -= 1;
+        }
+
+        let major = initial >> 5;
+        let arg = match initial & 0x1f {
+            x @ 0..=23 => Some(u64::from(x)),
+
+            x @ 24..=27 => {
+                let len = 1 << (x - 24);
+                fill(reader, buffer, len).await?;
+                let bytes = &buffer[offset + 1..];
+                Some(bytes.iter().fold(0, |n, b| n << 8 | u64::from(*b)))
+            }
+
+            31 if (2..=5).contains(&major) => None,
+            _ => return Err(Error::Syntax(offset)),
+        };
+
+        let push = match major {
+            0 | 1 | 7 => None,
+            4 => Some(arg),
+            5 => match arg {
+                Some(len) => Some(Some(len.checked_mul(2).ok_or(Error::Syntax(offset))?)),
+                None => Some(None),
+            },
+            6 => Some(Some(1)),
+
+            // Byte and text strings, whose chunks are items of their own
+            _ => match arg {
+                Some(len) => {
+                    let len = usize::try_from(len).map_err(|_| Error::Syntax(offset))?;
+                    fill(reader, buffer, len).await?;
+                    None
+                }
+
+                None => Some(None),
+            },
+        };
+
+        if let Some(len) = push {
+            if stack.len() > depth {
+                return Err(Error::depth(depth, offset));
+            }
+
+            stack.push(len);
+        }
+    }
+
+    Ok(())
+}
@@ -2,78 +2,262 @@
 
 //! Serde deserialization support for CBOR
 
+mod annotate;
 mod error;
+mod extract;
+#[cfg(feature = "async")]
+mod frame;
+mod warning;
 
-pub use error::Error;
+pub use ciborium_ll::LimitKind;
+use error::Internal;
+pub use error::{Error, ErrorKind, FoundKind};
+pub use extract::MapAccessor;
+pub use warning::{Warning, WarningKind};
+
+use warning::Step;
 
 use alloc::{string::String, vec::Vec};
 use core::convert::TryFrom;
 
-use ciborium_io::Read;
+#[cfg(feature = "async")]
+use ciborium_io::AsyncRead;
+use ciborium_io::{BufRead, Read};
 use ciborium_ll::*;
 use serde::{de, de::Deserializer as _, forward_to_deserialize_any};
 
-trait Expected<E: de::Error> {
-    fn expected(self, kind: &'static str) -> E;
+trait Expected<T> {
+    fn expected(self, offset: usize, kind: &'static str) -> Internal<T>;
 }
 
-impl<E: de::Error> Expected<E> for Header {
+impl<T> Expected<T> for Header {
     #[inline]
-    fn expected(self, kind: &'static str) -> E {
-        de::Error::invalid_type(
-            match self {
-                Header::Positive(x) => de::Unexpected::Unsigned(x),
-                Header::Negative(x) => de::Unexpected::Signed(x as i64 ^ !0),
-                Header::Bytes(..) => de::Unexpected::Other("bytes"),
-                Header::Text(..) => de::Unexpected::Other("string"),
+    fn expected(self, offset: usize, kind: &'static str) -> Internal<T> {
+        Internal(Error::InvalidType {
+            offset,
+            expected: kind,
+            found: self.into(),
+        })
+    }
+}
 
-                Header::Array(..) => de::Unexpected::Seq,
-                Header::Map(..) => de::Unexpected::Map,
+/// Access to the buffer of a [`BufRead`] reader
+///
+/// These are function pointers, so that the deserializer does not need a
+/// `BufRead` bound, or another type parameter, to use them when it can.
+struct Buffered<R: Read> {
+    contiguous: Contiguous<R>,
+    consume: fn(&mut Decoder<R>, usize),
+}
 
-                Header::Tag(..) => de::Unexpected::Other("tag"),
+type Contiguous<R> = for<'x> fn(
+    &'x mut Decoder<R>,
+    usize,
+)
+    -> Result<Option<&'x [u8]>, ciborium_ll::Error<<R as Read>::Error>>;
 
-                Header::Simple(simple::FALSE) => de::Unexpected::Bool(false),
-                Header::Simple(simple::TRUE) => de::Unexpected::Bool(true),
-                Header::Simple(simple::NULL) => de::Unexpected::Other("null"),
-                Header::Simple(simple::UNDEFINED) => de::Unexpected::Other("undefined"),
-                Header::Simple(..) => de::Unexpected::Other("simple"),
+impl<R: Read> Clone for Buffered<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
 
-                Header::Float(x) => de::Unexpected::Float(x),
-                Header::Break => de::Unexpected::Other("break"),
-            },
-            &kind,
-        )
+impl<R: Read> Copy for Buffered<R> {}
+
+impl<R: BufRead> Buffered<R> {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            contiguous: Decoder::contiguous,
+            consume: Decoder::consume,
+        }
     }
 }
 
-struct Deserializer<'b, R: Read> {
+/// The text of the map key which is being read, for the path of warnings
+#[derive(Default)]
+struct KeyText {
+    capture: bool,
+    text: Option<String>,
+}
+
+impl KeyText {
+    /// Keeps the text, if it is the first text of the key
+    #[inline]
+    fn keep(&mut self, text: &str) {
+        if self.capture {
+            self.capture = false;
+            self.text = Some(text.into());
+        }
+    }
+
+    /// Takes the step to the value of the key which was read
+    #[inline]
+    fn step(&mut self) -> Step {
+        match self.text.take() {
+            Some(x) => Step::Field(x),
+            None => Step::Key,
+        }
+    }
+}
+
+struct Deserializer<'de, 'b, R: Read> {
     decoder: Decoder<R>,
+    input: Option<&'de [u8]>,
     scratch: &'b mut [u8],
-    recurse: usize,
+    deny_tags: bool,
+    max_depth: usize,
+    buffered: Option<Buffered<R>>,
+    indefinite: usize,
+    warnings: Option<&'b mut dyn FnMut(Warning)>,
+    path: Vec<Step>,
+    key: KeyText,
 }
 
-impl<'de, 'a, 'b, R: Read> Deserializer<'b, R>
+impl<'de, 'b, R: Read> Deserializer<'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
+    /// Takes the next `len` bytes in place from the input slice, if there is one
     #[inline]
-    fn recurse<V, F: FnOnce(&mut Self) -> Result<V, Error<R::Error>>>(
-        &mut self,
-        func: F,
-    ) -> Result<V, Error<R::Error>> {
-        if self.recurse == 0 {
-            return Err(Error::RecursionLimitExceeded);
+    fn borrowed(&mut self, len: usize) -> Option<&'de [u8]> {
+        let input = self.input?;
+        let consume = self.buffered?.consume;
+
+        let start = self.decoder.offset();
+        let bytes = input.get(start..start.checked_add(len)?)?;
+        consume(&mut self.decoder, len);
+        Some(bytes)
+    }
+
+    /// Skips the next data item, within the levels left below the limit
+    #[inline]
+    fn skip(&mut self) -> Result<(), Internal<R::Error>> {
+        let depth = self.decoder.depth().unwrap_or_default();
+        let max_depth = self.max_depth.saturating_sub(depth);
+        Ok(self.decoder.skip_with_max_depth(max_depth)?)
+    }
+
+    /// Reads the next data item without parsing it, for raw CBOR
+    ///
+    /// The bytes of the item are borrowed from the input slice if there is
+    /// one, and copied otherwise.
+    fn raw<V: de::Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, Internal<R::Error>> {
+        if let Some(input) = self.input {
+            let start = self.decoder.offset();
+            self.skip()?;
+            return visitor.visit_borrowed_bytes(&input[start..self.decoder.offset()]);
+        }
+
+        let mut bytes = Vec::new();
+        self.decoder.capture(&mut bytes)?;
+        visitor.visit_byte_buf(bytes)
+    }
+
+    /// Reports a warning for the item at `offset`, if there is a sink
+    #[inline]
+    fn warn(&mut self, offset: usize, kind: WarningKind) {
+        if let Some(sink) = self.warnings.as_mut() {
+            sink(Warning {
+                kind,
+                offset,
+                path: warning::path(&self.path),
+            });
+        }
+    }
+
+    /// Runs `func` for an item of a collection, with the step to it on the
+    /// path of warnings
+    #[inline]
+    fn within<T>(&mut self, step: impl FnOnce() -> Step, func: impl FnOnce(&mut Self) -> T) -> T {
+        if self.warnings.is_none() {
+            return func(self);
+        }
+
+        self.path.push(step());
+        let value = func(self);
+        self.path.pop();
+        value
+    }
+
+    /// Reads the body of a text item, of any length, into a `String`
+    ///
+    /// The body is streamed through the scratch buffer, so its chunks may be
+    /// longer than the scratch buffer.
+    fn string(&mut self, len: Option<usize>) -> Result<String, Internal<R::Error>> {
+        let mut buffer = String::new();
+
+        let mut segments = self.decoder.text(len);
+        while let Some(mut segment) = segments.pull()? {
+            while let Some(chunk) = segment.pull(&mut self.scratch[..])? {
+                buffer.push_str(chunk);
+            }
         }
 
-        self.recurse -= 1;
-        let result = func(self);
-        self.recurse += 1;
-        result
+        self.key.keep(&buffer);
+        Ok(buffer)
     }
 
+    /// Reads the body of a bytes item, of any length, into a `Vec<u8>`
+    ///
+    /// The body is streamed through the scratch buffer, so its chunks may be
+    /// longer than the scratch buffer.
+    fn byte_buf(&mut self, len: Option<usize>) -> Result<Vec<u8>, Internal<R::Error>> {
+        let mut buffer = Vec::new();
+
+        let mut segments = self.decoder.bytes(len);
+        while let Some(mut segment) = segments.pull()? {
+            loop {
+                match segment.read(&mut self.scratch[..])? {
+                    0 => break,
+                    n => buffer.extend_from_slice(&self.scratch[..n]),
+                }
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /// Returns the error for a tag which the visitor did not ask for
     #[inline]
-    fn integer(&mut self, mut header: Option<Header>) -> Result<(bool, u128), Error<R::Error>> {
+    fn unexpected_tag(offset: usize, tag: u64) -> Internal<R::Error> {
+        Error::semantic(offset, alloc::format!("unexpected tag {}", tag)).into()
+    }
+
+    /// The error for a negative integer at `offset`, where it must be unsigned
+    #[inline]
+    fn negative(offset: usize) -> Internal<R::Error> {
+        Internal(Error::InvalidType {
+            offset,
+            expected: "unsigned integer",
+            found: FoundKind::Negative,
+        })
+    }
+
+    /// Pulls the next header, which may only be a tag if tags are allowed
+    ///
+    /// A break here does not end any container, so it is unexpected. The
+    /// callers skip the tags which are allowed, so they are reported here.
+    #[inline]
+    fn pull(&mut self) -> Result<Header, Internal<R::Error>> {
+        let offset = self.decoder.offset();
+
+        match self.decoder.pull()? {
+            Header::Break => Err(Error::UnexpectedBreak { offset }.into()),
+            Header::Tag(tag) if self.deny_tags => Err(Self::unexpected_tag(offset, tag)),
+            Header::Tag(tag) => {
+                self.warn(offset, WarningKind::IgnoredTag(tag));
+                Ok(Header::Tag(tag))
+            }
+            header => Ok(header),
+        }
+    }
+
+    #[inline]
+    fn integer(&mut self, mut header: Option<Header>) -> Result<(bool, u128), Internal<R::Error>> {
         loop {
+            let offset = self.decoder.offset();
             let header = match header.take() {
                 Some(h) => h,
                 None => self.decoder.pull()?,
@@ -84,14 +268,22 @@ where
                 Header::Negative(x) => return Ok((true, x.into())),
                 Header::Tag(tag::BIGPOS) => false,
                 Header::Tag(tag::BIGNEG) => true,
-                Header::Tag(..) => continue,
-                header => return Err(header.expected("integer")),
+                Header::Tag(tag) if self.deny_tags => {
+                    return Err(Self::unexpected_tag(offset, tag))
+                }
+                Header::Tag(tag) => {
+                    self.warn(offset, WarningKind::IgnoredTag(tag));
+                    continue;
+                }
+                Header::Break => return Err(Error::UnexpectedBreak { offset }.into()),
+                header => return Err(header.expected(offset, "integer")),
             };
 
             let mut buffer = [0u8; 16];
             let mut value = [0u8; 16];
             let mut index = 0usize;
 
+            let offset = self.decoder.offset();
             return match self.decoder.pull()? {
                 Header::Bytes(len) => {
                     let mut segments = self.decoder.bytes(len);
@@ -113,24 +305,21 @@ where
                     Ok((neg, u128::from_le_bytes(value)))
                 }
 
-                h => Err(h.expected("bytes")),
+                h => Err(h.expected(offset, "bytes")),
             };
         }
     }
 }
 
-impl<'de, 'a, 'b, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'b, R>
+impl<'de, 'a, 'b, R: Read> de::Deserializer<'de> for &'a mut Deserializer<'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let header = self.decoder.pull()?;
-        self.decoder.push(header);
-
-        match header {
+        match self.decoder.peek()? {
             Header::Positive(..) => self.deserialize_u64(visitor),
             Header::Negative(x) => match i64::try_from(x) {
                 Ok(..) => self.deserialize_i64(visitor),
@@ -138,12 +327,16 @@ where
             },
 
             Header::Bytes(len) => match len {
-                Some(len) if len <= self.scratch.len() => self.deserialize_bytes(visitor),
+                Some(len) if len <= self.scratch.len() || self.buffered.is_some() => {
+                    self.deserialize_bytes(visitor)
+                }
                 _ => self.deserialize_byte_buf(visitor),
             },
 
             Header::Text(len) => match len {
-                Some(len) if len <= self.scratch.len() => self.deserialize_str(visitor),
+                Some(len) if len <= self.scratch.len() || self.buffered.is_some() => {
+                    self.deserialize_str(visitor)
+                }
                 _ => self.deserialize_string(visitor),
             },
 
@@ -151,35 +344,68 @@ where
             Header::Map(..) => self.deserialize_map(visitor),
 
             Header::Tag(tag) => {
+                let offset = self.decoder.offset();
                 let _: Header = self.decoder.pull()?;
 
-                // Peek at the next item.
-                let header = self.decoder.pull()?;
-                self.decoder.push(header);
-
-                // If it is bytes, capture the length.
-                let len = match header {
-                    Header::Bytes(x) => x,
+                // If the next item is bytes, capture the length.
+                let len = match self.decoder.peek()? {
+                    Header::Bytes(x) => Some(x),
                     _ => None,
                 };
 
-                match (tag, len) {
-                    (tag::BIGPOS, Some(len)) | (tag::BIGNEG, Some(len)) if len <= 16 => {
-                        let result = match self.integer(Some(Header::Tag(tag)))? {
-                            (false, raw) => return visitor.visit_u128(raw),
-                            (true, raw) => i128::try_from(raw).map(|x| x ^ !0),
-                        };
+                let (neg, raw) = match (tag, len) {
+                    (tag::BIGPOS, Some(Some(len))) | (tag::BIGNEG, Some(Some(len)))
+                        if len <= 16 =>
+                    {
+                        self.integer(Some(Header::Tag(tag)))?
+                    }
+
+                    // The length of chunked bytes is only known once they
+                    // are read, so they are read first either way.
+                    (tag::BIGPOS, Some(None)) | (tag::BIGNEG, Some(None)) => {
+                        let _: Header = self.decoder.pull()?;
+                        let bytes = self.byte_buf(None)?;
+
+                        if bytes.len() > 16 {
+                            if self.deny_tags {
+                                return Err(Deserializer::<R>::unexpected_tag(offset, tag));
+                            }
 
-                        match result {
-                            Ok(x) => visitor.visit_i128(x),
-                            Err(..) => Err(de::Error::custom("integer too large")),
+                            let bytes = de::value::BytesDeserializer::new(&bytes);
+                            return visitor
+                                .visit_enum(crate::tag::TagAccess::new(bytes, Some(tag)));
                         }
+
+                        let mut raw = [0u8; 16];
+                        raw[16 - bytes.len()..].copy_from_slice(&bytes);
+                        (tag == tag::BIGNEG, u128::from_be_bytes(raw))
                     }
 
-                    _ => self.recurse(|me| {
-                        let access = crate::tag::TagAccess::new(me, Some(tag));
-                        visitor.visit_enum(access)
-                    }),
+                    _ if self.deny_tags => {
+                        return Err(Deserializer::<R>::unexpected_tag(offset, tag))
+                    }
+
+                    _ => return visitor.visit_enum(crate::tag::TagAccess::new(self, Some(tag))),
+                };
+
+                let result = match (neg, raw) {
+                    (false, raw) => return visitor.visit_u128(raw),
+                    (true, raw) => i128::try_from(raw).map(|x| x ^ !0),
+                };
+
+                match result {
+                    Ok(x) => visitor.visit_i128(x),
+
+                    // Like a longer bignum, a negative one below `i128::MIN`
+                    // stays tagged. It has all 16 bytes, so `raw` has them.
+                    Err(..) if self.deny_tags => {
+                        Err(Deserializer::<R>::unexpected_tag(offset, tag))
+                    }
+                    Err(..) => {
+                        let bytes = raw.to_be_bytes();
+                        let bytes = de::value::BytesDeserializer::new(&bytes);
+                        visitor.visit_enum(crate::tag::TagAccess::new(bytes, Some(tag)))
+                    }
                 }
             }
 
@@ -189,9 +415,14 @@ where
             Header::Simple(simple::TRUE) => self.deserialize_bool(visitor),
             Header::Simple(simple::NULL) => self.deserialize_option(visitor),
             Header::Simple(simple::UNDEFINED) => self.deserialize_option(visitor),
-            h @ Header::Simple(..) => Err(h.expected("known simple value")),
+            header @ Header::Simple(..) => {
+                Err(header.expected(self.decoder.offset(), "known simple value"))
+            }
 
-            h @ Header::Break => Err(h.expected("non-break")),
+            Header::Break => Err(Error::UnexpectedBreak {
+                offset: self.decoder.offset(),
+            }
+            .into()),
         }
     }
 
@@ -200,11 +431,11 @@ where
         loop {
             let offset = self.decoder.offset();
 
-            return match self.decoder.pull()? {
+            return match self.pull()? {
                 Header::Tag(..) => continue,
                 Header::Simple(simple::FALSE) => visitor.visit_bool(false),
                 Header::Simple(simple::TRUE) => visitor.visit_bool(true),
-                _ => Err(Error::semantic(offset, "expected bool")),
+                header => Err(header.expected(offset, "bool")),
             };
         }
     }
@@ -217,10 +448,12 @@ where
     #[inline]
     fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
                 Header::Tag(..) => continue,
                 Header::Float(x) => visitor.visit_f64(x),
-                h => Err(h.expected("float")),
+                h => Err(h.expected(offset, "float")),
             };
         }
     }
@@ -274,9 +507,10 @@ where
     }
 
     fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let offset = self.decoder.offset();
         let result = match self.integer(None)? {
             (false, raw) => u64::try_from(raw),
-            (true, ..) => return Err(de::Error::custom("unexpected negative integer")),
+            (true, ..) => return Err(Deserializer::<R>::negative(offset)),
         };
 
         match result {
@@ -286,16 +520,17 @@ where
     }
 
     fn deserialize_u128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let offset = self.decoder.offset();
         match self.integer(None)? {
             (false, raw) => visitor.visit_u128(raw),
-            (true, ..) => Err(de::Error::custom("unexpected negative integer")),
+            (true, ..) => Err(Deserializer::<R>::negative(offset)),
         }
     }
 
     fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
             let offset = self.decoder.offset();
-            let header = self.decoder.pull()?;
+            let header = self.pull()?;
 
             return match header {
                 Header::Tag(..) => continue,
@@ -307,13 +542,13 @@ where
                     match core::str::from_utf8(&buf[..len]) {
                         Ok(s) => match s.chars().count() {
                             1 => visitor.visit_char(s.chars().next().unwrap()),
-                            _ => Err(header.expected("char")),
+                            _ => Err(header.expected(offset, "char")),
                         },
-                        Err(..) => Err(Error::Syntax(offset)),
+                        Err(..) => Err(Error::InvalidUtf8 { chunk: 0, offset }.into()),
                     }
                 }
 
-                _ => Err(header.expected("char")),
+                _ => Err(header.expected(offset, "char")),
             };
         }
     }
@@ -322,57 +557,108 @@ where
         loop {
             let offset = self.decoder.offset();
 
-            return match self.decoder.pull()? {
+            return match self.pull()? {
                 Header::Tag(..) => continue,
 
-                Header::Text(Some(len)) if len <= self.scratch.len() => {
+                Header::Text(Some(len)) if self.buffered.is_some() || len <= self.scratch.len() => {
+                    if let Some(bytes) = self.borrowed(len) {
+                        return match core::str::from_utf8(bytes) {
+                            Ok(s) => {
+                                self.key.keep(s);
+                                visitor.visit_borrowed_str(s)
+                            }
+                            Err(..) => Err(Error::InvalidUtf8 { chunk: 0, offset }.into()),
+                        };
+                    }
+
+                    if let Some(buffered) = self.buffered {
+                        if let Some(bytes) = (buffered.contiguous)(&mut self.decoder, len)? {
+                            let result = match core::str::from_utf8(bytes) {
+                                Ok(s) => {
+                                    self.key.keep(s);
+                                    visitor.visit_str(s)
+                                }
+                                Err(..) => Err(Error::InvalidUtf8 { chunk: 0, offset }.into()),
+                            };
+
+                            (buffered.consume)(&mut self.decoder, len);
+                            return result;
+                        }
+                    }
+
+                    if len > self.scratch.len() {
+                        return visitor.visit_string(self.string(Some(len))?);
+                    }
+
                     self.decoder.read_exact(&mut self.scratch[..len])?;
 
                     match core::str::from_utf8(&self.scratch[..len]) {
-                        Ok(s) => visitor.visit_str(s),
-                        Err(..) => Err(Error::Syntax(offset)),
+                        Ok(s) => {
+                            self.key.keep(s);
+                            visitor.visit_str(s)
+                        }
+                        Err(..) => Err(Error::InvalidUtf8 { chunk: 0, offset }.into()),
                     }
                 }
 
-                header => Err(header.expected("str")),
+                // Strings which do not fit in the scratch buffer are streamed
+                // into an allocation instead.
+                Header::Text(len) => visitor.visit_string(self.string(len)?),
+
+                header => Err(header.expected(offset, "str")),
             };
         }
     }
 
     fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
-                Header::Tag(..) => continue,
-
-                Header::Text(len) => {
-                    let mut buffer = String::new();
+            let offset = self.decoder.offset();
 
-                    let mut segments = self.decoder.text(len);
-                    while let Some(mut segment) = segments.pull()? {
-                        while let Some(chunk) = segment.pull(&mut self.scratch[..])? {
-                            buffer.push_str(chunk);
-                        }
-                    }
+            return match self.pull()? {
+                Header::Tag(..) => continue,
 
-                    visitor.visit_string(buffer)
-                }
+                Header::Text(len) => visitor.visit_string(self.string(len)?),
 
-                header => Err(header.expected("string")),
+                header => Err(header.expected(offset, "string")),
             };
         }
     }
 
     fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
                 Header::Tag(..) => continue,
 
-                Header::Bytes(Some(len)) if len <= self.scratch.len() => {
+                Header::Bytes(Some(len))
+                    if self.buffered.is_some() || len <= self.scratch.len() =>
+                {
+                    if let Some(bytes) = self.borrowed(len) {
+                        return visitor.visit_borrowed_bytes(bytes);
+                    }
+
+                    if let Some(buffered) = self.buffered {
+                        if let Some(bytes) = (buffered.contiguous)(&mut self.decoder, len)? {
+                            let result = visitor.visit_bytes(bytes);
+                            (buffered.consume)(&mut self.decoder, len);
+                            return result;
+                        }
+                    }
+
+                    if len > self.scratch.len() {
+                        return visitor.visit_byte_buf(self.byte_buf(Some(len))?);
+                    }
+
                     self.decoder.read_exact(&mut self.scratch[..len])?;
                     visitor.visit_bytes(&self.scratch[..len])
                 }
 
-                header => Err(header.expected("bytes")),
+                // Bytes which do not fit in the scratch buffer are streamed
+                // into an allocation instead.
+                Header::Bytes(len) => visitor.visit_byte_buf(self.byte_buf(len)?),
+
+                header => Err(header.expected(offset, "bytes")),
             };
         }
     }
@@ -382,53 +668,50 @@ where
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
-                Header::Tag(..) => continue,
-
-                Header::Bytes(len) => {
-                    let mut buffer = Vec::new();
+            let offset = self.decoder.offset();
 
-                    let mut segments = self.decoder.bytes(len);
-                    while let Some(mut segment) = segments.pull()? {
-                        while let Some(chunk) = segment.pull(&mut self.scratch[..])? {
-                            buffer.extend_from_slice(chunk);
-                        }
-                    }
+            return match self.pull()? {
+                Header::Tag(..) => continue,
 
-                    visitor.visit_byte_buf(buffer)
-                }
+                Header::Bytes(len) => visitor.visit_byte_buf(self.byte_buf(len)?),
 
-                header => Err(header.expected("expected byte buffer")),
+                header => Err(header.expected(offset, "byte buffer")),
             };
         }
     }
 
     fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
                 Header::Tag(..) => continue,
 
-                Header::Array(len) => self.recurse(|me| {
-                    let access = Access(me, len);
-                    visitor.visit_seq(access)
-                }),
+                Header::Array(len) => {
+                    let mut access = Access(self, len, Declared { offset, len }, 0);
+                    let value = access.open(|access| visitor.visit_seq(access))?;
+                    access.end().map(|()| value)
+                }
 
-                header => Err(header.expected("array")),
+                header => Err(header.expected(offset, "array")),
             };
         }
     }
 
     fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
                 Header::Tag(..) => continue,
 
-                Header::Map(len) => self.recurse(|me| {
-                    let access = Access(me, len);
-                    visitor.visit_map(access)
-                }),
+                Header::Map(len) => {
+                    let mut access = Access(self, len, Declared { offset, len }, 0);
+                    let value = access.open(|access| visitor.visit_map(access))?;
+                    access.end().map(|()| value)
+                }
 
-                header => Err(header.expected("map")),
+                header => Err(header.expected(offset, "map")),
             };
         }
     }
@@ -470,14 +753,24 @@ where
         self,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        self.deserialize_any(visitor)
+        let offset = self.decoder.offset();
+        self.warn(offset, WarningKind::IgnoredItem);
+
+        // Nothing is built from the item, so it is only skipped over.
+        self.skip()?;
+        visitor.visit_unit()
     }
 
     #[inline]
     fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
-                Header::Simple(simple::UNDEFINED) => visitor.visit_none(),
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
+                Header::Simple(simple::UNDEFINED) => {
+                    self.warn(offset, WarningKind::Undefined);
+                    visitor.visit_none()
+                }
                 Header::Simple(simple::NULL) => visitor.visit_none(),
                 Header::Tag(..) => continue,
                 header => {
@@ -491,11 +784,16 @@ where
     #[inline]
     fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         loop {
-            return match self.decoder.pull()? {
-                Header::Simple(simple::UNDEFINED) => visitor.visit_unit(),
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
+                Header::Simple(simple::UNDEFINED) => {
+                    self.warn(offset, WarningKind::Undefined);
+                    visitor.visit_unit()
+                }
                 Header::Simple(simple::NULL) => visitor.visit_unit(),
                 Header::Tag(..) => continue,
-                header => Err(header.expected("unit")),
+                header => Err(header.expected(offset, "unit")),
             };
         }
     }
@@ -512,9 +810,26 @@ where
     #[inline]
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
+        if name == crate::value::raw::NAME {
+            return self.raw(visitor);
+        }
+
+        // Only a `Value` can hold undefined apart from null, or any other
+        // simple value, so only it is given them as such.
+        if name == crate::simple::VALUE {
+            return match self.decoder.peek()? {
+                Header::Simple(x) if !matches!(x, simple::FALSE | simple::TRUE | simple::NULL) => {
+                    let _: Header = self.decoder.pull()?;
+                    visitor.visit_enum(crate::simple::SimpleAccess::new(x))
+                }
+
+                _ => self.deserialize_any(visitor),
+            };
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -525,7 +840,7 @@ where
         _variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        if name == "@@TAG@@" {
+        if name == crate::tag::protocol::NAME {
             let tag = match self.decoder.pull()? {
                 Header::Tag(x) => Some(x),
                 header => {
@@ -534,24 +849,25 @@ where
                 }
             };
 
-            return self.recurse(|me| {
-                let access = crate::tag::TagAccess::new(me, tag);
-                visitor.visit_enum(access)
-            });
+            return visitor.visit_enum(crate::tag::TagAccess::new(self, tag));
         }
 
         loop {
-            match self.decoder.pull()? {
+            let offset = self.decoder.offset();
+
+            match self.pull()? {
                 Header::Tag(..) => continue,
                 Header::Map(Some(1)) => (),
                 header @ Header::Text(..) => self.decoder.push(header),
-                header => return Err(header.expected("enum")),
+                header => return Err(header.expected(offset, "enum")),
             }
 
-            return self.recurse(|me| {
-                let access = Access(me, Some(0));
-                visitor.visit_enum(access)
-            });
+            let declared = Declared {
+                offset,
+                len: Some(0),
+            };
+
+            return visitor.visit_enum(Access(self, Some(0), declared, 0));
         }
     }
 
@@ -561,13 +877,92 @@ where
     }
 }
 
-struct Access<'a, 'b, R: Read>(&'a mut Deserializer<'b, R>, Option<usize>);
+/// The header of an array or map, for checking how many items it has
+#[derive(Copy, Clone)]
+struct Declared {
+    offset: usize,
+    len: Option<usize>,
+}
+
+/// The items left, if the length is definite, and the items taken so far
+struct Access<'a, 'de, 'b, R: Read>(
+    &'a mut Deserializer<'de, 'b, R>,
+    Option<usize>,
+    Declared,
+    usize,
+);
 
-impl<'de, 'a, 'b, R: Read> de::SeqAccess<'de> for Access<'a, 'b, R>
+impl<'a, 'de, 'b, R: Read> Access<'a, 'de, 'b, R> {
+    /// Runs the visitor, counting an indefinite-length container as open
+    #[inline]
+    fn open<T>(&mut self, visit: impl FnOnce(&mut Self) -> T) -> T {
+        let indefinite = self.2.len.is_none() as usize;
+
+        self.0.indefinite += indefinite;
+        let value = visit(self);
+        self.0.indefinite -= indefinite;
+        value
+    }
+
+    /// The number of items or entries which were taken so far
+    #[inline]
+    fn taken(&self) -> (usize, usize) {
+        let declared = self.2.len.unwrap_or(0);
+        (declared, declared - self.1.unwrap_or(0))
+    }
+
+    /// Fails if the next item is a break which ends the container early
+    ///
+    /// A break there belongs to an indefinite-length container around this
+    /// one, if any; otherwise, it is left to fail as an unexpected break.
+    /// The `pending` items taken last are not whole yet.
+    #[inline]
+    fn underflow(&mut self, pending: usize) -> Result<(), Internal<R::Error>> {
+        if self.2.len.is_none() || self.0.indefinite == 0 {
+            return Ok(());
+        }
+
+        let header = self.0.decoder.pull()?;
+        self.0.decoder.push(header);
+
+        match header {
+            Header::Break => {
+                let (declared, taken) = self.taken();
+
+                Err(Internal(Error::ContainerUnderflow {
+                    offset: self.2.offset,
+                    declared,
+                    actual: taken - pending,
+                }))
+            }
+
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if the visitor left some of the declared items
+    #[inline]
+    fn end(&self) -> Result<(), Internal<R::Error>> {
+        match self.1 {
+            Some(0) | None => Ok(()),
+            Some(..) => {
+                let (declared, actual) = self.taken();
+
+                Err(Internal(Error::ContainerOverflow {
+                    offset: self.2.offset,
+                    declared,
+                    actual,
+                }))
+            }
+        }
+    }
+}
+
+impl<'de, 'a, 'b, R: Read> de::SeqAccess<'de> for Access<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn next_element_seed<U: de::DeserializeSeed<'de>>(
@@ -576,14 +971,22 @@ where
     ) -> Result<Option<U::Value>, Self::Error> {
         match self.1 {
             Some(0) => return Ok(None),
-            Some(x) => self.1 = Some(x - 1),
+            Some(x) => {
+                self.underflow(0)?;
+                self.1 = Some(x - 1);
+            }
             None => match self.0.decoder.pull()? {
                 Header::Break => return Ok(None),
                 header => self.0.decoder.push(header),
             },
         }
 
-        seed.deserialize(&mut *self.0).map(Some)
+        let index = self.3;
+        self.3 += 1;
+
+        self.0
+            .within(|| Step::Index(index), |de| seed.deserialize(de))
+            .map(Some)
     }
 
     #[inline]
@@ -592,11 +995,11 @@ where
     }
 }
 
-impl<'de, 'a, 'b, R: Read> de::MapAccess<'de> for Access<'a, 'b, R>
+impl<'de, 'a, 'b, R: Read> de::MapAccess<'de> for Access<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn next_key_seed<K: de::DeserializeSeed<'de>>(
@@ -605,14 +1008,21 @@ where
     ) -> Result<Option<K::Value>, Self::Error> {
         match self.1 {
             Some(0) => return Ok(None),
-            Some(x) => self.1 = Some(x - 1),
+            Some(x) => {
+                self.underflow(0)?;
+                self.1 = Some(x - 1);
+            }
             None => match self.0.decoder.pull()? {
                 Header::Break => return Ok(None),
                 header => self.0.decoder.push(header),
             },
         }
 
-        seed.deserialize(&mut *self.0).map(Some)
+        // The text of the key names the value on the path of warnings.
+        self.0.key.capture = self.0.warnings.is_some();
+        let key = seed.deserialize(&mut *self.0);
+        self.0.key.capture = false;
+        key.map(Some)
     }
 
     #[inline]
@@ -620,7 +1030,11 @@ where
         &mut self,
         seed: V,
     ) -> Result<V::Value, Self::Error> {
-        seed.deserialize(&mut *self.0)
+        // The key was taken, but its entry is not whole yet.
+        self.underflow(1)?;
+
+        let step = self.0.key.step();
+        self.0.within(|| step, |de| seed.deserialize(de))
     }
 
     #[inline]
@@ -629,11 +1043,11 @@ where
     }
 }
 
-impl<'de, 'a, 'b, R: Read> de::EnumAccess<'de> for Access<'a, 'b, R>
+impl<'de, 'a, 'b, R: Read> de::EnumAccess<'de> for Access<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
     type Variant = Self;
 
     #[inline]
@@ -646,11 +1060,11 @@ where
     }
 }
 
-impl<'de, 'a, 'b, R: Read> de::VariantAccess<'de> for Access<'a, 'b, R>
+impl<'de, 'a, 'b, R: Read> de::VariantAccess<'de> for Access<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn unit_variant(self) -> Result<(), Self::Error> {
@@ -684,13 +1098,13 @@ where
     }
 }
 
-struct TagAccess<'a, 'b, R: Read>(&'a mut Deserializer<'b, R>, usize);
+struct TagAccess<'a, 'de, 'b, R: Read>(&'a mut Deserializer<'de, 'b, R>, usize);
 
-impl<'de, 'a, 'b, R: Read> de::Deserializer<'de> for &mut TagAccess<'a, 'b, R>
+impl<'de, 'a, 'b, R: Read> de::Deserializer<'de> for &mut TagAccess<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
@@ -698,7 +1112,7 @@ where
 
         match self.0.decoder.pull()? {
             Header::Tag(x) => visitor.visit_u64(x),
-            _ => Err(Error::semantic(offset, "expected tag")),
+            header => Err(header.expected(offset, "tag")),
         }
     }
 
@@ -715,11 +1129,11 @@ where
     }
 }
 
-impl<'de, 'a, 'b, R: Read> de::SeqAccess<'de> for TagAccess<'a, 'b, R>
+impl<'de, 'a, 'b, R: Read> de::SeqAccess<'de> for TagAccess<'a, 'de, 'b, R>
 where
     R::Error: core::fmt::Debug,
 {
-    type Error = Error<R::Error>;
+    type Error = Internal<R::Error>;
 
     #[inline]
     fn next_element_seed<U: de::DeserializeSeed<'de>>(
@@ -745,19 +1159,461 @@ where
     }
 }
 
+/// Options which control deserialization
+///
+/// The default options match [`from_reader()`].
+///
+/// ```rust
+/// use ciborium::de::{Error, Options};
+/// use ciborium::value::Value;
+///
+/// // 0("2013-03-21T20:04:00Z")
+/// let bytes = b"\xc0\x742013-03-21T20:04:00Z";
+///
+/// let options = Options::new().deny_unexpected_tags(true);
+/// match options.from_reader::<String, _>(&bytes[..]).unwrap_err() {
+///     Error::Semantic(Some(0), msg) => assert_eq!(msg, "unexpected tag 0"),
+///     e => panic!("{:?}", e),
+/// }
+///
+/// // Tags are not expected by a `Value` either.
+/// assert!(options.from_reader::<Value, _>(&bytes[..]).is_err());
+///
+/// // By default, the tag is skipped.
+/// let text: String = Options::new().from_reader(&bytes[..]).unwrap();
+/// assert_eq!(text, "2013-03-21T20:04:00Z");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Options {
+    deny_unexpected_tags: bool,
+    max_items: Option<usize>,
+    max_depth: usize,
+}
+
+impl Default for Options {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            deny_unexpected_tags: false,
+            max_items: None,
+            max_depth: 256,
+        }
+    }
+}
+
+impl Options {
+    /// Creates the default options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether tags which were not asked for are rejected
+    ///
+    /// By default, tags are skipped wherever a type does not ask for one.
+    /// When this is enabled, such a tag is an error which reports the tag
+    /// and its offset instead. Tags are only asked for by the wrappers in
+    /// the [`tag`](crate::tag) module, such as [`Required`](crate::tag::Required)
+    /// and [`Captured`](crate::tag::Captured), and by the types built on
+    /// them. Any further tags inside the data item which such a wrapper
+    /// holds are rejected in turn, unless they are asked for as well.
+    /// Items which are ignored, such as the values of unknown fields, are
+    /// skipped over without being looked into, so tags there never fail.
+    ///
+    /// This applies to [`Value`](crate::value::Value) too, which then only
+    /// decodes untagged data. Bignums (tags 2 and 3) are always accepted
+    /// where an integer is expected, as they are just a larger encoding
+    /// of one.
+    #[inline]
+    pub fn deny_unexpected_tags(mut self, deny: bool) -> Self {
+        self.deny_unexpected_tags = deny;
+        self
+    }
+
+    /// Sets how many headers may be read, or `None` for no limit
+    ///
+    /// Every header of the input counts: each integer, string chunk, tag
+    /// and break, and each array or map start. Input with more headers fails
+    /// with [`Error::LimitExceeded`] for [`LimitKind::Items`], so that a
+    /// single data item cannot
+    /// keep the deserializer busy forever, such as an endless indefinite
+    /// length array of zeros which never gets its break. There is no limit
+    /// by default. See [`Decoder::with_item_budget()`].
+    ///
+    /// ```rust
+    /// use ciborium::de::{Error, LimitKind, Options};
+    ///
+    /// // [1, 2, 3]
+    /// let bytes = [0x83, 0x01, 0x02, 0x03];
+    ///
+    /// let items: Vec<u8> = Options::new().max_items(Some(4)).from_reader(&bytes[..]).unwrap();
+    /// assert_eq!(items, [1, 2, 3]);
+    ///
+    /// let result = Options::new().max_items(Some(3)).from_reader::<Vec<u8>, _>(&bytes[..]);
+    /// assert!(matches!(
+    ///     result,
+    ///     Err(Error::LimitExceeded {
+    ///         kind: LimitKind::Items,
+    ///         limit: 3,
+    ///         observed: 4,
+    ///         offset: 3,
+    ///     })
+    /// ));
+    /// ```
+    #[inline]
+    pub fn max_items(mut self, max_items: Option<usize>) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    /// Sets how deeply arrays, maps and tags may be nested
+    ///
+    /// Each array, map and tag opens a level, and input which nests deeper
+    /// fails with [`Error::LimitExceeded`] for [`LimitKind::Depth`], whose
+    /// kind is [`ErrorKind::RecursionLimit`]. serde recurses into each
+    /// level, so this prevents a stack overflow; the default of 256 levels
+    /// fits comfortably on the stack of a thread. The limit applies to
+    /// [`Options::from_reader_async()`] as well.
+    ///
+    /// ```rust
+    /// use ciborium::de::{Error, ErrorKind, LimitKind, Options};
+    /// use ciborium::value::Value;
+    ///
+    /// // [[[0]]]
+    /// let bytes = [0x81, 0x81, 0x81, 0x00];
+    ///
+    /// let value: Value = Options::new().max_depth(3).from_reader(&bytes[..]).unwrap();
+    /// assert_eq!(value, Value::Array(vec![Value::Array(vec![Value::Array(vec![0.into()])])]));
+    ///
+    /// let error = Options::new().max_depth(2).from_reader::<Value, _>(&bytes[..]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::RecursionLimit);
+    /// assert!(matches!(
+    ///     error,
+    ///     Error::LimitExceeded { kind: LimitKind::Depth, limit: 2, offset: 2, .. },
+    /// ));
+    /// ```
+    #[inline]
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Deserializes as CBOR from a type with [`impl ciborium_io::Read`](ciborium_io::Read)
+    #[inline]
+    pub fn from_reader<'de, T: de::Deserialize<'de>, R: Read>(
+        &self,
+        reader: R,
+    ) -> Result<T, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        self.from_reader_seed(reader, core::marker::PhantomData)
+    }
+
+    /// Deserializes as CBOR from a type with [`impl ciborium_io::Read`](ciborium_io::Read)
+    /// using a [`DeserializeSeed`](serde::de::DeserializeSeed)
+    #[inline]
+    pub fn from_reader_seed<'de, S: de::DeserializeSeed<'de>, R: Read>(
+        &self,
+        reader: R,
+        seed: S,
+    ) -> Result<S::Value, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        self.deserialize(reader, seed, None, None, None)
+    }
+
+    /// Deserializes as CBOR from a type with [`impl ciborium_io::BufRead`](ciborium_io::BufRead)
+    ///
+    /// This is like [`Options::from_reader()`], but strings and byte strings
+    /// which are entirely in the reader's buffer are visited in place,
+    /// rather than being copied into a scratch buffer first. Such strings
+    /// may also be longer than the scratch buffer and still be visited as
+    /// `&str` or `&[u8]`. Other strings are read as usual.
+    #[inline]
+    pub fn from_buf_reader<'de, T: de::Deserialize<'de>, R: BufRead>(
+        &self,
+        reader: R,
+    ) -> Result<T, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        self.deserialize(
+            reader,
+            core::marker::PhantomData,
+            Some(Buffered::new()),
+            None,
+            None,
+        )
+    }
+
+    /// Deserializes as CBOR from a slice, borrowing from it
+    ///
+    /// This is like [`Options::from_buf_reader()`], but strings and byte
+    /// strings of definite length are borrowed from the slice, so types
+    /// like `&str` and [`RawCborRef`](crate::value::RawCborRef) can be
+    /// deserialized. Strings made of several chunks are still copied.
+    ///
+    /// ```rust
+    /// use ciborium::de::Options;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Name<'a> {
+    ///     first: &'a str,
+    /// }
+    ///
+    /// // {"first": "Ada"}
+    /// let bytes = b"\xa1\x65first\x63Ada";
+    ///
+    /// let name: Name = Options::new().from_slice(bytes).unwrap();
+    /// assert_eq!(name.first, "Ada");
+    /// ```
+    #[inline]
+    pub fn from_slice<'de, T: de::Deserialize<'de>>(
+        &self,
+        slice: &'de [u8],
+    ) -> Result<T, Error<<&'de [u8] as Read>::Error>> {
+        self.deserialize(
+            slice,
+            core::marker::PhantomData,
+            Some(Buffered::new()),
+            None,
+            Some(slice),
+        )
+    }
+
+    /// Deserializes some of the fields of a map from a slice, borrowing from it
+    ///
+    /// This is like [`Options::from_slice()`], for a map encoded at the start
+    /// of the slice, but only the entries whose keys are among `fields` are
+    /// passed to the type. The values of all other entries are skipped over
+    /// without being parsed or copied, so few fields can be taken from a
+    /// large map cheaply. Keys which are not text, including tagged ones,
+    /// are never among the fields.
+    ///
+    /// The type is deserialized as a map, such as a struct or a `BTreeMap`,
+    /// and its keys are given the names in `fields`.
+    ///
+    /// ```rust
+    /// use ciborium::de::Options;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Summary<'a> {
+    ///     id: u32,
+    ///     name: &'a str,
+    /// }
+    ///
+    /// // {"id": 7, "readings": [...], "name": "probe"}
+    /// let mut bytes = b"\xa3\x62id\x07\x68readings\x99\x03\xe8".to_vec();
+    /// bytes.extend([0u8; 1000]);
+    /// bytes.extend(b"\x64name\x65probe");
+    ///
+    /// let summary: Summary = Options::new().extract(&bytes, &["id", "name"]).unwrap();
+    /// assert_eq!(summary, Summary { id: 7, name: "probe" });
+    /// ```
+    #[inline]
+    pub fn extract<'de, T: de::Deserialize<'de>>(
+        &self,
+        slice: &'de [u8],
+        fields: &[&str],
+    ) -> Result<T, Error<<&'de [u8] as Read>::Error>> {
+        self.run(slice, Some(Buffered::new()), None, Some(slice), |de| {
+            T::deserialize(extract::Fields(de, fields))
+        })
+    }
+
+    /// Deserializes as CBOR from a type with [`impl ciborium_io::Read`](ciborium_io::Read),
+    /// reporting what was accepted leniently
+    ///
+    /// This is like [`Options::from_reader()`], but each [`Warning`] is
+    /// passed to `on_warning` as soon as it happens, so that the warnings
+    /// before an error are seen too. Input which causes no warning is
+    /// decoded just the same.
+    ///
+    /// ```rust
+    /// use ciborium::de::{Options, WarningKind};
+    ///
+    /// // [1, 32("a")], where the tag is not asked for
+    /// let bytes = [0x82, 0x01, 0xd8, 0x20, 0x61, 0x61];
+    ///
+    /// let mut warnings = Vec::new();
+    /// let value: (u8, String) = Options::new()
+    ///     .from_reader_with_warnings(&bytes[..], |w| warnings.push(w))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(value, (1, "a".into()));
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].kind, WarningKind::IgnoredTag(32));
+    /// assert_eq!(warnings[0].offset, 2);
+    /// assert_eq!(warnings[0].path, "[1]");
+    /// ```
+    #[inline]
+    pub fn from_reader_with_warnings<'de, T: de::Deserialize<'de>, R: Read>(
+        &self,
+        reader: R,
+        mut on_warning: impl FnMut(Warning),
+    ) -> Result<T, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        self.deserialize(
+            reader,
+            core::marker::PhantomData,
+            None,
+            Some(&mut on_warning),
+            None,
+        )
+    }
+
+    fn deserialize<'de, S: de::DeserializeSeed<'de>, R: Read>(
+        &self,
+        reader: R,
+        seed: S,
+        buffered: Option<Buffered<R>>,
+        warnings: Option<&mut dyn FnMut(Warning)>,
+        input: Option<&'de [u8]>,
+    ) -> Result<S::Value, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        self.run(reader, buffered, warnings, input, |de| seed.deserialize(de))
+    }
+
+    /// Runs `func` with a deserializer set up by these options
+    fn run<'de, T, R: Read>(
+        &self,
+        reader: R,
+        buffered: Option<Buffered<R>>,
+        warnings: Option<&mut dyn FnMut(Warning)>,
+        input: Option<&'de [u8]>,
+        func: impl FnOnce(&mut Deserializer<'de, '_, R>) -> Result<T, Internal<R::Error>>,
+    ) -> Result<T, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        let mut scratch = [0; 4096];
+
+        let mut decoder = Decoder::with_max_depth(reader, self.max_depth);
+        if let Some(max_items) = self.max_items {
+            decoder.set_item_budget(max_items);
+        }
+
+        let mut reader = Deserializer {
+            decoder,
+            input,
+            scratch: &mut scratch,
+            deny_tags: self.deny_unexpected_tags,
+            max_depth: self.max_depth,
+            buffered,
+            indefinite: 0,
+            // The sink outlives the scratch buffer, so it is borrowed as long.
+            warnings: warnings.map(|x| x as _),
+            path: Vec::new(),
+            key: KeyText::default(),
+        };
+
+        func(&mut reader).map_err(|e| e.0.eof::<R>(reader.decoder.offset()))
+    }
+
+    /// Deserializes as CBOR from a type with [`impl ciborium_io::AsyncRead`](ciborium_io::AsyncRead)
+    ///
+    /// Since serde is synchronous, this reads the bytes of exactly one data
+    /// item into a buffer first, and then deserializes from the buffer.
+    /// Only the headers are parsed while reading, to find where the item
+    /// ends, so the reader is left just after the item. Memory use is the
+    /// size of the encoded item, in addition to the deserialized value.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<T: de::DeserializeOwned, R: AsyncRead + Unpin>(
+        &self,
+        mut reader: R,
+    ) -> Result<T, Error<R::Error>>
+    where
+        R::Error: core::fmt::Debug,
+    {
+        let mut buffer = Vec::new();
+        frame::read_item(&mut reader, &mut buffer, self.max_depth).await?;
+
+        // The buffer holds a whole data item, so it cannot run out early.
+        self.from_reader(&buffer[..]).map_err(|e| match e {
+            Error::Io(..) => Error::Syntax(buffer.len()),
+            e => e.map_io(|_| unreachable!()),
+        })
+    }
+}
+
 /// Deserializes as CBOR from a type with [`impl ciborium_io::Read`](ciborium_io::Read)
 #[inline]
 pub fn from_reader<'de, T: de::Deserialize<'de>, R: Read>(reader: R) -> Result<T, Error<R::Error>>
 where
     R::Error: core::fmt::Debug,
 {
-    let mut scratch = [0; 4096];
+    Options::new().from_reader(reader)
+}
 
-    let mut reader = Deserializer {
-        decoder: reader.into(),
-        scratch: &mut scratch,
-        recurse: 256,
-    };
+/// Deserializes as CBOR from a type with [`impl ciborium_io::Read`](ciborium_io::Read)
+/// using a [`DeserializeSeed`](serde::de::DeserializeSeed)
+///
+/// This is like [`from_reader()`], but for values which need some state to
+/// be deserialized, such as a [`Dispatcher`](crate::tag::Dispatcher).
+#[inline]
+pub fn from_reader_seed<'de, S: de::DeserializeSeed<'de>, R: Read>(
+    reader: R,
+    seed: S,
+) -> Result<S::Value, Error<R::Error>>
+where
+    R::Error: core::fmt::Debug,
+{
+    Options::new().from_reader_seed(reader, seed)
+}
 
-    T::deserialize(&mut reader)
+/// Deserializes as CBOR from a type with [`impl ciborium_io::BufRead`](ciborium_io::BufRead)
+///
+/// See [`Options::from_buf_reader()`] for how this differs from
+/// [`from_reader()`].
+#[inline]
+pub fn from_buf_reader<'de, T: de::Deserialize<'de>, R: BufRead>(
+    reader: R,
+) -> Result<T, Error<R::Error>>
+where
+    R::Error: core::fmt::Debug,
+{
+    Options::new().from_buf_reader(reader)
+}
+
+/// Deserializes as CBOR from a slice, borrowing from it
+///
+/// See [`Options::from_slice()`] for what is borrowed.
+#[inline]
+pub fn from_slice<'de, T: de::Deserialize<'de>>(
+    slice: &'de [u8],
+) -> Result<T, Error<<&'de [u8] as Read>::Error>> {
+    Options::new().from_slice(slice)
+}
+
+/// Deserializes some of the fields of a map from a slice, borrowing from it
+///
+/// See [`Options::extract()`] for which entries are read.
+#[inline]
+pub fn extract<'de, T: de::Deserialize<'de>>(
+    slice: &'de [u8],
+    fields: &[&str],
+) -> Result<T, Error<<&'de [u8] as Read>::Error>> {
+    Options::new().extract(slice, fields)
+}
+
+/// Deserializes as CBOR from a type with [`impl ciborium_io::AsyncRead`](ciborium_io::AsyncRead)
+///
+/// See [`Options::from_reader_async()`] for how the input is read.
+#[cfg(feature = "async")]
+#[inline]
+pub async fn from_reader_async<T: de::DeserializeOwned, R: AsyncRead + Unpin>(
+    reader: R,
+) -> Result<T, Error<R::Error>>
+where
+    R::Error: core::fmt::Debug,
+{
+    Options::new().from_reader_async(reader).await
 }
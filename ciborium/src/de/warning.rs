@@ -0,0 +1,95 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::string::String;
+use core::fmt::{Display, Formatter, Result, Write};
+
+/// Something which was accepted, but not as it was encoded
+///
+/// The deserializer is lenient by default: it skips what a type does not
+/// ask for and reads some items as close equivalents. Each time it does,
+/// a warning is passed to the sink given to
+/// [`Options::from_reader_with_warnings()`](crate::de::Options::from_reader_with_warnings),
+/// so that the producer of the input can be told. Where there is a strict
+/// mode, such as [`Options::deny_unexpected_tags()`](crate::de::Options::deny_unexpected_tags),
+/// the same input is an error instead.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Warning {
+    /// What was accepted
+    pub kind: WarningKind,
+
+    /// The offset of the header of the item
+    pub offset: usize,
+
+    /// The path to the item, like `accounts[0].name`, or empty for the
+    /// data item itself
+    ///
+    /// Fields and text keys are written by name and array elements by
+    /// index. Other keys are written as `[..]`.
+    pub path: String,
+}
+
+/// The kind of a [`Warning`]
+///
+/// More kinds may be added later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A tag with this number was skipped, as the type did not ask for it
+    ///
+    /// This is an error with [`Options::deny_unexpected_tags()`](crate::de::Options::deny_unexpected_tags).
+    IgnoredTag(u64),
+
+    /// `undefined` was read as `null`, for an option or a unit
+    Undefined,
+
+    /// An item was skipped, such as the value of an unknown field
+    IgnoredItem,
+}
+
+impl Display for WarningKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::IgnoredTag(x) => write!(f, "ignored tag {}", x),
+            Self::Undefined => f.write_str("undefined read as null"),
+            Self::IgnoredItem => f.write_str("ignored item"),
+        }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.path.is_empty() {
+            true => write!(f, "{} at offset {}", self.kind, self.offset),
+            false => write!(f, "{}: {} at offset {}", self.path, self.kind, self.offset),
+        }
+    }
+}
+
+/// A step from a collection into one of its items, on the path to a warning
+pub(super) enum Step {
+    /// A field of a struct, or the value of a map key which is text
+    Field(String),
+
+    /// An element of an array
+    Index(usize),
+
+    /// The value of any other map key
+    Key,
+}
+
+/// Writes the steps as the path of a [`Warning`]
+pub(super) fn path(steps: &[Step]) -> String {
+    let mut path = String::new();
+
+    for step in steps {
+        match step {
+            Step::Field(x) if path.is_empty() => path.push_str(x),
+            Step::Field(x) => write!(path, ".{}", x).unwrap(),
+            Step::Index(x) => write!(path, "[{}]", x).unwrap(),
+            Step::Key => path.push_str("[..]"),
+        }
+    }
+
+    path
+}
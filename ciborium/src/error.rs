@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{de, ser};
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::error::Error as StdError;
+use core::fmt::{Display, Formatter, Result};
+
+/// The message of an I/O error whose type was dropped
+///
+/// The errors of [`de`](crate::de) and [`ser`](crate::ser) are generic over
+/// the error of the reader or writer, so they are only `Send`, `Sync` or
+/// `'static` when that error is. Their `into_owned()` methods replace it by
+/// its message, which is all three, so the result can always be boxed and
+/// sent to another thread.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IoMessage(String);
+
+impl IoMessage {
+    /// Gets the message
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for IoMessage {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Display for IoMessage {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl core::error::Error for IoMessage {}
+
+/// An error of the reader or writer, boxed so that its type is dropped
+type BoxedIo = Box<dyn StdError + Send + Sync>;
+
+/// An error from deserializing or serializing, whatever the reader or writer
+///
+/// The errors of [`de`] and [`ser`] are generic over the error of the reader
+/// or writer, which makes them awkward to return through one application
+/// error. Both convert into this with `?`, as long as the error of the
+/// reader or writer implements `core::error::Error`; it is boxed, and can
+/// still be downcast to its own type. The structure of the error is kept,
+/// so [`de::Error::kind()`] and the offsets are still available.
+///
+/// The result of [`map_io()`](de::Error::map_io) can convert too, which
+/// helps when a reader or writer has an error of its own type.
+///
+/// ```rust
+/// use ciborium::value::Value;
+///
+/// fn transcode(input: &[u8], output: &mut Vec<u8>) -> Result<(), ciborium::Error> {
+///     let value: Value = ciborium::de::from_reader(input)?;
+///     ciborium::ser::into_writer(&value, output)?;
+///     Ok(())
+/// }
+///
+/// let mut output = Vec::new();
+/// transcode(&[0x81, 0x01], &mut output).unwrap();
+/// assert_eq!(output, [0x81, 0x01]);
+///
+/// match transcode(&[0x1c], &mut output).unwrap_err() {
+///     ciborium::Error::De(e) => assert_eq!(e.kind(), ciborium::de::ErrorKind::Syntax),
+///     e => panic!("{}", e),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Deserializing failed
+    De(de::Error<BoxedIo>),
+
+    /// Serializing failed
+    Ser(ser::Error<BoxedIo>),
+}
+
+impl<T: StdError + Send + Sync + 'static> From<de::Error<T>> for Error {
+    #[inline]
+    fn from(value: de::Error<T>) -> Self {
+        Self::De(value.map_io(|e| Box::new(e) as BoxedIo))
+    }
+}
+
+impl<T: StdError + Send + Sync + 'static> From<ser::Error<T>> for Error {
+    #[inline]
+    fn from(value: ser::Error<T>) -> Self {
+        Self::Ser(value.map_io(|e| Box::new(e) as BoxedIo))
+    }
+}
+
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::De(e) => Display::fmt(e, f),
+            Self::Ser(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::De(de::Error::Io(e)) => Some(&**e),
+            Self::Ser(ser::Error::Io(e) | ser::Error::Custom(e)) => Some(&**e),
+            _ => None,
+        }
+    }
+}
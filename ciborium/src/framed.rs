@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Length-prefixed frames of CBOR
+//!
+//! A frame is the length of a data item, as a 4-byte big-endian unsigned
+//! integer, followed by the data item itself. This is the usual way to
+//! send a stream of messages over a transport that has no message
+//! boundaries of its own, such as a TCP connection:
+//!
+//! ```text
+//! +----------------+------------------------+
+//! | length: u32 BE | data item: length bytes |
+//! +----------------+------------------------+
+//! ```
+//!
+//! The length counts only the data item, not itself, and a frame holds
+//! exactly one data item. A stream of frames ends cleanly only between
+//! frames.
+//!
+//! ```rust
+//! use ciborium::framed::{read_frame, write_frame, FrameIter};
+//!
+//! let mut stream = Vec::new();
+//! write_frame(&"hello", &mut stream).unwrap();
+//! write_frame(&[1, 2, 3], &mut stream).unwrap();
+//! assert_eq!(stream[..10], [0, 0, 0, 6, 0x65, b'h', b'e', b'l', b'l', b'o']);
+//!
+//! let mut reader = &stream[..];
+//! let text: String = read_frame(&mut reader, 1024).unwrap();
+//! assert_eq!(text, "hello");
+//!
+//! let rest: Vec<Vec<u8>> = FrameIter::new(reader, 1024).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(rest, [vec![1, 2, 3]]);
+//! ```
+
+use crate::de::{self, LimitKind};
+use crate::ser;
+
+use alloc::{format, vec};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+use ciborium_io::{Counter, Read, Sink, Write};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The number of bytes in the length of a frame
+const PREFIX: usize = 4;
+
+/// Writes a value as one frame
+///
+/// The length of the encoding is found first by serializing the value into
+/// a [`Sink`], which only counts the bytes, so the encoding is never held
+/// in memory. The value is then serialized again, after its length, so it
+/// must serialize the same way both times. An encoding longer than
+/// `u32::MAX` bytes cannot be framed, and fails with [`ser::Error::Value`]
+/// before anything is written.
+pub fn write_frame<T: ?Sized + Serialize, W: Write>(
+    value: &T,
+    writer: W,
+) -> Result<(), ser::Error<W::Error>> {
+    let mut sink = Sink::new();
+    ser::into_writer(value, ByRef(&mut sink)).map_err(|e| e.map_io(|e| match e {}))?;
+
+    let len = match u32::try_from(sink.count()) {
+        Ok(len) => len,
+        Err(..) => {
+            return Err(ser::Error::Value(format!(
+                "an encoding of {} bytes is too long for a frame",
+                sink.count()
+            )))
+        }
+    };
+
+    let mut counter = Counter::new(writer);
+    counter.get_mut().write_all(&len.to_be_bytes())?;
+    ser::into_writer(value, ByRef(&mut counter))?;
+
+    match counter.count() == u64::from(len) {
+        true => Ok(()),
+        false => Err(ser::Error::Value(format!(
+            "the value serialized to {} bytes, after a length of {}",
+            counter.count(),
+            len
+        ))),
+    }
+}
+
+/// A writer which is lent to the serializer, to be used again afterwards
+struct ByRef<'a, W>(&'a mut W);
+
+impl<W: Write> Write for ByRef<'_, W> {
+    type Error = W::Error;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(data)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+}
+
+/// Reads one frame, and deserializes its data item
+///
+/// A frame longer than `max_frame_len` fails with [`de::Error::LimitExceeded`]
+/// for [`LimitKind::FrameLength`], before anything is allocated for it.
+/// Otherwise, the whole frame is read into a buffer, and its data item is
+/// deserialized from there. A frame which holds more than one data item
+/// fails with [`de::Error::Semantic`].
+///
+/// Offsets in errors count from the start of the frame, so the data item
+/// starts at offset 4. Input which ends within the frame fails with
+/// [`de::Error::UnexpectedEof`], while a data item which would go on past
+/// the end of its frame is a syntax error at the end of the frame.
+#[inline]
+pub fn read_frame<T: DeserializeOwned, R: Read>(
+    mut reader: R,
+    max_frame_len: usize,
+) -> Result<T, de::Error<R::Error>> {
+    let mut prefix = [0u8; PREFIX];
+    read(&mut reader, &mut prefix, 0)?;
+    frame(&mut reader, prefix, max_frame_len, 0)
+}
+
+/// Reads `buffer` from the reader, which is at `offset`
+#[inline]
+fn read<R: Read>(
+    reader: &mut R,
+    buffer: &mut [u8],
+    offset: usize,
+) -> Result<(), de::Error<R::Error>> {
+    reader
+        .read_exact(buffer)
+        .map_err(|e| de::Error::Io(e).eof::<R>(offset))
+}
+
+/// Reads the rest of the frame at `offset`, after its length
+fn frame<T: DeserializeOwned, R: Read>(
+    reader: &mut R,
+    prefix: [u8; PREFIX],
+    max_frame_len: usize,
+    offset: usize,
+) -> Result<T, de::Error<R::Error>> {
+    let len = u32::from_be_bytes(prefix);
+
+    let len = match usize::try_from(len) {
+        Ok(len) if len <= max_frame_len => len,
+        _ => {
+            return Err(de::Error::LimitExceeded {
+                kind: LimitKind::FrameLength,
+                limit: max_frame_len as u64,
+                observed: len.into(),
+                offset,
+            })
+        }
+    };
+
+    let mut buffer = vec![0u8; len];
+    read(reader, &mut buffer, offset + PREFIX)?;
+
+    // The whole frame was read, so more input cannot help an item which
+    // runs out of it: the frame is too short for the item.
+    let mut rest = &buffer[..];
+    let value = de::from_buf_reader(&mut rest).map_err(|e| match e {
+        de::Error::Io(..) | de::Error::UnexpectedEof { .. } => {
+            de::Error::Syntax(offset + PREFIX + len)
+        }
+        e => e.map_io(|_| unreachable!()).shifted(offset + PREFIX),
+    })?;
+
+    match rest.len() {
+        0 => Ok(value),
+        n => Err(de::Error::semantic(
+            offset + PREFIX + len - n,
+            "trailing data in the frame",
+        )),
+    }
+}
+
+/// An iterator over the values of the frames of a reader
+///
+/// Each frame is read as by [`read_frame()`], and the iterator ends when
+/// the input ends cleanly between frames. Input which ends within a frame,
+/// even within its length, is an error. Offsets in errors count from the
+/// start of the input. The iterator ends after the first error, as the
+/// input cannot be read any further.
+///
+/// ```rust
+/// use ciborium::de::Error;
+/// use ciborium::framed::FrameIter;
+///
+/// // Two frames holding 1 and 2, and then a frame cut off in its length
+/// let input = [0, 0, 0, 1, 0x01, 0, 0, 0, 1, 0x02, 0, 0];
+///
+/// let mut frames = FrameIter::<_, u8>::new(&input[..], 16);
+/// assert_eq!(frames.next().unwrap().unwrap(), 1);
+/// assert_eq!(frames.next().unwrap().unwrap(), 2);
+/// assert!(matches!(frames.next(), Some(Err(Error::UnexpectedEof { offset: 11 }))));
+/// assert!(frames.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct FrameIter<R, T> {
+    reader: R,
+    max_frame_len: usize,
+    offset: usize,
+    done: bool,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<R: Read, T: DeserializeOwned> FrameIter<R, T> {
+    /// Creates an iterator over frames of at most `max_frame_len` bytes
+    #[inline]
+    pub fn new(reader: R, max_frame_len: usize) -> Self {
+        Self {
+            reader,
+            max_frame_len,
+            offset: 0,
+            done: false,
+            value: PhantomData,
+        }
+    }
+
+    /// The offset of the next frame, which is where the reader is at
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the reader
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn next_frame(&mut self) -> Result<Option<T>, de::Error<R::Error>> {
+        let mut prefix = [0u8; PREFIX];
+
+        // Only the first byte may be missing, for the input to end cleanly.
+        match self.reader.read_exact(&mut prefix[..1]) {
+            Err(e) if R::is_eof(&e) => return Ok(None),
+            result => result.map_err(de::Error::Io)?,
+        }
+
+        read(&mut self.reader, &mut prefix[1..], self.offset + 1)?;
+        let value = frame(&mut self.reader, prefix, self.max_frame_len, self.offset)?;
+
+        self.offset += PREFIX + u32::from_be_bytes(prefix) as usize;
+        Ok(Some(value))
+    }
+}
+
+impl<R: Read, T: DeserializeOwned> Iterator for FrameIter<R, T> {
+    type Item = Result<T, de::Error<R::Error>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = self.next_frame().transpose();
+        self.done = !matches!(next, Some(Ok(..)));
+        next
+    }
+}
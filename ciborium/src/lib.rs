@@ -92,11 +92,126 @@
 
 extern crate alloc;
 
+mod error;
+
+pub use error::{Error, IoMessage};
+
 pub mod de;
+pub mod framed;
+pub mod schema;
 pub mod ser;
 pub mod tag;
 pub mod value;
 
+#[cfg(feature = "cose")]
+pub mod cose;
+
+#[cfg(feature = "cwt")]
+pub mod cwt;
+
+#[cfg(feature = "json")]
+pub mod transcode;
+
+mod simple;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+
+    const fn digit(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    /// Counts the bytes of a hex literal, failing on anything malformed
+    pub const fn hex_len(hex: &str) -> usize {
+        let hex = hex.as_bytes();
+        let mut digits = 0;
+        let mut i = 0;
+
+        while i < hex.len() {
+            if !hex[i].is_ascii_whitespace() {
+                if digit(hex[i]).is_none() {
+                    panic!("invalid hex digit in hex! literal");
+                }
+
+                digits += 1;
+            }
+
+            i += 1;
+        }
+
+        if digits % 2 != 0 {
+            panic!("odd number of hex digits in hex! literal");
+        }
+
+        digits / 2
+    }
+
+    /// Decodes a hex literal already checked by `hex_len()`
+    pub const fn hex<const N: usize>(hex: &str) -> [u8; N] {
+        let hex = hex.as_bytes();
+        let mut bytes = [0u8; N];
+        let mut digits = 0;
+        let mut i = 0;
+
+        while i < hex.len() {
+            if let Some(d) = digit(hex[i]) {
+                bytes[digits / 2] = bytes[digits / 2] << 4 | d;
+                digits += 1;
+            }
+
+            i += 1;
+        }
+
+        bytes
+    }
+}
+
+/// Checks that a decoded value encodes and decodes back to itself
+///
+/// This is the invariant which fuzz targets check: whatever the bytes, if
+/// they decode to a [`Value`](value::Value), encoding that value and
+/// decoding the result must give the same value again. Bytes which do not
+/// decode are ignored. Returns the value, if any, for further checks.
+/// It is only for fuzzing, so it needs the `fuzzing` feature.
+///
+/// ```rust
+/// use ciborium::{fuzz_roundtrip, value::Value};
+///
+/// // A small bignum becomes an integer, which still round-trips.
+/// assert_eq!(fuzz_roundtrip(&[0xc2, 0x41, 0x01]), Some(Value::from(1)));
+/// assert_eq!(fuzz_roundtrip(&[0xff]), None);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the value cannot be encoded, or decodes to a different value.
+#[cfg(feature = "fuzzing")]
+pub fn fuzz_roundtrip(bytes: &[u8]) -> Option<value::Value> {
+    let value: value::Value = de::from_reader(bytes).ok()?;
+
+    let mut encoded = alloc::vec::Vec::new();
+    if let Err(e) = ser::into_writer(&value, &mut encoded) {
+        panic!(
+            "{:?} decoded from {:x?} cannot be encoded: {:?}",
+            value, bytes, e
+        );
+    }
+
+    match de::from_reader::<value::Value, _>(&encoded[..]) {
+        Ok(decoded) if decoded == value => Some(value),
+        x => panic!(
+            "{:?} decoded from {:x?} re-decodes as {:?}",
+            value, bytes, x
+        ),
+    }
+}
+
 /// Build a `Value` conveniently.
 ///
 /// The syntax should be intuitive if you are familiar with JSON. You can also
@@ -114,6 +229,73 @@ pub mod value;
 ///     "extra" => { "numbers" => [8.2341e+4, 0.251425] },
 /// }).unwrap();
 /// ```
+///
+/// In addition to the JSON-like syntax, a few CBOR-specific forms are
+/// supported anywhere a value (or map key) may appear:
+///
+///   * `tag!(TAG, VALUE)` wraps `VALUE` in a `Value::Tag`.
+///   * `bytes!(EXPR)` produces `Value::Bytes` from anything that is
+///     `AsRef<[u8]>`, such as a byte string literal.
+///   * `hex!("01 ff")` produces `Value::Bytes` from a string literal of hex
+///     digits, which may be separated by whitespace. The literal is decoded
+///     at compile time.
+///   * `..EXPR` inside an array splices in every item of an iterator.
+///
+/// ```
+/// use ciborium::cbor;
+/// use ciborium::value::Value;
+///
+/// let extra = vec![2, 3];
+/// let key = "computed";
+///
+/// let value = cbor!({
+///     "uri" => tag!(32, "https://example.com"),
+///     "raw" => bytes!(b"\x01\x02"),
+///     "hash" => hex!("de ad BE EF"),
+///     "list" => [1, ..extra, 4],
+///     key => { "nested" => tag!(6, [true]) },
+/// }).unwrap();
+///
+/// assert_eq!(value, Value::Map(vec![
+///     ("uri".into(), Value::Tag(32, Box::new("https://example.com".into()))),
+///     ("raw".into(), Value::Bytes(vec![1, 2])),
+///     ("hash".into(), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef])),
+///     ("list".into(), Value::Array(vec![1.into(), 2.into(), 3.into(), 4.into()])),
+///     ("computed".into(), Value::Map(vec![
+///         ("nested".into(), Value::Tag(6, Box::new(Value::Array(vec![true.into()])))),
+///     ])),
+/// ]));
+/// ```
+///
+/// A tag requires both a tag number and a value:
+///
+/// ```compile_fail
+/// use ciborium::cbor;
+///
+/// let value = cbor!(tag!(32)).unwrap();
+/// ```
+///
+/// Byte strings must be built from a single expression:
+///
+/// ```compile_fail
+/// use ciborium::cbor;
+///
+/// let value = cbor!(bytes!()).unwrap();
+/// ```
+///
+/// A hex literal must be a string of whole bytes:
+///
+/// ```compile_fail
+/// use ciborium::cbor;
+///
+/// let value = cbor!(hex!("0ff")).unwrap();
+/// ```
+///
+/// ```compile_fail
+/// use ciborium::cbor;
+///
+/// let value = cbor!(hex!("zz")).unwrap();
+/// ```
 #[macro_export]
 macro_rules! cbor {
     (@map {$($key:expr => $val:expr),*} $(,)?) => {{
@@ -142,6 +324,33 @@ macro_rules! cbor {
         )
     };
 
+    (@map {$($key:expr => $val:expr),*} tag!( $($nkey:tt)* ) => $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val),* }
+            cbor!(tag!( $($nkey)* ))? =>
+            $($next)*
+        )
+    };
+
+    (@map {$($key:expr => $val:expr),*} bytes!( $($nkey:tt)* ) => $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val),* }
+            cbor!(bytes!( $($nkey)* ))? =>
+            $($next)*
+        )
+    };
+
+    (@map {$($key:expr => $val:expr),*} hex!( $($nkey:tt)* ) => $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val),* }
+            cbor!(hex!( $($nkey)* ))? =>
+            $($next)*
+        )
+    };
+
     (@map {$($key:expr => $val:expr),*} $nkey:expr => { $($nval:tt)* }, $($next:tt)*) => {
         cbor!(
             @map
@@ -158,24 +367,49 @@ macro_rules! cbor {
         )
     };
 
-    (@map {$($key:expr => $val:expr),*} $nkey:expr => $nval:expr, $($next:tt)*) => {
+    (@map {$($key:expr => $val:expr),*} $nkey:expr => tag!( $($nval:tt)* ), $($next:tt)*) => {
         cbor!(
             @map
-            { $($key => $val,)* $nkey => cbor!($nval)? }
+            { $($key => $val,)* $nkey => cbor!(tag!( $($nval)* ))? }
             $($next)*
         )
     };
 
-    (@seq [$($val:expr),*] $(,)?) => {
-        $crate::value::Value::Array(
-            vec![$( cbor!($val)? ),*]
+    (@map {$($key:expr => $val:expr),*} $nkey:expr => bytes!( $($nval:tt)* ), $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val,)* $nkey => cbor!(bytes!( $($nval)* ))? }
+            $($next)*
+        )
+    };
+
+    (@map {$($key:expr => $val:expr),*} $nkey:expr => hex!( $($nval:tt)* ), $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val,)* $nkey => cbor!(hex!( $($nval)* ))? }
+            $($next)*
+        )
+    };
+
+    (@map {$($key:expr => $val:expr),*} $nkey:expr => $nval:expr, $($next:tt)*) => {
+        cbor!(
+            @map
+            { $($key => $val,)* $nkey => cbor!($nval)? }
+            $($next)*
         )
     };
 
+    (@seq [$($val:expr),*] $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut array = vec![];
+        $( ::core::iter::Extend::extend(&mut array, $val); )*
+        $crate::value::Value::Array(array)
+    }};
+
     (@seq [$($val:expr),*] { $($item:tt)* }, $($next:tt)*) => {
         cbor!(
             @seq
-            [ $($val,)* cbor!({ $($item)* })? ]
+            [ $($val,)* ::core::iter::once(cbor!({ $($item)* })?) ]
             $($next)*
         )
     };
@@ -183,7 +417,48 @@ macro_rules! cbor {
     (@seq [$($val:expr),*] [ $($item:tt)* ], $($next:tt)*) => {
         cbor!(
             @seq
-            [ $($val,)* cbor!([ $($item)* ])? ]
+            [ $($val,)* ::core::iter::once(cbor!([ $($item)* ])?) ]
+            $($next)*
+        )
+    };
+
+    (@seq [$($val:expr),*] tag!( $($item:tt)* ), $($next:tt)*) => {
+        cbor!(
+            @seq
+            [ $($val,)* ::core::iter::once(cbor!(tag!( $($item)* ))?) ]
+            $($next)*
+        )
+    };
+
+    (@seq [$($val:expr),*] bytes!( $($item:tt)* ), $($next:tt)*) => {
+        cbor!(
+            @seq
+            [ $($val,)* ::core::iter::once(cbor!(bytes!( $($item)* ))?) ]
+            $($next)*
+        )
+    };
+
+    (@seq [$($val:expr),*] hex!( $($item:tt)* ), $($next:tt)*) => {
+        cbor!(
+            @seq
+            [ $($val,)* ::core::iter::once(cbor!(hex!( $($item)* ))?) ]
+            $($next)*
+        )
+    };
+
+    (@seq [$($val:expr),*] .. $items:expr, $($next:tt)*) => {
+        cbor!(
+            @seq
+            [
+                $($val,)*
+                {
+                    let mut items = vec![];
+                    for item in $items {
+                        items.push($crate::value::Value::serialized(&item)?);
+                    }
+                    items
+                }
+            ]
             $($next)*
         )
     };
@@ -191,7 +466,7 @@ macro_rules! cbor {
     (@seq [$($val:expr),*] $item:expr, $($next:tt)*) => {
         cbor!(
             @seq
-            [ $($val,)* $item ]
+            [ $($val,)* ::core::iter::once(cbor!($item)?) ]
             $($next)*
         )
     };
@@ -204,6 +479,26 @@ macro_rules! cbor {
         ::core::result::Result::<_, $crate::value::Error>::from(Ok(cbor!(@seq [] $($next)* ,)))
     })()};
 
+    (tag!($tag:expr, $($val:tt)+)) => {(||{
+        ::core::result::Result::<_, $crate::value::Error>::from(Ok($crate::value::Value::Tag(
+            $tag,
+            ::core::convert::From::from(cbor!($($val)+)?),
+        )))
+    })()};
+
+    (hex!($hex:literal)) => {{
+        const BYTES: [u8; $crate::__private::hex_len($hex)] = $crate::__private::hex($hex);
+        ::core::result::Result::<_, $crate::value::Error>::Ok($crate::value::Value::Bytes(
+            BYTES.to_vec(),
+        ))
+    }};
+
+    (bytes!($bytes:expr)) => {
+        ::core::result::Result::<_, $crate::value::Error>::Ok($crate::value::Value::Bytes(
+            ::core::convert::AsRef::<[u8]>::as_ref(&$bytes).to_vec(),
+        ))
+    };
+
     ($val:expr) => {{
         #[allow(unused_imports)]
         use $crate::value::Value::Null as null;
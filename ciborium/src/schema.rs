@@ -0,0 +1,634 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checking the shape of a `Value` against a schema
+//!
+//! A [`Schema`] describes the values which are acceptable, in the spirit of
+//! a small subset of CDDL (RFC 8610): the kinds of values, ranges of
+//! integers, lengths, sets of literals, arrays of an item or of fixed
+//! positions, maps with required and optional keys, tags and choices. A
+//! schema is built up programmatically, and [`Schema::validate()`] checks a
+//! value against it, reporting every [`Violation`] found along with its
+//! path.
+//!
+//! ```rust
+//! use ciborium::{cbor, schema::Schema};
+//!
+//! // { 1 => "ES256" / "EdDSA", ? "kid" => bstr .size (1..=64), "n" => -65536..0 }
+//! let schema = Schema::map()
+//!     .required(1, Schema::literals(["ES256", "EdDSA"]))
+//!     .optional("kid", Schema::bytes().len(1..=64))
+//!     .required("n", Schema::int().range(-65536..0));
+//!
+//! assert!(schema.validate(&cbor!({ 1 => "EdDSA", "n" => -7 }).unwrap()).is_ok());
+//!
+//! let value = cbor!({ 1 => "RS1", "kid" => bytes!(b""), "x" => 0 }).unwrap();
+//! let lines: Vec<_> = schema
+//!     .validate(&value)
+//!     .unwrap_err()
+//!     .iter()
+//!     .map(|v| v.to_string())
+//!     .collect();
+//!
+//! assert_eq!(lines, [
+//!     "$: missing key \"n\"",
+//!     "${1}: value is not one of the allowed values",
+//!     "$.kid: length 0 is out of range",
+//! ]);
+//! ```
+
+use crate::value::{Integer, Path, Step, Value};
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{Display, Formatter, Result};
+use core::ops::{Bound, RangeBounds};
+
+/// A range of integers or lengths, as its bounds
+type Bounds<T> = (Bound<T>, Bound<T>);
+
+/// Converts any range into its bounds
+#[inline]
+fn bounds<T: Copy>(range: impl RangeBounds<T>) -> Bounds<T> {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+/// A description of acceptable values
+///
+/// Schemas are built starting with one of the constructors, which say what
+/// kind of value is accepted, and then narrowed with methods such as
+/// [`range()`](Self::range) or [`required()`](Self::required). Kinds are
+/// matched exactly: a float is not an integer, even when it is whole, and
+/// a tagged value is only accepted by [`Schema::tagged()`] or
+/// [`Schema::any()`].
+///
+/// The methods which narrow a schema only apply to some kinds, and panic
+/// when used on any other, as that is a mistake in the schema rather than
+/// in the value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema(Kind);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Any,
+    Null,
+    Bool,
+    Integer(Bounds<i128>),
+    Float,
+    Text(Bounds<usize>),
+    Bytes(Bounds<usize>),
+    Array(Items, Bounds<usize>),
+    Map(Entries, Bounds<usize>),
+    Literals(Vec<Value>),
+    Tagged(u64, Box<Schema>),
+    Choice(Vec<Schema>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Items {
+    Any,
+    Each(Box<Schema>),
+    Tuple(Vec<Schema>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entries {
+    fields: Vec<Field>,
+    others: Option<Box<(Schema, Schema)>>,
+    closed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Field {
+    key: Value,
+    required: bool,
+    schema: Schema,
+}
+
+const UNBOUNDED: Bounds<usize> = (Bound::Unbounded, Bound::Unbounded);
+
+impl Schema {
+    /// Accepts any value
+    #[inline]
+    pub fn any() -> Self {
+        Self(Kind::Any)
+    }
+
+    /// Accepts only null
+    #[inline]
+    pub fn null() -> Self {
+        Self(Kind::Null)
+    }
+
+    /// Accepts a boolean
+    #[inline]
+    pub fn bool() -> Self {
+        Self(Kind::Bool)
+    }
+
+    /// Accepts an integer, in any range unless narrowed by [`range()`](Self::range)
+    #[inline]
+    pub fn int() -> Self {
+        Self(Kind::Integer((Bound::Unbounded, Bound::Unbounded)))
+    }
+
+    /// Accepts a float, of any precision
+    #[inline]
+    pub fn float() -> Self {
+        Self(Kind::Float)
+    }
+
+    /// Accepts text
+    #[inline]
+    pub fn text() -> Self {
+        Self(Kind::Text(UNBOUNDED))
+    }
+
+    /// Accepts a byte string
+    #[inline]
+    pub fn bytes() -> Self {
+        Self(Kind::Bytes(UNBOUNDED))
+    }
+
+    /// Accepts an array, whatever its elements
+    #[inline]
+    pub fn array() -> Self {
+        Self(Kind::Array(Items::Any, UNBOUNDED))
+    }
+
+    /// Accepts an array whose every element is accepted by `item`
+    #[inline]
+    pub fn array_of(item: Schema) -> Self {
+        Self(Kind::Array(Items::Each(Box::new(item)), UNBOUNDED))
+    }
+
+    /// Accepts an array of exactly as many elements as `items`, each
+    /// accepted by the schema in the same position
+    #[inline]
+    pub fn tuple(items: impl IntoIterator<Item = Schema>) -> Self {
+        Self(Kind::Array(
+            Items::Tuple(items.into_iter().collect()),
+            UNBOUNDED,
+        ))
+    }
+
+    /// Accepts a map, with any entries until keys are added to the schema
+    ///
+    /// Entries whose keys the schema does not name are allowed, unless
+    /// [`others()`](Self::others) or
+    /// [`deny_unknown_keys()`](Self::deny_unknown_keys) say otherwise.
+    #[inline]
+    pub fn map() -> Self {
+        Self(Kind::Map(
+            Entries {
+                fields: Vec::new(),
+                others: None,
+                closed: false,
+            },
+            UNBOUNDED,
+        ))
+    }
+
+    /// Accepts a map whose every key and value are accepted by `key` and `value`
+    #[inline]
+    pub fn map_of(key: Schema, value: Schema) -> Self {
+        Self::map().others(key, value)
+    }
+
+    /// Accepts only a value equal to `value`
+    #[inline]
+    pub fn literal(value: impl Into<Value>) -> Self {
+        Self(Kind::Literals(alloc::vec![value.into()]))
+    }
+
+    /// Accepts only a value equal to one of `values`
+    #[inline]
+    pub fn literals<T: Into<Value>>(values: impl IntoIterator<Item = T>) -> Self {
+        Self(Kind::Literals(values.into_iter().map(Into::into).collect()))
+    }
+
+    /// Accepts a value with the tag `tag`, whose contents `schema` accepts
+    #[inline]
+    pub fn tagged(tag: u64, schema: Schema) -> Self {
+        Self(Kind::Tagged(tag, Box::new(schema)))
+    }
+
+    /// Accepts a value which any of `choices` accepts
+    ///
+    /// When no choice accepts a value, and exactly one of them is of the
+    /// kind of that value, the violations of that choice are reported.
+    /// Otherwise, a single [`ViolationKind::NoChoice`] is.
+    ///
+    /// ```rust
+    /// use ciborium::{schema::Schema, value::Value};
+    ///
+    /// let nullable = Schema::choice([Schema::null(), Schema::int().range(0..10)]);
+    /// assert!(nullable.validate(&Value::Null).is_ok());
+    ///
+    /// let violations = nullable.validate(&Value::from(10)).unwrap_err();
+    /// assert_eq!(violations[0].to_string(), "$: integer 10 is out of range");
+    /// ```
+    #[inline]
+    pub fn choice(choices: impl IntoIterator<Item = Schema>) -> Self {
+        Self(Kind::Choice(choices.into_iter().collect()))
+    }
+
+    /// Narrows an integer schema to the integers in `range`
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a schema made by [`Schema::int()`].
+    #[inline]
+    pub fn range(mut self, range: impl RangeBounds<i128>) -> Self {
+        match &mut self.0 {
+            Kind::Integer(x) => *x = bounds(range),
+            _ => panic!("only an integer schema has a range"),
+        }
+
+        self
+    }
+
+    /// Narrows the length of a text, byte string, array or map schema
+    ///
+    /// The length of text is counted in bytes of UTF-8, and that of a map
+    /// in entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a schema for one of those kinds.
+    #[inline]
+    pub fn len(mut self, range: impl RangeBounds<usize>) -> Self {
+        match &mut self.0 {
+            Kind::Text(x) | Kind::Bytes(x) | Kind::Array(_, x) | Kind::Map(_, x) => {
+                *x = bounds(range)
+            }
+            _ => panic!("only a text, bytes, array or map schema has a length"),
+        }
+
+        self
+    }
+
+    /// Adds a key which a map must have, with a value `schema` accepts
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a map schema.
+    #[inline]
+    pub fn required(self, key: impl Into<Value>, schema: Schema) -> Self {
+        self.field(key.into(), true, schema)
+    }
+
+    /// Adds a key which a map may have, with a value `schema` accepts
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a map schema.
+    #[inline]
+    pub fn optional(self, key: impl Into<Value>, schema: Schema) -> Self {
+        self.field(key.into(), false, schema)
+    }
+
+    /// Checks the entries of a map whose keys the schema does not name
+    ///
+    /// Their keys must be accepted by `key`, and their values by `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a map schema.
+    #[inline]
+    pub fn others(mut self, key: Schema, value: Schema) -> Self {
+        self.entries().others = Some(Box::new((key, value)));
+        self
+    }
+
+    /// Rejects the entries of a map whose keys the schema does not name
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is not a map schema.
+    #[inline]
+    pub fn deny_unknown_keys(mut self) -> Self {
+        self.entries().closed = true;
+        self
+    }
+
+    fn field(mut self, key: Value, required: bool, schema: Schema) -> Self {
+        self.entries().fields.push(Field {
+            key,
+            required,
+            schema,
+        });
+
+        self
+    }
+
+    fn entries(&mut self) -> &mut Entries {
+        match &mut self.0 {
+            Kind::Map(entries, _) => entries,
+            _ => panic!("only a map schema has keys"),
+        }
+    }
+
+    /// Checks a value against the schema
+    ///
+    /// Every violation is reported, in depth-first order, with map entries
+    /// in the order they appear in the value. Checking only descends as
+    /// deep as the schema does, so it is safe for untrusted values.
+    pub fn validate(&self, value: &Value) -> core::result::Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.check(value, &mut Path::default(), &mut violations);
+
+        match violations.is_empty() {
+            true => Ok(()),
+            false => Err(violations),
+        }
+    }
+
+    /// Whether the value is of the kind the schema is for
+    fn admits(&self, value: &Value) -> bool {
+        match (&self.0, value) {
+            (Kind::Any, _) => true,
+            (Kind::Null, Value::Null) => true,
+            (Kind::Bool, Value::Bool(..)) => true,
+            (Kind::Integer(..), Value::Integer(..)) => true,
+            (Kind::Float, Value::Float(..)) => true,
+            (Kind::Text(..), Value::Text(..)) => true,
+            (Kind::Bytes(..), Value::Bytes(..)) => true,
+            (Kind::Array(..), Value::Array(..)) => true,
+            (Kind::Map(..), Value::Map(..)) => true,
+            (Kind::Literals(x), v) => x.iter().any(|x| x.kind_name() == v.kind_name()),
+            (Kind::Tagged(t, _), Value::Tag(x, _)) => t == x,
+            (Kind::Choice(x), v) => x.iter().any(|x| x.admits(v)),
+            _ => false,
+        }
+    }
+
+    fn check(&self, value: &Value, path: &mut Path, violations: &mut Vec<Violation>) {
+        let expected = match (&self.0, value) {
+            (Kind::Any, _)
+            | (Kind::Null, Value::Null)
+            | (Kind::Bool, Value::Bool(..))
+            | (Kind::Float, Value::Float(..)) => return,
+
+            (Kind::Integer(range), Value::Integer(x)) => {
+                if !range.contains(&i128::from(*x)) {
+                    report(violations, path, ViolationKind::OutOfRange(*x));
+                }
+
+                return;
+            }
+
+            (Kind::Text(len), Value::Text(x)) => return check_len(len, x.len(), path, violations),
+            (Kind::Bytes(len), Value::Bytes(x)) => {
+                return check_len(len, x.len(), path, violations)
+            }
+
+            (Kind::Array(items, len), Value::Array(x)) => {
+                check_len(len, x.len(), path, violations);
+
+                match items {
+                    Items::Any => (),
+                    Items::Each(item) => {
+                        for (i, v) in x.iter().enumerate() {
+                            path.push(Step::Index(i));
+                            item.check(v, path, violations);
+                            path.pop();
+                        }
+                    }
+
+                    Items::Tuple(items) => {
+                        if items.len() != x.len() {
+                            report(violations, path, ViolationKind::Length(x.len()));
+                        }
+
+                        for (i, (item, v)) in items.iter().zip(x.iter()).enumerate() {
+                            path.push(Step::Index(i));
+                            item.check(v, path, violations);
+                            path.pop();
+                        }
+                    }
+                }
+
+                return;
+            }
+
+            (Kind::Map(entries, len), Value::Map(x)) => {
+                check_len(len, x.len(), path, violations);
+                return entries.check(x, path, violations);
+            }
+
+            (Kind::Literals(x), v) => {
+                if !x.contains(v) {
+                    report(violations, path, ViolationKind::NotAllowed);
+                }
+
+                return;
+            }
+
+            (Kind::Tagged(t, schema), Value::Tag(x, v)) if t == x => {
+                path.push(Step::Tag(*t));
+                schema.check(v, path, violations);
+                return path.pop();
+            }
+
+            (Kind::Tagged(t, _), v) => {
+                let found = match v {
+                    Value::Tag(x, _) => Some(*x),
+                    _ => None,
+                };
+
+                return report(
+                    violations,
+                    path,
+                    ViolationKind::Tag {
+                        expected: *t,
+                        found,
+                    },
+                );
+            }
+
+            (Kind::Choice(choices), v) => {
+                let mut admitted = None;
+                let mut count = 0;
+
+                for choice in choices {
+                    let mut attempt = Vec::new();
+                    choice.check(v, path, &mut attempt);
+
+                    if attempt.is_empty() {
+                        return;
+                    }
+
+                    if choice.admits(v) {
+                        admitted = Some(attempt);
+                        count += 1;
+                    }
+                }
+
+                match (admitted, count) {
+                    (Some(attempt), 1) => violations.extend(attempt),
+                    _ => report(violations, path, ViolationKind::NoChoice),
+                }
+
+                return;
+            }
+
+            (Kind::Null, _) => "null",
+            (Kind::Bool, _) => "bool",
+            (Kind::Integer(..), _) => "integer",
+            (Kind::Float, _) => "float",
+            (Kind::Text(..), _) => "text",
+            (Kind::Bytes(..), _) => "bytes",
+            (Kind::Array(..), _) => "array",
+            (Kind::Map(..), _) => "map",
+        };
+
+        report(
+            violations,
+            path,
+            ViolationKind::Type {
+                expected,
+                found: value.kind_name(),
+            },
+        );
+    }
+}
+
+/// Reports a violation at the path
+#[inline]
+fn report(violations: &mut Vec<Violation>, path: &Path, kind: ViolationKind) {
+    violations.push(Violation {
+        path: path.clone(),
+        kind,
+    });
+}
+
+/// Reports a length which is not in `len`
+#[inline]
+fn check_len(len: &Bounds<usize>, actual: usize, path: &Path, violations: &mut Vec<Violation>) {
+    if !len.contains(&actual) {
+        report(violations, path, ViolationKind::Length(actual));
+    }
+}
+
+impl Entries {
+    fn check(&self, map: &[(Value, Value)], path: &mut Path, violations: &mut Vec<Violation>) {
+        let mut seen = alloc::vec![false; self.fields.len()];
+
+        for field in self.fields.iter().filter(|f| f.required) {
+            if !map.iter().any(|(k, _)| *k == field.key) {
+                report(violations, path, ViolationKind::Missing(field.key.clone()));
+            }
+        }
+
+        for (k, v) in map {
+            path.push(Step::Key(k.clone()));
+
+            match self.fields.iter().position(|f| f.key == *k) {
+                Some(i) if seen[i] => report(violations, path, ViolationKind::Duplicate),
+
+                Some(i) => {
+                    seen[i] = true;
+                    self.fields[i].schema.check(v, path, violations);
+                }
+
+                None => match &self.others {
+                    Some(others) => {
+                        others.0.check(k, path, violations);
+                        others.1.check(v, path, violations);
+                    }
+
+                    None if self.closed => report(violations, path, ViolationKind::Unknown),
+
+                    None => (),
+                },
+            }
+
+            path.pop();
+        }
+    }
+}
+
+/// How a value fails to match a schema
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ViolationKind {
+    /// The value is of the wrong kind
+    Type {
+        /// The kind which the schema accepts
+        expected: &'static str,
+
+        /// The kind of the value
+        found: &'static str,
+    },
+
+    /// The value does not have the tag which the schema accepts
+    Tag {
+        /// The tag which the schema accepts
+        expected: u64,
+
+        /// The tag of the value, if it has one
+        found: Option<u64>,
+    },
+
+    /// The integer is not in the range of the schema
+    OutOfRange(Integer),
+
+    /// The text, byte string, array or map has this length, which is not
+    /// one the schema allows
+    Length(usize),
+
+    /// The value is not one of the literals of the schema
+    NotAllowed,
+
+    /// The value matches none of the choices of the schema
+    NoChoice,
+
+    /// The map does not have this required key
+    Missing(Value),
+
+    /// The map has an entry whose key the schema does not allow
+    Unknown,
+
+    /// The map has an earlier entry with the same key
+    Duplicate,
+}
+
+/// A single way in which a value fails to match a schema
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    /// The location of the value which does not match
+    ///
+    /// For [`ViolationKind::Missing`], this is the map itself.
+    pub path: Path,
+
+    /// How the value does not match
+    pub kind: ViolationKind,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}: ", self.path)?;
+
+        match &self.kind {
+            ViolationKind::Type { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+
+            ViolationKind::Tag {
+                expected,
+                found: Some(found),
+            } => write!(f, "expected tag {}, found tag {}", expected, found),
+            ViolationKind::Tag { expected, .. } => write!(f, "expected tag {}", expected),
+
+            ViolationKind::OutOfRange(x) => {
+                write!(f, "integer {} is out of range", i128::from(*x))
+            }
+
+            ViolationKind::Length(x) => write!(f, "length {} is out of range", x),
+            ViolationKind::NotAllowed => write!(f, "value is not one of the allowed values"),
+            ViolationKind::NoChoice => write!(f, "value matches none of the choices"),
+            ViolationKind::Missing(x) => write!(f, "missing key {}", x.display_truncated(32)),
+            ViolationKind::Unknown => write!(f, "unknown key"),
+            ViolationKind::Duplicate => write!(f, "duplicate key"),
+        }
+    }
+}
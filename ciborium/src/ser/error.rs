@@ -1,11 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
-use core::fmt::{Debug, Display, Formatter, Result};
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display, Formatter, Result, Write};
+
+use crate::IoMessage;
 
 use serde::ser::{Error as SerError, StdError};
 
 /// An error occurred during serialization
+///
+/// This implements `core::error::Error` when the error of the writer does,
+/// and that error is its `source()`. With the `std` feature, it can also be
+/// turned into a `std::io::Error`, keeping the kind of an I/O error.
+///
+/// The error is `Send`, `Sync` and `'static` whenever the error of the
+/// writer is; [`Error::into_owned()`] makes it so for any writer.
 #[derive(Debug)]
 pub enum Error<T> {
     /// An error occurred while writing bytes
@@ -15,8 +27,77 @@ pub enum Error<T> {
 
     /// An error indicating a value that cannot be serialized
     ///
-    /// Contains a description of the problem.
+    /// Contains a description of the problem. Unless the whole value is at
+    /// fault, the description starts with the path to the offending part.
     Value(String),
+
+    /// An error raised by a `Serialize` impl, kept as it was
+    ///
+    /// This is made by [`Error::custom_boxed()`] or, with the `std` feature,
+    /// by serde's `ser::Error::custom()` when it is given a [`Boxed`] error.
+    /// The error can be downcast to its own type. Unlike [`Error::Value`],
+    /// it has no path to the part of the value which failed.
+    Custom(Box<dyn core::error::Error + Send + Sync>),
+}
+
+impl<T> Error<T> {
+    /// Keeps an application error, so that the caller can downcast it
+    #[inline]
+    pub fn custom_boxed(error: impl Into<Box<dyn core::error::Error + Send + Sync>>) -> Self {
+        Self::Custom(error.into())
+    }
+
+    /// Gets the error of the writer, if writing failed
+    #[inline]
+    pub fn as_io(&self) -> Option<&T> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Takes the error of the writer, if writing failed
+    ///
+    /// Otherwise, the error is returned unchanged.
+    #[inline]
+    pub fn into_io(self) -> core::result::Result<T, Self> {
+        match self {
+            Self::Io(e) => Ok(e),
+            e => Err(e),
+        }
+    }
+
+    /// Replaces the error of the writer by its message
+    ///
+    /// The result is `Send`, `Sync` and `'static`, and implements
+    /// `core::error::Error`, whatever the writer.
+    pub fn into_owned(self) -> Error<IoMessage>
+    where
+        T: Display,
+    {
+        self.map_io(|e| e.to_string().into())
+    }
+
+    /// Changes the error of the writer with `func`, keeping everything else
+    ///
+    /// This unifies the errors of different writers, such as to return them
+    /// through one application error.
+    ///
+    /// ```rust
+    /// use ciborium::ser::{into_writer, Error};
+    ///
+    /// let mut buffer = [0u8; 1];
+    /// let error = into_writer(&"too long", &mut buffer[..]).unwrap_err();
+    /// let error: Error<String> = error.map_io(|e| e.to_string());
+    /// assert!(matches!(error, Error::Io(..)));
+    /// ```
+    pub fn map_io<U>(self, func: impl FnOnce(T) -> U) -> Error<U> {
+        match self {
+            Self::Io(e) => Error::Io(func(e)),
+            Self::Value(msg) => Error::Value(msg),
+            Self::Custom(e) => Error::Custom(e),
+        }
+    }
 }
 
 impl<T> From<T> for Error<T> {
@@ -33,10 +114,238 @@ impl<T: Debug> Display for Error<T> {
     }
 }
 
-impl<T: Debug> StdError for Error<T> {}
+impl<T: StdError + 'static> StdError for Error<T> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Value(..) => None,
+            Self::Custom(e) => Some(&**e),
+        }
+    }
+}
+
+impl<T: StdError + 'static> SerError for Error<T> {
+    fn custom<U: Display>(msg: U) -> Self {
+        match message(msg) {
+            Ok(msg) => Error::Value(msg),
+            Err(e) => Error::Custom(e),
+        }
+    }
+}
+
+/// An application error to be kept by serde's `ser::Error::custom()`
+///
+/// serde only passes the message of an error to the serializer, so a
+/// `Serialize` impl wraps its error in this to keep it. Given to the
+/// serializer of this crate, the error becomes [`Error::Custom`], which
+/// can be downcast; any other serializer sees only its message.
+///
+/// ```rust
+/// use ciborium::ser::{into_writer, Boxed, Error};
+/// use serde::{ser, Serialize, Serializer};
+///
+/// #[derive(Debug)]
+/// struct Locked;
+///
+/// impl std::fmt::Display for Locked {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         f.write_str("the account is locked")
+///     }
+/// }
+///
+/// impl std::error::Error for Locked {}
+///
+/// struct Account;
+///
+/// impl Serialize for Account {
+///     fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+///         Err(ser::Error::custom(Boxed::new(Locked)))
+///     }
+/// }
+///
+/// match into_writer(&Account, Vec::new()).unwrap_err() {
+///     Error::Custom(e) => assert!(e.downcast_ref::<Locked>().is_some()),
+///     e => panic!("unexpected error: {:?}", e),
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub struct Boxed {
+    msg: String,
+    error: core::cell::Cell<Option<Box<dyn core::error::Error + Send + Sync>>>,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Whether `custom()` is formatting a message, to take its error
+    static TAKING: core::cell::Cell<bool> = const { core::cell::Cell::new(false) };
+
+    /// The error taken from a [`Boxed`] message
+    static TAKEN: core::cell::Cell<Option<Box<dyn core::error::Error + Send + Sync>>> =
+        const { core::cell::Cell::new(None) };
+}
+
+#[cfg(feature = "std")]
+impl Boxed {
+    /// Wraps the error
+    #[inline]
+    pub fn new(error: impl Into<Box<dyn core::error::Error + Send + Sync>>) -> Self {
+        let error = error.into();
+
+        Self {
+            msg: error.to_string(),
+            error: Some(error).into(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for Boxed {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_tuple("Boxed").field(&self.msg).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for Boxed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if TAKING.with(|x| x.get()) {
+            if let Some(error) = self.error.take() {
+                TAKEN.with(|x| x.set(Some(error)));
+            }
+        }
+
+        f.write_str(&self.msg)
+    }
+}
+
+/// Formats the message, or takes its error if it was [`Boxed`]
+fn message<U: Display>(
+    msg: U,
+) -> core::result::Result<String, Box<dyn core::error::Error + Send + Sync>> {
+    #[cfg(feature = "std")]
+    {
+        let taking = TAKING.with(|x| x.replace(true));
+        let text = msg.to_string();
+        TAKING.with(|x| x.set(taking));
+
+        match TAKEN.with(|x| x.take()) {
+            Some(error) => Err(error),
+            None => Ok(text),
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    Ok(msg.to_string())
+}
+
+/// Turns the error into an I/O error, so it can be returned from I/O code
+///
+/// The error of the writer is returned as is, so its kind is kept. A value
+/// which cannot be serialized has the kind `InvalidInput`, and wraps this
+/// error.
+#[cfg(feature = "std")]
+impl From<Error<std::io::Error>> for std::io::Error {
+    #[inline]
+    fn from(value: Error<std::io::Error>) -> Self {
+        match value {
+            Error::Io(e) => e,
+            e => Self::new(std::io::ErrorKind::InvalidInput, e),
+        }
+    }
+}
+
+/// A step from a value into one of its parts, on the path to an error
+pub(super) enum Step {
+    /// A field of a struct, a variant, or a map key which is text
+    Field(Cow<'static, str>),
+
+    /// An element of an array, or a map key which is an integer
+    Index(i128),
+
+    /// Any other map key
+    Key,
+}
+
+/// The error returned by the serializer to serde
+///
+/// serde requires its errors to implement `Debug`, which the writer's error
+/// need not do. So the writer's error is never formatted here, and the
+/// wrapper is removed before an [`Error`] is returned to the caller.
+///
+/// While the error is returned through the collections being serialized,
+/// each one adds the step to the part which failed, from the innermost out.
+pub(super) struct Internal<T>(pub Error<T>, Vec<Step>);
+
+impl<T> Internal<T> {
+    #[inline]
+    pub fn value(msg: impl Into<String>) -> Self {
+        Self(Error::Value(msg.into()), Vec::new())
+    }
+
+    /// Adds the step to the part of a collection which failed
+    #[inline]
+    pub fn within(mut self, step: Step) -> Self {
+        // Only a value which cannot be serialized has a path.
+        if let Error::Value(..) = self.0 {
+            self.1.push(step);
+        }
+
+        self
+    }
+
+    /// Gets the error, with the path to the value in front of its message
+    pub fn into_error(self) -> Error<T> {
+        let msg = match self.0 {
+            Error::Value(msg) if !self.1.is_empty() => msg,
+            e => return e,
+        };
+
+        let mut path = String::new();
+        for step in self.1.iter().rev() {
+            match step {
+                Step::Field(x) if path.is_empty() => path.push_str(x),
+                Step::Field(x) => write!(path, ".{}", x).unwrap(),
+                Step::Index(x) => write!(path, "[{}]", x).unwrap(),
+                Step::Key => path.push_str("[..]"),
+            }
+        }
+
+        Error::Value(alloc::format!("{}: {}", path, msg))
+    }
+}
+
+impl<T> From<T> for Internal<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self(Error::Io(value), Vec::new())
+    }
+}
+
+impl<T> Debug for Internal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.0 {
+            Error::Io(..) => f.write_str("Io(..)"),
+            Error::Value(x) => f.debug_tuple("Value").field(x).finish(),
+            Error::Custom(x) => f.debug_tuple("Custom").field(x).finish(),
+        }
+    }
+}
+
+impl<T> Display for Internal<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl<T> StdError for Internal<T> {}
 
-impl<T: Debug> SerError for Error<T> {
+impl<T> SerError for Internal<T> {
     fn custom<U: Display>(msg: U) -> Self {
-        Error::Value(msg.to_string())
+        match message(msg) {
+            Ok(msg) => Self::value(msg),
+            Err(e) => Self(Error::Custom(e), Vec::new()),
+        }
     }
 }
@@ -6,6 +6,13 @@ mod error;
 
 pub use error::Error;
 
+#[cfg(feature = "std")]
+pub use error::Boxed;
+
+use error::{Internal, Step};
+
+use crate::tag::protocol;
+
 use alloc::string::ToString;
 use core::convert::TryFrom;
 
@@ -13,28 +20,26 @@ use ciborium_io::Write;
 use ciborium_ll::*;
 use serde::{ser, Serialize as _};
 
-struct Serializer<W: Write>(Encoder<W>);
+/// The encoder, and whether the next bytes are raw CBOR to write as they are
+struct Serializer<W: Write>(Encoder<W>, bool);
 
 impl<W: Write> From<W> for Serializer<W> {
     #[inline]
     fn from(writer: W) -> Self {
-        Self(writer.into())
+        Self(writer.into(), false)
     }
 }
 
 impl<W: Write> From<Encoder<W>> for Serializer<W> {
     #[inline]
     fn from(writer: Encoder<W>) -> Self {
-        Self(writer)
+        Self(writer, false)
     }
 }
 
-impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::Serializer for &'a mut Serializer<W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     type SerializeSeq = CollectionSerializer<'a, W>;
     type SerializeTuple = CollectionSerializer<'a, W>;
@@ -69,25 +74,20 @@ where
 
     #[inline]
     fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
-        Ok(self.0.push(match v.is_negative() {
-            false => Header::Positive(v as u64),
-            true => Header::Negative(v as u64 ^ !0),
-        })?)
+        self.serialize_i128(v.into())
     }
 
     #[inline]
     fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        if let Ok(header) = Header::from_i128(v) {
+            return Ok(self.0.push(header)?);
+        }
+
         let (tag, raw) = match v.is_negative() {
             false => (tag::BIGPOS, v as u128),
             true => (tag::BIGNEG, v as u128 ^ !0),
         };
 
-        match (tag, u64::try_from(raw)) {
-            (tag::BIGPOS, Ok(x)) => return Ok(self.0.push(Header::Positive(x))?),
-            (tag::BIGNEG, Ok(x)) => return Ok(self.0.push(Header::Negative(x))?),
-            _ => {}
-        }
-
         let bytes = raw.to_be_bytes();
 
         // Skip leading zeros.
@@ -164,6 +164,10 @@ where
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        if core::mem::take(&mut self.1) {
+            return Ok(self.0.write_all(v)?);
+        }
+
         self.0.push(Header::Bytes(v.len().into()))?;
         Ok(self.0.write_all(v)?)
     }
@@ -201,9 +205,16 @@ where
     #[inline]
     fn serialize_newtype_struct<U: ?Sized + ser::Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &U,
     ) -> Result<(), Self::Error> {
+        if name == crate::value::raw::NAME {
+            self.1 = true;
+            let result = value.serialize(&mut *self);
+            self.1 = false;
+            return result;
+        }
+
         value.serialize(self)
     }
 
@@ -215,22 +226,28 @@ where
         variant: &'static str,
         value: &U,
     ) -> Result<(), Self::Error> {
-        if name != "@@TAG@@" || variant != "@@UNTAGGED@@" {
-            self.0.push(Header::Map(Some(1)))?;
-            self.serialize_str(variant)?;
+        if name == crate::simple::NAME && variant == crate::simple::NAME {
+            return match value.serialize(crate::tag::Serializer).map(u8::try_from) {
+                Ok(Ok(x)) if !(24..32).contains(&x) => Ok(self.0.push(Header::Simple(x))?),
+                _ => Err(Internal::value("invalid simple value")),
+            };
         }
 
-        value.serialize(self)
+        if name == protocol::NAME && variant == protocol::UNTAGGED {
+            return value.serialize(self);
+        }
+
+        self.0.push(Header::Map(Some(1)))?;
+        self.serialize_str(variant)?;
+        value
+            .serialize(self)
+            .map_err(|e| e.within(Step::Field(variant.into())))
     }
 
     #[inline]
     fn serialize_seq(self, length: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
         self.0.push(Header::Array(length))?;
-        Ok(CollectionSerializer {
-            encoder: self,
-            ending: length.is_none(),
-            tag: false,
-        })
+        Ok(CollectionSerializer::new(self, length.is_none()))
     }
 
     #[inline]
@@ -256,10 +273,10 @@ where
         length: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
         match (name, variant) {
-            ("@@TAG@@", "@@TAGGED@@") => Ok(CollectionSerializer {
-                encoder: self,
-                ending: false,
+            (protocol::NAME, protocol::TAGGED) => Ok(CollectionSerializer {
                 tag: true,
+                indexed: false,
+                ..CollectionSerializer::new(self, false)
             }),
 
             _ => {
@@ -267,9 +284,8 @@ where
                 self.serialize_str(variant)?;
                 self.0.push(Header::Array(Some(length)))?;
                 Ok(CollectionSerializer {
-                    encoder: self,
-                    ending: false,
-                    tag: false,
+                    variant: Some(variant),
+                    ..CollectionSerializer::new(self, false)
                 })
             }
         }
@@ -278,11 +294,7 @@ where
     #[inline]
     fn serialize_map(self, length: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         self.0.push(Header::Map(length))?;
-        Ok(CollectionSerializer {
-            encoder: self,
-            ending: length.is_none(),
-            tag: false,
-        })
+        Ok(CollectionSerializer::new(self, length.is_none()))
     }
 
     #[inline]
@@ -292,11 +304,7 @@ where
         length: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
         self.0.push(Header::Map(Some(length)))?;
-        Ok(CollectionSerializer {
-            encoder: self,
-            ending: false,
-            tag: false,
-        })
+        Ok(CollectionSerializer::new(self, false))
     }
 
     #[inline]
@@ -311,9 +319,8 @@ where
         self.serialize_str(variant)?;
         self.0.push(Header::Map(Some(length)))?;
         Ok(CollectionSerializer {
-            encoder: self,
-            ending: false,
-            tag: false,
+            variant: Some(variant),
+            ..CollectionSerializer::new(self, false)
         })
     }
 
@@ -336,72 +343,121 @@ macro_rules! end {
     };
 }
 
+/// The step to the value of a map entry with this key
+fn entry<K: ?Sized + ser::Serialize>(key: &K) -> Step {
+    match crate::value::Value::serialized(key) {
+        Ok(crate::value::Value::Text(x)) => Step::Field(x.into()),
+        Ok(crate::value::Value::Integer(x)) => Step::Index(x.into()),
+        _ => Step::Key,
+    }
+}
+
 struct CollectionSerializer<'a, W: Write> {
     encoder: &'a mut Serializer<W>,
     ending: bool,
     tag: bool,
+
+    /// Whether the elements are in the path of an error, by their index
+    indexed: bool,
+
+    /// The variant whose fields these are, if any
+    variant: Option<&'static str>,
+
+    /// The index of the next element
+    index: usize,
+}
+
+impl<'a, W: Write> CollectionSerializer<'a, W> {
+    #[inline]
+    fn new(encoder: &'a mut Serializer<W>, ending: bool) -> Self {
+        Self {
+            encoder,
+            ending,
+            tag: false,
+            indexed: true,
+            variant: None,
+            index: 0,
+        }
+    }
+
+    /// Serializes a part of the collection, which is at `step` in the path
+    #[inline]
+    fn part<U: ?Sized + ser::Serialize>(
+        &mut self,
+        value: &U,
+        step: impl FnOnce() -> Step,
+    ) -> Result<(), Internal<W::Error>> {
+        let variant = self.variant;
+
+        value
+            .serialize(&mut *self.encoder)
+            .map_err(|e| match variant {
+                Some(x) => e.within(step()).within(Step::Field(x.into())),
+                None => e.within(step()),
+            })
+    }
+
+    /// Serializes the next element of an array
+    #[inline]
+    fn element<U: ?Sized + ser::Serialize>(&mut self, value: &U) -> Result<(), Internal<W::Error>> {
+        let index = self.index;
+        self.index += 1;
+
+        match self.indexed {
+            true => self.part(value, || Step::Index(index as i128)),
+            false => value.serialize(&mut *self.encoder),
+        }
+    }
 }
 
-impl<'a, W: Write> ser::SerializeSeq for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeSeq for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_element<U: ?Sized + ser::Serialize>(
         &mut self,
         value: &U,
     ) -> Result<(), Self::Error> {
-        value.serialize(&mut *self.encoder)
+        self.element(value)
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeTuple for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeTuple for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_element<U: ?Sized + ser::Serialize>(
         &mut self,
         value: &U,
     ) -> Result<(), Self::Error> {
-        value.serialize(&mut *self.encoder)
+        self.element(value)
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeTupleStruct for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeTupleStruct for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_field<U: ?Sized + ser::Serialize>(
         &mut self,
         value: &U,
     ) -> Result<(), Self::Error> {
-        value.serialize(&mut *self.encoder)
+        self.element(value)
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeTupleVariant for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeTupleVariant for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_field<U: ?Sized + ser::Serialize>(
@@ -409,25 +465,22 @@ where
         value: &U,
     ) -> Result<(), Self::Error> {
         if !self.tag {
-            return value.serialize(&mut *self.encoder);
+            return self.element(value);
         }
 
         self.tag = false;
         match value.serialize(crate::tag::Serializer) {
             Ok(x) => Ok(self.encoder.0.push(Header::Tag(x))?),
-            _ => Err(Error::Value("expected tag".into())),
+            _ => Err(Internal::value("expected tag")),
         }
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeMap for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeMap for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_key<U: ?Sized + ser::Serialize>(&mut self, key: &U) -> Result<(), Self::Error> {
@@ -439,18 +492,25 @@ where
         &mut self,
         value: &U,
     ) -> Result<(), Self::Error> {
-        value.serialize(&mut *self.encoder)
+        self.part(value, || Step::Key)
+    }
+
+    #[inline]
+    fn serialize_entry<K: ?Sized + ser::Serialize, U: ?Sized + ser::Serialize>(
+        &mut self,
+        key: &K,
+        value: &U,
+    ) -> Result<(), Self::Error> {
+        key.serialize(&mut *self.encoder)?;
+        self.part(value, || entry(key))
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeStruct for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeStruct for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_field<U: ?Sized + ser::Serialize>(
@@ -459,19 +519,15 @@ where
         value: &U,
     ) -> Result<(), Self::Error> {
         key.serialize(&mut *self.encoder)?;
-        value.serialize(&mut *self.encoder)?;
-        Ok(())
+        self.part(value, || Step::Field(key.into()))
     }
 
     end!();
 }
 
-impl<'a, W: Write> ser::SerializeStructVariant for CollectionSerializer<'a, W>
-where
-    W::Error: core::fmt::Debug,
-{
+impl<'a, W: Write> ser::SerializeStructVariant for CollectionSerializer<'a, W> {
     type Ok = ();
-    type Error = Error<W::Error>;
+    type Error = Internal<W::Error>;
 
     #[inline]
     fn serialize_field<U: ?Sized + ser::Serialize>(
@@ -480,22 +536,93 @@ where
         value: &U,
     ) -> Result<(), Self::Error> {
         key.serialize(&mut *self.encoder)?;
-        value.serialize(&mut *self.encoder)
+        self.part(value, || Step::Field(key.into()))
     }
 
     end!();
 }
 
 /// Serializes as CBOR into a type with [`impl ciborium_io::Write`](ciborium_io::Write)
+///
+/// If a part of the value cannot be serialized, the message of
+/// [`Error::Value`] starts with the path to that part, made of struct fields,
+/// variants, map keys and array indices, like `peers[3].addr: <message>`.
+///
+/// Serialization cannot be resumed after the writer fails, since part of
+/// the value may already have been written. To use a nonblocking writer,
+/// wrap it in [`ciborium_io::BlockingAdapter`] or serialize into a
+/// `Vec<u8>` first.
 #[inline]
 pub fn into_writer<T: ?Sized + ser::Serialize, W: Write>(
     value: &T,
     writer: W,
-) -> Result<(), Error<W::Error>>
-where
-    W::Error: core::fmt::Debug,
-{
+) -> Result<(), Error<W::Error>> {
     let mut encoder = Serializer::from(writer);
-    value.serialize(&mut encoder)?;
+    value
+        .serialize(&mut encoder)
+        .map_err(Internal::into_error)?;
     Ok(encoder.0.flush()?)
 }
+
+/// Collects the output of a serializer in memory
+#[cfg(feature = "async")]
+struct Buffer<'a>(&'a mut alloc::vec::Vec<u8>);
+
+#[cfg(feature = "async")]
+impl Write for Buffer<'_> {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.0.extend_from_slice(data);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes as CBOR into a type with [`impl ciborium_io::AsyncWrite`](ciborium_io::AsyncWrite)
+///
+/// Since serde is synchronous, the value is serialized into a buffer first,
+/// which is then written and flushed. Memory use is the size of the
+/// encoded value. Nothing is written if serialization fails. A write which
+/// accepts no bytes fails with the writer's
+/// [`write_zero()`](ciborium_io::AsyncWrite::write_zero) error, if it has one.
+#[cfg(feature = "async")]
+pub async fn into_writer_async<T: ?Sized + ser::Serialize, W: ciborium_io::AsyncWrite + Unpin>(
+    value: &T,
+    mut writer: W,
+) -> Result<(), Error<W::Error>> {
+    use core::future::poll_fn;
+    use core::pin::Pin;
+
+    let mut buffer = alloc::vec::Vec::new();
+    if let Err(e) = into_writer(value, Buffer(&mut buffer)) {
+        return Err(match e {
+            Error::Io(x) => match x {},
+            Error::Value(x) => Error::Value(x),
+            Error::Custom(x) => Error::Custom(x),
+        });
+    }
+
+    let mut data = &buffer[..];
+    while !data.is_empty() {
+        let n = poll_fn(|cx| Pin::new(&mut writer).poll_write(cx, data)).await?;
+
+        // A writer which accepts nothing would be polled forever, so this
+        // fails, as `std::io::Write::write_all()` does.
+        if n == 0 {
+            return Err(match W::write_zero() {
+                Some(e) => Error::Io(e),
+                None => Error::custom_boxed("failed to write whole buffer"),
+            });
+        }
+
+        data = &data[n.min(data.len())..];
+    }
+
+    Ok(poll_fn(|cx| Pin::new(&mut writer).poll_flush(cx)).await?)
+}
@@ -0,0 +1,86 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Internal helpers for passing CBOR simple values through serde
+//!
+//! Serde has no notion of CBOR simple values. Like tags, they are passed
+//! through serde as a magic newtype variant ([`NAME`]) wrapping the raw
+//! `u8` value.
+//!
+//! Only a `Value` can make sense of that variant, so ciborium's
+//! deserializers only present it to a `Value`, which asks for it by
+//! deserializing the newtype struct [`VALUE`]. Any other type sees
+//! undefined as none, as it sees null, and the other simple values as
+//! invalid.
+
+use core::marker::PhantomData;
+
+use serde::de::{self, IntoDeserializer};
+
+/// The name of the enum, and of its variant, which carries a simple value
+pub(crate) const NAME: &str = "@@ciborium::simple@@";
+
+/// The name of the newtype struct through which a `Value` is deserialized
+pub(crate) const VALUE: &str = "@@ciborium::value@@";
+
+pub(crate) struct SimpleAccess<E> {
+    simple: u8,
+    error: PhantomData<E>,
+}
+
+impl<E> SimpleAccess<E> {
+    pub fn new(simple: u8) -> Self {
+        Self {
+            simple,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> de::EnumAccess<'de> for SimpleAccess<E> {
+    type Error = E;
+    type Variant = Self;
+
+    #[inline]
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(NAME.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, E: de::Error> de::VariantAccess<'de> for SimpleAccess<E> {
+    type Error = E;
+
+    #[inline]
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Err(de::Error::custom("expected simple"))
+    }
+
+    #[inline]
+    fn newtype_variant_seed<U: de::DeserializeSeed<'de>>(
+        self,
+        seed: U,
+    ) -> Result<U::Value, Self::Error> {
+        seed.deserialize(self.simple.into_deserializer())
+    }
+
+    #[inline]
+    fn tuple_variant<V: de::Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("expected simple"))
+    }
+
+    #[inline]
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("expected simple"))
+    }
+}
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+
+use ciborium_ll::tag;
+use serde::{de, ser, Deserialize, Serialize};
+
+/// An arbitrary precision integer, encoded as a bignum (tag 2 or 3)
+///
+/// The value is kept as a sign and a big-endian magnitude without leading
+/// zeros, which is the form used by most bignum libraries (for example,
+/// `to_bytes_be()` and `from_bytes_be()` in `num-bigint`).
+///
+/// Values which fit in a CBOR integer are serialized as plain integers, as
+/// preferred by RFC 8949. Larger values are serialized as tag 2 (positive)
+/// or tag 3 (negative, holding `-1 - n`) wrapping the minimal magnitude.
+/// Deserialization accepts plain integers and both tags, including
+/// magnitudes with leading zeros.
+///
+/// ```rust
+/// use ciborium::{de::from_reader, ser::into_writer, tag::Bignum};
+///
+/// let bytes = [0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0];
+/// let bignum: Bignum = from_reader(&bytes[..]).unwrap();
+/// assert_eq!(bignum.to_string(), "18446744073709551616");
+/// assert_eq!(bignum.magnitude(), [1, 0, 0, 0, 0, 0, 0, 0, 0]);
+///
+/// let mut encoded = Vec::new();
+/// into_writer(&bignum, &mut encoded).unwrap();
+/// assert_eq!(encoded, bytes);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bignum {
+    negative: bool,
+    magnitude: Vec<u8>,
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    &bytes[zeros..]
+}
+
+/// Adds one to a big-endian magnitude
+fn increment(mut magnitude: Vec<u8>) -> Vec<u8> {
+    for byte in magnitude.iter_mut().rev() {
+        let (sum, carry) = byte.overflowing_add(1);
+        *byte = sum;
+        if !carry {
+            return magnitude;
+        }
+    }
+
+    magnitude.insert(0, 1);
+    magnitude
+}
+
+/// Subtracts one from a non-zero big-endian magnitude
+fn decrement(magnitude: &[u8]) -> Vec<u8> {
+    let mut magnitude = magnitude.to_vec();
+    for byte in magnitude.iter_mut().rev() {
+        let (difference, borrow) = byte.overflowing_sub(1);
+        *byte = difference;
+        if !borrow {
+            break;
+        }
+    }
+
+    trim(&magnitude).to_vec()
+}
+
+impl Bignum {
+    /// Creates a bignum from its sign and big-endian magnitude
+    ///
+    /// Leading zeros are removed and zero is never negative.
+    pub fn new(negative: bool, magnitude: &[u8]) -> Self {
+        let magnitude = trim(magnitude).to_vec();
+        let negative = negative && !magnitude.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    /// Whether the value is less than zero
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The big-endian magnitude (absolute value) without leading zeros
+    ///
+    /// Zero has an empty magnitude.
+    #[inline]
+    pub fn magnitude(&self) -> &[u8] {
+        &self.magnitude
+    }
+
+    /// The magnitude as a `u128`, if it fits
+    fn small(&self) -> Option<u128> {
+        if self.magnitude.len() > 16 {
+            return None;
+        }
+
+        let mut buffer = [0u8; 16];
+        buffer[16 - self.magnitude.len()..].copy_from_slice(&self.magnitude);
+        Some(u128::from_be_bytes(buffer))
+    }
+}
+
+impl From<u128> for Bignum {
+    #[inline]
+    fn from(value: u128) -> Self {
+        Self::new(false, &value.to_be_bytes())
+    }
+}
+
+impl From<i128> for Bignum {
+    #[inline]
+    fn from(value: i128) -> Self {
+        Self::new(value < 0, &value.unsigned_abs().to_be_bytes())
+    }
+}
+
+impl From<u64> for Bignum {
+    #[inline]
+    fn from(value: u64) -> Self {
+        u128::from(value).into()
+    }
+}
+
+impl From<i64> for Bignum {
+    #[inline]
+    fn from(value: i64) -> Self {
+        i128::from(value).into()
+    }
+}
+
+impl TryFrom<Bignum> for u128 {
+    type Error = core::num::TryFromIntError;
+
+    #[inline]
+    fn try_from(value: Bignum) -> Result<Self, Self::Error> {
+        match (value.negative, value.small()) {
+            (false, Some(x)) => Ok(x),
+
+            // `TryFromIntError` can only be created by a failed conversion.
+            _ => u128::try_from(-1i8),
+        }
+    }
+}
+
+impl TryFrom<Bignum> for i128 {
+    type Error = core::num::TryFromIntError;
+
+    #[inline]
+    fn try_from(value: Bignum) -> Result<Self, Self::Error> {
+        let magnitude = match value.small() {
+            Some(x) => x,
+            None => return i128::try_from(u128::MAX),
+        };
+
+        match value.negative {
+            false => i128::try_from(magnitude),
+            true if magnitude == 1 << 127 => Ok(i128::MIN),
+            true => i128::try_from(magnitude).map(|x| -x),
+        }
+    }
+}
+
+impl core::fmt::Display for Bignum {
+    /// Formats the value in decimal
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        const CHUNK: u64 = 10_000_000_000_000_000_000; // 10^19
+
+        // Repeatedly divide the magnitude, collecting 19 digit chunks.
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            let mut remainder = 0u128;
+            for byte in magnitude.iter_mut() {
+                let current = remainder << 8 | u128::from(*byte);
+                *byte = (current / u128::from(CHUNK)) as u8;
+                remainder = current % u128::from(CHUNK);
+            }
+
+            chunks.push(remainder as u64);
+            magnitude = trim(&magnitude).to_vec();
+        }
+
+        let mut chunks = chunks.iter().rev();
+        let first = chunks.next().copied().unwrap_or(0);
+
+        let mut digits = alloc::format!("{}", first);
+        for chunk in chunks {
+            digits.push_str(&alloc::format!("{:019}", chunk));
+        }
+
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+impl Serialize for Bignum {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let raw = match self.negative {
+            false => self.magnitude.clone(),
+            true => decrement(&self.magnitude),
+        };
+
+        match (self.negative, raw.len() <= 8) {
+            (false, true) => serializer.serialize_u64(self.small().unwrap() as u64),
+            (true, true) => serializer.serialize_i128(-(self.small().unwrap() as i128)),
+            (false, false) => Captured(Some(tag::BIGPOS), Bytes(&raw)).serialize(serializer),
+            (true, false) => Captured(Some(tag::BIGNEG), Bytes(&raw)).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bignum {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(None, Value::Integer(x)) => Ok(i128::from(x).into()),
+            Captured(Some(tag::BIGPOS), Value::Bytes(x)) => Ok(Self::new(false, &x)),
+            Captured(Some(tag::BIGNEG), Value::Bytes(x)) => {
+                Ok(Self::new(true, &increment(trim(&x).to_vec())))
+            }
+
+            Captured(Some(tag), ..) if tag != tag::BIGPOS && tag != tag::BIGNEG => {
+                Err(de::Error::custom(format_args!(
+                    "expected a bignum (tag 2 or 3), found tag {}",
+                    tag
+                )))
+            }
+
+            Captured(.., ref v) => Err(de::Error::invalid_type(v.into(), &"a (big)int")),
+        }
+    }
+}
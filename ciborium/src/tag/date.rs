@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::timestamp::{civil_from_days, days_from_civil, days_in_month, Parser};
+use super::{Captured, DateTimeError, Required, Timestamp};
+use crate::value::Value;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::str::FromStr;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// A calendar date as days since 1970-01-01 (tag 100)
+///
+/// This is the tag from RFC 8943. Negative days are dates before the
+/// epoch. The tag is required when reading.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::{DateString, EpochDate}, value::Value};
+/// use std::convert::TryFrom;
+///
+/// let date = EpochDate(-10676);
+/// assert_eq!(DateString::try_from(date).unwrap().to_string(), "1940-10-09");
+/// assert_eq!(EpochDate::from(DateString::new(1940, 10, 9).unwrap()), date);
+///
+/// let value = Value::serialized(&date).unwrap();
+/// assert_eq!(value, cbor!(tag!(100, -10676)).unwrap());
+/// assert_eq!(value.deserialized::<EpochDate>().unwrap(), date);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EpochDate(pub i64);
+
+impl From<DateString> for EpochDate {
+    #[inline]
+    fn from(value: DateString) -> Self {
+        let days = days_from_civil(value.year.into(), value.month.into(), value.day.into());
+        Self(days)
+    }
+}
+
+impl Serialize for EpochDate {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 100>(self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EpochDate {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(100), Value::Integer(x)) => match i64::try_from(x) {
+                Ok(days) => Ok(Self(days)),
+                Err(..) => Err(de::Error::custom("epoch date out of range")),
+            },
+
+            Captured(Some(100), ref v) => Err(de::Error::invalid_type(v.into(), &"an integer")),
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 100 (epoch date), found tag {}",
+                tag
+            ))),
+            Captured(None, ..) => Err(de::Error::custom(
+                "expected tag 100 (epoch date), found an untagged value",
+            )),
+        }
+    }
+}
+
+/// A calendar date as an RFC 3339 `full-date` string (tag 1004)
+///
+/// This is the tag from RFC 8943, such as `1004("1940-10-09")`. Years are
+/// limited to `0000` to `9999`. The tag is required when reading.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::{DateString, EpochDate}, value::Value};
+/// use std::convert::TryFrom;
+///
+/// let date: DateString = "1940-10-09".parse().unwrap();
+/// assert_eq!((date.year(), date.month(), date.day()), (1940, 10, 9));
+/// assert_eq!(DateString::try_from(EpochDate(-10676)), Ok(date));
+///
+/// let value = Value::serialized(&date).unwrap();
+/// assert_eq!(value, cbor!(tag!(1004, "1940-10-09")).unwrap());
+/// assert_eq!(value.deserialized::<DateString>().unwrap(), date);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateString {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl DateString {
+    /// Creates a date, unless it does not exist or the year is after 9999
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let valid = year <= 9999
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year.into(), month.into())).contains(&day.into());
+
+        match valid {
+            true => Some(Self { year, month, day }),
+            false => None,
+        }
+    }
+
+    /// The year
+    #[inline]
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// The month, from 1 to 12
+    #[inline]
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, from 1
+    #[inline]
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl FromStr for DateString {
+    type Err = DateTimeError;
+
+    /// Parses an RFC 3339 `full-date`, such as `1940-10-09`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser(text.as_bytes());
+
+        let year = parser.digits(4)?;
+        parser.expect(b"-")?;
+        let month = parser.range(2, 1, 12)?;
+        parser.expect(b"-")?;
+        let day = parser.range(2, 1, days_in_month(year.into(), month))?;
+
+        if !parser.0.is_empty() {
+            return Err(DateTimeError::Malformed);
+        }
+
+        Ok(Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+}
+
+impl core::fmt::Display for DateString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl TryFrom<EpochDate> for DateString {
+    type Error = DateTimeError;
+
+    /// Converts the date, unless its year is outside of `0000` to `9999`
+    fn try_from(value: EpochDate) -> Result<Self, Self::Error> {
+        let range = days_from_civil(0, 1, 1)..=days_from_civil(9999, 12, 31);
+        if !range.contains(&value.0) {
+            return Err(DateTimeError::OutOfRange);
+        }
+
+        let (year, month, day) = civil_from_days(value.0);
+        Ok(Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+}
+
+impl Serialize for DateString {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 1004>(alloc::format!("{}", self)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateString {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(1004), Value::Text(x)) => x.parse().map_err(|_| {
+                de::Error::custom(format_args!("malformed RFC 3339 full-date {:?}", x))
+            }),
+
+            Captured(Some(1004), ref v) => Err(de::Error::invalid_type(v.into(), &"a string")),
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 1004 (date string), found tag {}",
+                tag
+            ))),
+            Captured(None, ..) => Err(de::Error::custom(
+                "expected tag 1004 (date string), found an untagged value",
+            )),
+        }
+    }
+}
+
+/// A point in time in the map-based extended time layout (tag 1002)
+///
+/// This serializes as tag 1002 wrapping a map with integer keys. Key `1`
+/// holds the whole seconds since the epoch. Fractional seconds are written
+/// under key `-3` (milliseconds), `-6` (microseconds) or `-9`
+/// (nanoseconds), whichever is the shortest exact form.
+///
+/// When reading, key `1` may also hold a float, in which case no fraction
+/// key may be present. Other negative keys are elective and ignored, as
+/// the layout allows, while other keys are critical and rejected.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::{ExtendedTime, Timestamp}, value::Value};
+///
+/// let time = ExtendedTime(Timestamp::new(1363896240, 500_000_000).unwrap());
+///
+/// let value = Value::serialized(&time).unwrap();
+/// assert_eq!(value, cbor!(tag!(1002, { 1 => 1363896240, -3 => 500 })).unwrap());
+/// assert_eq!(value.deserialized::<ExtendedTime>().unwrap(), time);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExtendedTime(pub Timestamp);
+
+impl Serialize for ExtendedTime {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let nanos = self.0.nanos();
+        let mut map: Vec<(i8, Value)> = alloc::vec![(1, self.0.secs().into())];
+
+        match nanos {
+            0 => (),
+            n if n % 1_000_000 == 0 => map.push((-3, (n / 1_000_000).into())),
+            n if n % 1_000 == 0 => map.push((-6, (n / 1_000).into())),
+            n => map.push((-9, n.into())),
+        }
+
+        Required::<_, 1002>(Value::Map(
+            map.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        ))
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExtendedTime {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(1002), Value::Map(x)) => x,
+            Captured(Some(1002), ref v) => return Err(de::Error::invalid_type(v.into(), &"a map")),
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag 1002 (extended time), found tag {}",
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(
+                    "expected tag 1002 (extended time), found an untagged value",
+                ))
+            }
+        };
+
+        let mut base = None;
+        let mut fraction = None;
+
+        for (key, value) in map.iter() {
+            let key = match key {
+                Value::Integer(x) => i128::from(*x),
+                _ => return Err(de::Error::custom("extended time keys must be integers")),
+            };
+
+            let scale = match key {
+                1 => {
+                    base = Some(value);
+                    continue;
+                }
+
+                -3 => 1_000_000,
+                -6 => 1_000,
+                -9 => 1,
+                k if k < 0 => continue,
+                k => {
+                    return Err(de::Error::custom(format_args!(
+                        "unsupported critical key {} in extended time",
+                        k
+                    )))
+                }
+            };
+
+            if fraction.is_some() {
+                return Err(de::Error::custom(
+                    "extended time has more than one fractional seconds key",
+                ));
+            }
+
+            let limit = 1_000_000_000 / scale;
+            fraction = match value {
+                Value::Integer(x) => u32::try_from(*x).ok().filter(|x| *x < limit),
+                _ => None,
+            }
+            .map(|x| x * scale);
+
+            if fraction.is_none() {
+                return Err(de::Error::custom(format_args!(
+                    "invalid fractional seconds under key {} in extended time",
+                    key
+                )));
+            }
+        }
+
+        let time = match (base, fraction) {
+            (Some(Value::Integer(x)), nanos) => {
+                let secs = i64::try_from(*x).ok();
+                secs.and_then(|secs| Timestamp::new(secs, nanos.unwrap_or(0)))
+            }
+
+            (Some(Value::Float(x)), None) => Timestamp::from_secs_f64((*x).into()).ok(),
+            (Some(Value::Float(..)), Some(..)) => {
+                return Err(de::Error::custom(
+                    "extended time cannot combine fractional seconds with a float",
+                ))
+            }
+
+            (Some(..), _) => return Err(de::Error::custom("invalid extended time base")),
+            (None, _) => return Err(de::Error::custom("extended time is missing key 1")),
+        };
+
+        match time {
+            Some(time) => Ok(Self(time)),
+            None => Err(de::Error::custom("extended time out of range")),
+        }
+    }
+}
+
+impl From<Timestamp> for ExtendedTime {
+    #[inline]
+    fn from(value: Timestamp) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ExtendedTime> for Timestamp {
+    #[inline]
+    fn from(value: ExtendedTime) -> Self {
+        value.0
+    }
+}
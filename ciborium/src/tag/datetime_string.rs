@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes a date/time as tag 0 wrapping an RFC 3339 string
+//!
+//! Use this module with `#[serde(with = "ciborium::tag::datetime_string")]`
+//! on a field of any type which converts into and from a [`Timestamp`],
+//! such as `Timestamp` itself or `std::time::SystemTime`.
+//! Timestamps are always written in UTC. When reading, fractional seconds
+//! and offsets other than `Z` are accepted, but the tag is required.
+//!
+//! ```rust
+//! use ciborium::{cbor, tag::Timestamp, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "ciborium::tag::datetime_string")]
+//!     at: Timestamp,
+//! }
+//!
+//! let event: Event = cbor!({ "at" => tag!(0, "2013-03-21T22:04:00.25+02:00") })
+//!     .unwrap()
+//!     .deserialized()
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     Value::serialized(&event).unwrap(),
+//!     cbor!({ "at" => tag!(0, "2013-03-21T20:04:00.25Z") }).unwrap(),
+//! );
+//! ```
+
+use super::{Captured, Required, Timestamp};
+
+use alloc::string::String;
+use core::convert::{TryFrom, TryInto};
+use core::fmt::Display;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Serializes the value as tag 0 wrapping an RFC 3339 string
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Clone + TryInto<Timestamp>,
+    T::Error: Display,
+    S: ser::Serializer,
+{
+    let timestamp: Timestamp = value.clone().try_into().map_err(ser::Error::custom)?;
+    match timestamp.to_rfc3339() {
+        Ok(text) => Required::<_, 0>(text).serialize(serializer),
+        Err(e) => Err(ser::Error::custom(e)),
+    }
+}
+
+/// Deserializes the value from tag 0 wrapping an RFC 3339 string
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: TryFrom<Timestamp>,
+    T::Error: Display,
+    D: de::Deserializer<'de>,
+{
+    let text = match Captured::<String>::deserialize(deserializer)? {
+        Captured(Some(0), text) => text,
+        Captured(Some(tag), ..) => {
+            return Err(de::Error::custom(format_args!(
+                "expected tag 0 (date/time string), found tag {}",
+                tag
+            )))
+        }
+        Captured(None, ..) => {
+            return Err(de::Error::custom(
+                "expected tag 0 (date/time string), found untagged text",
+            ))
+        }
+    };
+
+    let timestamp: Timestamp = text.parse().map_err(|_| {
+        de::Error::invalid_value(de::Unexpected::Str(&text), &"an RFC 3339 date/time")
+    })?;
+
+    T::try_from(timestamp).map_err(de::Error::custom)
+}
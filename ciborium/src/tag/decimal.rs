@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Captured, Required};
+use crate::value::Value;
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::str::FromStr;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// An error converting a decimal fraction
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecimalFractionError {
+    /// The text is not a decimal number
+    Malformed,
+
+    /// The mantissa or exponent does not fit, or the float is not finite
+    OutOfRange,
+}
+
+impl core::fmt::Display for DecimalFractionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Malformed => "malformed decimal number",
+            Self::OutOfRange => "decimal number out of range",
+        })
+    }
+}
+
+impl serde::de::StdError for DecimalFractionError {}
+
+/// A decimal fraction (tag 4): `mantissa * 10^exponent`
+///
+/// This serializes as tag 4 wrapping the array `[exponent, mantissa]`. A
+/// mantissa which does not fit in a CBOR integer is written as a bignum.
+/// Deserialization requires the tag and accepts a bignum mantissa as long
+/// as it fits in an `i128`.
+///
+/// Decimal fractions convert exactly to and from decimal strings. The
+/// conversions to and from `f64` are lossy, as most decimal fractions have
+/// no exact binary representation.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::DecimalFraction, value::Value};
+///
+/// let price: DecimalFraction = "-12.345".parse().unwrap();
+/// assert_eq!(price, DecimalFraction { exponent: -3, mantissa: -12345 });
+/// assert_eq!(price.to_string(), "-12.345");
+///
+/// let value = Value::serialized(&price).unwrap();
+/// assert_eq!(value, cbor!(tag!(4, [-3, -12345])).unwrap());
+/// assert_eq!(value.deserialized::<DecimalFraction>().unwrap(), price);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DecimalFraction {
+    /// The power of ten by which the mantissa is multiplied
+    pub exponent: i64,
+
+    /// The digits of the value
+    pub mantissa: i128,
+}
+
+impl FromStr for DecimalFraction {
+    type Err = DecimalFractionError;
+
+    /// Parses a decimal number such as `-12.345`, `.5` or `1.5e-7`
+    ///
+    /// Trailing zeros are kept, so `1.50` has an exponent of `-2`.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (negative, text) = match text.as_bytes().first() {
+            Some(b'-') => (true, &text[1..]),
+            Some(b'+') => (false, &text[1..]),
+            _ => (false, text),
+        };
+
+        let (number, exponent) = match text.find(['e', 'E']) {
+            Some(i) => (&text[..i], Some(&text[i + 1..])),
+            None => (text, None),
+        };
+
+        let (whole, fraction) = match number.find('.') {
+            Some(i) => (&number[..i], &number[i + 1..]),
+            None => (number, ""),
+        };
+
+        let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.len() + fraction.len() == 0 || !digits(whole) || !digits(fraction) {
+            return Err(DecimalFractionError::Malformed);
+        }
+
+        // Accumulate with the sign applied so that `i128::MIN` can be parsed.
+        let mut mantissa = 0i128;
+        for digit in whole.bytes().chain(fraction.bytes()) {
+            let digit = i128::from(digit - b'0');
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| match negative {
+                    false => m.checked_add(digit),
+                    true => m.checked_sub(digit),
+                })
+                .ok_or(DecimalFractionError::OutOfRange)?;
+        }
+
+        let exponent = match exponent {
+            None => 0,
+            Some(e) => {
+                let unsigned = e.strip_prefix(['-', '+']).unwrap_or(e);
+                if unsigned.is_empty() || !digits(unsigned) {
+                    return Err(DecimalFractionError::Malformed);
+                }
+
+                e.parse::<i64>()
+                    .map_err(|_| DecimalFractionError::OutOfRange)?
+            }
+        };
+
+        let exponent = i64::try_from(fraction.len())
+            .ok()
+            .and_then(|len| exponent.checked_sub(len))
+            .ok_or(DecimalFractionError::OutOfRange)?;
+
+        Ok(Self { exponent, mantissa })
+    }
+}
+
+impl core::fmt::Display for DecimalFraction {
+    /// Formats the value so that it parses back exactly
+    ///
+    /// Values with a negative exponent of up to 40 are written with a
+    /// decimal point, others in scientific notation (such as `12e3`).
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.exponent == 0 {
+            return write!(f, "{}", self.mantissa);
+        }
+
+        if !(-40..0).contains(&self.exponent) {
+            return write!(f, "{}e{}", self.mantissa, self.exponent);
+        }
+
+        let scale = -self.exponent as usize;
+        let digits = alloc::format!(
+            "{:0>width$}",
+            self.mantissa.unsigned_abs(),
+            width = scale + 1
+        );
+        let (whole, fraction) = digits.split_at(digits.len() - scale);
+
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl TryFrom<f64> for DecimalFraction {
+    type Error = DecimalFractionError;
+
+    /// Converts from the shortest decimal which rounds to the same float
+    ///
+    /// For example, `0.1` converts to `1e-1` rather than the exact (and
+    /// much longer) value of the float. NaN and infinity produce an error.
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if !value.is_finite() {
+            return Err(DecimalFractionError::OutOfRange);
+        }
+
+        let text: String = alloc::format!("{:e}", value);
+        text.parse()
+    }
+}
+
+impl From<DecimalFraction> for f64 {
+    /// Converts to the nearest float, which may lose precision
+    ///
+    /// Values too large for a float convert to infinity.
+    #[inline]
+    fn from(value: DecimalFraction) -> Self {
+        alloc::format!("{}e{}", value.mantissa, value.exponent)
+            .parse()
+            .unwrap()
+    }
+}
+
+impl Serialize for DecimalFraction {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 4>((self.exponent, self.mantissa)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DecimalFraction {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(4), Value::Array(items)) => items,
+            Captured(Some(4), ref v) => return Err(de::Error::invalid_type(v.into(), &"an array")),
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag 4 (decimal fraction), found tag {}",
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(
+                    "expected tag 4 (decimal fraction), found an untagged value",
+                ))
+            }
+        };
+
+        let (exponent, mantissa) = match &items[..] {
+            [exponent, mantissa] => (exponent, mantissa),
+            _ => return Err(de::Error::invalid_length(items.len(), &"two items")),
+        };
+
+        // The exponent must be a plain integer, while the mantissa may be a bignum.
+        let exponent = match exponent {
+            Value::Integer(x) => i64::try_from(*x).ok(),
+            _ => None,
+        };
+
+        let exponent =
+            exponent.ok_or_else(|| de::Error::custom("invalid decimal fraction exponent"))?;
+        let mantissa = mantissa
+            .as_i128()
+            .ok_or_else(|| de::Error::custom("invalid decimal fraction mantissa"))?;
+
+        Ok(Self { exponent, mantissa })
+    }
+}
@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deserializes one of several types, chosen by the tag of the data item
+//!
+//! See [`Dispatcher`].
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use serde::{de, Deserialize};
+
+/// Dispatches on the tag of a data item to one of several types
+///
+/// Each case registers a type for a tag. During deserialization, the tag
+/// is read once and the data item inside it is deserialized directly with
+/// the registered type, which is then converted into `V`. If the same tag
+/// is registered more than once, the first case wins. A data item with
+/// any other tag, or with no tag at all, produces an error listing the
+/// registered tags.
+///
+/// A `Dispatcher` is a [`DeserializeSeed`](serde::de::DeserializeSeed),
+/// so it can be used with [`from_reader_seed()`](crate::de::from_reader_seed)
+/// or from another `DeserializeSeed`.
+///
+/// ```rust
+/// use ciborium::{cbor, de::from_reader_seed, ser::into_writer, tag::Dispatcher};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Request { id: u32 }
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Response { id: u32, ok: bool }
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Message {
+///     Request(Request),
+///     Response(Response),
+/// }
+///
+/// impl From<Request> for Message {
+///     fn from(x: Request) -> Self { Message::Request(x) }
+/// }
+///
+/// impl From<Response> for Message {
+///     fn from(x: Response) -> Self { Message::Response(x) }
+/// }
+///
+/// let mut bytes = Vec::new();
+/// into_writer(&cbor!(tag!(1002, { "id" => 7, "ok" => true })).unwrap(), &mut bytes).unwrap();
+///
+/// let dispatcher = Dispatcher::<Message>::new()
+///     .case::<Request>(1001)
+///     .case::<Response>(1002);
+///
+/// let message = from_reader_seed(&bytes[..], dispatcher).unwrap();
+/// assert_eq!(message, Message::Response(Response { id: 7, ok: true }));
+/// ```
+pub struct Dispatcher<V, C = End> {
+    cases: C,
+    output: PhantomData<fn() -> V>,
+}
+
+/// A case of a [`Dispatcher`], which deserializes `T` for one tag
+pub struct Case<T, P> {
+    tag: u64,
+    prev: P,
+    kind: PhantomData<fn() -> T>,
+}
+
+/// The end of the cases of a [`Dispatcher`]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct End;
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::End {}
+    impl<T, P> Sealed for super::Case<T, P> {}
+}
+
+/// The cases of a [`Dispatcher`] which produce a `V`
+///
+/// This trait is sealed and implemented by [`Case`] and [`End`].
+pub trait Cases<'de, V>: private::Sealed {
+    #[doc(hidden)]
+    fn tags(&self, tags: &mut Vec<u64>);
+
+    #[doc(hidden)]
+    fn dispatch<A: de::SeqAccess<'de>>(&self, tag: u64, seq: &mut A)
+        -> Result<Option<V>, A::Error>;
+}
+
+impl<'de, V> Cases<'de, V> for End {
+    #[inline]
+    fn tags(&self, _tags: &mut Vec<u64>) {}
+
+    #[inline]
+    fn dispatch<A: de::SeqAccess<'de>>(&self, _: u64, _: &mut A) -> Result<Option<V>, A::Error> {
+        Ok(None)
+    }
+}
+
+impl<'de, V, T, P> Cases<'de, V> for Case<T, P>
+where
+    T: Deserialize<'de> + Into<V>,
+    P: Cases<'de, V>,
+{
+    #[inline]
+    fn tags(&self, tags: &mut Vec<u64>) {
+        self.prev.tags(tags);
+        tags.push(self.tag);
+    }
+
+    #[inline]
+    fn dispatch<A: de::SeqAccess<'de>>(
+        &self,
+        tag: u64,
+        seq: &mut A,
+    ) -> Result<Option<V>, A::Error> {
+        if let Some(value) = self.prev.dispatch(tag, seq)? {
+            return Ok(Some(value));
+        }
+
+        if tag != self.tag {
+            return Ok(None);
+        }
+
+        match seq.next_element::<T>()? {
+            Some(value) => Ok(Some(value.into())),
+            None => Err(de::Error::invalid_length(1, &"a tagged data item")),
+        }
+    }
+}
+
+impl<V> Dispatcher<V> {
+    /// Creates a dispatcher without any cases
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cases: End,
+            output: PhantomData,
+        }
+    }
+}
+
+impl<V> Default for Dispatcher<V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, C> Dispatcher<V, C> {
+    /// Adds a case which deserializes `T` from a data item with tag `tag`
+    #[inline]
+    pub fn case<T>(self, tag: u64) -> Dispatcher<V, Case<T, C>> {
+        Dispatcher {
+            cases: Case {
+                tag,
+                prev: self.cases,
+                kind: PhantomData,
+            },
+            output: PhantomData,
+        }
+    }
+
+    /// Returns the registered tags, in the order they were added
+    pub fn tags<'de>(&self) -> Vec<u64>
+    where
+        C: Cases<'de, V>,
+    {
+        let mut tags = Vec::new();
+        self.cases.tags(&mut tags);
+        tags
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+// The names must match those in the `protocol` module.
+enum Variant {
+    #[serde(rename = "@@ciborium::untagged@@")]
+    Untagged,
+
+    #[serde(rename = "@@ciborium::tagged@@")]
+    Tagged,
+}
+
+struct Visitor<V, C>(Dispatcher<V, C>);
+
+impl<'de, V, C: Cases<'de, V>> de::Visitor<'de> for Visitor<V, C> {
+    type Value = V;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(formatter, "one of tags {:?}", self.0.tags())
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        use de::VariantAccess as _;
+
+        match data.variant()? {
+            (Variant::Tagged, access) => access.tuple_variant(2, self),
+            (Variant::Untagged, access) => {
+                access.newtype_variant::<de::IgnoredAny>()?;
+                Err(de::Error::custom(format_args!(
+                    "expected one of tags {:?}, found an untagged value",
+                    self.0.tags()
+                )))
+            }
+        }
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let tag: u64 = match seq.next_element()? {
+            Some(tag) => tag,
+            None => return Err(de::Error::invalid_length(0, &self)),
+        };
+
+        match self.0.cases.dispatch(tag, &mut seq)? {
+            Some(value) => Ok(value),
+            None => Err(de::Error::custom(format_args!(
+                "expected one of tags {:?}, found tag {}",
+                self.0.tags(),
+                tag
+            ))),
+        }
+    }
+}
+
+impl<'de, V, C: Cases<'de, V>> de::DeserializeSeed<'de> for Dispatcher<V, C> {
+    type Value = V;
+
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<V, D::Error> {
+        use super::protocol::{NAME, TAGGED, UNTAGGED};
+
+        deserializer.deserialize_enum(NAME, &[UNTAGGED, TAGGED], Visitor(self))
+    }
+}
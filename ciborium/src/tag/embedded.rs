@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use alloc::vec::Vec;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// An encoded CBOR data item (tag 24) which has not been decoded
+///
+/// This holds the bytes wrapped by tag 24 so that parsing can be deferred
+/// or skipped entirely. Use [`EmbeddedCbor`] to encode and decode the item
+/// in one step.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawEmbeddedCbor(pub Vec<u8>);
+
+impl RawEmbeddedCbor {
+    /// Encodes a value into an embedded data item
+    pub fn encode<T: ?Sized + Serialize>(
+        value: &T,
+    ) -> Result<Self, crate::ser::Error<core::convert::Infallible>> {
+        let mut bytes = Vec::new();
+        match crate::ser::into_writer(value, &mut bytes) {
+            Ok(()) => Ok(Self(bytes)),
+            Err(crate::ser::Error::Value(e)) => Err(crate::ser::Error::Value(e)),
+            Err(crate::ser::Error::Custom(e)) => Err(crate::ser::Error::Custom(e)),
+            Err(crate::ser::Error::Io(..)) => unreachable!(),
+        }
+    }
+
+    /// Decodes the embedded data item
+    #[inline]
+    pub fn decode<'de, T: Deserialize<'de>>(
+        &self,
+    ) -> Result<T, crate::de::Error<<&[u8] as ciborium_io::Read>::Error>> {
+        crate::de::from_reader(&self.0[..])
+    }
+}
+
+impl Serialize for RawEmbeddedCbor {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Captured(Some(24), Bytes(&self.0)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawEmbeddedCbor {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(24), Value::Bytes(x)) => Ok(Self(x)),
+            Captured(Some(24), ref v) => Err(de::Error::invalid_type(v.into(), &"bytes")),
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 24 (embedded CBOR), found tag {}",
+                tag
+            ))),
+            Captured(None, ..) => Err(de::Error::custom(
+                "expected tag 24 (embedded CBOR), found an untagged value",
+            )),
+        }
+    }
+}
+
+/// A value embedded as an encoded CBOR data item (tag 24)
+///
+/// The value is serialized to CBOR on its own and the result is emitted as
+/// a byte string wrapped in tag 24, so that intermediaries can pass it on
+/// without parsing it. Deserialization decodes the byte string back into
+/// the value, with the default recursion limit; its size is bounded by the
+/// byte string which contains it. Embedded items can be nested.
+///
+/// ```rust
+/// use ciborium::{tag::EmbeddedCbor, value::Value};
+///
+/// let embedded = EmbeddedCbor(vec![1, 2]);
+/// let value = Value::serialized(&embedded).unwrap();
+/// assert_eq!(value, Value::Tag(24, Value::Bytes(vec![0x82, 0x01, 0x02]).into()));
+///
+/// let decoded: EmbeddedCbor<Vec<u8>> = value.deserialized().unwrap();
+/// assert_eq!(decoded, embedded);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmbeddedCbor<T>(pub T);
+
+impl<T: Serialize> Serialize for EmbeddedCbor<T> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match RawEmbeddedCbor::encode(&self.0) {
+            Ok(raw) => raw.serialize(serializer),
+            Err(e) => Err(ser::Error::custom(format_args!(
+                "unable to embed CBOR: {:?}",
+                e
+            ))),
+        }
+    }
+}
+
+impl<'de, T: de::DeserializeOwned> Deserialize<'de> for EmbeddedCbor<T> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawEmbeddedCbor::deserialize(deserializer)?;
+        match raw.decode() {
+            Ok(x) => Ok(EmbeddedCbor(x)),
+            Err(e) => Err(de::Error::custom(format_args!(
+                "invalid embedded CBOR: {:?}",
+                e
+            ))),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes a date/time as tag 1 wrapping seconds since the epoch
+//!
+//! Use this module with `#[serde(with = "ciborium::tag::epoch_time")]` on
+//! a field of any type which converts into and from a [`Timestamp`], such
+//! as `Timestamp` itself or `std::time::SystemTime`. The seconds are written
+//! as an integer when they are whole and as a float otherwise. When reading,
+//! both forms are accepted, including negative values for times before
+//! 1970, but the tag is required.
+//!
+//! ```rust
+//! use ciborium::{cbor, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "ciborium::tag::epoch_time")]
+//!     at: SystemTime,
+//! }
+//!
+//! let event: Event = cbor!({ "at" => tag!(1, 1363896240.5) })
+//!     .unwrap()
+//!     .deserialized()
+//!     .unwrap();
+//!
+//! assert_eq!(event.at, UNIX_EPOCH + Duration::from_millis(1363896240500));
+//!
+//! let event = Event { at: UNIX_EPOCH + Duration::from_secs(1363896240) };
+//! assert_eq!(
+//!     Value::serialized(&event).unwrap(),
+//!     cbor!({ "at" => tag!(1, 1363896240) }).unwrap(),
+//! );
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+
+use super::{Captured, Required, Timestamp};
+use crate::value::Value;
+
+use core::convert::{TryFrom, TryInto};
+use core::fmt::Display;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Serializes the value as tag 1 wrapping an integer or float
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Clone + TryInto<Timestamp>,
+    T::Error: Display,
+    S: ser::Serializer,
+{
+    let timestamp: Timestamp = value.clone().try_into().map_err(ser::Error::custom)?;
+    match timestamp.nanos() {
+        0 => Required::<_, 1>(timestamp.secs()).serialize(serializer),
+        _ => Required::<_, 1>(timestamp.as_secs_f64()).serialize(serializer),
+    }
+}
+
+/// Deserializes the value from tag 1 wrapping an integer or float
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: TryFrom<Timestamp>,
+    T::Error: Display,
+    D: de::Deserializer<'de>,
+{
+    let value = match Captured::<Value>::deserialize(deserializer)? {
+        Captured(Some(1), value) => value,
+        Captured(Some(tag), ..) => {
+            return Err(de::Error::custom(format_args!(
+                "expected tag 1 (epoch time), found tag {}",
+                tag
+            )))
+        }
+        Captured(None, ..) => {
+            return Err(de::Error::custom(
+                "expected tag 1 (epoch time), found an untagged value",
+            ))
+        }
+    };
+
+    let timestamp = match value {
+        Value::Integer(x) => match i64::try_from(x) {
+            Ok(secs) => Timestamp::new(secs, 0).unwrap(),
+            Err(..) => return Err(de::Error::custom("epoch time out of range")),
+        },
+
+        Value::Float(x) => {
+            let x = f64::from(x);
+            if x.is_nan() {
+                return Err(de::Error::custom("epoch time is NaN"));
+            }
+
+            if x.is_infinite() {
+                return Err(de::Error::custom("epoch time is infinite"));
+            }
+
+            Timestamp::from_secs_f64(x).map_err(|_| de::Error::custom("epoch time out of range"))?
+        }
+
+        ref v => return Err(de::Error::invalid_type(v.into(), &"an integer or a float")),
+    };
+
+    T::try_from(timestamp).map_err(de::Error::custom)
+}
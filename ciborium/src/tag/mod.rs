@@ -1,20 +1,88 @@
 //! Contains helper types for dealing with CBOR tags
 
+pub mod datetime_string;
+pub mod dispatch;
+pub mod epoch_time;
+#[cfg(feature = "std")]
+pub mod network_address;
+pub mod protocol;
+pub mod required;
+pub mod uri;
+pub mod uuid;
+
+mod bignum;
+mod date;
+mod decimal;
+mod embedded;
+#[cfg(feature = "std")]
+mod prefix;
+mod rational;
+mod text;
+mod timestamp;
+mod typed;
+
+pub use bignum::Bignum;
+pub use date::{DateString, EpochDate, ExtendedTime};
+pub use decimal::{DecimalFraction, DecimalFractionError};
+pub use dispatch::Dispatcher;
+pub use embedded::{EmbeddedCbor, RawEmbeddedCbor};
+#[cfg(feature = "std")]
+pub use prefix::CidrPrefix;
+pub use rational::Rational;
+pub use text::{LangString, MimeMessage};
+pub use timestamp::{DateTimeError, FormattedTimestamp, Timestamp, TimestampForm};
+pub use typed::{Element, TypedArray};
+
+use alloc::vec::Vec;
+
 use serde::{de, de::Error as _, forward_to_deserialize_any, ser, Deserialize, Serialize};
 
-#[serde(rename = "@@TAG@@")]
+/// Serializes a slice as a byte string
+pub(crate) struct Bytes<'a>(pub &'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
 #[derive(Deserialize, Serialize)]
+// The names must match those in the `protocol` module.
+#[serde(rename = "@@ciborium::tag@@")]
 enum Internal<T> {
-    #[serde(rename = "@@UNTAGGED@@")]
+    #[serde(rename = "@@ciborium::untagged@@")]
     Untagged(T),
 
-    #[serde(rename = "@@TAGGED@@")]
+    #[serde(rename = "@@ciborium::tagged@@")]
     Tagged(u64, T),
 }
 
 /// An optional CBOR tag and its data item
 ///
-/// No semantic evaluation of the tag is made.
+/// No semantic evaluation of the tag is made. During deserialization, the
+/// tag which precedes the data item, if any, is recorded; during
+/// serialization, the recorded tag is emitted again. This allows
+/// dispatching on tags which are only known at runtime.
+///
+/// Only the outermost tag of a data item is captured. Any further tags are
+/// passed on to `V`: a `Captured` or a [`Value`](crate::value::Value)
+/// records them in turn, while other types skip them. So
+/// `Captured<Captured<V>>` captures two levels of tags, and
+/// [`CapturedAll`] captures all of them.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::Captured};
+///
+/// let value = cbor!(tag!(1002, [true])).unwrap();
+///
+/// let captured: Captured<Vec<bool>> = value.deserialized().unwrap();
+/// match captured {
+///     Captured(Some(1001), ..) => panic!("a request"),
+///     Captured(Some(1002), payload) => assert_eq!(payload, [true]),
+///     Captured(..) => panic!("unknown"),
+/// }
+/// ```
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Captured<V>(pub Option<u64>, pub V);
 
@@ -38,11 +106,89 @@ impl<V: Serialize> Serialize for Captured<V> {
     }
 }
 
+// The names must match those in the `protocol` module.
+#[derive(Deserialize)]
+#[serde(rename = "@@ciborium::tag@@")]
+enum Chain<V> {
+    #[serde(rename = "@@ciborium::untagged@@")]
+    Untagged(V),
+
+    #[serde(rename = "@@ciborium::tagged@@")]
+    Tagged(u64, CapturedAll<V>),
+}
+
+struct Nested<'a, V>(&'a [u64], &'a V);
+
+impl<V: Serialize> Serialize for Nested<'_, V> {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.split_first() {
+            Some((tag, rest)) => Internal::Tagged(*tag, Nested(rest, self.1)).serialize(serializer),
+            None => Internal::Untagged(self.1).serialize(serializer),
+        }
+    }
+}
+
+/// All CBOR tags of a data item, outermost first, and the data item
+///
+/// Like [`Captured`], but every tag of the data item is recorded, so that
+/// none of them reach `V`. This is [`Captured`] nested as deeply as the
+/// data item requires. During serialization, the tags are emitted in the
+/// same order.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::CapturedAll};
+///
+/// let value = cbor!(tag!(24, tag!(55799, [true]))).unwrap();
+///
+/// let captured: CapturedAll<Vec<bool>> = value.deserialized().unwrap();
+/// assert_eq!(captured, CapturedAll(vec![24, 55799], vec![true]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CapturedAll<V>(pub Vec<u64>, pub V);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for CapturedAll<V> {
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Chain::deserialize(deserializer)? {
+            Chain::Tagged(t, CapturedAll(mut tags, v)) => {
+                tags.insert(0, t);
+                Ok(CapturedAll(tags, v))
+            }
+
+            Chain::Untagged(v) => Ok(CapturedAll(Vec::new(), v)),
+        }
+    }
+}
+
+impl<V: Serialize> Serialize for CapturedAll<V> {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Nested(&self.0, &self.1).serialize(serializer)
+    }
+}
+
 /// A required CBOR tag
 ///
 /// This data type indicates that the specified tag, and **only** that tag,
 /// is required during deserialization. If the tag is missing, deserialization
 /// will fail. The tag will always be emitted during serialization.
+///
+/// The tag survives a trip through a [`Value`](crate::value::Value):
+/// serializing into a `Value` produces a `Value::Tag`, from which the
+/// `Required` deserializes again. The one exception is a small bignum
+/// (tag 2 or 3), which [`Value::from_reader()`](crate::value::Value::from_reader)
+/// normalizes to an integer; use
+/// [`Value::from_reader_with_encoding()`](crate::value::Value::from_reader_with_encoding)
+/// to keep the tag.
+///
+/// ```rust
+/// use ciborium::{tag::Required, value::Value};
+///
+/// let value = Value::serialized(&Required::<_, 37>("id")).unwrap();
+/// assert_eq!(value, Value::Tag(37, Value::from("id").into()));
+/// assert_eq!(value.deserialized::<Required<String, 37>>().unwrap().0, "id");
+/// ```
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Required<V, const TAG: u64>(pub V);
 
@@ -89,6 +235,205 @@ impl<V: Serialize, const TAG: u64> Serialize for Accepted<V, TAG> {
     }
 }
 
+/// A set of CBOR tags, for use with [`AcceptedAny`]
+///
+/// ```rust
+/// use ciborium::tag::TagSet;
+///
+/// /// The date/time tags
+/// struct DateTime;
+///
+/// impl TagSet for DateTime {
+///     const TAGS: &'static [u64] = &[0, 1];
+/// }
+/// ```
+pub trait TagSet {
+    /// The tags in the set, the first of which is the default
+    const TAGS: &'static [u64];
+}
+
+/// One of several optional CBOR tags
+///
+/// This data type indicates that any of the tags in the set `S`, and
+/// **only** those tags, are accepted, but not required, during
+/// deserialization. The tag which was found is recorded and emitted again
+/// during serialization. Values without a recorded tag are serialized
+/// with the first tag in the set.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::{AcceptedAny, TagSet}, value::Value};
+///
+/// struct DateTime;
+///
+/// impl TagSet for DateTime {
+///     const TAGS: &'static [u64] = &[0, 1];
+/// }
+///
+/// let value = cbor!(tag!(1, 1363896240)).unwrap();
+/// let time: AcceptedAny<u64, DateTime> = value.deserialized().unwrap();
+/// assert_eq!(time.tag(), Some(1));
+/// assert_eq!(time.value, 1363896240);
+/// assert_eq!(Value::serialized(&time).unwrap(), value);
+///
+/// let value = cbor!(tag!(2, 1363896240)).unwrap();
+/// assert!(value.deserialized::<AcceptedAny<u64, DateTime>>().is_err());
+/// ```
+pub struct AcceptedAny<V, S> {
+    tag: Option<u64>,
+
+    /// The data item
+    pub value: V,
+
+    set: core::marker::PhantomData<fn() -> S>,
+}
+
+impl<V, S: TagSet> AcceptedAny<V, S> {
+    /// Creates a value without a recorded tag
+    #[inline]
+    pub fn new(value: V) -> Self {
+        Self {
+            tag: None,
+            value,
+            set: core::marker::PhantomData,
+        }
+    }
+
+    /// Creates a value with a recorded tag, if the tag is in the set
+    #[inline]
+    pub fn with_tag(tag: u64, value: V) -> Option<Self> {
+        match S::TAGS.contains(&tag) {
+            true => Some(Self {
+                tag: Some(tag),
+                value,
+                set: core::marker::PhantomData,
+            }),
+            false => None,
+        }
+    }
+
+    /// The tag which was found during deserialization, if any
+    #[inline]
+    pub fn tag(&self) -> Option<u64> {
+        self.tag
+    }
+}
+
+impl<V: Clone, S> Clone for AcceptedAny<V, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            tag: self.tag,
+            value: self.value.clone(),
+            set: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<V: core::fmt::Debug, S> core::fmt::Debug for AcceptedAny<V, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("AcceptedAny")
+            .field("tag", &self.tag)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<V: PartialEq, S> PartialEq for AcceptedAny<V, S> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag && self.value == other.value
+    }
+}
+
+impl<V: Eq, S> Eq for AcceptedAny<V, S> {}
+
+impl<'de, V: Deserialize<'de>, S: TagSet> Deserialize<'de> for AcceptedAny<V, S> {
+    #[inline]
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Internal::deserialize(deserializer)? {
+            Internal::Tagged(t, v) => Self::with_tag(t, v).ok_or_else(|| {
+                de::Error::custom(format_args!(
+                    "expected one of tags {:?}, found tag {}",
+                    S::TAGS,
+                    t
+                ))
+            }),
+            Internal::Untagged(v) => Ok(Self::new(v)),
+        }
+    }
+}
+
+impl<V: Serialize, S: TagSet> Serialize for AcceptedAny<V, S> {
+    #[inline]
+    fn serialize<T: ser::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        let tag = match (self.tag, S::TAGS.first()) {
+            (Some(tag), ..) | (None, Some(&tag)) => tag,
+            (None, None) => return Internal::Untagged(&self.value).serialize(serializer),
+        };
+
+        Internal::Tagged(tag, &self.value).serialize(serializer)
+    }
+}
+
+macro_rules! expected {
+    ($($(#[$attr:meta])* $name:ident($tag:literal);)+) => {
+        $(
+            $(#[$attr])*
+            #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name<V>(pub V);
+
+            impl<'de, V: Deserialize<'de>> Deserialize<'de> for $name<V> {
+                #[inline]
+                fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    Accepted::<V, $tag>::deserialize(deserializer).map(|x| $name(x.0))
+                }
+            }
+
+            impl<V: Serialize> Serialize for $name<V> {
+                #[inline]
+                fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    Internal::Tagged($tag, &self.0).serialize(serializer)
+                }
+            }
+        )+
+    };
+}
+
+expected! {
+    /// A byte string expected to be rendered as base64url (tag 21)
+    ///
+    /// Tags 21 to 23 hint at how a byte string should be encoded when it is
+    /// converted to JSON. They carry no meaning in CBOR itself, so the tag is
+    /// emitted during serialization and stripped, if present, during
+    /// deserialization. This behaves just like [`Accepted`] with the given
+    /// tag. The wrapped type should serialize as bytes, such as
+    /// `serde_bytes::ByteBuf`.
+    ///
+    /// ```rust
+    /// use ciborium::{tag::ExpectedBase64Url, value::Value};
+    /// use serde_bytes::ByteBuf;
+    ///
+    /// let data = ExpectedBase64Url(ByteBuf::from(vec![1, 2]));
+    /// let value = Value::serialized(&data).unwrap();
+    /// assert_eq!(value, Value::Tag(21, Value::Bytes(vec![1, 2]).into()));
+    ///
+    /// // Untagged bytes are accepted too.
+    /// let value = Value::Bytes(vec![1, 2]);
+    /// assert_eq!(value.deserialized::<ExpectedBase64Url<ByteBuf>>().unwrap(), data);
+    /// ```
+    ExpectedBase64Url(21);
+
+    /// A byte string expected to be rendered as base64 (tag 22)
+    ///
+    /// See [`ExpectedBase64Url`].
+    ExpectedBase64(22);
+
+    /// A byte string expected to be rendered as base16 (tag 23)
+    ///
+    /// See [`ExpectedBase64Url`].
+    ExpectedBase16(23);
+}
+
 pub(crate) struct TagAccess<D> {
     parent: Option<D>,
     state: usize,
@@ -114,8 +459,8 @@ impl<'de, D: de::Deserializer<'de>> de::Deserializer<'de> for &mut TagAccess<D>
 
         match self.state {
             1 => visitor.visit_str(match self.tag {
-                Some(..) => "@@TAGGED@@",
-                None => "@@UNTAGGED@@",
+                Some(..) => protocol::TAGGED,
+                None => protocol::UNTAGGED,
             }),
 
             _ => visitor.visit_u64(self.tag.unwrap()),
@@ -426,7 +771,7 @@ impl ser::Serializer for Serializer {
     }
 }
 
-impl<'a> ser::SerializeSeq for Serializer {
+impl ser::SerializeSeq for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -441,7 +786,7 @@ impl<'a> ser::SerializeSeq for Serializer {
     }
 }
 
-impl<'a> ser::SerializeTuple for Serializer {
+impl ser::SerializeTuple for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -456,7 +801,7 @@ impl<'a> ser::SerializeTuple for Serializer {
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for Serializer {
+impl ser::SerializeTupleStruct for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -471,7 +816,7 @@ impl<'a> ser::SerializeTupleStruct for Serializer {
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for Serializer {
+impl ser::SerializeTupleVariant for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -486,7 +831,7 @@ impl<'a> ser::SerializeTupleVariant for Serializer {
     }
 }
 
-impl<'a> ser::SerializeMap for Serializer {
+impl ser::SerializeMap for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -506,7 +851,7 @@ impl<'a> ser::SerializeMap for Serializer {
     }
 }
 
-impl<'a> ser::SerializeStruct for Serializer {
+impl ser::SerializeStruct for Serializer {
     type Ok = u64;
     type Error = Error;
 
@@ -525,7 +870,7 @@ impl<'a> ser::SerializeStruct for Serializer {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for Serializer {
+impl ser::SerializeStructVariant for Serializer {
     type Ok = u64;
     type Error = Error;
 
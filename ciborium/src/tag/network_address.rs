@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes an IP address as tag 260 wrapping its bytes
+//!
+//! Use this module with `#[serde(with = "ciborium::tag::network_address")]`
+//! on a field of type `IpAddr`, `Ipv4Addr` or `Ipv6Addr`. IPv4 addresses
+//! take 4 bytes and IPv6 addresses 16 bytes; byte strings of any other
+//! length are rejected with an error which reports the length. The tag is
+//! required when reading unless the [`lenient`] module is used instead.
+//! See [`CidrPrefix`](super::CidrPrefix) for prefixes (tag 261).
+//!
+//! ```rust
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! use ciborium::{cbor, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Peer {
+//!     #[serde(with = "ciborium::tag::network_address")]
+//!     addr: IpAddr,
+//! }
+//!
+//! let peer = Peer { addr: Ipv4Addr::new(192, 0, 2, 1).into() };
+//! let value = Value::serialized(&peer).unwrap();
+//! assert_eq!(value, cbor!({ "addr" => tag!(260, Value::Bytes(vec![192, 0, 2, 1])) }).unwrap());
+//! assert_eq!(value.deserialized::<Peer>().unwrap(), peer);
+//! ```
+
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use alloc::vec::Vec;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{de, ser, Deserialize, Serialize};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An IP address type which this module can serialize
+///
+/// This trait is sealed; it is implemented for `IpAddr`, `Ipv4Addr` and
+/// `Ipv6Addr`.
+pub trait Address: Sized + private::Sealed {
+    #[doc(hidden)]
+    fn to_bytes(&self) -> Vec<u8>;
+
+    #[doc(hidden)]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str>;
+}
+
+impl private::Sealed for Ipv4Addr {}
+
+impl Address for Ipv4Addr {
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.octets().to_vec()
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut octets = [0u8; 4];
+        match bytes.len() {
+            4 => octets.copy_from_slice(bytes),
+            _ => return Err("a 4-byte IPv4 address"),
+        }
+
+        Ok(octets.into())
+    }
+}
+
+impl private::Sealed for Ipv6Addr {}
+
+impl Address for Ipv6Addr {
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.octets().to_vec()
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut octets = [0u8; 16];
+        match bytes.len() {
+            16 => octets.copy_from_slice(bytes),
+            _ => return Err("a 16-byte IPv6 address"),
+        }
+
+        Ok(octets.into())
+    }
+}
+
+impl private::Sealed for IpAddr {}
+
+impl Address for IpAddr {
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            IpAddr::V4(x) => x.to_bytes(),
+            IpAddr::V6(x) => x.to_bytes(),
+        }
+    }
+
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        match bytes.len() {
+            4 => Ipv4Addr::from_bytes(bytes).map(Into::into),
+            16 => Ipv6Addr::from_bytes(bytes).map(Into::into),
+            _ => Err("a 4 or 16-byte network address"),
+        }
+    }
+}
+
+/// Converts bytes into an address, reporting the length on failure
+pub(crate) fn address<T: Address, E: de::Error>(bytes: &[u8]) -> Result<T, E> {
+    T::from_bytes(bytes).map_err(|expected| {
+        de::Error::custom(format_args!(
+            "expected {}, found {} bytes",
+            expected,
+            bytes.len()
+        ))
+    })
+}
+
+fn parse<'de, T, D>(deserializer: D, lenient: bool) -> Result<T, D::Error>
+where
+    T: Address,
+    D: de::Deserializer<'de>,
+{
+    match Captured::<Value>::deserialize(deserializer)? {
+        Captured(Some(260), Value::Bytes(x)) => address(&x),
+        Captured(None, Value::Bytes(x)) if lenient => address(&x),
+        Captured(Some(260), ref v) => Err(de::Error::invalid_type(v.into(), &"bytes")),
+        Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+            "expected tag 260 (network address), found tag {}",
+            tag
+        ))),
+        Captured(None, ..) => Err(de::Error::custom(
+            "expected tag 260 (network address), found an untagged value",
+        )),
+    }
+}
+
+/// Serializes the address as tag 260 wrapping its bytes
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Address,
+    S: ser::Serializer,
+{
+    Captured(Some(260), Bytes(&value.to_bytes())).serialize(serializer)
+}
+
+/// Deserializes the address from tag 260 wrapping its bytes
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Address,
+    D: de::Deserializer<'de>,
+{
+    parse(deserializer, false)
+}
+
+/// Like the parent module, but also accepts untagged bytes when reading
+///
+/// Use this module with
+/// `#[serde(with = "ciborium::tag::network_address::lenient")]` to exchange
+/// addresses with encoders which don't emit tag 260. Values are still
+/// written with the tag.
+pub mod lenient {
+    use super::Address;
+
+    use serde::{de, ser};
+
+    /// Serializes the address as tag 260 wrapping its bytes
+    #[inline]
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Address,
+        S: ser::Serializer,
+    {
+        super::serialize(value, serializer)
+    }
+
+    /// Deserializes the address from bytes, optionally wrapped by tag 260
+    #[inline]
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Address,
+        D: de::Deserializer<'de>,
+    {
+        super::parse(deserializer, true)
+    }
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::network_address::{address, Address};
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use core::convert::TryFrom;
+use std::net::IpAddr;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// An IP network prefix (tag 261)
+///
+/// A prefix is encoded as tag 261 wrapping a map with a single entry,
+/// from the bytes of the address to the length of the prefix in bits.
+/// The address must take 4 or 16 bytes, and the length may not exceed
+/// the size of the address.
+///
+/// ```rust
+/// use std::net::Ipv4Addr;
+///
+/// use ciborium::{cbor, tag::CidrPrefix, value::Value};
+///
+/// let prefix = CidrPrefix::new(Ipv4Addr::new(192, 0, 2, 0).into(), 24).unwrap();
+/// let value = Value::serialized(&prefix).unwrap();
+/// assert_eq!(value, cbor!(tag!(261, { Value::Bytes(vec![192, 0, 2, 0]) => 24 })).unwrap());
+/// assert_eq!(value.deserialized::<CidrPrefix>().unwrap(), prefix);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CidrPrefix {
+    address: IpAddr,
+    length: u8,
+}
+
+impl CidrPrefix {
+    /// Creates a prefix, unless the length exceeds the size of the address
+    #[inline]
+    pub fn new(address: IpAddr, length: u8) -> Option<Self> {
+        let bits = match address {
+            IpAddr::V4(..) => 32,
+            IpAddr::V6(..) => 128,
+        };
+
+        match length <= bits {
+            true => Some(Self { address, length }),
+            false => None,
+        }
+    }
+
+    /// The address of the prefix
+    #[inline]
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    /// The length of the prefix in bits
+    #[inline]
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+}
+
+impl core::fmt::Display for CidrPrefix {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.address, self.length)
+    }
+}
+
+impl Serialize for CidrPrefix {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use ser::SerializeMap;
+
+        struct Entry<'a>(&'a CidrPrefix);
+
+        impl Serialize for Entry<'_> {
+            fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(&Bytes(&self.0.address.to_bytes()), &self.0.length)?;
+                map.end()
+            }
+        }
+
+        Captured(Some(261), Entry(self)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CidrPrefix {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut entries = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(261), Value::Map(x)) if x.len() == 1 => x,
+            Captured(Some(261), ..) => {
+                return Err(de::Error::custom(
+                    "expected a map from an address to a prefix length",
+                ))
+            }
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag 261 (network prefix), found tag {}",
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(
+                    "expected tag 261 (network prefix), found an untagged value",
+                ))
+            }
+        };
+
+        let (bytes, length) = match entries.pop() {
+            Some((Value::Bytes(bytes), Value::Integer(length))) => (bytes, length),
+            _ => {
+                return Err(de::Error::custom(
+                    "expected a map from an address to a prefix length",
+                ))
+            }
+        };
+
+        let address: IpAddr = address(&bytes)?;
+        u8::try_from(length)
+            .ok()
+            .and_then(|x| Self::new(address, x))
+            .ok_or_else(|| {
+                de::Error::custom(format_args!(
+                    "prefix length {} exceeds the size of {}",
+                    i128::from(length),
+                    address
+                ))
+            })
+    }
+}
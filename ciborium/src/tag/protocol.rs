@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The serde protocol which carries CBOR tags
+//!
+//! serde has no notion of tags, so ciborium passes them through an enum
+//! with reserved names. The names are namespaced to ciborium and contain
+//! `@`, which never appears in a Rust identifier, so they cannot clash
+//! with the names of derived types.
+//!
+//! A tagged data item is serialized as the tuple variant [`TAGGED`] of the
+//! enum [`NAME`], whose two fields are the tag and the data item. An
+//! untagged data item which may carry a tag is serialized as the newtype
+//! variant [`UNTAGGED`] of the same enum. ciborium's serializers emit the
+//! tag and the data item; other serializers use their usual enum encoding.
+//!
+//! When a type asks to deserialize the enum [`NAME`], ciborium's
+//! deserializers present the [`TAGGED`] variant, as a sequence of the tag
+//! and the data item, if the next data item has a tag, and the
+//! [`UNTAGGED`] variant otherwise. Other serializers and deserializers may
+//! implement the same protocol to exchange tags with these types.
+//!
+//! The types in [`crate::tag`] implement this protocol, so most wrappers
+//! can be built on [`Captured`](super::Captured). Using the protocol
+//! directly looks like this:
+//!
+//! ```rust
+//! use ciborium::{tag::{protocol, Captured}, value::Value};
+//! use serde::{ser::SerializeTupleVariant, Deserialize, Deserializer, Serialize, Serializer};
+//!
+//! /// A COSE_Sign1 message, which is tag 18
+//! #[derive(Debug, PartialEq)]
+//! struct Sign1(Vec<u8>);
+//!
+//! impl Serialize for Sign1 {
+//!     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+//!         let mut acc = serializer.serialize_tuple_variant(protocol::NAME, 0, protocol::TAGGED, 2)?;
+//!         acc.serialize_field(&18u64)?;
+//!         acc.serialize_field(&self.0)?;
+//!         acc.end()
+//!     }
+//! }
+//!
+//! impl<'de> Deserialize<'de> for Sign1 {
+//!     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+//!         match Captured::deserialize(deserializer)? {
+//!             Captured(Some(18), x) => Ok(Sign1(x)),
+//!             _ => Err(serde::de::Error::custom("expected tag 18")),
+//!         }
+//!     }
+//! }
+//!
+//! let value = Value::serialized(&Sign1(vec![1])).unwrap();
+//! assert_eq!(value, Value::Tag(18, Value::Array(vec![1.into()]).into()));
+//! assert_eq!(value.deserialized::<Sign1>().unwrap(), Sign1(vec![1]));
+//! ```
+
+/// The name of the enum which carries a tag
+pub const NAME: &str = "@@ciborium::tag@@";
+
+/// The variant for a tag and its data item
+pub const TAGGED: &str = "@@ciborium::tagged@@";
+
+/// The variant for a data item without a tag
+pub const UNTAGGED: &str = "@@ciborium::untagged@@";
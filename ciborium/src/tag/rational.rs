@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Captured, Required};
+use crate::value::Value;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// A rational number (tag 30): `num / den`
+///
+/// This serializes as tag 30 wrapping the array `[num, den]`. Integers
+/// which do not fit in a CBOR integer are written as bignums. Serialization
+/// fails if the denominator is zero.
+///
+/// Deserialization requires the tag and a nonzero denominator. Bignums are
+/// accepted for both items as long as they fit. The value is not reduced;
+/// see [`Rational::reduced()`].
+///
+/// ```rust
+/// use ciborium::{cbor, tag::Rational, value::Value};
+///
+/// let ratio = Rational::new(-6, 4).unwrap();
+/// assert_eq!(ratio.to_string(), "-6/4");
+/// assert_eq!(ratio.reduced(), Rational { num: -3, den: 2 });
+/// assert_eq!(f64::from(ratio), -1.5);
+///
+/// let value = Value::serialized(&ratio).unwrap();
+/// assert_eq!(value, cbor!(tag!(30, [-6, 4])).unwrap());
+/// assert_eq!(value.deserialized::<Rational>().unwrap(), ratio);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    /// The numerator
+    pub num: i128,
+
+    /// The denominator, which must not be zero
+    pub den: u128,
+}
+
+impl Rational {
+    /// Creates a rational number, unless the denominator is zero
+    #[inline]
+    pub fn new(num: i128, den: u128) -> Option<Self> {
+        match den {
+            0 => None,
+            den => Some(Self { num, den }),
+        }
+    }
+
+    /// Returns the same number in lowest terms
+    ///
+    /// Zero reduces to `0/1`. A zero denominator is left unchanged.
+    pub fn reduced(&self) -> Self {
+        if self.den == 0 {
+            return *self;
+        }
+
+        let (mut a, mut b) = (self.num.unsigned_abs(), self.den);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+
+        // The magnitude only shrinks, so only `i128::MIN` itself can wrap.
+        let num = (self.num.unsigned_abs() / a) as i128;
+        Self {
+            num: if self.num < 0 {
+                num.wrapping_neg()
+            } else {
+                num
+            },
+            den: self.den / a,
+        }
+    }
+}
+
+impl core::fmt::Display for Rational {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+impl From<Rational> for f64 {
+    /// Converts to a float, which may lose precision
+    ///
+    /// A zero denominator produces infinity or NaN.
+    #[inline]
+    fn from(value: Rational) -> Self {
+        value.num as f64 / value.den as f64
+    }
+}
+
+impl Serialize for Rational {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.den == 0 {
+            return Err(ser::Error::custom("rational denominator must be nonzero"));
+        }
+
+        Required::<_, 30>((self.num, self.den)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rational {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(30), Value::Array(items)) => items,
+            Captured(Some(30), ref v) => {
+                return Err(de::Error::invalid_type(v.into(), &"an array"))
+            }
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag 30 (rational number), found tag {}",
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(
+                    "expected tag 30 (rational number), found an untagged value",
+                ))
+            }
+        };
+
+        let (num, den) = match &items[..] {
+            [num, den] => (num, den),
+            _ => return Err(de::Error::invalid_length(items.len(), &"two items")),
+        };
+
+        let num = num
+            .as_i128()
+            .ok_or_else(|| de::Error::custom("invalid rational numerator"))?;
+        let den = den
+            .as_u128()
+            .ok_or_else(|| de::Error::custom("invalid rational denominator"))?;
+
+        Self::new(num, den).ok_or_else(|| de::Error::custom("rational denominator must be nonzero"))
+    }
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serde helper functions which require a tag around a field
+//!
+//! These functions do the same job as [`Required`](super::Required), but
+//! let a field keep its own type. The tag is given as the first generic
+//! argument:
+//!
+//! ```rust
+//! use ciborium::{cbor, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(
+//!         serialize_with = "ciborium::tag::required::serialize::<37, _, _>",
+//!         deserialize_with = "ciborium::tag::required::deserialize::<37, _, _>"
+//!     )]
+//!     id: String,
+//! }
+//!
+//! let record = Record { id: "8c8c8c8c".into() };
+//! let value = Value::serialized(&record).unwrap();
+//! assert_eq!(value, cbor!({ "id" => tag!(37, "8c8c8c8c") }).unwrap());
+//! assert_eq!(value.deserialized::<Record>().unwrap(), record);
+//! ```
+//!
+//! The [`tagged_with!`](crate::tagged_with) macro generates a module
+//! around these functions for use with `#[serde(with = "...")]`.
+
+use super::Required;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// Serializes the value wrapped by tag `TAG`
+#[inline]
+pub fn serialize<const TAG: u64, T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: ser::Serializer,
+{
+    Required::<&T, TAG>(value).serialize(serializer)
+}
+
+/// Deserializes the value, which must be wrapped by tag `TAG`
+#[inline]
+pub fn deserialize<'de, const TAG: u64, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: de::Deserializer<'de>,
+{
+    Required::<T, TAG>::deserialize(deserializer).map(|x| x.0)
+}
+
+/// Like the parent module, but for `Option` fields
+///
+/// `None` is written as an untagged null and `Some` values are written
+/// wrapped by the tag. When reading, a null produces `None` and any other
+/// value must carry the tag.
+pub mod option {
+    use super::super::Captured;
+    use super::Required;
+
+    use serde::{de, ser, Deserialize};
+
+    /// Serializes `None` as null and `Some` wrapped by tag `TAG`
+    #[inline]
+    pub fn serialize<const TAG: u64, T, S>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        T: ser::Serialize,
+        S: ser::Serializer,
+    {
+        match value {
+            Some(x) => serializer.serialize_some(&Required::<&T, TAG>(x)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserializes null as `None` and other values from tag `TAG`
+    #[inline]
+    pub fn deserialize<'de, const TAG: u64, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: de::Deserializer<'de>,
+    {
+        match Captured::<Option<T>>::deserialize(deserializer)? {
+            Captured(Some(t), Some(v)) if t == TAG => Ok(Some(v)),
+            Captured(None, None) => Ok(None),
+            _ => Err(de::Error::custom("required tag not found")),
+        }
+    }
+}
+
+/// Generates a module which requires a tag around a field
+///
+/// The generated module contains `serialize` and `deserialize` functions
+/// for use with `#[serde(with = "...")]`, which write the tag before the
+/// value and require it when reading. A nested `option` module does the
+/// same for `Option` fields, writing `None` as an untagged null. The tag
+/// may be any constant expression in scope at the invocation, but the
+/// invocation must then be at module level rather than inside a function.
+///
+/// ```rust
+/// use ciborium::{cbor, value::Value};
+/// use serde::{Deserialize, Serialize};
+///
+/// const DATE: u64 = 1004;
+///
+/// ciborium::tagged_with!(mod uuid_tag = 37);
+/// ciborium::tagged_with!(mod date_tag = DATE);
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Record {
+///     #[serde(with = "uuid_tag")]
+///     id: u32,
+///
+///     #[serde(with = "date_tag::option")]
+///     born: Option<String>,
+/// }
+///
+/// fn main() {
+///     let record = Record { id: 7, born: Some("1940-10-09".into()) };
+///     let value = Value::serialized(&record).unwrap();
+///     assert_eq!(value, cbor!({
+///         "id" => tag!(37, 7),
+///         "born" => tag!(1004, "1940-10-09"),
+///     }).unwrap());
+///     assert_eq!(value.deserialized::<Record>().unwrap(), record);
+/// }
+/// ```
+#[macro_export]
+macro_rules! tagged_with {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident = $tag:expr) => {
+        $(#[$attr])*
+        $vis mod $name {
+            #[allow(unused_imports)]
+            const TAG: u64 = {
+                use super::*;
+                $tag
+            };
+
+            /// Serializes the value wrapped by the tag
+            #[inline]
+            pub fn serialize<T, S>(value: &T, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                T: ?Sized + $crate::__private::serde::Serialize,
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::tag::required::serialize::<TAG, T, S>(value, serializer)
+            }
+
+            /// Deserializes the value, which must be wrapped by the tag
+            #[inline]
+            pub fn deserialize<'de, T, D>(deserializer: D) -> ::core::result::Result<T, D::Error>
+            where
+                T: $crate::__private::serde::Deserialize<'de>,
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                $crate::tag::required::deserialize::<TAG, T, D>(deserializer)
+            }
+
+            /// Like the parent module, but for `Option` fields
+            pub mod option {
+                /// Serializes `None` as null and `Some` wrapped by the tag
+                #[inline]
+                pub fn serialize<T, S>(
+                    value: &::core::option::Option<T>,
+                    serializer: S,
+                ) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    T: $crate::__private::serde::Serialize,
+                    S: $crate::__private::serde::Serializer,
+                {
+                    $crate::tag::required::option::serialize::<{ super::TAG }, T, S>(value, serializer)
+                }
+
+                /// Deserializes null as `None` and other values from the tag
+                #[inline]
+                pub fn deserialize<'de, T, D>(
+                    deserializer: D,
+                ) -> ::core::result::Result<::core::option::Option<T>, D::Error>
+                where
+                    T: $crate::__private::serde::Deserialize<'de>,
+                    D: $crate::__private::serde::Deserializer<'de>,
+                {
+                    $crate::tag::required::option::deserialize::<{ super::TAG }, T, D>(deserializer)
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Captured, Required};
+use crate::value::Value;
+
+use alloc::string::String;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+/// A MIME message (tag 36)
+///
+/// This serializes as tag 36 wrapping the text of the message, including
+/// its headers. The tag is required when reading, but the message itself
+/// is not parsed.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::MimeMessage, value::Value};
+///
+/// let message = MimeMessage("Content-Type: text/plain\r\n\r\nHello".into());
+///
+/// let value = Value::serialized(&message).unwrap();
+/// assert_eq!(value, cbor!(tag!(36, "Content-Type: text/plain\r\n\r\nHello")).unwrap());
+/// assert_eq!(value.deserialized::<MimeMessage>().unwrap(), message);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MimeMessage(pub String);
+
+impl Serialize for MimeMessage {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 36>(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MimeMessage {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(36), Value::Text(x)) => Ok(Self(x)),
+            Captured(Some(36), ref v) => Err(de::Error::invalid_type(v.into(), &"a string")),
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 36 (MIME message), found tag {}",
+                tag
+            ))),
+            Captured(None, ..) => Err(de::Error::custom(
+                "expected tag 36 (MIME message), found an untagged value",
+            )),
+        }
+    }
+}
+
+/// A text string in a particular language (tag 38)
+///
+/// This serializes as tag 38 wrapping the array `[lang, text]`, where
+/// `lang` is a BCP 47 language tag such as `en` or `de-CH`. When reading,
+/// the tag and exactly two text items are required. The syntax of the
+/// language tag is only checked on request, with
+/// [`LangString::is_well_formed()`].
+///
+/// ```rust
+/// use ciborium::{cbor, tag::LangString, value::Value};
+///
+/// let greeting = LangString { lang: "de-CH".into(), text: "Grüezi".into() };
+/// assert!(greeting.is_well_formed());
+///
+/// let value = Value::serialized(&greeting).unwrap();
+/// assert_eq!(value, cbor!(tag!(38, ["de-CH", "Grüezi"])).unwrap());
+/// assert_eq!(value.deserialized::<LangString>().unwrap(), greeting);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LangString {
+    /// The BCP 47 language tag
+    pub lang: String,
+
+    /// The text
+    pub text: String,
+}
+
+impl LangString {
+    /// Checks that the language tag follows the BCP 47 syntax
+    ///
+    /// The tag must consist of subtags of one to eight ASCII letters or
+    /// digits, separated by hyphens. The first subtag must be letters
+    /// only, and a single-character subtag (such as the `x` of a private
+    /// use tag) must be followed by another subtag. The subtags are not
+    /// checked against the IANA registry.
+    pub fn is_well_formed(&self) -> bool {
+        let mut subtags = self.lang.split('-');
+
+        match subtags.next() {
+            Some(first) if (1..=8).contains(&first.len()) => {
+                if !first.bytes().all(|b| b.is_ascii_alphabetic()) {
+                    return false;
+                }
+            }
+
+            _ => return false,
+        }
+
+        let mut singleton = self.lang.len() == 1;
+        for subtag in subtags {
+            if !(1..=8).contains(&subtag.len())
+                || !subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+            {
+                return false;
+            }
+
+            singleton = subtag.len() == 1;
+        }
+
+        !singleton
+    }
+}
+
+impl Serialize for LangString {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Required::<_, 38>((&self.lang, &self.text)).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LangString {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let items = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(38), Value::Array(items)) => items,
+            Captured(Some(38), ref v) => {
+                return Err(de::Error::invalid_type(v.into(), &"an array"))
+            }
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag 38 (language-tagged string), found tag {}",
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(
+                    "expected tag 38 (language-tagged string), found an untagged value",
+                ))
+            }
+        };
+
+        let (lang, text) = match &items[..] {
+            [lang, text] => (lang, text),
+            _ => return Err(de::Error::invalid_length(items.len(), &"two items")),
+        };
+
+        match (lang, text) {
+            (Value::Text(lang), Value::Text(text)) => Ok(Self {
+                lang: lang.clone(),
+                text: text.clone(),
+            }),
+
+            _ => Err(de::Error::custom(
+                "language-tagged string items must both be text",
+            )),
+        }
+    }
+}
@@ -0,0 +1,478 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Captured, Required};
+use crate::value::Value;
+
+use alloc::string::String;
+use core::convert::TryFrom;
+use core::fmt::Write as _;
+use core::str::FromStr;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+const NANOS: u32 = 1_000_000_000;
+const DAY: i64 = 86_400;
+
+/// An error converting a date/time to or from its CBOR representation
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DateTimeError {
+    /// The text is not a valid RFC 3339 date/time
+    Malformed,
+
+    /// The date/time cannot be represented in the target form
+    OutOfRange,
+
+    /// The tag wraps neither text (tag 0) nor a number (tag 1)
+    InvalidContent,
+}
+
+impl core::fmt::Display for DateTimeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Malformed => "malformed RFC 3339 date/time",
+            Self::OutOfRange => "date/time out of range",
+            Self::InvalidContent => "tag 0 must contain text and tag 1 a number",
+        })
+    }
+}
+
+impl serde::de::StdError for DateTimeError {}
+
+/// A point in time relative to the Unix epoch
+///
+/// This is the common representation of the date/time tags: tag 0 (an
+/// RFC 3339 string) and tag 1 (seconds since the epoch). It has nanosecond
+/// precision and ignores leap seconds, just like POSIX time.
+///
+/// ```rust
+/// use ciborium::tag::Timestamp;
+///
+/// let time: Timestamp = "2013-03-21T20:04:00.5+01:00".parse().unwrap();
+/// assert_eq!(time.secs(), 1363892640);
+/// assert_eq!(time.nanos(), 500_000_000);
+/// assert_eq!(time.to_rfc3339().unwrap(), "2013-03-21T19:04:00.5Z");
+/// ```
+///
+/// As a field, a `Timestamp` accepts either tag 0 or tag 1 when reading.
+/// It is written as tag 1, wrapping an integer when the seconds are whole
+/// and a float otherwise; [`Timestamp::with_form()`] selects another form.
+/// To require one of the tags when reading, use the
+/// [`datetime_string`](super::datetime_string) or
+/// [`epoch_time`](super::epoch_time) modules instead.
+///
+/// ```rust
+/// use ciborium::{cbor, tag::{Timestamp, TimestampForm}, value::Value};
+///
+/// let time: Timestamp = cbor!(tag!(0, "2013-03-21T20:04:00Z"))
+///     .unwrap()
+///     .deserialized()
+///     .unwrap();
+///
+/// let value = Value::serialized(&time).unwrap();
+/// assert_eq!(value, cbor!(tag!(1, 1363896240)).unwrap());
+///
+/// let value = Value::serialized(&time.with_form(TimestampForm::Text)).unwrap();
+/// assert_eq!(value, cbor!(tag!(0, "2013-03-21T20:04:00Z")).unwrap());
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    secs: i64,
+    nanos: u32,
+}
+
+/// Returns the number of days since 1970-01-01 of a proleptic Gregorian date
+pub(super) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Returns the proleptic Gregorian date of a number of days since 1970-01-01
+pub(super) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+pub(super) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+pub(super) struct Parser<'a>(pub &'a [u8]);
+
+impl Parser<'_> {
+    pub(super) fn digits(&mut self, count: usize) -> Result<u32, DateTimeError> {
+        if self.0.len() < count || !self.0[..count].iter().all(u8::is_ascii_digit) {
+            return Err(DateTimeError::Malformed);
+        }
+
+        let (digits, rest) = self.0.split_at(count);
+        self.0 = rest;
+        Ok(digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+    }
+
+    pub(super) fn expect(&mut self, any: &[u8]) -> Result<u8, DateTimeError> {
+        match self.0.split_first() {
+            Some((c, rest)) if any.contains(c) => {
+                self.0 = rest;
+                Ok(*c)
+            }
+
+            _ => Err(DateTimeError::Malformed),
+        }
+    }
+
+    pub(super) fn range(&mut self, count: usize, min: u32, max: u32) -> Result<u32, DateTimeError> {
+        match self.digits(count)? {
+            n if (min..=max).contains(&n) => Ok(n),
+            _ => Err(DateTimeError::Malformed),
+        }
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = DateTimeError;
+
+    /// Parses an RFC 3339 date/time, such as `2013-03-21T20:04:00Z`
+    ///
+    /// Fractional seconds beyond nanosecond precision are truncated. A leap
+    /// second (`:60`) is treated as the first second of the next minute.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser(text.as_bytes());
+
+        let year = i64::from(parser.digits(4)?);
+        parser.expect(b"-")?;
+        let month = parser.range(2, 1, 12)?;
+        parser.expect(b"-")?;
+        let day = parser.range(2, 1, days_in_month(year, month))?;
+        parser.expect(b"Tt")?;
+        let hour = parser.range(2, 0, 23)?;
+        parser.expect(b":")?;
+        let minute = parser.range(2, 0, 59)?;
+        parser.expect(b":")?;
+        let second = parser.range(2, 0, 60)?;
+
+        let mut nanos = 0;
+        if parser.0.first() == Some(&b'.') {
+            parser.0 = &parser.0[1..];
+
+            let count = parser.0.iter().take_while(|c| c.is_ascii_digit()).count();
+            if count == 0 {
+                return Err(DateTimeError::Malformed);
+            }
+
+            for (i, digit) in parser.0[..count].iter().enumerate().take(9) {
+                nanos += u32::from(digit - b'0') * 10u32.pow(8 - i as u32);
+            }
+
+            parser.0 = &parser.0[count..];
+        }
+
+        let offset = match parser.expect(b"Zz+-")? {
+            b'Z' | b'z' => 0,
+            sign => {
+                let hours = parser.range(2, 0, 23)?;
+                parser.expect(b":")?;
+                let minutes = parser.range(2, 0, 59)?;
+
+                let offset = i64::from(hours * 3600 + minutes * 60);
+                match sign {
+                    b'+' => offset,
+                    _ => -offset,
+                }
+            }
+        };
+
+        if !parser.0.is_empty() {
+            return Err(DateTimeError::Malformed);
+        }
+
+        let secs = days_from_civil(year, month, day) * DAY
+            + i64::from(hour * 3600 + minute * 60 + second)
+            - offset;
+
+        Ok(Self { secs, nanos })
+    }
+}
+
+impl Timestamp {
+    /// Creates a timestamp from seconds and nanoseconds since the epoch
+    ///
+    /// The nanoseconds are always added, even when `secs` is negative.
+    /// Returns `None` when `nanos` is a second or more.
+    #[inline]
+    pub const fn new(secs: i64, nanos: u32) -> Option<Self> {
+        match nanos < NANOS {
+            true => Some(Self { secs, nanos }),
+            false => None,
+        }
+    }
+
+    /// Creates a timestamp from a (possibly fractional) number of seconds
+    ///
+    /// The number is rounded to the nearest nanosecond. An error is returned
+    /// for NaN, infinity and values which do not fit in an `i64` of seconds.
+    pub fn from_secs_f64(secs: f64) -> Result<Self, DateTimeError> {
+        // 2^63 is exact as a float, unlike `i64::MAX`.
+        const LIMIT: f64 = 9_223_372_036_854_775_808.0;
+
+        let whole = secs.floor();
+        if !(-LIMIT..LIMIT).contains(&whole) {
+            return Err(DateTimeError::OutOfRange);
+        }
+
+        let secs_i64 = whole as i64;
+        match ((secs - whole) * f64::from(NANOS)).round() as u32 {
+            NANOS => match secs_i64.checked_add(1) {
+                Some(secs) => Ok(Self { secs, nanos: 0 }),
+                None => Err(DateTimeError::OutOfRange),
+            },
+
+            nanos => Ok(Self {
+                secs: secs_i64,
+                nanos,
+            }),
+        }
+    }
+
+    /// The whole seconds since the epoch, rounded towards negative infinity
+    #[inline]
+    pub const fn secs(&self) -> i64 {
+        self.secs
+    }
+
+    /// The nanoseconds after [`Timestamp::secs()`]
+    #[inline]
+    pub const fn nanos(&self) -> u32 {
+        self.nanos
+    }
+
+    /// The seconds since the epoch as a float, which may lose precision
+    #[inline]
+    pub fn as_secs_f64(&self) -> f64 {
+        self.secs as f64 + f64::from(self.nanos) / f64::from(NANOS)
+    }
+
+    /// Formats the timestamp as an RFC 3339 date/time in UTC
+    ///
+    /// Fractional seconds are included only when they are not zero, and
+    /// without trailing zeros. Years outside of `0000` to `9999` cannot be
+    /// represented and produce an error.
+    pub fn to_rfc3339(&self) -> Result<String, DateTimeError> {
+        let (year, month, day) = civil_from_days(self.secs.div_euclid(DAY));
+        if !(0..=9999).contains(&year) {
+            return Err(DateTimeError::OutOfRange);
+        }
+
+        let time = self.secs.rem_euclid(DAY);
+        let mut text = String::new();
+        write!(
+            text,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time / 3600,
+            time / 60 % 60,
+            time % 60
+        )
+        .unwrap();
+
+        if self.nanos != 0 {
+            let fraction = alloc::format!("{:09}", self.nanos);
+            text.push('.');
+            text.push_str(fraction.trim_end_matches('0'));
+        }
+
+        text.push('Z');
+        Ok(text)
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<std::time::SystemTime> for Timestamp {
+    type Error = DateTimeError;
+
+    fn try_from(value: std::time::SystemTime) -> Result<Self, Self::Error> {
+        use core::convert::TryInto;
+
+        let (negative, duration) = match value.duration_since(std::time::UNIX_EPOCH) {
+            Ok(duration) => (false, duration),
+            Err(e) => (true, e.duration()),
+        };
+
+        let secs: i64 = duration
+            .as_secs()
+            .try_into()
+            .map_err(|_| DateTimeError::OutOfRange)?;
+
+        let nanos = duration.subsec_nanos();
+        Ok(match (negative, nanos) {
+            (false, _) => Self { secs, nanos },
+            (true, 0) => Self { secs: -secs, nanos },
+            (true, _) => Self {
+                secs: -secs - 1,
+                nanos: NANOS - nanos,
+            },
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Timestamp> for std::time::SystemTime {
+    type Error = DateTimeError;
+
+    fn try_from(value: Timestamp) -> Result<Self, Self::Error> {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let time = match value.secs {
+            secs if secs >= 0 => UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64)),
+            secs => UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs())),
+        };
+
+        time.and_then(|t| t.checked_add(Duration::from_nanos(value.nanos.into())))
+            .ok_or(DateTimeError::OutOfRange)
+    }
+}
+
+/// The form in which a [`Timestamp`] is serialized
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TimestampForm {
+    /// Tag 1 wrapping an integer when the seconds are whole, else a float
+    Epoch,
+
+    /// Tag 1 wrapping a float, which may lose precision
+    EpochFloat,
+
+    /// Tag 0 wrapping an RFC 3339 string in UTC
+    Text,
+}
+
+impl Default for TimestampForm {
+    #[inline]
+    fn default() -> Self {
+        Self::Epoch
+    }
+}
+
+/// A [`Timestamp`] which serializes in a chosen [`TimestampForm`]
+///
+/// See [`Timestamp::with_form()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FormattedTimestamp {
+    timestamp: Timestamp,
+    form: TimestampForm,
+}
+
+impl Timestamp {
+    /// Returns a wrapper which serializes the timestamp in `form`
+    #[inline]
+    pub const fn with_form(self, form: TimestampForm) -> FormattedTimestamp {
+        FormattedTimestamp {
+            timestamp: self,
+            form,
+        }
+    }
+}
+
+impl FormattedTimestamp {
+    /// The timestamp
+    #[inline]
+    pub const fn timestamp(&self) -> Timestamp {
+        self.timestamp
+    }
+
+    /// The form in which the timestamp is serialized
+    #[inline]
+    pub const fn form(&self) -> TimestampForm {
+        self.form
+    }
+}
+
+impl Serialize for FormattedTimestamp {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let time = self.timestamp;
+
+        match self.form {
+            TimestampForm::Epoch if time.nanos == 0 => {
+                Required::<_, 1>(time.secs).serialize(serializer)
+            }
+
+            TimestampForm::Epoch | TimestampForm::EpochFloat => {
+                Required::<_, 1>(time.as_secs_f64()).serialize(serializer)
+            }
+
+            TimestampForm::Text => match time.to_rfc3339() {
+                Ok(text) => Required::<_, 0>(text).serialize(serializer),
+                Err(e) => Err(ser::Error::custom(e)),
+            },
+        }
+    }
+}
+
+impl Serialize for Timestamp {
+    #[inline]
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.with_form(TimestampForm::default())
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(0), Value::Text(x)) => x.parse().map_err(|_| {
+                de::Error::custom(format_args!("malformed RFC 3339 date/time {:?}", x))
+            }),
+
+            Captured(Some(1), Value::Integer(x)) => match i64::try_from(x) {
+                Ok(secs) => Ok(Self { secs, nanos: 0 }),
+                Err(..) => Err(de::Error::custom("epoch time out of range")),
+            },
+
+            Captured(Some(1), Value::Float(x)) => {
+                let x = f64::from(x);
+                if x.is_nan() {
+                    return Err(de::Error::custom("epoch time is NaN"));
+                }
+
+                if x.is_infinite() {
+                    return Err(de::Error::custom("epoch time is infinite"));
+                }
+
+                Self::from_secs_f64(x).map_err(|_| de::Error::custom("epoch time out of range"))
+            }
+
+            Captured(Some(0), ref v) => Err(de::Error::invalid_type(v.into(), &"a string")),
+            Captured(Some(1), ref v) => {
+                Err(de::Error::invalid_type(v.into(), &"an integer or a float"))
+            }
+
+            Captured(Some(tag), ..) => Err(de::Error::custom(format_args!(
+                "expected tag 0 or 1 (date/time), found tag {}",
+                tag
+            ))),
+            Captured(None, ..) => Err(de::Error::custom(
+                "expected tag 0 or 1 (date/time), found an untagged value",
+            )),
+        }
+    }
+}
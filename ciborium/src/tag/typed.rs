@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A numeric type which can be stored in a [`TypedArray`]
+///
+/// This trait is sealed; it is implemented for the fixed width integers
+/// and for `f32` and `f64`.
+pub trait Element: Copy + private::Sealed {
+    /// The tag of a big endian array of this type
+    const TAG: u64;
+
+    /// The size of one element in bytes
+    const SIZE: usize;
+
+    #[doc(hidden)]
+    fn read(bytes: &[u8], little_endian: bool) -> Self;
+
+    #[doc(hidden)]
+    fn write(self, little_endian: bool, output: &mut Vec<u8>);
+}
+
+macro_rules! element {
+    ($($t:ident => $tag:expr,)+) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl Element for $t {
+                const TAG: u64 = $tag;
+                const SIZE: usize = core::mem::size_of::<$t>();
+
+                #[inline]
+                fn read(bytes: &[u8], little_endian: bool) -> Self {
+                    let bytes = bytes.try_into().unwrap();
+                    match little_endian {
+                        true => $t::from_le_bytes(bytes),
+                        false => $t::from_be_bytes(bytes),
+                    }
+                }
+
+                #[inline]
+                fn write(self, little_endian: bool, output: &mut Vec<u8>) {
+                    match little_endian {
+                        true => output.extend_from_slice(&self.to_le_bytes()),
+                        false => output.extend_from_slice(&self.to_be_bytes()),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+element! {
+    u8 => 64,
+    u16 => 65,
+    u32 => 66,
+    u64 => 67,
+    i8 => 72,
+    i16 => 73,
+    i32 => 74,
+    i64 => 75,
+    f32 => 81,
+    f64 => 82,
+}
+
+/// The little endian tag for arrays of `T`, if there is one
+#[inline]
+fn little_endian_tag<T: Element>() -> Option<u64> {
+    match T::TAG {
+        // Single byte types have no byte order; tag 68 holds clamped bytes.
+        64 => Some(68),
+        72 => None,
+        tag => Some(tag | 4),
+    }
+}
+
+/// A homogeneous numeric array (RFC 8746, tags 64 to 87)
+///
+/// The elements are packed into a single byte string in the chosen byte
+/// order, which is much smaller and faster to process than an array of
+/// individually encoded numbers. When reading, either byte order is
+/// accepted and recorded. A byte string whose length is not a multiple of
+/// the element size is rejected.
+///
+/// ```rust
+/// use ciborium::{tag::TypedArray, value::Value};
+///
+/// let array = TypedArray::big_endian(vec![1u16, 2]);
+/// let value = Value::serialized(&array).unwrap();
+/// assert_eq!(value, Value::Tag(65, Value::Bytes(vec![0, 1, 0, 2]).into()));
+///
+/// let decoded: TypedArray<u16> = value.deserialized().unwrap();
+/// assert_eq!(decoded, array);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TypedArray<T> {
+    /// The elements of the array
+    pub elements: Vec<T>,
+
+    /// Whether the elements are stored in little endian byte order
+    pub little_endian: bool,
+}
+
+impl<T: Element> TypedArray<T> {
+    /// Creates an array which is stored in big endian byte order
+    #[inline]
+    pub fn big_endian(elements: Vec<T>) -> Self {
+        Self {
+            elements,
+            little_endian: false,
+        }
+    }
+
+    /// Creates an array which is stored in little endian byte order
+    ///
+    /// Single byte elements have no byte order and are always stored with
+    /// the big endian tag, except for `u8` which uses the clamped tag (68).
+    #[inline]
+    pub fn little_endian(elements: Vec<T>) -> Self {
+        Self {
+            elements,
+            little_endian: true,
+        }
+    }
+
+    /// The tag this array is serialized with
+    #[inline]
+    pub fn tag(&self) -> u64 {
+        match self.little_endian {
+            true => little_endian_tag::<T>().unwrap_or(T::TAG),
+            false => T::TAG,
+        }
+    }
+}
+
+impl<T: Element> From<Vec<T>> for TypedArray<T> {
+    /// Creates an array which is stored in the native byte order
+    #[inline]
+    fn from(elements: Vec<T>) -> Self {
+        Self {
+            elements,
+            little_endian: cfg!(target_endian = "little"),
+        }
+    }
+}
+
+impl<T: Element> Serialize for TypedArray<T> {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(self.elements.len() * T::SIZE);
+        for element in self.elements.iter() {
+            element.write(self.little_endian, &mut bytes);
+        }
+
+        Captured(Some(self.tag()), Bytes(&bytes)).serialize(serializer)
+    }
+}
+
+impl<'de, T: Element> Deserialize<'de> for TypedArray<T> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (tag, bytes) = match Captured::<Value>::deserialize(deserializer)? {
+            Captured(Some(tag), Value::Bytes(x)) => (tag, x),
+            Captured(Some(tag), ref v) if (64..=87).contains(&tag) => {
+                return Err(de::Error::invalid_type(v.into(), &"bytes"))
+            }
+            Captured(Some(tag), ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag {} (typed array), found tag {}",
+                    T::TAG,
+                    tag
+                )))
+            }
+            Captured(None, ..) => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag {} (typed array), found an untagged value",
+                    T::TAG
+                )))
+            }
+        };
+
+        let little_endian = match tag {
+            tag if tag == T::TAG => false,
+            tag if Some(tag) == little_endian_tag::<T>() => true,
+            tag => {
+                return Err(de::Error::custom(format_args!(
+                    "expected tag {} (typed array), found tag {}",
+                    T::TAG,
+                    tag
+                )))
+            }
+        };
+
+        if bytes.len() % T::SIZE != 0 {
+            return Err(de::Error::custom(format_args!(
+                "typed array of {} bytes is not a multiple of the element size ({})",
+                bytes.len(),
+                T::SIZE
+            )));
+        }
+
+        Ok(Self {
+            elements: bytes
+                .chunks_exact(T::SIZE)
+                .map(|x| T::read(x, little_endian))
+                .collect(),
+            little_endian,
+        })
+    }
+}
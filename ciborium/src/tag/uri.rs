@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes a URI as tag 32 wrapping its text
+//!
+//! Use this module with `#[serde(with = "ciborium::tag::uri")]` on a field
+//! of any type which displays as a URI and parses from one, such as
+//! `url::Url`. Parse failures are reported as semantic errors. The tag is
+//! required when reading unless the [`lenient`] module is used instead.
+//!
+//! ```rust
+//! use ciborium::{cbor, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Link {
+//!     #[serde(with = "ciborium::tag::uri")]
+//!     href: String,
+//! }
+//!
+//! let link = Link { href: "http://www.example.com".into() };
+//! let value = Value::serialized(&link).unwrap();
+//! assert_eq!(value, cbor!({ "href" => tag!(32, "http://www.example.com") }).unwrap());
+//! assert_eq!(value.deserialized::<Link>().unwrap(), link);
+//! ```
+
+use super::{Captured, Required};
+
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use core::str::FromStr;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+fn parse<'de, T, D>(deserializer: D, lenient: bool) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: de::Deserializer<'de>,
+{
+    let text = match Captured::<String>::deserialize(deserializer)? {
+        Captured(Some(32), text) => text,
+        Captured(None, text) if lenient => text,
+        Captured(Some(tag), ..) => {
+            return Err(de::Error::custom(format_args!(
+                "expected tag 32 (URI), found tag {}",
+                tag
+            )))
+        }
+        Captured(None, ..) => {
+            return Err(de::Error::custom(
+                "expected tag 32 (URI), found untagged text",
+            ))
+        }
+    };
+
+    text.parse()
+        .map_err(|e| de::Error::custom(format_args!("invalid URI {:?}: {}", text, e)))
+}
+
+/// Serializes the value as tag 32 wrapping its text
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Display,
+    S: ser::Serializer,
+{
+    Required::<_, 32>(value.to_string()).serialize(serializer)
+}
+
+/// Deserializes the value from tag 32 wrapping its text
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: de::Deserializer<'de>,
+{
+    parse(deserializer, false)
+}
+
+/// Like the parent module, but also accepts untagged text when reading
+///
+/// Use this module with `#[serde(with = "ciborium::tag::uri::lenient")]`
+/// to exchange URIs with encoders which don't emit tag 32. Values are
+/// still written with the tag.
+pub mod lenient {
+    use core::fmt::Display;
+    use core::str::FromStr;
+
+    use serde::{de, ser};
+
+    /// Serializes the value as tag 32 wrapping its text
+    #[inline]
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + Display,
+        S: ser::Serializer,
+    {
+        super::serialize(value, serializer)
+    }
+
+    /// Deserializes the value from text, optionally wrapped by tag 32
+    #[inline]
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: de::Deserializer<'de>,
+    {
+        super::parse(deserializer, true)
+    }
+}
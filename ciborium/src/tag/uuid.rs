@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serializes a UUID as tag 37 wrapping its 16 bytes
+//!
+//! Use this module with `#[serde(with = "ciborium::tag::uuid")]` on a field
+//! of any type which exposes its 16 bytes and converts from them, such as
+//! `[u8; 16]`. A byte string of any other length is rejected with an error
+//! which reports its length. The tag is required when reading unless the
+//! [`lenient`] module is used instead.
+//!
+//! ```rust
+//! use ciborium::{cbor, value::Value};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "ciborium::tag::uuid")]
+//!     id: [u8; 16],
+//! }
+//!
+//! let record = Record { id: [0x8c; 16] };
+//! let value = Value::serialized(&record).unwrap();
+//! assert_eq!(value, cbor!({ "id" => tag!(37, Value::Bytes(vec![0x8c; 16])) }).unwrap());
+//! assert_eq!(value.deserialized::<Record>().unwrap(), record);
+//! ```
+
+use super::{Bytes, Captured};
+use crate::value::Value;
+
+use serde::{de, ser, Deserialize, Serialize};
+
+fn parse<'de, T, D>(deserializer: D, lenient: bool) -> Result<T, D::Error>
+where
+    T: From<[u8; 16]>,
+    D: de::Deserializer<'de>,
+{
+    let bytes = match Captured::<Value>::deserialize(deserializer)? {
+        Captured(Some(37), Value::Bytes(x)) => x,
+        Captured(None, Value::Bytes(x)) if lenient => x,
+        Captured(Some(37), ref v) => return Err(de::Error::invalid_type(v.into(), &"bytes")),
+        Captured(Some(tag), ..) => {
+            return Err(de::Error::custom(format_args!(
+                "expected tag 37 (UUID), found tag {}",
+                tag
+            )))
+        }
+        Captured(None, ..) => {
+            return Err(de::Error::custom(
+                "expected tag 37 (UUID), found an untagged value",
+            ))
+        }
+    };
+
+    let mut uuid = [0u8; 16];
+    match bytes.len() {
+        16 => uuid.copy_from_slice(&bytes),
+        n => {
+            return Err(de::Error::custom(format_args!(
+                "expected a 16-byte UUID, found {} bytes",
+                n
+            )))
+        }
+    }
+
+    Ok(T::from(uuid))
+}
+
+/// Serializes the value as tag 37 wrapping its 16 bytes
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: ser::Serializer,
+{
+    match value.as_ref() {
+        bytes if bytes.len() == 16 => Captured(Some(37), Bytes(bytes)).serialize(serializer),
+        bytes => Err(ser::Error::custom(format_args!(
+            "expected a 16-byte UUID, found {} bytes",
+            bytes.len()
+        ))),
+    }
+}
+
+/// Deserializes the value from tag 37 wrapping its 16 bytes
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: From<[u8; 16]>,
+    D: de::Deserializer<'de>,
+{
+    parse(deserializer, false)
+}
+
+/// Like the parent module, but also accepts untagged bytes when reading
+///
+/// Use this module with `#[serde(with = "ciborium::tag::uuid::lenient")]`
+/// to exchange UUIDs with encoders which don't emit tag 37. Values are
+/// still written with the tag.
+pub mod lenient {
+    use serde::{de, ser};
+
+    /// Serializes the value as tag 37 wrapping its 16 bytes
+    #[inline]
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + AsRef<[u8]>,
+        S: ser::Serializer,
+    {
+        super::serialize(value, serializer)
+    }
+
+    /// Deserializes the value from 16 bytes, optionally wrapped by tag 37
+    #[inline]
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: From<[u8; 16]>,
+        D: de::Deserializer<'de>,
+    {
+        super::parse(deserializer, true)
+    }
+}
@@ -0,0 +1,917 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transcoding between CBOR and JSON
+//!
+//! [`to_json_writer()`] reads one CBOR data item and writes it as JSON as it
+//! goes, straight into `serde_json`'s serializer, without building a
+//! [`Value`](crate::value::Value) first. CBOR has more to say than JSON, so
+//! the [`Options`] choose what happens to byte strings, map keys which are
+//! not text, tags and floats which are not finite. The defaults follow the
+//! advice of RFC 8949, section 6.1.
+//!
+//! [`from_json_slice()`] goes the other way. JSON does not give the length
+//! of an array or object up front, so the input is parsed twice: once to
+//! count the items of each container and once to write them, each with a
+//! definite length.
+//!
+//! ```rust
+//! use ciborium::transcode::{from_json_slice, to_json_writer, Options, TagPolicy};
+//!
+//! // {"id": 32("https://example.com"), 1: h'fbff'}
+//! let cbor = b"\xa2\x62id\xd8\x20\x73https://example.com\x01\x42\xfb\xff";
+//!
+//! let mut json = Vec::new();
+//! to_json_writer(&cbor[..], &mut json, Options::new()).unwrap();
+//! assert_eq!(json, br#"{"id":"https://example.com","1":"-_8"}"#);
+//!
+//! let mut json = Vec::new();
+//! let options = Options::new().tags(TagPolicy::Annotate);
+//! to_json_writer(&cbor[..], &mut json, options).unwrap();
+//! assert_eq!(
+//!     json,
+//!     br#"{"id":{"__tag":32,"value":"https://example.com"},"1":"-_8"}"#,
+//! );
+//!
+//! let mut cbor = Vec::new();
+//! from_json_slice(br#"{"a": [1, -2.5, null]}"#, &mut cbor).unwrap();
+//! assert_eq!(cbor, b"\xa1\x61a\x83\x01\xf9\xc1\x00\xf6");
+//! ```
+
+use crate::{de, ser};
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::convert::TryFrom;
+use core::error::Error as StdError;
+use core::fmt::{self, Display, Formatter};
+
+use ciborium_io::{Read, Write};
+use ciborium_ll::{simple, tag, Decoder, Encoder, Header};
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap as _, SerializeSeq as _};
+use serde::{Serialize, Serializer};
+
+/// How many levels of containers and annotated tags JSON may get
+const MAX_DEPTH: usize = 256;
+
+/// How byte strings are written as JSON strings
+///
+/// With [`TagPolicy::Unwrap`], the tags for an expected encoding override
+/// this for the byte strings inside them, as RFC 8949 advises: tag 21
+/// for base64url, tag 22 for base64 and tag 23 for hexadecimal. The
+/// innermost such tag wins.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BytesPolicy {
+    /// base64url, without padding
+    #[default]
+    Base64Url,
+
+    /// base64, with padding
+    Base64,
+
+    /// Lowercase hexadecimal
+    Hex,
+}
+
+impl BytesPolicy {
+    /// The encoding which a tag proposes for the byte strings inside it
+    fn expected(tag: u64) -> Option<Self> {
+        match tag {
+            21 => Some(Self::Base64Url),
+            22 => Some(Self::Base64),
+            23 => Some(Self::Hex),
+            _ => None,
+        }
+    }
+}
+
+/// How map keys which are integers are written
+///
+/// JSON keys are always strings. Text keys are written as they are and byte
+/// string keys follow the [`BytesPolicy`]; any other key is an error.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KeyPolicy {
+    /// As their decimal digits, so `1` becomes `"1"`
+    #[default]
+    Stringify,
+
+    /// Not at all: they are an error
+    Error,
+}
+
+/// How tags are written
+///
+/// Bignums (tags 2 and 3) which fit in 128 bits are always written as
+/// numbers, as they are just a larger encoding of an integer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TagPolicy {
+    /// Dropped, so only the tagged item is written
+    #[default]
+    Unwrap,
+
+    /// Not at all: they are an error
+    Error,
+
+    /// As an object like `{"__tag": 32, "value": "https://example.com"}`
+    ///
+    /// A tagged map key cannot be annotated, so it is an error.
+    Annotate,
+}
+
+/// How floats which are NaN or infinite are written
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FloatPolicy {
+    /// As `null`
+    #[default]
+    Null,
+
+    /// As the strings `"NaN"`, `"Infinity"` and `"-Infinity"`
+    String,
+
+    /// Not at all: they are an error
+    Error,
+}
+
+/// Options which control transcoding to JSON
+///
+/// The default options follow RFC 8949, section 6.1.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Options {
+    bytes: BytesPolicy,
+    keys: KeyPolicy,
+    tags: TagPolicy,
+    floats: FloatPolicy,
+}
+
+impl Options {
+    /// Creates the default options
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how byte strings are written
+    #[inline]
+    pub fn bytes(mut self, policy: BytesPolicy) -> Self {
+        self.bytes = policy;
+        self
+    }
+
+    /// Sets how map keys which are integers are written
+    #[inline]
+    pub fn keys(mut self, policy: KeyPolicy) -> Self {
+        self.keys = policy;
+        self
+    }
+
+    /// Sets how tags are written
+    #[inline]
+    pub fn tags(mut self, policy: TagPolicy) -> Self {
+        self.tags = policy;
+        self
+    }
+
+    /// Sets how floats which are NaN or infinite are written
+    #[inline]
+    pub fn floats(mut self, policy: FloatPolicy) -> Self {
+        self.floats = policy;
+        self
+    }
+}
+
+/// An error while transcoding
+///
+/// Output may have been written before the error.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The CBOR could not be read or written
+    Cbor(crate::Error),
+
+    /// The JSON could not be read or written
+    Json(serde_json::Error),
+
+    /// An item of the CBOR has no JSON form under the options
+    Unsupported {
+        /// The offset of the header of the item
+        offset: usize,
+
+        /// What the item is, such as `"tag"` or `"map key"`
+        item: &'static str,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cbor(e) => write!(f, "CBOR: {}", e),
+            Self::Json(e) => write!(f, "JSON: {}", e),
+            Self::Unsupported { offset, item } => {
+                write!(f, "{} at offset {} has no JSON form", item, offset)
+            }
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Cbor(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Unsupported { .. } => None,
+        }
+    }
+}
+
+/// Bytes, written as text under a policy
+struct Encoded<'a>(BytesPolicy, &'a [u8]);
+
+impl Display for Encoded<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const BASE64URL: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        const BASE64: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let (alphabet, padded) = match self.0 {
+            BytesPolicy::Hex => return self.1.iter().try_for_each(|x| write!(f, "{:02x}", x)),
+            BytesPolicy::Base64Url => (BASE64URL, false),
+            BytesPolicy::Base64 => (BASE64, true),
+        };
+
+        for chunk in self.1.chunks(3) {
+            let mut group = [0u8; 3];
+            group[..chunk.len()].copy_from_slice(chunk);
+            let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+
+            let mut chars = [b'='; 4];
+            let used = chunk.len() + 1;
+            for (i, c) in chars[..used].iter_mut().enumerate() {
+                *c = alphabet[(bits >> (18 - 6 * i)) as usize & 0x3f];
+            }
+
+            let len = if padded { 4 } else { used };
+            f.write_str(core::str::from_utf8(&chars[..len]).unwrap())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An item as it is written to JSON
+enum Item {
+    Null,
+    Bool(bool),
+    Unsigned(u128),
+    Signed(i128),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Array(Option<usize>),
+    Map(Option<usize>),
+    Tag(u64),
+
+    /// A dropped tag for an expected encoding, with the encoding outside it
+    Expected(Option<BytesPolicy>),
+}
+
+struct Reader<'b, R: Read> {
+    decoder: Decoder<R>,
+    scratch: &'b mut [u8],
+    recurse: usize,
+    options: Options,
+    failure: Option<Error>,
+
+    /// The encoding proposed by the innermost tag, if any
+    expected: Option<BytesPolicy>,
+}
+
+impl<'b, R: Read> Reader<'b, R>
+where
+    R::Error: StdError + Send + Sync + 'static,
+{
+    /// The error for the CBOR, at the current offset
+    fn cbor(&self, error: impl Into<de::Error<R::Error>>) -> Error {
+        let error = error.into().eof::<R>(self.decoder.offset());
+        Error::Cbor(error.into())
+    }
+
+    fn pull(&mut self) -> Result<Header, Error> {
+        self.decoder.pull().map_err(|e| self.cbor(e))
+    }
+
+    /// Enters a container or an annotated tag, whose header is at `offset`
+    fn open(&mut self, offset: usize) -> Result<(), Error> {
+        if self.recurse == 0 {
+            return Err(self.cbor(de::Error::depth(MAX_DEPTH, offset)));
+        }
+
+        self.recurse -= 1;
+        Ok(())
+    }
+
+    fn close(&mut self) {
+        self.recurse += 1;
+    }
+
+    /// The encoding of the byte strings here
+    fn encoding(&self) -> BytesPolicy {
+        self.expected.unwrap_or(self.options.bytes)
+    }
+
+    fn bytes(&mut self, len: Option<usize>) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::new();
+
+        let scratch = &mut *self.scratch;
+        let mut segments = self.decoder.bytes(len);
+        let result = (|| {
+            while let Some(mut segment) = segments.pull()? {
+                while let Some(chunk) = segment.pull(scratch)? {
+                    buffer.extend_from_slice(chunk);
+                }
+            }
+
+            Ok(())
+        })();
+
+        result.map_err(|e: ciborium_ll::Error<R::Error>| self.cbor(e))?;
+        Ok(buffer)
+    }
+
+    fn text(&mut self, len: Option<usize>) -> Result<String, Error> {
+        let mut buffer = String::new();
+
+        let scratch = &mut *self.scratch;
+        let mut segments = self.decoder.text(len);
+        let result = (|| {
+            while let Some(mut segment) = segments.pull()? {
+                while let Some(chunk) = segment.pull(scratch)? {
+                    buffer.push_str(chunk);
+                }
+            }
+
+            Ok(())
+        })();
+
+        result.map_err(|e: ciborium_ll::Error<R::Error>| self.cbor(e))?;
+        Ok(buffer)
+    }
+
+    /// Reads a bignum as an integer, if it is small enough
+    ///
+    /// Annotated bignums are not read this way, so the tag is either dropped
+    /// or an error if the integer is too large after all.
+    fn bignum(&mut self, offset: usize, tag: u64) -> Result<Option<Item>, Error> {
+        let len = match self.decoder.peek().map_err(|e| self.cbor(e))? {
+            Header::Bytes(Some(len)) if len <= 16 => len,
+            _ => return Ok(None),
+        };
+
+        self.pull()?;
+        let bytes = self.bytes(Some(len))?;
+
+        let mut buffer = [0u8; 16];
+        buffer[16 - bytes.len()..].copy_from_slice(&bytes);
+        let raw = u128::from_be_bytes(buffer);
+
+        Ok(Some(match tag {
+            tag::BIGPOS => Item::Unsigned(raw),
+            _ => match i128::try_from(raw) {
+                Ok(x) => Item::Signed(x ^ !0),
+                Err(..) => match self.options.tags {
+                    TagPolicy::Unwrap => Item::Bytes(bytes),
+                    _ => {
+                        return Err(Error::Unsupported {
+                            offset,
+                            item: "tag",
+                        })
+                    }
+                },
+            },
+        }))
+    }
+
+    /// Reads the next item, applying the options
+    fn next(&mut self) -> Result<Item, Error> {
+        // Dropped tags are skipped here, rather than by recursing, so that
+        // any number of them can be read.
+        loop {
+            if let Some(item) = self.item()? {
+                return Ok(item);
+            }
+        }
+    }
+
+    /// Reads the next item, or `None` for a tag which is dropped
+    fn item(&mut self) -> Result<Option<Item>, Error> {
+        let offset = self.decoder.offset();
+
+        Ok(Some(match self.pull()? {
+            Header::Positive(x) => Item::Unsigned(x.into()),
+            Header::Negative(x) => Item::Signed(-1 - i128::from(x)),
+
+            Header::Float(x) if x.is_finite() => Item::Float(x),
+            Header::Float(x) => match self.options.floats {
+                FloatPolicy::Null => Item::Null,
+                FloatPolicy::Error => {
+                    return Err(Error::Unsupported {
+                        offset,
+                        item: "non-finite float",
+                    })
+                }
+                FloatPolicy::String => Item::Text(
+                    match (x.is_nan(), x > 0.0) {
+                        (true, _) => "NaN",
+                        (false, true) => "Infinity",
+                        (false, false) => "-Infinity",
+                    }
+                    .into(),
+                ),
+            },
+
+            Header::Simple(simple::FALSE) => Item::Bool(false),
+            Header::Simple(simple::TRUE) => Item::Bool(true),
+            Header::Simple(simple::NULL) | Header::Simple(simple::UNDEFINED) => Item::Null,
+            Header::Simple(..) => {
+                return Err(Error::Unsupported {
+                    offset,
+                    item: "simple value",
+                })
+            }
+
+            Header::Bytes(len) => Item::Bytes(self.bytes(len)?),
+            Header::Text(len) => Item::Text(self.text(len)?),
+
+            Header::Array(len) => {
+                self.open(offset)?;
+                Item::Array(len)
+            }
+
+            Header::Map(len) => {
+                self.open(offset)?;
+                Item::Map(len)
+            }
+
+            Header::Tag(x @ tag::BIGPOS) | Header::Tag(x @ tag::BIGNEG)
+                if self.options.tags != TagPolicy::Annotate =>
+            {
+                match self.bignum(offset, x)? {
+                    Some(item) => item,
+                    None => return self.tag(offset, x),
+                }
+            }
+
+            Header::Tag(x) => return self.tag(offset, x),
+
+            Header::Break => return Err(self.cbor(de::Error::Syntax(offset))),
+        }))
+    }
+
+    /// Applies the tag policy, giving `None` if the tag is dropped
+    fn tag(&mut self, offset: usize, tag: u64) -> Result<Option<Item>, Error> {
+        match self.options.tags {
+            TagPolicy::Unwrap => match BytesPolicy::expected(tag) {
+                Some(policy) => {
+                    self.open(offset)?;
+                    Ok(Some(Item::Expected(self.expected.replace(policy))))
+                }
+
+                None => Ok(None),
+            },
+            TagPolicy::Error => Err(Error::Unsupported {
+                offset,
+                item: "tag",
+            }),
+            TagPolicy::Annotate => {
+                self.open(offset)?;
+                Ok(Some(Item::Tag(tag)))
+            }
+        }
+    }
+
+    /// Reads the next map key, as a JSON key
+    fn key(&mut self) -> Result<String, Error> {
+        let mut policy = self.encoding();
+
+        loop {
+            let offset = self.decoder.offset();
+
+            return match self.pull()? {
+                Header::Text(len) => self.text(len),
+
+                Header::Bytes(len) => {
+                    let bytes = self.bytes(len)?;
+                    Ok(Encoded(policy, &bytes).to_string())
+                }
+
+                Header::Positive(x) if self.options.keys == KeyPolicy::Stringify => {
+                    Ok(x.to_string())
+                }
+
+                Header::Negative(x) if self.options.keys == KeyPolicy::Stringify => {
+                    Ok((-1 - i128::from(x)).to_string())
+                }
+
+                Header::Tag(x) if self.options.tags == TagPolicy::Unwrap => {
+                    policy = BytesPolicy::expected(x).unwrap_or(policy);
+                    continue;
+                }
+
+                Header::Tag(..) => Err(Error::Unsupported {
+                    offset,
+                    item: "tag",
+                }),
+
+                _ => Err(Error::Unsupported {
+                    offset,
+                    item: "map key",
+                }),
+            };
+        }
+    }
+
+    /// Whether a container with `len` items, `index` of them done, has more
+    fn more(&mut self, len: Option<usize>, index: &mut usize) -> Result<bool, Error> {
+        *index += 1;
+
+        match len {
+            Some(len) => Ok(*index <= len),
+            None => match self.pull()? {
+                Header::Break => Ok(false),
+                header => {
+                    self.decoder.push(header);
+                    Ok(true)
+                }
+            },
+        }
+    }
+}
+
+/// The next item of the reader, which is read as it is serialized
+struct Json<'r, 'b, R: Read>(&'r RefCell<Reader<'b, R>>);
+
+impl<R: Read> Json<'_, '_, R>
+where
+    R::Error: StdError + Send + Sync + 'static,
+{
+    /// Runs `func` on the reader, keeping any error for later
+    ///
+    /// The serializer only sees a placeholder, since it cannot carry the
+    /// error itself.
+    fn attempt<T, E: serde::ser::Error>(
+        &self,
+        func: impl FnOnce(&mut Reader<'_, R>) -> Result<T, Error>,
+    ) -> Result<T, E> {
+        let mut reader = self.0.borrow_mut();
+
+        match func(&mut reader) {
+            Ok(x) => Ok(x),
+            Err(e) => {
+                reader.failure = Some(e);
+                Err(E::custom("transcoding failed"))
+            }
+        }
+    }
+}
+
+impl<R: Read> Serialize for Json<'_, '_, R>
+where
+    R::Error: StdError + Send + Sync + 'static,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut index = 0;
+
+        match self.attempt(|r| r.next())? {
+            Item::Null => serializer.serialize_unit(),
+            Item::Bool(x) => serializer.serialize_bool(x),
+            Item::Unsigned(x) => serializer.serialize_u128(x),
+            Item::Signed(x) => serializer.serialize_i128(x),
+            Item::Float(x) => serializer.serialize_f64(x),
+            Item::Text(x) => serializer.serialize_str(&x),
+
+            Item::Bytes(x) => {
+                let policy = self.0.borrow().encoding();
+                serializer.collect_str(&Encoded(policy, &x))
+            }
+
+            Item::Array(len) => {
+                let mut seq = serializer.serialize_seq(len)?;
+                while self.attempt(|r| r.more(len, &mut index))? {
+                    seq.serialize_element(self)?;
+                }
+
+                self.0.borrow_mut().close();
+                seq.end()
+            }
+
+            Item::Map(len) => {
+                let mut map = serializer.serialize_map(len)?;
+                while self.attempt(|r| r.more(len, &mut index))? {
+                    let key = self.attempt(|r| r.key())?;
+                    map.serialize_entry(&key, self)?;
+                }
+
+                self.0.borrow_mut().close();
+                map.end()
+            }
+
+            Item::Tag(x) => {
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("__tag", &x)?;
+                map.serialize_entry("value", self)?;
+
+                self.0.borrow_mut().close();
+                map.end()
+            }
+
+            Item::Expected(outer) => {
+                let result = self.serialize(serializer);
+
+                let mut reader = self.0.borrow_mut();
+                reader.expected = outer;
+                reader.close();
+                result
+            }
+        }
+    }
+}
+
+/// Transcodes one CBOR data item from a reader into JSON
+///
+/// The JSON is written as the CBOR is read, so only strings are held in
+/// memory. Anything after the data item is left unread.
+///
+/// ```rust
+/// use ciborium::transcode::{to_json_writer, Error, FloatPolicy, Options};
+///
+/// // [1.5, NaN]
+/// let cbor = [0x82, 0xf9, 0x3e, 0x00, 0xf9, 0x7e, 0x00];
+///
+/// let mut json = Vec::new();
+/// to_json_writer(&cbor[..], &mut json, Options::new()).unwrap();
+/// assert_eq!(json, b"[1.5,null]");
+///
+/// let options = Options::new().floats(FloatPolicy::Error);
+/// match to_json_writer(&cbor[..], Vec::new(), options).unwrap_err() {
+///     Error::Unsupported { offset, item } => assert_eq!((offset, item), (4, "non-finite float")),
+///     e => panic!("{}", e),
+/// }
+/// ```
+pub fn to_json_writer<R: Read, W: std::io::Write>(
+    reader: R,
+    writer: W,
+    options: Options,
+) -> Result<(), Error>
+where
+    R::Error: StdError + Send + Sync + 'static,
+{
+    let mut scratch = [0; 4096];
+
+    let reader = RefCell::new(Reader {
+        decoder: reader.into(),
+        scratch: &mut scratch,
+        recurse: MAX_DEPTH,
+        options,
+        failure: None,
+        expected: None,
+    });
+
+    let mut serializer = serde_json::Serializer::new(writer);
+    let result = Json(&reader).serialize(&mut serializer);
+
+    if let Some(e) = reader.into_inner().failure {
+        return Err(e);
+    }
+
+    result.map_err(Error::Json)?;
+
+    serializer
+        .into_inner()
+        .flush()
+        .map_err(|e| Error::Json(serde_json::Error::io(e)))
+}
+
+/// Counts the items of each container of a JSON value, in order
+struct Count<'a>(&'a mut Vec<usize>);
+
+impl<'de> DeserializeSeed<'de> for Count<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Count<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "any JSON value")
+    }
+
+    fn visit_bool<E: serde::de::Error>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E: serde::de::Error>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<(), A::Error> {
+        let slot = self.0.len();
+        self.0.push(0);
+
+        let mut len = 0;
+        while access.next_element_seed(Count(self.0))?.is_some() {
+            len += 1;
+        }
+
+        self.0[slot] = len;
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<(), A::Error> {
+        let slot = self.0.len();
+        self.0.push(0);
+
+        let mut len = 0;
+        while access.next_key::<IgnoredAny>()?.is_some() {
+            access.next_value_seed(Count(self.0))?;
+            len += 1;
+        }
+
+        self.0[slot] = len;
+        Ok(())
+    }
+}
+
+struct Emitter<W: Write> {
+    encoder: Encoder<W>,
+    counts: alloc::vec::IntoIter<usize>,
+    failure: Option<crate::Error>,
+}
+
+/// Writes a JSON value as CBOR, with the counts from [`Count`]
+struct Emit<'a, W: Write>(&'a mut Emitter<W>);
+
+impl<W: Write> Emit<'_, W>
+where
+    W::Error: StdError + Send + Sync + 'static,
+{
+    /// Writes a header, keeping any error for later
+    fn push<E: serde::de::Error>(&mut self, header: Header) -> Result<(), E> {
+        let result = self.0.encoder.push(header);
+        self.keep(result)
+    }
+
+    fn keep<E: serde::de::Error>(&mut self, result: Result<(), W::Error>) -> Result<(), E> {
+        result.map_err(|e| {
+            self.0.failure = Some(ser::Error::Io(e).into());
+            E::custom("transcoding failed")
+        })
+    }
+
+    fn integer<E: serde::de::Error>(mut self, value: i128) -> Result<(), E> {
+        match Header::from_i128(value) {
+            Ok(header) => self.push(header),
+            Err(..) => unreachable!("a JSON integer always fits in a header"),
+        }
+    }
+}
+
+impl<'de, W: Write> DeserializeSeed<'de> for Emit<'_, W>
+where
+    W::Error: StdError + Send + Sync + 'static,
+{
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, W: Write> Visitor<'de> for Emit<'_, W>
+where
+    W::Error: StdError + Send + Sync + 'static,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "any JSON value")
+    }
+
+    fn visit_bool<E: serde::de::Error>(mut self, v: bool) -> Result<(), E> {
+        self.push(Header::Simple(match v {
+            false => simple::FALSE,
+            true => simple::TRUE,
+        }))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<(), E> {
+        self.integer(v.into())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<(), E> {
+        self.integer(v.into())
+    }
+
+    fn visit_f64<E: serde::de::Error>(mut self, v: f64) -> Result<(), E> {
+        self.push(Header::Float(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(mut self, v: &str) -> Result<(), E> {
+        let result = self.0.encoder.text(v, None);
+        self.keep(result)
+    }
+
+    fn visit_unit<E: serde::de::Error>(mut self) -> Result<(), E> {
+        self.push(Header::Simple(simple::NULL))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut access: A) -> Result<(), A::Error> {
+        let len = self.0.counts.next();
+        self.push(Header::Array(len))?;
+
+        while access.next_element_seed(Emit(&mut *self.0))?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut access: A) -> Result<(), A::Error> {
+        let len = self.0.counts.next();
+        self.push(Header::Map(len))?;
+
+        while access.next_key_seed(Emit(&mut *self.0))?.is_some() {
+            access.next_value_seed(Emit(&mut *self.0))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Transcodes a JSON value into CBOR
+///
+/// Arrays and objects are written with definite lengths, integers in their
+/// shortest form and other numbers as the shortest float which holds them
+/// exactly. The JSON is read as it is: nothing is decoded from base64url
+/// and `__tag` objects stay objects.
+///
+/// ```rust
+/// use ciborium::transcode::{from_json_slice, Error};
+///
+/// let mut cbor = Vec::new();
+/// from_json_slice(br#"[true, "x", 1e300]"#, &mut cbor).unwrap();
+/// assert_eq!(cbor[..4], [0x83, 0xf5, 0x61, b'x']);
+///
+/// assert!(matches!(from_json_slice(b"[1,", Vec::new()), Err(Error::Json(..))));
+/// ```
+pub fn from_json_slice<W: Write>(json: &[u8], writer: W) -> Result<(), Error>
+where
+    W::Error: StdError + Send + Sync + 'static,
+{
+    let mut counts = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    Count(&mut counts)
+        .deserialize(&mut deserializer)
+        .and_then(|()| deserializer.end())
+        .map_err(Error::Json)?;
+
+    let mut emitter = Emitter {
+        encoder: writer.into(),
+        counts: counts.into_iter(),
+        failure: None,
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    if let Err(e) = Emit(&mut emitter).deserialize(&mut deserializer) {
+        return Err(match emitter.failure.take() {
+            Some(failure) => Error::Cbor(failure),
+            None => Error::Json(e),
+        });
+    }
+
+    emitter
+        .encoder
+        .flush()
+        .map_err(|e| Error::Cbor(ser::Error::Io(e).into()))
+}
@@ -0,0 +1,339 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Float, Integer, Value};
+
+use crate::de::Error;
+
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::convert::TryFrom;
+
+use ciborium_io::Read;
+use ciborium_ll::{tag, Decoder, Header};
+
+/// The number of nested levels allowed, as in the serde deserializer
+const MAX_DEPTH: usize = 256;
+
+/// A dynamic CBOR value which borrows its strings from the input
+///
+/// This mirrors [`Value`], except that byte and text strings borrow from
+/// the slice the value was decoded from. Only strings encoded in several
+/// chunks (indefinite length) need to be copied.
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValueRef<'a> {
+    /// An integer
+    Integer(Integer),
+
+    /// Bytes
+    Bytes(Cow<'a, [u8]>),
+
+    /// A float
+    Float(Float),
+
+    /// A string
+    Text(Cow<'a, str>),
+
+    /// A boolean
+    Bool(bool),
+
+    /// Null
+    Null,
+
+    /// Undefined
+    Undefined,
+
+    /// A simple value other than false, true, null or undefined
+    Simple(u8),
+
+    /// Tag
+    Tag(u64, Box<ValueRef<'a>>),
+
+    /// An array
+    Array(Vec<ValueRef<'a>>),
+
+    /// A map
+    Map(Vec<(ValueRef<'a>, ValueRef<'a>)>),
+}
+
+type SliceError<'a> = Error<<&'a [u8] as Read>::Error>;
+
+struct Parser<'a, 'b> {
+    input: &'a [u8],
+    rest: &'a [u8],
+    scratch: &'b mut [u8],
+    recurse: usize,
+}
+
+impl<'a, 'b> Parser<'a, 'b> {
+    #[inline]
+    fn offset(&self) -> usize {
+        self.input.len() - self.rest.len()
+    }
+
+    #[inline]
+    fn recurse<V, F: FnOnce(&mut Self) -> Result<V, SliceError<'a>>>(
+        &mut self,
+        offset: usize,
+        func: F,
+    ) -> Result<V, SliceError<'a>> {
+        if self.recurse == 0 {
+            return Err(Error::depth(MAX_DEPTH, offset));
+        }
+
+        self.recurse -= 1;
+        let result = func(self);
+        self.recurse += 1;
+        result
+    }
+
+    /// Runs `func` on a decoder which continues from the current position
+    fn decode<V>(
+        &mut self,
+        func: impl FnOnce(&mut Decoder<&mut &'a [u8]>, &mut [u8]) -> Result<V, SliceError<'a>>,
+    ) -> Result<V, SliceError<'a>> {
+        let offset = self.offset();
+        let mut decoder = Decoder::from(&mut self.rest);
+
+        func(&mut decoder, self.scratch).map_err(|e| match e.eof::<&[u8]>(decoder.offset()) {
+            Error::Syntax(x) => Error::Syntax(offset + x),
+            Error::InvalidUtf8 { chunk, offset: x } => Error::InvalidUtf8 {
+                chunk,
+                offset: offset + x,
+            },
+            Error::UnexpectedEof { offset: x } => Error::UnexpectedEof { offset: offset + x },
+            e => e,
+        })
+    }
+
+    fn pull(&mut self) -> Result<Header, SliceError<'a>> {
+        self.decode(|decoder, _| Ok(decoder.pull()?))
+    }
+
+    fn peek(&mut self) -> Result<Header, SliceError<'a>> {
+        let rest = self.rest;
+        let header = self.pull();
+        self.rest = rest;
+        header
+    }
+
+    /// Borrows the next `len` bytes of the input
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SliceError<'a>> {
+        if len > self.rest.len() {
+            let offset = self.offset();
+            return Err(Error::UnexpectedEof { offset });
+        }
+
+        let (head, tail) = self.rest.split_at(len);
+        self.rest = tail;
+        Ok(head)
+    }
+
+    fn bytes(
+        &mut self,
+        start: &'a [u8],
+        len: Option<usize>,
+    ) -> Result<Cow<'a, [u8]>, SliceError<'a>> {
+        if let Some(len) = len {
+            return Ok(self.take(len)?.into());
+        }
+
+        // Chunked bytes are copied; rewind so the decoder sees the header.
+        self.rest = start;
+        self.decode(|decoder, scratch| {
+            decoder.pull()?;
+
+            let mut buffer = Vec::new();
+            let mut segments = decoder.bytes(None);
+            while let Some(mut segment) = segments.pull()? {
+                while let Some(chunk) = segment.pull(scratch)? {
+                    buffer.extend_from_slice(chunk);
+                }
+            }
+
+            Ok(buffer.into())
+        })
+    }
+
+    fn text(
+        &mut self,
+        start: &'a [u8],
+        len: Option<usize>,
+    ) -> Result<Cow<'a, str>, SliceError<'a>> {
+        if let Some(len) = len {
+            let offset = self.input.len() - start.len();
+            let bytes = self.take(len)?;
+            return match core::str::from_utf8(bytes) {
+                Ok(text) => Ok(text.into()),
+                Err(..) => Err(Error::InvalidUtf8 { chunk: 0, offset }),
+            };
+        }
+
+        // Chunked text is copied; rewind so the decoder sees the header.
+        self.rest = start;
+        self.decode(|decoder, scratch| {
+            decoder.pull()?;
+
+            let mut buffer = alloc::string::String::new();
+            let mut segments = decoder.text(None);
+            while let Some(mut segment) = segments.pull()? {
+                while let Some(chunk) = segment.pull(scratch)? {
+                    buffer.push_str(chunk);
+                }
+            }
+
+            Ok(buffer.into())
+        })
+    }
+
+    fn tag(&mut self, offset: usize, tag: u64) -> Result<ValueRef<'a>, SliceError<'a>> {
+        let bytes = matches!(self.peek()?, Header::Bytes(..));
+        let value = self.recurse(offset, |me| me.value())?;
+
+        // Small bignums are normalized to integers, just like `Value`, even
+        // when their bytes are in chunks.
+        let raw = match (tag, &value) {
+            (tag::BIGPOS, ValueRef::Bytes(x)) | (tag::BIGNEG, ValueRef::Bytes(x))
+                if bytes && x.len() <= 16 =>
+            {
+                let mut buffer = [0u8; 16];
+                buffer[16 - x.len()..].copy_from_slice(x);
+                u128::from_be_bytes(buffer)
+            }
+
+            _ => return Ok(ValueRef::Tag(tag, Box::new(value))),
+        };
+
+        Ok(match tag {
+            tag::BIGPOS => Value::from(raw).into(),
+            _ => match i128::try_from(raw) {
+                Ok(x) => Value::from(x ^ !0).into(),
+                Err(..) => ValueRef::Tag(tag, Box::new(value)),
+            },
+        })
+    }
+
+    fn value(&mut self) -> Result<ValueRef<'a>, SliceError<'a>> {
+        let offset = self.offset();
+        let start = self.rest;
+
+        Ok(match self.pull()? {
+            Header::Positive(x) => ValueRef::Integer(x.into()),
+            h @ Header::Negative(..) => Value::from(h.as_i128().unwrap()).into(),
+            Header::Float(x) => ValueRef::Float(x.into()),
+            Header::Simple(x) => Value::from_simple(x).into(),
+            Header::Bytes(len) => ValueRef::Bytes(self.bytes(start, len)?),
+            Header::Text(len) => ValueRef::Text(self.text(start, len)?),
+            Header::Tag(x) => self.tag(offset, x)?,
+            Header::Break => return Err(Error::UnexpectedBreak { offset }),
+
+            Header::Array(len) => self.recurse(offset, |me| {
+                let mut array = Vec::with_capacity(len.unwrap_or(0).min(4096));
+
+                while len.map(|x| array.len() < x).unwrap_or(true) {
+                    if len.is_none() && me.peek()? == Header::Break {
+                        me.pull()?;
+                        break;
+                    }
+
+                    array.push(me.value()?);
+                }
+
+                Ok(ValueRef::Array(array))
+            })?,
+
+            Header::Map(len) => self.recurse(offset, |me| {
+                let mut map = Vec::with_capacity(len.unwrap_or(0).min(4096));
+
+                while len.map(|x| map.len() < x).unwrap_or(true) {
+                    if len.is_none() && me.peek()? == Header::Break {
+                        me.pull()?;
+                        break;
+                    }
+
+                    let key = me.value()?;
+                    let val = me.value()?;
+                    map.push((key, val));
+                }
+
+                Ok(ValueRef::Map(map))
+            })?,
+        })
+    }
+}
+
+impl From<Value> for ValueRef<'_> {
+    #[inline]
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Integer(x) => ValueRef::Integer(x),
+            Value::Bytes(x) => ValueRef::Bytes(x.into()),
+            Value::Float(x) => ValueRef::Float(x),
+            Value::Text(x) => ValueRef::Text(x.into()),
+            Value::Bool(x) => ValueRef::Bool(x),
+            Value::Null => ValueRef::Null,
+            Value::Undefined => ValueRef::Undefined,
+            Value::Simple(x) => ValueRef::Simple(x),
+            Value::Tag(t, v) => ValueRef::Tag(t, Box::new((*v).into())),
+            Value::Array(x) => ValueRef::Array(x.into_iter().map(Into::into).collect()),
+            Value::Map(x) => {
+                ValueRef::Map(x.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+            }
+        }
+    }
+}
+
+impl<'a> ValueRef<'a> {
+    /// Decodes a `ValueRef` from a slice, borrowing its strings
+    ///
+    /// This produces the same value as [`Value::from_reader()`], but without
+    /// copying byte and text strings out of the input.
+    ///
+    /// ```rust
+    /// use ciborium::value::{Value, ValueRef};
+    ///
+    /// let bytes = [0x82, 0x63, b'a', b'b', b'c', 0x42, 0x01, 0x02];
+    /// let value = ValueRef::from_slice(&bytes).unwrap();
+    ///
+    /// match &value {
+    ///     ValueRef::Array(x) => assert_eq!(x[0], ValueRef::Text("abc".into())),
+    ///     _ => panic!(),
+    /// }
+    ///
+    /// assert_eq!(value.to_owned(), Value::from_reader(&bytes[..]).unwrap());
+    /// ```
+    #[inline]
+    pub fn from_slice(slice: &'a [u8]) -> Result<Self, Error<<&'a [u8] as Read>::Error>> {
+        let mut scratch = [0; 4096];
+
+        let mut parser = Parser {
+            input: slice,
+            rest: slice,
+            scratch: &mut scratch,
+            recurse: MAX_DEPTH,
+        };
+
+        parser.value()
+    }
+
+    /// Copies this value into an owned `Value`
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_owned(&self) -> Value {
+        match self {
+            ValueRef::Integer(x) => Value::Integer(*x),
+            ValueRef::Bytes(x) => Value::Bytes(x.to_vec()),
+            ValueRef::Float(x) => Value::Float(*x),
+            ValueRef::Text(x) => Value::Text(x.as_ref().into()),
+            ValueRef::Bool(x) => Value::Bool(*x),
+            ValueRef::Null => Value::Null,
+            ValueRef::Undefined => Value::Undefined,
+            ValueRef::Simple(x) => Value::Simple(*x),
+            ValueRef::Tag(t, v) => Value::Tag(*t, Box::new(ValueRef::to_owned(v))),
+            ValueRef::Array(x) => Value::Array(x.iter().map(ValueRef::to_owned).collect()),
+            ValueRef::Map(x) => Value::Map(
+                x.iter()
+                    .map(|(k, v)| (k.to_owned(), v.to_owned()))
+                    .collect(),
+            ),
+        }
+    }
+}
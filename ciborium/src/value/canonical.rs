@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Path, Step, Value};
+
+use alloc::vec::Vec;
+use ciborium_ll::compare_canonical;
+use core::ops::ControlFlow;
+
+/// An error producing the canonical encoding of a `Value`
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CanonicalError {
+    /// A map contains the same key more than once
+    ///
+    /// The path leads to the second occurrence of the key.
+    DuplicateKey(Path),
+
+    /// A value has no CBOR encoding, such as a reserved simple value
+    Unrepresentable(Path),
+}
+
+impl core::fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DuplicateKey(path) => write!(f, "duplicate map key at {}", path),
+            Self::Unrepresentable(path) => write!(f, "unrepresentable value at {}", path),
+        }
+    }
+}
+
+impl serde::de::StdError for CanonicalError {}
+
+/// Encodes a key in the form used for canonical ordering
+///
+/// The key is encoded with its own maps sorted so that keys which differ
+/// only by the order of their nested map entries compare equal.
+fn encoded(key: &Value) -> Vec<u8> {
+    let mut key = key.clone();
+    key.sort_keys();
+
+    let mut bytes = Vec::new();
+    let _ = key.into_writer(&mut bytes);
+    bytes
+}
+
+/// Iterates over a value and all of its descendants, including map keys
+fn nodes(value: &Value) -> impl Iterator<Item = &Value> {
+    let mut stack = alloc::vec![value];
+
+    core::iter::from_fn(move || {
+        let value = stack.pop()?;
+        match value {
+            Value::Tag(_, v) => stack.push(v),
+            Value::Array(x) => stack.extend(x.iter()),
+            Value::Map(x) => {
+                for (k, v) in x.iter() {
+                    stack.push(k);
+                    stack.push(v);
+                }
+            }
+            _ => (),
+        }
+
+        Some(value)
+    })
+}
+
+fn unrepresentable(value: &Value) -> bool {
+    matches!(value, Value::Simple(x) if (24..32).contains(x))
+}
+
+impl Value {
+    fn sort_maps(&mut self, dedup: bool) -> usize {
+        let mut dropped = 0;
+        let mut stack = Vec::new();
+        stack.push(self);
+
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Tag(_, v) => stack.push(&mut **v),
+                Value::Array(x) => stack.extend(x.iter_mut()),
+
+                Value::Map(x) => {
+                    let mut entries: Vec<_> = x.drain(..).map(|e| (encoded(&e.0), e)).collect();
+                    // A key with no encoding is cut short by the error, and
+                    // only sorts by the bytes which were written.
+                    entries.sort_by(|a, b| {
+                        compare_canonical(&a.0, &b.0).unwrap_or_else(|_| a.0.cmp(&b.0))
+                    });
+
+                    if dedup {
+                        let len = entries.len();
+                        entries.dedup_by(|later, kept| match later.0 == kept.0 {
+                            false => false,
+                            true => {
+                                core::mem::swap(&mut later.1, &mut kept.1);
+                                true
+                            }
+                        });
+                        dropped += len - entries.len();
+                    }
+
+                    x.extend(entries.into_iter().map(|e| e.1));
+
+                    for (k, v) in x.iter_mut() {
+                        stack.push(k);
+                        stack.push(v);
+                    }
+                }
+
+                _ => (),
+            }
+        }
+
+        dropped
+    }
+
+    /// Recursively sorts the entries of every map by canonical key order
+    ///
+    /// Keys are ordered bytewise by their encoded form, as described in
+    /// RFC 8949 section 4.2.1. Maps nested inside arrays, tags, map keys and
+    /// map values are all sorted; other values are left untouched. Entries
+    /// with duplicate keys are kept in their original relative order.
+    pub fn sort_keys(&mut self) {
+        self.sort_maps(false);
+    }
+
+    /// Recursively sorts every map like [`Value::sort_keys()`] and removes duplicate keys
+    ///
+    /// When a map contains the same key more than once, only the last
+    /// value is kept. Returns the number of entries that were removed.
+    pub fn sort_and_dedup_keys(&mut self) -> usize {
+        self.sort_maps(true)
+    }
+
+    /// Encodes the value deterministically, as described in RFC 8949 section 4.2
+    ///
+    /// Map keys are sorted as by [`Value::sort_keys()`], every NaN is
+    /// encoded as the canonical half precision `0xf97e00`, integers, lengths
+    /// and floats use their shortest form and no indefinite lengths are
+    /// used. Tags are preserved. Maps containing a duplicate key are rejected
+    /// rather than silently deduplicated, as are values with no encoding.
+    ///
+    /// ```rust
+    /// use ciborium::cbor;
+    ///
+    /// let value = cbor!({ "b" => f64::NAN, "a" => 1.5 }).unwrap();
+    /// let bytes = value.to_canonical_vec().unwrap();
+    /// assert_eq!(bytes, [0xa2, 0x61, b'a', 0xf9, 0x3e, 0x00, 0x61, b'b', 0xf9, 0x7e, 0x00]);
+    /// ```
+    pub fn to_canonical_vec(&self) -> Result<Vec<u8>, CanonicalError> {
+        let mut error = None;
+
+        self.walk(|path, value| {
+            let mut path = path.clone();
+            match value {
+                x if unrepresentable(x) => (),
+                Value::Map(x) => match x.iter().find(|(k, _)| nodes(k).any(unrepresentable)) {
+                    Some((k, _)) => path.push(Step::Key(k.clone())),
+                    None => return ControlFlow::Continue(()),
+                },
+                _ => return ControlFlow::Continue(()),
+            }
+
+            error = Some(CanonicalError::Unrepresentable(path));
+            ControlFlow::Break(())
+        });
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let mut value = self.clone();
+        let mut stack = alloc::vec![&mut value];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Float(x) if f64::from(*x).is_nan() => *x = f64::NAN.into(),
+                Value::Tag(_, v) => stack.push(&mut **v),
+                Value::Array(x) => stack.extend(x.iter_mut()),
+                Value::Map(x) => {
+                    for (k, v) in x.iter_mut() {
+                        stack.push(k);
+                        stack.push(v);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        value.sort_keys();
+
+        // Sorting places equal keys next to each other.
+        value.walk(|path, value| {
+            if let Value::Map(x) = value {
+                let mut previous = None;
+                for (k, _) in x {
+                    let key = encoded(k);
+                    if previous.as_ref() == Some(&key) {
+                        let mut path = path.clone();
+                        path.push(Step::Key(k.clone()));
+                        error = Some(CanonicalError::DuplicateKey(path));
+                        return ControlFlow::Break(());
+                    }
+
+                    previous = Some(key);
+                }
+            }
+
+            ControlFlow::Continue(())
+        });
+
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let mut bytes = Vec::new();
+        match value.into_writer(&mut bytes) {
+            Ok(()) => Ok(bytes),
+            Err(..) => Err(CanonicalError::Unrepresentable(Path::default())),
+        }
+    }
+}
@@ -0,0 +1,323 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Float, Integer, Value};
+
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+
+/// An error that occurred while converting a `Value` into another type
+///
+/// The error records what was expected and hands back the original value.
+#[derive(Debug)]
+pub struct TryFromValueError {
+    expected: &'static str,
+    value: Value,
+}
+
+impl TryFromValueError {
+    #[inline]
+    fn new(expected: &'static str, value: Value) -> Self {
+        Self { expected, value }
+    }
+
+    /// The kind of value that the conversion expected
+    #[inline]
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// The kind of value that was actually found
+    #[inline]
+    pub fn found(&self) -> &'static str {
+        self.value.kind_name()
+    }
+
+    /// Returns the value that failed to convert
+    #[inline]
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+}
+
+impl core::fmt::Display for TryFromValueError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found())
+    }
+}
+
+impl serde::de::StdError for TryFromValueError {}
+
+impl Value {
+    /// The name of the kind of this value, as used in errors
+    pub(crate) fn kind_name(&self) -> &'static str {
+        match self {
+            Value::Integer(..) => "integer",
+            Value::Bytes(..) => "bytes",
+            Value::Float(..) => "float",
+            Value::Text(..) => "text",
+            Value::Bool(..) => "bool",
+            Value::Null => "null",
+            Value::Undefined => "undefined",
+            Value::Simple(..) => "simple",
+            Value::Tag(..) => "tag",
+            Value::Array(..) => "array",
+            Value::Map(..) => "map",
+        }
+    }
+}
+
+macro_rules! implvariant {
+    ($($v:ident($t:ty) => $expected:literal),+ $(,)?) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = TryFromValueError;
+
+                #[inline]
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    match value {
+                        Value::$v(x) => Ok(x),
+                        value => Err(TryFromValueError::new($expected, value)),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+implvariant! {
+    Integer(Integer) => "integer",
+    Bytes(Vec<u8>) => "bytes",
+    Float(Float) => "float",
+    Text(String) => "text",
+    Bool(bool) => "bool",
+    Array(Vec<Value>) => "array",
+    Map(Vec<(Value, Value)>) => "map",
+}
+
+macro_rules! implint {
+    ($($t:ident)+) => {
+        $(
+            impl TryFrom<&Value> for $t {
+                type Error = TryFromValueError;
+
+                #[inline]
+                fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                    match value {
+                        Value::Integer(x) => match $t::try_from(*x) {
+                            Ok(x) => Ok(x),
+                            Err(..) => Err(TryFromValueError::new(stringify!($t), value.clone())),
+                        },
+
+                        value => Err(TryFromValueError::new(stringify!($t), value.clone())),
+                    }
+                }
+            }
+
+            impl TryFrom<Value> for $t {
+                type Error = TryFromValueError;
+
+                #[inline]
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    match value {
+                        Value::Integer(x) => match $t::try_from(x) {
+                            Ok(x) => Ok(x),
+                            Err(..) => Err(TryFromValueError::new(stringify!($t), value)),
+                        },
+
+                        value => Err(TryFromValueError::new(stringify!($t), value)),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+implint! {
+    u8 u16 u32 u64
+    i8 i16 i32 i64
+}
+
+#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+implint! { usize isize }
+
+macro_rules! implbig {
+    ($($t:ident)+) => {
+        $(
+            impl TryFrom<&Value> for $t {
+                type Error = TryFromValueError;
+
+                /// Converts integers as well as bignums (tags 2 and 3) which fit
+                #[inline]
+                fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                    match value.deserialized() {
+                        Ok(x) => Ok(x),
+                        Err(..) => Err(TryFromValueError::new(stringify!($t), value.clone())),
+                    }
+                }
+            }
+
+            impl TryFrom<Value> for $t {
+                type Error = TryFromValueError;
+
+                /// Converts integers as well as bignums (tags 2 and 3) which fit
+                #[inline]
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    match value.deserialized() {
+                        Ok(x) => Ok(x),
+                        Err(..) => Err(TryFromValueError::new(stringify!($t), value)),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+implbig! { u128 i128 }
+
+impl TryFrom<&Value> for Integer {
+    type Error = TryFromValueError;
+
+    #[inline]
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Integer(x) => Ok(*x),
+            value => Err(TryFromValueError::new("integer", value.clone())),
+        }
+    }
+}
+
+impl TryFrom<Value> for (u64, Value) {
+    type Error = TryFromValueError;
+
+    /// Splits a tagged value into its outermost tag and the tagged value
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Tag(t, v) => Ok((t, *v)),
+            value => Err(TryFromValueError::new("tag", value)),
+        }
+    }
+}
+
+impl Value {
+    /// Returns the outermost tag and the tagged value, if this is a tag
+    #[inline]
+    pub fn as_tag(&self) -> Option<(u64, &Value)> {
+        match self {
+            Value::Tag(t, v) => Some((*t, v)),
+            _ => None,
+        }
+    }
+
+    /// Returns the value inside any number of tags
+    ///
+    /// ```rust
+    /// use ciborium::{cbor, value::Value};
+    ///
+    /// let value = cbor!(tag!(55799, tag!(24, "x"))).unwrap();
+    /// assert_eq!(value.as_tag().map(|(t, _)| t), Some(55799));
+    /// assert_eq!(value.untag(), &Value::from("x"));
+    /// ```
+    #[inline]
+    pub fn untag(&self) -> &Value {
+        let mut value = self;
+        while let Value::Tag(_, v) = value {
+            value = v;
+        }
+
+        value
+    }
+
+    /// Returns the value as a `u64` if it is an integer in range
+    #[inline]
+    pub fn as_u64(&self) -> Option<u64> {
+        u64::try_from(self).ok()
+    }
+
+    /// Returns the value as an `i64` if it is an integer in range
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        i64::try_from(self).ok()
+    }
+
+    /// Returns the value as a `u128` if it is an integer or a bignum in range
+    #[inline]
+    pub fn as_u128(&self) -> Option<u128> {
+        u128::try_from(self).ok()
+    }
+
+    /// Returns the value as an `i128` if it is an integer or a bignum in range
+    #[inline]
+    pub fn as_i128(&self) -> Option<i128> {
+        i128::try_from(self).ok()
+    }
+
+    /// Returns the value as an `i128` if it is integral, including floats
+    ///
+    /// Floats are converted only when they are finite, have no fractional
+    /// part and are in range; `-0.0` converts to `0`. Integers and bignums
+    /// behave as in [`Value::as_i128()`].
+    ///
+    /// ```rust
+    /// use ciborium::value::Value;
+    ///
+    /// assert_eq!(Value::from(30.0).as_integer_exact(), Some(30));
+    /// assert_eq!(Value::from(30.5).as_integer_exact(), None);
+    /// assert_eq!(Value::from(30).as_integer_exact(), Some(30));
+    /// ```
+    pub fn as_integer_exact(&self) -> Option<i128> {
+        let x = match self {
+            Value::Float(x) => f64::from(*x),
+            _ => return self.as_i128(),
+        };
+
+        // The bounds are powers of two, so they are exact as floats.
+        const LIMIT: f64 = 170141183460469231731687303715884105728.0; // 2^127
+        if x.is_finite() && x.fract() == 0.0 && (-LIMIT..LIMIT).contains(&x) {
+            Some(x as i128)
+        } else {
+            None
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = TryFromValueError;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Float(x) => Ok(x.into()),
+            value => Err(TryFromValueError::new("f64", value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = TryFromValueError;
+
+    /// Only converts floats which are exactly representable as an `f32`
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Float(x) => match f32::try_from(x) {
+                Ok(x) => Ok(x),
+                Err(..) => Err(TryFromValueError::new("f32", value)),
+            },
+
+            value => Err(TryFromValueError::new("f32", value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for () {
+    type Error = TryFromValueError;
+
+    #[inline]
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Null | Value::Undefined => Ok(()),
+            value => Err(TryFromValueError::new("null", value)),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Value;
+use crate::tag::{DateTimeError, Timestamp};
+
+use alloc::boxed::Box;
+use core::convert::TryFrom;
+
+impl Value {
+    /// Interprets the value as a date/time tag
+    ///
+    /// Tag 0 must wrap an RFC 3339 string and tag 1 an integer or float
+    /// number of seconds since the epoch. Returns `None` when the value is
+    /// neither tag and an error when it is one of them but is invalid, so
+    /// that a broken date/time can be told apart from something else.
+    ///
+    /// ```rust
+    /// use ciborium::{cbor, tag::DateTimeError};
+    ///
+    /// let value = cbor!(tag!(1, 1363896240.5)).unwrap();
+    /// let time = value.as_datetime().unwrap().unwrap();
+    /// assert_eq!((time.secs(), time.nanos()), (1363896240, 500_000_000));
+    ///
+    /// let value = cbor!(tag!(0, "2013-02-30T00:00:00Z")).unwrap();
+    /// assert_eq!(value.as_datetime(), Some(Err(DateTimeError::Malformed)));
+    ///
+    /// assert_eq!(cbor!("2013-03-21T20:04:00Z").unwrap().as_datetime(), None);
+    /// ```
+    pub fn as_datetime(&self) -> Option<Result<Timestamp, DateTimeError>> {
+        let (tag, value) = match self {
+            Value::Tag(tag, value) if *tag <= 1 => (*tag, value.as_ref()),
+            _ => return None,
+        };
+
+        Some(match (tag, value) {
+            (0, Value::Text(x)) => x.parse(),
+
+            (1, Value::Integer(x)) => match i64::try_from(*x) {
+                Ok(secs) => Ok(Timestamp::new(secs, 0).unwrap()),
+                Err(..) => Err(DateTimeError::OutOfRange),
+            },
+
+            (1, Value::Float(x)) => Timestamp::from_secs_f64((*x).into()),
+            _ => Err(DateTimeError::InvalidContent),
+        })
+    }
+
+    /// Creates a tag 0 value holding the timestamp as an RFC 3339 string
+    ///
+    /// See [`Timestamp::to_rfc3339()`] for the values that can be
+    /// represented.
+    #[inline]
+    pub fn from_datetime_string(timestamp: Timestamp) -> Result<Self, DateTimeError> {
+        let text = timestamp.to_rfc3339()?;
+        Ok(Value::Tag(0, Box::new(text.into())))
+    }
+
+    /// Creates a tag 1 value holding the timestamp as seconds since the epoch
+    ///
+    /// The seconds are an integer when the timestamp is a whole number of
+    /// seconds and a float, which may lose precision, otherwise.
+    #[inline]
+    pub fn from_epoch_time(timestamp: Timestamp) -> Self {
+        let secs = match timestamp.nanos() {
+            0 => timestamp.secs().into(),
+            _ => timestamp.as_secs_f64().into(),
+        };
+
+        Value::Tag(1, Box::new(secs))
+    }
+}
@@ -1,12 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{Error, Integer, Value};
+use super::{Error, Integer, Step, Value};
+use crate::simple::SimpleAccess;
+use crate::tag::protocol;
 
 use alloc::{boxed::Box, string::String, vec::Vec};
 use core::convert::{TryFrom, TryInto};
 use core::iter::Peekable;
 
-use ciborium_ll::tag;
+use ciborium_ll::{simple, tag};
 use serde::de::{self, Deserializer as _};
 
 impl<'a> From<Integer> for de::Unexpected<'a> {
@@ -34,6 +36,8 @@ impl<'a> From<&'a Value> for de::Unexpected<'a> {
             Value::Array(..) => Self::Seq,
             Value::Map(..) => Self::Map,
             Value::Null => Self::Other("null"),
+            Value::Undefined => Self::Other("undefined"),
+            Value::Simple(..) => Self::Other("simple"),
             Value::Tag(..) => Self::Other("tag"),
         }
     }
@@ -160,21 +164,26 @@ impl<'de> serde::de::Visitor<'de> for Visitor {
         }
 
         let (name, data): (String, _) = acc.variant()?;
-        assert_eq!("@@TAGGED@@", name);
-        data.tuple_variant(2, Inner)
+        match name.as_str() {
+            protocol::TAGGED => data.tuple_variant(2, Inner),
+            crate::simple::NAME => Ok(Value::from_simple(data.newtype_variant()?)),
+            _ => Err(de::Error::custom("unexpected enum")),
+        }
     }
 }
 
 impl<'de> de::Deserialize<'de> for Value {
     #[inline]
     fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        deserializer.deserialize_any(Visitor)
+        deserializer.deserialize_newtype_struct(crate::simple::VALUE, Visitor)
     }
 }
 
-struct Deserializer<T>(T);
+/// Wraps the data being deserialized along with whether integral floats
+/// may be deserialized as integers
+struct Deserializer<T>(T, bool);
 
-impl<'a, 'de> Deserializer<&'a Value> {
+impl Deserializer<&Value> {
     fn integer<N>(&self, kind: &'static str) -> Result<N, Error>
     where
         N: TryFrom<u128>,
@@ -210,6 +219,10 @@ impl<'a, 'de> Deserializer<&'a Value> {
 
         Ok(match self.0 {
             Value::Integer(x) => i128::from(*x).try_into().map_err(|_| err())?,
+            Value::Float(..) if self.1 => match self.0.as_integer_exact() {
+                Some(x) => x.try_into().map_err(|_| err())?,
+                None => return Err(err()),
+            },
             Value::Tag(t, v) if *t == tag::BIGPOS => raw(v)?.try_into().map_err(|_| err())?,
             Value::Tag(t, v) if *t == tag::BIGNEG => i128::try_from(raw(v)?)
                 .map(|x| x ^ !0)
@@ -220,7 +233,7 @@ impl<'a, 'de> Deserializer<&'a Value> {
     }
 }
 
-impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
+impl<'de> de::Deserializer<'de> for Deserializer<&Value> {
     type Error = Error;
 
     #[inline]
@@ -228,13 +241,17 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
         match self.0 {
             Value::Bytes(x) => visitor.visit_bytes(x),
             Value::Text(x) => visitor.visit_str(x),
-            Value::Array(x) => visitor.visit_seq(Deserializer(x.iter())),
-            Value::Map(x) => visitor.visit_map(Deserializer(x.iter().peekable())),
+            Value::Array(x) => visitor.visit_seq(Deserializer(x.iter().enumerate(), self.1)),
+            Value::Map(x) => visitor.visit_map(Deserializer(x.iter().peekable(), self.1)),
             Value::Bool(x) => visitor.visit_bool(*x),
-            Value::Null => visitor.visit_none(),
+            Value::Null | Value::Undefined => visitor.visit_none(),
+            Value::Simple(..) => Err(de::Error::invalid_type(
+                self.0.into(),
+                &"known simple value",
+            )),
 
             Value::Tag(t, v) => {
-                let parent: Deserializer<&Value> = Deserializer(&*v);
+                let parent: Deserializer<&Value> = Deserializer(v, self.1);
                 let access = crate::tag::TagAccess::new(parent, Some(*t));
                 visitor.visit_enum(access)
             }
@@ -244,10 +261,8 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
                     visitor.visit_u64(x)
                 } else if let Ok(x) = i64::try_from(*x) {
                     visitor.visit_i64(x)
-                } else if let Ok(x) = i128::try_from(*x) {
-                    visitor.visit_i128(x)
                 } else {
-                    unreachable!()
+                    visitor.visit_i128(i128::from(*x))
                 }
             }
 
@@ -281,7 +296,7 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
         }
 
         match value {
-            Value::Float(x) => visitor.visit_f64(x.clone().into()),
+            Value::Float(x) => visitor.visit_f64((*x).into()),
             _ => Err(de::Error::invalid_type(value.into(), &"f64")),
         }
     }
@@ -384,7 +399,7 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
         }
 
         match value {
-            Value::Array(x) => visitor.visit_seq(Deserializer(x.iter())),
+            Value::Array(x) => visitor.visit_seq(Deserializer(x.iter().enumerate(), self.1)),
             _ => Err(de::Error::invalid_type(value.into(), &"array")),
         }
     }
@@ -396,7 +411,7 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
         }
 
         match value {
-            Value::Map(x) => visitor.visit_map(Deserializer(x.iter().peekable())),
+            Value::Map(x) => visitor.visit_map(Deserializer(x.iter().peekable(), self.1)),
             _ => Err(de::Error::invalid_type(value.into(), &"map")),
         }
     }
@@ -444,15 +459,15 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
     #[inline]
     fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         match self.0 {
-            Value::Null => visitor.visit_none(),
-            x => visitor.visit_some(Self(x)),
+            Value::Null | Value::Undefined => visitor.visit_none(),
+            x => visitor.visit_some(Self(x, self.1)),
         }
     }
 
     #[inline]
     fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
         match self.0 {
-            Value::Null => visitor.visit_unit(),
+            Value::Null | Value::Undefined => visitor.visit_unit(),
             _ => Err(de::Error::invalid_type(self.0.into(), &"null")),
         }
     }
@@ -469,9 +484,26 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
     #[inline]
     fn deserialize_newtype_struct<V: de::Visitor<'de>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
+        // Raw CBOR is encoded from the value.
+        if name == super::raw::NAME {
+            let mut bytes = Vec::new();
+            return match self.0.into_writer(&mut bytes) {
+                Ok(()) => visitor.visit_byte_buf(bytes),
+                Err(e) => Err(de::Error::custom(e)),
+            };
+        }
+
+        if name == crate::simple::VALUE {
+            return match self.0 {
+                Value::Undefined => visitor.visit_enum(SimpleAccess::new(simple::UNDEFINED)),
+                Value::Simple(x) => visitor.visit_enum(SimpleAccess::new(*x)),
+                _ => self.deserialize_any(visitor),
+            };
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -482,27 +514,29 @@ impl<'a, 'de> de::Deserializer<'de> for Deserializer<&'a Value> {
         variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        if name == "@@TAG@@" {
+        if name == protocol::NAME {
             let (tag, val) = match self.0 {
                 Value::Tag(t, v) => (Some(*t), v.as_ref()),
                 v => (None, v),
             };
 
-            let parent: Deserializer<&Value> = Deserializer(&*val);
+            let parent: Deserializer<&Value> = Deserializer(val, self.1);
             let access = crate::tag::TagAccess::new(parent, tag);
             return visitor.visit_enum(access);
         }
 
         match self.0 {
-            Value::Tag(.., v) => Deserializer(v.as_ref()).deserialize_enum(name, variants, visitor),
-            Value::Map(x) if x.len() == 1 => visitor.visit_enum(Deserializer(&x[0])),
-            x @ Value::Text(..) => visitor.visit_enum(Deserializer(x)),
+            Value::Tag(.., v) => {
+                Deserializer(v.as_ref(), self.1).deserialize_enum(name, variants, visitor)
+            }
+            Value::Map(x) if x.len() == 1 => visitor.visit_enum(Deserializer(&x[0], self.1)),
+            x @ Value::Text(..) => visitor.visit_enum(Deserializer(x, self.1)),
             _ => Err(de::Error::invalid_type(self.0.into(), &"map")),
         }
     }
 }
 
-impl<'a, 'de, T: Iterator<Item = &'a Value>> de::SeqAccess<'de> for Deserializer<T> {
+impl<'a, 'de, T: Iterator<Item = (usize, &'a Value)>> de::SeqAccess<'de> for Deserializer<T> {
     type Error = Error;
 
     #[inline]
@@ -512,7 +546,10 @@ impl<'a, 'de, T: Iterator<Item = &'a Value>> de::SeqAccess<'de> for Deserializer
     ) -> Result<Option<U::Value>, Self::Error> {
         match self.0.next() {
             None => Ok(None),
-            Some(v) => seed.deserialize(Deserializer(v)).map(Some),
+            Some((i, v)) => seed
+                .deserialize(Deserializer(v, self.1))
+                .map(Some)
+                .map_err(|e| e.at(Step::Index(i))),
         }
     }
 }
@@ -529,7 +566,7 @@ impl<'a, 'de, T: Iterator<Item = &'a (Value, Value)>> de::MapAccess<'de>
     ) -> Result<Option<K::Value>, Self::Error> {
         match self.0.peek() {
             None => Ok(None),
-            Some(x) => Ok(Some(seed.deserialize(Deserializer(&x.0))?)),
+            Some(x) => Ok(Some(seed.deserialize(Deserializer(&x.0, self.1))?)),
         }
     }
 
@@ -538,7 +575,9 @@ impl<'a, 'de, T: Iterator<Item = &'a (Value, Value)>> de::MapAccess<'de>
         &mut self,
         seed: V,
     ) -> Result<V::Value, Self::Error> {
-        seed.deserialize(Deserializer(&self.0.next().unwrap().1))
+        let (k, v) = self.0.next().unwrap();
+        seed.deserialize(Deserializer(v, self.1))
+            .map_err(|e| e.at(Step::Key(k.clone())))
     }
 }
 
@@ -551,8 +590,8 @@ impl<'a, 'de> de::EnumAccess<'de> for Deserializer<&'a (Value, Value)> {
         self,
         seed: V,
     ) -> Result<(V::Value, Self::Variant), Self::Error> {
-        let k = seed.deserialize(Deserializer(&self.0 .0))?;
-        Ok((k, Deserializer(&self.0 .1)))
+        let k = seed.deserialize(Deserializer(&self.0 .0, self.1))?;
+        Ok((k, Deserializer(&self.0 .1, self.1)))
     }
 }
 
@@ -565,12 +604,13 @@ impl<'a, 'de> de::EnumAccess<'de> for Deserializer<&'a Value> {
         self,
         seed: V,
     ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let exact = self.1;
         let k = seed.deserialize(self)?;
-        Ok((k, Deserializer(&Value::Null)))
+        Ok((k, Deserializer(&Value::Null, exact)))
     }
 }
 
-impl<'a, 'de> de::VariantAccess<'de> for Deserializer<&'a Value> {
+impl<'de> de::VariantAccess<'de> for Deserializer<&Value> {
     type Error = Error;
 
     #[inline]
@@ -610,8 +650,46 @@ impl<'a, 'de> de::VariantAccess<'de> for Deserializer<&'a Value> {
 
 impl Value {
     /// Deserializes the `Value` into an object
+    ///
+    /// When the failure occurred inside of an array or a map, the error
+    /// is an [`Error::At`] which records the location of the failing value.
+    ///
+    /// ```rust
+    /// use ciborium::{cbor, value::{Error, Step}};
+    ///
+    /// use std::collections::BTreeMap;
+    ///
+    /// let value = cbor!({ "ports" => [80, "443"] }).unwrap();
+    /// let error = value.deserialized::<BTreeMap<String, Vec<u16>>>().unwrap_err();
+    ///
+    /// match error {
+    ///     Error::At(path, ..) => assert_eq!(&path[..], [Step::Key("ports".into()), Step::Index(1)]),
+    ///     _ => panic!("unexpected error: {:?}", error),
+    /// }
+    /// ```
     #[inline]
     pub fn deserialized<'de, T: de::Deserialize<'de>>(&self) -> Result<T, Error> {
-        T::deserialize(Deserializer(self))
+        T::deserialize(Deserializer(self, false))
+    }
+
+    /// Deserializes the `Value` into an object, accepting integral floats
+    ///
+    /// This behaves like [`Value::deserialized()`], except that wherever an
+    /// integer is expected, a float is also accepted as long as it converts
+    /// to that integer type without loss (see [`Value::as_integer_exact()`]).
+    /// This is useful for documents which have passed through JSON.
+    ///
+    /// ```rust
+    /// use ciborium::cbor;
+    ///
+    /// let value = cbor!({ "port" => 8080.0 }).unwrap();
+    /// assert!(value.deserialized::<std::collections::BTreeMap<String, u16>>().is_err());
+    ///
+    /// let map: std::collections::BTreeMap<String, u16> = value.deserialized_exact_floats().unwrap();
+    /// assert_eq!(map["port"], 8080);
+    /// ```
+    #[inline]
+    pub fn deserialized_exact_floats<'de, T: de::Deserialize<'de>>(&self) -> Result<T, Error> {
+        T::deserialize(Deserializer(self, true))
     }
 }
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Path, Step, Value};
+
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt::{Display, Formatter, Result};
+
+/// How a value differs between two documents
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiffKind<'a> {
+    /// The value is only present in the new document
+    Added(&'a Value),
+
+    /// The value is only present in the old document
+    Removed(&'a Value),
+
+    /// The value is present in both documents but differs
+    Changed {
+        /// The value in the old document
+        old: &'a Value,
+
+        /// The value in the new document
+        new: &'a Value,
+    },
+}
+
+/// A single difference between two documents
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffEntry<'a> {
+    /// The location of the difference
+    pub path: Path,
+
+    /// The kind of difference
+    pub kind: DiffKind<'a>,
+}
+
+impl Display for DiffEntry<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.kind {
+            DiffKind::Added(v) => write!(f, "{}: added {}", self.path, v.display_truncated(32)),
+            DiffKind::Removed(v) => write!(f, "{}: removed {}", self.path, v.display_truncated(32)),
+            DiffKind::Changed { old, new } => write!(
+                f,
+                "{}: changed {} to {}",
+                self.path,
+                old.display_truncated(32),
+                new.display_truncated(32)
+            ),
+        }
+    }
+}
+
+/// Computes the structural differences between two values
+///
+/// Maps are compared by key, regardless of the order of their entries;
+/// when a key appears more than once, its last entry is used. Arrays are
+/// compared by position and tags are compared by number before their
+/// contents are compared. Values which differ in kind are reported as
+/// changed. The differences are returned in depth-first order, visiting
+/// map entries in key order.
+///
+/// ```rust
+/// use ciborium::{cbor, value::diff};
+///
+/// let old = cbor!({ "name" => "web", "ports" => [80, 443] }).unwrap();
+/// let new = cbor!({ "ports" => [8080], "name" => "web" }).unwrap();
+///
+/// let lines: Vec<_> = diff(&old, &new).iter().map(|e| e.to_string()).collect();
+/// assert_eq!(lines, ["$.ports[0]: changed 80 to 8080", "$.ports[1]: removed 443"]);
+/// ```
+#[inline]
+pub fn diff<'a>(old: &'a Value, new: &'a Value) -> Vec<DiffEntry<'a>> {
+    diff_limited(old, new, usize::MAX)
+}
+
+/// Computes at most `limit` structural differences between two values
+///
+/// This behaves like [`diff()`] but stops once `limit` differences have
+/// been found, which bounds the size of the output for untrusted input.
+pub fn diff_limited<'a>(old: &'a Value, new: &'a Value, limit: usize) -> Vec<DiffEntry<'a>> {
+    enum Task<'a> {
+        Compare(&'a Value, &'a Value),
+        Report(DiffKind<'a>),
+    }
+
+    let mut entries = Vec::new();
+    let mut path = Path::default();
+    let mut stack = Vec::new();
+    stack.push((0, None, Task::Compare(old, new)));
+
+    while let Some((depth, step, task)) = stack.pop() {
+        if entries.len() >= limit {
+            break;
+        }
+
+        path.enter(depth, step);
+        let depth = path.len();
+
+        let (old, new) = match task {
+            Task::Compare(old, new) => (old, new),
+            Task::Report(kind) => {
+                entries.push(DiffEntry {
+                    path: path.clone(),
+                    kind,
+                });
+                continue;
+            }
+        };
+
+        let mut children = Vec::new();
+
+        match (old, new) {
+            (Value::Tag(a, x), Value::Tag(b, y)) if a == b => {
+                children.push((Step::Tag(*a), Task::Compare(x, y)));
+            }
+
+            (Value::Array(x), Value::Array(y)) => {
+                for (i, (a, b)) in x.iter().zip(y.iter()).enumerate() {
+                    children.push((Step::Index(i), Task::Compare(a, b)));
+                }
+
+                for (i, a) in x.iter().enumerate().skip(y.len()) {
+                    children.push((Step::Index(i), Task::Report(DiffKind::Removed(a))));
+                }
+
+                for (i, b) in y.iter().enumerate().skip(x.len()) {
+                    children.push((Step::Index(i), Task::Report(DiffKind::Added(b))));
+                }
+            }
+
+            (Value::Map(x), Value::Map(y)) => {
+                let x: BTreeMap<_, _> = x.iter().map(|(k, v)| (k, v)).collect();
+                let y: BTreeMap<_, _> = y.iter().map(|(k, v)| (k, v)).collect();
+
+                for (k, a) in x.iter() {
+                    let task = match y.get(k) {
+                        Some(b) => Task::Compare(a, b),
+                        None => Task::Report(DiffKind::Removed(a)),
+                    };
+
+                    children.push((Step::Key((*k).clone()), task));
+                }
+
+                for (k, b) in y.iter() {
+                    if !x.contains_key(k) {
+                        children.push((Step::Key((*k).clone()), Task::Report(DiffKind::Added(b))));
+                    }
+                }
+            }
+
+            (a, b) if a == b => (),
+
+            (a, b) => entries.push(DiffEntry {
+                path: path.clone(),
+                kind: DiffKind::Changed { old: a, new: b },
+            }),
+        }
+
+        let children = children.into_iter().rev();
+        stack.extend(children.map(|(step, task)| (depth, Some(step), task)));
+    }
+
+    entries
+}
@@ -1,5 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use super::{Path, Step};
+
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 
 /// The error when serializing to/from a `Value`
@@ -7,6 +10,23 @@ use alloc::string::{String, ToString};
 pub enum Error {
     /// A custom error string produced by serde
     Custom(String),
+
+    /// An error that occurred at the given location within a `Value`
+    At(Path, Box<Error>),
+}
+
+impl Error {
+    /// Adds a leading step to the location of this error
+    pub(crate) fn at(self, step: Step) -> Self {
+        match self {
+            Self::At(mut path, error) => {
+                path.prepend(step);
+                Self::At(path, error)
+            }
+
+            error => Self::At(Path::from(step), Box::new(error)),
+        }
+    }
 }
 
 impl core::fmt::Display for Error {
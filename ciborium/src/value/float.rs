@@ -11,6 +11,10 @@ use serde::{Deserialize, Serialize};
 pub struct TryFromFloatError(());
 
 /// An abstract floating point value
+///
+/// Floats are compared and hashed by their bit patterns. This means that
+/// NaNs with the same bit pattern are equal to each other and that `0.0` and
+/// `-0.0` are distinct.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Float(f64);
 
@@ -68,10 +72,17 @@ impl PartialEq for Float {
 impl Ord for Float {
     #[inline]
     fn cmp(&self, rhs: &Self) -> Ordering {
+        // Equality is bitwise, so the ordering must tell apart the values that
+        // compare equal numerically: `-0.0` sorts before `0.0` and NaNs sort by
+        // their bit patterns after every other value.
         match (self.0.is_nan(), rhs.0.is_nan()) {
-            (false, false) => self.0.partial_cmp(&rhs.0).unwrap(),
+            (false, false) => self
+                .0
+                .partial_cmp(&rhs.0)
+                .unwrap()
+                .then_with(|| rhs.0.is_sign_negative().cmp(&self.0.is_sign_negative())),
             (false, true) => Ordering::Less,
-            (true, true) => Ordering::Equal,
+            (true, true) => self.0.to_bits().cmp(&rhs.0.to_bits()),
             (true, false) => Ordering::Greater,
         }
     }
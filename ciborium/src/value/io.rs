@@ -0,0 +1,448 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Value;
+
+use crate::{de, ser};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::convert::TryFrom;
+
+use ciborium_io::{Read, Write};
+use ciborium_ll::{simple, tag, Decoder, Encoder, Header};
+
+/// The number of nested levels allowed, as in the serde deserializer
+const MAX_DEPTH: usize = 256;
+
+/// How a decoded `Value` was laid out on the wire
+///
+/// [`Value`] does not distinguish between definite and indefinite length
+/// items, so re-encoding a decoded value normally writes every container
+/// and string with a definite length, and every float in its shortest
+/// width. An `Encoding`, obtained from [`Value::from_reader_with_encoding()`],
+/// records the length form of each array and map, the chunking of each byte
+/// and text string and the width of each float so that
+/// [`Value::into_writer_with_encoding()`] can reproduce them.
+///
+/// The forms are recorded in the order the items appear in the input. If
+/// the value is modified in between, the forms are applied to whichever
+/// items are now at those positions; a form which no longer fits an item
+/// (such as chunks which do not add up to the length of a string, or a
+/// width too narrow for a float) is ignored and the item is written as it
+/// would be without an encoding.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Encoding(Vec<Form>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Form {
+    Definite,
+    Indefinite,
+    Chunked(Vec<usize>),
+
+    /// A float of this many bytes
+    Float(usize),
+}
+
+struct Reader<'b, R: Read> {
+    decoder: Decoder<R>,
+    scratch: &'b mut [u8],
+    recurse: usize,
+    forms: Option<Vec<Form>>,
+}
+
+impl<'b, R: Read> Reader<'b, R> {
+    #[inline]
+    fn recurse<V, F: FnOnce(&mut Self) -> Result<V, de::Error<R::Error>>>(
+        &mut self,
+        offset: usize,
+        func: F,
+    ) -> Result<V, de::Error<R::Error>> {
+        if self.recurse == 0 {
+            return Err(de::Error::depth(MAX_DEPTH, offset));
+        }
+
+        self.recurse -= 1;
+        let result = func(self);
+        self.recurse += 1;
+        result
+    }
+
+    #[inline]
+    fn record(&mut self, form: impl FnOnce() -> Form) {
+        if let Some(forms) = self.forms.as_mut() {
+            forms.push(form());
+        }
+    }
+
+    #[inline]
+    fn record_chunks(&mut self, len: Option<usize>, chunks: Vec<usize>) {
+        self.record(|| match len {
+            Some(..) => Form::Definite,
+            None => Form::Chunked(chunks),
+        })
+    }
+
+    fn bytes(&mut self, len: Option<usize>) -> Result<Vec<u8>, de::Error<R::Error>> {
+        let mut buffer = Vec::new();
+        let mut chunks = Vec::new();
+        let record = self.forms.is_some();
+
+        let mut segments = self.decoder.bytes(len);
+        while let Some(mut segment) = segments.pull()? {
+            if record {
+                chunks.push(segment.left());
+            }
+
+            while let Some(chunk) = segment.pull(self.scratch)? {
+                buffer.extend_from_slice(chunk);
+            }
+        }
+
+        self.record_chunks(len, chunks);
+        Ok(buffer)
+    }
+
+    fn text(&mut self, len: Option<usize>) -> Result<String, de::Error<R::Error>> {
+        let mut buffer = String::new();
+        let mut chunks = Vec::new();
+        let record = self.forms.is_some();
+
+        let mut segments = self.decoder.text(len);
+        while let Some(mut segment) = segments.pull()? {
+            if record {
+                chunks.push(segment.left());
+            }
+
+            while let Some(chunk) = segment.pull(self.scratch)? {
+                buffer.push_str(chunk);
+            }
+        }
+
+        self.record_chunks(len, chunks);
+        Ok(buffer)
+    }
+
+    fn tag(&mut self, offset: usize, tag: u64) -> Result<Value, de::Error<R::Error>> {
+        let header = self.decoder.peek()?;
+        let bytes = matches!(header, Header::Bytes(..));
+
+        let value = self.recurse(offset, |me| me.value())?;
+
+        // Small bignums are normalized to integers, just like the serde path,
+        // whether or not their bytes are in chunks. When recording the
+        // encoding they are kept so that they round-trip.
+        let bytes = bytes && self.forms.is_none();
+        let raw = match (tag, &value) {
+            (tag::BIGPOS, Value::Bytes(x)) | (tag::BIGNEG, Value::Bytes(x))
+                if bytes && x.len() <= 16 =>
+            {
+                let mut buffer = [0u8; 16];
+                buffer[16 - x.len()..].copy_from_slice(x);
+                u128::from_be_bytes(buffer)
+            }
+
+            _ => return Ok(Value::Tag(tag, Box::new(value))),
+        };
+
+        Ok(match tag {
+            tag::BIGPOS => raw.into(),
+            _ => match i128::try_from(raw) {
+                Ok(x) => (x ^ !0).into(),
+                Err(..) => Value::Tag(tag, Box::new(value)),
+            },
+        })
+    }
+
+    fn value(&mut self) -> Result<Value, de::Error<R::Error>> {
+        let offset = self.decoder.offset();
+
+        Ok(match self.decoder.pull()? {
+            Header::Positive(x) => x.into(),
+            h @ Header::Negative(..) => h.as_i128().unwrap().into(),
+            Header::Float(x) => {
+                let width = self.decoder.offset() - offset - 1;
+                self.record(|| Form::Float(width));
+                x.into()
+            }
+
+            Header::Simple(x) => Value::from_simple(x),
+            Header::Bytes(len) => Value::Bytes(self.bytes(len)?),
+            Header::Text(len) => Value::Text(self.text(len)?),
+            Header::Tag(x) => self.tag(offset, x)?,
+            Header::Break => return Err(de::Error::UnexpectedBreak { offset }),
+
+            Header::Array(len) => self.recurse(offset, |me| {
+                me.record(|| match len {
+                    Some(..) => Form::Definite,
+                    None => Form::Indefinite,
+                });
+
+                let mut array = Vec::with_capacity(len.unwrap_or(0).min(4096));
+
+                while len.map(|x| array.len() < x).unwrap_or(true) {
+                    // Peeking keeps the header as it was encoded, which
+                    // pushing it back would not, for the width of a float.
+                    if len.is_none() && me.decoder.peek()? == Header::Break {
+                        me.decoder.pull()?;
+                        break;
+                    }
+
+                    array.push(me.value()?);
+                }
+
+                Ok(Value::Array(array))
+            })?,
+
+            Header::Map(len) => self.recurse(offset, |me| {
+                me.record(|| match len {
+                    Some(..) => Form::Definite,
+                    None => Form::Indefinite,
+                });
+
+                let mut map = Vec::with_capacity(len.unwrap_or(0).min(4096));
+
+                while len.map(|x| map.len() < x).unwrap_or(true) {
+                    if len.is_none() && me.decoder.peek()? == Header::Break {
+                        me.decoder.pull()?;
+                        break;
+                    }
+
+                    let key = me.value()?;
+                    let val = me.value()?;
+                    map.push((key, val));
+                }
+
+                Ok(Value::Map(map))
+            })?,
+        })
+    }
+}
+
+struct Writer<'e, W: Write> {
+    encoder: Encoder<W>,
+    forms: core::slice::Iter<'e, Form>,
+}
+
+impl<'e, W: Write> Writer<'e, W> {
+    /// Returns the chunk lengths to use for a string of `len` bytes, if any
+    fn chunks(&mut self, len: usize) -> Option<&'e [usize]> {
+        match self.forms.next() {
+            Some(Form::Chunked(x)) if x.iter().sum::<usize>() == len => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns the length to write in the header of a container
+    fn length(&mut self, len: usize) -> Option<usize> {
+        match self.forms.next() {
+            Some(Form::Indefinite) => None,
+            _ => Some(len),
+        }
+    }
+
+    /// Writes a float as wide as it was, if it fits, or else in its shortest width
+    fn float(&mut self, x: f64) -> Result<(), W::Error> {
+        match self.forms.next() {
+            Some(Form::Float(4)) if f64::from(x as f32).to_bits() == x.to_bits() => {
+                self.encoder.write_all(&[0xfa])?;
+                self.encoder.write_all(&(x as f32).to_be_bytes())
+            }
+
+            Some(Form::Float(8)) => {
+                self.encoder.write_all(&[0xfb])?;
+                self.encoder.write_all(&x.to_be_bytes())
+            }
+
+            _ => self.encoder.push(Header::Float(x)),
+        }
+    }
+
+    fn segmented(
+        &mut self,
+        bytes: &[u8],
+        chunks: &[usize],
+        header: fn(Option<usize>) -> Header,
+    ) -> Result<(), W::Error> {
+        self.encoder.push(header(None))?;
+
+        let mut bytes = bytes;
+        for len in chunks {
+            let (chunk, rest) = bytes.split_at(*len);
+            self.encoder.push(header(Some(*len)))?;
+            self.encoder.write_all(chunk)?;
+            bytes = rest;
+        }
+
+        self.encoder.push(Header::Break)
+    }
+
+    fn write(&mut self, value: &Value) -> Result<(), ser::Error<W::Error>> {
+        match value {
+            Value::Integer(x) => match Header::from_i128(i128::from(*x)) {
+                Ok(header) => self.encoder.push(header)?,
+                Err(..) => unreachable!("an integer always fits in a header"),
+            },
+
+            Value::Bytes(x) => match self.chunks(x.len()) {
+                Some(chunks) => self.segmented(x, chunks, Header::Bytes)?,
+                None => self.encoder.bytes(x, None)?,
+            },
+
+            Value::Text(x) => {
+                // Each chunk of a text string must itself be valid UTF-8.
+                let chunks = self.chunks(x.len()).filter(|chunks| {
+                    let mut offset = 0;
+                    chunks.iter().all(|len| {
+                        offset += len;
+                        x.is_char_boundary(offset)
+                    })
+                });
+
+                match chunks {
+                    Some(chunks) => self.segmented(x.as_bytes(), chunks, Header::Text)?,
+                    None => self.encoder.text(x, None)?,
+                }
+            }
+
+            Value::Float(x) => self.float((*x).into())?,
+
+            Value::Bool(false) => self.encoder.push(Header::Simple(simple::FALSE))?,
+            Value::Bool(true) => self.encoder.push(Header::Simple(simple::TRUE))?,
+            Value::Null => self.encoder.push(Header::Simple(simple::NULL))?,
+            Value::Undefined => self.encoder.push(Header::Simple(simple::UNDEFINED))?,
+            Value::Simple(x) if (24..32).contains(x) => {
+                return Err(ser::Error::Value("invalid simple value".into()))
+            }
+            Value::Simple(x) => self.encoder.push(Header::Simple(*x))?,
+
+            Value::Tag(t, v) => {
+                self.encoder.push(Header::Tag(*t))?;
+                self.write(v)?;
+            }
+
+            Value::Array(x) => {
+                let len = self.length(x.len());
+                self.encoder.push(Header::Array(len))?;
+                for v in x {
+                    self.write(v)?;
+                }
+
+                if len.is_none() {
+                    self.encoder.push(Header::Break)?;
+                }
+            }
+
+            Value::Map(x) => {
+                let len = self.length(x.len());
+                self.encoder.push(Header::Map(len))?;
+                for (k, v) in x {
+                    self.write(k)?;
+                    self.write(v)?;
+                }
+
+                if len.is_none() {
+                    self.encoder.push(Header::Break)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Value {
+    /// Decodes a `Value` directly from a reader
+    ///
+    /// This produces the same result as [`de::from_reader()`](crate::de::from_reader)
+    /// but builds the `Value` straight from the low-level decoder, bypassing
+    /// serde entirely.
+    #[inline]
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, de::Error<R::Error>> {
+        let mut scratch = [0; 4096];
+
+        let mut reader = Reader {
+            decoder: reader.into(),
+            scratch: &mut scratch,
+            recurse: MAX_DEPTH,
+            forms: None,
+        };
+
+        reader
+            .value()
+            .map_err(|e| e.eof::<R>(reader.decoder.offset()))
+    }
+
+    /// Decodes a `Value` from a reader, recording how it was encoded
+    ///
+    /// The returned [`Encoding`] can be passed to
+    /// [`Value::into_writer_with_encoding()`] to reproduce indefinite length
+    /// arrays and maps, the chunking of byte and text strings and the width
+    /// of floats.
+    /// Unlike [`Value::from_reader()`], bignums are not normalized to
+    /// integers so that they are written back unchanged.
+    ///
+    /// For input which otherwise uses the shortest form of each integer and
+    /// length, decoding and re-encoding produces identical bytes.
+    ///
+    /// ```rust
+    /// use ciborium::value::Value;
+    ///
+    /// // [_ 1, (_ h'01', h'0203')]
+    /// let bytes = [0x9f, 0x01, 0x5f, 0x41, 0x01, 0x42, 0x02, 0x03, 0xff, 0xff];
+    /// let (value, encoding) = Value::from_reader_with_encoding(&bytes[..]).unwrap();
+    ///
+    /// let mut definite = Vec::new();
+    /// value.into_writer(&mut definite).unwrap();
+    /// assert_eq!(definite, [0x82, 0x01, 0x43, 0x01, 0x02, 0x03]);
+    ///
+    /// let mut original = Vec::new();
+    /// value.into_writer_with_encoding(&encoding, &mut original).unwrap();
+    /// assert_eq!(original, bytes);
+    /// ```
+    #[inline]
+    pub fn from_reader_with_encoding<R: Read>(
+        reader: R,
+    ) -> Result<(Self, Encoding), de::Error<R::Error>> {
+        let mut scratch = [0; 4096];
+
+        let mut reader = Reader {
+            decoder: reader.into(),
+            scratch: &mut scratch,
+            recurse: MAX_DEPTH,
+            forms: Some(Vec::new()),
+        };
+
+        let value = reader
+            .value()
+            .map_err(|e| e.eof::<R>(reader.decoder.offset()))?;
+
+        Ok((value, Encoding(reader.forms.unwrap_or_default())))
+    }
+
+    /// Encodes this `Value` directly into a writer
+    ///
+    /// This produces the same result as [`ser::into_writer()`](crate::ser::into_writer)
+    /// but writes the `Value` straight to the low-level encoder, bypassing
+    /// serde entirely.
+    #[inline]
+    pub fn into_writer<W: Write>(&self, writer: W) -> Result<(), ser::Error<W::Error>> {
+        self.into_writer_with_encoding(&Encoding::default(), writer)
+    }
+
+    /// Encodes this `Value` into a writer, following a recorded encoding
+    ///
+    /// See [`Value::from_reader_with_encoding()`].
+    #[inline]
+    pub fn into_writer_with_encoding<W: Write>(
+        &self,
+        encoding: &Encoding,
+        writer: W,
+    ) -> Result<(), ser::Error<W::Error>> {
+        let mut writer = Writer {
+            encoder: Encoder::from(writer),
+            forms: encoding.0.iter(),
+        };
+
+        writer.write(self)?;
+        Ok(writer.encoder.flush()?)
+    }
+}
@@ -1,22 +1,70 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! A dynamic CBOR value
+//!
+//! # Conversions
+//!
+//! `Value` can be built from many Rust types with `From` and converted back
+//! with `TryFrom`. Failed conversions return a [`TryFromValueError`] which
+//! describes the kind of value found and hands back the original value.
+//!
+//! | Rust type                              | `From` produces        | `TryFrom<Value>` accepts       |
+//! |----------------------------------------|------------------------|--------------------------------|
+//! | `u8`..`u64`, `i8`..`i64`, `usize`, `isize` | `Integer`          | `Integer` in range             |
+//! | `u128`, `i128`                         | `Integer` or a bignum  | `Integer` or a bignum in range |
+//! | `Integer`                              | `Integer`              | `Integer`                      |
+//! | `f32`, `f64`, `Float`                  | `Float`                | `Float` (`f32` only if exact)  |
+//! | `bool`                                 | `Bool`                 | `Bool`                         |
+//! | `char`, `&str`, `String`               | `Text`                 | `Text` (as `String`)           |
+//! | `&[u8]`, `Vec<u8>`                     | `Bytes`                | `Bytes` (as `Vec<u8>`)         |
+//! | `&[Value]`, `Vec<Value>`, `[T; N]`     | `Array`                | `Array` (as `Vec<Value>`)      |
+//! | `Vec<(Value, Value)>`, `BTreeMap`, `HashMap` | `Map`            | `Map` (as `Vec<(Value, Value)>`) |
+//! | `()`                                   | `Null`                 | `Null` or `Undefined`          |
+//! | `Option<T>`                            | `Null` or `T`'s value  | -                              |
+//!
+//! Note that `[u8; N]` converts to an `Array` of integers; use a slice or
+//! a `Vec<u8>` to produce `Bytes`.
 
 mod float;
 mod integer;
 
+mod borrowed;
+mod canonical;
+mod convert;
+mod datetime;
 mod de;
+mod diff;
 mod error;
+mod io;
+pub(crate) mod raw;
+mod select;
 mod ser;
+mod truncated;
+mod walk;
 
+pub use borrowed::ValueRef;
+pub use canonical::CanonicalError;
+pub use convert::TryFromValueError;
+pub use diff::{diff, diff_limited, DiffEntry, DiffKind};
 pub use error::Error;
 pub use float::{Float, TryFromFloatError};
 pub use integer::Integer;
+pub use io::Encoding;
+pub use raw::{RawCbor, RawCborRef};
+pub use select::SelectorError;
+pub use truncated::Truncated;
+pub use walk::{Path, Step};
 
-use alloc::{boxed::Box, string::String, vec::Vec};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
 use core::convert::TryFrom;
 
 /// A representation of a dynamic CBOR value that can handled dynamically
+///
+/// Equality, ordering and hashing are structural and agree with each other,
+/// so a `Value` can be used as a key in a `HashMap` or a `BTreeMap`. Floats
+/// compare bitwise (see [`Float`]) and maps compare their entries in order,
+/// so two maps holding the same entries in a different order are unequal.
+/// Use [`Value::sort_keys()`] to normalize maps before comparing them.
 #[non_exhaustive]
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Value {
@@ -38,6 +86,12 @@ pub enum Value {
     /// Null
     Null,
 
+    /// Undefined
+    Undefined,
+
+    /// A simple value other than false, true, null or undefined
+    Simple(u8),
+
     /// Tag
     Tag(u64, Box<Value>),
 
@@ -128,6 +182,74 @@ impl From<i128> for Value {
     }
 }
 
+impl Value {
+    #[inline]
+    fn from_simple(simple: u8) -> Self {
+        match simple {
+            ciborium_ll::simple::FALSE => Value::Bool(false),
+            ciborium_ll::simple::TRUE => Value::Bool(true),
+            ciborium_ll::simple::NULL => Value::Null,
+            ciborium_ll::simple::UNDEFINED => Value::Undefined,
+            x => Value::Simple(x),
+        }
+    }
+}
+
+#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+implfrom! {
+    Integer(usize),
+    Integer(isize),
+}
+
+impl From<()> for Value {
+    #[inline]
+    fn from(_: ()) -> Self {
+        Value::Null
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(x) => x.into(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    #[inline]
+    fn from(value: [T; N]) -> Self {
+        Value::Array(IntoIterator::into_iter(value).map(Into::into).collect())
+    }
+}
+
+impl<K: Into<Value>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    #[inline]
+    fn from(value: BTreeMap<K, V>) -> Self {
+        Value::Map(
+            value
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Into<Value>, V: Into<Value>, S> From<std::collections::HashMap<K, V, S>> for Value {
+    #[inline]
+    fn from(value: std::collections::HashMap<K, V, S>) -> Self {
+        Value::Map(
+            value
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
 impl From<char> for Value {
     #[inline]
     fn from(value: char) -> Self {
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{de, ser};
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{Formatter, Result as FmtResult};
+
+use ciborium_io::{Read, Write};
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The name of the newtype struct which carries the bytes of raw CBOR
+///
+/// ciborium's deserializers capture the next data item when this is asked
+/// for, and its serializers write the bytes out as they are.
+pub(crate) const NAME: &str = "@@ciborium::raw@@";
+
+/// The encoding of one data item, kept unparsed
+///
+/// Deserializing a `RawCbor` does not build anything from the item: its
+/// extent is found by skipping over it, and its bytes are kept exactly as
+/// they were encoded. Serializing it writes the same bytes back out. This
+/// suits messages which are routed on a few fields and forwarded otherwise
+/// untouched; the item can still be parsed later with
+/// [`RawCbor::deserialized()`]. [`RawCborRef`] borrows the bytes instead.
+///
+/// ```rust
+/// use ciborium::value::RawCbor;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct Envelope {
+///     to: String,
+///     body: RawCbor,
+/// }
+///
+/// // {"to": "b", "body": [_ 1, 2]}
+/// let bytes = b"\xa2\x62to\x61b\x64body\x9f\x01\x02\xff";
+///
+/// let envelope: Envelope = ciborium::de::from_reader(&bytes[..]).unwrap();
+/// assert_eq!(envelope.body.as_bytes(), [0x9f, 0x01, 0x02, 0xff]);
+/// assert_eq!(envelope.body.deserialized::<Vec<u8>>().unwrap(), [1, 2]);
+///
+/// // The body is forwarded as it was, indefinite length and all.
+/// let mut forwarded = Vec::new();
+/// ciborium::ser::into_writer(&envelope, &mut forwarded).unwrap();
+/// assert_eq!(forwarded, bytes);
+/// ```
+///
+/// Only ciborium's serializers and deserializers, including those of
+/// [`Value`](super::Value), know about raw CBOR. Other serializers write the
+/// bytes as a byte string, and other deserializers cannot produce it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawCbor(Box<[u8]>);
+
+/// The encoding of one data item, kept unparsed and borrowed from the input
+///
+/// This is [`RawCbor`], but borrowed, so it can only be deserialized with
+/// [`de::from_slice()`](crate::de::from_slice).
+///
+/// ```rust
+/// use ciborium::value::RawCborRef;
+///
+/// // [1, {"a": 2}]
+/// let bytes = [0x82, 0x01, 0xa1, 0x61, b'a', 0x02];
+///
+/// let (number, raw): (u8, RawCborRef) = ciborium::de::from_slice(&bytes).unwrap();
+/// assert_eq!(number, 1);
+/// assert_eq!(raw.as_bytes(), &bytes[2..]);
+///
+/// // Reading from anything other than a slice cannot borrow.
+/// assert!(ciborium::de::from_reader::<(u8, RawCborRef), _>(&bytes[..]).is_err());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawCborRef<'a>(&'a [u8]);
+
+type SliceError = de::Error<<&'static [u8] as Read>::Error>;
+
+/// Checks that the bytes are exactly one data item
+fn check(bytes: &[u8]) -> Result<(), SliceError> {
+    let mut decoder = ciborium_ll::Decoder::from(bytes);
+    decoder.skip()?;
+
+    match decoder.offset() == bytes.len() {
+        true => Ok(()),
+        false => Err(de::Error::Semantic(
+            Some(decoder.offset()),
+            "trailing data after the data item".into(),
+        )),
+    }
+}
+
+impl RawCbor {
+    /// Wraps the encoding of a data item
+    ///
+    /// The bytes must be exactly one well-formed data item. Only the
+    /// structure is checked, as a deserializer would see while skipping it.
+    pub fn from_bytes(bytes: impl Into<Box<[u8]>>) -> Result<Self, SliceError> {
+        let bytes = bytes.into();
+        check(&bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Serializes an object into raw CBOR
+    #[inline]
+    pub fn serialized<T: ?Sized + Serialize>(
+        value: &T,
+    ) -> Result<Self, ser::Error<<Vec<u8> as Write>::Error>> {
+        let mut bytes = Vec::new();
+        ser::into_writer(value, &mut bytes)?;
+        Ok(Self(bytes.into()))
+    }
+
+    /// Gets the encoding of the data item
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Borrows the encoding as a [`RawCborRef`]
+    #[inline]
+    pub fn as_raw_ref(&self) -> RawCborRef<'_> {
+        RawCborRef(&self.0)
+    }
+
+    /// Returns the encoding of the data item
+    #[inline]
+    pub fn into_bytes(self) -> Box<[u8]> {
+        self.0
+    }
+
+    /// Parses the data item into an object
+    #[inline]
+    pub fn deserialized<'de, T: Deserialize<'de>>(&'de self) -> Result<T, SliceError> {
+        self.as_raw_ref().deserialized()
+    }
+}
+
+impl<'a> RawCborRef<'a> {
+    /// Wraps the encoding of a data item
+    ///
+    /// See [`RawCbor::from_bytes()`].
+    #[inline]
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, SliceError> {
+        check(bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Gets the encoding of the data item
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Copies the encoding into a [`RawCbor`]
+    #[allow(clippy::wrong_self_convention)]
+    #[inline]
+    pub fn to_owned(&self) -> RawCbor {
+        RawCbor(self.0.into())
+    }
+
+    /// Parses the data item into an object, which may borrow from it
+    #[inline]
+    pub fn deserialized<T: Deserialize<'a>>(&self) -> Result<T, SliceError> {
+        crate::de::from_slice(self.0)
+    }
+}
+
+/// The bytes inside the newtype struct [`NAME`]
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl Serialize for RawCbor {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_raw_ref().serialize(serializer)
+    }
+}
+
+impl Serialize for RawCborRef<'_> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(NAME, &Bytes(self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for RawCbor {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Owned;
+
+        impl<'de> Visitor<'de> for Owned {
+            type Value = RawCbor;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+                write!(formatter, "raw cbor")
+            }
+
+            #[inline]
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(RawCbor(v.into()))
+            }
+
+            #[inline]
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(RawCbor(v.into()))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(NAME, Owned)
+    }
+}
+
+impl<'a, 'de: 'a> Deserialize<'de> for RawCborRef<'a> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Borrowed;
+
+        impl<'de> Visitor<'de> for Borrowed {
+            type Value = RawCborRef<'de>;
+
+            fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+                write!(formatter, "raw cbor borrowed from a slice")
+            }
+
+            #[inline]
+            fn visit_borrowed_bytes<E: serde::de::Error>(
+                self,
+                v: &'de [u8],
+            ) -> Result<Self::Value, E> {
+                Ok(RawCborRef(v))
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(NAME, Borrowed)
+    }
+}
@@ -0,0 +1,394 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{walk::is_identifier, Path, Step, Value};
+
+use alloc::{string::String, vec::Vec};
+use core::convert::TryFrom;
+use core::ops::ControlFlow;
+
+/// An error that occurred while parsing a selector
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorError {
+    offset: usize,
+    reason: &'static str,
+}
+
+impl SelectorError {
+    /// The byte offset within the selector where the error occurred
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// A description of what went wrong
+    #[inline]
+    pub fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl core::fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "invalid selector at offset {}: {}",
+            self.offset, self.reason
+        )
+    }
+}
+
+impl serde::de::StdError for SelectorError {}
+
+enum Part {
+    Index(usize),
+    Key(Value),
+    Tag(u64),
+    Wildcard,
+}
+
+struct Segment {
+    recursive: bool,
+    part: Part,
+}
+
+struct Parser<'s> {
+    selector: &'s str,
+    offset: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn error<T>(&self, reason: &'static str) -> Result<T, SelectorError> {
+        Err(SelectorError {
+            offset: self.offset,
+            reason,
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.selector[self.offset..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.peek() {
+            Some(x) if x == c => {
+                self.offset += c.len_utf8();
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    fn expect(&mut self, c: char, reason: &'static str) -> Result<(), SelectorError> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => self.error(reason),
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'s str {
+        let start = self.offset;
+        while let Some(c) = self.peek().filter(|c| f(*c)) {
+            self.offset += c.len_utf8();
+        }
+
+        &self.selector[start..self.offset]
+    }
+
+    fn number(&mut self) -> Result<u64, SelectorError> {
+        let start = self.offset;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+
+        if digits.is_empty() {
+            return self.error("expected a number");
+        }
+
+        digits.parse().or_else(|_| {
+            self.offset = start;
+            self.error("number too large")
+        })
+    }
+
+    fn name(&mut self) -> Result<Part, SelectorError> {
+        if self.eat('*') {
+            return Ok(Part::Wildcard);
+        }
+
+        let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        match is_identifier(name) {
+            true => Ok(Part::Key(name.into())),
+            false => {
+                self.offset -= name.len();
+                self.error("expected a name or `*`")
+            }
+        }
+    }
+
+    fn text(&mut self) -> Result<String, SelectorError> {
+        self.expect('"', "expected `\"`")?;
+        let mut text = String::new();
+
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return self.error("unterminated string"),
+            };
+
+            self.offset += c.len_utf8();
+            match c {
+                '"' => return Ok(text),
+                '\\' => {
+                    let escape = self.offset - 1;
+                    let c = match self.peek() {
+                        Some(c) => c,
+                        None => return self.error("unterminated string"),
+                    };
+
+                    self.offset += c.len_utf8();
+                    text.push(match c {
+                        '"' | '\\' | '\'' => c,
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        '0' => '\0',
+                        'u' => {
+                            self.expect('{', "expected `{`")?;
+                            let hex = self.take_while(|c| c.is_ascii_hexdigit());
+                            self.expect('}', "expected `}`")?;
+
+                            match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                                Some(c) => c,
+                                None => {
+                                    self.offset = escape;
+                                    return self.error("invalid unicode escape");
+                                }
+                            }
+                        }
+
+                        _ => {
+                            self.offset = escape;
+                            return self.error("invalid escape");
+                        }
+                    });
+                }
+
+                c => text.push(c),
+            }
+        }
+    }
+
+    fn key(&mut self) -> Result<Value, SelectorError> {
+        match self.peek() {
+            Some('"') => Ok(self.text()?.into()),
+            Some('-') => {
+                self.offset += 1;
+                Ok((-i128::from(self.number()?)).into())
+            }
+
+            Some(c) if c.is_ascii_digit() => Ok(self.number()?.into()),
+            _ => self.error("expected a number or a string"),
+        }
+    }
+
+    fn part(&mut self) -> Result<Part, SelectorError> {
+        match self.peek() {
+            Some('[') => {
+                self.offset += 1;
+                let part = match self.eat('*') {
+                    true => Part::Wildcard,
+                    false => {
+                        let start = self.offset;
+                        let n = self.number()?;
+                        match usize::try_from(n) {
+                            Ok(n) => Part::Index(n),
+                            Err(..) => {
+                                self.offset = start;
+                                return self.error("index too large");
+                            }
+                        }
+                    }
+                };
+
+                self.expect(']', "expected `]`")?;
+                Ok(part)
+            }
+
+            Some('{') => {
+                self.offset += 1;
+                let key = self.key()?;
+                self.expect('}', "expected `}`")?;
+                Ok(Part::Key(key))
+            }
+
+            Some('<') => {
+                self.offset += 1;
+                let part = match self.eat('*') {
+                    true => Part::Wildcard,
+                    false => Part::Tag(self.number()?),
+                };
+
+                self.expect('>', "expected `>`")?;
+                Ok(part)
+            }
+
+            _ => self.error("expected `.`, `..`, `[`, `{` or `<`"),
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Segment>, SelectorError> {
+        self.expect('$', "expected `$`")?;
+
+        let mut segments = Vec::new();
+        while self.peek().is_some() {
+            let recursive = self.selector[self.offset..].starts_with("..");
+
+            let part = match recursive {
+                true => {
+                    self.offset += 2;
+                    match self.peek() {
+                        Some('[') | Some('{') | Some('<') => self.part()?,
+                        _ => self.name()?,
+                    }
+                }
+
+                false if self.eat('.') => self.name()?,
+                false => self.part()?,
+            };
+
+            segments.push(Segment { recursive, part });
+        }
+
+        Ok(segments)
+    }
+}
+
+/// Appends the children of `value` selected by `part` to `output`
+fn children<'a>(path: &Path, value: &'a Value, part: &Part, output: &mut Vec<(Path, &'a Value)>) {
+    let mut child = |step, value| {
+        let mut path = path.clone();
+        path.push(step);
+        output.push((path, value));
+    };
+
+    match (part, value) {
+        (Part::Wildcard, Value::Tag(t, v)) => child(Step::Tag(*t), v),
+        (Part::Tag(n), Value::Tag(t, v)) if n == t => child(Step::Tag(*t), v),
+
+        (Part::Wildcard, Value::Array(x)) => {
+            for (i, v) in x.iter().enumerate() {
+                child(Step::Index(i), v);
+            }
+        }
+
+        (Part::Index(i), Value::Array(x)) => {
+            if let Some(v) = x.get(*i) {
+                child(Step::Index(*i), v);
+            }
+        }
+
+        (Part::Wildcard, Value::Map(x)) => {
+            for (k, v) in x.iter() {
+                child(Step::Key(k.clone()), v);
+            }
+        }
+
+        (Part::Key(key), Value::Map(x)) => {
+            for (k, v) in x.iter().filter(|(k, _)| k == key) {
+                child(Step::Key(k.clone()), v);
+            }
+        }
+
+        _ => (),
+    }
+}
+
+impl Value {
+    /// Finds every value in this document which matches a predicate
+    ///
+    /// Values are visited in depth-first order, starting with this value.
+    /// Each match is returned together with its path.
+    pub fn find_all(&self, mut pred: impl FnMut(&Value) -> bool) -> Vec<(Path, &Value)> {
+        let mut found = Vec::new();
+
+        self.walk(|path, value| {
+            if pred(value) {
+                found.push((path.clone(), value));
+            }
+
+            ControlFlow::Continue(())
+        });
+
+        found
+    }
+
+    /// Selects the values in this document which match a selector
+    ///
+    /// A selector starts with `$`, which refers to this value, followed by
+    /// any number of segments, each of which moves from the values selected
+    /// so far to some of their children:
+    ///
+    /// | Segment         | Selects                                              |
+    /// |-----------------|------------------------------------------------------|
+    /// | `.name`         | the value of the text key `name` in a map            |
+    /// | `{"any text"}`  | the value of a text key that is not a plain name     |
+    /// | `{-1}`          | the value of an integer key                          |
+    /// | `[N]`           | the element at index `N` of an array                 |
+    /// | `<N>`           | the value wrapped by tag `N`                         |
+    /// | `.*`, `[*]`, `<*>` | every array element, map value or tagged value    |
+    ///
+    /// Prefixing a segment with `..` instead of `.` applies it to each
+    /// descendant, so `$..alg` selects every value stored under an `alg` key
+    /// anywhere in the document. Paths display in this syntax, so the
+    /// display of a [`Path`] selects the value at that path.
+    ///
+    /// ```rust
+    /// use ciborium::cbor;
+    ///
+    /// let value = cbor!({
+    ///     "headers" => [{ "alg" => "ES256" }, { "alg" => "EdDSA", "kid" => 7 }],
+    /// }).unwrap();
+    ///
+    /// let algs: Vec<_> = value
+    ///     .select("$.headers[*].alg")
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(|(path, value)| format!("{} = {}", path, value.display_truncated(16)))
+    ///     .collect();
+    ///
+    /// assert_eq!(algs, ["$.headers[0].alg = \"ES256\"", "$.headers[1].alg = \"EdDSA\""]);
+    /// ```
+    pub fn select(&self, selector: &str) -> Result<Vec<(Path, &Value)>, SelectorError> {
+        let segments = Parser {
+            selector,
+            offset: 0,
+        }
+        .parse()?;
+
+        let mut current = alloc::vec![(Path::default(), self)];
+
+        for segment in segments {
+            let mut next = Vec::new();
+
+            for (path, value) in current {
+                if !segment.recursive {
+                    children(&path, value, &segment.part, &mut next);
+                    continue;
+                }
+
+                value.walk(|relative, value| {
+                    let mut path = path.clone();
+                    for step in relative.iter() {
+                        path.push(step.clone());
+                    }
+
+                    children(&path, value, &segment.part, &mut next);
+                    ControlFlow::Continue(())
+                });
+            }
+
+            current = next;
+        }
+
+        Ok(current)
+    }
+}
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{Error, Value};
+use crate::tag::protocol;
 
 use alloc::{vec, vec::Vec};
 use core::convert::TryFrom;
@@ -16,8 +17,20 @@ impl ser::Serialize for Value {
             Value::Text(x) => serializer.serialize_str(x),
             Value::Null => serializer.serialize_unit(),
 
+            Value::Undefined => serializer.serialize_newtype_variant(
+                crate::simple::NAME,
+                0,
+                crate::simple::NAME,
+                &ciborium_ll::simple::UNDEFINED,
+            ),
+
+            Value::Simple(x) => {
+                serializer.serialize_newtype_variant(crate::simple::NAME, 0, crate::simple::NAME, x)
+            }
+
             Value::Tag(t, v) => {
-                let mut acc = serializer.serialize_tuple_variant("@@TAG@@", 0, "@@TAGGED@@", 2)?;
+                let mut acc =
+                    serializer.serialize_tuple_variant(protocol::NAME, 0, protocol::TAGGED, 2)?;
                 acc.serialize_field(t)?;
                 acc.serialize_field(v)?;
                 acc.end()
@@ -26,10 +39,8 @@ impl ser::Serialize for Value {
             Value::Float(x) => {
                 if let Ok(x) = f32::try_from(*x) {
                     serializer.serialize_f32(x)
-                } else if let Ok(x) = f64::try_from(*x) {
-                    serializer.serialize_f64(x)
                 } else {
-                    unreachable!()
+                    serializer.serialize_f64(f64::from(*x))
                 }
             }
 
@@ -52,10 +63,8 @@ impl ser::Serialize for Value {
                     serializer.serialize_i64(x)
                 } else if let Ok(x) = u128::try_from(*x) {
                     serializer.serialize_u128(x)
-                } else if let Ok(x) = i128::try_from(*x) {
-                    serializer.serialize_i128(x)
                 } else {
-                    unreachable!()
+                    serializer.serialize_i128(i128::from(*x))
                 }
             }
 
@@ -178,10 +187,17 @@ impl ser::Serializer for Serializer<()> {
     #[inline]
     fn serialize_newtype_struct<U: ?Sized + ser::Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &U,
     ) -> Result<Value, Error> {
-        value.serialize(self)
+        // Raw CBOR is decoded, so that the value is the same either way.
+        match (name, value.serialize(self)?) {
+            (super::raw::NAME, Value::Bytes(x)) => {
+                Value::from_reader(&x[..]).map_err(ser::Error::custom)
+            }
+
+            (_, value) => Ok(value),
+        }
     }
 
     #[inline]
@@ -193,7 +209,13 @@ impl ser::Serializer for Serializer<()> {
         value: &U,
     ) -> Result<Value, Error> {
         Ok(match (name, variant) {
-            ("@@TAG@@", "@@UNTAGGED@@") => Value::serialized(value)?,
+            (protocol::NAME, protocol::UNTAGGED) => Value::serialized(value)?,
+            (crate::simple::NAME, crate::simple::NAME) => {
+                match value.serialize(crate::tag::Serializer).map(u8::try_from) {
+                    Ok(Ok(x)) if !(24..32).contains(&x) => Value::from_simple(x),
+                    _ => return Err(ser::Error::custom("invalid simple value")),
+                }
+            }
             _ => vec![(variant.into(), Value::serialized(value)?)].into(),
         })
     }
@@ -229,7 +251,7 @@ impl ser::Serializer for Serializer<()> {
             name: variant,
             data: Vec::with_capacity(length),
             tag: match (name, variant) {
-                ("@@TAG@@", "@@TAGGED@@") => Some(Tagged {
+                (protocol::NAME, protocol::TAGGED) => Some(Tagged {
                     tag: None,
                     val: None,
                 }),
@@ -272,7 +294,7 @@ impl ser::Serializer for Serializer<()> {
     }
 }
 
-impl<'a> ser::SerializeSeq for Serializer<Vec<Value>> {
+impl ser::SerializeSeq for Serializer<Vec<Value>> {
     type Ok = Value;
     type Error = Error;
 
@@ -288,7 +310,7 @@ impl<'a> ser::SerializeSeq for Serializer<Vec<Value>> {
     }
 }
 
-impl<'a> ser::SerializeTuple for Serializer<Vec<Value>> {
+impl ser::SerializeTuple for Serializer<Vec<Value>> {
     type Ok = Value;
     type Error = Error;
 
@@ -304,7 +326,7 @@ impl<'a> ser::SerializeTuple for Serializer<Vec<Value>> {
     }
 }
 
-impl<'a> ser::SerializeTupleStruct for Serializer<Vec<Value>> {
+impl ser::SerializeTupleStruct for Serializer<Vec<Value>> {
     type Ok = Value;
     type Error = Error;
 
@@ -320,7 +342,7 @@ impl<'a> ser::SerializeTupleStruct for Serializer<Vec<Value>> {
     }
 }
 
-impl<'a> ser::SerializeTupleVariant for Serializer<Named<Vec<Value>>> {
+impl ser::SerializeTupleVariant for Serializer<Named<Vec<Value>>> {
     type Ok = Value;
     type Error = Error;
 
@@ -358,7 +380,7 @@ impl<'a> ser::SerializeTupleVariant for Serializer<Named<Vec<Value>>> {
     }
 }
 
-impl<'a> ser::SerializeMap for Serializer<Map> {
+impl ser::SerializeMap for Serializer<Map> {
     type Ok = Value;
     type Error = Error;
 
@@ -383,7 +405,7 @@ impl<'a> ser::SerializeMap for Serializer<Map> {
     }
 }
 
-impl<'a> ser::SerializeStruct for Serializer<Vec<(Value, Value)>> {
+impl ser::SerializeStruct for Serializer<Vec<(Value, Value)>> {
     type Ok = Value;
     type Error = Error;
 
@@ -405,7 +427,7 @@ impl<'a> ser::SerializeStruct for Serializer<Vec<(Value, Value)>> {
     }
 }
 
-impl<'a> ser::SerializeStructVariant for Serializer<Named<Vec<(Value, Value)>>> {
+impl ser::SerializeStructVariant for Serializer<Named<Vec<(Value, Value)>>> {
     type Ok = Value;
     type Error = Error;
 
@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Value;
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result};
+
+use ciborium_ll::tag;
+
+/// A `Value` formatted with long strings and containers elided
+///
+/// This type is returned by [`Value::display_truncated()`].
+#[derive(Copy, Clone, Debug)]
+pub struct Truncated<'a> {
+    value: &'a Value,
+    max: usize,
+}
+
+enum Item<'a> {
+    Value(&'a Value),
+    Str(&'static str),
+    More(usize),
+}
+
+/// Finds the largest char boundary in `text` which is not after `index`
+fn floor(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+/// Finds the smallest char boundary in `text` which is not before `index`
+fn ceil(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+
+    index
+}
+
+impl Truncated<'_> {
+    fn bytes(&self, f: &mut Formatter<'_>, bytes: &[u8]) -> Result {
+        let hex = |f: &mut Formatter<'_>, bytes: &[u8]| {
+            bytes.iter().try_for_each(|b| write!(f, "{:02x}", b))
+        };
+
+        if bytes.len() <= self.max {
+            write!(f, "h'")?;
+            hex(f, bytes)?;
+            return write!(f, "'");
+        }
+
+        let head = &bytes[..self.max.div_ceil(2)];
+        let tail = &bytes[bytes.len() - self.max / 2..];
+
+        write!(f, "h'")?;
+        hex(f, head)?;
+        write!(f, "..")?;
+        hex(f, tail)?;
+        write!(f, "' ({} bytes)", bytes.len())
+    }
+
+    fn text(&self, f: &mut Formatter<'_>, text: &str) -> Result {
+        if text.len() <= self.max {
+            return write!(f, "\"{}\"", text.escape_debug());
+        }
+
+        let head = &text[..floor(text, self.max.div_ceil(2))];
+        let tail = &text[ceil(text, text.len() - self.max / 2)..];
+
+        write!(
+            f,
+            "\"{}..{}\" ({} bytes)",
+            head.escape_debug(),
+            tail.escape_debug(),
+            text.len()
+        )
+    }
+}
+
+impl Display for Truncated<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut stack = Vec::new();
+        stack.push(Item::Value(self.value));
+
+        while let Some(item) = stack.pop() {
+            let value = match item {
+                Item::Value(value) => value,
+                Item::Str(s) => {
+                    f.write_str(s)?;
+                    continue;
+                }
+                Item::More(n) => {
+                    write!(f, "..({} more)", n)?;
+                    continue;
+                }
+            };
+
+            match value {
+                Value::Integer(x) => write!(f, "{}", i128::from(*x))?,
+                Value::Bytes(x) => self.bytes(f, x)?,
+                Value::Text(x) => self.text(f, x)?,
+                Value::Bool(x) => write!(f, "{}", x)?,
+                Value::Null => write!(f, "null")?,
+                Value::Undefined => write!(f, "undefined")?,
+                Value::Simple(x) => write!(f, "simple({})", x)?,
+
+                Value::Float(x) => match f64::from(*x) {
+                    x if x.is_nan() => write!(f, "NaN")?,
+                    x if x.is_infinite() && x > 0.0 => write!(f, "Infinity")?,
+                    x if x.is_infinite() => write!(f, "-Infinity")?,
+                    x => write!(f, "{:?}", x)?,
+                },
+
+                Value::Tag(t, v) => {
+                    write!(f, "{}(", t)?;
+                    if let Some(name) = tag::name(*t).filter(|_| f.alternate()) {
+                        write!(f, "/ {} / ", name)?;
+                    }
+
+                    stack.push(Item::Str(")"));
+                    stack.push(Item::Value(v));
+                }
+
+                Value::Array(x) => {
+                    write!(f, "[")?;
+                    stack.push(Item::Str("]"));
+
+                    if x.len() > self.max {
+                        stack.push(Item::More(x.len() - self.max));
+                        if self.max > 0 {
+                            stack.push(Item::Str(", "));
+                        }
+                    }
+
+                    for (i, v) in x.iter().take(self.max).enumerate().rev() {
+                        stack.push(Item::Value(v));
+                        if i > 0 {
+                            stack.push(Item::Str(", "));
+                        }
+                    }
+                }
+
+                Value::Map(x) => {
+                    write!(f, "{{")?;
+                    stack.push(Item::Str("}"));
+
+                    if x.len() > self.max {
+                        stack.push(Item::More(x.len() - self.max));
+                        if self.max > 0 {
+                            stack.push(Item::Str(", "));
+                        }
+                    }
+
+                    for (i, (k, v)) in x.iter().take(self.max).enumerate().rev() {
+                        stack.push(Item::Value(v));
+                        stack.push(Item::Str(": "));
+                        stack.push(Item::Value(k));
+                        if i > 0 {
+                            stack.push(Item::Str(", "));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Value {
+    /// Returns a displayable form of this value which elides large payloads
+    ///
+    /// The output resembles CBOR diagnostic notation. Byte and text strings
+    /// longer than `max` bytes only show their first and last bytes along
+    /// with their total length, and arrays and maps only show their first
+    /// `max` entries. Formatting is iterative, so it is safe to use on
+    /// deeply nested values. The alternate form (`{:#}`) annotates known
+    /// tags with their names as comments.
+    ///
+    /// ```rust
+    /// use ciborium::value::Value;
+    ///
+    /// let value = Value::Array(vec![Value::Bytes(vec![0xaa; 1024]), 1.into(), 2.into()]);
+    /// let text = value.display_truncated(2).to_string();
+    /// assert_eq!(text, "[h'aa..aa' (1024 bytes), 1, ..(1 more)]");
+    ///
+    /// let value = Value::Tag(1, Value::from(1363896240).into());
+    /// let text = format!("{:#}", value.display_truncated(2));
+    /// assert_eq!(text, "1(/ epoch time / 1363896240)");
+    /// ```
+    #[inline]
+    pub fn display_truncated(&self, max: usize) -> Truncated<'_> {
+        Truncated { value: self, max }
+    }
+}
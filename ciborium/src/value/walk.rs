@@ -0,0 +1,183 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Value;
+
+use alloc::vec::Vec;
+use core::ops::{ControlFlow, Deref};
+
+/// A single step from a `Value` to one of its children
+#[non_exhaustive]
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    /// The element at this index of an array
+    Index(usize),
+
+    /// The value stored under this key of a map
+    Key(Value),
+
+    /// The value wrapped by a tag with this number
+    Tag(u64),
+}
+
+/// The location of a `Value` within a document
+///
+/// A path is the sequence of steps taken from the root to reach a value. The
+/// root itself has an empty path.
+///
+/// Paths are displayed starting with `$` for the root, followed by `[N]`
+/// for array indices, `.name` for text keys which are plain identifiers,
+/// `{KEY}` for any other key and `<N>` for tags. For example, `$.certs[0]<24>`.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Path(Vec<Step>);
+
+impl Deref for Path {
+    type Target = [Step];
+
+    #[inline]
+    fn deref(&self) -> &[Step] {
+        &self.0
+    }
+}
+
+impl core::fmt::Display for Path {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "$")?;
+
+        for step in self.iter() {
+            match step {
+                Step::Index(i) => write!(f, "[{}]", i)?,
+                Step::Tag(t) => write!(f, "<{}>", t)?,
+
+                Step::Key(Value::Text(x)) if is_identifier(x) => write!(f, ".{}", x)?,
+                Step::Key(x) => write!(f, "{{{}}}", x.display_truncated(32))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether a text key can be displayed as `.name` in a path
+pub(crate) fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => (),
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+impl From<Step> for Path {
+    #[inline]
+    fn from(step: Step) -> Self {
+        Self(alloc::vec![step])
+    }
+}
+
+impl Path {
+    #[inline]
+    pub(crate) fn push(&mut self, step: Step) {
+        self.0.push(step);
+    }
+
+    #[inline]
+    pub(crate) fn pop(&mut self) {
+        self.0.pop();
+    }
+
+    #[inline]
+    pub(crate) fn prepend(&mut self, step: Step) {
+        self.0.insert(0, step);
+    }
+
+    #[inline]
+    pub(crate) fn enter(&mut self, depth: usize, step: Option<Step>) {
+        self.0.truncate(depth);
+        self.0.extend(step);
+    }
+}
+
+impl Value {
+    /// Visits this value and all of its descendants in depth-first order
+    ///
+    /// The callback receives the path to each value along with the value
+    /// itself. Returning `ControlFlow::Break(())` stops the walk. Map keys
+    /// are not visited on their own; they appear as steps in the path.
+    ///
+    /// The traversal is iterative, so deeply nested values will not
+    /// overflow the stack.
+    pub fn walk<'a>(&'a self, mut f: impl FnMut(&Path, &'a Value) -> ControlFlow<()>) {
+        let mut path = Path::default();
+        let mut stack = Vec::new();
+        stack.push((0, None, self));
+
+        while let Some((depth, step, value)) = stack.pop() {
+            path.enter(depth, step);
+
+            if f(&path, value).is_break() {
+                return;
+            }
+
+            let depth = path.len();
+
+            match value {
+                Value::Tag(t, v) => stack.push((depth, Some(Step::Tag(*t)), v)),
+
+                Value::Array(x) => {
+                    for (i, v) in x.iter().enumerate().rev() {
+                        stack.push((depth, Some(Step::Index(i)), v));
+                    }
+                }
+
+                Value::Map(x) => {
+                    for (k, v) in x.iter().rev() {
+                        stack.push((depth, Some(Step::Key(k.clone())), v));
+                    }
+                }
+
+                _ => (),
+            }
+        }
+    }
+
+    /// Visits this value and all of its descendants mutably, in depth-first order
+    ///
+    /// This behaves like [`Value::walk()`] except that the callback may
+    /// modify or replace the visited value. Children are visited after the
+    /// callback returns, so the walk descends into any replacement value.
+    pub fn walk_mut(&mut self, mut f: impl FnMut(&Path, &mut Value) -> ControlFlow<()>) {
+        let mut path = Path::default();
+        let mut stack = Vec::new();
+        stack.push((0, None, self));
+
+        while let Some((depth, step, value)) = stack.pop() {
+            path.enter(depth, step);
+
+            if f(&path, value).is_break() {
+                return;
+            }
+
+            let depth = path.len();
+
+            match value {
+                Value::Tag(t, v) => stack.push((depth, Some(Step::Tag(*t)), &mut **v)),
+
+                Value::Array(x) => {
+                    for (i, v) in x.iter_mut().enumerate().rev() {
+                        stack.push((depth, Some(Step::Index(i)), v));
+                    }
+                }
+
+                Value::Map(x) => {
+                    for (k, v) in x.iter_mut().rev() {
+                        stack.push((depth, Some(Step::Key(k.clone())), v));
+                    }
+                }
+
+                _ => (),
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use ciborium::de::{from_reader, Error, Options};
+use ciborium::value::Value;
+use serde::de::DeserializeOwned;
+
+fn annotate<T: DeserializeOwned + Debug>(hex: &str) -> String {
+    let input = hex::decode(hex).unwrap();
+    let error = from_reader::<T, _>(&input[..]).unwrap_err();
+    error.annotate(&input)
+}
+
+#[test]
+fn syntax() {
+    assert_eq!(
+        annotate::<Value>("82bf0102ff1c"),
+        "\
+Syntax(5)
+       0  82 bf 01 02 ff 1c
+                         ^^
+       0  array(2)
+       1    map(_)
+       2      1
+       3      2
+       4    break
+       5    ??  <--
+"
+    );
+}
+
+#[test]
+fn eof() {
+    assert_eq!(
+        annotate::<Value>("830102"),
+        "\
+UnexpectedEof { offset: 3 }
+       0  83 01 02
+                   ^^
+       0  array(3)
+       1    1
+       2    2
+       3    ??  <--
+"
+    );
+}
+
+#[test]
+fn long() {
+    assert_eq!(
+        annotate::<Value>("9815000102030405060708090a0b0c0d0e0f101112131c"),
+        "\
+Syntax(22)
+       7  05 06 07 08 09 0a 0b 0c 0d 0e 0f 10 11 12 13 1c
+                                                       ^^
+       ...
+      14    12
+      15    13
+      16    14
+      17    15
+      18    16
+      19    17
+      20    18
+      21    19
+      22    ??  <--
+"
+    );
+}
+
+#[test]
+fn body() {
+    // The offset of the truncated read is within the body of the string.
+    assert_eq!(
+        annotate::<Value>("82646162"),
+        "\
+UnexpectedEof { offset: 2 }
+       0  82 64 61 62
+                ^^
+       0  array(2)
+       1    text(4)
+"
+    );
+}
+
+#[test]
+fn invalid_type() {
+    assert_eq!(
+        annotate::<BTreeMap<String, Vec<String>>>("a1617882616101"),
+        "\
+invalid type at offset 6: expected string, found positive integer
+       0  a1 61 78 82 61 61 01
+                            ^^
+       0  map(1)
+       1    text(1)
+       3    array(2)
+       4      text(1)
+       6      1  <--
+"
+    );
+}
+
+#[test]
+fn limit() {
+    let input = [0x83, 0x01, 0x02, 0x03];
+    let error = Options::new()
+        .max_items(Some(2))
+        .from_reader::<Value, _>(&input[..])
+        .unwrap_err();
+
+    assert_eq!(
+        error.annotate(&input),
+        "\
+item count limit of 2 exceeded at offset 2, with 3
+       0  83 01 02 03
+                ^^
+       0  array(3)
+       1    1
+       2    2  <--
+"
+    );
+}
+
+#[test]
+fn unknown() {
+    let input = [0x01];
+    let error = Error::<std::io::Error>::semantic(None, "refused");
+    assert_eq!(error.annotate(&input), "Semantic(None, \"refused\")\n");
+}
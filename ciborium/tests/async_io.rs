@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::io::{Error as IoError, ErrorKind};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use ciborium::{
+    cbor,
+    de::{self, from_reader_async, Error, Options},
+    ser::{into_writer, into_writer_async},
+    value::Value,
+};
+use ciborium_io::{AsyncRead, AsyncWrite};
+use rstest::rstest;
+
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls the future until it is ready
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        if let Poll::Ready(x) = future.as_mut().poll(&mut cx) {
+            return x;
+        }
+    }
+}
+
+/// Yields at most `step` bytes per read, and is pending every other poll
+struct Trickle {
+    data: Vec<u8>,
+    step: usize,
+    ready: bool,
+}
+
+impl Trickle {
+    fn new(data: &[u8], step: usize) -> Self {
+        Self {
+            data: data.to_vec(),
+            step,
+            ready: false,
+        }
+    }
+}
+
+impl AsyncRead for Trickle {
+    type Error = IoError;
+
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &mut [u8],
+    ) -> Poll<Result<usize, IoError>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        if self.data.is_empty() {
+            return Poll::Ready(Err(ErrorKind::UnexpectedEof.into()));
+        }
+
+        let len = data.len().min(self.step).min(self.data.len());
+        data[..len].copy_from_slice(&self.data[..len]);
+        self.data.drain(..len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+/// Accepts at most `step` bytes per write, and is pending every other poll
+struct Drip {
+    data: Vec<u8>,
+    step: usize,
+    ready: bool,
+    flushed: bool,
+}
+
+impl AsyncWrite for Drip {
+    type Error = IoError;
+
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        data: &[u8],
+    ) -> Poll<Result<usize, IoError>> {
+        self.ready = !self.ready;
+        if !self.ready {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let len = data.len().min(self.step);
+        self.data.extend_from_slice(&data[..len]);
+        self.flushed = false;
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), IoError>> {
+        self.flushed = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn write_zero() -> Option<IoError> {
+        Some(ErrorKind::WriteZero.into())
+    }
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+fn values() -> Vec<Value> {
+    vec![
+        cbor!(0).unwrap(),
+        cbor!(u64::MAX).unwrap(),
+        cbor!(-1.5).unwrap(),
+        cbor!(null).unwrap(),
+        cbor!("hello").unwrap(),
+        cbor!({ "a" => [1, 2, { 3 => [] }], "b" => tag!(37, 5) }).unwrap(),
+        Value::Bytes(vec![7; 10_000]),
+        Value::from(u128::MAX),
+    ]
+}
+
+#[rstest(step, case(1), case(2), case(3), case(7), case(4096))]
+fn roundtrip(step: usize) {
+    for value in values() {
+        let bytes = encode(&value);
+
+        let decoded: Value = block_on(from_reader_async(Trickle::new(&bytes, step))).unwrap();
+        assert_eq!(decoded, value);
+
+        let mut writer = Drip {
+            data: Vec::new(),
+            step,
+            ready: false,
+            flushed: false,
+        };
+
+        block_on(into_writer_async(&value, &mut writer)).unwrap();
+        assert_eq!(writer.data, bytes);
+        assert!(writer.flushed);
+    }
+}
+
+#[rstest(bytes,
+    // (_ h'0102', h'03')
+    case("5f42010241 03ff"),
+
+    // (_ "ab", "c")
+    case("7f626162 6163ff"),
+
+    // [_ 1, [_ ], {_ 2: 3}]
+    case("9f01 9fff bf0203ff ff"),
+
+    // 24(h'a0')
+    case("d818 41a0"),
+)]
+fn indefinite(bytes: &str) {
+    let bytes = hex::decode(bytes.replace(' ', "")).unwrap();
+    let answer: Value = ciborium::de::from_reader(&bytes[..]).unwrap();
+
+    let decoded: Value = block_on(from_reader_async(Trickle::new(&bytes, 1))).unwrap();
+    assert_eq!(decoded, answer);
+}
+
+#[test]
+fn sequence() {
+    // Only one item is read, so the next one is left for the next call.
+    let mut bytes = encode(&cbor!([1, "two"]).unwrap());
+    bytes.extend(encode(&cbor!({ 3 => 4 }).unwrap()));
+
+    let mut reader = Trickle::new(&bytes, 2);
+    let first: Value = block_on(from_reader_async(&mut reader)).unwrap();
+    let second: Value = block_on(from_reader_async(&mut reader)).unwrap();
+    assert_eq!(first, cbor!([1, "two"]).unwrap());
+    assert_eq!(second, cbor!({ 3 => 4 }).unwrap());
+    assert!(reader.data.is_empty());
+
+    let slice: Value = block_on(from_reader_async(&bytes[..])).unwrap();
+    assert_eq!(slice, first);
+}
+
+#[rstest(bytes,
+    // [1, 2] without its last item
+    case("8201"),
+
+    // a string of 10 bytes with only 2
+    case("4a0102"),
+
+    // a huge string length with no contents
+    case("5bffffffffffffffff"),
+
+    // an indefinite array without a break
+    case("9f0102"),
+)]
+fn truncated(bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    match block_on(from_reader_async::<Value, _>(Trickle::new(&bytes, 3))).unwrap_err() {
+        Error::Io(e) => assert_eq!(e.kind(), ErrorKind::UnexpectedEof),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(bytes, offset,
+    // a reserved additional information value
+    case("1c", 0),
+
+    // an indefinite-length integer
+    case("821f", 1),
+)]
+fn syntax(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    match block_on(from_reader_async::<Value, _>(Trickle::new(&bytes, 1))).unwrap_err() {
+        Error::Syntax(x) => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn missing_break() {
+    // a text chunk inside a byte string is not one of its chunks
+    let bytes = hex::decode("5f6161ff").unwrap();
+
+    match block_on(from_reader_async::<Value, _>(Trickle::new(&bytes, 1))).unwrap_err() {
+        Error::MissingBreak { container_offset } => assert_eq!(container_offset, 0),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn unexpected_break() {
+    // a break outside of an indefinite-length item
+    let bytes = hex::decode("8201ff").unwrap();
+
+    match block_on(from_reader_async::<Value, _>(Trickle::new(&bytes, 1))).unwrap_err() {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 2),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn recursion() {
+    let bytes = vec![0x81; 100_000];
+
+    match block_on(from_reader_async::<Value, _>(&bytes[..])).unwrap_err() {
+        Error::LimitExceeded {
+            kind: de::LimitKind::Depth,
+            limit: 256,
+            offset: 256,
+            ..
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(max_depth, case(0), case(1), case(3), case(300))]
+fn depth(max_depth: usize) {
+    // The same limit is accepted and exceeded as in the sync path.
+    for depth in [max_depth, max_depth + 1] {
+        let mut bytes = vec![0x81; depth];
+        bytes.push(0x00);
+
+        let options = Options::new().max_depth(max_depth);
+        let sync = options.from_reader::<Value, _>(&bytes[..]);
+        let result = block_on(options.from_reader_async::<Value, _>(Trickle::new(&bytes, 2)));
+
+        match depth > max_depth {
+            false => assert_eq!(result.unwrap(), sync.unwrap()),
+            true => {
+                let sync = sync.unwrap_err();
+                assert_eq!(sync.kind(), de::ErrorKind::RecursionLimit);
+                assert_eq!(result.unwrap_err().to_string(), sync.to_string());
+            }
+        }
+    }
+}
+
+#[test]
+fn stalled() {
+    // A reader which yields nothing has ended, so it is not polled forever.
+    match block_on(from_reader_async::<Value, _>(Trickle::new(&[0x00], 0))).unwrap_err() {
+        Error::UnexpectedEof { offset } => assert_eq!(offset, 0),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // Neither is a writer which accepts nothing.
+    let mut writer = Drip {
+        data: Vec::new(),
+        step: 0,
+        ready: false,
+        flushed: false,
+    };
+
+    match block_on(into_writer_async(&[1, 2, 3], &mut writer)).unwrap_err() {
+        ciborium::ser::Error::Io(e) => assert_eq!(e.kind(), ErrorKind::WriteZero),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    assert!(!writer.flushed);
+}
+
+#[test]
+fn options() {
+    let bytes = encode(&cbor!(tag!(1, 5)).unwrap());
+    let options = Options::new().deny_unexpected_tags(true);
+
+    match block_on(options.from_reader_async::<u8, _>(Trickle::new(&bytes, 1))).unwrap_err() {
+        Error::Semantic(Some(0), msg) => assert_eq!(msg, "unexpected tag 1"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    let x: u8 = block_on(Options::new().from_reader_async(Trickle::new(&bytes, 1))).unwrap();
+    assert_eq!(x, 5);
+}
+
+#[test]
+fn unserializable() {
+    struct Fails;
+
+    impl serde::Serialize for Fails {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("nope"))
+        }
+    }
+
+    let mut writer = Vec::new();
+    match block_on(into_writer_async(&Fails, &mut writer)).unwrap_err() {
+        ciborium::ser::Error::Value(msg) => assert_eq!(msg, "nope"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    assert!(writer.is_empty());
+}
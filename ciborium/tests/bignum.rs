@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::TryFrom;
+
+use ciborium::{de::from_reader, ser::into_writer, tag::Bignum, value::Value};
+use rstest::rstest;
+
+// Examples from RFC 8949, appendix A, plus larger values
+#[rstest(
+    bytes,
+    decimal,
+    case("00", "0"),
+    case("1bffffffffffffffff", "18446744073709551615"),
+    case("c249010000000000000000", "18446744073709551616"),
+    case("3bffffffffffffffff", "-18446744073709551616"),
+    case("c349010000000000000000", "-18446744073709551617"),
+    case(
+        "c2510100000000000000000000000000000000",
+        "340282366920938463463374607431768211456"
+    ),
+    case(
+        "c350ffffffffffffffffffffffffffffffff",
+        "-340282366920938463463374607431768211456"
+    ),
+    case(
+        "c25820ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+    ),
+    case(
+        "c35820ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "-115792089237316195423570985008687907853269984665640564039457584007913129639936"
+    )
+)]
+fn roundtrip(bytes: &str, decimal: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let bignum: Bignum = from_reader(&bytes[..]).unwrap();
+    assert_eq!(bignum.to_string(), decimal);
+
+    let value = Value::from_reader(&bytes[..]).unwrap();
+    assert_eq!(value.deserialized::<Bignum>().unwrap(), bignum);
+    assert_eq!(Value::serialized(&bignum).unwrap(), value);
+
+    let mut encoded = Vec::new();
+    into_writer(&bignum, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+// Lenient encodings: leading zeros and bignums which fit in an integer
+#[rstest(
+    bytes,
+    decimal,
+    preferred,
+    case("c240", "0", "00"),
+    case("c340", "-1", "20"),
+    case("c2420001", "1", "01"),
+    case("c3420001", "-2", "21"),
+    case("c24800000000000000ff", "255", "18ff"),
+    case("c2490000000000000000ff", "255", "18ff"),
+    case("c24a00000000000000000001", "1", "01"),
+    case(
+        "c34b00ffffffffffffffffffff",
+        "-1208925819614629174706176",
+        "c34affffffffffffffffffff"
+    ),
+    case("c34b00000000000000000000ff", "-256", "38ff"),
+    case("c25f41014100ff", "256", "190100"),
+    case(
+        "c35100ffffffffffffffffffffffffffffffff",
+        "-340282366920938463463374607431768211456",
+        "c350ffffffffffffffffffffffffffffffff"
+    )
+)]
+fn lenient(bytes: &str, decimal: &str, preferred: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let bignum: Bignum = from_reader(&bytes[..]).unwrap();
+    assert_eq!(bignum.to_string(), decimal);
+
+    let mut encoded = Vec::new();
+    into_writer(&bignum, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), preferred);
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Bignum::from(u128::MAX).magnitude(), [0xff; 16]);
+    assert_eq!(Bignum::from(i128::MIN).to_string(), i128::MIN.to_string());
+    assert_eq!(i128::try_from(Bignum::from(i128::MIN)), Ok(i128::MIN));
+    assert_eq!(u128::try_from(Bignum::from(u128::MAX)), Ok(u128::MAX));
+    assert!(u128::try_from(Bignum::from(-1i64)).is_err());
+    assert!(i128::try_from(Bignum::from(u128::MAX)).is_err());
+    assert!(u128::try_from(Bignum::new(false, &[1; 17])).is_err());
+
+    let zero = Bignum::new(true, &[0, 0]);
+    assert!(!zero.is_negative());
+    assert_eq!(zero, Bignum::default());
+    assert_eq!(format!("{:>5}", Bignum::from(-12i64)), "  -12");
+}
+
+#[rstest(bytes, case("c04130"), case("6130"), case("c26130"), case("f5"))]
+fn invalid(bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    assert!(from_reader::<Bignum, _>(&bytes[..]).is_err());
+}
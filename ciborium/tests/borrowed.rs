@@ -0,0 +1,171 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::borrow::Cow;
+
+use ciborium::{
+    de::{Error, LimitKind},
+    value::{Value, ValueRef},
+};
+use rstest::rstest;
+
+#[rstest(
+    bytes,
+    case("00"),
+    case("3863"),
+    case("c249010000000000000000"),
+    case("c349010000000000000000"),
+    case("c2540100000000000000000000000000000000000000"),
+    case("c2420100"),
+    case("c25fff"),
+    case("c35f4101404102ff"),
+    case("c25f48010000000000000049000000000000000000ff"),
+    case("c35080000000000000000000000000000000"),
+    case("c35f48ff00000000000000480000000000000001ff"),
+    case("f93e00"),
+    case("f4"),
+    case("f7"),
+    case("f820"),
+    case("40"),
+    case("4401020304"),
+    case("5f42010243030405ff"),
+    case("60"),
+    case("6449455446"),
+    case("7f657374726561646d696e67ff"),
+    case("d82076687474703a2f2f7777772e6578616d706c652e636f6d"),
+    case("83010203"),
+    case("9f018202039f0405ffff"),
+    case("a26161016162820203"),
+    case("bf61610161629f0203ffff")
+)]
+fn owned(bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let borrowed = ValueRef::from_slice(&bytes).unwrap();
+    let owned = Value::from_reader(&bytes[..]).unwrap();
+    assert_eq!(borrowed.to_owned(), owned);
+    assert_eq!(ValueRef::from(owned), borrowed);
+}
+
+fn inside(input: &[u8], slice: &[u8]) -> bool {
+    let range = input.as_ptr_range();
+    range.start <= slice.as_ptr() && slice.as_ptr() < range.end
+}
+
+#[test]
+fn zero_copy() {
+    // ["abc", h'0102', (_ h'03', h'04')]
+    let bytes = hex::decode("83636162634201025f41034104ff").unwrap();
+
+    let value = ValueRef::from_slice(&bytes).unwrap();
+    let items = match value {
+        ValueRef::Array(x) => x,
+        _ => panic!("expected array"),
+    };
+
+    match &items[0] {
+        ValueRef::Text(Cow::Borrowed(x)) => {
+            assert_eq!(*x, "abc");
+            assert!(inside(&bytes, x.as_bytes()));
+        }
+        x => panic!("expected borrowed text: {:?}", x),
+    }
+
+    match &items[1] {
+        ValueRef::Bytes(Cow::Borrowed(x)) => {
+            assert_eq!(*x, [1, 2]);
+            assert!(inside(&bytes, x));
+        }
+        x => panic!("expected borrowed bytes: {:?}", x),
+    }
+
+    match &items[2] {
+        ValueRef::Bytes(Cow::Owned(x)) => assert_eq!(x, &[3, 4]),
+        x => panic!("expected owned bytes: {:?}", x),
+    }
+}
+
+#[rstest(bytes, offset,
+    // Invalid minor
+    case("811c", 1),
+)]
+fn syntax(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes).unwrap();
+    match ValueRef::from_slice(&bytes).unwrap_err() {
+        Error::Syntax(x) => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(bytes, chunk, offset,
+    // Invalid UTF-8
+    case("8162C328", 0, 1),
+
+    // Invalid UTF-8 in a string continuation
+    case("827F62C328FF", 0, 2),
+
+    // A character split between string continuations
+    case("817F6161 61C3 61A9 FF", 1, 4),
+)]
+fn invalid_utf8(bytes: &str, chunk: usize, offset: usize) {
+    let bytes = hex::decode(bytes.replace(' ', "")).unwrap();
+    match ValueRef::from_slice(&bytes).unwrap_err() {
+        Error::InvalidUtf8 {
+            chunk: c,
+            offset: x,
+        } => assert_eq!((c, x), (chunk, offset)),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(
+    bytes,
+    offset,
+    case("ff", 0),
+    case("82ff", 1),
+    case("bf01ff", 2),
+    case("c6ff", 1)
+)]
+fn unexpected_break(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    // The same error as Value::from_reader() gives
+    match ValueRef::from_slice(&bytes).unwrap_err() {
+        Error::UnexpectedBreak { offset: x } => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    match Value::from_reader(&bytes[..]).unwrap_err() {
+        Error::UnexpectedBreak { offset: x } => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(
+    bytes,
+    offset,
+    case("45010203", 1),
+    case("8301", 2),
+    case("d8", 1),
+    case("7f6161", 3)
+)]
+fn truncated(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes).unwrap();
+    match ValueRef::from_slice(&bytes).unwrap_err() {
+        Error::UnexpectedEof { offset: x } => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn recursion() {
+    let bytes = [0x9f; 128 * 1024];
+    match ValueRef::from_slice(&bytes).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            offset: 256,
+            ..
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
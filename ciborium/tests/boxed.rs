@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::error::Error as _;
+use std::fmt;
+
+use ciborium::ser::{into_writer, Boxed, Error};
+use serde::{ser, Serialize, Serializer};
+
+/// An application error with some structure
+#[derive(Debug, PartialEq)]
+struct Refused {
+    code: u16,
+}
+
+impl fmt::Display for Refused {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "refused with code {}", self.code)
+    }
+}
+
+impl std::error::Error for Refused {}
+
+/// A field which fails to serialize with `Refused`
+struct Secret;
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(ser::Error::custom(Boxed::new(Refused { code: 403 })))
+    }
+}
+
+#[derive(Serialize)]
+struct Account {
+    name: &'static str,
+    secret: Secret,
+}
+
+#[derive(Serialize)]
+struct Accounts {
+    accounts: Vec<Account>,
+}
+
+fn custom(value: &impl Serialize) -> Box<dyn std::error::Error + Send + Sync> {
+    match into_writer(value, Vec::new()).unwrap_err() {
+        Error::Custom(e) => e,
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn field() {
+    let account = Account {
+        name: "admin",
+        secret: Secret,
+    };
+
+    let error = custom(&account);
+    assert_eq!(error.downcast_ref(), Some(&Refused { code: 403 }));
+}
+
+#[test]
+fn nested() {
+    let accounts = Accounts {
+        accounts: vec![Account {
+            name: "admin",
+            secret: Secret,
+        }],
+    };
+
+    let error = custom(&accounts);
+    assert_eq!(error.downcast_ref(), Some(&Refused { code: 403 }));
+}
+
+#[test]
+fn source() {
+    let error = into_writer(&Secret, Vec::new()).unwrap_err();
+    let source = error.source().unwrap();
+    assert_eq!(source.downcast_ref(), Some(&Refused { code: 403 }));
+}
+
+#[test]
+fn message() {
+    // Other serializers see only the message.
+    let error = ciborium::value::Value::serialized(&Secret).unwrap_err();
+    assert_eq!(error.to_string(), r#"Custom("refused with code 403")"#);
+
+    // A plain message still has a path.
+    struct Plain;
+
+    impl Serialize for Plain {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(ser::Error::custom("plain"))
+        }
+    }
+
+    match into_writer(&[Plain], Vec::new()).unwrap_err() {
+        Error::Value(msg) => assert_eq!(msg, "[0]: plain"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn constructed() {
+    let error = Error::<std::io::Error>::custom_boxed(Refused { code: 1 });
+    let owned = error.into_owned();
+
+    match owned {
+        Error::Custom(e) => assert_eq!(e.downcast_ref(), Some(&Refused { code: 1 })),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::fmt;
+use std::io::BufReader;
+use std::time::Instant;
+
+use ciborium::{
+    cbor,
+    de::{from_buf_reader, from_reader, Error},
+    ser::into_writer,
+    value::Value,
+};
+use rstest::rstest;
+use serde::{de, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Record {
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+fn records(count: u32) -> Vec<Record> {
+    (0..count)
+        .map(|id| Record {
+            id,
+            name: format!("record {}", id),
+            tags: vec!["a".into(), "bb".repeat(id as usize % 5)],
+            data: vec![id as u8; id as usize % 17],
+        })
+        .collect()
+}
+
+/// The length of a string, which must be visited as a `&str`
+#[derive(Debug, PartialEq)]
+struct StrLen(usize);
+
+impl<'de> Deserialize<'de> for StrLen {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = StrLen;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a str")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(StrLen(v.len()))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+#[rstest(capacity, case(1), case(7), case(64), case(8192))]
+fn same_as_reader(capacity: usize) {
+    let records = records(100);
+    let mut bytes = Vec::new();
+    into_writer(&records, &mut bytes).unwrap();
+
+    let reader = BufReader::with_capacity(capacity, &bytes[..]);
+    let decoded: Vec<Record> = from_buf_reader(reader).unwrap();
+    assert_eq!(decoded, records);
+
+    let reader = BufReader::with_capacity(capacity, &bytes[..]);
+    let value: Value = from_buf_reader(reader).unwrap();
+    assert_eq!(value, from_reader::<Value, _>(&bytes[..]).unwrap());
+}
+
+#[test]
+fn long_str() {
+    let mut bytes = Vec::new();
+    into_writer(&"x".repeat(10_000), &mut bytes).unwrap();
+
+    // The string does not fit in the scratch buffer, so it is copied...
+    let len: StrLen = from_reader(&bytes[..]).unwrap();
+    assert_eq!(len, StrLen(10_000));
+
+    // ...but it is visited in place when it is all in the reader's buffer.
+    let len: StrLen = from_buf_reader(&bytes[..]).unwrap();
+    assert_eq!(len, StrLen(10_000));
+
+    let reader = BufReader::with_capacity(16_384, &bytes[..]);
+    let len: StrLen = from_buf_reader(reader).unwrap();
+    assert_eq!(len, StrLen(10_000));
+
+    // Types which ask for any item get the same.
+    let value: Value = from_buf_reader(&bytes[..]).unwrap();
+    assert_eq!(value, Value::Text("x".repeat(10_000)));
+}
+
+#[test]
+fn chunked_str() {
+    // (_ "xxx...", "xxx...", "xxx..."), with chunks longer than the scratch buffer
+    let mut bytes = vec![0x7f];
+    for _ in 0..3 {
+        bytes.extend_from_slice(&[0x79, 0x13, 0x88]);
+        bytes.extend_from_slice(&[b'x'; 5000]);
+    }
+    bytes.push(0xff);
+
+    let len: StrLen = from_reader(&bytes[..]).unwrap();
+    assert_eq!(len, StrLen(15_000));
+
+    let len: StrLen = from_buf_reader(&bytes[..]).unwrap();
+    assert_eq!(len, StrLen(15_000));
+
+    let value: Value = from_reader(&bytes[..]).unwrap();
+    assert_eq!(value, Value::Text("x".repeat(15_000)));
+}
+
+#[rstest(bytes, offset,
+    // ["ab", <invalid UTF-8>]
+    case("82626162 62ff61", 4),
+
+    // ["ab", h'01', <reserved>]
+    case("83626162 4101 1c", 6),
+)]
+fn offsets(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes.replace(' ', "")).unwrap();
+
+    match from_buf_reader::<Value, _>(&bytes[..]).unwrap_err() {
+        Error::Syntax(x) | Error::InvalidUtf8 { offset: x, .. } => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+#[ignore]
+fn benchmark() {
+    let mut bytes = Vec::new();
+    for record in records(200_000) {
+        into_writer(
+            &cbor!({ "id" => record.id, "name" => record.name }).unwrap(),
+            &mut bytes,
+        )
+        .unwrap();
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Small<'a> {
+        id: u32,
+        #[serde(borrow)]
+        name: std::borrow::Cow<'a, str>,
+    }
+
+    let start = Instant::now();
+    let mut reader = BufReader::new(&bytes[..]);
+    for _ in 0..200_000 {
+        let _: Small = from_reader(&mut reader).unwrap();
+    }
+    let plain = start.elapsed();
+
+    let start = Instant::now();
+    let mut reader = BufReader::new(&bytes[..]);
+    for _ in 0..200_000 {
+        let _: Small = from_buf_reader(&mut reader).unwrap();
+    }
+    let buffered = start.elapsed();
+
+    println!("from_reader: {:?}, from_buf_reader: {:?}", plain, buffered);
+}
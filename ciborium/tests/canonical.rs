@@ -0,0 +1,165 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    value::{CanonicalError, Value},
+};
+use rstest::rstest;
+
+fn map(entries: &[(Value, Value)]) -> Value {
+    Value::Map(entries.to_vec())
+}
+
+#[rstest(input, answer,
+    case(cbor!(1).unwrap(), cbor!(1).unwrap()),
+    case(cbor!([3, 1, 2]).unwrap(), cbor!([3, 1, 2]).unwrap()),
+    case(
+        map(&[("b".into(), 1.into()), ("a".into(), 2.into())]),
+        map(&[("a".into(), 2.into()), ("b".into(), 1.into())])
+    ),
+    // Shorter encodings sort first, so 10 comes before -1 and "aa"
+    case(
+        map(&[("aa".into(), 1.into()), ((-1).into(), 2.into()), (10.into(), 3.into())]),
+        map(&[(10.into(), 3.into()), ((-1).into(), 2.into()), ("aa".into(), 1.into())])
+    ),
+    // Maps nested in arrays, tags and map values are sorted too
+    case(
+        cbor!([tag!(7, { "z" => 1, "y" => { "b" => 2, "a" => 3 } })]).unwrap(),
+        Value::Array(vec![Value::Tag(7, Box::new(map(&[
+            ("y".into(), map(&[("a".into(), 3.into()), ("b".into(), 2.into())])),
+            ("z".into(), 1.into()),
+        ])))])
+    ),
+    // Maps used as keys are sorted as well
+    case(
+        map(&[(map(&[("b".into(), 1.into()), ("a".into(), 2.into())]), Value::Null)]),
+        map(&[(map(&[("a".into(), 2.into()), ("b".into(), 1.into())]), Value::Null)])
+    ),
+    // Duplicates are kept in their original order
+    case(
+        map(&[("b".into(), 1.into()), ("a".into(), 2.into()), ("b".into(), 3.into())]),
+        map(&[("a".into(), 2.into()), ("b".into(), 1.into()), ("b".into(), 3.into())])
+    ),
+)]
+fn sort_keys(mut input: Value, answer: Value) {
+    input.sort_keys();
+    assert_eq!(input, answer);
+}
+
+#[rstest(input, answer, dropped,
+    case(cbor!([1, 1]).unwrap(), cbor!([1, 1]).unwrap(), 0),
+    case(
+        map(&[("b".into(), 1.into()), ("a".into(), 2.into()), ("b".into(), 3.into())]),
+        map(&[("a".into(), 2.into()), ("b".into(), 3.into())]),
+        1
+    ),
+    // Keys that only differ by nested map order are duplicates
+    case(
+        map(&[
+            (map(&[("b".into(), 1.into()), ("a".into(), 2.into())]), 1.into()),
+            (map(&[("a".into(), 2.into()), ("b".into(), 1.into())]), 2.into()),
+        ]),
+        map(&[(map(&[("a".into(), 2.into()), ("b".into(), 1.into())]), 2.into())]),
+        1
+    ),
+    case(
+        Value::Array(vec![
+            map(&[(1.into(), 1.into()), (1.into(), 2.into())]),
+            map(&[(2.into(), 1.into()), (2.into(), 2.into()), (2.into(), 3.into())]),
+        ]),
+        Value::Array(vec![map(&[(1.into(), 2.into())]), map(&[(2.into(), 3.into())])]),
+        3
+    ),
+)]
+fn sort_and_dedup_keys(mut input: Value, answer: Value, dropped: usize) {
+    assert_eq!(input.sort_and_dedup_keys(), dropped);
+    assert_eq!(input, answer);
+}
+
+#[test]
+fn deep() {
+    let mut value = map(&[("b".into(), 1.into()), ("a".into(), 2.into())]);
+    for _ in 0..100_000 {
+        value = Value::Array(vec![value]);
+    }
+
+    value.sort_keys();
+
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        match value {
+            Value::Array(x) => stack.extend(x),
+            x => assert_eq!(x, map(&[("a".into(), 2.into()), ("b".into(), 1.into())])),
+        }
+    }
+}
+
+// Examples from RFC 8949, sections 4.2.1 and 4.2.2 and appendix A
+#[rstest(value, bytes,
+    case(cbor!(0).unwrap(), "00"),
+    case(cbor!(23).unwrap(), "17"),
+    case(cbor!(24).unwrap(), "1818"),
+    case(cbor!(1000000).unwrap(), "1a000f4240"),
+    case(cbor!(u64::MAX).unwrap(), "1bffffffffffffffff"),
+    case(cbor!(-1000).unwrap(), "3903e7"),
+    case(Value::from(u128::from(u64::MAX) + 1), "c249010000000000000000"),
+    case(cbor!(0.0).unwrap(), "f90000"),
+    case(cbor!(-0.0).unwrap(), "f98000"),
+    case(cbor!(1.5).unwrap(), "f93e00"),
+    case(cbor!(100000.0).unwrap(), "fa47c35000"),
+    case(cbor!(1.1).unwrap(), "fb3ff199999999999a"),
+    case(cbor!(f64::INFINITY).unwrap(), "f97c00"),
+    case(cbor!(f64::NAN).unwrap(), "f97e00"),
+    case(Value::Float(f64::from_bits(0x7ff0_0000_0000_0001).into()), "f97e00"),
+    case(Value::Float(f64::from_bits(0xfff8_0000_0000_0000).into()), "f97e00"),
+    case(cbor!(tag!(1, 1363896240)).unwrap(), "c11a514b67b0"),
+    case(
+        cbor!({ false => 8, [-1] => 7, [100] => 6, "aa" => 5, "z" => 4, -1 => 3, 100 => 2, 10 => 1 }).unwrap(),
+        "a80a011864022003617a046261610581186406812007f408"
+    ),
+    case(
+        cbor!({ "b" => { "d" => 1, "c" => [{ "f" => 2, "e" => 3 }] }, "a" => 0 }).unwrap(),
+        "a26161006162a2616381a2616503616602616401"
+    ),
+)]
+fn canonical_vec(value: Value, bytes: &str) {
+    assert_eq!(hex::encode(value.to_canonical_vec().unwrap()), bytes);
+}
+
+#[test]
+fn canonical_vec_errors() {
+    let value = cbor!({ "a" => [{ 1 => 1, 2 => 2, 1 => 3 }] }).unwrap();
+    let error = value.to_canonical_vec().unwrap_err();
+    assert_eq!(error.to_string(), "duplicate map key at $.a[0]{1}");
+    match error {
+        CanonicalError::DuplicateKey(path) => assert_eq!(path.len(), 3),
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    // Keys which only differ by the order of their maps are duplicates.
+    let value = cbor!({ { 1 => 1, 2 => 2 } => 1, { 2 => 2, 1 => 1 } => 2 }).unwrap();
+    assert!(matches!(
+        value.to_canonical_vec(),
+        Err(CanonicalError::DuplicateKey(..))
+    ));
+
+    // So are NaN keys with different payloads.
+    let nan = Value::Float(f64::from_bits(0x7ff0_0000_0000_0001).into());
+    let value = Value::Map(vec![(nan, 1.into()), (f64::NAN.into(), 2.into())]);
+    assert!(matches!(
+        value.to_canonical_vec(),
+        Err(CanonicalError::DuplicateKey(..))
+    ));
+
+    let value = Value::Array(vec![1.into(), Value::Array(vec![Value::Simple(24)])]);
+    let error = value.to_canonical_vec().unwrap_err();
+    assert_eq!(error.to_string(), "unrepresentable value at $[1][0]");
+
+    let key = Value::Array(vec![Value::Simple(31)]);
+    let value = Value::Map(vec![(key, 1.into())]);
+    let error = value.to_canonical_vec().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unrepresentable value at ${[simple(31)]}"
+    );
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::Infallible;
+
+use ciborium::{
+    cbor,
+    de::{from_buf_reader, from_reader},
+    ser::into_writer,
+    value::Value,
+};
+use ciborium_io::{FnWrite, IterRead, Slices};
+
+fn value() -> Value {
+    cbor!({
+        "name" => "chunky",
+        "bytes" => Value::Bytes(vec![1, 2, 3, 4]),
+        "items" => [1, -1000, 1.5, null, [true, false]],
+    })
+    .unwrap()
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn split() {
+    let value = value();
+    let bytes = encode(&value);
+
+    for i in 0..=bytes.len() {
+        let chunks = vec![bytes[..i].to_vec(), bytes[i..].to_vec()];
+        let reader = IterRead::new(chunks.into_iter().map(Ok::<_, Infallible>));
+        let decoded: Value = from_reader(reader).unwrap();
+        assert_eq!(decoded, value, "split at {}", i);
+    }
+}
+
+#[test]
+fn slices() {
+    let value = value();
+    let bytes = encode(&value);
+
+    for i in 0..=bytes.len() {
+        for j in i..=bytes.len() {
+            let reader = Slices::new([&bytes[..i], &bytes[i..j], &bytes[j..]]);
+            let decoded: Value = from_buf_reader(reader).unwrap();
+            assert_eq!(decoded, value, "split at {} and {}", i, j);
+        }
+    }
+}
+
+#[test]
+fn bytewise() {
+    let value = value();
+    let bytes = encode(&value);
+
+    let reader = IterRead::new(bytes.iter().map(|b| Ok::<_, Infallible>([*b])));
+    let decoded: Value = from_reader(reader).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn truncated() {
+    let bytes = encode(&value());
+
+    for i in 0..bytes.len() {
+        let reader = IterRead::new(Some(Ok::<_, Infallible>(&bytes[..i])));
+        match from_reader::<Value, _>(reader) {
+            Err(ciborium::de::Error::UnexpectedEof { offset }) => assert!(offset <= i),
+            x => panic!("unexpected result at {}: {:?}", i, x),
+        }
+    }
+}
+
+#[test]
+fn frames() {
+    let value = value();
+    let mut frames = Vec::new();
+
+    let writer = FnWrite::new(|data: &[u8]| {
+        frames.push(data.to_vec());
+        Ok::<_, Infallible>(())
+    });
+
+    into_writer(&value, writer).unwrap();
+    assert!(frames.len() > 1);
+    assert_eq!(frames.concat(), encode(&value));
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+//! Structural errors are reported as such, and never as errors of the reader.
+//!
+//! Each input is read through a `std::io::Read` adapter as well as from a
+//! slice, so that a failure inside the adapter would show up as `Error::Io`.
+
+use std::io::{BufReader, ErrorKind, Read};
+
+use ciborium::de::{from_reader, Error, LimitKind};
+use ciborium::value::{Value, ValueRef};
+
+/// A reader which fails after the bytes it was given
+struct Failing<'a>(&'a [u8]);
+
+impl Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf)? {
+            0 => Err(ErrorKind::ConnectionReset.into()),
+            n => Ok(n),
+        }
+    }
+}
+
+/// Decodes the input in every way, which must fail alike
+fn errors(bytes: &[u8]) -> Vec<Error<std::io::Error>> {
+    let adapter = BufReader::with_capacity(3, bytes.chain(&[][..]));
+
+    let errors = vec![
+        from_reader::<Value, _>(bytes).unwrap_err(),
+        from_reader::<Value, _>(adapter).unwrap_err(),
+        Value::from_reader(BufReader::new(bytes)).unwrap_err(),
+    ];
+
+    // A slice has its own reader error, which a structural error never is.
+    let borrowed = ValueRef::from_slice(bytes).unwrap_err();
+    assert!(borrowed.as_io().is_none(), "{:?}", borrowed);
+
+    errors
+}
+
+#[test]
+fn depth() {
+    let bytes = [0x81; 1024];
+
+    for error in errors(&bytes) {
+        match error {
+            Error::LimitExceeded {
+                kind: LimitKind::Depth,
+                ..
+            } => (),
+            e => panic!("incorrect error: {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn truncated() {
+    // [1, "ab"], without its last byte, which fails the read of the body
+    let bytes = [0x82, 0x01, 0x62, 0x61];
+
+    for error in errors(&bytes) {
+        match error {
+            Error::UnexpectedEof { offset: 3 } => (),
+            e => panic!("incorrect error: {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn invalid_utf8() {
+    // [1, "\xff"]
+    let bytes = [0x82, 0x01, 0x61, 0xff];
+
+    for error in errors(&bytes) {
+        match error {
+            Error::InvalidUtf8 {
+                chunk: 0,
+                offset: 2,
+            } => (),
+            e => panic!("incorrect error: {:?}", e),
+        }
+    }
+
+    let error = from_reader::<String, _>(&bytes[2..]).unwrap_err();
+    assert!(
+        matches!(
+            error,
+            Error::InvalidUtf8 {
+                chunk: 0,
+                offset: 0
+            }
+        ),
+        "{:?}",
+        error
+    );
+    assert_eq!(error.kind(), ciborium::de::ErrorKind::Syntax);
+}
+
+#[test]
+fn reader() {
+    // The reader fails in the middle of `[1, "ab"]`.
+    let bytes = [0x82, 0x01, 0x62];
+
+    let error = from_reader::<Value, _>(Failing(&bytes)).unwrap_err();
+    match error {
+        Error::Io(e) => assert_eq!(e.kind(), ErrorKind::ConnectionReset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    let error = Value::from_reader(Failing(&bytes)).unwrap_err();
+    match error {
+        Error::Io(e) => assert_eq!(e.kind(), ErrorKind::ConnectionReset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
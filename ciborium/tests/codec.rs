@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
+#![allow(clippy::excessive_precision)]
+
 extern crate std;
 
 use std::collections::{BTreeMap, HashMap};
@@ -202,33 +204,34 @@ macro_rules! map {
     case(-4.0f64, val!(-4.0f64), "f9c400", false, Float::from),
     case(-4.1f32, val!(-4.1f32), "fac0833333", false, Float::from), // Not In RFC
     case(-4.1f64, val!(-4.1f64), "fbc010666666666666", false, Float::from),
-    case(core::f32::INFINITY, val!(core::f32::INFINITY), "f97c00", false, Float::from),
-    case(core::f64::INFINITY, val!(core::f64::INFINITY), "f97c00", false, Float::from),
-    case(core::f32::INFINITY, val!(core::f32::INFINITY), "fa7f800000", true, Float::from),
-    case(core::f64::INFINITY, val!(core::f64::INFINITY), "fa7f800000", true, Float::from),
-    case(core::f32::INFINITY, val!(core::f32::INFINITY), "fb7ff0000000000000", true, Float::from),
-    case(core::f64::INFINITY, val!(core::f64::INFINITY), "fb7ff0000000000000", true, Float::from),
-    case(-core::f32::INFINITY, val!(-core::f32::INFINITY), "f9fc00", false, Float::from),
-    case(-core::f64::INFINITY, val!(-core::f64::INFINITY), "f9fc00", false, Float::from),
-    case(-core::f32::INFINITY, val!(-core::f32::INFINITY), "faff800000", true, Float::from),
-    case(-core::f64::INFINITY, val!(-core::f64::INFINITY), "faff800000", true, Float::from),
-    case(-core::f32::INFINITY, val!(-core::f32::INFINITY), "fbfff0000000000000", true, Float::from),
-    case(-core::f64::INFINITY, val!(-core::f64::INFINITY), "fbfff0000000000000", true, Float::from),
-    case(core::f32::NAN, val!(core::f32::NAN), "f97e00", false, Float::from),
-    case(core::f64::NAN, val!(core::f64::NAN), "f97e00", false, Float::from),
-    case(core::f32::NAN, val!(core::f32::NAN), "fa7fc00000", true, Float::from),
-    case(core::f64::NAN, val!(core::f64::NAN), "fa7fc00000", true, Float::from),
-    case(core::f32::NAN, val!(core::f32::NAN), "fb7ff8000000000000", true, Float::from),
-    case(core::f64::NAN, val!(core::f64::NAN), "fb7ff8000000000000", true, Float::from),
-    case(-core::f32::NAN, val!(-core::f32::NAN), "f9fe00", false, Float::from),            // Not In RFC
-    case(-core::f64::NAN, val!(-core::f64::NAN), "f9fe00", false, Float::from),            // Not In RFC
-    case(-core::f32::NAN, val!(-core::f32::NAN), "faffc00000", true, Float::from),         // Not In RFC
-    case(-core::f64::NAN, val!(-core::f64::NAN), "faffc00000", true, Float::from),         // Not In RFC
-    case(-core::f32::NAN, val!(-core::f32::NAN), "fbfff8000000000000", true, Float::from), // Not In RFC
-    case(-core::f64::NAN, val!(-core::f64::NAN), "fbfff8000000000000", true, Float::from), // Not In RFC
+    case(f32::INFINITY, val!(f32::INFINITY), "f97c00", false, Float::from),
+    case(f64::INFINITY, val!(f64::INFINITY), "f97c00", false, Float::from),
+    case(f32::INFINITY, val!(f32::INFINITY), "fa7f800000", true, Float::from),
+    case(f64::INFINITY, val!(f64::INFINITY), "fa7f800000", true, Float::from),
+    case(f32::INFINITY, val!(f32::INFINITY), "fb7ff0000000000000", true, Float::from),
+    case(f64::INFINITY, val!(f64::INFINITY), "fb7ff0000000000000", true, Float::from),
+    case(-f32::INFINITY, val!(-f32::INFINITY), "f9fc00", false, Float::from),
+    case(-f64::INFINITY, val!(-f64::INFINITY), "f9fc00", false, Float::from),
+    case(-f32::INFINITY, val!(-f32::INFINITY), "faff800000", true, Float::from),
+    case(-f64::INFINITY, val!(-f64::INFINITY), "faff800000", true, Float::from),
+    case(-f32::INFINITY, val!(-f32::INFINITY), "fbfff0000000000000", true, Float::from),
+    case(-f64::INFINITY, val!(-f64::INFINITY), "fbfff0000000000000", true, Float::from),
+    case(f32::NAN, val!(f32::NAN), "f97e00", false, Float::from),
+    case(f64::NAN, val!(f64::NAN), "f97e00", false, Float::from),
+    case(f32::NAN, val!(f32::NAN), "fa7fc00000", true, Float::from),
+    case(f64::NAN, val!(f64::NAN), "fa7fc00000", true, Float::from),
+    case(f32::NAN, val!(f32::NAN), "fb7ff8000000000000", true, Float::from),
+    case(f64::NAN, val!(f64::NAN), "fb7ff8000000000000", true, Float::from),
+    case(-f32::NAN, val!(-f32::NAN), "f9fe00", false, Float::from),            // Not In RFC
+    case(-f64::NAN, val!(-f64::NAN), "f9fe00", false, Float::from),            // Not In RFC
+    case(-f32::NAN, val!(-f32::NAN), "faffc00000", true, Float::from),         // Not In RFC
+    case(-f64::NAN, val!(-f64::NAN), "faffc00000", true, Float::from),         // Not In RFC
+    case(-f32::NAN, val!(-f32::NAN), "fbfff8000000000000", true, Float::from), // Not In RFC
+    case(-f64::NAN, val!(-f64::NAN), "fbfff8000000000000", true, Float::from), // Not In RFC
     case(false, val!(false), "f4", false, same),
     case(true, val!(true), "f5", false, same),
     case(Value::Null, Value::Null, "f6", false, same),
+    case(Value::Undefined, Value::Undefined, "f7", false, same),
     case(hex!(""), val!(&b""[..]), "40", false, same),
     case(hex!("01020304"), val!(&b"\x01\x02\x03\x04"[..]), "4401020304", false, same),
     case(hex!("0102030405"), val!(&b"\x01\x02\x03\x04\x05"[..]), "5f42010243030405ff", true, same),
@@ -254,8 +257,8 @@ macro_rules! map {
     case(cbor!([1, [2, 3], [4, 5]]).unwrap(), cbor!([1, [2, 3], [4, 5]]).unwrap(), "83019f0203ff820405", true, same),
     case((1..=25).collect::<Vec<u8>>(), (1..=25).map(|x| x.into()).collect::<Vec<Value>>().into(), "98190102030405060708090a0b0c0d0e0f101112131415161718181819", false, same),
     case((1..=25).collect::<Vec<u8>>(), (1..=25).map(|x| x.into()).collect::<Vec<Value>>().into(), "9f0102030405060708090a0b0c0d0e0f101112131415161718181819ff", true, same),
-    case(HashMap::<Value, Value>::new(), Value::Map(vec![].into()), "a0", false, same),
-    case(BTreeMap::<Value, Value>::new(), Value::Map(vec![].into()), "a0", false, same),
+    case(HashMap::<Value, Value>::new(), Value::Map(vec![]), "a0", false, same),
+    case(BTreeMap::<Value, Value>::new(), Value::Map(vec![]), "a0", false, same),
     case(map!{1 => 2, 3 => 4}, cbor!({1 => 2, 3 => 4}).unwrap(), "a201020304", false, same),
     case(cbor!({"a" => 1, "b" => [2, 3]}).unwrap(), cbor!({"a" => 1, "b" => [2, 3]}).unwrap(), "a26161016162820203", false, same),
     case(cbor!({"a" => 1, "b" => [2, 3]}).unwrap(), cbor!({"a" => 1, "b" => [2, 3]}).unwrap(), "bf61610161629f0203ffff", true, same),
@@ -264,6 +267,7 @@ macro_rules! map {
     case(cbor!({"Fun" => true, "Amt" => -2}).unwrap(), cbor!({"Fun" => true, "Amt" => -2}).unwrap(), "bf6346756ef563416d7421ff", true, same),
     case(map_big(), vmap_big(), "a56161614161626142616361436164614461656145", false, same),
     case(Option::<u8>::None, Value::Null, "f6", false, same), // Not In RFC
+    case(Option::<u8>::None, Value::Undefined, "f7", true, same), // Not In RFC
     case(Option::Some(7u8), val!(7u8), "07", false, same), // Not In RFC
     case((), Value::Null, "f6", false, same), // Not In RFC
     case(UnitStruct, Value::Null, "f6", false, same), // Not In RFC
@@ -295,6 +299,11 @@ fn codec<'de, T: Serialize + Clone, V: Debug + Eq + Deserialize<'de>, F: Fn(T) -
         eprintln!("{:x?} == {:x?}", bytes, encoded);
         assert_eq!(bytes, encoded);
 
+        let mut encoded = Vec::new();
+        value.into_writer(&mut encoded).unwrap();
+        eprintln!("{:x?} == {:x?}", bytes, encoded);
+        assert_eq!(bytes, encoded);
+
         let encoded = Value::serialized(&input).unwrap();
         eprintln!("{:x?} == {:x?}", &value, &encoded);
         assert_eq!(&value, &encoded);
@@ -309,6 +318,10 @@ fn codec<'de, T: Serialize + Clone, V: Debug + Eq + Deserialize<'de>, F: Fn(T) -
     eprintln!("{:x?} == {:x?}", &value, &decoded);
     assert_eq!(value, decoded);
 
+    let decoded = Value::from_reader(&bytes[..]).unwrap();
+    eprintln!("{:x?} == {:x?}", &value, &decoded);
+    assert_eq!(value, decoded);
+
     let decoded: V = value.deserialized().unwrap();
     let answer = equality(input);
     eprintln!("{:x?} == {:x?}", answer, decoded);
@@ -363,3 +376,100 @@ enum Enum {
     Tuple(u8, u16),
     Struct { first: u8, second: u16 },
 }
+
+#[test]
+fn large() {
+    let value = Value::Array(
+        (0..10_000u64)
+            .map(|i| {
+                Value::Map(vec![
+                    (Value::Text("id".into()), Value::from(i)),
+                    (Value::Text("neg".into()), Value::from(-(i as i64))),
+                    (Value::Text("big".into()), Value::from(u128::from(i) << 64)),
+                    (Value::Text("float".into()), Value::from(i as f64 / 3.0)),
+                    (
+                        Value::Text("bytes".into()),
+                        Value::Bytes(i.to_be_bytes().into()),
+                    ),
+                    (
+                        Value::Tag(i, Value::Null.into()),
+                        Value::Simple(i as u8 | 0x80),
+                    ),
+                ])
+            })
+            .collect(),
+    );
+
+    let mut direct = Vec::new();
+    value.into_writer(&mut direct).unwrap();
+
+    let mut serde = Vec::new();
+    into_writer(&value, &mut serde).unwrap();
+    assert_eq!(direct, serde);
+
+    let serde: Value = from_reader(&direct[..]).unwrap();
+    assert_eq!(value, serde);
+
+    let direct = Value::from_reader(&direct[..]).unwrap();
+    assert_eq!(value, direct);
+}
+
+#[rstest(bytes, value,
+    // Chunked bignums of up to 16 bytes are integers...
+    case("c25fff", Value::from(0)),
+    case("c35f 4101 40 4102 ff", Value::from(-0x0102 - 1)),
+    case("c25f 48 0100000000000000 48 0000000000000000 ff", Value::from(1u128 << 120)),
+    // ...and longer ones are tagged bytes, whichever way they are decoded.
+    case(
+        "c25f 48 0100000000000000 49 000000000000000000 ff",
+        Value::Tag(2, Value::Bytes([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0].into()).into())
+    ),
+    // So are negative ones below i128::MIN, chunked or not.
+    case(
+        "c3 50 80000000000000000000000000000000",
+        Value::Tag(3, Value::Bytes([0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0].into()).into())
+    ),
+    case(
+        "c35f 48 ff00000000000000 48 0000000000000001 ff",
+        Value::Tag(3, Value::Bytes([0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1].into()).into())
+    ),
+    // The smallest one which fits is an integer.
+    case("c3 50 7fffffffffffffffffffffffffffffff", Value::from(i128::MIN)),
+)]
+fn chunked_bignum(bytes: &str, value: Value) {
+    let bytes = hex::decode(bytes.replace(' ', "")).unwrap();
+
+    let serde: Value = from_reader(&bytes[..]).unwrap();
+    let direct = Value::from_reader(&bytes[..]).unwrap();
+    assert_eq!(serde, value);
+    assert_eq!(direct, serde);
+
+    // The value can be encoded and decoded again, either way.
+    let mut encoded = Vec::new();
+    into_writer(&value, &mut encoded).unwrap();
+    assert_eq!(from_reader::<Value, _>(&encoded[..]).unwrap(), value);
+    assert_eq!(Value::from_reader(&encoded[..]).unwrap(), value);
+}
+
+#[rstest(
+    bytes,
+    offset,
+    case("ff", 0),
+    case("8201ff", 2),
+    case("a1ff", 1),
+    case("c6ff", 1)
+)]
+fn stray_break(bytes: &str, offset: usize) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    // Both decoders report a break where an item should be the same way.
+    let serde = from_reader::<Value, _>(&bytes[..]).unwrap_err();
+    let direct = Value::from_reader(&bytes[..]).unwrap_err();
+
+    for error in [serde, direct] {
+        match error {
+            ciborium::de::Error::UnexpectedBreak { offset: x } => assert_eq!(x, offset),
+            e => panic!("incorrect error: {:?}", e),
+        }
+    }
+}
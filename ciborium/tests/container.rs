@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use ciborium::de::{from_reader, Error, ErrorKind};
+use ciborium::value::Value;
+use rstest::rstest;
+use serde::de::DeserializeOwned;
+
+fn error<T: DeserializeOwned + Debug>(bytes: &str) -> Error<std::io::Error> {
+    let bytes = hex::decode(bytes).unwrap();
+    from_reader::<T, _>(&bytes[..]).unwrap_err()
+}
+
+fn counts(error: Error<std::io::Error>) -> (&'static str, usize, usize, usize) {
+    match error {
+        Error::ContainerUnderflow {
+            offset,
+            declared,
+            actual,
+        } => ("underflow", offset, declared, actual),
+
+        Error::ContainerOverflow {
+            offset,
+            declared,
+            actual,
+        } => ("overflow", offset, declared, actual),
+
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(actual, expected,
+    // An array or map which the outer break ends one item early
+    case(counts(error::<Value>("9f8201ff")), ("underflow", 1, 2, 1)),
+    case(counts(error::<Value>("9fa30102ff")), ("underflow", 1, 3, 1)),
+    case(counts(error::<Vec<BTreeMap<u8, u8>>>("9fa50101020203030404ff")), ("underflow", 1, 5, 4)),
+
+    // A map which ends before the value of an entry
+    case(counts(error::<Value>("9fa101ff")), ("underflow", 1, 1, 0)),
+
+    // Deeper nesting, where the innermost container is reported
+    case(counts(error::<Value>("bf6161a20102ff")), ("underflow", 3, 2, 1)),
+    case(counts(error::<Value>("bf0182830102ff")), ("underflow", 3, 3, 2)),
+    case(counts(error::<Value>("9f9f8300ffff")), ("underflow", 2, 3, 1)),
+
+    // An array with one item more than the type takes
+    case(counts(error::<(u8, u8)>("83010203")), ("overflow", 0, 3, 2)),
+    case(counts(error::<Vec<(u8, u8)>>("8283010203820405")), ("overflow", 1, 3, 2)),
+    case(counts(error::<Vec<Vec<(u8,)>>>("8181820102")), ("overflow", 2, 2, 1)),
+)]
+fn snapshot(actual: (&str, usize, usize, usize), expected: (&str, usize, usize, usize)) {
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn outside() {
+    // Without an indefinite-length container around it, the break is stray.
+    match error::<Value>("8201ff") {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 2),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // A tag without an item does not cut the array short.
+    match error::<Value>("9f81c1ff") {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 3),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn exact() {
+    let bytes = hex::decode("9f820102a10304ff").unwrap();
+    let value: Value = from_reader(&bytes[..]).unwrap();
+    let array = Value::Array(vec![1.into(), 2.into()]);
+    let map = Value::Map(vec![(3.into(), 4.into())]);
+    assert_eq!(value, Value::Array(vec![array, map]));
+
+    let bytes = hex::decode("820102").unwrap();
+    let pair: (u8, u8) = from_reader(&bytes[..]).unwrap();
+    assert_eq!(pair, (1, 2));
+}
+
+#[test]
+fn kind() {
+    assert_eq!(error::<Value>("9f8201ff").kind(), ErrorKind::Syntax);
+    assert_eq!(error::<(u8, u8)>("83010203").kind(), ErrorKind::Semantic);
+}
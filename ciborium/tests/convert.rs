@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+use ciborium::value::{Integer, Value};
+use rstest::rstest;
+
+fn bigpos() -> Value {
+    Value::Tag(2, Value::Bytes(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]).into())
+}
+
+fn bigneg() -> Value {
+    Value::Tag(3, Value::Bytes(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]).into())
+}
+
+#[rstest(input, value,
+    case(Value::from(()), Value::Null),
+    case(Value::from(None::<u8>), Value::Null),
+    case(Value::from(Some(7u8)), Value::Integer(7.into())),
+    case(Value::from(Some("x")), Value::Text("x".into())),
+    case(Value::from(7usize), Value::Integer(7.into())),
+    case(Value::from(-7isize), Value::Integer((-7).into())),
+    case(Value::from(u128::from(u64::MAX) + 1), bigpos()),
+    case(Value::from(-i128::from(u64::MAX) - 2), bigneg()),
+    case(Value::from('a'), Value::Text("a".into())),
+    case(Value::from(&b"ab"[..]), Value::Bytes(vec![b'a', b'b'])),
+    case(Value::from([1u8, 2]), Value::Array(vec![1.into(), 2.into()])),
+    case(Value::from(["a", "b"]), Value::Array(vec!["a".into(), "b".into()])),
+    case(Value::from([None, Some(true)]), Value::Array(vec![Value::Null, true.into()])),
+    case(
+        Value::from(vec![("b", 2), ("a", 1)].into_iter().collect::<BTreeMap<_, _>>()),
+        Value::Map(vec![("a".into(), 1.into()), ("b".into(), 2.into())])
+    ),
+    case(
+        Value::from(vec![("a", 1)].into_iter().collect::<HashMap<_, _>>()),
+        Value::Map(vec![("a".into(), 1.into())])
+    ),
+)]
+fn from(input: Value, value: Value) {
+    assert_eq!(input, value);
+}
+
+fn ok<T: TryFrom<Value> + Debug + PartialEq>(value: Value, answer: T)
+where
+    T::Error: Debug,
+{
+    assert_eq!(T::try_from(value).unwrap(), answer);
+}
+
+#[rstest(value, check,
+    case(Value::from(7u8), |v| ok(v, 7u8)),
+    case(Value::from(-7i8), |v| ok(v, -7i64)),
+    case(Value::from(u64::MAX), |v| ok(v, u64::MAX)),
+    case(Value::from(7u8), |v| ok(v, Integer::from(7u8))),
+    case(Value::from(7u8), |v| ok(v, 7usize)),
+    case(Value::from(7u8), |v| ok(v, 7u128)),
+    case(bigpos(), |v| ok(v, u128::from(u64::MAX) + 1)),
+    case(bigneg(), |v| ok(v, -i128::from(u64::MAX) - 2)),
+    case(Value::from(1.5f64), |v| ok(v, 1.5f64)),
+    case(Value::from(1.5f64), |v| ok(v, 1.5f32)),
+    case(Value::from(true), |v| ok(v, true)),
+    case(Value::from("x"), |v| ok(v, String::from("x"))),
+    case(Value::from(&b"x"[..]), |v| ok(v, b"x".to_vec())),
+    case(Value::from([1u8]), |v| ok(v, vec![Value::from(1u8)])),
+    case(Value::Map(vec![]), |v| ok(v, Vec::<(Value, Value)>::new())),
+    case(Value::Null, |v| ok(v, ())),
+    case(Value::Undefined, |v| ok(v, ())),
+)]
+fn try_from_ok(value: Value, check: fn(Value)) {
+    check(value)
+}
+
+fn err<T: TryFrom<Value, Error = ciborium::value::TryFromValueError> + Debug>(
+    value: Value,
+    expected: &str,
+    found: &str,
+) {
+    let error = T::try_from(value.clone()).unwrap_err();
+    assert_eq!(error.expected(), expected);
+    assert_eq!(error.found(), found);
+    assert_eq!(
+        error.to_string(),
+        format!("expected {}, found {}", expected, found)
+    );
+    assert_eq!(error.into_value(), value);
+}
+
+#[rstest(value, check,
+    case(Value::from(256u16), |v| err::<u8>(v, "u8", "integer")),
+    case(Value::from(-1i8), |v| err::<u64>(v, "u64", "integer")),
+    case(Value::from("7"), |v| err::<i64>(v, "i64", "text")),
+    case(bigpos(), |v| err::<u64>(v, "u64", "tag")),
+    case(bigneg(), |v| err::<u128>(v, "u128", "tag")),
+    case(Value::from(1.1f64), |v| err::<f32>(v, "f32", "float")),
+    case(Value::from(1u8), |v| err::<f64>(v, "f64", "integer")),
+    case(Value::Null, |v| err::<bool>(v, "bool", "null")),
+    case(Value::from(&b"x"[..]), |v| err::<String>(v, "text", "bytes")),
+    case(Value::from("x"), |v| err::<Vec<u8>>(v, "bytes", "text")),
+    case(Value::Map(vec![]), |v| err::<Vec<Value>>(v, "array", "map")),
+    case(Value::Array(vec![]), |v| err::<Vec<(Value, Value)>>(v, "map", "array")),
+    case(Value::Simple(0), |v| err::<()>(v, "null", "simple")),
+)]
+fn try_from_err(value: Value, check: fn(Value)) {
+    check(value)
+}
+
+#[test]
+fn try_from_ref() {
+    let value = Value::from(300u16);
+    assert_eq!(u16::try_from(&value).unwrap(), 300);
+    assert_eq!(i128::try_from(&value).unwrap(), 300);
+    assert_eq!(Integer::try_from(&value).unwrap(), Integer::from(300u16));
+
+    let error = u8::try_from(&value).unwrap_err();
+    assert_eq!(error.to_string(), "expected u8, found integer");
+    assert_eq!(error.into_value(), value);
+
+    let error = Integer::try_from(&Value::Null).unwrap_err();
+    assert_eq!(error.to_string(), "expected integer, found null");
+}
+
+#[rstest(value, u64, i64, u128, i128,
+    case(Value::from(7u8), Some(7), Some(7), Some(7), Some(7)),
+    case(Value::from(-7i8), None, Some(-7), None, Some(-7)),
+    case(Value::from(u64::MAX), Some(u64::MAX), None, Some(u64::MAX.into()), Some(u64::MAX.into())),
+    case(bigpos(), None, None, Some(u128::from(u64::MAX) + 1), Some(i128::from(u64::MAX) + 1)),
+    case(bigneg(), None, None, None, Some(-i128::from(u64::MAX) - 2)),
+    case(Value::from(7.0f64), None, None, None, None),
+    case(Value::from("7"), None, None, None, None),
+)]
+fn accessors(
+    value: Value,
+    u64: Option<u64>,
+    i64: Option<i64>,
+    u128: Option<u128>,
+    i128: Option<i128>,
+) {
+    assert_eq!(value.as_u64(), u64);
+    assert_eq!(value.as_i64(), i64);
+    assert_eq!(value.as_u128(), u128);
+    assert_eq!(value.as_i128(), i128);
+}
+
+#[rstest(value, answer,
+    case(Value::from(30.0f64), Some(30)),
+    case(Value::from(-30.0f64), Some(-30)),
+    case(Value::from(-0.0f64), Some(0)),
+    case(Value::from(9007199254740991.0f64), Some(9007199254740991)),
+    case(Value::from(-9007199254740991.0f64), Some(-9007199254740991)),
+    // 2^53 + 1 is not representable; the literal rounds down to 2^53
+    case(Value::from(9007199254740993.0f64), Some(9007199254740992)),
+    case(Value::from(9007199254740994.0f64), Some(9007199254740994)),
+    case(Value::from(-170141183460469231731687303715884105728.0f64), Some(i128::MIN)),
+    case(Value::from(170141183460469231731687303715884105728.0f64), None),
+    case(Value::from(0.5f64), None),
+    case(Value::from(-1.5f64), None),
+    case(Value::from(1e-300f64), None),
+    case(Value::from(f64::INFINITY), None),
+    case(Value::from(f64::NAN), None),
+    case(Value::from(7u8), Some(7)),
+    case(bigneg(), Some(-i128::from(u64::MAX) - 2)),
+    case(Value::from("7"), None),
+)]
+fn integer_exact(value: Value, answer: Option<i128>) {
+    assert_eq!(value.as_integer_exact(), answer);
+}
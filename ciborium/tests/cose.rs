@@ -0,0 +1,249 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cose")]
+
+use std::fmt::Debug;
+
+use ciborium::{cbor, cose::*, de::from_reader, ser::into_writer, value::Value};
+use rstest::rstest;
+use serde::{de::DeserializeOwned, Serialize};
+
+// RFC 9052, appendix C.2.1
+const SIGN1: &str = "d28443a10126a10442313154546869732069732074686520636f6e74656e742e58408eb33e4ca31d1c465ab05aac34cc6b23d58fef5c083106c4d25a91aef0b0117e2af9a291aa32e14ab834dc56ed2a223444547e01f11d3b0916e5a4c345cacb36";
+const SIGN1_TBS: &str =
+    "846a5369676e61747572653143a101264054546869732069732074686520636f6e74656e742e";
+
+fn roundtrip<T: Serialize + DeserializeOwned + Debug + PartialEq>(item: &T, bytes: &[u8]) {
+    let mut encoded = Vec::new();
+    into_writer(item, &mut encoded).unwrap();
+    assert_eq!(encoded, bytes);
+
+    let decoded: T = from_reader(bytes).unwrap();
+    assert_eq!(&decoded, item);
+
+    // The tag is optional when reading.
+    if bytes[0] & 0xe0 == 0xc0 {
+        let offset = if bytes[0] == 0xd8 { 2 } else { 1 };
+        let decoded: T = from_reader(&bytes[offset..]).unwrap();
+        assert_eq!(&decoded, item);
+    }
+}
+
+fn header() -> Value {
+    cbor!({ 4 => Value::Bytes(b"11".to_vec()) }).unwrap()
+}
+
+#[test]
+fn sign1() {
+    let bytes = hex::decode(SIGN1).unwrap();
+
+    let message: CoseSign1 = from_reader(&bytes[..]).unwrap();
+    assert_eq!(message.protected, [0xa1, 0x01, 0x26]);
+    assert_eq!(message.unprotected, header());
+    assert_eq!(
+        message.payload.as_deref(),
+        Some(&b"This is the content."[..])
+    );
+    assert_eq!(message.signature.len(), 64);
+    roundtrip(&message, &bytes);
+
+    let tbs = message.sig_structure(&[], None).unwrap();
+    assert_eq!(hex::encode(tbs), SIGN1_TBS);
+
+    // A detached payload must be supplied to build the structure.
+    let detached = CoseSign1 {
+        payload: None,
+        ..message.clone()
+    };
+    assert_eq!(detached.sig_structure(&[], None), None);
+    assert_eq!(
+        hex::encode(
+            detached
+                .sig_structure(&[], Some(b"This is the content."))
+                .unwrap()
+        ),
+        SIGN1_TBS
+    );
+}
+
+#[test]
+fn sign() {
+    let message = CoseSign {
+        protected: vec![],
+        unprotected: cbor!({}).unwrap(),
+        payload: None,
+        signatures: vec![CoseSignature {
+            protected: vec![0xa1, 0x01, 0x26],
+            unprotected: header(),
+            signature: vec![1, 2],
+        }],
+    };
+
+    // 98([h'', {}, null, [[h'A10126', {4: h'3131'}, h'0102']]])
+    let bytes = hex::decode("d8628440a0f6818343a10126a104423131420102").unwrap();
+    roundtrip(&message, &bytes);
+
+    let tbs = message
+        .sig_structure(&message.signatures[0], b"aad", Some(b"x"))
+        .unwrap();
+    let answer = cbor!([
+        "Signature",
+        Value::Bytes(vec![]),
+        Value::Bytes(vec![0xa1, 0x01, 0x26]),
+        Value::Bytes(b"aad".to_vec()),
+        Value::Bytes(b"x".to_vec())
+    ])
+    .unwrap();
+    assert_eq!(Value::from_reader(&tbs[..]).unwrap(), answer);
+}
+
+#[test]
+fn mac() {
+    let mac0 = CoseMac0 {
+        protected: vec![0xa1, 0x01, 0x05],
+        unprotected: cbor!({}).unwrap(),
+        payload: Some(b"x".to_vec()),
+        tag: vec![7],
+    };
+
+    // 17([h'A10105', {}, h'78', h'07'])
+    roundtrip(&mac0, &hex::decode("d18443a10105a041784107").unwrap());
+
+    let tbs = mac0.mac_structure(&[], None).unwrap();
+    let answer = cbor!([
+        "MAC0",
+        Value::Bytes(vec![0xa1, 0x01, 0x05]),
+        Value::Bytes(vec![]),
+        Value::Bytes(b"x".to_vec())
+    ])
+    .unwrap();
+    assert_eq!(Value::from_reader(&tbs[..]).unwrap(), answer);
+
+    let mac = CoseMac {
+        protected: vec![],
+        unprotected: cbor!({}).unwrap(),
+        payload: None,
+        tag: vec![7],
+        recipients: vec![CoseRecipient {
+            protected: vec![],
+            unprotected: header(),
+            ciphertext: None,
+            recipients: vec![],
+        }],
+    };
+
+    // 97([h'', {}, null, h'07', [[h'', {4: h'3131'}, null]]])
+    roundtrip(
+        &mac,
+        &hex::decode("d8618540a0f64107818340a104423131f6").unwrap(),
+    );
+
+    let tbs = mac.mac_structure(&[], Some(b"y")).unwrap();
+    let answer = cbor!([
+        "MAC",
+        Value::Bytes(vec![]),
+        Value::Bytes(vec![]),
+        Value::Bytes(b"y".to_vec())
+    ])
+    .unwrap();
+    assert_eq!(Value::from_reader(&tbs[..]).unwrap(), answer);
+}
+
+#[test]
+fn encrypt() {
+    let encrypt0 = CoseEncrypt0 {
+        protected: vec![0xa1, 0x01, 0x01],
+        unprotected: header(),
+        ciphertext: Some(vec![9]),
+    };
+
+    // 16([h'A10101', {4: h'3131'}, h'09'])
+    roundtrip(
+        &encrypt0,
+        &hex::decode("d08343a10101a1044231314109").unwrap(),
+    );
+
+    let aad = encrypt0.enc_structure(b"a");
+    let answer = cbor!([
+        "Encrypt0",
+        Value::Bytes(vec![0xa1, 0x01, 0x01]),
+        Value::Bytes(b"a".to_vec())
+    ])
+    .unwrap();
+    assert_eq!(Value::from_reader(&aad[..]).unwrap(), answer);
+
+    let encrypt = CoseEncrypt {
+        protected: vec![],
+        unprotected: cbor!({}).unwrap(),
+        ciphertext: Some(vec![9]),
+        recipients: vec![CoseRecipient {
+            protected: vec![],
+            unprotected: cbor!({}).unwrap(),
+            ciphertext: Some(vec![8]),
+            recipients: vec![CoseRecipient {
+                protected: vec![],
+                unprotected: header(),
+                ciphertext: None,
+                recipients: vec![],
+            }],
+        }],
+    };
+
+    // 96([h'', {}, h'09', [[h'', {}, h'08', [[h'', {4: h'3131'}, null]]]]])
+    roundtrip(
+        &encrypt,
+        &hex::decode("d8608440a04109818440a04108818340a104423131f6").unwrap(),
+    );
+
+    let aad = encrypt.enc_structure(&[]);
+    let answer = cbor!(["Encrypt", Value::Bytes(vec![]), Value::Bytes(vec![])]).unwrap();
+    assert_eq!(Value::from_reader(&aad[..]).unwrap(), answer);
+}
+
+#[rstest(
+    bytes,
+    message,
+    case("d3 8440a0f640", "expected tag 18 (COSE_Sign1), found tag 19"),
+    case("d2 8340a0f6", "COSE_Sign1 must be an array of 4 items"),
+    case("d2 a0", "COSE_Sign1 must be an array of 4 items"),
+    case("d2 84f6a0f640", "COSE_Sign1 protected header must be a byte string"),
+    case("d2 844080f640", "COSE_Sign1 unprotected header must be a map"),
+    case("d2 8440a00040", "COSE_Sign1 payload must be a byte string or null"),
+    case("d2 8440a0f6f6", "COSE_Sign1 signature must be a byte string")
+)]
+fn invalid(bytes: &str, message: &str) {
+    let bytes = hex::decode(bytes.replace(' ', "")).unwrap();
+    match from_reader::<CoseSign1, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(
+    bytes,
+    message,
+    case(
+        "d8628440a0f681c38340a040",
+        "COSE_Signature must not be tagged, found tag 3"
+    ),
+    case("d8628440a0f6818240a0", "COSE_Signature must be an array of 3 items"),
+    case("d8628440a0f6a0", "COSE_Sign signatures must be an array")
+)]
+fn invalid_nested(bytes: &str, message: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    match from_reader::<CoseSign, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn invalid_recipient() {
+    let bytes = hex::decode("d8608440a0f6818240a0").unwrap();
+    match from_reader::<CoseEncrypt, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => {
+            assert_eq!(msg, "COSE_recipient must be an array of 3 to 4 items")
+        }
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,141 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "cwt")]
+
+use ciborium::{
+    cbor,
+    cwt::{Claims, Token},
+    de::{from_reader, Error},
+    ser::into_writer,
+    tag::Timestamp,
+    value::Value,
+};
+use rstest::rstest;
+
+// RFC 8392, appendix A.1
+const CLAIMS: &str = "a70175636f61703a2f2f61732e6578616d706c652e636f6d02656572696b77037818636f61703a2f2f6c696768742e6578616d706c652e636f6d041a5612aeb0051a5610d9f0061a5610d9f007420b71";
+
+fn example() -> Claims {
+    Claims {
+        iss: Some("coap://as.example.com".into()),
+        sub: Some("erikw".into()),
+        aud: Some("coap://light.example.com".into()),
+        exp: Timestamp::new(1444064944, 0),
+        nbf: Timestamp::new(1443944944, 0),
+        iat: Timestamp::new(1443944944, 0),
+        cti: Some(vec![0x0b, 0x71]),
+        other: Vec::new(),
+    }
+}
+
+#[test]
+fn rfc8392() {
+    let bytes = hex::decode(CLAIMS).unwrap();
+
+    let claims: Claims = from_reader(&bytes[..]).unwrap();
+    assert_eq!(claims, example());
+
+    let mut encoded = Vec::new();
+    into_writer(&claims, &mut encoded).unwrap();
+    assert_eq!(hex::encode(&encoded), CLAIMS);
+}
+
+#[test]
+fn other() {
+    let claims = Claims {
+        exp: Timestamp::new(1444064944, 500_000_000),
+        other: vec![
+            (Value::from(8), cbor!({ 1 => {} }).unwrap()),
+            (Value::from(-65537), Value::from("private")),
+            (Value::from("text"), Value::from(true)),
+        ],
+        ..Default::default()
+    };
+
+    let value = Value::serialized(&claims).unwrap();
+    assert_eq!(
+        value,
+        cbor!({
+            4 => 1444064944.5,
+            8 => { 1 => {} },
+            -65537 => "private",
+            "text" => true,
+        })
+        .unwrap()
+    );
+
+    assert_eq!(value.deserialized::<Claims>().unwrap(), claims);
+    assert_eq!(
+        Value::serialized(&Claims::default()).unwrap(),
+        Value::Map(vec![])
+    );
+}
+
+#[rstest(value, message,
+    case(cbor!([1]).unwrap(), "CWT claims must be a map"),
+    case(cbor!(tag!(61, {})).unwrap(), "CWT claims must be a map"),
+    case(cbor!({ 1 => 1 }).unwrap(), "CWT claim 1 must be text"),
+    case(cbor!({ 3 => ["a", "b"] }).unwrap(), "CWT claim 3 must be text"),
+    case(cbor!({ 4 => "1444064944" }).unwrap(), "CWT claim 4 must be a number"),
+    case(cbor!({ 5 => tag!(1, 1444064944) }).unwrap(), "CWT claim 5 must be a number"),
+    case(cbor!({ 6 => f64::NAN }).unwrap(), "CWT claim 6 must be a number"),
+    case(cbor!({ 6 => u64::MAX }).unwrap(), "CWT claim 6 out of range"),
+    case(cbor!({ 7 => "0b71" }).unwrap(), "CWT claim 7 must be a byte string"),
+    case(cbor!({ 2 => "a", 2 => "b" }).unwrap(), "duplicate CWT claim 2"),
+)]
+fn invalid(value: Value, message: &str) {
+    let mut bytes = Vec::new();
+    into_writer(&value, &mut bytes).unwrap();
+
+    match from_reader::<Claims, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn token() {
+    let bytes = hex::decode(CLAIMS).unwrap();
+
+    let mut encoded = Vec::new();
+    into_writer(&Token(example()), &mut encoded).unwrap();
+    assert_eq!(&encoded[..2], b"\xd8\x3d");
+    assert_eq!(encoded[2..], bytes[..]);
+
+    let token: Token<Claims> = from_reader(&encoded[..]).unwrap();
+    assert_eq!(token, Token(example()));
+
+    // The tag is optional when reading.
+    let token: Token<Claims> = from_reader(&bytes[..]).unwrap();
+    assert_eq!(token, Token(example()));
+
+    let value = cbor!(tag!(18, {})).unwrap();
+    let err = value.deserialized::<Token<Claims>>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Custom(\"expected tag 61 (CBOR Web Token), found tag 18\")"
+    );
+}
+
+#[cfg(feature = "cose")]
+#[test]
+fn signed() {
+    use ciborium::cose::CoseSign1;
+
+    let message = CoseSign1 {
+        protected: vec![0xa1, 0x01, 0x26],
+        unprotected: cbor!({ 4 => Value::Bytes(b"AsymmetricECDSA256".to_vec()) }).unwrap(),
+        payload: Some(hex::decode(CLAIMS).unwrap()),
+        signature: vec![0; 64],
+    };
+
+    let mut encoded = Vec::new();
+    into_writer(&Token(message.clone()), &mut encoded).unwrap();
+    assert_eq!(&encoded[..3], b"\xd8\x3d\xd2");
+
+    let token: Token<CoseSign1> = from_reader(&encoded[..]).unwrap();
+    assert_eq!(token.0, message);
+
+    let claims: Claims = from_reader(&token.0.payload.unwrap()[..]).unwrap();
+    assert_eq!(claims, example());
+}
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::TryFrom;
+
+use ciborium::{
+    cbor,
+    de::from_reader,
+    ser::into_writer,
+    tag::{DateString, DateTimeError, EpochDate, ExtendedTime, Timestamp},
+    value::Value,
+};
+use rstest::rstest;
+use serde::{de::DeserializeOwned, Serialize};
+
+fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(bytes: &str, item: T) {
+    let bytes = hex::decode(bytes).unwrap();
+    let decoded: T = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, item);
+
+    let mut encoded = Vec::new();
+    into_writer(&decoded, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+fn date(text: &str) -> DateString {
+    text.parse().unwrap()
+}
+
+// The dates are the examples from RFC 8943, section 2
+#[rstest(text, days, epoch, string,
+    case("1940-10-09", -10676, "d8643929b3", "d903ec6a313934302d31302d3039"),
+    case("1980-12-08", 3994, "d864190f9a", "d903ec6a313938302d31322d3038"),
+    case("1970-01-01", 0, "d86400", "d903ec6a313937302d30312d3031"),
+)]
+fn rfc8943(text: &str, days: i64, epoch: &str, string: &str) {
+    roundtrip(epoch, EpochDate(days));
+    roundtrip(string, date(text));
+
+    assert_eq!(EpochDate::from(date(text)), EpochDate(days));
+    assert_eq!(DateString::try_from(EpochDate(days)), Ok(date(text)));
+    assert_eq!(date(text).to_string(), text);
+}
+
+#[rstest(
+    text,
+    case(""),
+    case("1940-10"),
+    case("1940-1-09"),
+    case("1940-13-01"),
+    case("1940-10-32"),
+    case("1941-02-29"),
+    case("1940-10-09T00:00:00Z"),
+    case("-0001-01-01")
+)]
+fn malformed(text: &str) {
+    assert_eq!(text.parse::<DateString>(), Err(DateTimeError::Malformed));
+}
+
+#[test]
+fn ranges() {
+    assert_eq!(DateString::new(2000, 2, 29), Some(date("2000-02-29")));
+    assert_eq!(DateString::new(1900, 2, 29), None);
+    assert_eq!(DateString::new(10000, 1, 1), None);
+
+    let first = EpochDate::from(date("0000-01-01"));
+    let last = EpochDate::from(date("9999-12-31"));
+    assert_eq!(DateString::try_from(first), Ok(date("0000-01-01")));
+    assert_eq!(DateString::try_from(last), Ok(date("9999-12-31")));
+    assert_eq!(
+        DateString::try_from(EpochDate(first.0 - 1)),
+        Err(DateTimeError::OutOfRange)
+    );
+    assert_eq!(
+        DateString::try_from(EpochDate(last.0 + 1)),
+        Err(DateTimeError::OutOfRange)
+    );
+    assert_eq!(
+        DateString::try_from(EpochDate(i64::MAX)),
+        Err(DateTimeError::OutOfRange)
+    );
+}
+
+// The first case is the example from RFC 9581, section 3
+#[rstest(bytes, secs, nanos,
+    // 1002({1: 1363896240, -3: 500})
+    case("d903eaa2011a514b67b0221901f4", 1363896240, 500_000_000),
+
+    // 1002({1: 1363896240})
+    case("d903eaa1011a514b67b0", 1363896240, 0),
+
+    // 1002({1: -1, -6: 250})
+    case("d903eaa201202518fa", -1, 250_000),
+
+    // 1002({1: 0, -9: 1})
+    case("d903eaa201002801", 0, 1),
+)]
+fn extended(bytes: &str, secs: i64, nanos: u32) {
+    let time = ExtendedTime(Timestamp::new(secs, nanos).unwrap());
+    roundtrip(bytes, time);
+}
+
+#[test]
+fn extended_lenient() {
+    // Elective keys are ignored and the base may be a float.
+    let value = cbor!(tag!(1002, { 1 => 1.5, -10 => "ignored" })).unwrap();
+    let time: ExtendedTime = value.deserialized().unwrap();
+    assert_eq!(time, ExtendedTime(Timestamp::new(1, 500_000_000).unwrap()));
+}
+
+#[rstest(value, message,
+    case(cbor!(100).unwrap(), "expected tag 100 (epoch date), found an untagged value"),
+    case(cbor!(tag!(1, 100)).unwrap(), "expected tag 100 (epoch date), found tag 1"),
+    case(cbor!(tag!(100, "1940-10-09")).unwrap(), "invalid type: string \"1940-10-09\", expected an integer"),
+    case(cbor!(tag!(100, u64::MAX)).unwrap(), "epoch date out of range"),
+)]
+fn invalid_epoch(value: Value, message: &str) {
+    let error = value.deserialized::<EpochDate>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
+
+#[rstest(value, message,
+    case(cbor!("1940-10-09").unwrap(), "expected tag 1004 (date string), found an untagged value"),
+    case(cbor!(tag!(0, "1940-10-09")).unwrap(), "expected tag 1004 (date string), found tag 0"),
+    case(cbor!(tag!(1004, 19401009)).unwrap(), "invalid type: integer `19401009`, expected a string"),
+    case(cbor!(tag!(1004, "1940-10-32")).unwrap(), "malformed RFC 3339 full-date \"1940-10-32\""),
+)]
+fn invalid_string(value: Value, message: &str) {
+    let error = value.deserialized::<DateString>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
+
+#[rstest(value, message,
+    case(cbor!({ 1 => 0 }).unwrap(), "expected tag 1002 (extended time), found an untagged value"),
+    case(cbor!(tag!(1001, { 1 => 0 })).unwrap(), "expected tag 1002 (extended time), found tag 1001"),
+    case(cbor!(tag!(1002, [1, 0])).unwrap(), "invalid type: sequence, expected a map"),
+    case(cbor!(tag!(1002, { "1" => 0 })).unwrap(), "extended time keys must be integers"),
+    case(cbor!(tag!(1002, { -3 => 0 })).unwrap(), "extended time is missing key 1"),
+    case(cbor!(tag!(1002, { 1 => 0, 2 => 0 })).unwrap(), "unsupported critical key 2 in extended time"),
+    case(cbor!(tag!(1002, { 1 => 0, 0 => 0 })).unwrap(), "unsupported critical key 0 in extended time"),
+    case(cbor!(tag!(1002, { 1 => 0, -3 => 1000 })).unwrap(), "invalid fractional seconds under key -3 in extended time"),
+    case(cbor!(tag!(1002, { 1 => 0, -6 => -1 })).unwrap(), "invalid fractional seconds under key -6 in extended time"),
+    case(cbor!(tag!(1002, { 1 => 0, -3 => 1, -9 => 1 })).unwrap(), "extended time has more than one fractional seconds key"),
+    case(cbor!(tag!(1002, { 1 => 0.5, -3 => 1 })).unwrap(), "extended time cannot combine fractional seconds with a float"),
+    case(cbor!(tag!(1002, { 1 => "now" })).unwrap(), "invalid extended time base"),
+    case(cbor!(tag!(1002, { 1 => u64::MAX })).unwrap(), "extended time out of range"),
+)]
+fn invalid_extended(value: Value, message: &str) {
+    let error = value.deserialized::<ExtendedTime>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
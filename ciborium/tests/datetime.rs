@@ -0,0 +1,326 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use ciborium::{
+    cbor,
+    de::{from_reader, Error},
+    ser::into_writer,
+    tag::{DateTimeError, Timestamp, TimestampForm},
+    value::Value,
+};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[rstest(text, secs, nanos,
+    case("1970-01-01T00:00:00Z", 0, 0),
+    case("2013-03-21T20:04:00Z", 1363896240, 0),
+    case("2013-03-21t20:04:00z", 1363896240, 0),
+    case("2013-03-21T20:04:00.5Z", 1363896240, 500_000_000),
+    case("2013-03-21T20:04:00.123456789123Z", 1363896240, 123_456_789),
+    case("2013-03-21T22:04:00+02:00", 1363896240, 0),
+    case("2013-03-21T15:34:00-04:30", 1363896240, 0),
+    case("1969-12-31T23:59:59.25Z", -1, 250_000_000),
+    case("0000-01-01T00:00:00Z", -62167219200, 0),
+    case("9999-12-31T23:59:59Z", 253402300799, 0),
+    case("2000-02-29T00:00:00Z", 951782400, 0),
+    case("2016-12-31T23:59:60Z", 1483228800, 0),
+)]
+fn parse(text: &str, secs: i64, nanos: u32) {
+    let time: Timestamp = text.parse().unwrap();
+    assert_eq!((time.secs(), time.nanos()), (secs, nanos));
+
+    let value = Value::Tag(0, Box::new(text.into()));
+    assert_eq!(value.as_datetime(), Some(Ok(time)));
+}
+
+#[rstest(
+    text,
+    case(""),
+    case("2013-03-21"),
+    case("2013-03-21T20:04:00"),
+    case("2013-03-21 20:04:00Z"),
+    case("2013-13-21T20:04:00Z"),
+    case("2013-00-21T20:04:00Z"),
+    case("2013-02-29T20:04:00Z"),
+    case("1900-02-29T20:04:00Z"),
+    case("2013-03-21T24:00:00Z"),
+    case("2013-03-21T20:60:00Z"),
+    case("2013-03-21T20:04:61Z"),
+    case("2013-03-21T20:04:00.Z"),
+    case("2013-03-21T20:04:00+0200"),
+    case("2013-03-21T20:04:00Z "),
+    case("+2013-03-21T20:04:00Z"),
+    case("2013-3-21T20:04:00Z")
+)]
+fn malformed(text: &str) {
+    assert_eq!(text.parse::<Timestamp>(), Err(DateTimeError::Malformed));
+
+    let value = Value::Tag(0, Box::new(text.into()));
+    assert_eq!(value.as_datetime(), Some(Err(DateTimeError::Malformed)));
+}
+
+#[rstest(secs, nanos, text,
+    case(0, 0, "1970-01-01T00:00:00Z"),
+    case(1363896240, 500_000_000, "2013-03-21T20:04:00.5Z"),
+    case(1363896240, 1, "2013-03-21T20:04:00.000000001Z"),
+    case(-1, 250_000_000, "1969-12-31T23:59:59.25Z"),
+    case(951782400, 0, "2000-02-29T00:00:00Z"),
+    case(-62167219200, 0, "0000-01-01T00:00:00Z"),
+)]
+fn format(secs: i64, nanos: u32, text: &str) {
+    let time = Timestamp::new(secs, nanos).unwrap();
+    assert_eq!(time.to_rfc3339().unwrap(), text);
+    assert_eq!(text.parse(), Ok(time));
+    assert_eq!(
+        Value::from_datetime_string(time).unwrap(),
+        Value::Tag(0, Box::new(text.into()))
+    );
+}
+
+#[test]
+fn format_range() {
+    let early = Timestamp::new(-62167219201, 0).unwrap();
+    assert_eq!(early.to_rfc3339(), Err(DateTimeError::OutOfRange));
+
+    let late = Timestamp::new(253402300800, 0).unwrap();
+    assert_eq!(
+        Value::from_datetime_string(late),
+        Err(DateTimeError::OutOfRange)
+    );
+
+    assert_eq!(Timestamp::new(0, 1_000_000_000), None);
+}
+
+#[rstest(value, answer,
+    case(cbor!(tag!(1, 1363896240)).unwrap(), Ok((1363896240, 0))),
+    case(cbor!(tag!(1, 1363896240.5)).unwrap(), Ok((1363896240, 500_000_000))),
+    case(cbor!(tag!(1, -1)).unwrap(), Ok((-1, 0))),
+    case(cbor!(tag!(1, -1.5)).unwrap(), Ok((-2, 500_000_000))),
+    case(cbor!(tag!(1, 0.9999999999)).unwrap(), Ok((1, 0))),
+    case(cbor!(tag!(1, u64::MAX)).unwrap(), Err(DateTimeError::OutOfRange)),
+    case(cbor!(tag!(1, 1e19)).unwrap(), Err(DateTimeError::OutOfRange)),
+    case(cbor!(tag!(1, f64::NAN)).unwrap(), Err(DateTimeError::OutOfRange)),
+    case(cbor!(tag!(1, f64::INFINITY)).unwrap(), Err(DateTimeError::OutOfRange)),
+    case(cbor!(tag!(1, "0")).unwrap(), Err(DateTimeError::InvalidContent)),
+    case(cbor!(tag!(0, 0)).unwrap(), Err(DateTimeError::InvalidContent))
+)]
+fn epoch(value: Value, answer: Result<(i64, u32), DateTimeError>) {
+    let time = value.as_datetime().unwrap();
+    assert_eq!(time.map(|t| (t.secs(), t.nanos())), answer);
+}
+
+#[rstest(
+    value,
+    case(cbor!(1363896240).unwrap()),
+    case(cbor!("2013-03-21T20:04:00Z").unwrap()),
+    case(cbor!(tag!(2, 1)).unwrap()),
+    case(cbor!(tag!(32, "2013-03-21T20:04:00Z")).unwrap())
+)]
+fn not_datetime(value: Value) {
+    assert_eq!(value.as_datetime(), None);
+}
+
+#[test]
+fn from_epoch_time() {
+    let whole = Timestamp::new(1363896240, 0).unwrap();
+    assert_eq!(
+        Value::from_epoch_time(whole),
+        cbor!(tag!(1, 1363896240)).unwrap()
+    );
+
+    let half = Timestamp::new(-2, 500_000_000).unwrap();
+    assert_eq!(Value::from_epoch_time(half), cbor!(tag!(1, -1.5)).unwrap());
+    assert_eq!(Value::from_epoch_time(half).as_datetime(), Some(Ok(half)));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    #[serde(with = "ciborium::tag::datetime_string")]
+    at: Timestamp,
+}
+
+#[rstest(bytes, secs, nanos,
+    // 0("2013-03-21T20:04:00Z")
+    case("c074323031332d30332d32315432303a30343a30305a", 1363896240, 0),
+    // 0("2013-03-21T22:04:00.5+02:00")
+    case("c0781b323031332d30332d32315432323a30343a30302e352b30323a3030", 1363896240, 500_000_000),
+)]
+fn datetime_string(bytes: &str, secs: i64, nanos: u32) {
+    let bytes = hex::decode(bytes).unwrap();
+    let at: Value = from_reader(&bytes[..]).unwrap();
+    let event: Event = cbor!({ "at" => at }).unwrap().deserialized().unwrap();
+    assert_eq!((event.at.secs(), event.at.nanos()), (secs, nanos));
+
+    let mut encoded = Vec::new();
+    into_writer(&event, &mut encoded).unwrap();
+    let decoded: Event = from_reader(&encoded[..]).unwrap();
+    assert_eq!(decoded, event);
+}
+
+#[rstest(value, message,
+    case(cbor!("2013-03-21T20:04:00Z").unwrap(), "expected tag 0 (date/time string), found untagged text"),
+    case(cbor!(tag!(1, "2013-03-21T20:04:00Z")).unwrap(), "expected tag 0 (date/time string), found tag 1"),
+    case(cbor!(tag!(0, "2013-03-21")).unwrap(), "invalid value: string \"2013-03-21\", expected an RFC 3339 date/time"),
+)]
+fn datetime_string_errors(value: Value, message: &str) {
+    let mut bytes = Vec::new();
+    into_writer(&cbor!({ "at" => value }).unwrap(), &mut bytes).unwrap();
+
+    match from_reader::<Event, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn datetime_string_range() {
+    let event = Event {
+        at: Timestamp::new(253402300800, 0).unwrap(),
+    };
+
+    assert!(into_writer(&event, Vec::new()).is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Epoch {
+    #[serde(with = "ciborium::tag::epoch_time")]
+    at: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SystemEpoch {
+    #[serde(with = "ciborium::tag::epoch_time")]
+    at: SystemTime,
+}
+
+#[rstest(value, secs, nanos,
+    case(cbor!(tag!(1, 1363896240)).unwrap(), 1363896240, 0),
+    case(cbor!(tag!(1, 1363896240.5)).unwrap(), 1363896240, 500_000_000),
+    case(cbor!(tag!(1, -1)).unwrap(), -1, 0),
+    case(cbor!(tag!(1, -0.25)).unwrap(), -1, 750_000_000),
+    case(cbor!(tag!(1, -1e10)).unwrap(), -10_000_000_000, 0),
+)]
+fn epoch_time(value: Value, secs: i64, nanos: u32) {
+    let epoch: Epoch = cbor!({ "at" => value }).unwrap().deserialized().unwrap();
+    assert_eq!((epoch.at.secs(), epoch.at.nanos()), (secs, nanos));
+
+    // Whole seconds are written as integers and others as floats.
+    let encoded = Value::serialized(&epoch).unwrap();
+    assert_eq!(
+        encoded,
+        cbor!({ "at" => Value::from_epoch_time(epoch.at) }).unwrap()
+    );
+    assert_eq!(encoded.deserialized::<Epoch>().unwrap(), epoch);
+
+    let system: SystemEpoch = encoded.deserialized().unwrap();
+    assert_eq!(Value::serialized(&system).unwrap(), encoded);
+}
+
+#[rstest(value, message,
+    case(cbor!(1363896240).unwrap(), "expected tag 1 (epoch time), found an untagged value"),
+    case(cbor!(tag!(0, 1363896240)).unwrap(), "expected tag 1 (epoch time), found tag 0"),
+    case(cbor!(tag!(1, "1363896240")).unwrap(), "invalid type: string \"1363896240\", expected an integer or a float"),
+    case(cbor!(tag!(1, f64::NAN)).unwrap(), "epoch time is NaN"),
+    case(cbor!(tag!(1, f64::NEG_INFINITY)).unwrap(), "epoch time is infinite"),
+    case(cbor!(tag!(1, 1e19)).unwrap(), "epoch time out of range"),
+    case(cbor!(tag!(1, u64::MAX)).unwrap(), "epoch time out of range"),
+)]
+fn epoch_time_errors(value: Value, message: &str) {
+    let mut bytes = Vec::new();
+    into_writer(&cbor!({ "at" => value }).unwrap(), &mut bytes).unwrap();
+
+    match from_reader::<Epoch, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[rstest(time, secs, nanos,
+    case(UNIX_EPOCH, 0, 0),
+    case(UNIX_EPOCH + Duration::new(1363896240, 5), 1363896240, 5),
+    case(UNIX_EPOCH - Duration::new(1, 0), -1, 0),
+    case(UNIX_EPOCH - Duration::new(0, 250_000_000), -1, 750_000_000),
+    case(UNIX_EPOCH - Duration::new(10, 1), -11, 999_999_999),
+)]
+fn system_time(time: SystemTime, secs: i64, nanos: u32) {
+    let timestamp = Timestamp::try_from(time).unwrap();
+    assert_eq!((timestamp.secs(), timestamp.nanos()), (secs, nanos));
+    assert_eq!(SystemTime::try_from(timestamp), Ok(time));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Either {
+    at: Timestamp,
+}
+
+#[rstest(value,
+    case(cbor!(tag!(0, "2013-03-21T20:04:00.5Z")).unwrap()),
+    case(cbor!(tag!(0, "2013-03-21T21:04:00.5+01:00")).unwrap()),
+    case(cbor!(tag!(1, 1363896240.5)).unwrap()),
+)]
+fn either(value: Value) {
+    let either: Either = cbor!({ "at" => value }).unwrap().deserialized().unwrap();
+    assert_eq!(either.at, Timestamp::new(1363896240, 500_000_000).unwrap());
+
+    let encoded = Value::serialized(&either).unwrap();
+    assert_eq!(encoded, cbor!({ "at" => tag!(1, 1363896240.5) }).unwrap());
+    assert_eq!(encoded.deserialized::<Either>().unwrap(), either);
+}
+
+#[rstest(secs, nanos, form, bytes,
+    // 1(1363896240)
+    case(1363896240, 0, TimestampForm::Epoch, "c11a514b67b0"),
+
+    // 1(1363896240.5)
+    case(1363896240, 500_000_000, TimestampForm::Epoch, "c1fb41d452d9ec200000"),
+
+    // 1(1363896240.0)
+    case(1363896240, 0, TimestampForm::EpochFloat, "c1fb41d452d9ec000000"),
+
+    // 0("2013-03-21T20:04:00Z")
+    case(1363896240, 0, TimestampForm::Text, "c074323031332d30332d32315432303a30343a30305a"),
+)]
+fn preferred(secs: i64, nanos: u32, form: TimestampForm, bytes: &str) {
+    let time = Timestamp::new(secs, nanos).unwrap();
+    let formatted = time.with_form(form);
+    assert_eq!((formatted.timestamp(), formatted.form()), (time, form));
+
+    let mut encoded = Vec::new();
+    into_writer(&formatted, &mut encoded).unwrap();
+    assert_eq!(hex::encode(&encoded), bytes);
+
+    let decoded: Timestamp = from_reader(&encoded[..]).unwrap();
+    assert_eq!(decoded, time);
+}
+
+#[rstest(value, message,
+    case(cbor!(1363896240).unwrap(), "expected tag 0 or 1 (date/time), found an untagged value"),
+    case(cbor!(tag!(1004, "2013-03-21")).unwrap(), "expected tag 0 or 1 (date/time), found tag 1004"),
+    case(cbor!(tag!(0, "2013-03-21")).unwrap(), "malformed RFC 3339 date/time \"2013-03-21\""),
+    case(cbor!(tag!(0, 1363896240)).unwrap(), "invalid type: integer `1363896240`, expected a string"),
+    case(cbor!(tag!(1, "1363896240")).unwrap(), "invalid type: string \"1363896240\", expected an integer or a float"),
+    case(cbor!(tag!(1, f64::NAN)).unwrap(), "epoch time is NaN"),
+    case(cbor!(tag!(1, f64::INFINITY)).unwrap(), "epoch time is infinite"),
+    case(cbor!(tag!(1, 1e19)).unwrap(), "epoch time out of range"),
+    case(cbor!(tag!(1, u64::MAX)).unwrap(), "epoch time out of range"),
+)]
+fn either_errors(value: Value, message: &str) {
+    let mut bytes = Vec::new();
+    into_writer(&cbor!({ "at" => value }).unwrap(), &mut bytes).unwrap();
+
+    match from_reader::<Either, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn form_range() {
+    let time = Timestamp::new(253402300800, 0).unwrap();
+    assert!(into_writer(&time.with_form(TimestampForm::Text), Vec::new()).is_err());
+    into_writer(&time, Vec::new()).unwrap();
+}
@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::de::{from_reader, Error, Options};
+use serde::Deserialize;
+
+/// A reader error which implements only `Debug`
+#[derive(Debug, PartialEq)]
+struct Empty;
+
+/// A reader over a slice whose error does not implement `std::error::Error`
+struct Slice<'a>(&'a [u8]);
+
+impl ciborium_io::Read for Slice<'_> {
+    type Error = Empty;
+
+    fn read_exact(&mut self, data: &mut [u8]) -> Result<(), Self::Error> {
+        if data.len() > self.0.len() {
+            return Err(Empty);
+        }
+
+        let (prefix, suffix) = self.0.split_at(data.len());
+        data.copy_from_slice(prefix);
+        self.0 = suffix;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Point {
+    x: u8,
+    y: u8,
+}
+
+#[test]
+fn reads() {
+    let point: Point = from_reader(Slice(&[0xa2, 0x61, b'x', 0x01, 0x61, b'y', 0x02])).unwrap();
+    assert_eq!(point, Point { x: 1, y: 2 });
+
+    let items: Vec<u8> = Options::new()
+        .max_items(Some(4))
+        .from_reader(Slice(&[0x83, 0x01, 0x02, 0x03]))
+        .unwrap();
+    assert_eq!(items, [1, 2, 3]);
+}
+
+#[test]
+fn io_error() {
+    match from_reader::<String, _>(Slice(&[0x65, b'h', b'e'])) {
+        Err(Error::Io(Empty)) => (),
+        e => panic!("expected an io error: {:?}", e),
+    }
+}
+
+#[test]
+fn semantic_error() {
+    // The error of the visitor is kept, as it is with any other reader.
+    match from_reader::<Point, _>(Slice(&[0xa1, 0x61, b'x', 0x01])) {
+        Err(Error::Semantic(None, msg)) => assert_eq!(msg, "missing field `y`"),
+        e => panic!("expected a semantic error: {:?}", e),
+    }
+}
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::convert::TryFrom;
+
+use ciborium::{
+    cbor,
+    de::from_reader,
+    ser::into_writer,
+    tag::{DecimalFraction, DecimalFractionError},
+    value::Value,
+};
+use rstest::rstest;
+
+fn decimal(exponent: i64, mantissa: i128) -> DecimalFraction {
+    DecimalFraction { exponent, mantissa }
+}
+
+#[rstest(text, answer, display,
+    case("0", decimal(0, 0), "0"),
+    case("-12.345", decimal(-3, -12345), "-12.345"),
+    case("+12.345", decimal(-3, 12345), "12.345"),
+    case("273.15", decimal(-2, 27315), "273.15"),
+    case("1.50", decimal(-2, 150), "1.50"),
+    case(".5", decimal(-1, 5), "0.5"),
+    case("5.", decimal(0, 5), "5"),
+    case("-0.001", decimal(-3, -1), "-0.001"),
+    case("1.5e-7", decimal(-8, 15), "0.00000015"),
+    case("12E3", decimal(3, 12), "12e3"),
+    case("1e-50", decimal(-50, 1), "1e-50"),
+    case(
+        "-170141183460469231731687303715884105728",
+        decimal(0, i128::MIN),
+        "-170141183460469231731687303715884105728"
+    ),
+)]
+fn parse(text: &str, answer: DecimalFraction, display: &str) {
+    let parsed: DecimalFraction = text.parse().unwrap();
+    assert_eq!(parsed, answer);
+    assert_eq!(parsed.to_string(), display);
+    assert_eq!(display.parse::<DecimalFraction>().unwrap(), parsed);
+}
+
+#[rstest(
+    text,
+    error,
+    case("", DecimalFractionError::Malformed),
+    case("-", DecimalFractionError::Malformed),
+    case(".", DecimalFractionError::Malformed),
+    case("1.2.3", DecimalFractionError::Malformed),
+    case("1e", DecimalFractionError::Malformed),
+    case("1e+", DecimalFractionError::Malformed),
+    case("1,5", DecimalFractionError::Malformed),
+    case(" 1", DecimalFractionError::Malformed),
+    case("0x10", DecimalFractionError::Malformed),
+    case(
+        "170141183460469231731687303715884105728",
+        DecimalFractionError::OutOfRange
+    ),
+    case("1e9223372036854775808", DecimalFractionError::OutOfRange),
+    case(".1e-9223372036854775808", DecimalFractionError::OutOfRange)
+)]
+fn malformed(text: &str, error: DecimalFractionError) {
+    assert_eq!(text.parse::<DecimalFraction>(), Err(error));
+}
+
+#[rstest(float, answer,
+    case(0.1, decimal(-1, 1)),
+    case(-273.15, decimal(-2, -27315)),
+    case(1e300, decimal(300, 1)),
+    case(5e-324, decimal(-324, 5)),
+)]
+fn floats(float: f64, answer: DecimalFraction) {
+    assert_eq!(DecimalFraction::try_from(float), Ok(answer));
+    assert_eq!(f64::from(answer), float);
+}
+
+#[test]
+fn float_lossy() {
+    assert_eq!(
+        DecimalFraction::try_from(f64::NAN),
+        Err(DecimalFractionError::OutOfRange)
+    );
+    assert_eq!(
+        DecimalFraction::try_from(f64::INFINITY),
+        Err(DecimalFractionError::OutOfRange)
+    );
+
+    assert_eq!(
+        f64::from(decimal(-20, 1234567890123456789012345)),
+        12345.678901234567
+    );
+    assert_eq!(f64::from(decimal(400, 1)), f64::INFINITY);
+}
+
+// The first example is from RFC 8949, section 3.4.4
+#[rstest(bytes, answer,
+    case("c48221196ab3", decimal(-2, 27315)),
+    case("c482201903e7", decimal(-1, 999)),
+    case("c48220c24c010000000000000000000000", decimal(-1, 1 << 88)),
+    case("c482213bffffffffffffffff", decimal(-2, -(1 << 64))),
+)]
+fn encoding(bytes: &str, answer: DecimalFraction) {
+    let bytes = hex::decode(bytes).unwrap();
+    let decoded: DecimalFraction = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, answer);
+
+    let mut encoded = Vec::new();
+    into_writer(&decoded, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+#[rstest(value, message,
+    case(cbor!([-2, 27315]).unwrap(), "expected tag 4 (decimal fraction), found an untagged value"),
+    case(cbor!(tag!(5, [-2, 27315])).unwrap(), "expected tag 4 (decimal fraction), found tag 5"),
+    case(cbor!(tag!(4, "27315e-2")).unwrap(), "invalid type: string \"27315e-2\", expected an array"),
+    case(cbor!(tag!(4, [-2])).unwrap(), "invalid length 1, expected two items"),
+    case(cbor!(tag!(4, [-2, 27315, 0])).unwrap(), "invalid length 3, expected two items"),
+    case(cbor!(tag!(4, [-2.0, 27315])).unwrap(), "invalid decimal fraction exponent"),
+    case(cbor!(tag!(4, [u64::MAX, 27315])).unwrap(), "invalid decimal fraction exponent"),
+    case(cbor!(tag!(4, [-2, 2.5])).unwrap(), "invalid decimal fraction mantissa"),
+)]
+fn invalid(value: Value, message: &str) {
+    let error = value.deserialized::<DecimalFraction>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
+
+#[test]
+fn bignum_mantissa() {
+    let big = Value::Tag(2, Value::Bytes(vec![0xff; 17]).into());
+    let value = Value::Tag(4, Value::Array(vec![0.into(), big]).into());
+    assert!(value.deserialized::<DecimalFraction>().is_err());
+
+    let big = Value::Tag(
+        3,
+        Value::Bytes(vec![0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]).into(),
+    );
+    let value = Value::Tag(4, Value::Array(vec![0.into(), big]).into());
+    assert_eq!(
+        value.deserialized::<DecimalFraction>().unwrap(),
+        decimal(0, -(1 << 64) - 1)
+    );
+}
@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    de::{Error, Options},
+    ser::into_writer,
+    tag::{Captured, Dispatcher, Required},
+    value::Value,
+};
+use rstest::rstest;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Record {
+    name: String,
+    when: Option<u64>,
+    id: Required<u32, 37>,
+}
+
+fn strict() -> Options {
+    Options::new().deny_unexpected_tags(true)
+}
+
+fn encode(value: Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(&value, &mut bytes).unwrap();
+    bytes
+}
+
+fn accept<T: DeserializeOwned + PartialEq + Debug>(value: Value, answer: T) {
+    let bytes = encode(value);
+    assert_eq!(strict().from_reader::<T, _>(&bytes[..]).unwrap(), answer);
+}
+
+fn reject<T: DeserializeOwned + Debug>(value: Value, tag: u64, offset: usize) {
+    let bytes = encode(value);
+
+    match strict().from_reader::<T, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(Some(x), msg) => {
+            assert_eq!(msg, format!("unexpected tag {}", tag));
+            assert_eq!(x, offset);
+        }
+
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // The same input is accepted when tags may be skipped.
+    Options::new().from_reader::<T, _>(&bytes[..]).unwrap();
+}
+
+#[rstest(value, tag, offset,
+    case(cbor!(tag!(0, "2013-03-21T20:04:00Z")).unwrap(), 0, 0),
+    case(cbor!({ "name" => tag!(0, "x"), "when" => null, "id" => tag!(37, 1) }).unwrap(), 0, 6),
+    case(cbor!({ "name" => "x", "when" => tag!(1, 5), "id" => tag!(37, 1) }).unwrap(), 1, 13),
+    case(cbor!({ "name" => "x", "when" => null, "id" => tag!(37, tag!(1000, 1)) }).unwrap(), 1000, 19),
+)]
+fn records(value: Value, tag: u64, offset: usize) {
+    match value {
+        Value::Tag(..) => reject::<String>(value, tag, offset),
+        _ => reject::<Record>(value, tag, offset),
+    }
+}
+
+#[rstest(value, tag, offset,
+    case(cbor!(tag!(1, 5)).unwrap(), 1, 0),
+    case(cbor!([1, tag!(6, 2)]).unwrap(), 6, 2),
+    case(cbor!({ tag!(1, 1) => 2 }).unwrap(), 1, 1),
+    case(cbor!(tag!(2, "not a bignum")).unwrap(), 2, 0),
+)]
+fn values(value: Value, tag: u64, offset: usize) {
+    reject::<Value>(value, tag, offset);
+}
+
+#[test]
+fn requested() {
+    accept(
+        cbor!({ "name" => "x", "when" => 5, "id" => tag!(37, 1) }).unwrap(),
+        Record {
+            name: "x".into(),
+            when: Some(5),
+            id: Required(1),
+        },
+    );
+
+    accept(
+        cbor!(tag!(6, 1)).unwrap(),
+        Captured(Some(6), Value::from(1)),
+    );
+    accept(cbor!(1).unwrap(), Captured(None, Value::from(1)));
+
+    let dispatcher = Dispatcher::<u32>::new().case::<u32>(1001);
+    let bytes = encode(cbor!(tag!(1001, 7)).unwrap());
+    assert_eq!(
+        strict().from_reader_seed(&bytes[..], dispatcher).unwrap(),
+        7
+    );
+}
+
+#[test]
+fn bignums() {
+    // 2^64, as a bignum
+    let bytes = hex::decode("c249010000000000000000").unwrap();
+    let big: u128 = strict().from_reader(&bytes[..]).unwrap();
+    assert_eq!(big, 1 << 64);
+
+    let value: Value = strict().from_reader(&bytes[..]).unwrap();
+    assert_eq!(value, Value::from(1u128 << 64));
+}
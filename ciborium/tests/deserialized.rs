@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::{
+    cbor,
+    value::{Error, Step, Value},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Server {
+    name: String,
+    ports: Vec<u16>,
+    limits: BTreeMap<String, u32>,
+}
+
+fn path(error: Error) -> Vec<Step> {
+    match error {
+        Error::At(path, ..) => path.to_vec(),
+        Error::Custom(..) => Vec::new(),
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let server = Server {
+        name: "web".into(),
+        ports: vec![80, 443],
+        limits: vec![("conns".into(), 100)].into_iter().collect(),
+    };
+
+    let value = Value::serialized(&server).unwrap();
+    assert_eq!(
+        value,
+        cbor!({
+            "name" => "web",
+            "ports" => [80, 443],
+            "limits" => { "conns" => 100 },
+        })
+        .unwrap()
+    );
+
+    assert_eq!(value.deserialized::<Server>().unwrap(), server);
+}
+
+#[test]
+fn root() {
+    let error = Value::Null.deserialized::<Server>().unwrap_err();
+    assert_eq!(path(error), vec![]);
+}
+
+#[test]
+fn index() {
+    let value = cbor!({ "name" => "web", "ports" => [80, -1], "limits" => {} }).unwrap();
+    let error = value.deserialized::<Server>().unwrap_err();
+    assert_eq!(path(error), vec![Step::Key("ports".into()), Step::Index(1)]);
+}
+
+#[test]
+fn key() {
+    let value = cbor!({
+        "name" => "web",
+        "ports" => [],
+        "limits" => { "conns" => 100, "rate" => "fast" },
+    })
+    .unwrap();
+
+    let error = value.deserialized::<Server>().unwrap_err();
+    assert_eq!(
+        path(error),
+        vec![Step::Key("limits".into()), Step::Key("rate".into())]
+    );
+}
+
+#[test]
+fn nested() {
+    let value = cbor!([[], [[true, 1]]]).unwrap();
+    let error = value.deserialized::<Vec<Vec<Vec<bool>>>>().unwrap_err();
+    assert_eq!(
+        path(error),
+        vec![Step::Index(1), Step::Index(0), Step::Index(1)]
+    );
+}
+
+#[test]
+fn exact_floats() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Port {
+        port: u16,
+        backup: Option<i64>,
+    }
+
+    let value = cbor!({ "port" => 8080.0, "backup" => -0.0 }).unwrap();
+    assert!(value.deserialized::<Port>().is_err());
+    assert_eq!(
+        value.deserialized_exact_floats::<Port>().unwrap(),
+        Port {
+            port: 8080,
+            backup: Some(0)
+        }
+    );
+
+    // Fractional and out of range floats are still rejected.
+    let value = cbor!({ "port" => 80.5, "backup" => null }).unwrap();
+    let error = value.deserialized_exact_floats::<Port>().unwrap_err();
+    assert_eq!(path(error), [Step::Key("port".into())]);
+
+    let value = cbor!({ "port" => 65536.0, "backup" => null }).unwrap();
+    let error = value.deserialized_exact_floats::<Port>().unwrap_err();
+    assert_eq!(path(error), [Step::Key("port".into())]);
+
+    let value = cbor!({ "port" => 9007199254740992.0, "backup" => 9007199254740992.0 }).unwrap();
+    assert!(value.deserialized_exact_floats::<Port>().is_err());
+    let backup: BTreeMap<String, i64> = value.deserialized_exact_floats().unwrap();
+    assert_eq!(backup["backup"], 9007199254740992);
+}
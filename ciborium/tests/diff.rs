@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    value::{diff, diff_limited, DiffKind, Step, Value},
+};
+use rstest::rstest;
+
+fn lines(old: &Value, new: &Value) -> Vec<String> {
+    diff(old, new).iter().map(|e| e.to_string()).collect()
+}
+
+#[rstest(old, new, answer,
+    case(cbor!(1).unwrap(), cbor!(1).unwrap(), &[]),
+    case(cbor!(1).unwrap(), cbor!(2).unwrap(), &["$: changed 1 to 2"]),
+    case(cbor!(1).unwrap(), cbor!(1.0).unwrap(), &["$: changed 1 to 1.0"]),
+    case(cbor!([1]).unwrap(), cbor!({}).unwrap(), &["$: changed [1] to {}"]),
+    case(
+        cbor!([1, 2, 3]).unwrap(),
+        cbor!([1, 5]).unwrap(),
+        &["$[1]: changed 2 to 5", "$[2]: removed 3"]
+    ),
+    case(
+        cbor!([1]).unwrap(),
+        cbor!([1, [2]]).unwrap(),
+        &["$[1]: added [2]"]
+    ),
+    // Maps are compared by key, not by position
+    case(
+        cbor!({ "a" => 1, "b" => 2 }).unwrap(),
+        cbor!({ "b" => 2, "a" => 1 }).unwrap(),
+        &[]
+    ),
+    case(
+        cbor!({ "a" => 1, "b" => { "c" => true } }).unwrap(),
+        cbor!({ "b" => { "c" => false }, "d" => null }).unwrap(),
+        &["$.a: removed 1", "$.b.c: changed true to false", "$.d: added null"]
+    ),
+    case(
+        cbor!({ 1 => "x", "two words" => "y" }).unwrap(),
+        cbor!({ 1 => "z", "two words" => "y" }).unwrap(),
+        &["${1}: changed \"x\" to \"z\""]
+    ),
+    case(
+        cbor!({ "two words" => 1 }).unwrap(),
+        cbor!({ "two words" => 2 }).unwrap(),
+        &["${\"two words\"}: changed 1 to 2"]
+    ),
+    // Tags are compared by number first
+    case(
+        cbor!(tag!(24, [1])).unwrap(),
+        cbor!(tag!(24, [2])).unwrap(),
+        &["$<24>[0]: changed 1 to 2"]
+    ),
+    case(
+        cbor!(tag!(24, [1])).unwrap(),
+        cbor!(tag!(25, [1])).unwrap(),
+        &["$: changed 24([1]) to 25([1])"]
+    ),
+)]
+fn display(old: Value, new: Value, answer: &[&str]) {
+    assert_eq!(lines(&old, &new), answer);
+}
+
+#[test]
+fn entries() {
+    let old = cbor!({ "a" => [1] }).unwrap();
+    let new = cbor!({ "a" => [2, 3] }).unwrap();
+    let entries = diff(&old, &new);
+
+    let a = Step::Key("a".into());
+    assert_eq!(entries.len(), 2);
+    assert_eq!(&entries[0].path[..], [a.clone(), Step::Index(0)]);
+    assert_eq!(
+        entries[0].kind,
+        DiffKind::Changed {
+            old: &1.into(),
+            new: &2.into()
+        }
+    );
+    assert_eq!(&entries[1].path[..], [a, Step::Index(1)]);
+    assert_eq!(entries[1].kind, DiffKind::Added(&3.into()));
+}
+
+#[test]
+fn limited() {
+    let old = Value::Array((0..10_000u32).map(Value::from).collect());
+    let new = Value::Array((1..10_001u32).map(Value::from).collect());
+
+    assert_eq!(diff(&old, &new).len(), 10_000);
+    assert_eq!(diff_limited(&old, &new, 10).len(), 10);
+    assert_eq!(diff_limited(&old, &new, 0).len(), 0);
+}
+
+#[test]
+fn deep() {
+    let mut old = Value::Bool(false);
+    let mut new = Value::Bool(true);
+    for _ in 0..100_000 {
+        old = Value::Array(vec![old]);
+        new = Value::Array(vec![new]);
+    }
+
+    let entries = diff(&old, &new);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path.len(), 100_000);
+
+    // Avoid recursion when dropping the deeply nested values.
+    let mut stack = vec![old, new];
+    while let Some(value) = stack.pop() {
+        if let Value::Array(x) = value {
+            stack.extend(x);
+        }
+    }
+}
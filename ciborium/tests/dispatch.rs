@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    de::{from_reader_seed, Error},
+    ser::into_writer,
+    tag::dispatch::{Case, End},
+    tag::Dispatcher,
+    value::Value,
+};
+use rstest::rstest;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Request {
+    id: u32,
+    method: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Response(u32, bool);
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Failure(String);
+
+#[derive(Debug, PartialEq)]
+enum Message {
+    Request(Request),
+    Response(Response),
+    Failure(Failure),
+    Code(u8),
+}
+
+impl From<Request> for Message {
+    fn from(x: Request) -> Self {
+        Message::Request(x)
+    }
+}
+
+impl From<Response> for Message {
+    fn from(x: Response) -> Self {
+        Message::Response(x)
+    }
+}
+
+impl From<Failure> for Message {
+    fn from(x: Failure) -> Self {
+        Message::Failure(x)
+    }
+}
+
+impl From<u8> for Message {
+    fn from(x: u8) -> Self {
+        Message::Code(x)
+    }
+}
+
+type Cases = Case<u8, Case<Failure, Case<Response, Case<Request, End>>>>;
+
+fn dispatcher() -> Dispatcher<Message, Cases> {
+    Dispatcher::new()
+        .case::<Request>(1001)
+        .case::<Response>(1002)
+        .case::<Failure>(1003)
+        .case::<u8>(1003)
+}
+
+fn encode(value: Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(&value, &mut bytes).unwrap();
+    bytes
+}
+
+#[rstest(value, answer,
+    case(
+        cbor!(tag!(1001, { "id" => 7, "method" => "get" })).unwrap(),
+        Message::Request(Request { id: 7, method: "get".into() })
+    ),
+    case(cbor!(tag!(1002, [7, true])).unwrap(), Message::Response(Response(7, true))),
+
+    // The first case registered for a tag wins.
+    case(cbor!(tag!(1003, "oops")).unwrap(), Message::Failure(Failure("oops".into()))),
+
+    // Nested tags are left to the chosen type.
+    case(cbor!(tag!(1002, tag!(6, [7, false]))).unwrap(), Message::Response(Response(7, false))),
+)]
+fn dispatch(value: Value, answer: Message) {
+    let bytes = encode(value);
+    assert_eq!(from_reader_seed(&bytes[..], dispatcher()).unwrap(), answer);
+}
+
+#[rstest(value, message,
+    case(cbor!(tag!(1004, 0)).unwrap(), "expected one of tags [1001, 1002, 1003, 1003], found tag 1004"),
+    case(cbor!([7, true]).unwrap(), "expected one of tags [1001, 1002, 1003, 1003], found an untagged value"),
+    case(cbor!(tag!(1002, { "id" => 7 })).unwrap(), "invalid type at offset 3: expected array, found map"),
+)]
+fn invalid(value: Value, message: &str) {
+    let bytes = encode(value);
+    match from_reader_seed(&bytes[..], dispatcher()).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e @ Error::InvalidType { .. } => assert_eq!(e.to_string(), message),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn tags() {
+    assert_eq!(dispatcher().tags(), [1001, 1002, 1003, 1003]);
+    assert_eq!(Dispatcher::<Message>::new().tags(), [0u64; 0]);
+}
+
+#[test]
+fn empty() {
+    let bytes = encode(cbor!(tag!(1001, 0)).unwrap());
+    match from_reader_seed(&bytes[..], Dispatcher::<Message>::new()).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, "expected one of tags [], found tag 1001"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    de::from_reader,
+    ser::into_writer,
+    tag::{EmbeddedCbor, RawEmbeddedCbor},
+    value::Value,
+};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+struct Payload {
+    kid: u8,
+    msg: String,
+}
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn roundtrip() {
+    let payload = EmbeddedCbor(Payload {
+        kid: 7,
+        msg: "hi".into(),
+    });
+
+    // 24(h'A2636B69640763...')
+    let bytes = encode(&payload);
+    assert_eq!(hex::encode(&bytes), "d8184da2636b696407636d7367626869");
+
+    let decoded: EmbeddedCbor<Payload> = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, payload);
+
+    let value = Value::serialized(&payload).unwrap();
+    let inner = cbor!({ "kid" => 7, "msg" => "hi" }).unwrap();
+    assert_eq!(value, Value::Tag(24, Value::Bytes(encode(&inner)).into()));
+    assert_eq!(
+        value.deserialized::<EmbeddedCbor<Payload>>().unwrap(),
+        payload
+    );
+}
+
+#[test]
+fn nested() {
+    let nested = EmbeddedCbor(EmbeddedCbor(vec![1u8]));
+
+    let bytes = encode(&nested);
+    assert_eq!(hex::encode(&bytes), "d81845d818428101");
+
+    let decoded: EmbeddedCbor<EmbeddedCbor<Vec<u8>>> = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, nested);
+}
+
+#[test]
+fn raw() {
+    let bytes = hex::decode("d81845d818428101").unwrap();
+
+    let raw: RawEmbeddedCbor = from_reader(&bytes[..]).unwrap();
+    assert_eq!(raw.0, hex::decode("d818428101").unwrap());
+    assert_eq!(encode(&raw), bytes);
+
+    let inner: EmbeddedCbor<Vec<u8>> = raw.decode().unwrap();
+    assert_eq!(inner.0, [1]);
+
+    let raw = RawEmbeddedCbor::encode(&inner).unwrap();
+    assert_eq!(raw.0, hex::decode("d818428101").unwrap());
+}
+
+#[rstest(bytes, message,
+    // Untagged bytes
+    case("428101", "expected tag 24 (embedded CBOR), found an untagged value"),
+
+    // The wrong tag
+    case("d819428101", "expected tag 24 (embedded CBOR), found tag 25"),
+
+    // Tag 24 wrapping anything but bytes
+    case("d8188101", "invalid type: sequence, expected bytes"),
+
+    // Bytes which are not a valid data item
+    case("d8184182", "invalid embedded CBOR"),
+
+    // Bytes of the wrong type
+    case("d81841f5", "invalid embedded CBOR"),
+)]
+fn invalid(bytes: &str, message: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    match from_reader::<EmbeddedCbor<Vec<u8>>, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => assert!(msg.starts_with(message), "{}", msg),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,223 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::value::Value;
+use rand::Rng;
+use rstest::rstest;
+
+const ITERATIONS: usize = 16 * 1024;
+
+fn roundtrip(bytes: &[u8]) -> Vec<u8> {
+    let (value, encoding) = Value::from_reader_with_encoding(bytes).unwrap();
+
+    let mut output = Vec::new();
+    value
+        .into_writer_with_encoding(&encoding, &mut output)
+        .unwrap();
+    output
+}
+
+#[rstest(
+    bytes,
+    case("5fff"),
+    case("7fff"),
+    case("9fff"),
+    case("bfff"),
+    case("5f42010243030405ff"),
+    case("5f4040ff"),
+    case("7f657374726561646d696e67ff"),
+    case("7f62c3a96161ff"),
+    case("9f018202039f0405ffff"),
+    case("83019f0203ff820405"),
+    case("bf61610161629f0203ffff"),
+    case("a1bfff9fff"),
+    case("d8189f5f41ffffff"),
+    case("c2420100"),
+    case("c349010000000000000000"),
+    // Floats wider than they need be
+    case("fa3fc00000"),
+    case("fb3ff8000000000000"),
+    case("fb3ff199999999999a"),
+    case("fb7ff8000000000000"),
+    case("82fa3fc00000f93e00"),
+    case("9ffa3fc00000ff"),
+    case("bffa3fc00000fb3ff8000000000000ff")
+)]
+fn fixed(bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    assert_eq!(roundtrip(&bytes), bytes);
+}
+
+#[test]
+fn modified() {
+    // (_ "ab", "cm") re-encoded after the string changes length
+    let bytes = hex::decode("7f62616262636dff").unwrap();
+    let (value, encoding) = Value::from_reader_with_encoding(&bytes[..]).unwrap();
+    assert_eq!(value, Value::Text("abcm".into()));
+
+    let mut output = Vec::new();
+    let changed = Value::Text("abc".into());
+    changed
+        .into_writer_with_encoding(&encoding, &mut output)
+        .unwrap();
+    assert_eq!(output, hex::decode("63616263").unwrap());
+
+    // Chunks which would split a character are not used.
+    let mut output = Vec::new();
+    let changed = Value::Text("c\u{e9}m".into());
+    changed
+        .into_writer_with_encoding(&encoding, &mut output)
+        .unwrap();
+    assert_eq!(output, hex::decode("6463c3a96d").unwrap());
+}
+
+fn head(output: &mut Vec<u8>, major: u8, n: u64) {
+    let major = major << 5;
+    match n {
+        0..=23 => output.push(major | n as u8),
+        24..=0xff => output.extend_from_slice(&[major | 24, n as u8]),
+        0x100..=0xffff => {
+            output.push(major | 25);
+            output.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            output.push(major | 26);
+            output.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            output.push(major | 27);
+            output.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+/// Writes a string, either with a definite length or in random chunks
+fn string(rng: &mut impl Rng, output: &mut Vec<u8>, major: u8, body: &[u8], bounds: &[usize]) {
+    if rng.gen() {
+        head(output, major, body.len() as u64);
+        output.extend_from_slice(body);
+        return;
+    }
+
+    output.push(major << 5 | 31);
+
+    let mut start = 0;
+    while start < body.len() || rng.gen_bool(0.1) {
+        let candidates: Vec<_> = bounds.iter().filter(|b| **b >= start).collect();
+        let end = *candidates[rng.gen_range(0..candidates.len())];
+        head(output, major, (end - start) as u64);
+        output.extend_from_slice(&body[start..end]);
+        start = end;
+    }
+
+    output.push(0xff);
+}
+
+/// Writes a random float, as a half, single or double float
+fn float(rng: &mut impl Rng, output: &mut Vec<u8>) {
+    // Signaling NaNs are not generated: widening them to an `f64` may
+    // quiet them.
+    match rng.gen_range(0..3) {
+        0 => {
+            output.push(0xf9);
+            output.extend_from_slice(&rng.gen::<u16>().to_be_bytes());
+        }
+
+        1 => {
+            let x = f32::from_bits(rng.gen());
+            let x = if x.is_nan() { f32::NAN } else { x };
+            output.push(0xfa);
+            output.extend_from_slice(&x.to_be_bytes());
+        }
+
+        _ => {
+            let x = f64::from_bits(rng.gen());
+            let x = if x.is_nan() { f64::NAN } else { x };
+            output.push(0xfb);
+            output.extend_from_slice(&x.to_be_bytes());
+        }
+    }
+}
+
+/// Writes a random document in preferred serialization, except that
+/// containers and strings are randomly indefinite or chunked, and floats
+/// are randomly wider than they need be
+fn generate(rng: &mut impl Rng, output: &mut Vec<u8>, depth: usize) {
+    let floats: [&[u8]; 5] = [
+        &[0xf9, 0x3e, 0x00],
+        &[0xf9, 0x80, 0x00],
+        &[0xf9, 0x7e, 0x00],
+        &[0xfa, 0x47, 0xc3, 0x50, 0x00],
+        &[0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a],
+    ];
+
+    match rng.gen_range(0..if depth == 0 { 6 } else { 9 }) {
+        0 => {
+            let n = rng.gen::<u64>() >> rng.gen_range(0..64);
+            head(output, rng.gen_range(0..2), n);
+        }
+
+        1 => match rng.gen() {
+            true => output.extend_from_slice(floats[rng.gen_range(0..floats.len())]),
+            false => float(rng, output),
+        },
+
+        2 => {
+            let body: Vec<u8> = (0..rng.gen_range(0..40)).map(|_| rng.gen()).collect();
+            let bounds: Vec<_> = (0..=body.len()).collect();
+            string(rng, output, 2, &body, &bounds);
+        }
+
+        3 => {
+            let text: String = (0..rng.gen_range(0..12))
+                .map(|_| ['a', 'z', '\u{e9}', '\u{20ac}', '\u{1f600}'][rng.gen_range(0..5)])
+                .collect();
+            let bounds: Vec<_> = (0..=text.len())
+                .filter(|i| text.is_char_boundary(*i))
+                .collect();
+            string(rng, output, 3, text.as_bytes(), &bounds);
+        }
+
+        4 => match rng.gen_range(0..3) {
+            0 => head(output, 7, rng.gen_range(0..24)),
+            _ => output.extend_from_slice(&[0xf8, rng.gen_range(32..=255)]),
+        },
+
+        5 => head(output, 7, rng.gen_range(20..24)),
+
+        6 => {
+            head(output, 6, rng.gen_range(0..30));
+            generate(rng, output, depth - 1);
+        }
+
+        major => {
+            let len = rng.gen_range(0..4);
+            let items = if major == 7 { len } else { len * 2 };
+            let major = if major == 7 { 4 } else { 5 };
+
+            let definite = rng.gen();
+            match definite {
+                true => head(output, major, len),
+                false => output.push(major << 5 | 31),
+            }
+
+            for _ in 0..items {
+                generate(rng, output, depth - 1);
+            }
+
+            if !definite {
+                output.push(0xff);
+            }
+        }
+    }
+}
+
+#[test]
+fn generated() {
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..ITERATIONS {
+        let mut bytes = Vec::new();
+        generate(&mut rng, &mut bytes, 4);
+        assert_eq!(roundtrip(&bytes), bytes, "{}", hex::encode(&bytes));
+    }
+}
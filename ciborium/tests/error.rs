@@ -13,17 +13,37 @@ use rstest::rstest;
     // Indeterminate integer in an array
     case("83011f03", Error::Syntax(2)),
 
-    // Integer in a string continuation
-    case("7F616101FF", Error::Syntax(3)),
+    // Integer in a string continuation, so its break is missing
+    case("7F616101FF", Error::MissingBreak { container_offset: 0 }),
+    case("827F616101", Error::MissingBreak { container_offset: 1 }),
+    case("5F410180", Error::MissingBreak { container_offset: 0 }),
 
-    // Bytes in a string continuation
-    case("7F61614101FF", Error::Syntax(3)),
+    // Bytes in a string continuation, which are not a chunk of it either
+    case("7F61614101FF", Error::MissingBreak { container_offset: 0 }),
+
+    // Breaks which do not end an indefinite-length item
+    case("FF", Error::UnexpectedBreak { offset: 0 }),
+    case("8201FF", Error::UnexpectedBreak { offset: 2 }),
+    case("A101FF", Error::UnexpectedBreak { offset: 2 }),
+    case("BF01FF", Error::UnexpectedBreak { offset: 2 }),
+    case("C1FF", Error::UnexpectedBreak { offset: 1 }),
+    case("9FC1FF", Error::UnexpectedBreak { offset: 2 }),
 
     // Invalid UTF-8
-    case("62C328", Error::Syntax(0)),
+    case("62C328", Error::InvalidUtf8 { chunk: 0, offset: 0 }),
 
     // Invalid UTF-8 in a string continuation
-    case("7F62C328FF", Error::Syntax(1)),
+    case("7F62C328FF", Error::InvalidUtf8 { chunk: 0, offset: 1 }),
+
+    // Truncated headers and bodies
+    case("19", Error::UnexpectedEof { offset: 1 }),
+    case("8201", Error::UnexpectedEof { offset: 2 }),
+    case("62C3", Error::UnexpectedEof { offset: 1 }),
+    case("7F6161", Error::UnexpectedEof { offset: 3 }),
+
+    // A character split between string continuations
+    case("7F61C361A9FF", Error::InvalidUtf8 { chunk: 0, offset: 1 }),
+    case("7F616163E282AC62E28261ACFF", Error::InvalidUtf8 { chunk: 2, offset: 7 }),
 )]
 fn test(bytes: &str, error: Error<std::io::Error>) {
     let bytes = hex::decode(bytes).unwrap();
@@ -31,17 +51,71 @@ fn test(bytes: &str, error: Error<std::io::Error>) {
     let correct = match error {
         Error::Io(..) => panic!(),
         Error::Syntax(x) => ("syntax", Some(x), None),
+        Error::UnexpectedBreak { offset } => ("break", Some(offset), None),
+        Error::InvalidUtf8 { chunk, offset } => ("utf8", Some(offset), Some(chunk.to_string())),
+        Error::MissingBreak { container_offset } => ("missing", Some(container_offset), None),
+        Error::UnexpectedEof { offset } => ("eof", Some(offset), None),
         Error::Semantic(x, y) => ("semantic", x, Some(y)),
+        Error::InvalidType { .. } => panic!(),
+        Error::ContainerUnderflow { .. } => panic!(),
+        Error::ContainerOverflow { .. } => panic!(),
+        #[allow(deprecated)]
         Error::RecursionLimitExceeded => panic!(),
+        Error::LimitExceeded { .. } => panic!(),
     };
 
     let result: Result<Value, _> = from_reader(&bytes[..]);
     let actual = match result.unwrap_err() {
         Error::Io(..) => panic!(),
         Error::Syntax(x) => ("syntax", Some(x), None),
+        Error::UnexpectedBreak { offset } => ("break", Some(offset), None),
+        Error::InvalidUtf8 { chunk, offset } => ("utf8", Some(offset), Some(chunk.to_string())),
+        Error::MissingBreak { container_offset } => ("missing", Some(container_offset), None),
+        Error::UnexpectedEof { offset } => ("eof", Some(offset), None),
         Error::Semantic(x, y) => ("semantic", x, Some(y)),
+        Error::InvalidType { .. } => panic!(),
+        Error::ContainerUnderflow { .. } => panic!(),
+        Error::ContainerOverflow { .. } => panic!(),
+        #[allow(deprecated)]
         Error::RecursionLimitExceeded => panic!(),
+        Error::LimitExceeded { .. } => panic!(),
     };
 
     assert_eq!(correct, actual);
 }
+
+#[test]
+fn split() {
+    // "a", then a character split at each of its bytes, in a chunk each
+    for c in ['é', '€', '😀'] {
+        let mut utf8 = [0u8; 4];
+        let utf8 = c.encode_utf8(&mut utf8).as_bytes();
+
+        for at in 1..utf8.len() {
+            let (head, tail) = utf8.split_at(at);
+
+            let mut bytes = vec![0x7f, 0x61, b'a', 0x60 + head.len() as u8];
+            bytes.extend_from_slice(head);
+            bytes.push(0x60 + tail.len() as u8);
+            bytes.extend_from_slice(tail);
+            bytes.push(0xff);
+
+            let errors = [
+                from_reader::<String, _>(&bytes[..]).unwrap_err(),
+                from_reader::<Value, _>(&bytes[..]).unwrap_err(),
+            ];
+
+            for error in errors {
+                match error {
+                    Error::InvalidUtf8 { chunk, offset } => assert_eq!((chunk, offset), (1, 3)),
+                    e => panic!("incorrect error: {:?}", e),
+                }
+            }
+
+            match ciborium::value::ValueRef::from_slice(&bytes).unwrap_err() {
+                Error::InvalidUtf8 { chunk, offset } => assert_eq!((chunk, offset), (1, 3)),
+                e => panic!("incorrect error: {:?}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+
+use ciborium::de::{extract, from_reader, from_slice, Error, MapAccessor, Options};
+use ciborium::ser::into_writer;
+use ciborium::value::{RawCborRef, Value};
+use ciborium::{cbor, tag::Required};
+use serde::Deserialize;
+
+/// Counts the bytes allocated by each thread, so that tests can run in parallel
+struct Counting;
+
+thread_local! {
+    static CURRENT: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.with(|x| {
+            x.set(x.get() + layout.size());
+            x.get()
+        });
+
+        PEAK.with(|x| x.set(x.get().max(current)));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.with(|x| x.set(x.get().saturating_sub(layout.size())));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Runs `func`, returning the most it had allocated at any one time
+fn peak<T>(func: impl FnOnce() -> T) -> (T, usize) {
+    let base = CURRENT.with(|x| x.get());
+    PEAK.with(|x| x.set(base));
+
+    let value = func();
+    (value, PEAK.with(|x| x.get()) - base)
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Two {
+    f10: u32,
+    f40: String,
+}
+
+/// A map of fifty fields, where all but `f10` and `f40` are large
+fn document() -> Vec<u8> {
+    let mut map = Vec::new();
+
+    for i in 0..50 {
+        let value = match i {
+            10 => Value::from(10),
+            40 => Value::from("probe"),
+            i if i % 3 == 0 => Value::Text("x".repeat(10_000)),
+            i if i % 3 == 1 => Value::Bytes(vec![0xff; 10_000]),
+            _ => cbor!([[1, "a"], { "b" => [2.5, null] }, 3]).unwrap(),
+        };
+
+        map.push((Value::Text(format!("f{:02}", i)), value));
+    }
+
+    let mut bytes = Vec::new();
+    into_writer(&Value::Map(map), &mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn allocations() {
+    let bytes = document();
+    let expected = Two {
+        f10: 10,
+        f40: "probe".into(),
+    };
+
+    // Besides the extracted string, only the decoder's small stack of open
+    // containers is allocated.
+    let (two, used) = peak(|| extract::<Two>(&bytes, &["f10", "f40"]).unwrap());
+    assert_eq!(two, expected);
+    assert!(used < 128, "{} bytes", used);
+
+    let (value, used) = peak(|| MapAccessor::new(&bytes).get::<u32>("f10").unwrap());
+    assert_eq!(value, Some(10));
+    assert!(used < 128, "{} bytes", used);
+
+    // Unknown fields are skipped without being buffered, from any reader.
+    let (two, used) = peak(|| from_slice::<Two>(&bytes).unwrap());
+    assert_eq!(two, expected);
+    assert!(used < 128, "{} bytes", used);
+
+    let (two, used) = peak(|| from_reader::<Two, _>(&bytes[..]).unwrap());
+    assert_eq!(two, expected);
+    assert!(used < 128, "{} bytes", used);
+
+    // Whereas a `Value` holds the whole document.
+    let (_, used) = peak(|| from_slice::<Value>(&bytes).unwrap());
+    assert!(used > bytes.len() / 2, "{} bytes", used);
+}
+
+#[test]
+fn fields() {
+    // {"a": 1, 2: "b", "c": [3], "d": {"e": 4}, "a": 5}
+    let value = cbor!({ "a" => 1, 2 => "b", "c" => [3], "d" => { "e" => 4 }, "a" => 5 }).unwrap();
+    let mut bytes = Vec::new();
+    into_writer(&value, &mut bytes).unwrap();
+
+    let map: BTreeMap<String, Value> = extract(&bytes, &["c", "d", "x"]).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["c"], cbor!([3]).unwrap());
+    assert_eq!(map["d"], cbor!({ "e" => 4 }).unwrap());
+
+    // Each entry is passed on, including any with the same key.
+    let all: Vec<(String, u8)> = extract::<BTreeMap<String, u8>>(&bytes, &["a"])
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(all, [("a".into(), 5)]);
+
+    let map = MapAccessor::new(&bytes);
+    assert_eq!(map.get::<u8>("a").unwrap(), Some(1));
+    assert_eq!(map.get::<String>("b").unwrap(), None);
+    assert_eq!(
+        map.get::<RawCborRef>("c").unwrap().unwrap().as_bytes(),
+        [0x81, 0x03]
+    );
+}
+
+#[test]
+fn encodings() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct One<'a> {
+        b: &'a str,
+    }
+
+    // {_ "a": [_ 1], 1("b"): 0, "b": "c"}, where the tagged key is not text
+    let bytes = hex::decode("bf61619f01ffc161620061626163ff").unwrap();
+    let one: One = extract(&bytes, &["b"]).unwrap();
+    assert_eq!(one, One { b: "c" });
+
+    // 55799({"b": "d"}), where the map itself is tagged
+    let bytes = hex::decode("d9d9f7a161626164").unwrap();
+    let one: One = extract(&bytes, &["b"]).unwrap();
+    assert_eq!(one, One { b: "d" });
+
+    // {"b": 1(5)}, where the tag is asked for
+    let bytes = hex::decode("a16162c105").unwrap();
+    let tagged: BTreeMap<String, Required<u8, 1>> = extract(&bytes, &["b"]).unwrap();
+    assert_eq!(tagged["b"].0, 5);
+}
+
+#[test]
+fn errors() {
+    // {"a": "x", "b": [1, 2, truncated
+    let bytes = hex::decode("a2616161786162830102").unwrap();
+
+    // The skipped entries must still be well-formed.
+    match MapAccessor::new(&bytes).get::<String>("a").unwrap_err() {
+        Error::UnexpectedEof { offset } => assert_eq!(offset, 10),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // Offsets are within the whole input.
+    match MapAccessor::new(&bytes[..6]).get::<u8>("a").unwrap_err() {
+        Error::InvalidType { offset, .. } => assert_eq!(offset, 3),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    match extract::<Two>(&[0x80], &["f10"]).unwrap_err() {
+        Error::InvalidType {
+            offset, expected, ..
+        } => assert_eq!((offset, expected), (0, "map")),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // A field which is not selected is missing.
+    let bytes = document();
+    match extract::<Two>(&bytes, &["f10"]).unwrap_err() {
+        Error::Semantic(None, msg) => assert_eq!(msg, "missing field `f40`"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn ignored() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct A {
+        a: u8,
+    }
+
+    // {"a": 1, "k": 32([_ "x"])}: the tag and text in "k" are never looked at
+    let bytes = hex::decode("a2616101616bd8209f6178ff").unwrap();
+    let options = Options::new().deny_unexpected_tags(true);
+    assert_eq!(options.from_reader::<A, _>(&bytes[..]).unwrap(), A { a: 1 });
+    assert_eq!(options.extract::<A>(&bytes, &["a"]).unwrap(), A { a: 1 });
+
+    // But they are when the field is taken.
+    let result = options.extract::<BTreeMap<String, Value>>(&bytes, &["k"]);
+    match result.unwrap_err() {
+        Error::Semantic(Some(6), msg) => assert_eq!(msg, "unexpected tag 32"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // An ignored field must still be well-formed: {"a": 1, "b": {_ 1}}
+    let bytes = hex::decode("a26161016162bf01ff").unwrap();
+    match options.from_reader::<A, _>(&bytes[..]).unwrap_err() {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 8),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,256 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::io::Cursor;
+
+use ciborium::de::{Error, LimitKind};
+use ciborium::framed::{read_frame, write_frame, FrameIter};
+use ciborium::ser;
+use ciborium::value::Value;
+
+use rstest::rstest;
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Message {
+    id: u32,
+    body: String,
+}
+
+#[rstest(value, frame,
+    case(Value::from(0), "0000000100"),
+    case(Value::from(1_000_000), "000000051a000f4240"),
+    case(Value::from("IETF"), "000000056449455446"),
+    case(Value::Array(vec![]), "0000000180"),
+    case(Value::Bytes(vec![0; 300]), "0000012f59012c"),
+)]
+fn interop(value: Value, frame: &str) {
+    let frame = hex::decode(frame).unwrap();
+
+    let mut bytes = Vec::new();
+    write_frame(&value, &mut bytes).unwrap();
+    assert_eq!(bytes[..frame.len()], frame[..]);
+
+    let len = bytes.len() - 4;
+    assert_eq!(bytes[..4], (len as u32).to_be_bytes());
+
+    let decoded: Value = read_frame(&bytes[..], 1024).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn handwritten() {
+    // Frames as another implementation would write them, for
+    // {"id": 1, "body": "a"} and {"id": 2, "body": ""}
+    let mut input = Vec::new();
+    for (id, body) in [(1u8, "a"), (2, "")] {
+        let mut item = vec![0xa2, 0x62, b'i', b'd', id, 0x64, b'b', b'o', b'd', b'y'];
+        item.push(0x60 | body.len() as u8);
+        item.extend_from_slice(body.as_bytes());
+
+        input.extend_from_slice(&(item.len() as u32).to_be_bytes());
+        input.extend_from_slice(&item);
+    }
+
+    let messages: Vec<Message> = FrameIter::new(&input[..], 64)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        messages,
+        [
+            Message {
+                id: 1,
+                body: "a".into()
+            },
+            Message {
+                id: 2,
+                body: "".into()
+            },
+        ]
+    );
+
+    // Writing the same messages gives the same frames.
+    let mut output = Vec::new();
+    for message in &messages {
+        write_frame(message, &mut output).unwrap();
+    }
+
+    assert_eq!(output, input);
+}
+
+#[test]
+fn stream() {
+    let mut output = Vec::new();
+    for i in 0..100u32 {
+        let mut map = BTreeMap::new();
+        map.insert(i, "x".repeat(i as usize));
+        write_frame(&map, &mut output).unwrap();
+    }
+
+    let mut frames = FrameIter::<_, BTreeMap<u32, String>>::new(Cursor::new(output), 256);
+    for i in 0..100u32 {
+        let map = frames.next().unwrap().unwrap();
+        assert_eq!(map[&i], "x".repeat(i as usize));
+    }
+
+    assert!(frames.next().is_none());
+    assert_eq!(frames.offset(), frames.into_inner().position() as usize);
+}
+
+#[rstest(input, offset,
+    // In the length
+    case("", 0),
+    case("000000", 0),
+
+    // In the data item
+    case("00000002", 4),
+    case("0000000261", 4),
+)]
+fn truncated(input: &str, offset: usize) {
+    let input = hex::decode(input).unwrap();
+
+    match read_frame::<Value, _>(&input[..], 16).unwrap_err() {
+        Error::UnexpectedEof { offset: x } => assert_eq!(x, offset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn truncated_stream() {
+    // A whole frame, then one cut off in its length and in its data item
+    let input = hex::decode("000000010100").unwrap();
+    let mut frames = FrameIter::<_, u8>::new(&input[..], 16);
+    assert_eq!(frames.next().unwrap().unwrap(), 1);
+    match frames.next().unwrap().unwrap_err() {
+        Error::UnexpectedEof { offset } => assert_eq!(offset, 6),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    let input = hex::decode("0000000101000000036161").unwrap();
+    let mut frames = FrameIter::<_, String>::new(&input[..], 16);
+    assert!(frames.next().unwrap().is_err());
+    assert!(frames.next().is_none());
+
+    let mut frames = FrameIter::<_, Value>::new(&input[..], 16);
+    assert_eq!(frames.next().unwrap().unwrap(), Value::from(1));
+    match frames.next().unwrap().unwrap_err() {
+        Error::UnexpectedEof { offset } => assert_eq!(offset, 9),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // The iterator stops after an error.
+    assert!(frames.next().is_none());
+}
+
+#[test]
+fn oversize() {
+    // 4 GiB - 1 bytes are declared, but nothing is allocated for them.
+    let input = hex::decode("ffffffff00").unwrap();
+    match read_frame::<Value, _>(&input[..], 1 << 20).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::FrameLength,
+            limit,
+            observed,
+            offset: 0,
+        } => assert_eq!((limit, observed), (1 << 20, u32::MAX.into())),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // The limit itself is allowed.
+    let mut bytes = Vec::new();
+    write_frame(&"abc", &mut bytes).unwrap();
+    assert_eq!(read_frame::<String, _>(&bytes[..], 4).unwrap(), "abc");
+    assert!(matches!(
+        read_frame::<String, _>(&bytes[..], 3),
+        Err(Error::LimitExceeded { observed: 4, .. })
+    ));
+
+    // The offset is that of the frame in the stream.
+    let mut input = bytes.clone();
+    input.extend_from_slice(&hex::decode("0000100000").unwrap());
+    let mut frames = FrameIter::<_, String>::new(&input[..], 16);
+    assert_eq!(frames.next().unwrap().unwrap(), "abc");
+    match frames.next().unwrap().unwrap_err() {
+        Error::LimitExceeded { offset, .. } => assert_eq!(offset, 8),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn mismatched() {
+    // [1, 2] in a frame of 2 bytes: the item goes on past the frame.
+    let input = hex::decode("00000002820102").unwrap();
+    match read_frame::<Value, _>(&input[..], 16).unwrap_err() {
+        Error::Syntax(offset) => assert_eq!(offset, 6),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // 1, 2 in a frame of 2 bytes: there is data after the item.
+    let input = hex::decode("000000020102").unwrap();
+    match read_frame::<Value, _>(&input[..], 16).unwrap_err() {
+        Error::Semantic(Some(offset), msg) => {
+            assert_eq!((offset, msg.as_str()), (5, "trailing data in the frame"))
+        }
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // Errors in the data item are at their offset in the stream.
+    let input = hex::decode("00000001000000000261ff").unwrap();
+    let mut frames = FrameIter::<_, String>::new(&input[..], 16);
+    assert!(frames.next().unwrap().is_err());
+
+    let mut frames = FrameIter::<_, Value>::new(&input[..], 16);
+    assert_eq!(frames.next().unwrap().unwrap(), Value::from(0));
+    match frames.next().unwrap().unwrap_err() {
+        Error::InvalidUtf8 { chunk, offset } => assert_eq!((chunk, offset), (0, 9)),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn unstable() {
+    /// A value which serializes longer every time
+    struct Growing(Cell<usize>);
+
+    impl Serialize for Growing {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.set(self.0.get() + 1);
+            serializer.serialize_str(&"x".repeat(self.0.get()))
+        }
+    }
+
+    let mut bytes = Vec::new();
+    match write_frame(&Growing(Cell::new(0)), &mut bytes).unwrap_err() {
+        ser::Error::Value(msg) => {
+            assert_eq!(msg, "the value serialized to 3 bytes, after a length of 2")
+        }
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn writer_errors() {
+    let mut buffer = [0u8; 6];
+    let result = write_frame(&"abc", &mut buffer[..]);
+    assert!(matches!(result, Err(ser::Error::Io(..))));
+
+    // A value which fails to serialize fails before anything is written.
+    struct Failing;
+
+    impl Serialize for Failing {
+        fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("no"))
+        }
+    }
+
+    let mut bytes = Vec::new();
+    assert!(matches!(
+        write_frame(&Failing, &mut bytes),
+        Err(ser::Error::Value(..))
+    ));
+    assert!(bytes.is_empty());
+}
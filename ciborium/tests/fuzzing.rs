@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "fuzzing")]
+
+use ciborium::{fuzz_roundtrip, value::Value};
+use rand::Rng;
+
+const ITERATIONS: usize = 128 * 1024;
+
+#[test]
+fn roundtrip() {
+    let mut rng = rand::thread_rng();
+    let mut buffer = [0u8; 32];
+
+    for _ in 0..ITERATIONS {
+        let len = rng.gen_range(0..buffer.len());
+        rng.fill(&mut buffer[..len]);
+        fuzz_roundtrip(&buffer[..len]);
+    }
+
+    // Inputs which are not in their preferred encoding decode the same.
+    let inputs: &[&[u8]] = &[
+        &[0x19, 0x00, 0x01],
+        &[0xfb, 0x7f, 0xf8, 0, 0, 0, 0, 0, 1],
+        &[0x7f, 0x61, 0x61, 0x61, 0x62, 0xff],
+        &[0xbf, 0x01, 0x9f, 0xff, 0xff],
+        &[0xc3, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        &[0xc2, 0x5f, 0xff],
+        &[0xc3, 0x5f, 0x41, 0x01, 0x40, 0x41, 0x02, 0xff],
+        &[
+            0xc2, 0x5f, 0x48, 1, 0, 0, 0, 0, 0, 0, 0, 0x49, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff,
+        ],
+    ];
+
+    for input in inputs {
+        assert!(fuzz_roundtrip(input).is_some(), "{:x?}", input);
+    }
+
+    // Bignums in chunks are integers whenever they fit in one.
+    let values: Vec<_> = inputs[5..]
+        .iter()
+        .map(|x| fuzz_roundtrip(x).unwrap())
+        .collect();
+    assert_eq!(values[0], Value::from(0));
+    assert_eq!(values[1], Value::from(-0x0102 - 1));
+    assert!(matches!(values[2], Value::Tag(2, ..)));
+}
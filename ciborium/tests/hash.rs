@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use ciborium::value::Value;
+use rand::Rng;
+
+const ITERATIONS: usize = 64 * 1024;
+
+fn hash(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Generates a small random value
+///
+/// The domains are kept tiny so that independently generated values are
+/// frequently equal, which exercises the interesting side of the property.
+fn generate(rng: &mut impl Rng, depth: usize) -> Value {
+    let floats = [0.0, -0.0, 1.0, f64::NAN, -f64::NAN, f64::INFINITY];
+
+    match rng.gen_range(0..if depth == 0 { 6 } else { 9 }) {
+        0 => Value::from(rng.gen_range(-2i64..2)),
+        1 => Value::Float(floats[rng.gen_range(0..floats.len())].into()),
+        2 => Value::Bytes(vec![0; rng.gen_range(0..2)]),
+        3 => Value::Text("a".repeat(rng.gen_range(0..2))),
+        4 => Value::Bool(rng.gen()),
+        5 => [Value::Null, Value::Undefined, Value::Simple(0)][rng.gen_range(0..3)].clone(),
+        6 => Value::Tag(rng.gen_range(0..2), generate(rng, depth - 1).into()),
+        7 => Value::Array(
+            (0..rng.gen_range(0..3))
+                .map(|_| generate(rng, depth - 1))
+                .collect(),
+        ),
+        _ => Value::Map(
+            (0..rng.gen_range(0..3))
+                .map(|_| (generate(rng, depth - 1), generate(rng, depth - 1)))
+                .collect(),
+        ),
+    }
+}
+
+#[test]
+fn consistency() {
+    let mut rng = rand::thread_rng();
+    let mut equal = 0;
+
+    for _ in 0..ITERATIONS {
+        let a = generate(&mut rng, 2);
+        let b = generate(&mut rng, 2);
+
+        assert_eq!(a, a.clone());
+        assert_eq!(hash(&a), hash(&a.clone()));
+        assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        assert_eq!(a == b, a.cmp(&b) == Ordering::Equal, "{:?} {:?}", a, b);
+
+        if a == b {
+            assert_eq!(hash(&a), hash(&b), "{:?} {:?}", a, b);
+            equal += 1;
+        }
+    }
+
+    // Make sure that the generator actually produces equal pairs.
+    assert!(equal > ITERATIONS / 100);
+}
+
+#[test]
+fn floats() {
+    let zero = Value::Float(0.0.into());
+    let nzero = Value::Float((-0.0).into());
+    let nan = Value::Float(f64::NAN.into());
+
+    assert_ne!(zero, nzero);
+    assert_eq!(nzero.cmp(&zero), Ordering::Less);
+
+    assert_eq!(nan, nan.clone());
+    assert_eq!(hash(&nan), hash(&nan.clone()));
+    assert_ne!(nan, Value::Float((-f64::NAN).into()));
+}
+
+#[test]
+fn maps() {
+    let a = Value::Map(vec![(1.into(), 2.into()), (3.into(), 4.into())]);
+    let b = Value::Map(vec![(3.into(), 4.into()), (1.into(), 2.into())]);
+    assert_ne!(a, b);
+
+    let (mut a, mut b) = (a, b);
+    a.sort_keys();
+    b.sort_keys();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn hashmap() {
+    let mut cache = HashMap::new();
+    cache.insert(Value::Array(vec!["GET".into(), "/".into()]), 200);
+    cache.insert(Value::Array(vec!["GET".into(), "/missing".into()]), 404);
+
+    assert_eq!(cache[&Value::Array(vec!["GET".into(), "/".into()])], 200);
+    assert_eq!(
+        cache.get(&Value::Array(vec!["GET".into(), "/missing".into()])),
+        Some(&404)
+    );
+    assert_eq!(cache.get(&Value::Null), None);
+}
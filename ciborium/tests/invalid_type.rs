@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use ciborium::de::{from_reader, Error, FoundKind};
+use rstest::rstest;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+
+#[derive(Debug, Deserialize)]
+struct Point {
+    _x: u8,
+}
+
+#[derive(Debug, Deserialize)]
+enum Unit {
+    _Only,
+}
+
+fn message<T: DeserializeOwned + Debug>(bytes: &str) -> String {
+    let bytes = hex::decode(bytes).unwrap();
+
+    match from_reader::<T, _>(&bytes[..]).unwrap_err() {
+        e @ Error::InvalidType { .. } => e.to_string(),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(actual, expected,
+    case(message::<bool>("01"), "invalid type at offset 0: expected bool, found positive integer"),
+    case(message::<bool>("f6"), "invalid type at offset 0: expected bool, found null"),
+    case(message::<u8>("20"), "invalid type at offset 0: expected unsigned integer, found negative integer"),
+    case(message::<i32>("6161"), "invalid type at offset 0: expected integer, found text"),
+    case(message::<f64>("f5"), "invalid type at offset 0: expected float, found bool"),
+    case(message::<char>("626262"), "invalid type at offset 0: expected char, found text"),
+    case(message::<String>("4100"), "invalid type at offset 0: expected string, found bytes"),
+    case(message::<ByteBuf>("f7"), "invalid type at offset 0: expected byte buffer, found undefined"),
+    case(message::<Vec<u8>>("a0"), "invalid type at offset 0: expected array, found map"),
+    case(message::<BTreeMap<u8, u8>>("80"), "invalid type at offset 0: expected map, found array"),
+    case(message::<Point>("f93c00"), "invalid type at offset 0: expected map, found float"),
+    case(message::<()>("e0"), "invalid type at offset 0: expected unit, found simple value"),
+    case(message::<Unit>("80"), "invalid type at offset 0: expected enum, found array"),
+
+    // The offset is that of the item, inside containers and after tags
+    case(message::<Vec<u8>>("82016161"), "invalid type at offset 2: expected integer, found text"),
+    case(message::<u8>("c1f93c00"), "invalid type at offset 1: expected integer, found float"),
+    case(message::<u128>("c26161"), "invalid type at offset 1: expected bytes, found text"),
+)]
+fn snapshot(actual: String, expected: &str) {
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn fields() {
+    let error = from_reader::<Vec<u8>, _>(&[0x81, 0x80][..]).unwrap_err();
+
+    match error {
+        Error::InvalidType {
+            offset,
+            expected,
+            found,
+        } => {
+            assert_eq!(offset, 1);
+            assert_eq!(expected, "integer");
+            assert_eq!(found, FoundKind::Array);
+        }
+
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::io::{repeat, Read};
+
+use ciborium::{
+    de::{Error, LimitKind, Options},
+    value::Value,
+};
+
+#[test]
+fn endless() {
+    // An indefinite-length array of zeros which never ends
+    let reader = [0x9f].chain(repeat(0));
+
+    match Options::new()
+        .max_items(Some(10_000))
+        .from_reader::<Value, _>(reader)
+    {
+        Err(Error::LimitExceeded {
+            kind: LimitKind::Items,
+            limit,
+            observed,
+            ..
+        }) => assert_eq!((limit, observed), (10_000, 10_001)),
+        e => panic!("incorrect result: {:?}", e),
+    }
+}
+
+#[test]
+fn exact() {
+    // {"a": [_ 1, h'02'], "b": 0("c")}
+    let bytes = hex::decode("a261619f014102ff6162c06163").unwrap();
+    let value: Value = Options::new()
+        .max_items(Some(9))
+        .from_reader(&bytes[..])
+        .unwrap();
+    let expected: Value = Options::new().from_reader(&bytes[..]).unwrap();
+    assert_eq!(value, expected);
+
+    match Options::new()
+        .max_items(Some(8))
+        .from_reader::<Value, _>(&bytes[..])
+    {
+        Err(Error::LimitExceeded {
+            kind: LimitKind::Items,
+            offset,
+            ..
+        }) => assert_eq!(offset, 11),
+        e => panic!("incorrect result: {:?}", e),
+    }
+}
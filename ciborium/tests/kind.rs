@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use ciborium::de::{from_reader, ErrorKind, Options};
+use ciborium::value::Value;
+
+/// A reader whose connection was reset
+struct Closed;
+
+impl std::io::Read for Closed {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::ErrorKind::ConnectionReset.into())
+    }
+}
+
+fn kind(bytes: &[u8]) -> ErrorKind {
+    from_reader::<Value, _>(bytes).unwrap_err().kind()
+}
+
+#[test]
+fn io() {
+    let error = from_reader::<Value, _>(Closed).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+}
+
+#[test]
+fn unexpected_eof() {
+    assert_eq!(kind(&[0x18]), ErrorKind::UnexpectedEof);
+    assert_eq!(kind(&[0x82, 0x01]), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn syntax() {
+    assert_eq!(kind(&[0x1c]), ErrorKind::Syntax);
+    assert_eq!(kind(&[0x81, 0xff]), ErrorKind::Syntax);
+    assert_eq!(kind(&[0x61, 0xff]), ErrorKind::Syntax);
+}
+
+#[test]
+fn semantic() {
+    let error = from_reader::<bool, _>(&[0x01][..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Semantic);
+
+    let error = Options::new()
+        .deny_unexpected_tags(true)
+        .from_reader::<u8, _>(&[0xc1, 0x01][..])
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Semantic);
+
+    // Duplicate keys are found by the type, through serde.
+    #[derive(Debug, serde::Deserialize)]
+    struct Point {
+        #[allow(dead_code)]
+        x: u8,
+    }
+
+    // {"x": 1, "x": 2}
+    let bytes = [0xa2, 0x61, b'x', 0x01, 0x61, b'x', 0x02];
+    let error = from_reader::<Point, _>(&bytes[..]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Semantic);
+}
+
+#[test]
+fn large_strings() {
+    // Strings longer than the scratch buffer are streamed, not rejected.
+    let mut bytes = vec![0x79, 0x40, 0x00];
+    bytes.resize(3 + 0x4000, b'a');
+    let text: String = from_reader(&bytes[..]).unwrap();
+    assert_eq!(text.len(), 0x4000);
+}
+
+#[test]
+fn recursion_limit() {
+    assert_eq!(kind(&[0x81; 1024]), ErrorKind::RecursionLimit);
+}
+
+#[test]
+fn limit_exceeded() {
+    let error = Options::new()
+        .max_items(Some(2))
+        .from_reader::<Value, _>(&[0x82, 0x01, 0x02][..])
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::LimitExceeded);
+}
@@ -35,7 +35,7 @@ macro_rules! arr {
     case(Value::Bool(true), cbor!(true).unwrap()),
     case(Value::Bool(false), cbor!(false).unwrap()),
     case(Value::Text("foo".into()), cbor!("foo").unwrap()),
-    case(Value::Bytes(vec![0, 1, 2].into()), cbor!(Bytes::new(b"\x00\x01\x02")).unwrap()),
+    case(Value::Bytes(vec![0, 1, 2]), cbor!(Bytes::new(b"\x00\x01\x02")).unwrap()),
 
     // Numeric simple types
     case(Value::Integer(Integer::from(123)), cbor!(123).unwrap()),
@@ -45,6 +45,35 @@ macro_rules! arr {
     case(Value::Float(Float::from(2.5e+1)), cbor!(2.5e+1).unwrap()),
     case(Value::Float(Float::from(-2.5e+1)), cbor!(-2.5e+1).unwrap()),
 
+    // Tags and byte strings
+    case(Value::Tag(32, Value::Text("foo".into()).into()), cbor!(tag!(32, "foo")).unwrap()),
+    case(Value::Tag(6, arr![1, 2].into()), cbor!(tag!(6, [1, 2])).unwrap()),
+    case(Value::Tag(6, map! {1=>2}.into()), cbor!(tag!(6, {1=>2})).unwrap()),
+    case(Value::Tag(6, Value::Tag(7, Null.into()).into()), cbor!(tag!(6, tag!(7, null))).unwrap()),
+    case(Value::Bytes(vec![0, 1, 2]), cbor!(bytes!(b"\x00\x01\x02")).unwrap()),
+    case(Value::Bytes(vec![0, 1, 2]), cbor!(bytes!(vec![0u8, 1, 2])).unwrap()),
+    case(arr![Value::Tag(6, Null.into())], cbor!([tag!(6, null)]).unwrap()),
+    case(arr![Value::Bytes(vec![1])], cbor!([bytes!(b"\x01")]).unwrap()),
+    case(map! {Value::Tag(6, Null.into()) => 1}, cbor!({ tag!(6, null) => 1 }).unwrap()),
+    case(map! {1 => Value::Tag(6, Null.into())}, cbor!({ 1 => tag!(6, null) }).unwrap()),
+    case(map! {Value::Bytes(vec![1]) => 1}, cbor!({ bytes!(b"\x01") => 1 }).unwrap()),
+    case(map! {1 => Value::Bytes(vec![1])}, cbor!({ 1 => bytes!(b"\x01") }).unwrap()),
+    case(Value::Bytes(vec![]), cbor!(hex!("")).unwrap()),
+    case(Value::Bytes(vec![0x01, 0xff]), cbor!(hex!("01ff")).unwrap()),
+    case(Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef]), cbor!(hex!("de AD\n be\tEF")).unwrap()),
+    case(arr![Value::Bytes(vec![1])], cbor!([hex!("01")]).unwrap()),
+    case(map! {Value::Bytes(vec![1]) => 1}, cbor!({ hex!("01") => 1 }).unwrap()),
+    case(map! {1 => Value::Bytes(vec![1])}, cbor!({ 1 => hex!("01") }).unwrap()),
+
+    // Spreads and computed keys
+    case(arr![1, 2], cbor!([..[1, 2]]).unwrap()),
+    case(arr![0, 1, 2, 3], cbor!([0, ..1..3, 3]).unwrap()),
+    case(arr![0, "a", "b"], cbor!([0, ..["a", "b"].iter()]).unwrap()),
+    case(arr![Null, true], cbor!([..[Null, Value::Bool(true)]]).unwrap()),
+    case(arr![1, 2, 3, 4], cbor!([..[1, 2], ..[3, 4].iter()]).unwrap()),
+    case(map! {2 => 3}, cbor!({ 1 + 1 => 3 }).unwrap()),
+    case(map! {"foobar" => 3}, cbor!({ ["foo", "bar"].concat() => 3 }).unwrap()),
+
     // Simple array formulations
     case(arr![], cbor!([]).unwrap()),
     case(arr![Null], cbor!([null]).unwrap()),
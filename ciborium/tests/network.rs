@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use ciborium::{de::from_reader, ser::into_writer, tag::CidrPrefix};
+use rstest::rstest;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Any(#[serde(with = "ciborium::tag::network_address")] IpAddr);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct V4(#[serde(with = "ciborium::tag::network_address")] Ipv4Addr);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct V6(#[serde(with = "ciborium::tag::network_address")] Ipv6Addr);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Lenient(#[serde(with = "ciborium::tag::network_address::lenient")] IpAddr);
+
+fn encode<T: Serialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).unwrap();
+    hex::encode(bytes)
+}
+
+fn semantic<T: DeserializeOwned + std::fmt::Debug>(bytes: &str) -> String {
+    let bytes = hex::decode(bytes).unwrap();
+    match from_reader::<T, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => msg,
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+fn decode<T: DeserializeOwned>(bytes: &str) -> T {
+    from_reader(&hex::decode(bytes).unwrap()[..]).unwrap()
+}
+
+const V4_BYTES: &str = "d9010444c0000201";
+const V6_BYTES: &str = "d901045020010db8000000000000000000000001";
+
+#[test]
+fn addresses() {
+    let v4 = Ipv4Addr::new(192, 0, 2, 1);
+    let v6 = "2001:db8::1".parse::<Ipv6Addr>().unwrap();
+
+    assert_eq!(encode(&V4(v4)), V4_BYTES);
+    assert_eq!(encode(&V6(v6)), V6_BYTES);
+    assert_eq!(encode(&Any(v4.into())), V4_BYTES);
+    assert_eq!(encode(&Any(v6.into())), V6_BYTES);
+    assert_eq!(encode(&Lenient(v4.into())), V4_BYTES);
+
+    assert_eq!(decode::<V4>(V4_BYTES), V4(v4));
+    assert_eq!(decode::<V6>(V6_BYTES), V6(v6));
+    assert_eq!(decode::<Any>(V4_BYTES), Any(v4.into()));
+    assert_eq!(decode::<Any>(V6_BYTES), Any(v6.into()));
+    assert_eq!(decode::<Lenient>(V6_BYTES), Lenient(v6.into()));
+
+    // Only the lenient module accepts untagged bytes.
+    assert_eq!(decode::<Lenient>(&V4_BYTES[6..]), Lenient(v4.into()));
+    assert_eq!(
+        semantic::<Any>(&V4_BYTES[6..]),
+        "expected tag 260 (network address), found an untagged value"
+    );
+}
+
+#[rstest(
+    bytes,
+    message,
+    case(
+        "d9010446000000000000",
+        "expected a 4 or 16-byte network address, found 6 bytes"
+    ),
+    case("d9010440", "expected a 4 or 16-byte network address, found 0 bytes"),
+    case(
+        "d9010504c0000201",
+        "expected tag 260 (network address), found tag 261"
+    ),
+    case("d9010401", "invalid type: integer `1`, expected bytes")
+)]
+fn invalid(bytes: &str, message: &str) {
+    assert_eq!(semantic::<Any>(bytes), message);
+    assert_eq!(semantic::<Lenient>(bytes), message);
+}
+
+#[test]
+fn lengths() {
+    assert_eq!(
+        semantic::<V4>(V6_BYTES),
+        "expected a 4-byte IPv4 address, found 16 bytes"
+    );
+    assert_eq!(
+        semantic::<V6>(V4_BYTES),
+        "expected a 16-byte IPv6 address, found 4 bytes"
+    );
+}
+
+#[test]
+fn prefix() {
+    let v4 = CidrPrefix::new(Ipv4Addr::new(192, 0, 2, 0).into(), 24).unwrap();
+    let v6 = CidrPrefix::new("2001:db8::".parse().unwrap(), 128).unwrap();
+
+    assert_eq!(v4.to_string(), "192.0.2.0/24");
+    assert_eq!(encode(&v4), "d90105a144c00002001818");
+    assert_eq!(decode::<CidrPrefix>("d90105a144c00002001818"), v4);
+
+    let bytes = "d90105a15020010db80000000000000000000000001880";
+    assert_eq!(encode(&v6), bytes);
+    assert_eq!(decode::<CidrPrefix>(bytes), v6);
+
+    assert_eq!(CidrPrefix::new(Ipv4Addr::LOCALHOST.into(), 33), None);
+    assert_eq!(CidrPrefix::new(Ipv6Addr::LOCALHOST.into(), 129), None);
+}
+
+#[rstest(
+    bytes,
+    message,
+    case(
+        "d90105a144c00002001821",
+        "prefix length 33 exceeds the size of 192.0.2.0"
+    ),
+    case(
+        "d90105a144c000020020",
+        "prefix length -1 exceeds the size of 192.0.2.0"
+    ),
+    case(
+        "d90105a143c000021818",
+        "expected a 4 or 16-byte network address, found 3 bytes"
+    ),
+    case("d90105a0", "expected a map from an address to a prefix length"),
+    case(
+        "d90105a144c0000200f6",
+        "expected a map from an address to a prefix length"
+    ),
+    case(
+        "d90104a144c00002001818",
+        "expected tag 261 (network prefix), found tag 260"
+    ),
+    case(
+        "a144c00002001818",
+        "expected tag 261 (network prefix), found an untagged value"
+    )
+)]
+fn invalid_prefix(bytes: &str, message: &str) {
+    assert_eq!(semantic::<CidrPrefix>(bytes), message);
+}
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{ser::into_writer, ser::Error, tag::Rational};
+
+/// A writer error which implements nothing at all
+struct Full;
+
+/// A bounded writer whose error does not implement `Debug`
+struct Bounded<'a> {
+    data: &'a mut Vec<u8>,
+    limit: usize,
+}
+
+impl ciborium_io::Write for Bounded<'_> {
+    type Error = Full;
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        if self.data.len() + data.len() > self.limit {
+            return Err(Full);
+        }
+
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn writes() {
+    let mut data = Vec::new();
+    let writer = Bounded {
+        data: &mut data,
+        limit: 8,
+    };
+
+    assert!(into_writer(&[1u8, 2, 3], writer).is_ok());
+    assert_eq!(data, [0x83, 0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn io_error() {
+    let mut data = Vec::new();
+    let writer = Bounded {
+        data: &mut data,
+        limit: 2,
+    };
+
+    match into_writer("hello", writer) {
+        Err(Error::Io(Full)) => (),
+        _ => panic!("expected an io error"),
+    }
+}
+
+#[test]
+fn value_error() {
+    let mut data = Vec::new();
+    let writer = Bounded {
+        data: &mut data,
+        limit: 8,
+    };
+
+    let ratio = Rational { num: 1, den: 0 };
+    match into_writer(&ratio, writer) {
+        Err(Error::Value(msg)) => assert_eq!(msg, "rational denominator must be nonzero"),
+        _ => panic!("expected a value error"),
+    }
+}
@@ -1,13 +1,37 @@
 // SPDX-License-Identifier: Apache-2.0
 
-#![cfg(all(feature = "serde", not(feature = "std")))]
+#![cfg(not(feature = "std"))]
 #![no_std]
 
 extern crate alloc;
 
 use alloc::vec::Vec;
 
+use core::error::Error;
+
 use ciborium::{de::from_reader, ser::into_writer};
+use ciborium_io::{IterRead, IterReadError, OutOfSpace};
+
+/// An error of the transport
+#[derive(Debug)]
+struct Reset;
+
+impl core::fmt::Display for Reset {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("connection reset")
+    }
+}
+
+impl Error for Reset {}
+
+/// An error of a device driver, which implements only `Debug`
+#[derive(Debug)]
+struct Timeout;
+
+/// Requires the error trait, as error reporting crates do
+fn source<E: Error + 'static>(error: &E) -> Option<&(dyn Error + 'static)> {
+    error.source()
+}
 
 #[test]
 fn decode() {
@@ -16,7 +40,10 @@ fn decode() {
 
 #[test]
 fn eof() {
-    from_reader::<u8, &[u8]>(&[]).unwrap_err();
+    match from_reader::<u8, &[u8]>(&[0x18]) {
+        Err(ciborium::de::Error::UnexpectedEof { offset: 1 }) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
 }
 
 #[test]
@@ -37,3 +64,32 @@ fn encode_vec() {
 fn oos() {
     into_writer(&3u8, &mut [][..]).unwrap_err();
 }
+
+#[test]
+fn error_trait() {
+    let reader = IterRead::new([Err::<&[u8], _>(Reset)]);
+    let error = from_reader::<u8, _>(reader).unwrap_err();
+    let inner = source(&error).unwrap();
+    assert!(inner.is::<IterReadError<Reset>>());
+    assert!(inner.source().unwrap().is::<Reset>());
+
+    let error = into_writer(&3u8, &mut [][..]).unwrap_err();
+    assert!(source(&error).unwrap().is::<OutOfSpace>());
+
+    // A syntax error has no source.
+    let mut decoder = ciborium_ll::Decoder::from(&[0x1c][..]);
+    let error = decoder.pull().unwrap_err();
+    assert!(source(&error).is_none());
+}
+
+#[test]
+fn debug_only() {
+    let reader = IterRead::new([Ok::<_, Timeout>(&[0x82, 0x01][..]), Err(Timeout)]);
+    match from_reader::<Vec<u8>, _>(reader) {
+        Err(ciborium::de::Error::Io(IterReadError::Io(Timeout))) => (),
+        x => panic!("unexpected result: {:?}", x),
+    }
+
+    let reader = IterRead::new([Ok::<_, Timeout>(&[0x82, 0x01, 0x02][..])]);
+    assert_eq!(from_reader::<Vec<u8>, _>(reader).unwrap(), [1, 2]);
+}
@@ -0,0 +1,118 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use ciborium::ser::{into_writer, Error};
+use serde::{Serialize, Serializer};
+
+/// A value which always fails to serialize
+struct Bad;
+
+impl Serialize for Bad {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("bad address"))
+    }
+}
+
+/// Serializes as `Bad` when `fail` is set
+struct Addr {
+    fail: bool,
+}
+
+impl Serialize for Addr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.fail {
+            true => Bad.serialize(serializer),
+            false => serializer.serialize_str("127.0.0.1"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Peer {
+    name: &'static str,
+    addr: Addr,
+}
+
+#[derive(Serialize)]
+struct Config {
+    peers: Vec<Peer>,
+}
+
+#[derive(Serialize)]
+struct Root {
+    config: Config,
+}
+
+#[derive(Serialize)]
+enum Mode {
+    Fixed(Bad),
+    Pair(u8, Bad),
+    Named { value: Bad },
+}
+
+fn message(value: &impl Serialize) -> String {
+    match into_writer(value, Vec::new()).unwrap_err() {
+        Error::Value(msg) => msg,
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn nested() {
+    let peers = (0..5)
+        .map(|i| Peer {
+            name: "peer",
+            addr: Addr { fail: i == 3 },
+        })
+        .collect();
+
+    let root = Root {
+        config: Config { peers },
+    };
+
+    assert_eq!(message(&root), "config.peers[3].addr: bad address");
+}
+
+#[test]
+fn top() {
+    assert_eq!(message(&Bad), "bad address");
+}
+
+#[test]
+fn seq() {
+    assert_eq!(message(&(1, Bad)), "[1]: bad address");
+    assert_eq!(message(&vec![vec![], vec![Bad]]), "[1][0]: bad address");
+}
+
+#[test]
+fn map() {
+    let mut text = BTreeMap::new();
+    text.insert("addr", Bad);
+    assert_eq!(message(&text), "addr: bad address");
+
+    let mut integer = BTreeMap::new();
+    integer.insert(-7, Bad);
+    assert_eq!(message(&integer), "[-7]: bad address");
+
+    let mut other = BTreeMap::new();
+    other.insert((1, 2), Bad);
+    assert_eq!(message(&other), "[..]: bad address");
+}
+
+#[test]
+fn variant() {
+    assert_eq!(message(&Mode::Fixed(Bad)), "Fixed: bad address");
+    assert_eq!(message(&Mode::Pair(1, Bad)), "Pair[1]: bad address");
+    assert_eq!(
+        message(&Mode::Named { value: Bad }),
+        "Named.value: bad address"
+    );
+    assert_eq!(message(&[Mode::Fixed(Bad)]), "[0].Fixed: bad address");
+}
+
+#[test]
+fn tagged() {
+    let value = ciborium::tag::Required::<_, 7>(Bad);
+    assert_eq!(message(&value), "bad address");
+}
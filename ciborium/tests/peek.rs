@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{cbor, de::from_reader, ser::into_writer, tag::Captured, value::Value};
+use ciborium_io::Peekable;
+
+#[test]
+fn sniff() {
+    let value = cbor!(tag!(55799, { "a" => [1, 2] })).unwrap();
+    let mut bytes = Vec::new();
+    into_writer(&value, &mut bytes).unwrap();
+
+    let mut reader = Peekable::<_, 3>::new(&bytes[..]);
+    let mut magic = [0u8; 3];
+    reader.peek(&mut magic).unwrap();
+    assert_eq!(magic, [0xd9, 0xd9, 0xf7]);
+
+    // The deserializer reads the peeked bytes too.
+    let decoded: Captured<Value> = from_reader(reader).unwrap();
+    assert_eq!(
+        decoded,
+        Captured(Some(55799), cbor!({ "a" => [1, 2] }).unwrap())
+    );
+}
@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{cbor, de::from_reader, ser::into_writer, tag::Rational, value::Value};
+use rstest::rstest;
+
+fn ratio(num: i128, den: u128) -> Rational {
+    Rational { num, den }
+}
+
+#[rstest(bytes, answer,
+    // 30([1, 3])
+    case("d81e820103", ratio(1, 3)),
+
+    // 30([-6, 4]), which is kept unreduced
+    case("d81e822504", ratio(-6, 4)),
+
+    // 30([2^64, 2^64 + 1]), both as bignums
+    case("d81e82c249010000000000000000c249010000000000000001", ratio(1 << 64, (1 << 64) + 1)),
+
+    // 30([-2^64 - 1, 1]), with a negative bignum numerator
+    case("d81e82c34901000000000000000001", ratio(-(1 << 64) - 1, 1)),
+)]
+fn encoding(bytes: &str, answer: Rational) {
+    let bytes = hex::decode(bytes).unwrap();
+    let decoded: Rational = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, answer);
+
+    let mut encoded = Vec::new();
+    into_writer(&decoded, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+#[rstest(value, message,
+    case(cbor!([1, 3]).unwrap(), "expected tag 30 (rational number), found an untagged value"),
+    case(cbor!(tag!(4, [1, 3])).unwrap(), "expected tag 30 (rational number), found tag 4"),
+    case(cbor!(tag!(30, "1/3")).unwrap(), "invalid type: string \"1/3\", expected an array"),
+    case(cbor!(tag!(30, [1])).unwrap(), "invalid length 1, expected two items"),
+    case(cbor!(tag!(30, [1, 3, 5])).unwrap(), "invalid length 3, expected two items"),
+    case(cbor!(tag!(30, [1.5, 3])).unwrap(), "invalid rational numerator"),
+    case(cbor!(tag!(30, [1, -3])).unwrap(), "invalid rational denominator"),
+    case(cbor!(tag!(30, [1, "3"])).unwrap(), "invalid rational denominator"),
+    case(cbor!(tag!(30, [1, 0])).unwrap(), "rational denominator must be nonzero"),
+)]
+fn invalid(value: Value, message: &str) {
+    let error = value.deserialized::<Rational>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
+
+#[test]
+fn zero_denominator() {
+    assert_eq!(Rational::new(1, 0), None);
+
+    let mut encoded = Vec::new();
+    let error = into_writer(&ratio(1, 0), &mut encoded).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Value(\"rational denominator must be nonzero\")"
+    );
+}
+
+#[rstest(input, reduced,
+    case(ratio(6, 4), ratio(3, 2)),
+    case(ratio(-6, 4), ratio(-3, 2)),
+    case(ratio(0, 7), ratio(0, 1)),
+    case(ratio(5, 7), ratio(5, 7)),
+    case(ratio(i128::MIN, 1), ratio(i128::MIN, 1)),
+    case(ratio(i128::MIN, 1 << 127), ratio(-1, 1)),
+    case(ratio(3, 0), ratio(3, 0)),
+)]
+fn reduce(input: Rational, reduced: Rational) {
+    assert_eq!(input.reduced(), reduced);
+}
+
+#[rstest(input, float,
+    case(ratio(1, 4), 0.25),
+    case(ratio(-3, 2), -1.5),
+    case(ratio(1, 3), 1.0 / 3.0),
+    case(ratio(i128::MAX, u128::MAX), 0.5),
+)]
+fn floats(input: Rational, float: f64) {
+    assert_eq!(f64::from(input), float);
+}
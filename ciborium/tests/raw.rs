@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::de::{from_reader, from_slice, Error};
+use ciborium::ser::into_writer;
+use ciborium::value::{RawCbor, RawCborRef, Value};
+use ciborium::{cbor, tag::Required};
+
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Message {
+    kind: String,
+    body: RawCbor,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MessageRef<'a> {
+    kind: &'a str,
+    #[serde(borrow)]
+    body: RawCborRef<'a>,
+}
+
+/// A message with the body, which is any data item, in the middle
+fn message(body: &[u8]) -> Vec<u8> {
+    let mut bytes = hex::decode("a3646b696e6465706f696e7464626f6479").unwrap();
+    bytes.extend_from_slice(body);
+    bytes.extend_from_slice(&hex::decode("62696401").unwrap());
+    bytes
+}
+
+#[rstest(
+    body,
+    case("01"),
+    case("1801"),
+    case("fb3ff8000000000000"),
+    case("5f4201024103ff"),
+    case("7f616161626163ff"),
+    case("c1c21a514b67b0"),
+    case("9f018202039f0405ffff"),
+    case("b9000161619fbf01c606ffff")
+)]
+fn roundtrip(body: &str) {
+    let body = hex::decode(body).unwrap();
+    let bytes = message(&body);
+
+    // Unknown fields are skipped, and the body is kept exactly.
+    let owned: Message = from_reader(&bytes[..]).unwrap();
+    assert_eq!(owned.kind, "point");
+    assert_eq!(owned.body.as_bytes(), &body[..]);
+
+    let borrowed: MessageRef = from_slice(&bytes).unwrap();
+    assert_eq!(borrowed.kind, "point");
+    assert_eq!(borrowed.body.as_bytes(), &body[..]);
+
+    // Serializing writes the bytes out as they are.
+    let mut encoded = Vec::new();
+    into_writer(&owned, &mut encoded).unwrap();
+    assert_eq!(encoded[0], 0xa2);
+    assert_eq!(encoded[1..], bytes[1..encoded.len()]);
+
+    let mut again = Vec::new();
+    into_writer(&borrowed, &mut again).unwrap();
+    assert_eq!(again, encoded);
+
+    // Parsing later gives the same as parsing in place.
+    let value: Value = owned.body.deserialized().unwrap();
+    assert_eq!(value, Value::from_reader(&body[..]).unwrap());
+}
+
+#[test]
+fn forward() {
+    #[derive(Deserialize, Serialize)]
+    struct Routed {
+        to: u8,
+        body: RawCbor,
+    }
+
+    let input = cbor!([
+        { "to" => 1, "body" => { "x" => [1, 2] } },
+        { "to" => 2, "body" => bytes!(b"\x00") },
+    ])
+    .unwrap();
+
+    let mut bytes = Vec::new();
+    into_writer(&input, &mut bytes).unwrap();
+
+    let routed: Vec<Routed> = from_reader(&bytes[..]).unwrap();
+    let mut outputs = [Vec::new(), Vec::new()];
+    for message in &routed {
+        into_writer(&message.body, &mut outputs[message.to as usize - 1]).unwrap();
+    }
+
+    // Each body is forwarded on its own, still encoded as it was.
+    let first: Value = from_reader(&outputs[0][..]).unwrap();
+    assert_eq!(first, cbor!({ "x" => [1, 2] }).unwrap());
+    assert_eq!(outputs[1], [0x41, 0x00]);
+
+    // The whole input re-encodes to the same bytes.
+    let mut encoded = Vec::new();
+    into_writer(&routed, &mut encoded).unwrap();
+    assert_eq!(encoded, bytes);
+}
+
+#[test]
+fn value() {
+    let raw = RawCbor::from_bytes(hex::decode("c1820102").unwrap()).unwrap();
+
+    // A `Value` holds the decoded item, and encodes it again.
+    let value = Value::serialized(&raw).unwrap();
+    assert_eq!(value, Value::Tag(1, Box::new(cbor!([1, 2]).unwrap())));
+
+    let back: RawCbor = value.deserialized().unwrap();
+    assert_eq!(back, raw);
+
+    // Tagged items can be captured behind a tag, too.
+    let tagged: Required<RawCbor, 24> = from_reader(&[0xd8, 0x18, 0x82, 0x01, 0x02][..]).unwrap();
+    assert_eq!(tagged.0.as_bytes(), [0x82, 0x01, 0x02]);
+}
+
+#[test]
+fn constructors() {
+    assert!(RawCbor::from_bytes(vec![0x82, 0x01]).is_err());
+
+    // A key with no value is not a well-formed map.
+    match RawCbor::from_bytes(vec![0xbf, 0x01, 0xff]).unwrap_err() {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 2),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    match RawCborRef::from_bytes(&[0x01, 0x02]).unwrap_err() {
+        Error::Semantic(Some(1), msg) => assert_eq!(msg, "trailing data after the data item"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    let raw = RawCbor::serialized(&("a", 1)).unwrap();
+    assert_eq!(raw.as_bytes(), [0x82, 0x61, b'a', 0x01]);
+    assert_eq!(raw.as_raw_ref().to_owned(), raw);
+    assert_eq!(raw.deserialized::<(&str, u8)>().unwrap(), ("a", 1));
+    assert_eq!(&*raw.into_bytes(), [0x82, 0x61, b'a', 0x01]);
+}
+
+#[test]
+fn errors() {
+    // The body is truncated.
+    let bytes = message(&[0x82, 0x01]);
+    let bytes = &bytes[..bytes.len() - 4];
+
+    assert!(matches!(
+        from_reader::<Message, _>(bytes).unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+    assert!(matches!(
+        from_slice::<MessageRef>(bytes).unwrap_err(),
+        Error::UnexpectedEof { .. }
+    ));
+
+    // A stray break where the body should be
+    let bytes = message(&[0xff]);
+    match from_reader::<Message, _>(&bytes[..]).unwrap_err() {
+        Error::UnexpectedBreak { offset } => assert_eq!(offset, 17),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn borrowed_strings() {
+    #[derive(Deserialize)]
+    struct Borrowed<'a> {
+        text: &'a str,
+        #[serde(with = "serde_bytes")]
+        bytes: &'a [u8],
+    }
+
+    let value = cbor!({ "text" => "abc", "bytes" => bytes!(b"\x01\x02") }).unwrap();
+    let mut encoded = Vec::new();
+    into_writer(&value, &mut encoded).unwrap();
+
+    let borrowed: Borrowed = from_slice(&encoded).unwrap();
+    assert_eq!(borrowed.text, "abc");
+    assert_eq!(borrowed.bytes, [1, 2]);
+
+    // Text in chunks cannot be borrowed.
+    assert!(from_slice::<&str>(b"\x7f\x61a\xff").is_err());
+    assert_eq!(from_slice::<String>(b"\x7f\x61a\xff").unwrap(), "a");
+}
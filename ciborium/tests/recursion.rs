@@ -7,7 +7,7 @@
 //! test each of these types here to ensure there is no stack overflow.
 
 use ciborium::{
-    de::{from_reader, Error},
+    de::{from_reader, Error, LimitKind},
     value::Value,
 };
 
@@ -15,7 +15,12 @@ use ciborium::{
 fn array() {
     let bytes = [0x9f; 128 * 1024];
     match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
-        Error::RecursionLimitExceeded => (),
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
         e => panic!("incorrect error: {:?}", e),
     }
 }
@@ -24,7 +29,45 @@ fn array() {
 fn map() {
     let bytes = [0xbf; 128 * 1024];
     match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
-        Error::RecursionLimitExceeded => (),
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn tag() {
+    let bytes = [0xc6; 128 * 1024];
+    match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn limit() {
+    // 256 nested arrays are accepted, but not 257.
+    let mut bytes = vec![0x81; 256];
+    bytes.push(0x00);
+    from_reader::<Value, _>(&bytes[..]).unwrap();
+
+    bytes.insert(0, 0x81);
+    match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
         e => panic!("incorrect error: {:?}", e),
     }
 }
@@ -33,7 +76,7 @@ fn map() {
 fn bytes() {
     let bytes = [0x5f; 128 * 1024];
     match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
-        Error::Io(..) => (),
+        Error::UnexpectedEof { offset } => assert_eq!(offset, bytes.len()),
         e => panic!("incorrect error: {:?}", e),
     }
 }
@@ -42,7 +85,97 @@ fn bytes() {
 fn text() {
     let bytes = [0x7f; 128 * 1024];
     match from_reader::<Value, _>(&bytes[..]).unwrap_err() {
-        Error::Io(..) => (),
+        Error::UnexpectedEof { offset } => assert_eq!(offset, bytes.len()),
         e => panic!("incorrect error: {:?}", e),
     }
 }
+
+#[test]
+fn value_array() {
+    let bytes = [0x9f; 128 * 1024];
+    match Value::from_reader(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn value_map() {
+    let bytes = [0xbf; 128 * 1024];
+    match Value::from_reader(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn value_tag() {
+    let bytes = [0xc6; 128 * 1024];
+    match Value::from_reader(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn value_limit() {
+    // Tags count as levels, so 256 are accepted, but not 257.
+    let mut bytes = vec![0xc6; 256];
+    bytes.push(0x00);
+    Value::from_reader(&bytes[..]).unwrap();
+
+    bytes.insert(0, 0xc6);
+    match Value::from_reader(&bytes[..]).unwrap_err() {
+        Error::LimitExceeded {
+            kind: LimitKind::Depth,
+            limit: 256,
+            observed: 257,
+            offset: 256,
+        } => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn ignored() {
+    use ciborium::de::Options;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Kept {
+        #[allow(dead_code)]
+        b: Value,
+    }
+
+    #[derive(Deserialize)]
+    struct Ignored {}
+
+    // {"b": [[[...0...]]]}, with 300 arrays inside the map
+    let mut bytes = vec![0xa1, 0x61, b'b'];
+    bytes.extend_from_slice(&[0x81; 300]);
+    bytes.push(0x00);
+
+    // An ignored field is held to the same limit as one which is kept.
+    let options = Options::new().max_depth(301);
+    options.from_reader::<Kept, _>(&bytes[..]).unwrap();
+    options.from_reader::<Ignored, _>(&bytes[..]).unwrap();
+
+    let options = Options::new().max_depth(300);
+    assert!(options.from_reader::<Kept, _>(&bytes[..]).is_err());
+    assert!(options.from_reader::<Ignored, _>(&bytes[..]).is_err());
+}
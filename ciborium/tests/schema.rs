@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    schema::{Schema, ViolationKind},
+    value::{Step, Value},
+};
+use rstest::rstest;
+
+fn lines(schema: &Schema, value: &Value) -> Vec<String> {
+    match schema.validate(value) {
+        Ok(()) => Vec::new(),
+        Err(violations) => violations.iter().map(|v| v.to_string()).collect(),
+    }
+}
+
+#[rstest(schema, value, answer,
+    case(Schema::any(), cbor!([1, { "a" => null }]).unwrap(), &[]),
+    case(Schema::null(), Value::Null, &[]),
+    case(Schema::null(), Value::Undefined, &["$: expected null, found undefined"]),
+    case(Schema::bool(), cbor!(1).unwrap(), &["$: expected bool, found integer"]),
+    case(Schema::float(), cbor!(1).unwrap(), &["$: expected float, found integer"]),
+    case(Schema::int(), cbor!(1.0).unwrap(), &["$: expected integer, found float"]),
+    case(Schema::text(), cbor!(bytes!(b"a")).unwrap(), &["$: expected text, found bytes"]),
+    case(Schema::bytes(), cbor!("a").unwrap(), &["$: expected bytes, found text"]),
+    case(Schema::array(), cbor!({}).unwrap(), &["$: expected array, found map"]),
+    case(Schema::map(), cbor!([]).unwrap(), &["$: expected map, found array"]),
+    // Tagged values are only accepted where the schema has the tag
+    case(Schema::int(), Value::Tag(1, Box::new(1.into())), &["$: expected integer, found tag"]),
+    case(Schema::tagged(1, Schema::int()), Value::Tag(1, Box::new(1.into())), &[]),
+    case(Schema::tagged(1, Schema::int()), cbor!(1).unwrap(), &["$: expected tag 1"]),
+    case(
+        Schema::tagged(1, Schema::int()),
+        Value::Tag(0, Box::new(1.into())),
+        &["$: expected tag 1, found tag 0"]
+    ),
+    case(
+        Schema::tagged(1, Schema::int()),
+        Value::Tag(1, Box::new("x".into())),
+        &["$<1>: expected integer, found text"]
+    ),
+)]
+fn kinds(schema: Schema, value: Value, answer: &[&str]) {
+    assert_eq!(lines(&schema, &value), answer);
+}
+
+#[rstest(schema, value, answer,
+    case(Schema::int().range(-65536..0), cbor!(-65536).unwrap(), &[]),
+    case(Schema::int().range(-65536..0), cbor!(0).unwrap(), &["$: integer 0 is out of range"]),
+    case(Schema::int().range(..=5), cbor!(-1_000_000).unwrap(), &[]),
+    case(
+        Schema::int().range(0..),
+        cbor!(-18446744073709551616i128).unwrap(),
+        &["$: integer -18446744073709551616 is out of range"]
+    ),
+    case(Schema::text().len(1..=3), cbor!("abc").unwrap(), &[]),
+    // Text is counted in bytes of UTF-8
+    case(Schema::text().len(1..=3), cbor!("ééé").unwrap(), &["$: length 6 is out of range"]),
+    case(Schema::bytes().len(4..), cbor!(bytes!(b"")).unwrap(), &["$: length 0 is out of range"]),
+    case(Schema::array().len(..2), cbor!([1, 2]).unwrap(), &["$: length 2 is out of range"]),
+    case(Schema::map().len(1..), cbor!({}).unwrap(), &["$: length 0 is out of range"]),
+    case(Schema::literals(["a", "b"]), cbor!("b").unwrap(), &[]),
+    case(Schema::literals(["a", "b"]), cbor!("c").unwrap(), &["$: value is not one of the allowed values"]),
+    case(Schema::literal(1), cbor!(1.0).unwrap(), &["$: value is not one of the allowed values"]),
+)]
+fn constraints(schema: Schema, value: Value, answer: &[&str]) {
+    assert_eq!(lines(&schema, &value), answer);
+}
+
+#[test]
+fn arrays() {
+    let schema = Schema::array_of(Schema::int().range(0..10)).len(1..);
+    assert_eq!(lines(&schema, &cbor!([1, 2, 3]).unwrap()), [] as [&str; 0]);
+    assert_eq!(
+        lines(&schema, &cbor!([]).unwrap()),
+        ["$: length 0 is out of range"]
+    );
+    assert_eq!(
+        lines(&schema, &cbor!([1, 10, "x"]).unwrap()),
+        [
+            "$[1]: integer 10 is out of range",
+            "$[2]: expected integer, found text"
+        ]
+    );
+
+    // A tuple checks its length and each position.
+    let schema = Schema::tuple([Schema::text(), Schema::array_of(Schema::bool())]);
+    assert_eq!(
+        lines(&schema, &cbor!(["a", [true]]).unwrap()),
+        [] as [&str; 0]
+    );
+    assert_eq!(
+        lines(&schema, &cbor!([1, [true, 0], null]).unwrap()),
+        [
+            "$: length 3 is out of range",
+            "$[0]: expected text, found integer",
+            "$[1][1]: expected bool, found integer",
+        ]
+    );
+}
+
+#[test]
+fn maps() {
+    let schema = Schema::map()
+        .required("alg", Schema::int().range(-65536..0))
+        .optional(4, Schema::bytes())
+        .required("crv", Schema::literals(["P-256", "Ed25519"]));
+
+    let value = cbor!({ "alg" => -7, "crv" => "Ed25519", "x" => [1] }).unwrap();
+    assert_eq!(schema.validate(&value), Ok(()));
+
+    // Unknown keys are allowed unless denied.
+    let closed = schema.clone().deny_unknown_keys();
+    assert_eq!(lines(&closed, &value), ["$.x: unknown key"]);
+
+    let value = cbor!({ 4 => "kid", "alg" => 1, "alg" => -7 }).unwrap();
+    assert_eq!(
+        lines(&schema, &value),
+        [
+            "$: missing key \"crv\"",
+            "${4}: expected bytes, found text",
+            "$.alg: integer 1 is out of range",
+            "$.alg: duplicate key",
+        ]
+    );
+
+    // Other entries can be checked instead of denied.
+    let value = cbor!({ "alg" => -7, "crv" => "P-256", "x" => 0, 5 => true }).unwrap();
+    let counted = schema.others(Schema::text(), Schema::int());
+    assert_eq!(
+        lines(&counted, &value),
+        [
+            "${5}: expected text, found integer",
+            "${5}: expected integer, found bool"
+        ]
+    );
+
+    let schema = Schema::map_of(Schema::text(), Schema::map().required("id", Schema::int()));
+    let value = cbor!({ "a" => { "id" => 1 }, "b" => {} }).unwrap();
+    assert_eq!(lines(&schema, &value), ["$.b: missing key \"id\""]);
+}
+
+#[test]
+fn choices() {
+    let nullable = Schema::choice([Schema::null(), Schema::int().range(0..10)]);
+    assert_eq!(lines(&nullable, &Value::Null), [] as [&str; 0]);
+    assert_eq!(lines(&nullable, &cbor!(5).unwrap()), [] as [&str; 0]);
+
+    // Only the integer choice is of the right kind, so it alone is reported.
+    assert_eq!(
+        lines(&nullable, &cbor!(10).unwrap()),
+        ["$: integer 10 is out of range"]
+    );
+    assert_eq!(
+        lines(&nullable, &cbor!("5").unwrap()),
+        ["$: value matches none of the choices"]
+    );
+
+    let either = Schema::choice([Schema::int().range(..0), Schema::int().range(10..)]);
+    assert_eq!(
+        lines(&either, &cbor!(5).unwrap()),
+        ["$: value matches none of the choices"]
+    );
+
+    // Literals admit values of their own kinds.
+    let key = Schema::choice([Schema::literals([1, 2]), Schema::text()]);
+    assert_eq!(
+        lines(&key, &cbor!(3).unwrap()),
+        ["$: value is not one of the allowed values"]
+    );
+}
+
+#[test]
+fn violations() {
+    let schema = Schema::map().required(
+        "certs",
+        Schema::array_of(Schema::tagged(24, Schema::bytes())),
+    );
+    let value = cbor!({ "certs" => [Value::Tag(24, Box::new(1.into()))] }).unwrap();
+
+    let violations = schema.validate(&value).unwrap_err();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(
+        violations[0].path[..],
+        [Step::Key("certs".into()), Step::Index(0), Step::Tag(24)]
+    );
+    assert_eq!(
+        violations[0].kind,
+        ViolationKind::Type {
+            expected: "bytes",
+            found: "integer"
+        }
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "$.certs[0]<24>: expected bytes, found integer"
+    );
+}
+
+#[test]
+#[should_panic(expected = "only an integer schema has a range")]
+fn misuse() {
+    let _ = Schema::text().range(0..1);
+}
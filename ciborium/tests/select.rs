@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    value::{Step, Value},
+};
+use rstest::rstest;
+
+fn document() -> Value {
+    cbor!({
+        "headers" => [
+            { "alg" => "ES256", "kid" => 1 },
+            { "alg" => "EdDSA" },
+        ],
+        "payload" => tag!(24, { "alg" => "none", "nested" => { "alg" => "A128" } }),
+        "two words" => true,
+        -1 => "negative",
+        7 => "positive",
+    })
+    .unwrap()
+}
+
+#[rstest(selector, answer,
+    case("$", &["$"]),
+    case("$.headers", &["$.headers"]),
+    case("$.headers[0]", &["$.headers[0]"]),
+    case("$.headers[2]", &[]),
+    case("$.headers[*].alg", &["$.headers[0].alg", "$.headers[1].alg"]),
+    case("$.headers.*.kid", &["$.headers[0].kid"]),
+    case("$.missing[*]", &[]),
+    case("$.payload<24>.alg", &["$.payload<24>.alg"]),
+    case("$.payload<25>.alg", &[]),
+    case("$.payload<*>.nested.alg", &["$.payload<24>.nested.alg"]),
+    case("$.payload.alg", &[]),
+    case("${\"two words\"}", &["${\"two words\"}"]),
+    case("${-1}", &["${-1}"]),
+    case("${7}", &["${7}"]),
+    case("$[0]", &[]),
+    case(
+        "$..alg",
+        &[
+            "$.headers[0].alg",
+            "$.headers[1].alg",
+            "$.payload<24>.alg",
+            "$.payload<24>.nested.alg",
+        ]
+    ),
+    case("$.payload..alg", &["$.payload<24>.alg", "$.payload<24>.nested.alg"]),
+    case("$..[1]", &["$.headers[1]"]),
+    case("$..<24>.nested", &["$.payload<24>.nested"]),
+    case("$..{\"two words\"}", &["${\"two words\"}"]),
+)]
+fn select(selector: &str, answer: &[&str]) {
+    let document = document();
+    let paths: Vec<_> = document
+        .select(selector)
+        .unwrap()
+        .into_iter()
+        .map(|(path, _)| path.to_string())
+        .collect();
+
+    assert_eq!(paths, answer);
+}
+
+#[rstest(
+    selector,
+    offset,
+    reason,
+    case("", 0, "expected `$`"),
+    case("headers", 0, "expected `$`"),
+    case("$headers", 1, "expected `.`, `..`, `[`, `{` or `<`"),
+    case("$.", 2, "expected a name or `*`"),
+    case("$.0", 2, "expected a name or `*`"),
+    case("$[", 2, "expected a number"),
+    case("$[1", 3, "expected `]`"),
+    case("$[99999999999999999999]", 2, "number too large"),
+    case("$<x>", 2, "expected a number"),
+    case("${x}", 2, "expected a number or a string"),
+    case("${\"abc", 6, "unterminated string"),
+    case("${\"a\\qb\"}", 4, "invalid escape"),
+    case("${\"\\u{d800}\"}", 3, "invalid unicode escape"),
+    case("${1]", 3, "expected `}`")
+)]
+fn error(selector: &str, offset: usize, reason: &str) {
+    let error = document().select(selector).unwrap_err();
+    assert_eq!(error.offset(), offset);
+    assert_eq!(error.reason(), reason);
+    assert_eq!(
+        error.to_string(),
+        format!("invalid selector at offset {}: {}", offset, reason)
+    );
+}
+
+#[test]
+fn values() {
+    let document = document();
+    let found = document.select("$.headers[*].alg").unwrap();
+
+    let values: Vec<_> = found.iter().map(|(_, v)| *v).collect();
+    assert_eq!(values, [&Value::from("ES256"), &Value::from("EdDSA")]);
+
+    let path = &found[0].0;
+    assert_eq!(
+        &path[..],
+        [
+            Step::Key("headers".into()),
+            Step::Index(0),
+            Step::Key("alg".into())
+        ]
+    );
+}
+
+#[test]
+fn escapes() {
+    let value = cbor!({ "a\"b\\c\n\u{e9}" => 1 }).unwrap();
+    let found = value.select("${\"a\\\"b\\\\c\\n\\u{e9}\"}").unwrap();
+    assert_eq!(found.len(), 1);
+}
+
+#[test]
+fn paths() {
+    // Every path displays as a selector which finds the value at that path.
+    let document = document();
+    for (path, value) in document.find_all(|_| true) {
+        let found = document.select(&path.to_string()).unwrap();
+        assert_eq!(found, [(path, value)]);
+    }
+}
+
+#[test]
+fn find_all() {
+    let document = document();
+    let found = document.find_all(|v| matches!(v, Value::Text(x) if x.starts_with('E')));
+
+    let paths: Vec<_> = found.iter().map(|(p, _)| p.to_string()).collect();
+    assert_eq!(paths, ["$.headers[0].alg", "$.headers[1].alg"]);
+
+    let values: Vec<_> = found.into_iter().map(|(_, v)| v.clone()).collect();
+    assert_eq!(values, [Value::from("ES256"), Value::from("EdDSA")]);
+}
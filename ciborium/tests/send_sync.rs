@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::rc::Rc;
+
+use ciborium::de::from_reader;
+use ciborium::ser::into_writer;
+use ciborium::value::Value;
+use ciborium::IoMessage;
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+fn assert_static<T: 'static>() {}
+fn assert_boxable<T: std::error::Error + Send + Sync + 'static>() {}
+
+/// An error which is neither `Send` nor `Sync`
+#[derive(Debug)]
+struct Failure(Rc<str>);
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// A reader and writer which fail with a `Failure`
+struct Shared;
+
+impl ciborium_io::Read for Shared {
+    type Error = Failure;
+
+    fn read_exact(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+        Err(Failure("shared failure".into()))
+    }
+}
+
+impl ciborium_io::Write for Shared {
+    type Error = Failure;
+
+    fn write_all(&mut self, _: &[u8]) -> Result<(), Self::Error> {
+        Err(Failure("shared failure".into()))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn io() {
+    assert_send::<ciborium::de::Error<std::io::Error>>();
+    assert_sync::<ciborium::de::Error<std::io::Error>>();
+    assert_static::<ciborium::de::Error<std::io::Error>>();
+    assert_boxable::<ciborium::de::Error<std::io::Error>>();
+
+    assert_send::<ciborium::ser::Error<std::io::Error>>();
+    assert_sync::<ciborium::ser::Error<std::io::Error>>();
+    assert_static::<ciborium::ser::Error<std::io::Error>>();
+    assert_boxable::<ciborium::ser::Error<std::io::Error>>();
+
+    assert_boxable::<ciborium_ll::Error<std::io::Error>>();
+}
+
+#[test]
+fn owned() {
+    assert_boxable::<IoMessage>();
+    assert_boxable::<ciborium::de::Error<IoMessage>>();
+    assert_boxable::<ciborium::ser::Error<IoMessage>>();
+    assert_boxable::<ciborium::Error>();
+}
+
+#[test]
+fn de() {
+    let error = from_reader::<Value, _>(Shared).unwrap_err().into_owned();
+
+    let boxed: Box<dyn std::error::Error + Send + Sync> = error.into();
+    let message = std::thread::spawn(move || boxed.source().unwrap().to_string())
+        .join()
+        .unwrap();
+
+    assert_eq!(message, "shared failure");
+}
+
+#[test]
+fn ser() {
+    let error = into_writer(&Value::Null, Shared).unwrap_err().into_owned();
+
+    match error {
+        ciborium::ser::Error::Io(x) => assert_eq!(x.as_str(), "shared failure"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
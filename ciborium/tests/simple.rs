@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    de::{from_reader, Error},
+    ser::into_writer,
+    value::Value,
+};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+#[rstest(
+    value,
+    bytes,
+    case(Value::Simple(0), "e0"),
+    case(Value::Simple(19), "f3"),
+    case(Value::Bool(false), "f4"),
+    case(Value::Bool(true), "f5"),
+    case(Value::Null, "f6"),
+    case(Value::Undefined, "f7"),
+    case(Value::Simple(32), "f820"),
+    case(Value::Simple(255), "f8ff")
+)]
+fn codec(value: Value, bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let mut encoded = Vec::new();
+    into_writer(&value, &mut encoded).unwrap();
+    assert_eq!(bytes, encoded);
+
+    let mut encoded = Vec::new();
+    value.into_writer(&mut encoded).unwrap();
+    assert_eq!(bytes, encoded);
+
+    let decoded: Value = from_reader(&bytes[..]).unwrap();
+    assert_eq!(value, decoded);
+
+    let decoded = Value::from_reader(&bytes[..]).unwrap();
+    assert_eq!(value, decoded);
+
+    let decoded: Value = value.deserialized().unwrap();
+    assert_eq!(value, decoded);
+}
+
+#[test]
+fn roundtrip() {
+    for simple in (0u8..=255).filter(|x| !(24..32).contains(x)) {
+        let value = Value::Simple(simple);
+
+        let mut encoded = Vec::new();
+        into_writer(&value, &mut encoded).unwrap();
+
+        let decoded: Value = from_reader(&encoded[..]).unwrap();
+        let expected = match simple {
+            20 => Value::Bool(false),
+            21 => Value::Bool(true),
+            22 => Value::Null,
+            23 => Value::Undefined,
+            x => Value::Simple(x),
+        };
+
+        assert_eq!(expected, decoded);
+        assert_eq!(expected, Value::serialized(&value).unwrap());
+    }
+}
+
+#[test]
+fn reserved() {
+    for simple in 24u8..32 {
+        let value = Value::Simple(simple);
+        into_writer(&value, Vec::new()).unwrap_err();
+        value.into_writer(Vec::new()).unwrap_err();
+        Value::serialized(&value).unwrap_err();
+    }
+}
+
+#[test]
+fn other_types() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Reading {
+        Sample { id: u8, value: Option<u8> },
+        Code(u8),
+    }
+
+    // Only a `Value` tells undefined apart from null: other types see none.
+    let json: serde_json::Value = from_reader(&[0xf7][..]).unwrap();
+    assert_eq!(json, serde_json::Value::Null);
+
+    // {"id": 1, "value": undefined}
+    let bytes = hex::decode("a2626964016576616c7565f7").unwrap();
+    let reading: Reading = from_reader(&bytes[..]).unwrap();
+    assert_eq!(reading, Reading::Sample { id: 1, value: None });
+
+    let json: serde_json::Value = from_reader(&bytes[..]).unwrap();
+    assert_eq!(json, serde_json::json!({ "id": 1, "value": null }));
+
+    let json: serde_json::Value = Value::Undefined.deserialized().unwrap();
+    assert_eq!(json, serde_json::Value::Null);
+
+    // Other simple values have no equivalent, as they never had.
+    for value in [
+        from_reader::<serde_json::Value, _>(&[0xf8, 0x20][..]).unwrap_err(),
+        from_reader::<Reading, _>(&[0xf8, 0x20][..]).unwrap_err(),
+    ] {
+        match value {
+            Error::InvalidType {
+                offset, expected, ..
+            } => assert_eq!((offset, expected), (0, "known simple value")),
+            e => panic!("incorrect error: {:?}", e),
+        }
+    }
+
+    assert!(Value::Simple(32)
+        .deserialized::<serde_json::Value>()
+        .is_err());
+}
+
+#[test]
+fn lookalike() {
+    // An enum which happens to use the old marker name is not a simple value.
+    #[derive(Serialize)]
+    #[serde(rename = "@@SIMPLE@@")]
+    enum Lookalike {
+        #[serde(rename = "@@SIMPLE@@")]
+        Simple(u8),
+    }
+
+    // Nor is it undefined, which goes through the same marker.
+    for simple in [23, 32] {
+        let value = Lookalike::Simple(simple);
+        let expected = Value::Map(vec![("@@SIMPLE@@".into(), simple.into())]);
+        assert_eq!(Value::serialized(&value).unwrap(), expected);
+
+        let mut encoded = Vec::new();
+        into_writer(&value, &mut encoded).unwrap();
+        assert_eq!(from_reader::<Value, _>(&encoded[..]).unwrap(), expected);
+    }
+
+    // While undefined itself still is.
+    let mut encoded = Vec::new();
+    into_writer(&Value::Undefined, &mut encoded).unwrap();
+    assert_eq!(encoded, [0xf7]);
+    assert_eq!(
+        Value::serialized(&Value::Undefined).unwrap(),
+        Value::Undefined
+    );
+}
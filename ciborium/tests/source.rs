@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::error::Error as _;
+use std::io::{Error, ErrorKind};
+
+use ciborium::{de, de::from_reader, ser, ser::into_writer, value::Value};
+
+/// A reader whose connection was reset
+struct Closed;
+
+impl std::io::Read for Closed {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(ErrorKind::ConnectionReset.into())
+    }
+}
+
+/// A value which refuses to be serialized
+struct Refuse;
+
+impl serde::Serialize for Refuse {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("refused"))
+    }
+}
+
+#[test]
+fn de() {
+    let error = from_reader::<Value, _>(Closed).unwrap_err();
+    assert_eq!(error.as_io().unwrap().kind(), ErrorKind::ConnectionReset);
+
+    let source = error.source().unwrap().downcast_ref::<Error>().unwrap();
+    assert_eq!(source.kind(), ErrorKind::ConnectionReset);
+    assert_eq!(Error::from(error).kind(), ErrorKind::ConnectionReset);
+
+    // The end of the input keeps its kind.
+    let error = from_reader::<Value, _>(&[0x82, 0x01][..]).unwrap_err();
+    assert!(matches!(error, de::Error::UnexpectedEof { offset: 2 }));
+    assert_eq!(Error::from(error).kind(), ErrorKind::UnexpectedEof);
+
+    // Other errors have no source, and are wrapped.
+    let error = from_reader::<Value, _>(&[0x1c][..]).unwrap_err();
+    assert!(error.source().is_none());
+    assert!(error.as_io().is_none());
+
+    let error = Error::from(error);
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let inner = error.get_ref().unwrap().downcast_ref::<de::Error<Error>>();
+    assert!(matches!(inner, Some(de::Error::Syntax(0))));
+    assert!(matches!(
+        de::Error::<Error>::Syntax(0).into_io(),
+        Err(de::Error::Syntax(0))
+    ));
+}
+
+#[test]
+fn ser() {
+    let mut buffer = [0u8; 2];
+    let error = into_writer(&[1, 2, 3], &mut buffer[..]).unwrap_err();
+    assert_eq!(error.as_io().unwrap().kind(), ErrorKind::WriteZero);
+
+    let source = error.source().unwrap().downcast_ref::<Error>().unwrap();
+    assert_eq!(source.kind(), ErrorKind::WriteZero);
+    assert_eq!(error.into_io().unwrap().kind(), ErrorKind::WriteZero);
+
+    let error = into_writer(&Refuse, Vec::new()).unwrap_err();
+    assert!(error.source().is_none());
+
+    let error = Error::from(error);
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let inner = error.get_ref().unwrap().downcast_ref::<ser::Error<Error>>();
+    assert!(matches!(inner, Some(ser::Error::Value(x)) if x == "refused"));
+}
+
+/// Errors slot into functions which return I/O errors
+#[test]
+fn question_mark() {
+    fn decode(bytes: &[u8]) -> std::io::Result<Value> {
+        Ok(from_reader(bytes)?)
+    }
+
+    assert_eq!(decode(&[0x01]).unwrap(), Value::from(1));
+    assert_eq!(
+        decode(&[0x18]).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
@@ -2,16 +2,50 @@
 
 extern crate alloc;
 
-use ciborium::{de::from_reader, ser::into_writer, tag::*, value::Value};
+use ciborium::{
+    cbor,
+    de::from_reader,
+    ser::into_writer,
+    tag::*,
+    value::{Value, ValueRef},
+};
 use rstest::rstest;
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
 
+use core::convert::{TryFrom, TryInto};
 use core::fmt::Debug;
 
+struct Times;
+
+impl TagSet for Times {
+    const TAGS: &'static [u64] = &[0, 1];
+}
+
+type Time = AcceptedAny<u32, Times>;
+
 #[rstest(item, bytes, value, encode, success,
     case(Captured(Some(6), true), "c6f5", Value::Tag(6, Value::Bool(true).into()), true, true),
     case(Captured(None, true), "f5", Value::Bool(true), true, true),
 
+    // Only the outermost tag is captured; inner tags are kept by nested captures
+    case(Captured(Some(6), Captured(Some(7), true)), "c6c7f5", Value::Tag(6, Value::Tag(7, Value::Bool(true).into()).into()), true, true),
+    case(Captured(Some(6), Captured(None, true)), "c6f5", Value::Tag(6, Value::Bool(true).into()), true, true),
+    case(Captured(Some(6), true), "c6c7f5", Value::Tag(6, Value::Tag(7, Value::Bool(true).into()).into()), false, true),
+    case(Captured(Some(6), Value::Tag(7, Value::Bool(true).into())), "c6c7f5", Value::Tag(6, Value::Tag(7, Value::Bool(true).into()).into()), true, true),
+
+    // Nested tags are emitted and peeled outermost first
+    case(Required::<_, 6>(Required::<_, 7>(true)), "c6c7f5", Value::Tag(6, Value::Tag(7, Value::Bool(true).into()).into()), true, true),
+    case(Required::<_, 6>(Required::<_, 7>(true)), "c7c6f5", Value::Tag(7, Value::Tag(6, Value::Bool(true).into()).into()), false, false),
+    case(Required::<_, 6>(Required::<_, 7>(Required::<_, 8>(true))), "c6c7c8f5", Value::Tag(6, Value::Tag(7, Value::Tag(8, Value::Bool(true).into()).into()).into()), true, true),
+    case(Required::<_, 6>(Accepted::<_, 7>(true)), "c6f5", Value::Tag(6, Value::Bool(true).into()), false, true),
+    case(Accepted::<_, 6>(Required::<_, 7>(true)), "c7f5", Value::Tag(7, Value::Bool(true).into()), false, false),
+
+    case(CapturedAll(vec![6, 7, 8], true), "c6c7c8f5", Value::Tag(6, Value::Tag(7, Value::Tag(8, Value::Bool(true).into()).into()).into()), true, true),
+    case(CapturedAll(vec![6], true), "c6f5", Value::Tag(6, Value::Bool(true).into()), true, true),
+    case(CapturedAll(vec![], true), "f5", Value::Bool(true), true, true),
+    case(Captured(Some(6), CapturedAll(vec![7, 8], true)), "c6c7c8f5", Value::Tag(6, Value::Tag(7, Value::Tag(8, Value::Bool(true).into()).into()).into()), true, true),
+
     case(Required::<_, 6>(true), "c6f5", Value::Tag(6, Value::Bool(true).into()), true, true),
     case(Required::<_, 6>(true), "c7f5", Value::Tag(7, Value::Bool(true).into()), false, false),
     case(Required::<_, 6>(true), "f5", Value::Bool(true), false, false),
@@ -19,6 +53,19 @@ use core::fmt::Debug;
     case(Accepted::<_, 6>(true), "c6f5", Value::Tag(6, Value::Bool(true).into()), true, true),
     case(Accepted::<_, 6>(true), "c7f5", Value::Tag(7, Value::Bool(true).into()), false, false),
     case(Accepted::<_, 6>(true), "f5", Value::Bool(true), false, true),
+
+    case(Time::with_tag(0, 7).unwrap(), "c007", Value::Tag(0, Value::from(7).into()), true, true),
+    case(Time::with_tag(1, 7).unwrap(), "c107", Value::Tag(1, Value::from(7).into()), true, true),
+    case(Time::with_tag(1, 7).unwrap(), "c207", Value::Tag(2, Value::from(7).into()), false, false),
+    case(Time::new(7), "07", Value::from(7), false, true),
+
+    case(ExpectedBase64Url(ByteBuf::from(vec![1])), "d54101", Value::Tag(21, Value::Bytes(vec![1]).into()), true, true),
+    case(ExpectedBase64Url(ByteBuf::from(vec![1])), "4101", Value::Bytes(vec![1]), false, true),
+    case(ExpectedBase64Url(ByteBuf::from(vec![1])), "d64101", Value::Tag(22, Value::Bytes(vec![1]).into()), false, false),
+    case(ExpectedBase64(ByteBuf::from(vec![1])), "d64101", Value::Tag(22, Value::Bytes(vec![1]).into()), true, true),
+    case(ExpectedBase64(ByteBuf::from(vec![1])), "4101", Value::Bytes(vec![1]), false, true),
+    case(ExpectedBase16(ByteBuf::from(vec![1])), "d74101", Value::Tag(23, Value::Bytes(vec![1]).into()), true, true),
+    case(ExpectedBase16(ByteBuf::from(vec![1])), "4101", Value::Bytes(vec![1]), false, true),
 )]
 fn test<'de, T: Serialize + Deserialize<'de> + Debug + Eq>(
     item: T,
@@ -43,7 +90,7 @@ fn test<'de, T: Serialize + Deserialize<'de> + Debug + Eq>(
     match from_reader(&bytes[..]) {
         Ok(x) if success => assert_eq!(item, x),
         Ok(..) => panic!("unexpected success"),
-        Err(e) if success => Err(e).unwrap(),
+        Err(e) if success => panic!("{:?}", e),
         Err(..) => (),
     }
 
@@ -51,7 +98,163 @@ fn test<'de, T: Serialize + Deserialize<'de> + Debug + Eq>(
     match value.deserialized() {
         Ok(x) if success => assert_eq!(item, x),
         Ok(..) => panic!("unexpected success"),
-        Err(e) if success => Err(e).unwrap(),
+        Err(e) if success => panic!("{:?}", e),
         Err(..) => (),
     }
 }
+
+#[test]
+fn accepted_any() {
+    assert!(Time::with_tag(2, 7).is_none());
+
+    // Without a recorded tag, the first tag in the set is emitted.
+    let value = Value::serialized(&Time::new(7)).unwrap();
+    assert_eq!(value, Value::Tag(0, Value::from(7).into()));
+
+    let error = Value::Tag(2, Value::from(7).into())
+        .deserialized::<Time>()
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Custom(\"expected one of tags [0, 1], found tag 2\")"
+    );
+}
+
+// Tags are kept when decoding into a `Value`, wherever they are
+#[rstest(bytes, value,
+    // At the top level
+    case("c101", cbor!(tag!(1, 1)).unwrap()),
+    case("c1c2c3f5", cbor!(tag!(1, tag!(2, tag!(3, true)))).unwrap()),
+
+    // On array elements
+    case("82c101c2c3f5", cbor!([tag!(1, 1), tag!(2, tag!(3, true))]).unwrap()),
+
+    // On map keys and values
+    case("a1c10102", cbor!({ tag!(1, 1) => 2 }).unwrap()),
+    case("a101c102", cbor!({ 1 => tag!(1, 2) }).unwrap()),
+    case("a1c1c20001", cbor!({ tag!(1, tag!(2, 0)) => 1 }).unwrap()),
+
+    // Deeply nested
+    case("c1a1c382c40080c5f6", cbor!(tag!(1, { tag!(3, [tag!(4, 0), []]) => tag!(5, null) })).unwrap()),
+)]
+fn value_tags(bytes: &str, value: Value) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    assert_eq!(from_reader::<Value, _>(&bytes[..]).unwrap(), value);
+    assert_eq!(Value::from_reader(&bytes[..]).unwrap(), value);
+    assert_eq!(ValueRef::from_slice(&bytes).unwrap().to_owned(), value);
+    assert_eq!(value.deserialized::<Value>().unwrap(), value);
+
+    let mut encoded = Vec::new();
+    into_writer(&value, &mut encoded).unwrap();
+    assert_eq!(encoded, bytes);
+}
+
+#[test]
+fn untag() {
+    let value = cbor!(tag!(1, tag!(2, [3]))).unwrap();
+
+    let (tag, inner) = value.as_tag().unwrap();
+    assert_eq!(tag, 1);
+    assert_eq!(inner, &cbor!(tag!(2, [3])).unwrap());
+    assert_eq!(value.untag(), &cbor!([3]).unwrap());
+
+    let plain = cbor!([3]).unwrap();
+    assert_eq!(plain.as_tag(), None);
+    assert_eq!(plain.untag(), &plain);
+
+    let (tag, inner): (u64, Value) = value.try_into().unwrap();
+    assert_eq!((tag, inner), (1, cbor!(tag!(2, [3])).unwrap()));
+
+    let error = <(u64, Value)>::try_from(plain).unwrap_err();
+    assert_eq!(error.to_string(), "expected tag, found array");
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Document {
+    id: Required<ByteBuf, 37>,
+    count: Accepted<u32, 6>,
+    extra: Captured<String>,
+    maybe: Option<Required<u8, 7>>,
+    list: Vec<Required<bool, 8>>,
+    nested: Required<Required<i8, 9>, 10>,
+}
+
+// Tag wrappers survive the trip through a `Value` in either direction
+#[rstest(document, value,
+    case(
+        Document {
+            id: Required(ByteBuf::from(vec![1, 2])),
+            count: Accepted(3),
+            extra: Captured(Some(32), "x".into()),
+            maybe: Some(Required(4)),
+            list: vec![Required(true), Required(false)],
+            nested: Required(Required(-5)),
+        },
+        cbor!({
+            "id" => tag!(37, Value::Bytes(vec![1, 2])),
+            "count" => tag!(6, 3),
+            "extra" => tag!(32, "x"),
+            "maybe" => tag!(7, 4),
+            "list" => [tag!(8, true), tag!(8, false)],
+            "nested" => tag!(10, tag!(9, -5)),
+        }).unwrap()
+    ),
+    case(
+        Document {
+            id: Required(ByteBuf::new()),
+            count: Accepted(0),
+            extra: Captured(None, "".into()),
+            maybe: None,
+            list: vec![],
+            nested: Required(Required(0)),
+        },
+        cbor!({
+            "id" => tag!(37, Value::Bytes(vec![])),
+            "count" => tag!(6, 0),
+            "extra" => "",
+            "maybe" => null,
+            "list" => [],
+            "nested" => tag!(10, tag!(9, 0)),
+        }).unwrap()
+    ),
+)]
+fn value_roundtrip(document: Document, value: Value) {
+    // typed -> Value -> typed
+    let serialized = Value::serialized(&document).unwrap();
+    assert_eq!(serialized, value);
+    assert_eq!(serialized.deserialized::<Document>().unwrap(), document);
+
+    // Value -> typed -> Value
+    let deserialized: Document = value.deserialized().unwrap();
+    assert_eq!(deserialized, document);
+    assert_eq!(Value::serialized(&deserialized).unwrap(), value);
+
+    // bytes -> Value -> typed
+    let mut bytes = Vec::new();
+    into_writer(&document, &mut bytes).unwrap();
+    let decoded: Value = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, value);
+    assert_eq!(decoded.deserialized::<Document>().unwrap(), document);
+}
+
+// Small bignums are normalized when decoding into a `Value`, so their tag is lost
+#[test]
+fn value_bignum() {
+    let item = Required::<ByteBuf, 2>(ByteBuf::from(vec![1, 2]));
+
+    let value = Value::serialized(&item).unwrap();
+    assert_eq!(value, Value::Tag(2, Value::Bytes(vec![1, 2]).into()));
+    assert_eq!(value.deserialized::<Required<ByteBuf, 2>>().unwrap(), item);
+
+    let mut bytes = Vec::new();
+    into_writer(&item, &mut bytes).unwrap();
+
+    let normalized = Value::from_reader(&bytes[..]).unwrap();
+    assert_eq!(normalized, Value::from(258));
+    assert!(normalized.deserialized::<Required<ByteBuf, 2>>().is_err());
+
+    let (raw, _) = Value::from_reader_with_encoding(&bytes[..]).unwrap();
+    assert_eq!(raw, value);
+    assert_eq!(raw.deserialized::<Required<ByteBuf, 2>>().unwrap(), item);
+}
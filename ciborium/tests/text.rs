@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{
+    cbor,
+    de::from_reader,
+    ser::into_writer,
+    tag::{LangString, MimeMessage},
+    value::Value,
+};
+use rstest::rstest;
+
+fn lang(lang: &str, text: &str) -> LangString {
+    LangString {
+        lang: lang.into(),
+        text: text.into(),
+    }
+}
+
+#[test]
+fn mime() {
+    // 36("MIME-Version: 1.0\r\n\r\nhi")
+    let bytes = hex::decode("d824774d494d452d56657273696f6e3a20312e300d0a0d0a6869").unwrap();
+    let message = MimeMessage("MIME-Version: 1.0\r\n\r\nhi".into());
+
+    let decoded: MimeMessage = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, message);
+
+    let mut encoded = Vec::new();
+    into_writer(&message, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+#[rstest(bytes, answer,
+    // 38(["en", "Hello"])
+    case("d8268262656e6548656c6c6f", lang("en", "Hello")),
+
+    // 38(["", ""])
+    case("d826826060", lang("", "")),
+)]
+fn encoding(bytes: &str, answer: LangString) {
+    let bytes = hex::decode(bytes).unwrap();
+    let decoded: LangString = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, answer);
+
+    let mut encoded = Vec::new();
+    into_writer(&decoded, &mut encoded).unwrap();
+    assert_eq!(hex::encode(encoded), hex::encode(&bytes));
+}
+
+#[rstest(
+    tag,
+    valid,
+    case("en", true),
+    case("de-CH", true),
+    case("zh-Hant-TW", true),
+    case("sl-rozaj-biske", true),
+    case("en-US-x-twain", true),
+    case("x-whatever", true),
+    case("i-klingon", true),
+    case("", false),
+    case("-en", false),
+    case("en-", false),
+    case("en--US", false),
+    case("en_US", false),
+    case("1en", false),
+    case("en-x", false),
+    case("x", false),
+    case("abcdefghi", false),
+    case("en-abcdefghi", false)
+)]
+fn well_formed(tag: &str, valid: bool) {
+    assert_eq!(lang(tag, "text").is_well_formed(), valid);
+}
+
+#[rstest(value, message,
+    case(cbor!("hi").unwrap(), "expected tag 36 (MIME message), found an untagged value"),
+    case(cbor!(tag!(38, "hi")).unwrap(), "expected tag 36 (MIME message), found tag 38"),
+    case(cbor!(tag!(36, [1])).unwrap(), "invalid type: sequence, expected a string"),
+)]
+fn invalid_mime(value: Value, message: &str) {
+    let error = value.deserialized::<MimeMessage>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
+
+#[rstest(value, message,
+    case(cbor!(["en", "Hello"]).unwrap(), "expected tag 38 (language-tagged string), found an untagged value"),
+    case(cbor!(tag!(36, ["en", "Hello"])).unwrap(), "expected tag 38 (language-tagged string), found tag 36"),
+    case(cbor!(tag!(38, "Hello")).unwrap(), "invalid type: string \"Hello\", expected an array"),
+    case(cbor!(tag!(38, ["en"])).unwrap(), "invalid length 1, expected two items"),
+    case(cbor!(tag!(38, ["en", "Hello", "ltr"])).unwrap(), "invalid length 3, expected two items"),
+    case(cbor!(tag!(38, [1, "Hello"])).unwrap(), "language-tagged string items must both be text"),
+    case(cbor!(tag!(38, ["en", Value::Bytes(vec![])])).unwrap(), "language-tagged string items must both be text"),
+)]
+fn invalid_lang(value: Value, message: &str) {
+    let error = value.deserialized::<LangString>().unwrap_err();
+    assert_eq!(error.to_string(), format!("Custom({:?})", message));
+}
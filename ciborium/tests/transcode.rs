@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "json")]
+
+use ciborium::transcode::*;
+use ciborium::value::Value;
+use ciborium::{cbor, de};
+
+use rstest::rstest;
+
+fn json(hex: &str, options: Options) -> Result<String, Error> {
+    let bytes = hex::decode(hex).unwrap();
+    let mut json = Vec::new();
+    to_json_writer(&bytes[..], &mut json, options)?;
+    Ok(String::from_utf8(json).unwrap())
+}
+
+fn unsupported(hex: &str, options: Options) -> (usize, &'static str) {
+    match json(hex, options).unwrap_err() {
+        Error::Unsupported { offset, item } => (offset, item),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[rstest(hex, expected,
+    case("00", "0"),
+    case("1bffffffffffffffff", "18446744073709551615"),
+    case("3bffffffffffffffff", "-18446744073709551616"),
+    case("f93e00", "1.5"),
+    case("f4", "false"),
+    case("f5", "true"),
+    case("f6", "null"),
+    case("f7", "null"),
+    case("6461c3a962", r#""aéb""#),
+    case("7f616161626163ff", r#""abc""#),
+    case("9f01820203a0ff", "[1,[2,3],{}]"),
+    case("bf616101ff", r#"{"a":1}"#),
+
+    // Bignums which fit in 128 bits are numbers.
+    case("c249010000000000000000", "18446744073709551616"),
+    case("c349010000000000000000", "-18446744073709551617"),
+)]
+fn items(hex: &str, expected: &str) {
+    assert_eq!(json(hex, Options::new()).unwrap(), expected);
+}
+
+#[rstest(
+    policy,
+    expected,
+    case(BytesPolicy::Base64Url, r#"["","-_8","AQID","AQIDBA"]"#),
+    case(BytesPolicy::Hex, r#"["","fbff","010203","01020304"]"#)
+)]
+fn bytes(policy: BytesPolicy, expected: &str) {
+    // [h'', h'fbff', h'010203', (_ h'0102', h'0304')]
+    let hex = "844042fbff430102035f420102420304ff";
+    assert_eq!(json(hex, Options::new().bytes(policy)).unwrap(), expected);
+}
+
+#[rstest(hex, expected,
+    // 21(h'01ff'), 22(h'01ff') and 23(h'01ff')
+    case("d54201ff", r#""Af8""#),
+    case("d64201ff", r#""Af8=""#),
+    case("d74201ff", r#""01ff""#),
+
+    // Padding and the characters which differ from base64url
+    case("d68442fbff41fb430102034401020304", r#"["+/8=","+w==","AQID","AQIDBA=="]"#),
+
+    // The tag applies inside the item, and the innermost tag wins.
+    case("d7824201ffd641fb", r#"["01ff","+w=="]"#),
+    case("d7a1614141ff", r#"{"A":"ff"}"#),
+    case("82d7410141fb", r#"["01","-w"]"#),
+
+    // Byte string keys are written the same way.
+    case("a1d741ff00", r#"{"ff":0}"#),
+    case("d6a141fbd54101", r#"{"+w==":"AQ"}"#),
+)]
+fn encodings(hex: &str, expected: &str) {
+    assert_eq!(json(hex, Options::new()).unwrap(), expected);
+}
+
+#[test]
+fn encoding_policy() {
+    // The tags override the policy, but only inside them.
+    let options = Options::new().bytes(BytesPolicy::Hex);
+    assert_eq!(json("82d5410141fb", options).unwrap(), r#"["AQ","fb"]"#);
+
+    // When tags are kept, so is the policy.
+    let options = options.tags(TagPolicy::Annotate);
+    assert_eq!(
+        json("d74201ff", options).unwrap(),
+        r#"{"__tag":23,"value":"01ff"}"#
+    );
+    let options = Options::new().tags(TagPolicy::Annotate);
+    assert_eq!(
+        json("d74201ff", options).unwrap(),
+        r#"{"__tag":23,"value":"Af8"}"#
+    );
+}
+
+#[test]
+fn many_tags() {
+    // Dropped tags do not count as levels, so any number can be read.
+    let hex = "c6".repeat(100_000) + "01";
+    assert_eq!(json(&hex, Options::new()).unwrap(), "1");
+}
+
+#[test]
+fn keys() {
+    // {1: 0, -2: 1, "x": 2, h'ff': 3}
+    let hex = "a40100210161780241ff03";
+
+    let options = Options::new().bytes(BytesPolicy::Hex);
+    assert_eq!(
+        json(hex, options).unwrap(),
+        r#"{"1":0,"-2":1,"x":2,"ff":3}"#
+    );
+
+    let options = Options::new().keys(KeyPolicy::Error);
+    assert_eq!(unsupported(hex, options), (1, "map key"));
+
+    // {[]: 0}
+    assert_eq!(unsupported("a18000", Options::new()), (1, "map key"));
+}
+
+#[rstest(policy, expected,
+    case(TagPolicy::Unwrap, Ok(r#"["https://example.com",[1]]"#)),
+    case(TagPolicy::Error, Err((1, "tag"))),
+    case(
+        TagPolicy::Annotate,
+        Ok(r#"[{"__tag":32,"value":"https://example.com"},{"__tag":1000,"value":{"__tag":6,"value":[1]}}]"#)
+    ),
+)]
+fn tags(policy: TagPolicy, expected: Result<&str, (usize, &str)>) {
+    // [32("https://example.com"), 1000(6([1]))]
+    let hex = "82d8207368747470733a2f2f6578616d706c652e636f6dd903e8c68101";
+    let options = Options::new().tags(policy);
+
+    match expected {
+        Ok(expected) => assert_eq!(json(hex, options).unwrap(), expected),
+        Err(expected) => assert_eq!(unsupported(hex, options), expected),
+    }
+}
+
+#[test]
+fn tagged_keys() {
+    // {0("a"): 1}
+    let hex = "a1c0616101";
+
+    assert_eq!(json(hex, Options::new()).unwrap(), r#"{"a":1}"#);
+
+    let options = Options::new().tags(TagPolicy::Annotate);
+    assert_eq!(unsupported(hex, options), (1, "tag"));
+}
+
+#[test]
+fn bignums() {
+    // 2(h'01'), which is just 1 whatever the policy
+    let options = Options::new().tags(TagPolicy::Error);
+    assert_eq!(json("c24101", options).unwrap(), "1");
+
+    // Annotated bignums keep their bytes.
+    let options = Options::new().tags(TagPolicy::Annotate);
+    assert_eq!(
+        json("c24101", options).unwrap(),
+        r#"{"__tag":2,"value":"AQ"}"#
+    );
+
+    // 3(h'80000000000000000000000000000000') is too small for 128 bits.
+    let hex = "c35080000000000000000000000000000000";
+    assert_eq!(
+        json(hex, Options::new()).unwrap(),
+        r#""gAAAAAAAAAAAAAAAAAAAAA""#
+    );
+
+    let options = Options::new().tags(TagPolicy::Error);
+    assert_eq!(unsupported(hex, options), (0, "tag"));
+
+    // A bignum with more than 16 bytes follows the policy too.
+    let hex = "c251010000000000000000000000000000000000";
+    assert_eq!(unsupported(hex, options), (0, "tag"));
+}
+
+#[rstest(policy, expected,
+    case(FloatPolicy::Null, Ok("[null,null,null,0.5]")),
+    case(FloatPolicy::String, Ok(r#"["NaN","Infinity","-Infinity",0.5]"#)),
+    case(FloatPolicy::Error, Err((1, "non-finite float"))),
+)]
+fn floats(policy: FloatPolicy, expected: Result<&str, (usize, &str)>) {
+    // [NaN, Infinity, -Infinity, 0.5]
+    let hex = "84f97e00f97c00f9fc00f93800";
+    let options = Options::new().floats(policy);
+
+    match expected {
+        Ok(expected) => assert_eq!(json(hex, options).unwrap(), expected),
+        Err(expected) => assert_eq!(unsupported(hex, options), expected),
+    }
+}
+
+#[test]
+fn simple() {
+    assert_eq!(unsupported("8201f0", Options::new()), (2, "simple value"));
+}
+
+#[test]
+fn cbor_errors() {
+    // ["a", truncated
+    match json("826161", Options::new()).unwrap_err() {
+        Error::Cbor(ciborium::Error::De(de::Error::UnexpectedEof { offset: 3 })) => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // A stray break
+    match json("81ff", Options::new()).unwrap_err() {
+        Error::Cbor(ciborium::Error::De(de::Error::Syntax(1))) => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    let deep = "81".repeat(300) + "00";
+    match json(&deep, Options::new()).unwrap_err() {
+        Error::Cbor(ciborium::Error::De(de::Error::LimitExceeded {
+            kind: de::LimitKind::Depth,
+            limit: 256,
+            offset: 256,
+            ..
+        })) => (),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn json_errors() {
+    struct Broken;
+
+    impl std::io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let bytes = hex::decode("8101").unwrap();
+    match to_json_writer(&bytes[..], Broken, Options::new()).unwrap_err() {
+        Error::Json(e) => assert_eq!(e.io_error_kind(), Some(std::io::ErrorKind::BrokenPipe)),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    match from_json_slice(b"[1] 2", Vec::new()).unwrap_err() {
+        Error::Json(e) => assert!(e.is_syntax()),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn trailing() {
+    // Only the first data item is read.
+    assert_eq!(json("0102", Options::new()).unwrap(), "1");
+}
+
+#[test]
+fn from_json() {
+    let json = br#"{"a": [1, -1, 1.5, 1e300, "x", true, null], "b": {}, "c": [[]]}"#;
+
+    let mut bytes = Vec::new();
+    from_json_slice(json, &mut bytes).unwrap();
+
+    let value: Value = ciborium::de::from_reader(&bytes[..]).unwrap();
+    let expected = cbor!({
+        "a" => [1, -1, 1.5, 1e300, "x", true, null],
+        "b" => {},
+        "c" => [[]],
+    })
+    .unwrap();
+
+    assert_eq!(value, expected);
+
+    // Every container has a definite length, and floats are shortest.
+    let mut encoded = Vec::new();
+    ciborium::ser::into_writer(&expected, &mut encoded).unwrap();
+    assert_eq!(bytes, encoded);
+}
+
+#[test]
+fn roundtrip() {
+    let json = r#"{"id":7,"tags":["a","b"],"ratio":0.25,"nested":{"deep":[[1],[2,[3]]]}}"#;
+
+    let mut bytes = Vec::new();
+    from_json_slice(json.as_bytes(), &mut bytes).unwrap();
+    assert_eq!(
+        self::json(&hex::encode(&bytes), Options::new()).unwrap(),
+        json
+    );
+}
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{cbor, value::Value};
+use rstest::rstest;
+
+#[rstest(value, max, text,
+    case(cbor!(1).unwrap(), 4, "1"),
+    case(cbor!(-1).unwrap(), 4, "-1"),
+    case(cbor!(1.5).unwrap(), 4, "1.5"),
+    case(Value::Float(f64::NAN.into()), 4, "NaN"),
+    case(Value::Float(f64::NEG_INFINITY.into()), 4, "-Infinity"),
+    case(cbor!(true).unwrap(), 4, "true"),
+    case(cbor!(null).unwrap(), 4, "null"),
+    case(Value::Undefined, 4, "undefined"),
+    case(Value::Simple(16), 4, "simple(16)"),
+    case(Value::Bytes(vec![0x00, 0x11]), 4, "h'0011'"),
+    case(Value::Bytes((0..=255).collect()), 4, "h'0001..feff' (256 bytes)"),
+    case(Value::Bytes((0..=255).collect()), 3, "h'0001..ff' (256 bytes)"),
+    case(Value::Bytes((0..=255).collect()), 0, "h'..' (256 bytes)"),
+    case(cbor!("abcd").unwrap(), 4, "\"abcd\""),
+    case(cbor!("abcdefgh").unwrap(), 4, "\"ab..gh\" (8 bytes)"),
+    case(cbor!("ääää").unwrap(), 4, "\"ä..ä\" (8 bytes)"),
+    case(cbor!("a\"b").unwrap(), 4, "\"a\\\"b\""),
+    case(cbor!(tag!(32, "x")).unwrap(), 4, "32(\"x\")"),
+    case(cbor!([]).unwrap(), 4, "[]"),
+    case(cbor!([1, 2]).unwrap(), 4, "[1, 2]"),
+    case(cbor!([1, 2, 3, 4, 5]).unwrap(), 2, "[1, 2, ..(3 more)]"),
+    case(cbor!([1, 2]).unwrap(), 0, "[..(2 more)]"),
+    case(cbor!({}).unwrap(), 4, "{}"),
+    case(cbor!({ "a" => [1], 2 => { 3 => 4 } }).unwrap(), 4, "{\"a\": [1], 2: {3: 4}}"),
+    case(cbor!({ 1 => 1, 2 => 2, 3 => 3 }).unwrap(), 1, "{1: 1, ..(2 more)}"),
+)]
+fn display(value: Value, max: usize, text: &str) {
+    assert_eq!(value.display_truncated(max).to_string(), text);
+}
+
+#[test]
+fn annotated() {
+    let value = cbor!([tag!(24, tag!(55799, "x")), tag!(6, 1)]).unwrap();
+    assert_eq!(
+        format!("{:#}", value.display_truncated(4)),
+        "[24(/ embedded CBOR / 55799(/ self-described CBOR / \"x\")), 6(1)]"
+    );
+    assert_eq!(
+        value.display_truncated(4).to_string(),
+        "[24(55799(\"x\")), 6(1)]"
+    );
+}
+
+#[test]
+fn large() {
+    let value = Value::Map(vec![(
+        "payload".into(),
+        Value::Bytes(vec![7; 5 * 1024 * 1024]),
+    )]);
+    assert_eq!(
+        value.display_truncated(8).to_string(),
+        "{\"payload\": h'07070707..07070707' (5242880 bytes)}"
+    );
+}
+
+#[test]
+fn deep() {
+    let mut value = Value::Null;
+    for _ in 0..100_000 {
+        value = Value::Array(vec![value]);
+    }
+
+    let text = value.display_truncated(4).to_string();
+    assert_eq!(text.len(), 100_000 * 2 + 4);
+    assert_eq!(&text[99_998..100_006], "[[null]]");
+
+    // Avoid recursion when dropping the deeply nested value.
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        if let Value::Array(x) = value {
+            stack.extend(x);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::Debug;
+
+use ciborium::{
+    de::from_reader,
+    ser::into_writer,
+    tag::{Element, TypedArray},
+};
+use rstest::rstest;
+use serde::{de::DeserializeOwned, Serialize};
+
+fn encode<T: Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    into_writer(value, &mut bytes).unwrap();
+    bytes
+}
+
+fn roundtrip<T: Element + Debug + PartialEq>(array: TypedArray<T>, bytes: &str)
+where
+    TypedArray<T>: DeserializeOwned,
+{
+    let bytes = hex::decode(bytes).unwrap();
+    assert_eq!(encode(&array), bytes);
+
+    let decoded: TypedArray<T> = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, array);
+}
+
+// Each type in both byte orders; single bytes have no byte order, but the
+// clamped tag (68) is used for little endian bytes and there is no tag for
+// little endian signed bytes.
+#[test]
+fn elements() {
+    roundtrip(TypedArray::big_endian(vec![1u8, 255]), "d8404201ff");
+    roundtrip(TypedArray::little_endian(vec![1u8, 255]), "d8444201ff");
+    roundtrip(TypedArray::big_endian(vec![1u16, 0xfffe]), "d841440001fffe");
+    roundtrip(
+        TypedArray::little_endian(vec![1u16, 0xfffe]),
+        "d845440100feff",
+    );
+    roundtrip(
+        TypedArray::big_endian(vec![1u32, 0xfffe_fdfc]),
+        "d8424800000001fffefdfc",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1u32, 0xfffe_fdfc]),
+        "d8464801000000fcfdfeff",
+    );
+    roundtrip(
+        TypedArray::big_endian(vec![1u64, u64::MAX - 1]),
+        "d843500000000000000001fffffffffffffffe",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1u64, u64::MAX - 1]),
+        "d847500100000000000000feffffffffffffff",
+    );
+    roundtrip(TypedArray::big_endian(vec![1i8, -2]), "d8484201fe");
+    roundtrip(TypedArray::big_endian(vec![1i16, -2]), "d849440001fffe");
+    roundtrip(TypedArray::little_endian(vec![1i16, -2]), "d84d440100feff");
+    roundtrip(
+        TypedArray::big_endian(vec![1i32, -2]),
+        "d84a4800000001fffffffe",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1i32, -2]),
+        "d84e4801000000feffffff",
+    );
+    roundtrip(
+        TypedArray::big_endian(vec![1i64, -2]),
+        "d84b500000000000000001fffffffffffffffe",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1i64, -2]),
+        "d84f500100000000000000feffffffffffffff",
+    );
+    roundtrip(
+        TypedArray::big_endian(vec![1.5f32, -0.25]),
+        "d851483fc00000be800000",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1.5f32, -0.25]),
+        "d855480000c03f000080be",
+    );
+    roundtrip(
+        TypedArray::big_endian(vec![1.5f64, -0.25]),
+        "d852503ff8000000000000bfd0000000000000",
+    );
+    roundtrip(
+        TypedArray::little_endian(vec![1.5f64, -0.25]),
+        "d85650000000000000f83f000000000000d0bf",
+    );
+}
+
+#[test]
+fn native() {
+    let array = TypedArray::from(vec![1u16]);
+    assert_eq!(array.little_endian, cfg!(target_endian = "little"));
+    assert_eq!(array.tag(), if array.little_endian { 69 } else { 65 });
+}
+
+#[test]
+fn size() {
+    let samples: Vec<f32> = (0..1000).map(|x| x as f32 / 7.0).collect();
+    let typed = encode(&TypedArray::little_endian(samples.clone()));
+    assert_eq!(typed.len(), 5 + 4 * samples.len());
+    assert!(typed.len() < encode(&samples).len());
+
+    // Each of these takes three bytes as a plain integer.
+    let samples: Vec<u16> = (1000..2000).collect();
+    let typed = encode(&TypedArray::little_endian(samples.clone()));
+    assert_eq!(typed.len(), 5 + 2 * samples.len());
+    assert_eq!(encode(&samples).len(), 3 + 3 * samples.len());
+}
+
+#[rstest(bytes, message,
+    // Not a multiple of the element size
+    case("d84543010203", "typed array of 3 bytes is not a multiple of the element size (2)"),
+
+    // An array of a different type
+    case("d8424400000001", "expected tag 65 (typed array), found tag 66"),
+    case("d8514400000001", "expected tag 65 (typed array), found tag 81"),
+
+    // Not bytes
+    case("d8418101", "invalid type: sequence, expected bytes"),
+
+    // Not a typed array
+    case("d81840", "expected tag 65 (typed array), found tag 24"),
+    case("40", "expected tag 65 (typed array), found an untagged value"),
+)]
+fn invalid(bytes: &str, message: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    match from_reader::<TypedArray<u16>, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => assert_eq!(msg, message),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::error::Error as _;
+use std::io::ErrorKind;
+
+use ciborium::{de, de::from_reader, ser, ser::into_writer, value::Value, Error};
+
+/// A reader whose connection was reset
+struct Closed;
+
+impl std::io::Read for Closed {
+    fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+        Err(ErrorKind::ConnectionReset.into())
+    }
+}
+
+/// The error of a writer which is not an `std::error::Error`
+#[derive(Debug, PartialEq)]
+struct Full(usize);
+
+/// A writer which holds a few bytes only
+struct Small(Vec<u8>);
+
+impl ciborium_io::Write for Small {
+    type Error = Full;
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        let len = self.0.len() + data.len();
+        if len > 4 {
+            return Err(Full(len));
+        }
+
+        self.0.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Copies a value from a slice into a buffer, with the errors of both
+fn transcode(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let value: Value = from_reader(input)?;
+    let mut output = Vec::new();
+    into_writer(&value, &mut output)?;
+    Ok(output)
+}
+
+#[test]
+fn map_io() {
+    let error = from_reader::<Value, _>(Closed).unwrap_err();
+    match error.map_io(|e| e.kind()) {
+        de::Error::Io(kind) => assert_eq!(kind, ErrorKind::ConnectionReset),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // Everything but the error of the reader is kept.
+    let error = from_reader::<Value, _>(&[0x82, 0x01][..]).unwrap_err();
+    let error: de::Error<()> = error.map_io(|_| ());
+    assert!(matches!(error, de::Error::UnexpectedEof { offset: 2 }));
+
+    let error = into_writer(&"hello", Small(Vec::new())).unwrap_err();
+    match error.map_io(|Full(n)| n) {
+        ser::Error::Io(n) => assert_eq!(n, 6),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn de() {
+    assert_eq!(transcode(&[0x81, 0x01]).unwrap(), [0x81, 0x01]);
+
+    match transcode(&[0x82, 0x01]).unwrap_err() {
+        Error::De(e) => assert!(matches!(e, de::Error::UnexpectedEof { offset: 2 })),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    // The error of the reader is boxed, and is still the source.
+    let error = Error::from(from_reader::<Value, _>(Closed).unwrap_err());
+    let source = error.source().unwrap();
+    let source = source.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!(source.kind(), ErrorKind::ConnectionReset);
+}
+
+#[test]
+fn ser() {
+    // A writer whose error is not an `std::error::Error` is mapped first.
+    let error = into_writer(&"hello", Small(Vec::new())).unwrap_err();
+    let error = Error::from(error.map_io(|Full(n)| std::io::Error::other(format!("{} bytes", n))));
+
+    match &error {
+        Error::Ser(ser::Error::Io(e)) => assert_eq!(e.to_string(), "6 bytes"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+
+    assert_eq!(error.source().unwrap().to_string(), "6 bytes");
+}
+
+#[test]
+fn owned() {
+    let error = from_reader::<Value, _>(&[0x1c][..]).unwrap_err();
+    let error = Error::from(error.into_owned());
+    assert_eq!(error.to_string(), "Syntax(0)");
+    assert!(error.source().is_none());
+
+    let sent = std::thread::spawn(move || error.to_string())
+        .join()
+        .unwrap();
+    assert_eq!(sent, "Syntax(0)");
+}
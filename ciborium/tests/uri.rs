@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+use std::str::FromStr;
+
+use ciborium::{cbor, de::from_reader, value::Value};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+/// A stand-in for `url::Url` which only accepts absolute URIs
+#[derive(Debug, PartialEq, Eq)]
+struct Absolute(String);
+
+impl FromStr for Absolute {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((scheme, _)) if !scheme.is_empty() => Ok(Self(s.into())),
+            _ => Err("relative URL without a base"),
+        }
+    }
+}
+
+impl fmt::Display for Absolute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Strict {
+    #[serde(with = "ciborium::tag::uri")]
+    href: Absolute,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Lenient {
+    #[serde(with = "ciborium::tag::uri::lenient")]
+    href: Absolute,
+}
+
+fn semantic<T: serde::de::DeserializeOwned + std::fmt::Debug>(value: &Value) -> String {
+    let mut bytes = Vec::new();
+    ciborium::ser::into_writer(value, &mut bytes).unwrap();
+
+    match from_reader::<T, _>(&bytes[..]).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => msg,
+        e @ ciborium::de::Error::InvalidType { .. } => e.to_string(),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let strict = Strict {
+        href: Absolute("http://www.example.com".into()),
+    };
+
+    // Example from RFC 8949, appendix A
+    let mut tagged = hex::decode("a16468726566").unwrap();
+    tagged.extend(hex::decode("d82076687474703a2f2f7777772e6578616d706c652e636f6d").unwrap());
+
+    let mut encoded = Vec::new();
+    ciborium::ser::into_writer(&strict, &mut encoded).unwrap();
+    assert_eq!(encoded, tagged);
+    assert_eq!(from_reader::<Strict, _>(&tagged[..]).unwrap(), strict);
+
+    let lenient: Lenient = from_reader(&tagged[..]).unwrap();
+    assert_eq!(lenient.href, strict.href);
+
+    let mut encoded = Vec::new();
+    ciborium::ser::into_writer(&lenient, &mut encoded).unwrap();
+    assert_eq!(encoded, tagged);
+}
+
+#[test]
+fn untagged() {
+    let value = cbor!({ "href" => "urn:isbn:0451450523" }).unwrap();
+
+    let lenient: Lenient = value.deserialized().unwrap();
+    assert_eq!(lenient.href, Absolute("urn:isbn:0451450523".into()));
+
+    assert_eq!(
+        semantic::<Strict>(&value),
+        "expected tag 32 (URI), found untagged text"
+    );
+}
+
+#[rstest(value, message,
+    case(
+        cbor!({ "href" => tag!(32, "/relative") }).unwrap(),
+        "invalid URI \"/relative\": relative URL without a base"
+    ),
+    case(
+        cbor!({ "href" => tag!(33, "http://x") }).unwrap(),
+        "expected tag 32 (URI), found tag 33"
+    ),
+    case(
+        cbor!({ "href" => tag!(32, 7) }).unwrap(),
+        "invalid type at offset 8: expected string, found positive integer"
+    ),
+)]
+fn invalid(value: Value, message: &str) {
+    assert_eq!(semantic::<Strict>(&value), message);
+    assert_eq!(semantic::<Lenient>(&value), message);
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{cbor, de::from_reader, ser::into_writer, value::Value};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+/// A stand-in for `uuid::Uuid`
+#[derive(Debug, PartialEq, Eq)]
+struct Uuid([u8; 16]);
+
+impl From<[u8; 16]> for Uuid {
+    fn from(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for Uuid {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Strict(#[serde(with = "ciborium::tag::uuid")] Uuid);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Lenient(#[serde(with = "ciborium::tag::uuid::lenient")] Uuid);
+
+#[derive(Serialize)]
+struct Slice<'a>(#[serde(serialize_with = "ciborium::tag::uuid::serialize")] &'a [u8]);
+
+fn semantic<T: serde::de::DeserializeOwned + std::fmt::Debug>(bytes: &[u8]) -> String {
+    match from_reader::<T, _>(bytes).unwrap_err() {
+        ciborium::de::Error::Semantic(_, msg) => msg,
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+// 37(h'8C8A8D48C00F4D27B8E1C55B9D5E0A4F'), which is the UUID
+// 8c8a8d48-c00f-4d27-b8e1-c55b9d5e0a4f
+const FIXTURE: &str = "d825508c8a8d48c00f4d27b8e1c55b9d5e0a4f";
+
+#[test]
+fn roundtrip() {
+    let bytes = hex::decode(FIXTURE).unwrap();
+    let mut uuid = Uuid([0; 16]);
+    uuid.0.copy_from_slice(&bytes[3..]);
+
+    let strict: Strict = from_reader(&bytes[..]).unwrap();
+    assert_eq!(strict.0, uuid);
+
+    let lenient: Lenient = from_reader(&bytes[..]).unwrap();
+    assert_eq!(lenient.0, uuid);
+
+    for value in [Value::serialized(&strict), Value::serialized(&lenient)] {
+        let mut encoded = Vec::new();
+        into_writer(&value.unwrap(), &mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+    }
+}
+
+#[test]
+fn untagged() {
+    let bytes = hex::decode(&FIXTURE[4..]).unwrap();
+
+    let lenient: Lenient = from_reader(&bytes[..]).unwrap();
+    assert_eq!(&lenient.0 .0[..], &bytes[1..]);
+
+    assert_eq!(
+        semantic::<Strict>(&bytes),
+        "expected tag 37 (UUID), found an untagged value"
+    );
+}
+
+#[rstest(
+    bytes,
+    message,
+    case(
+        "d8254f8c8a8d48c00f4d27b8e1c55b9d5e0a",
+        "expected a 16-byte UUID, found 15 bytes"
+    ),
+    case(
+        "d825518c8a8d48c00f4d27b8e1c55b9d5e0a4f00",
+        "expected a 16-byte UUID, found 17 bytes"
+    ),
+    case("d82640", "expected tag 37 (UUID), found tag 38"),
+    case("d82507", "invalid type: integer `7`, expected bytes")
+)]
+fn invalid(bytes: &str, message: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+    assert_eq!(semantic::<Strict>(&bytes), message);
+    assert_eq!(semantic::<Lenient>(&bytes), message);
+}
+
+#[test]
+fn length() {
+    let error = Value::serialized(&Slice(&[0; 15])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Custom(\"expected a 16-byte UUID, found 15 bytes\")"
+    );
+
+    let value = Value::serialized(&Slice(&[0; 16])).unwrap();
+    assert_eq!(value, cbor!(tag!(37, Value::Bytes(vec![0; 16]))).unwrap());
+}
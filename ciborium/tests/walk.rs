@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use core::ops::ControlFlow;
+
+use ciborium::{
+    cbor,
+    value::{Step, Value},
+};
+
+#[test]
+fn order() {
+    let value = cbor!({ "a" => [1, tag!(7, 2)], "b" => 3 }).unwrap();
+
+    let mut paths = Vec::new();
+    value.walk(|path, _| {
+        paths.push(path.to_vec());
+        ControlFlow::Continue(())
+    });
+
+    let a = || Step::Key("a".into());
+    assert_eq!(
+        paths,
+        vec![
+            vec![],
+            vec![a()],
+            vec![a(), Step::Index(0)],
+            vec![a(), Step::Index(1)],
+            vec![a(), Step::Index(1), Step::Tag(7)],
+            vec![Step::Key("b".into())],
+        ]
+    );
+}
+
+#[test]
+fn stop() {
+    let value = cbor!([1, 2, 3, 4]).unwrap();
+
+    let mut seen = Vec::new();
+    value.walk(|_, v| {
+        seen.push(v.clone());
+        match v {
+            Value::Integer(x) if i128::from(*x) == 2 => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    });
+
+    assert_eq!(seen, vec![value.clone(), 1.into(), 2.into()]);
+}
+
+#[test]
+fn redact() {
+    let mut value = cbor!({
+        "user" => "alice",
+        "password" => "hunter2",
+        "accounts" => [
+            { "name" => "work", "password" => "secret" },
+            { "name" => "home", "tokens" => { "password" => [1, 2, 3] } },
+        ],
+    })
+    .unwrap();
+
+    value.walk_mut(|path, v| {
+        if let Some(Step::Key(Value::Text(key))) = path.last() {
+            if key == "password" {
+                *v = Value::Text("<redacted>".into());
+            }
+        }
+
+        ControlFlow::Continue(())
+    });
+
+    let answer = cbor!({
+        "user" => "alice",
+        "password" => "<redacted>",
+        "accounts" => [
+            { "name" => "work", "password" => "<redacted>" },
+            { "name" => "home", "tokens" => { "password" => "<redacted>" } },
+        ],
+    })
+    .unwrap();
+
+    assert_eq!(value, answer);
+}
+
+#[test]
+fn deep() {
+    let mut value = Value::Null;
+    for _ in 0..100_000 {
+        value = Value::Array(vec![value]);
+    }
+
+    let mut count = 0;
+    value.walk(|_, _| {
+        count += 1;
+        ControlFlow::Continue(())
+    });
+    assert_eq!(count, 100_001);
+
+    value.walk_mut(|path, v| {
+        if path.len() == 100_000 {
+            *v = Value::Bool(true);
+        }
+
+        ControlFlow::Continue(())
+    });
+
+    let mut leaf = None;
+    value.walk(|_, v| {
+        if !matches!(v, Value::Array(..)) {
+            leaf = Some(v.clone());
+        }
+
+        ControlFlow::Continue(())
+    });
+    assert_eq!(leaf, Some(Value::Bool(true)));
+
+    // Avoid recursion when dropping the deeply nested value.
+    let mut stack = vec![value];
+    while let Some(value) = stack.pop() {
+        if let Value::Array(x) = value {
+            stack.extend(x);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+
+use ciborium::de::{Error, Options, Warning, WarningKind};
+use ciborium::value::Value;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Reading {
+    name: String,
+    note: Option<String>,
+    values: Vec<u8>,
+}
+
+/// The kind, offset and path of a warning
+type Seen = (WarningKind, usize, String);
+
+/// Decodes with the default options, collecting the warnings
+fn warnings<'de, T: Deserialize<'de>>(hex: &str) -> (Result<T, Error<std::io::Error>>, Vec<Seen>) {
+    let bytes = hex::decode(hex).unwrap();
+    let mut warnings = Vec::new();
+
+    let result = Options::new().from_reader_with_warnings(&bytes[..], |w: Warning| {
+        warnings.push((w.kind, w.offset, w.path))
+    });
+
+    (result, warnings)
+}
+
+// {"name": 32("probe"), "note": undefined, "extra": 1, "values": [1, 1(2)]}
+const READING: &str =
+    "a4646e616d65d8206570726f6265646e6f7465f7656578747261016676616c7565738201c102";
+
+#[test]
+fn lenient() {
+    let (result, warnings) = warnings::<Reading>(READING);
+
+    let reading = Reading {
+        name: "probe".into(),
+        note: None,
+        values: vec![1, 2],
+    };
+
+    assert_eq!(result.unwrap(), reading);
+    assert_eq!(
+        warnings,
+        [
+            (WarningKind::IgnoredTag(32), 6, "name".into()),
+            (WarningKind::Undefined, 19, "note".into()),
+            (WarningKind::IgnoredItem, 26, "extra".into()),
+            (WarningKind::IgnoredTag(1), 36, "values[1]".into()),
+        ]
+    );
+}
+
+#[test]
+fn strict() {
+    let bytes = hex::decode(READING).unwrap();
+    let error = Options::new()
+        .deny_unexpected_tags(true)
+        .from_reader::<Reading, _>(&bytes[..])
+        .unwrap_err();
+
+    match error {
+        Error::Semantic(Some(6), msg) => assert_eq!(msg, "unexpected tag 32"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}
+
+#[test]
+fn none() {
+    // {"name": "probe", "note": null, "values": []}
+    let (result, warnings) =
+        warnings::<Reading>("a3646e616d656570726f6265646e6f7465f66676616c75657380");
+
+    assert_eq!(result.unwrap().note, None);
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn keys() {
+    // {1: [undefined], "a b": 0([null])}
+    let (result, warnings) = warnings::<BTreeMap<Value, Vec<()>>>("a20181f763612062c081f6");
+    assert!(result.is_ok());
+
+    assert_eq!(
+        warnings,
+        [
+            (WarningKind::Undefined, 3, "[..][0]".into()),
+            (WarningKind::IgnoredTag(0), 8, "a b".into()),
+        ]
+    );
+}
+
+#[test]
+fn before_error() {
+    // [0(1), "x"], where the second element is not an integer
+    let (result, warnings) = warnings::<Vec<u8>>("82c0016178");
+    assert!(result.is_err());
+    assert_eq!(warnings, [(WarningKind::IgnoredTag(0), 1, "[0]".into())]);
+}
+
+#[test]
+fn display() {
+    let bytes = hex::decode(READING).unwrap();
+    let mut warnings = Vec::new();
+
+    Options::new()
+        .from_reader_with_warnings::<Reading, _>(&bytes[..], |w| warnings.push(w.to_string()))
+        .unwrap();
+
+    assert_eq!(warnings[0], "name: ignored tag 32 at offset 6");
+    assert_eq!(warnings[1], "note: undefined read as null at offset 19");
+}
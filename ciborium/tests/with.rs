@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use ciborium::{de::from_reader, de::Error, ser::into_writer};
+use rstest::rstest;
+use serde::{Deserialize, Serialize};
+
+const BORN: u64 = 1004;
+
+ciborium::tagged_with!(mod uuid_tag = 37);
+ciborium::tagged_with!(mod date_tag = BORN);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Named {
+    #[serde(with = "uuid_tag")]
+    id: Vec<u8>,
+
+    #[serde(with = "date_tag::option")]
+    born: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Tuple(
+    #[serde(
+        serialize_with = "ciborium::tag::required::serialize::<37, _, _>",
+        deserialize_with = "ciborium::tag::required::deserialize::<37, _, _>"
+    )]
+    u8,
+    #[serde(
+        serialize_with = "ciborium::tag::required::option::serialize::<32, _, _>",
+        deserialize_with = "ciborium::tag::required::option::deserialize::<32, _, _>"
+    )]
+    Option<String>,
+);
+
+#[rstest(item, bytes,
+    // {"id": 37([1, 2]), "born": 1004("1940-10-09")}
+    case(
+        Named { id: vec![1, 2], born: Some("1940-10-09".into()) },
+        "a2626964d82582010264626f726ed903ec6a313934302d31302d3039"
+    ),
+
+    // {"id": 37([]), "born": null}
+    case(Named { id: vec![], born: None }, "a2626964d8258064626f726ef6"),
+)]
+fn named(item: Named, bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let mut encoded = Vec::new();
+    into_writer(&item, &mut encoded).unwrap();
+    assert_eq!(hex::encode(&encoded), hex::encode(&bytes));
+
+    let decoded: Named = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, item);
+}
+
+#[rstest(item, bytes,
+    // [37(7), 32("a")]
+    case(Tuple(7, Some("a".into())), "82d82507d8206161"),
+
+    // [37(7), null]
+    case(Tuple(7, None), "82d82507f6"),
+)]
+fn tuple(item: Tuple, bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    let mut encoded = Vec::new();
+    into_writer(&item, &mut encoded).unwrap();
+    assert_eq!(hex::encode(&encoded), hex::encode(&bytes));
+
+    let decoded: Tuple = from_reader(&bytes[..]).unwrap();
+    assert_eq!(decoded, item);
+}
+
+#[rstest(bytes,
+    // [7, null]: the tag is missing
+    case("8207f6"),
+
+    // [38(7), null]: the wrong tag
+    case("82d82607f6"),
+
+    // [37(7), "a"]: an optional value without its tag
+    case("82d825076161"),
+)]
+fn missing(bytes: &str) {
+    let bytes = hex::decode(bytes).unwrap();
+
+    match from_reader::<Tuple, _>(&bytes[..]).unwrap_err() {
+        Error::Semantic(_, msg) => assert_eq!(msg, "required tag not found"),
+        e => panic!("incorrect error: {:?}", e),
+    }
+}